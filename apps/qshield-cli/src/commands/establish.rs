@@ -1,10 +1,13 @@
 use std::path::Path;
 
+use quantumshield_refimpl::qsp::SignedPrekey;
+
 use crate::actor::ActorClient;
 use crate::config::{self, Config};
 use crate::relay_client::{
     get_json, post_json, post_json_allow_status, BundleResponse, ConsumeRequest,
-    EstablishRecordRequest, EstablishRecordResponse, GenericOk,
+    EstablishRecordRequest, EstablishRecordResponse, GenericOk, PrekeyClaimRequest,
+    PrekeyClaimResponse,
 };
 use crate::store::{SessionEntry, StoreState};
 use crate::util::{
@@ -91,6 +94,12 @@ pub fn run(
     if role != "A" && role != "B" {
         return Err("role must be A or B".to_string());
     }
+    // Only the initiator takes a prekey from the responder's pool, as it would for HS1.
+    let claimed_prekey_id = if role == "A" {
+        Some(claim_prekey(&cfg, &relay_token, peer_id)?.id)
+    } else {
+        None
+    };
 
     let session_id = demo_session_id_bytes(&my_id, peer_id);
     let session_id_hex = hex::encode(session_id);
@@ -181,6 +190,7 @@ pub fn run(
             pq_prekey_id: pq_prekey_id as u32,
             dh_self_pub_hex,
             dh_peer_pub_hex: dh_peer_pub_hex.to_string(),
+            claimed_prekey_id,
         },
     );
     save_state(&state_path, &state)?;
@@ -188,3 +198,24 @@ pub fn run(
     println!("established session with {peer_id}: session_id={sid_b64u}");
     Ok(())
 }
+
+/// Take one of the peer's prekeys from the relay: a one-time key while any are left, else its
+/// last-resort key. The demo derives both sides' session material from the bundles alone and
+/// has no first message to name the key in, so the claimed id is recorded with the session.
+fn claim_prekey(cfg: &Config, relay_token: &str, peer_id: &str) -> Result<SignedPrekey, String> {
+    let req = PrekeyClaimRequest {
+        id: peer_id.to_string(),
+    };
+    let (status, resp): (u16, PrekeyClaimResponse) =
+        post_json_allow_status(&cfg.relay_url, "/prekeys/claim", &req, relay_token)?;
+    if status != 200 || !resp.ok {
+        let msg = resp.error.unwrap_or_else(|| format!("status {status}"));
+        return Err(format!("relay prekey claim failed for {peer_id}: {msg}"));
+    }
+    let bytes = resp
+        .prekey
+        .as_deref()
+        .and_then(|v| hex::decode(v).ok())
+        .ok_or_else(|| "relay prekey claim returned no prekey".to_string())?;
+    SignedPrekey::decode(&bytes).map_err(|e| format!("bad claimed prekey: {e}"))
+}
@@ -1,12 +1,22 @@
 use std::path::Path;
 
+use quantumshield_refimpl::qsp::{PREKEY_LAST_RESORT_FLAG, PREKEY_LOW_WATER};
+
 use crate::config::{self, Config};
-use crate::relay_client::{post_json, GenericOk, RegisterRequest};
+use crate::relay_client::{
+    post_json, post_json_allow_status, PrekeyUploadRequest, PrekeyUploadResponse, RegisterRequest,
+    RegisterResponse,
+};
 use crate::store::{StoreState, STATE_FILE_NAME};
 use crate::util::{
-    demo_dh_pub_hex, demo_pq_kem_pub_id_hex, demo_pq_prekey_id, load_or_init_state, save_state,
+    demo_dh_pub_hex, demo_pq_kem_pub_id_hex, demo_pq_prekey_id, demo_prekey, load_or_init_state,
+    save_state,
 };
 
+/// One-time prekeys uploaded at registration: enough that the relay does not ask for more
+/// straight away.
+const DEMO_PREKEY_BATCH: u32 = 2 * PREKEY_LOW_WATER as u32;
+
 pub fn run(store_path: &Path, my_id: &str) -> Result<(), String> {
     let cfg_path = store_path.join(config::CONFIG_FILE_NAME);
     let cfg: Config = config::read_config(&cfg_path).map_err(|_| {
@@ -40,15 +50,51 @@ pub fn run(store_path: &Path, my_id: &str) -> Result<(), String> {
         id: my_id.to_string(),
         bundle,
     };
-    let resp: GenericOk = post_json(&cfg.relay_url, "/register", &req, &relay_token)?;
+    let resp: RegisterResponse = post_json(&cfg.relay_url, "/register", &req, &relay_token)?;
     if !resp.ok {
         return Err("relay register failed".to_string());
     }
+    let upload_key = resp
+        .prekey_upload_key
+        .ok_or_else(|| "relay register returned no prekey upload key".to_string())?;
+    upload_prekeys(&cfg, &relay_token, my_id, upload_key)?;
 
     state.my_id = Some(my_id.to_string());
     state.dh_pub_hex = Some(demo_dh_pub_hex(my_id));
     save_state(&state_path, &state)?;
 
     println!("registered id={my_id} with relay {}", cfg.relay_url);
+    println!("uploaded {DEMO_PREKEY_BATCH} one-time prekeys and a last-resort prekey");
+    Ok(())
+}
+
+/// Publish the demo prekey pool of a fresh registration: one-time ids from zero and the first
+/// last-resort id.
+fn upload_prekeys(
+    cfg: &Config,
+    relay_token: &str,
+    my_id: &str,
+    upload_key: String,
+) -> Result<(), String> {
+    let encode = |prekey_id: u32| {
+        demo_prekey(my_id, prekey_id)
+            .encode()
+            .map(hex::encode)
+            .map_err(|e| format!("encode prekey: {e}"))
+    };
+    let req = PrekeyUploadRequest {
+        id: my_id.to_string(),
+        upload_key,
+        prekeys: (0..DEMO_PREKEY_BATCH)
+            .map(encode)
+            .collect::<Result<Vec<_>, _>>()?,
+        last_resort: encode(PREKEY_LAST_RESORT_FLAG)?,
+    };
+    let (status, resp): (u16, PrekeyUploadResponse) =
+        post_json_allow_status(&cfg.relay_url, "/prekeys/upload", &req, relay_token)?;
+    if status != 200 || !resp.ok {
+        let msg = resp.error.unwrap_or_else(|| format!("status {status}"));
+        return Err(format!("relay prekey upload failed: {msg}"));
+    }
     Ok(())
 }
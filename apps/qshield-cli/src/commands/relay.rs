@@ -7,6 +7,7 @@ use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use hex::encode as hex_encode;
+use quantumshield_refimpl::qsp::{PrekeyDirectory, PrekeyError, SignedPrekey, PREKEY_UPLOAD_MAX};
 use serde_json::json;
 use sha2::{Digest, Sha256};
 use tiny_http::{Header, Method, Response, Server};
@@ -15,6 +16,7 @@ use crate::config::{self, Config};
 use crate::relay_client::{post_json, GenericOk, PollRequest, PollResponse, SendRequest};
 
 const MAX_BODY_BYTES: usize = 64 * 1024;
+const MAX_PREKEY_BODY_BYTES: usize = 1024 * 1024;
 const MAX_QUEUE_PER_RECIPIENT: usize = 256;
 const MAX_TOTAL_QUEUE: usize = 10_000;
 const MAX_QUEUE_PER_TOKEN: usize = 512;
@@ -143,6 +145,10 @@ struct RelayState {
     queues: HashMap<String, VecDeque<QueuedMsg>>,
     token_queued: HashMap<String, usize>,
     cover: DemoCoverLedger,
+    prekeys: HashMap<String, PrekeyDirectory>,
+    /// Per-id prekey upload key, issued by `/register`: the relay token is shared, so only the
+    /// registrant holding this key may publish prekeys for its id.
+    prekey_upload_keys: HashMap<String, String>,
     total_msgs: usize,
     next_msg_seq: u64,
 }
@@ -427,8 +433,184 @@ fn handle_request(
                 json!({ "ok": false, "error": "id already registered" }),
             );
         }
-        state.bundles.insert(id, bundle);
-        return json_response(request, 200, json!({ "ok": true }));
+        let upload_key = match random_hex32() {
+            Ok(v) => v,
+            Err(err) => {
+                return json_response(request, 500, json!({ "ok": false, "error": err }));
+            }
+        };
+        state.bundles.insert(id.clone(), bundle);
+        // A fresh registration starts a fresh pool: keys left by an earlier registration of
+        // this id answer to secrets its new holder does not have.
+        state.prekeys.remove(&id);
+        state.prekey_upload_keys.insert(id, upload_key.clone());
+        return json_response(
+            request,
+            200,
+            json!({ "ok": true, "prekey_upload_key": upload_key }),
+        );
+    }
+
+    if method == Method::Post && url == "/prekeys/upload" {
+        let body = match read_json_body(&mut request, MAX_PREKEY_BODY_BYTES) {
+            Ok(v) => v,
+            Err(e) => {
+                let status = json_body_error_status(&e);
+                return json_response(request, status, json!({ "ok": false, "error": e }));
+            }
+        };
+        let Some(id) = body
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+        else {
+            return json_response(request, 400, json!({ "ok": false, "error": "missing id" }));
+        };
+        if !valid_relay_id(&id) {
+            return json_response(
+                request,
+                400,
+                json!({ "ok": false, "error": "invalid id format" }),
+            );
+        }
+        let (one_time, last_resort) = match parse_prekey_upload(&body) {
+            Ok(v) => v,
+            Err(e) => {
+                return json_response(request, 400, json!({ "ok": false, "error": e }));
+            }
+        };
+        let mut state = match lock_relay_state(state) {
+            Ok(guard) => guard,
+            Err(err) => {
+                return json_response(request, 500, json!({ "ok": false, "error": err }));
+            }
+        };
+        if !check_rate_limit(&mut state, &token_value, RateKind::Register) {
+            return rate_limit_response(request, json_response);
+        }
+        let Some(expected_key) = state.prekey_upload_keys.get(&id) else {
+            return json_response(
+                request,
+                404,
+                json!({ "ok": false, "error": "id not registered" }),
+            );
+        };
+        let presented_key = body.get("upload_key").and_then(|v| v.as_str());
+        if !presented_key.is_some_and(|key| upload_key_matches(expected_key, key)) {
+            return json_response(
+                request,
+                403,
+                json!({ "ok": false, "error": "prekey upload not authorized" }),
+            );
+        }
+        // Apply to a copy so a rejected last-resort key does not leave the batch half-stored.
+        let mut directory = state.prekeys.get(&id).cloned().unwrap_or_default();
+        if let Err(err) = apply_prekey_upload(&mut directory, one_time, last_resort) {
+            return json_response(
+                request,
+                409,
+                json!({ "ok": false, "error": err.to_string() }),
+            );
+        }
+        let status = directory.status();
+        state.prekeys.insert(id, directory);
+        return json_response(
+            request,
+            200,
+            json!({
+                "ok": true,
+                "remaining": status.remaining,
+                "last_resort_id": status.last_resort_id,
+                "replenish": status.replenish
+            }),
+        );
+    }
+
+    if method == Method::Post && url == "/prekeys/claim" {
+        let body = match read_json_body(&mut request, MAX_BODY_BYTES) {
+            Ok(v) => v,
+            Err(e) => {
+                let status = json_body_error_status(&e);
+                return json_response(request, status, json!({ "ok": false, "error": e }));
+            }
+        };
+        let Some(id) = body
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+        else {
+            return json_response(request, 400, json!({ "ok": false, "error": "missing id" }));
+        };
+        let mut state = match lock_relay_state(state) {
+            Ok(guard) => guard,
+            Err(err) => {
+                return json_response(request, 500, json!({ "ok": false, "error": err }));
+            }
+        };
+        if !check_rate_limit(&mut state, &token_value, RateKind::Register) {
+            return rate_limit_response(request, json_response);
+        }
+        let claimed = match state.prekeys.get_mut(&id) {
+            Some(directory) => directory.claim(),
+            None => Err(PrekeyError::Exhausted),
+        };
+        return match claimed {
+            Ok(key) => match key.encode() {
+                Ok(bytes) => json_response(
+                    request,
+                    200,
+                    json!({
+                        "ok": true,
+                        "prekey": hex_encode(bytes),
+                        "last_resort": key.is_last_resort()
+                    }),
+                ),
+                Err(_) => json_response(
+                    request,
+                    500,
+                    json!({ "ok": false, "error": "prekey encoding failed" }),
+                ),
+            },
+            Err(err) => json_response(
+                request,
+                404,
+                json!({ "ok": false, "error": err.to_string() }),
+            ),
+        };
+    }
+
+    if method == Method::Post && url == "/prekeys/status" {
+        let body = match read_json_body(&mut request, MAX_BODY_BYTES) {
+            Ok(v) => v,
+            Err(e) => {
+                let status = json_body_error_status(&e);
+                return json_response(request, status, json!({ "ok": false, "error": e }));
+            }
+        };
+        let Some(id) = body
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+        else {
+            return json_response(request, 400, json!({ "ok": false, "error": "missing id" }));
+        };
+        let state = match lock_relay_state(state) {
+            Ok(guard) => guard,
+            Err(err) => {
+                return json_response(request, 500, json!({ "ok": false, "error": err }));
+            }
+        };
+        let status = state.prekeys.get(&id).cloned().unwrap_or_default().status();
+        return json_response(
+            request,
+            200,
+            json!({
+                "ok": true,
+                "remaining": status.remaining,
+                "last_resort_id": status.last_resort_id,
+                "replenish": status.replenish
+            }),
+        );
     }
 
     if method == Method::Post && url == "/cover-traffic" {
@@ -1067,6 +1249,10 @@ fn handle_request(
         if !check_rate_limit(&mut state, &token_value, RateKind::Poll) {
             return rate_limit_response(request, json_response);
         }
+        let prekeys_replenish = state
+            .prekeys
+            .get(&id)
+            .is_some_and(|directory| directory.status().replenish);
         let (msgs, removed, removed_tokens, purged_cover_items) = {
             let queue = state.queues.entry(id).or_default();
             let mut msgs = Vec::new();
@@ -1107,7 +1293,11 @@ fn handle_request(
         if purged_cover_items > 0 {
             state.cover.purged_items = state.cover.purged_items.saturating_add(purged_cover_items);
         }
-        return json_response(request, 200, json!({ "ok": true, "msgs": msgs }));
+        let mut body = json!({ "ok": true, "msgs": msgs });
+        if prekeys_replenish {
+            body["prekeys_replenish"] = json!(true);
+        }
+        return json_response(request, 200, body);
     }
 
     let resp = Response::from_string("not found").with_status_code(404);
@@ -1582,6 +1772,53 @@ fn parse_batch_send_member(value: &serde_json::Value) -> Result<BatchSendMember,
     })
 }
 
+/// `{"prekeys": [hex...], "last_resort": hex}` as canonical `SignedPrekey` encodings; at least
+/// one of the two must be present.
+fn parse_prekey_upload(
+    body: &serde_json::Value,
+) -> Result<(Vec<SignedPrekey>, Option<SignedPrekey>), &'static str> {
+    let decode = |value: &serde_json::Value| -> Result<SignedPrekey, &'static str> {
+        let hex_str = value.as_str().ok_or("invalid prekey encoding")?;
+        let bytes = hex::decode(hex_str).map_err(|_| "invalid prekey encoding")?;
+        SignedPrekey::decode(&bytes).map_err(|_| "invalid prekey encoding")
+    };
+    let one_time = match body.get("prekeys") {
+        None => Vec::new(),
+        Some(list) => {
+            let list = list.as_array().ok_or("invalid prekeys")?;
+            if list.len() > PREKEY_UPLOAD_MAX {
+                return Err("too many prekeys");
+            }
+            list.iter().map(decode).collect::<Result<Vec<_>, _>>()?
+        }
+    };
+    let last_resort = body.get("last_resort").map(decode).transpose()?;
+    if one_time.is_empty() && last_resort.is_none() {
+        return Err("missing prekeys");
+    }
+    Ok((one_time, last_resort))
+}
+
+fn apply_prekey_upload(
+    directory: &mut PrekeyDirectory,
+    one_time: Vec<SignedPrekey>,
+    last_resort: Option<SignedPrekey>,
+) -> Result<(), PrekeyError> {
+    if !one_time.is_empty() {
+        directory.upload(one_time)?;
+    }
+    if let Some(key) = last_resort {
+        directory.publish_last_resort(key)?;
+    }
+    Ok(())
+}
+
+/// Constant-time comparison of the registered and presented upload keys.
+fn upload_key_matches(expected: &str, presented: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), presented.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 fn lock_relay_state(
     state: &Arc<Mutex<RelayState>>,
 ) -> Result<MutexGuard<'_, RelayState>, &'static str> {
@@ -1744,6 +1981,10 @@ fn load_or_generate_token() -> Result<String, String> {
             return Ok(token);
         }
     }
+    random_hex32()
+}
+
+fn random_hex32() -> Result<String, String> {
     let mut buf = [0u8; 32];
     let mut f = File::open("/dev/urandom").map_err(|e| format!("read /dev/urandom: {e}"))?;
     f.read_exact(&mut buf)
@@ -95,15 +95,10 @@ pub fn run(
             .as_ref()
             .ok_or_else(|| "padding enabled but no buckets configured".to_string())?;
         let ct_len = wire_bytes.len() as u32;
-        let mut chosen: Option<u32> = None;
-        for b in buckets {
-            if *b >= ct_len {
-                chosen = Some(*b);
-                break;
-            }
-        }
-        let bucket_size =
-            chosen.ok_or_else(|| "no padding bucket large enough for ciphertext".to_string())?;
+        let bucket_size = config::demo_padding_policy(buckets)?
+            .padded_len(ct_len as usize)
+            .ok_or_else(|| "no padding bucket large enough for ciphertext".to_string())?
+            as u32;
         let pad = bucket_size.saturating_sub(ct_len);
         wire_bytes.extend(std::iter::repeat_n(0u8, pad as usize));
         pad_len = Some(pad);
@@ -1,3 +1,5 @@
+use quantumshield_refimpl::codec::CodecError;
+use quantumshield_refimpl::qse::PaddingPolicy;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
@@ -116,38 +118,37 @@ pub fn demo_padding_buckets_from_env() -> Result<Option<Vec<u32>>, String> {
 }
 
 pub fn validate_demo_padding_buckets(buckets: &[u32]) -> Result<(), String> {
-    if buckets.is_empty() {
-        return Err("padding buckets empty".to_string());
-    }
-    let mut prev: Option<u32> = None;
-    for bucket in buckets {
-        if *bucket == 0 {
-            return Err("padding bucket must be > 0".to_string());
-        }
-        if *bucket > DEMO_PADDING_MAX_PADDED_PAYLOAD_BYTES {
-            return Err("padding bucket exceeds demo maximum".to_string());
-        }
-        if let Some(prev) = prev {
-            if *bucket == prev {
-                return Err("padding bucket duplicate".to_string());
-            }
-            if *bucket < prev {
-                return Err("padding buckets must be sorted".to_string());
-            }
+    demo_padding_policy(buckets).map(|_| ())
+}
+
+/// The configured buckets as the refimpl padding policy `send` pads with.
+pub fn demo_padding_policy(buckets: &[u32]) -> Result<PaddingPolicy, String> {
+    let policy = PaddingPolicy::Buckets(buckets.iter().map(|b| *b as usize).collect());
+    policy.validate().map_err(|e| {
+        match e {
+            CodecError::Invalid("padding_buckets_empty") => "padding buckets empty",
+            CodecError::Invalid("padding_bucket_zero") => "padding bucket must be > 0",
+            CodecError::Invalid("padding_bucket_duplicate") => "padding bucket duplicate",
+            CodecError::Invalid("padding_buckets_unsorted") => "padding buckets must be sorted",
+            _ => "invalid padding buckets",
         }
-        prev = Some(*bucket);
+        .to_string()
+    })?;
+    if buckets
+        .iter()
+        .any(|b| *b > DEMO_PADDING_MAX_PADDED_PAYLOAD_BYTES)
+    {
+        return Err("padding bucket exceeds demo maximum".to_string());
     }
-    Ok(())
+    Ok(policy)
 }
 
 fn demo_padding_max_overhead(buckets: &[u32]) -> u32 {
-    let mut previous = 0;
-    let mut max_overhead = 0;
-    for bucket in buckets {
-        max_overhead = max_overhead.max(bucket.saturating_sub(previous).saturating_sub(1));
-        previous = *bucket;
-    }
-    max_overhead
+    let Ok(policy) = demo_padding_policy(buckets) else {
+        return 0;
+    };
+    let max_len = buckets.last().copied().unwrap_or(0) as usize;
+    policy.overhead(1..=max_len).max_padding as u32
 }
 
 pub fn demo_attachment_size_classes_from_env() -> Result<Option<Vec<u32>>, String> {
@@ -6,6 +6,19 @@ pub struct RegisterRequest {
     pub bundle: serde_json::Value,
 }
 
+#[derive(Debug, Serialize)]
+pub struct PrekeyUploadRequest {
+    pub id: String,
+    pub upload_key: String,
+    pub prekeys: Vec<String>,
+    pub last_resort: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PrekeyClaimRequest {
+    pub id: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SendRequest {
     pub to: String,
@@ -70,6 +83,25 @@ pub struct GenericOk {
     pub ok: bool,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RegisterResponse {
+    pub ok: bool,
+    pub prekey_upload_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrekeyUploadResponse {
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrekeyClaimResponse {
+    pub ok: bool,
+    pub prekey: Option<String>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct EstablishRecordResponse {
     pub ok: bool,
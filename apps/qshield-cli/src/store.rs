@@ -23,6 +23,9 @@ pub struct SessionEntry {
     pub pq_prekey_id: u32,
     pub dh_self_pub_hex: String,
     pub dh_peer_pub_hex: String,
+    /// The peer prekey claimed from the relay for this session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claimed_prekey_id: Option<u32>,
 }
 
 pub fn read_state(path: &Path) -> Result<StoreState, String> {
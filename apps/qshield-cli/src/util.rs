@@ -1,3 +1,4 @@
+use quantumshield_refimpl::qsp::{SignedPrekey, SZ_ED25519_SIG, SZ_MLDSA65_SIG, SZ_MLKEM768_PUB};
 use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::Path;
@@ -32,6 +33,29 @@ pub fn demo_pq_prekey_id(id: &str) -> u32 {
     u32::from_be_bytes([b[0], b[1], b[2], b[3]])
 }
 
+/// Demo prekey `prekey_id` of `id`: deterministic key material in the canonical
+/// `SignedPrekey` layout with zeroed signatures, like the rest of the demo bundle.
+pub fn demo_prekey(id: &str, prekey_id: u32) -> SignedPrekey {
+    let pid = prekey_id.to_be_bytes();
+    let mut pq_pub = Vec::with_capacity(SZ_MLKEM768_PUB + 32);
+    let mut block = 0u32;
+    while pq_pub.len() < SZ_MLKEM768_PUB {
+        pq_pub.extend_from_slice(&demo_hash(
+            "qshield-demo-prekey-pq",
+            &[id.as_bytes(), &pid, &block.to_be_bytes()],
+        ));
+        block += 1;
+    }
+    pq_pub.truncate(SZ_MLKEM768_PUB);
+    SignedPrekey {
+        id: prekey_id,
+        dh_pub: demo_hash("qshield-demo-prekey-dh", &[id.as_bytes(), &pid]),
+        pq_pub,
+        sig_ec: vec![0u8; SZ_ED25519_SIG],
+        sig_pq: vec![0u8; SZ_MLDSA65_SIG],
+    }
+}
+
 pub fn demo_session_id_bytes(a: &str, b: &str) -> [u8; 16] {
     let mut ids = [a, b];
     ids.sort();
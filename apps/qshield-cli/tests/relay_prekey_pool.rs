@@ -0,0 +1,397 @@
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use quantumshield_refimpl::qsp::{
+    SignedPrekey, PREKEY_LAST_RESORT_FLAG, PREKEY_LOW_WATER, SZ_ED25519_SIG, SZ_MLDSA65_SIG,
+    SZ_MLKEM768_PUB,
+};
+use serde_json::{json, Value};
+
+struct RelayHarness {
+    child: Child,
+    addr: String,
+    token: String,
+}
+
+impl RelayHarness {
+    fn start(name: &str) -> Self {
+        let port = free_port();
+        let addr = format!("127.0.0.1:{port}");
+        let token = format!("prekeytoken{name}{port}");
+        let child = Command::new(env!("CARGO_BIN_EXE_qshield"))
+            .args(["relay", "serve", "--listen", &addr])
+            .env("QSHIELD_RELAY_TOKEN", &token)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("start qshield relay");
+        let harness = Self { child, addr, token };
+        harness.wait_ready();
+        harness
+    }
+
+    fn wait_ready(&self) {
+        for _ in 0..50 {
+            if ureq::get(&format!("http://{}/health", self.addr))
+                .call()
+                .is_ok()
+            {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        panic!("relay health check did not become ready");
+    }
+
+    fn post(&self, path: &str, body: Value) -> (u16, Value) {
+        let resp = ureq::post(&format!("http://{}{}", self.addr, path))
+            .set("Content-Type", "application/json")
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .send_json(body);
+        match resp {
+            Ok(resp) => {
+                let status = resp.status();
+                (
+                    status,
+                    resp.into_json::<Value>().expect("parse response json"),
+                )
+            }
+            Err(ureq::Error::Status(status, resp)) => (
+                status,
+                resp.into_json::<Value>()
+                    .expect("parse error response json"),
+            ),
+            Err(err) => panic!("relay post {path} failed: {err}"),
+        }
+    }
+}
+
+impl Drop for RelayHarness {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn unique_temp_dir(name: &str) -> PathBuf {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time")
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "qshield-prekeys-{name}-{}-{now}",
+        std::process::id()
+    ))
+}
+
+/// Run the qshield binary against `relay`, with no actor available so establish stops right
+/// after its relay round trips.
+fn qshield(relay: &RelayHarness, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_qshield"))
+        .args(args)
+        .env("QSHIELD_RELAY_TOKEN", &relay.token)
+        .env("QSHIELD_ACTOR", "/nonexistent/refimpl_actor")
+        .output()
+        .expect("run qshield")
+}
+
+fn init_and_register(relay: &RelayHarness, store: &Path, id: &str) {
+    let store = store.to_str().expect("store path");
+    let url = format!("http://{}", relay.addr);
+    let out = qshield(relay, &["init", "--store", store, "--relay-url", &url]);
+    assert!(out.status.success(), "{out:?}");
+    let out = qshield(relay, &["register", "--store", store, "--id", id]);
+    assert!(out.status.success(), "{out:?}");
+}
+
+fn establish(relay: &RelayHarness, store: &Path, peer: &str) -> Output {
+    qshield(
+        relay,
+        &[
+            "establish",
+            "--store",
+            store.to_str().expect("store path"),
+            "--peer",
+            peer,
+            "--demo-identity-verified",
+        ],
+    )
+}
+
+fn remaining(relay: &RelayHarness, id: &str) -> Value {
+    let (status, body) = relay.post("/prekeys/status", json!({ "id": id }));
+    assert_eq!(status, 200, "{body}");
+    body
+}
+
+fn free_port() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind free port");
+    listener.local_addr().expect("local addr").port()
+}
+
+/// The relay stores prekeys opaquely, so unsigned placeholders suffice here.
+fn prekey_hex(id: u32) -> String {
+    let key = SignedPrekey {
+        id,
+        dh_pub: [id as u8; 32],
+        pq_pub: vec![0u8; SZ_MLKEM768_PUB],
+        sig_ec: vec![0u8; SZ_ED25519_SIG],
+        sig_pq: vec![0u8; SZ_MLDSA65_SIG],
+    };
+    hex::encode(key.encode().expect("encode prekey"))
+}
+
+/// Register `id` and return the prekey upload key the relay issued for it.
+fn register(relay: &RelayHarness, id: &str) -> String {
+    let (status, body) = relay.post("/register", json!({ "id": id, "bundle": { "id": id } }));
+    assert_eq!(status, 200, "{body}");
+    body["prekey_upload_key"]
+        .as_str()
+        .expect("upload key")
+        .to_string()
+}
+
+/// An upload body for `id` carrying `key`, with the given `prekeys`/`last_resort` fields.
+fn upload(key: &str, id: &str, fields: Value) -> Value {
+    let mut body = fields;
+    body["id"] = json!(id);
+    body["upload_key"] = json!(key);
+    body
+}
+
+fn claim(relay: &RelayHarness, id: &str) -> (u16, Value) {
+    relay.post("/prekeys/claim", json!({ "id": id }))
+}
+
+fn claimed_id(body: &Value) -> u32 {
+    let bytes = hex::decode(body["prekey"].as_str().expect("prekey hex")).expect("hex");
+    SignedPrekey::decode(&bytes).expect("decode prekey").id
+}
+
+fn poll_flag(relay: &RelayHarness, id: &str) -> Option<Value> {
+    let (status, body) = relay.post("/poll", json!({ "id": id, "max": 1 }));
+    assert_eq!(status, 200);
+    body.get("prekeys_replenish").cloned()
+}
+
+#[test]
+fn one_time_prekeys_are_claimed_once_then_last_resort_is_served() {
+    let relay = RelayHarness::start("claim");
+    let key = register(&relay, "bob");
+    let last_resort = PREKEY_LAST_RESORT_FLAG | 1;
+    let (status, body) = relay.post(
+        "/prekeys/upload",
+        upload(
+            &key,
+            "bob",
+            json!({
+                "prekeys": [prekey_hex(0), prekey_hex(1), prekey_hex(2)],
+                "last_resort": prekey_hex(last_resort)
+            }),
+        ),
+    );
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(body["remaining"], json!(3));
+    assert_eq!(body["replenish"], json!(true));
+
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        let (status, body) = claim(&relay, "bob");
+        assert_eq!(status, 200);
+        assert_eq!(body["last_resort"], json!(false));
+        ids.push(claimed_id(&body));
+    }
+    assert_eq!(ids, vec![0, 1, 2]);
+
+    for _ in 0..2 {
+        let (status, body) = claim(&relay, "bob");
+        assert_eq!(status, 200);
+        assert_eq!(body["last_resort"], json!(true));
+        assert_eq!(claimed_id(&body), last_resort);
+    }
+
+    let (status, body) = relay.post("/prekeys/status", json!({ "id": "bob" }));
+    assert_eq!(status, 200);
+    assert_eq!(body["remaining"], json!(0));
+    assert_eq!(body["last_resort_id"], json!(last_resort));
+
+    let (status, body) = claim(&relay, "nobody");
+    assert_eq!(status, 404);
+    assert_eq!(body["ok"], json!(false));
+}
+
+#[test]
+fn poll_signals_replenishment_only_while_pool_is_low() {
+    let relay = RelayHarness::start("replenish");
+    assert_eq!(poll_flag(&relay, "bob"), None);
+
+    let key = register(&relay, "bob");
+    let (status, _) = relay.post(
+        "/prekeys/upload",
+        upload(
+            &key,
+            "bob",
+            json!({
+                "prekeys": [prekey_hex(0)],
+                "last_resort": prekey_hex(PREKEY_LAST_RESORT_FLAG)
+            }),
+        ),
+    );
+    assert_eq!(status, 200);
+    assert_eq!(poll_flag(&relay, "bob"), Some(json!(true)));
+    assert_eq!(poll_flag(&relay, "alice"), None);
+
+    let batch: Vec<String> = (1..=PREKEY_LOW_WATER as u32).map(prekey_hex).collect();
+    let (status, body) = relay.post(
+        "/prekeys/upload",
+        upload(&key, "bob", json!({ "prekeys": batch })),
+    );
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(body["replenish"], json!(false));
+    assert_eq!(poll_flag(&relay, "bob"), None);
+
+    let (status, _) = claim(&relay, "bob");
+    assert_eq!(status, 200);
+    let (status, _) = claim(&relay, "bob");
+    assert_eq!(status, 200);
+    assert_eq!(poll_flag(&relay, "bob"), Some(json!(true)));
+}
+
+#[test]
+fn reissued_or_malformed_uploads_are_rejected_without_partial_state() {
+    let relay = RelayHarness::start("reject");
+    let key = register(&relay, "bob");
+    let (status, _) = relay.post(
+        "/prekeys/upload",
+        upload(
+            &key,
+            "bob",
+            json!({ "prekeys": [prekey_hex(0), prekey_hex(1)] }),
+        ),
+    );
+    assert_eq!(status, 200);
+
+    // Reusing an id, even after it was claimed, is refused; so is a stale last-resort key
+    // riding along with an otherwise fresh batch.
+    let (status, _) = claim(&relay, "bob");
+    assert_eq!(status, 200);
+    let (status, body) = relay.post(
+        "/prekeys/upload",
+        upload(&key, "bob", json!({ "prekeys": [prekey_hex(0)] })),
+    );
+    assert_eq!(status, 409, "{body}");
+    let (status, _) = relay.post(
+        "/prekeys/upload",
+        upload(
+            &key,
+            "bob",
+            json!({ "last_resort": prekey_hex(PREKEY_LAST_RESORT_FLAG | 5) }),
+        ),
+    );
+    assert_eq!(status, 200);
+    let (status, _) = relay.post(
+        "/prekeys/upload",
+        upload(
+            &key,
+            "bob",
+            json!({
+                "prekeys": [prekey_hex(2)],
+                "last_resort": prekey_hex(PREKEY_LAST_RESORT_FLAG | 4)
+            }),
+        ),
+    );
+    assert_eq!(status, 409);
+
+    for body in [
+        upload(&key, "bob", json!({ "prekeys": ["zz"] })),
+        upload(&key, "bob", json!({ "prekeys": [hex::encode([0u8; 8])] })),
+        upload(&key, "bob", json!({ "prekeys": [] })),
+        upload(&key, "Bob!", json!({ "prekeys": [prekey_hex(3)] })),
+    ] {
+        let (status, resp) = relay.post("/prekeys/upload", body);
+        assert_eq!(status, 400, "{resp}");
+    }
+
+    let (status, body) = relay.post("/prekeys/status", json!({ "id": "bob" }));
+    assert_eq!(status, 200);
+    assert_eq!(body["remaining"], json!(1));
+    assert_eq!(body["last_resort_id"], json!(PREKEY_LAST_RESORT_FLAG | 5));
+}
+
+#[test]
+fn uploads_are_bound_to_the_registrant() {
+    let relay = RelayHarness::start("owner");
+    let bob_key = register(&relay, "bob");
+    let mallory_key = register(&relay, "mallory");
+    let batch = json!({ "prekeys": [prekey_hex(0)] });
+
+    // The shared relay token alone is not enough: an unregistered id, a missing key and
+    // another registrant's key are all refused before the pool is touched.
+    let (status, body) = relay.post("/prekeys/upload", upload(&bob_key, "carol", batch.clone()));
+    assert_eq!(status, 404, "{body}");
+    let (status, body) = relay.post(
+        "/prekeys/upload",
+        json!({ "id": "bob", "prekeys": [prekey_hex(0)] }),
+    );
+    assert_eq!(status, 403, "{body}");
+    let (status, body) = relay.post(
+        "/prekeys/upload",
+        upload(&mallory_key, "bob", batch.clone()),
+    );
+    assert_eq!(status, 403, "{body}");
+    let (status, _) = claim(&relay, "bob");
+    assert_eq!(status, 404);
+
+    let (status, body) = relay.post("/prekeys/upload", upload(&bob_key, "bob", batch));
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(body["remaining"], json!(1));
+}
+
+#[test]
+fn register_uploads_a_pool_and_the_initiator_claims_from_it() {
+    let relay = RelayHarness::start("client");
+    let root = unique_temp_dir("client");
+    let (alice, bob) = (root.join("alice"), root.join("bob"));
+    init_and_register(&relay, &alice, "alice");
+    init_and_register(&relay, &bob, "bob");
+
+    let pool = 2 * PREKEY_LOW_WATER;
+    let status = remaining(&relay, "bob");
+    assert_eq!(status["remaining"], json!(pool));
+    assert_eq!(status["last_resort_id"], json!(PREKEY_LAST_RESORT_FLAG));
+
+    // The claim happens before the actor is needed, so it shows even though establish then
+    // fails to start one. Only the initiator (alice, by id order) claims.
+    let out = establish(&relay, &alice, "bob");
+    assert!(!out.status.success());
+    assert_eq!(remaining(&relay, "bob")["remaining"], json!(pool - 1));
+    let out = establish(&relay, &bob, "alice");
+    assert!(!out.status.success());
+    assert_eq!(remaining(&relay, "alice")["remaining"], json!(pool));
+
+    // A peer that never published prekeys cannot be established with.
+    let (status, _) = relay.post(
+        "/register",
+        json!({
+            "id": "carol",
+            "bundle": {
+                "id": "carol",
+                "dh_pub": hex::encode([7u8; 32]),
+                "pq_kem_pub_id": hex::encode([8u8; 32]),
+                "pq_prekey_id": 1
+            }
+        }),
+    );
+    assert_eq!(status, 200);
+    let out = establish(&relay, &alice, "carol");
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("relay prekey claim failed for carol"),
+        "{stderr}"
+    );
+
+    let _ = std::fs::remove_dir_all(&root);
+}
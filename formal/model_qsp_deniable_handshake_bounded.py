@@ -0,0 +1,406 @@
+"""Bounded executable model of the QSP signed/deniable handshake modes.
+
+Goals: G2, G4
+
+The refimpl QSP handshake (`tools/refimpl/quantumshield_refimpl/src/qsp/handshake.rs`) has two
+modes sharing one wire layout, told apart by `suite_id`:
+
+- SIGNED (`QSP_SUITE_ID`): HS1/HS2 transcripts carry Ed25519 + ML-DSA-65 signatures;
+- DENIABLE (`QSP_SUITE_ID_DENIABLE`): signature fields are all-zero. B is authenticated by
+  `conf_b` (keyed by RK0, hence by ss1 decapsulated under B's KT-published SPK_PQ) and A
+  implicitly, because RK0 mixes ss3 encapsulated to A's KT-bound PQ_RCV key.
+
+The mode is bound three ways: each entry point accepts only its own suite, the suite is inside
+both transcripts (so inside `conf_b`), and the RK0 master-secret label is mode-specific. The
+responder admits modes through a `HandshakeModePolicy` before any HS1 processing.
+
+This model enumerates initiator mode x responder policy x adversary action on HS1 x adversary
+action on HS2 x every subset of the four honest long-term secrets, and checks downgrade
+resistance, signature-freedom of deniable runs, policy enforcement, KEM-based authentication,
+and reject hygiene. Crypto is symbolic: a key is its derivation tuple, a signature is valid iff
+it is the signer's tag over exactly the verified transcript, and the adversary knows a KEM
+shared secret iff it produced the ciphertext or holds the decapsulation key.
+
+Two counterfactuals keep the "zero counterexamples" results honest: a responder that picks the
+mode from signature presence instead of `suite_id` + policy is caught downgrading a SIGNED_ONLY
+responder, and an RK0 without the ss3 mix lets an adversary holding nothing impersonate A in
+the deniable mode.
+"""
+
+from __future__ import annotations
+
+from dataclasses import dataclass
+from enum import Enum
+from itertools import combinations
+from typing import Dict, FrozenSet, Iterable, Optional, Tuple
+
+
+class Mode(str, Enum):
+    SIGNED = "signed"
+    DENIABLE = "deniable"
+
+
+class Policy(str, Enum):
+    SIGNED_ONLY = "signed_only"
+    DENIABLE_ONLY = "deniable_only"
+    EITHER = "either"
+
+
+SUITE = {Mode.SIGNED: 0x0001, Mode.DENIABLE: 0x0101}
+MODE_OF_SUITE = {v: k for k, v in SUITE.items()}
+MS_LABEL = {Mode.SIGNED: "QSP4.3/MS", Mode.DENIABLE: "QSP4.3/MS/DENIABLE"}
+
+# Honest long-term secrets the adversary may hold.
+A_RCV = "a_pq_rcv"
+B_SPK = "b_spk_pq"
+A_SIG = "a_sig"
+B_SIG = "b_sig"
+SECRETS = (A_RCV, B_SPK, A_SIG, B_SIG)
+
+HS1_ACTIONS = (
+    "pass",
+    "rewrite_suite",
+    "strip_to_deniable",
+    "replay_other_mode",
+    "forge_signed",
+    "forge_deniable",
+)
+HS2_ACTIONS = ("pass", "rewrite_suite", "strip_to_deniable", "forge_signed", "forge_deniable")
+
+REJECT_REASONS = frozenset(
+    {
+        "mode_not_admitted",
+        "suite_id_mismatch",
+        "bad_signature",
+        "sig_nonzero",
+        "bad_confirmation",
+        "no_hs2",
+    }
+)
+
+ZERO = None
+
+
+def _other(mode: Mode) -> Mode:
+    return Mode.DENIABLE if mode is Mode.SIGNED else Mode.SIGNED
+
+
+def _admits(policy: Policy, mode: Mode) -> bool:
+    return policy is Policy.EITHER or (
+        (policy is Policy.SIGNED_ONLY) == (mode is Mode.SIGNED)
+    )
+
+
+@dataclass(frozen=True, slots=True)
+class Hs1:
+    suite: int
+    ct1: str  # whose encapsulation: honest run tag or "E"
+    sig: Optional[Tuple[str, tuple]]
+
+    def transcript(self) -> tuple:
+        # Signatures are zeroed in the transcript (HS1 = SHA512("QSP4.3/HS1" || ...)).
+        return ("hs1", self.suite, self.ct1, A_RCV)
+
+
+@dataclass(frozen=True, slots=True)
+class Hs2:
+    suite: int
+    ct3: str
+    hs1_seen: tuple
+    conf: tuple
+    sig: Optional[Tuple[str, tuple]]
+
+    def transcript(self, hs1_transcript: tuple) -> tuple:
+        return ("hs2", hs1_transcript, self.suite, self.ct3)
+
+
+@dataclass(frozen=True, slots=True)
+class Config:
+    initiator_mode: Mode
+    policy: Policy
+    hs1_action: str
+    hs2_action: str
+    compromised: FrozenSet[str]
+
+
+@dataclass(frozen=True, slots=True)
+class Rules:
+    mode_from_suite: bool = True  # landed: responder_mode(policy, hs1) + per-mode entry points
+    mix_ss3: bool = True  # landed: RK0 = KMAC(rk0_pre, "QSP4.3/RK0/SS3", session_id || ss3)
+
+
+LANDED = Rules()
+
+
+@dataclass(frozen=True, slots=True)
+class Session:
+    mode: Mode
+    rk: tuple
+
+
+@dataclass(frozen=True, slots=True)
+class Outcome:
+    responder: Optional[Session]
+    responder_reject: Optional[str]
+    initiator: Optional[Session]
+    initiator_reject: Optional[str]
+    hs2_from_responder: bool
+    honest_signatures: int
+    accepted_deniable_sigs: int
+    adversary_knows_responder_rk: bool
+
+
+def _sign(signer: str, transcript: tuple) -> Tuple[str, tuple]:
+    return (signer, transcript)
+
+
+def _rk(rules: Rules, mode: Mode, ss1: str, ss3: str) -> tuple:
+    if rules.mix_ss3:
+        return ("rk", MS_LABEL[mode], ss1, ss3)
+    return ("rk", MS_LABEL[mode], ss1)
+
+
+def _honest_hs1(mode: Mode, run: str) -> Tuple[Hs1, int]:
+    hs1 = Hs1(suite=SUITE[mode], ct1=run, sig=ZERO)
+    if mode is Mode.SIGNED:
+        return Hs1(hs1.suite, hs1.ct1, _sign("A", hs1.transcript())), 1
+    return hs1, 0
+
+
+def _adversary_hs1(cfg: Config, honest: Hs1) -> Hs1:
+    c = cfg.compromised
+    action = cfg.hs1_action
+    if action == "pass":
+        return honest
+    if action == "replay_other_mode":
+        # A's genuine HS1 from an earlier run in the other mode.
+        return _honest_hs1(_other(cfg.initiator_mode), "old")[0]
+    if action == "forge_deniable":
+        return Hs1(SUITE[Mode.DENIABLE], "E", ZERO)
+    if action == "forge_signed":
+        forged = Hs1(SUITE[Mode.SIGNED], "E", ZERO)
+        sig = _sign("A", forged.transcript()) if A_SIG in c else ZERO
+        return Hs1(forged.suite, forged.ct1, sig)
+    if action == "strip_to_deniable":
+        return Hs1(SUITE[Mode.DENIABLE], honest.ct1, ZERO)
+    assert action == "rewrite_suite"
+    target = MODE_OF_SUITE[honest.suite]
+    rewritten = Hs1(SUITE[_other(target)], honest.ct1, honest.sig)
+    if _other(target) is Mode.SIGNED and A_SIG in c:
+        return Hs1(rewritten.suite, rewritten.ct1, _sign("A", rewritten.transcript()))
+    return rewritten
+
+
+def _responder(
+    rules: Rules, policy: Policy, hs1: Hs1
+) -> Tuple[Optional[Session], Optional[Hs2], Optional[str], int]:
+    if rules.mode_from_suite:
+        mode = MODE_OF_SUITE[hs1.suite]
+        if not _admits(policy, mode):
+            return None, None, "mode_not_admitted", 0
+    else:
+        mode = Mode.DENIABLE if hs1.sig is ZERO else Mode.SIGNED
+    if mode is Mode.SIGNED:
+        if hs1.sig != _sign("A", hs1.transcript()):
+            return None, None, "bad_signature", 0
+    elif hs1.sig is not ZERO:
+        return None, None, "sig_nonzero", 0
+
+    ss1 = "ss1:" + hs1.ct1
+    ss3 = "ss3:B"
+    rk = _rk(rules, mode, ss1, ss3)
+    unsigned = Hs2(SUITE[mode], "B", hs1.transcript(), (), ZERO)
+    hs2_t = unsigned.transcript(hs1.transcript())
+    conf = ("conf", rk, hs2_t)
+    sig = _sign("B", hs2_t) if mode is Mode.SIGNED else ZERO
+    signatures = 1 if mode is Mode.SIGNED else 0
+    return Session(mode, rk), Hs2(unsigned.suite, "B", hs1.transcript(), conf, sig), None, signatures
+
+
+def _adversary_hs2(
+    rules: Rules, cfg: Config, own_hs1: Hs1, honest: Optional[Hs2]
+) -> Optional[Hs2]:
+    c = cfg.compromised
+    action = cfg.hs2_action
+    if action in ("forge_signed", "forge_deniable"):
+        mode = Mode.SIGNED if action == "forge_signed" else Mode.DENIABLE
+        unsigned = Hs2(SUITE[mode], "E", own_hs1.transcript(), (), ZERO)
+        hs2_t = unsigned.transcript(own_hs1.transcript())
+        # The adversary chose ss3; ss1 needs ct1 decapsulated under B's SPK_PQ.
+        conf = ("conf", _rk(rules, mode, "ss1:" + own_hs1.ct1, "ss3:E"), hs2_t) if B_SPK in c else ()
+        sig = _sign("B", hs2_t) if mode is Mode.SIGNED and B_SIG in c else ZERO
+        return Hs2(unsigned.suite, "E", unsigned.hs1_seen, conf, sig)
+    if honest is None:
+        return None
+    if action == "pass":
+        return honest
+    if action == "strip_to_deniable":
+        return Hs2(SUITE[Mode.DENIABLE], honest.ct3, honest.hs1_seen, honest.conf, ZERO)
+    assert action == "rewrite_suite"
+    target = _other(MODE_OF_SUITE[honest.suite])
+    rewritten = Hs2(SUITE[target], honest.ct3, honest.hs1_seen, honest.conf, honest.sig)
+    if target is Mode.SIGNED and B_SIG in c:
+        resigned = _sign("B", rewritten.transcript(rewritten.hs1_seen))
+        return Hs2(rewritten.suite, rewritten.ct3, rewritten.hs1_seen, rewritten.conf, resigned)
+    return rewritten
+
+
+def _initiator(
+    rules: Rules, mode: Mode, own_hs1: Hs1, hs2: Optional[Hs2]
+) -> Tuple[Optional[Session], Optional[str]]:
+    if hs2 is None:
+        return None, "no_hs2"
+    if hs2.suite != SUITE[mode] or own_hs1.suite != SUITE[mode]:
+        return None, "suite_id_mismatch"
+    hs2_t = hs2.transcript(own_hs1.transcript())
+    if mode is Mode.SIGNED:
+        if hs2.sig != _sign("B", hs2_t):
+            return None, "bad_signature"
+    elif hs2.sig is not ZERO:
+        return None, "sig_nonzero"
+    rk = _rk(rules, mode, "ss1:" + own_hs1.ct1, "ss3:" + hs2.ct3)
+    if hs2.conf != ("conf", rk, hs2_t):
+        return None, "bad_confirmation"
+    return Session(mode, rk), None
+
+
+def _adversary_knows(rules: Rules, c: FrozenSet[str], rk: tuple) -> bool:
+    ss1 = rk[2]
+    knows_ss1 = ss1 == "ss1:E" or B_SPK in c
+    if not rules.mix_ss3:
+        return knows_ss1
+    knows_ss3 = rk[3] == "ss3:E" or A_RCV in c
+    return knows_ss1 and knows_ss3
+
+
+def run(cfg: Config, rules: Rules = LANDED) -> Outcome:
+    own_hs1, signatures = _honest_hs1(cfg.initiator_mode, "cur")
+    delivered = _adversary_hs1(cfg, own_hs1)
+    resp, hs2, resp_reject, resp_sigs = _responder(rules, cfg.policy, delivered)
+    signatures += resp_sigs
+    delivered_hs2 = _adversary_hs2(rules, cfg, own_hs1, hs2)
+    init, init_reject = _initiator(rules, cfg.initiator_mode, own_hs1, delivered_hs2)
+
+    deniable_sigs = 0
+    if resp is not None and resp.mode is Mode.DENIABLE and delivered.sig is not ZERO:
+        deniable_sigs += 1
+    if init is not None and init.mode is Mode.DENIABLE and delivered_hs2.sig is not ZERO:
+        deniable_sigs += 1
+    return Outcome(
+        responder=resp,
+        responder_reject=resp_reject,
+        initiator=init,
+        initiator_reject=init_reject,
+        hs2_from_responder=delivered_hs2 is not None and delivered_hs2.ct3 == "B",
+        honest_signatures=signatures,
+        accepted_deniable_sigs=deniable_sigs,
+        adversary_knows_responder_rk=resp is not None
+        and _adversary_knows(rules, cfg.compromised, resp.rk),
+    )
+
+
+def _subsets() -> Iterable[FrozenSet[str]]:
+    for n in range(len(SECRETS) + 1):
+        for combo in combinations(SECRETS, n):
+            yield frozenset(combo)
+
+
+def _configs() -> Iterable[Config]:
+    for mode in Mode:
+        for policy in Policy:
+            for a1 in HS1_ACTIONS:
+                for a2 in HS2_ACTIONS:
+                    for c in _subsets():
+                        yield Config(mode, policy, a1, a2, c)
+
+
+def _check(cfg: Config, out: Outcome) -> None:
+    c = cfg.compromised
+    # P17: an initiator completes only in the mode it chose, and only against the honest
+    # responder's session in that mode with the same root -- unless B's SPK_PQ is compromised.
+    if out.initiator is not None:
+        assert out.initiator.mode is cfg.initiator_mode
+        if out.hs2_from_responder:
+            assert out.responder is not None
+            assert out.responder.mode is cfg.initiator_mode
+            assert out.responder.rk == out.initiator.rk
+        else:
+            assert B_SPK in c, cfg
+    # P18: deniable runs produce and accept no signatures.
+    assert out.accepted_deniable_sigs == 0, cfg
+    if cfg.initiator_mode is Mode.DENIABLE and out.responder is not None:
+        if out.responder.mode is Mode.DENIABLE:
+            assert out.honest_signatures == 0, cfg
+    # P19: the responder never holds a session in a mode its policy does not admit.
+    if out.responder is not None:
+        assert _admits(cfg.policy, out.responder.mode), cfg
+    # P20: a responder session attributed to A is readable by the adversary only if A's
+    # PQ_RCV secret is compromised (implicit, KEM-based authentication of A).
+    if out.adversary_knows_responder_rk:
+        assert A_RCV in c, cfg
+    # P21: rejects commit nothing and carry a reason from the fixed set.
+    assert (out.responder is None) == (out.responder_reject is not None)
+    assert (out.initiator is None) == (out.initiator_reject is not None)
+    for reason in (out.responder_reject, out.initiator_reject):
+        assert reason is None or reason in REJECT_REASONS, reason
+
+
+def check_qsp_deniable_handshake_model() -> Dict[str, int]:
+    """Run the bounded signed/deniable mode checks.
+
+    Returns model statistics; raises AssertionError on any property violation.
+    """
+
+    configs = tuple(_configs())
+    assert len(configs) == 2 * 3 * len(HS1_ACTIONS) * len(HS2_ACTIONS) * 16
+
+    stats = {
+        "configurations": len(configs),
+        "initiator_completions": 0,
+        "deniable_completions": 0,
+        "responder_rejects": 0,
+        "initiator_rejects": 0,
+        "kci_canaries": 0,
+        "b_spk_canaries": 0,
+        "counterfactual_policy_violations": 0,
+        "counterfactual_impersonations": 0,
+    }
+    blind = Rules(mode_from_suite=False)
+    unmixed = Rules(mix_ss3=False)
+    for cfg in configs:
+        out = run(cfg)
+        assert run(cfg) == out  # deterministic
+        _check(cfg, out)
+        if out.initiator is not None:
+            stats["initiator_completions"] += 1
+            if out.initiator.mode is Mode.DENIABLE and out.hs2_from_responder:
+                stats["deniable_completions"] += 1
+            if not out.hs2_from_responder:
+                stats["b_spk_canaries"] += 1
+        else:
+            stats["initiator_rejects"] += 1
+        if out.responder is None:
+            stats["responder_rejects"] += 1
+        elif out.adversary_knows_responder_rk:
+            stats["kci_canaries"] += 1
+
+        cf = run(cfg, blind)
+        if cf.responder is not None and not _admits(cfg.policy, cf.responder.mode):
+            stats["counterfactual_policy_violations"] += 1
+        cf = run(cfg, unmixed)
+        if cf.adversary_knows_responder_rk and A_RCV not in cfg.compromised:
+            stats["counterfactual_impersonations"] += 1
+
+    # Non-vacuity: honest deniable runs complete, the adversary's capabilities are reachable
+    # when the assumptions are dropped, and each landed defence is load-bearing.
+    assert stats["deniable_completions"] > 0
+    assert stats["kci_canaries"] > 0
+    assert stats["b_spk_canaries"] > 0
+    assert stats["counterfactual_policy_violations"] > 0
+    assert stats["counterfactual_impersonations"] > 0
+    return stats
+
+
+if __name__ == "__main__":
+    for key, value in check_qsp_deniable_handshake_model().items():
+        print(f"{key}: {value}")
@@ -22,6 +22,9 @@ if REPO_ROOT not in sys.path:
     sys.path.insert(0, REPO_ROOT)
 
 from formal.model_scka_bounded import explore  # noqa: E402
+from formal.model_qsp_deniable_handshake_bounded import (  # noqa: E402
+    check_qsp_deniable_handshake_model,
+)
 from formal.model_qsc_handshake_authentication_bounded import (  # noqa: E402
     emit_qsc_hs_handshake_authentication_model_report,
 )
@@ -86,6 +89,20 @@ def main() -> int:
     hs_p3 = hs_auth["p3"]
     print(f"QSC.HS auth P3 reverse-pin redundant: {hs_p3.redundant}")
     print(f"QSC.HS auth P3 unbound-sig commits: {hs_p3.unbound_commits}")
+    print("QSP signed/deniable handshake-mode bounded model checks")
+    deniable = check_qsp_deniable_handshake_model()
+    print("OK: QSP deniable handshake-mode formal model checks passed")
+    print(f"QSP mode configurations: {deniable['configurations']}")
+    print(f"QSP mode initiator completions: {deniable['initiator_completions']}")
+    print(f"QSP deniable completions: {deniable['deniable_completions']}")
+    print(
+        "QSP mode counterfactual policy violations: "
+        f"{deniable['counterfactual_policy_violations']}"
+    )
+    print(
+        "QSP mode counterfactual impersonations: "
+        f"{deniable['counterfactual_impersonations']}"
+    )
     return 0
 
 
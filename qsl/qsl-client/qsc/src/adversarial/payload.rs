@@ -35,6 +35,64 @@ pub struct ReceiptControlPayload {
     /// their behaviour is byte-identical to before this lane.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ns: Option<String>,
+    /// DESIGN F4: the disappearing-message timer a `t=timer` announcement carries, in
+    /// seconds. `0` turns the timer off. Absent on every other type, so their bytes are
+    /// unchanged, and an older build that meets it ignores the field and then the type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expire_s: Option<u64>,
+    /// DESIGN F5: the group a `t=data` envelope's body belongs to. Absent on one-to-one
+    /// traffic, so those bytes are unchanged.
+    ///
+    /// ⚠ An older build unwraps the envelope and shows the body as a one-to-one message from
+    /// the sender. That is a misfiled message, not a lost one, and it is the price of reusing
+    /// the data envelope rather than minting a type older builds would quarantine.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// DESIGN F5: the signed membership statement a `t=group` control carries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub membership: Option<GroupMembershipSigned>,
+    /// DESIGN F5: the hex `SenderKeyDistribution` a `t=group`, `kind=sender_key` control
+    /// carries. It holds a chain key, so it only ever travels inside a pairwise session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender_key: Option<String>,
+}
+
+/// A group membership statement and the ML-DSA signature over it.
+///
+/// `body` is the statement's JSON TEXT, and the signature covers those exact bytes, so the
+/// receiver verifies before it parses and no canonical re-encoding is ever needed. The key
+/// travels with it because a contact record pins only the key's fingerprint (`sig_fp`).
+/// Hex rather than byte arrays: a 50-member statement must stay well inside one Suite-2 body.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GroupMembershipSigned {
+    pub body: String,
+    pub sig_pk: String,
+    pub sig: String,
+}
+
+/// The statement itself. Members are signing-key fingerprints (a contact's `sig_fp`), never
+/// contact labels: a label is local to whoever chose it, while the fingerprint names the same
+/// key everywhere and is the key the statement is verified under.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct GroupMembershipBody {
+    pub v: u8,
+    /// `membership` (the admin's full list) or `leave` (a member removing itself).
+    pub op: String,
+    pub group_id: String,
+    pub name: String,
+    /// Strictly increasing per group, bumped by the admin on every change. A `leave` carries
+    /// the epoch it was sent at and does not bump it.
+    pub epoch: u64,
+    pub admin: String,
+    pub members: Vec<String>,
+    /// `sender_key` for a sender-key group; absent for pairwise fan-out, so a pairwise
+    /// group's statements are byte-identical to before. Fixed when the group is created.
+    ///
+    /// ⚠ An older build refuses a statement carrying it (`deny_unknown_fields`), so it never
+    /// joins a sender-key group -- better than joining one whose messages it cannot open.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
 }
 
 /// The one namespace marker. Anything carrying it is ours; anything else is a user message.
@@ -44,6 +102,59 @@ pub const CTRL_NS: &str = "qsc.ctrl";
 /// IGNORED rather than decoded on today's rules.
 pub const CTRL_VERSION_MAX: u8 = 2;
 
+/// The disappearing-timer announcement: `t=timer`, `kind=disappearing`, `expire_s` set.
+pub const TIMER_CONTROL_TYPE: &str = "timer";
+pub const DISAPPEARING_TIMER_KIND: &str = "disappearing";
+
+/// The read receipt: `t=ack`, `kind=read`. v2 only -- it postdates the marker, so there is no
+/// legacy shape to match.
+pub const READ_RECEIPT_KIND: &str = "read";
+
+/// A group membership change: `t=group`, `kind=membership` or `kind=leave`, `membership` set.
+pub const GROUP_CONTROL_TYPE: &str = "group";
+pub const GROUP_MEMBERSHIP_KIND: &str = "membership";
+pub const GROUP_LEAVE_KIND: &str = "leave";
+pub const GROUP_MEMBERSHIP_VERSION: u8 = 1;
+/// A member's sender key for a group: `t=group`, `kind=sender_key`, `group` and `sender_key`
+/// set.
+pub const GROUP_SENDER_KEY_KIND: &str = "sender_key";
+/// The one group mode besides pairwise fan-out.
+pub const GROUP_MODE_SENDER_KEY: &str = "sender_key";
+
+/// The largest group either side will create or accept, counting its own member. Fan-out is
+/// pairwise, so every message costs one Suite-2 send per member; this is where that stops
+/// being reasonable.
+pub const GROUP_MEMBERS_MAX: usize = 50;
+
+/// The cap for a sender-key group. A message is sealed once whatever the size; what still
+/// grows with it is the membership statement and one distribution per member per rotation.
+pub const GROUP_SENDER_KEY_MEMBERS_MAX: usize = 200;
+
+/// The member cap for a group mode, or `None` for a mode this build does not know.
+pub fn group_members_cap(mode: Option<&str>) -> Option<usize> {
+    match mode {
+        None => Some(GROUP_MEMBERS_MAX),
+        Some(GROUP_MODE_SENDER_KEY) => Some(GROUP_SENDER_KEY_MEMBERS_MAX),
+        Some(_) => None,
+    }
+}
+
+/// A group id is 16 CSPRNG bytes rendered as 32 lowercase hex characters.
+pub fn group_id_valid(id: &str) -> bool {
+    id.len() == 32
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+/// The longest timer either side will set or accept: four weeks.
+pub const DISAPPEARING_TIMER_MAX_S: u64 = 28 * 24 * 60 * 60;
+
+/// A timer value this build will store. `0` is valid and means "off".
+pub fn disappearing_timer_valid(expire_s: u64) -> bool {
+    expire_s <= DISAPPEARING_TIMER_MAX_S
+}
+
 /// What a decoded control payload is, from the receiver's point of view.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ControlClass {
@@ -51,8 +162,16 @@ pub enum ControlClass {
     DeliveredAck,
     /// A data envelope carrying a body plus a delivery-receipt request.
     DataEnvelope,
+    /// The peer displayed a message of ours (`t=ack`, `kind=read`). Opt-in on their side.
+    ReadAck,
+    /// The peer announced a disappearing-message timer for this conversation.
+    DisappearingTimer,
+    /// A signed group membership change. The signature is checked by `groups`, not here.
+    GroupMembership,
+    /// A member's sender key for a sender-key group. Checked and stored by `groups`.
+    GroupSenderKey,
     /// Recognisably OURS (carries `ns`) but of a type this build does not know.
-    /// ⚠ IGNORE IT -- never render it to the user. This is the read-receipt seam.
+    /// ⚠ IGNORE IT -- never render it to the user. This is the seam new types ride on.
     UnknownControl,
     /// Not ours. Deliver it as an ordinary message, exactly as before.
     NotControl,
@@ -86,9 +205,39 @@ pub fn classify_control(ctrl: &ReceiptControlPayload) -> ControlClass {
     if ours && known_version && ctrl.kind == "delivered" && ctrl.t == "data" {
         return ControlClass::DataEnvelope;
     }
+    if ours && known_version && ctrl.kind == READ_RECEIPT_KIND && ctrl.t == "ack" {
+        return ControlClass::ReadAck;
+    }
+    // ⚠ A timer with no value is not a timer. It stays UnknownControl rather than being read
+    // as "off", because turning a conversation's expiry off is not something to infer.
+    if ours
+        && known_version
+        && ctrl.kind == DISAPPEARING_TIMER_KIND
+        && ctrl.t == TIMER_CONTROL_TYPE
+        && ctrl.expire_s.is_some()
+    {
+        return ControlClass::DisappearingTimer;
+    }
+    if ours
+        && known_version
+        && ctrl.t == GROUP_CONTROL_TYPE
+        && (ctrl.kind == GROUP_MEMBERSHIP_KIND || ctrl.kind == GROUP_LEAVE_KIND)
+        && ctrl.membership.is_some()
+    {
+        return ControlClass::GroupMembership;
+    }
+    if ours
+        && known_version
+        && ctrl.t == GROUP_CONTROL_TYPE
+        && ctrl.kind == GROUP_SENDER_KEY_KIND
+        && ctrl.group.is_some()
+        && ctrl.sender_key.is_some()
+    {
+        return ControlClass::GroupSenderKey;
+    }
     if ours {
-        // Ours, but a type this build does not know -- the seam a future read-receipt
-        // rides on. Ignoring it is what makes "no format break" true.
+        // Ours, but a type this build does not know -- the seam the read receipt rode in
+        // on, and the next type will too. Ignoring it is what makes "no format break" true.
         return ControlClass::UnknownControl;
     }
     ControlClass::NotControl
@@ -237,6 +386,10 @@ mod tests {
             msg_id: "0123456789abcdef0123456789abcdef".to_string(),
             body: None,
             ns: ns.map(|x| x.to_string()),
+            expire_s: None,
+            group: None,
+            membership: None,
+            sender_key: None,
         }
     }
 
@@ -267,7 +420,7 @@ mod tests {
 
     #[test]
     fn an_unknown_control_type_carrying_the_marker_is_ignored_not_rendered() {
-        // The read-receipt seam: a future type must be IGNORED by this build, which is what
+        // The forward-compat seam: a future type must be IGNORED by this build, which is what
         // makes DESIGN F2's "no format break" true rather than aspirational.
         assert_eq!(
             classify_control(&ctrl(2, "read_receipt", "read", Some(CTRL_NS))),
@@ -303,6 +456,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_read_ack_is_recognised_only_as_a_v2_ack_with_the_marker() {
+        assert_eq!(
+            classify_control(&ctrl(2, "ack", READ_RECEIPT_KIND, Some(CTRL_NS))),
+            ControlClass::ReadAck
+        );
+        // There is no v1 read ack; without the marker it is a user message.
+        assert_eq!(
+            classify_control(&ctrl(1, "ack", READ_RECEIPT_KIND, None)),
+            ControlClass::NotControl
+        );
+        // A read kind on a data envelope is not a request this build knows how to honour.
+        assert_eq!(
+            classify_control(&ctrl(2, "data", READ_RECEIPT_KIND, Some(CTRL_NS))),
+            ControlClass::UnknownControl
+        );
+        assert_eq!(
+            classify_control(&ctrl(9, "ack", READ_RECEIPT_KIND, Some(CTRL_NS))),
+            ControlClass::UnknownControl
+        );
+    }
+
+    #[test]
+    fn a_disappearing_timer_is_recognised_only_with_marker_and_value() {
+        let mut timer = ctrl(
+            2,
+            TIMER_CONTROL_TYPE,
+            DISAPPEARING_TIMER_KIND,
+            Some(CTRL_NS),
+        );
+        timer.expire_s = Some(3600);
+        assert_eq!(classify_control(&timer), ControlClass::DisappearingTimer);
+        timer.expire_s = Some(0);
+        assert_eq!(
+            classify_control(&timer),
+            ControlClass::DisappearingTimer,
+            "zero is an explicit off, not a missing value"
+        );
+
+        timer.expire_s = None;
+        assert_eq!(classify_control(&timer), ControlClass::UnknownControl);
+        timer.expire_s = Some(3600);
+        timer.v = 9;
+        assert_eq!(classify_control(&timer), ControlClass::UnknownControl);
+        timer.v = 2;
+        timer.ns = None;
+        assert_eq!(
+            classify_control(&timer),
+            ControlClass::NotControl,
+            "without the marker it is a user message, however it is shaped"
+        );
+    }
+
+    #[test]
+    fn a_group_change_is_recognised_only_with_marker_and_statement() {
+        let mut change = ctrl(2, GROUP_CONTROL_TYPE, GROUP_MEMBERSHIP_KIND, Some(CTRL_NS));
+        assert_eq!(
+            classify_control(&change),
+            ControlClass::UnknownControl,
+            "a change with no statement is not a change"
+        );
+        change.membership = Some(GroupMembershipSigned {
+            body: "{}".to_string(),
+            sig_pk: String::new(),
+            sig: String::new(),
+        });
+        assert_eq!(classify_control(&change), ControlClass::GroupMembership);
+        change.kind = GROUP_LEAVE_KIND.to_string();
+        assert_eq!(classify_control(&change), ControlClass::GroupMembership);
+        change.kind = "rename".to_string();
+        assert_eq!(classify_control(&change), ControlClass::UnknownControl);
+        change.kind = GROUP_MEMBERSHIP_KIND.to_string();
+        change.ns = None;
+        assert_eq!(classify_control(&change), ControlClass::NotControl);
+    }
+
+    #[test]
+    fn a_sender_key_is_recognised_only_with_its_group_and_key() {
+        let mut dist = ctrl(2, GROUP_CONTROL_TYPE, GROUP_SENDER_KEY_KIND, Some(CTRL_NS));
+        dist.group = Some("0123456789abcdef0123456789abcdef".to_string());
+        assert_eq!(classify_control(&dist), ControlClass::UnknownControl);
+        dist.sender_key = Some("00".to_string());
+        assert_eq!(classify_control(&dist), ControlClass::GroupSenderKey);
+        dist.group = None;
+        assert_eq!(classify_control(&dist), ControlClass::UnknownControl);
+        assert_eq!(group_members_cap(None), Some(GROUP_MEMBERS_MAX));
+        assert_eq!(
+            group_members_cap(Some(GROUP_MODE_SENDER_KEY)),
+            Some(GROUP_SENDER_KEY_MEMBERS_MAX)
+        );
+        assert_eq!(group_members_cap(Some("mesh")), None);
+    }
+
+    #[test]
+    fn group_ids_are_fixed_width_lowercase_hex() {
+        assert!(group_id_valid("0123456789abcdef0123456789abcdef"));
+        assert!(!group_id_valid("0123456789ABCDEF0123456789ABCDEF"));
+        assert!(!group_id_valid("0123456789abcdef"));
+        assert!(!group_id_valid("../../0123456789abcdef0123456789a"));
+    }
+
+    #[test]
+    fn disappearing_timer_bounds() {
+        assert!(disappearing_timer_valid(0));
+        assert!(disappearing_timer_valid(DISAPPEARING_TIMER_MAX_S));
+        assert!(!disappearing_timer_valid(DISAPPEARING_TIMER_MAX_S + 1));
+    }
+
     #[test]
     fn a_wrong_namespace_is_not_ours() {
         assert_eq!(
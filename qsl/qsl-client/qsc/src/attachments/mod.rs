@@ -2,6 +2,8 @@
 
 use crate::protocol_state::SendOrigination;
 use super::*;
+use quantumshield_refimpl::crypto::stream::{StreamNonce, StreamOpener, StreamSealer};
+use quantumshield_refimpl::crypto::traits::{Aead as RefimplAead, CryptoError};
 
 type FileConfirmPayload = adversarial::payload::FileConfirmPayload;
 
@@ -201,11 +203,30 @@ fn attachment_ciphertext_part_len(
     usize::try_from(ciphertext_len.checked_sub(offset)?).ok()
 }
 
-fn attachment_nonce(prefix: &[u8; 8], part_index: u32) -> [u8; 12] {
-    let mut out = [0u8; 12];
-    out[..8].copy_from_slice(prefix);
-    out[8..].copy_from_slice(&part_index.to_be_bytes());
-    out
+// The QATT part cipher (DOC-CAN-007 §4.5) behind the refimpl `Aead` trait, so part
+// sealing/opening runs on the shared chunked-AEAD primitive. `StreamNonce::PrefixCounter`
+// reproduces the §4.3 nonce (nonce_prefix || u32be(i)) byte-for-byte; finality stays
+// bound through part_count/part_index in the §4.4 AAD, so the wire format is unchanged.
+struct AttachmentPartAead;
+
+impl RefimplAead for AttachmentPartAead {
+    fn seal(&self, key32: &[u8; 32], nonce12: &[u8; 12], ad: &[u8], pt: &[u8]) -> Vec<u8> {
+        ChaCha20Poly1305::new(Key::from_slice(key32))
+            .encrypt(Nonce::from_slice(nonce12), Payload { msg: pt, aad: ad })
+            .unwrap_or_default()
+    }
+
+    fn open(
+        &self,
+        key32: &[u8; 32],
+        nonce12: &[u8; 12],
+        ad: &[u8],
+        ct: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        ChaCha20Poly1305::new(Key::from_slice(key32))
+            .decrypt(Nonce::from_slice(nonce12), Payload { msg: ct, aad: ad })
+            .map_err(|_| CryptoError::AuthFail)
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -710,7 +731,11 @@ fn attachment_build_outbound_record(
         .map_err(|_| "attachment_stage_unavailable")?;
     #[cfg(unix)]
     enforce_file_perms(&staged_path).map_err(|_| "attachment_stage_unavailable")?;
-    let cipher = ChaCha20Poly1305::new(Key::from_slice(&cek));
+    let mut sealer = StreamSealer::new(
+        &AttachmentPartAead,
+        &cek,
+        StreamNonce::PrefixCounter(nonce_prefix),
+    );
     let capacity =
         attachment_plaintext_capacity(&part_size_class).ok_or("attachment_shape_invalid")?;
     let mut leaves = Vec::with_capacity(part_count as usize);
@@ -744,7 +769,6 @@ fn attachment_build_outbound_record(
             read_len += n;
         }
         content_remaining -= read_len as u64;
-        let nonce = attachment_nonce(&nonce_prefix, produced);
         let aad = attachment_part_aad(
             &attachment_id,
             ATTACHMENT_ENC_CTX_ALG_V1,
@@ -755,14 +779,8 @@ fn attachment_build_outbound_record(
             part_count,
             produced,
         );
-        let ciphertext = cipher
-            .encrypt(
-                Nonce::from_slice(&nonce),
-                Payload {
-                    msg: &buf[..part_plain_len],
-                    aad: &aad,
-                },
-            )
+        let ciphertext = sealer
+            .seal_chunk(&aad, &buf[..part_plain_len], produced + 1 == part_count)
             .map_err(|_| "attachment_encrypt_failed")?;
         dst.write_all(&ciphertext)
             .map_err(|_| "attachment_stage_unavailable")?;
@@ -1424,7 +1442,11 @@ fn attachment_decrypt_to_output(
     let ciphertext_path = attachment_path_from_rel(cfg_dir, rel)?;
     let mut src = File::open(ciphertext_path).map_err(|_| "REJECT_ATT_DECRYPT_CTX_MISMATCH")?;
     let (mut cek, nonce_prefix) = attachment_decode_enc_ctx(&record.enc_ctx_b64u)?;
-    let cipher = ChaCha20Poly1305::new(Key::from_slice(&cek));
+    let mut opener = StreamOpener::new(
+        &AttachmentPartAead,
+        &cek,
+        StreamNonce::PrefixCounter(nonce_prefix),
+    );
     let output_name = record
         .download_output_name
         .clone()
@@ -1463,7 +1485,6 @@ fn attachment_decrypt_to_output(
         let mut ciphertext = vec![0u8; ct_len];
         src.read_exact(&mut ciphertext)
             .map_err(|_| "REJECT_ATT_DECRYPT_AUTH")?;
-        let nonce = attachment_nonce(&nonce_prefix, part_index);
         let aad = attachment_part_aad(
             &record.attachment_id,
             &record.enc_ctx_alg,
@@ -1474,14 +1495,8 @@ fn attachment_decrypt_to_output(
             record.part_count,
             part_index,
         );
-        let plaintext = cipher
-            .decrypt(
-                Nonce::from_slice(&nonce),
-                Payload {
-                    msg: &ciphertext,
-                    aad: &aad,
-                },
-            )
+        let plaintext = opener
+            .open_chunk(&aad, &ciphertext, part_index + 1 == record.part_count)
             .map_err(|_| "REJECT_ATT_DECRYPT_AUTH")?;
         decrypted_total = decrypted_total.saturating_add(plaintext.len() as u64);
         // Write only up to content_len; the padded remainder is verified and discarded.
@@ -1494,9 +1509,12 @@ fn attachment_decrypt_to_output(
         }
     }
     dst.sync_all().map_err(|_| "REJECT_ATT_PLAINTEXT_SHAPE")?;
-    // Exact-length integrity check preserved (over the padded length); and the true
-    // content must be fully recovered.
-    if decrypted_total != record.plaintext_len || written != record.content_len {
+    // Exact-length integrity check preserved (over the padded length); the true content
+    // must be fully recovered, and the final part must have been opened.
+    if decrypted_total != record.plaintext_len
+        || written != record.content_len
+        || opener.finish().is_err()
+    {
         let _ = fs::remove_file(&tmp_path);
         return Err("REJECT_ATT_PLAINTEXT_SHAPE");
     }
@@ -2486,3 +2504,59 @@ mod na0614_padding_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod part_cipher_stream_tests {
+    use super::*;
+
+    // DOC-CAN-007 §4.3–§4.5 computed by hand: the shared stream primitive must produce the
+    // exact v1 part ciphertexts, or attachments sent by older builds stop decrypting.
+    #[test]
+    fn stream_sealer_matches_v1_part_cipher_bytes() {
+        let cek = [0x11u8; 32];
+        let prefix = [0x22u8; 8];
+        let parts: [&[u8]; 2] = [b"first part", b"last"];
+        let mut sealer = StreamSealer::new(
+            &AttachmentPartAead,
+            &cek,
+            StreamNonce::PrefixCounter(prefix),
+        );
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&cek));
+        for (i, pt) in parts.iter().enumerate() {
+            let aad = attachment_part_aad(
+                "id",
+                ATTACHMENT_ENC_CTX_ALG_V1,
+                14,
+                14,
+                46,
+                "p64k",
+                2,
+                i as u32,
+            );
+            let mut nonce = [0u8; 12];
+            nonce[..8].copy_from_slice(&prefix);
+            nonce[8..].copy_from_slice(&(i as u32).to_be_bytes());
+            let want = cipher
+                .encrypt(Nonce::from_slice(&nonce), Payload { msg: pt, aad: &aad })
+                .expect("encrypt");
+            let got = sealer
+                .seal_chunk(&aad, pt, i + 1 == parts.len())
+                .expect("seal");
+            assert_eq!(got, want);
+        }
+    }
+
+    #[test]
+    fn stream_opener_rejects_part_out_of_order() {
+        let cek = [0x33u8; 32];
+        let nonce = StreamNonce::PrefixCounter([0x44u8; 8]);
+        let mut sealer = StreamSealer::new(&AttachmentPartAead, &cek, nonce);
+        let aad0 = attachment_part_aad("id", ATTACHMENT_ENC_CTX_ALG_V1, 2, 2, 34, "p64k", 2, 0);
+        let aad1 = attachment_part_aad("id", ATTACHMENT_ENC_CTX_ALG_V1, 2, 2, 34, "p64k", 2, 1);
+        let _ct0 = sealer.seal_chunk(&aad0, b"a", false).expect("seal 0");
+        let ct1 = sealer.seal_chunk(&aad1, b"b", true).expect("seal 1");
+        let mut opener = StreamOpener::new(&AttachmentPartAead, &cek, nonce);
+        assert!(opener.open_chunk(&aad1, &ct1, false).is_err());
+        assert!(opener.finish().is_err());
+    }
+}
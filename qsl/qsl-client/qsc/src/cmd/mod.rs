@@ -171,7 +171,7 @@ pub enum Cmd {
         #[command(subcommand)]
         cmd: HandshakeCmd,
     },
-    /// Identity utilities (show/rotate).
+    /// Identity utilities (show/rotate/kt-monitor).
     Identity {
         #[command(subcommand)]
         cmd: IdentityCmd,
@@ -196,6 +196,11 @@ pub enum Cmd {
         #[command(subcommand)]
         cmd: TimelineCmd,
     },
+    /// Small groups fanned out over pairwise sessions, with admin-signed membership.
+    Group {
+        #[command(subcommand)]
+        cmd: GroupCmd,
+    },
     /// NA-0682: the durable message queue — status, retry, and the named discard.
     Outbox {
         #[command(subcommand)]
@@ -274,6 +279,11 @@ pub enum SendTransport {
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReceiptKind {
     Delivered,
+    /// The recipient displayed the message. Emitted only by the recipient, under its own
+    /// opt-in, so it is never something a sender can request -- hence hidden from the flags
+    /// that take this type.
+    #[value(skip)]
+    Read,
 }
 
 /// What the CALLER of `qsc send` asked for, as a THREE-state value — NA-0688 C3 (D622 R1b,
@@ -418,6 +428,32 @@ pub enum IdentityCmd {
         #[arg(long)]
         reset_peers: bool,
     },
+    /// Check a Key Transparency log for bundles under this identity that this vault did
+    /// not mint (walks entries appended since the last run).
+    KtMonitor {
+        /// Local label (defaults to the only identity, or "self").
+        #[arg(long = "as", value_name = "LABEL")]
+        as_label: Option<String>,
+        /// User id this identity publishes bundles under.
+        #[arg(long, value_name = "USER_ID")]
+        user_id: String,
+        /// KT log base URL (https, or http to loopback).
+        #[arg(long, value_name = "URL")]
+        log_url: String,
+        /// Pinned 32-byte log id, hex.
+        #[arg(long, value_name = "HEX")]
+        log_id: String,
+        /// Pinned 32-byte Ed25519 STH verification key, hex.
+        #[arg(long, value_name = "HEX")]
+        log_key: String,
+        /// Oldest acceptable STH, in seconds.
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value_t = crate::kt_monitor::KT_MONITOR_DEFAULT_MAX_STH_AGE_SECS
+        )]
+        max_sth_age_secs: u64,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -496,6 +532,57 @@ pub enum ContactsCmd {
         #[command(subcommand)]
         cmd: ContactsRequestCmd,
     },
+    /// Disappearing-message timer for a contact.
+    Timer {
+        #[command(subcommand)]
+        cmd: ContactsTimerCmd,
+    },
+    /// Per-contact read-receipt opt-out (the account switch is `config set read-receipts`).
+    ReadReceipts {
+        #[command(subcommand)]
+        cmd: ContactsReadReceiptsCmd,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ContactsReadReceiptsCmd {
+    /// Show whether read receipts are sent to this contact.
+    Show {
+        #[arg(long, value_name = "LABEL")]
+        label: String,
+    },
+    /// Allow or withhold read receipts for this contact.
+    Set {
+        #[arg(long, value_name = "LABEL")]
+        label: String,
+        #[arg(long, value_enum)]
+        mode: ReadReceiptsMode,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadReceiptsMode {
+    On,
+    Off,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ContactsTimerCmd {
+    /// Announce a disappearing-message timer to the peer and apply it here (0 = off).
+    Set {
+        #[arg(long, value_name = "LABEL")]
+        label: String,
+        #[arg(long, value_name = "SECONDS")]
+        seconds: u64,
+        /// Relay base URL the announcement is pushed through.
+        #[arg(long)]
+        relay: String,
+    },
+    /// Show the current disappearing-message timer.
+    Show {
+        #[arg(long, value_name = "LABEL")]
+        label: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -699,6 +786,72 @@ pub enum TimelineCmd {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum GroupCmd {
+    /// Create a group with this side as admin and announce it to its members.
+    Create {
+        #[arg(long)]
+        name: String,
+        /// Contact label of a member (repeatable).
+        #[arg(long = "member", value_name = "LABEL", required = true)]
+        members: Vec<String>,
+        /// Seal each message once under per-member sender keys instead of per member.
+        #[arg(long)]
+        sender_keys: bool,
+        /// Relay base URL the announcement is pushed through.
+        #[arg(long)]
+        relay: String,
+    },
+    /// Add members (admin only).
+    Add {
+        #[arg(long, value_name = "GROUP_ID")]
+        id: String,
+        #[arg(long = "member", value_name = "LABEL", required = true)]
+        members: Vec<String>,
+        #[arg(long)]
+        relay: String,
+    },
+    /// Remove members (admin only); they are told too.
+    Remove {
+        #[arg(long, value_name = "GROUP_ID")]
+        id: String,
+        #[arg(long = "member", value_name = "LABEL", required = true)]
+        members: Vec<String>,
+        #[arg(long)]
+        relay: String,
+    },
+    /// Leave a group and tell its other members.
+    Leave {
+        #[arg(long, value_name = "GROUP_ID")]
+        id: String,
+        #[arg(long)]
+        relay: String,
+    },
+    /// List the groups this side belongs to.
+    List,
+    /// Show a group's members.
+    Show {
+        #[arg(long, value_name = "GROUP_ID")]
+        id: String,
+    },
+    /// Send a file's bytes to every reachable member.
+    Send {
+        #[arg(long, value_name = "GROUP_ID")]
+        id: String,
+        #[arg(long, value_name = "PATH")]
+        file: PathBuf,
+        #[arg(long)]
+        relay: String,
+    },
+    /// List a group's timeline, with per-member delivery for outbound messages.
+    Timeline {
+        #[arg(long, value_name = "GROUP_ID")]
+        id: String,
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum RelayCmd {
     /// Run a local relay with deterministic fault injection.
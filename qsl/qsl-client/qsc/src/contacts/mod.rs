@@ -70,11 +70,18 @@ pub(super) fn relay_self_inbox_route_token() -> Result<String, &'static str> {
 pub(super) fn relay_peer_route_token(peer: &str) -> Result<String, &'static str> {
     let peer_alias = peer_alias_from_channel(peer);
     let rec = contacts_entry_read(peer_alias).map_err(|_| QSC_ERR_CONTACT_ROUTE_TOKEN_REQUIRED)?;
+    // An `alias#device` channel is that device's own inbox; only the primary falls back to
+    // the contact-level token.
     let token = rec
-        .and_then(|v| {
-            primary_device(&v)
+        .and_then(|v| match channel_device_id(peer) {
+            Some(id) if primary_device(&v).is_none_or(|p| p.device_id != id) => v
+                .devices
+                .iter()
+                .find(|d| d.device_id == id)
+                .and_then(|d| d.route_token.clone()),
+            _ => primary_device(&v)
                 .and_then(|d| d.route_token.clone())
-                .or(v.route_token)
+                .or(v.route_token),
         })
         .ok_or(QSC_ERR_CONTACT_ROUTE_TOKEN_REQUIRED)?;
     normalize_route_token(token.as_str()).map_err(|_| QSC_ERR_CONTACT_ROUTE_TOKEN_REQUIRED)
@@ -301,6 +308,9 @@ pub(super) struct SendRoutingTarget {
     pub(super) implicit_primary: bool,
 }
 
+/// Where a send or handshake to `peer` goes. A bare alias is the contact's primary device; an
+/// `alias#device` channel names one of its other devices, which keeps its own route token and
+/// session and leaves the contact's primary untouched.
 pub(super) fn resolve_peer_device_target(
     peer: &str,
     require_trusted: bool,
@@ -318,7 +328,17 @@ pub(super) fn resolve_peer_device_target(
     let Some(primary) = primary_device(&rec).cloned() else {
         return Err("no_trusted_device");
     };
-    let canonical_state = canonical_device_state(primary.state.as_str());
+    let target = match channel_device_id(peer) {
+        Some(id) if id != primary.device_id => rec
+            .devices
+            .iter()
+            .find(|d| d.device_id == id)
+            .cloned()
+            .ok_or("device_unknown")?,
+        _ => primary.clone(),
+    };
+    let is_primary = target.device_id == primary.device_id;
+    let canonical_state = canonical_device_state(target.state.as_str());
     match canonical_state {
         "CHANGED" => return Err("device_changed_reapproval_required"),
         "REVOKED" => return Err("device_revoked"),
@@ -326,19 +346,24 @@ pub(super) fn resolve_peer_device_target(
         _ if require_trusted => return Err("no_trusted_device"),
         _ => {}
     }
-    let route_token = primary
+    let contact_token = if is_primary {
+        rec.route_token.clone()
+    } else {
+        None
+    };
+    let route_token = target
         .route_token
         .clone()
-        .or_else(|| rec.route_token.clone())
+        .or(contact_token)
         .ok_or("contact_route_token_missing")?;
     let route_token =
         normalize_route_token(route_token.as_str()).map_err(|_| "contact_route_token_missing")?;
-    if rec.route_token != Some(route_token.clone()) {
+    if is_primary && rec.route_token != Some(route_token.clone()) {
         rec.route_token = Some(route_token.clone());
         mutated = true;
     }
-    if rec.primary_device_id.as_deref() != Some(primary.device_id.as_str()) {
-        rec.primary_device_id = Some(primary.device_id.clone());
+    if is_primary && rec.primary_device_id.as_deref() != Some(target.device_id.as_str()) {
+        rec.primary_device_id = Some(target.device_id.clone());
         mutated = true;
     }
     let multi_device = rec.devices.len() > 1;
@@ -346,7 +371,7 @@ pub(super) fn resolve_peer_device_target(
         contacts_entry_upsert(peer_alias, rec).map_err(|_| "contacts_store_invalid")?;
     }
     let channel = if multi_device {
-        channel_label_for_device(peer_alias, primary.device_id.as_str())
+        channel_label_for_device(peer_alias, target.device_id.as_str())
             .ok_or("qsp_channel_invalid")?
     } else {
         peer_alias.to_string()
@@ -354,7 +379,7 @@ pub(super) fn resolve_peer_device_target(
     Ok(SendRoutingTarget {
         peer_alias: peer_alias.to_string(),
         channel,
-        device_id: primary.device_id,
+        device_id: target.device_id,
         route_token,
         implicit_primary,
     })
@@ -364,6 +389,62 @@ pub(super) fn resolve_send_routing_target(peer: &str) -> Result<SendRoutingTarge
     resolve_peer_device_target(peer, true)
 }
 
+/// DESIGN F1 multi-device: where one message to a contact goes.
+pub(super) struct DeviceFanout {
+    /// `(device_id, channel)`, primary first.
+    pub(super) devices: Vec<(String, String)>,
+    /// `(device_id, reason)` for every device the message will not reach.
+    pub(super) skipped: Vec<(String, String)>,
+    /// Set when the primary was skipped: the channel of `devices[0]`, which the contact's own
+    /// queue row drains through in its place.
+    pub(super) route: Option<String>,
+}
+
+/// Every device that is TRUSTED, has its own route token and has an established session,
+/// primary first. Anything less -- the primary included -- is skipped by name rather than
+/// queued to a device that can never take it, so the send fails only when `devices` is empty.
+pub(super) fn contact_fanout_devices(peer_alias: &str) -> Result<DeviceFanout, &'static str> {
+    // Resolving the bare alias first lets it settle an implicit primary, as a send would.
+    let primary = resolve_send_routing_target(peer_alias);
+    let rec = contacts_entry_read(peer_alias)
+        .map_err(|_| "contacts_store_invalid")?
+        .ok_or("unknown_contact")?;
+    let primary_id = primary_device(&rec)
+        .map(|d| d.device_id.clone())
+        .ok_or("no_trusted_device")?;
+    let mut out = DeviceFanout {
+        devices: Vec::new(),
+        skipped: Vec::new(),
+        route: None,
+    };
+    for dev in rec.devices.iter() {
+        let target = if dev.device_id == primary_id {
+            primary.clone()
+        } else {
+            let Some(channel) = channel_label_for_device(peer_alias, dev.device_id.as_str()) else {
+                out.skipped
+                    .push((dev.device_id.clone(), "qsp_channel_invalid".to_string()));
+                continue;
+            };
+            resolve_send_routing_target(channel.as_str())
+        };
+        let reachable = target.map_err(str::to_string).and_then(|t| {
+            protocol_active_or_reason_for_peer(t.channel.as_str()).map(|()| t.channel)
+        });
+        match reachable {
+            Ok(channel) if dev.device_id == primary_id => {
+                out.devices.insert(0, (dev.device_id.clone(), channel))
+            }
+            Ok(channel) => out.devices.push((dev.device_id.clone(), channel)),
+            Err(reason) => out.skipped.push((dev.device_id.clone(), reason)),
+        }
+    }
+    if out.skipped.iter().any(|(id, _)| *id == primary_id) {
+        out.route = out.devices.first().map(|(_, channel)| channel.clone());
+    }
+    Ok(out)
+}
+
 pub(super) fn contacts_store_load() -> Result<ContactsStore, ErrorCode> {
     match vault::secret_get(CONTACTS_SECRET_KEY) {
         Ok(None) => Ok(ContactsStore::default()),
@@ -490,6 +571,19 @@ pub(super) fn contacts_set_blocked(label: &str, blocked: bool) -> Result<bool, E
     Ok(true)
 }
 
+pub(super) fn contacts_set_read_receipts_off(label: &str, off: bool) -> Result<bool, ErrorCode> {
+    if !channel_label_ok(label) {
+        return Err(ErrorCode::ParseFailed);
+    }
+    let mut store = contacts_store_load()?;
+    let Some(rec) = store.peers.get_mut(label) else {
+        return Ok(false);
+    };
+    rec.read_receipts_off = off;
+    contacts_store_save(&store)?;
+    Ok(true)
+}
+
 pub(super) fn contacts_list_entries() -> Result<Vec<(String, ContactRecord)>, ErrorCode> {
     let store = contacts_store_load()?;
     Ok(store.peers.into_iter().collect())
@@ -873,6 +967,8 @@ pub(crate) fn contacts_provision_from_invite(
         // Dormant until the relay-pinning feature exists.
         pinned_cert_fp: None,
         invite_id: Some(invite_id.to_string()),
+        disappearing_timer_s: None,
+        read_receipts_off: false,
     };
     contacts_entry_upsert(alias, rec).map_err(|_| "contacts_store_unavailable")?;
     Ok(fp)
@@ -892,6 +988,45 @@ pub(crate) fn contacts_set_route_token(alias: &str, route_token: &str) -> Result
     contacts_entry_upsert(alias, rec).map_err(|_| "contacts_store_unavailable")
 }
 
+/// DESIGN F4: the conversation's disappearing-message timer in seconds, `None` when off.
+///
+/// ⚠ An unreadable contact store reads as "no timer". The alternative is refusing to store the
+/// message at all, and losing a message is worse than keeping one past its timer.
+pub(crate) fn contact_disappearing_timer(peer: &str) -> Option<u64> {
+    contacts_entry_read(peer_alias_from_channel(peer))
+        .ok()
+        .flatten()
+        .and_then(|rec| rec.disappearing_timer_s)
+}
+
+/// Whether this contact is opted out of read receipts.
+///
+/// ⚠ An unreadable store, or a peer with no contact record, reads as OPTED OUT -- the opposite of
+/// the timer's fallback above, by the same reasoning: here the harmless failure is a receipt not
+/// sent, and the harmful one is a read the user asked to keep private going out.
+pub(crate) fn contact_read_receipts_off(peer: &str) -> bool {
+    match contacts_entry_read(peer_alias_from_channel(peer)) {
+        Ok(Some(rec)) => rec.read_receipts_off,
+        Ok(None) | Err(_) => true,
+    }
+}
+
+/// Store the disappearing timer for an EXISTING contact; `0` turns it off.
+pub(crate) fn contacts_set_disappearing_timer(
+    peer: &str,
+    expire_s: u64,
+) -> Result<(), &'static str> {
+    if !crate::adversarial::payload::disappearing_timer_valid(expire_s) {
+        return Err("disappearing_timer_invalid");
+    }
+    let alias = peer_alias_from_channel(peer);
+    let mut rec = contacts_entry_read(alias)
+        .map_err(|_| "contacts_store_unavailable")?
+        .ok_or("contacts_unknown")?;
+    rec.disappearing_timer_s = (expire_s > 0).then_some(expire_s);
+    contacts_entry_upsert(alias, rec).map_err(|_| "contacts_store_unavailable")
+}
+
 pub fn contacts_add(
     label: &str,
     fp: &str,
@@ -1521,6 +1656,46 @@ pub fn contacts_unblock(label: &str) -> CliResult {
     Ok(())
 }
 
+pub fn contacts_read_receipts_show(label: &str) -> CliResult {
+    require_unlocked("contacts_read_receipts_show")?;
+    let rec = contacts_entry_read(label)
+        .map_err(|_| CliError::code("contacts_store_unavailable"))?
+        .ok_or_else(|| CliError::code("peer_unknown"))?;
+    let mode = if rec.read_receipts_off { "off" } else { "on" };
+    // The account switch is reported beside the contact's, so "on" here is never mistaken for
+    // receipts actually going out while the account opt-in is off.
+    let account = if load_receipt_policy_from_account().read_receipts {
+        "on"
+    } else {
+        "off"
+    };
+    emit_marker(
+        "contacts_read_receipts",
+        None,
+        &[("label", label), ("mode", mode), ("account", account)],
+    );
+    Ok(())
+}
+
+pub fn contacts_read_receipts_set(label: &str, mode: ReadReceiptsMode) -> CliResult {
+    require_unlocked("contacts_read_receipts_set")?;
+    let off = mode == ReadReceiptsMode::Off;
+    match contacts_set_read_receipts_off(label, off) {
+        Ok(true) => emit_marker(
+            "contacts_read_receipts",
+            None,
+            &[
+                ("label", label),
+                ("mode", if off { "off" } else { "on" }),
+                ("ok", "true"),
+            ],
+        ),
+        Ok(false) => return Err(CliError::code("peer_unknown")),
+        Err(_) => return Err(CliError::code("contacts_store_unavailable")),
+    }
+    Ok(())
+}
+
 pub fn contacts_trust_mode_show() -> CliResult {
     require_unlocked("contacts_trust_mode_show")?;
     let mode = load_trust_onboarding_mode_from_account();
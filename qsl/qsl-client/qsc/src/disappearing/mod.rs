@@ -0,0 +1,172 @@
+//! DESIGN F4: per-contact disappearing messages.
+//!
+//! A timer is set on one side and announced to the peer as a `t=timer` control payload, so
+//! both ends of a conversation hold the same value on the contact record. Each side then stamps
+//! `expires_at` on what it stores -- the sender on its message-queue rows at enqueue, both sides
+//! on their timeline rows when they are written -- and `purge_expired_messages` deletes whatever
+//! has run out every time the vault is unlocked (`set_vault_unlocked`, which the CLI, the guarded
+//! unlock and facade hosts all go through). Timeline reads skip expired rows in between, so a
+//! long-running unlocked host never shows a message past its deadline.
+//!
+//! ⚠ Every deletion emits its own `message_expired` marker. A purge that removed user content
+//! silently would be indistinguishable from a loss, which is the one thing this client promises
+//! never to do.
+//!
+//! ⚠ HONEST LIMITS. There is no background daemon: an expired message is hidden from reads at
+//! once but stays in the store until the next unlock. A peer running an older build ignores
+//! the announcement (it is an unknown control type there) and keeps everything. And
+//! `recv_N.bin` files written to a user-chosen output directory are the user's, not the
+//! store's, so they are never touched.
+
+use super::*;
+use crate::adversarial::payload::{
+    disappearing_timer_valid, DISAPPEARING_TIMER_KIND, TIMER_CONTROL_TYPE,
+};
+
+fn build_timer_announcement(expire_s: u64) -> Result<Vec<u8>, &'static str> {
+    let ctrl = ReceiptControlPayload {
+        v: CTRL_VERSION,
+        t: TIMER_CONTROL_TYPE.to_string(),
+        kind: DISAPPEARING_TIMER_KIND.to_string(),
+        msg_id: msgqueue::mint_msg_id(),
+        body: None,
+        ns: Some(adversarial::payload::CTRL_NS.to_string()),
+        expire_s: Some(expire_s),
+        group: None,
+        membership: None,
+        sender_key: None,
+    };
+    serde_json::to_vec(&ctrl).map_err(|_| "disappearing_timer_encode_failed")
+}
+
+fn send_timer_announcement(relay: &str, to: &str, expire_s: u64) -> Result<(), &'static str> {
+    let payload = build_timer_announcement(expire_s)?;
+    send_control_payload(relay, to, &payload)
+}
+
+/// `contacts timer set`: announce the timer to the peer, then store it here. `0` turns it off.
+///
+/// The announcement goes first so this side never expires messages on a timer the peer was
+/// never told about. The timer applies to messages written from now on; rows already stored
+/// keep the deadline they were stamped with.
+pub fn contacts_timer_set(label: &str, seconds: u64, relay: &str) -> CliResult {
+    require_unlocked("contacts_timer_set")?;
+    if !disappearing_timer_valid(seconds) {
+        return Err(CliError::code("disappearing_timer_invalid"));
+    }
+    match contacts_entry_read(label) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(CliError::code("peer_unknown")),
+        Err(_) => return Err(CliError::code("contacts_store_unavailable")),
+    }
+    enforce_cli_send_contact_trust(label).map_err(CliError::code)?;
+    enforce_peer_not_blocked(label).map_err(CliError::code)?;
+    if let Err(reason) = protocol_active_or_reason_for_send_peer(label) {
+        return Err(protocol_inactive_error(reason.as_str()));
+    }
+    send_timer_announcement(relay, label, seconds).map_err(CliError::code)?;
+    contacts_set_disappearing_timer(label, seconds).map_err(CliError::code)?;
+    let seconds_s = seconds.to_string();
+    emit_marker(
+        "disappearing_timer_set",
+        None,
+        &[
+            ("label", label),
+            ("seconds", seconds_s.as_str()),
+            ("origin", "local"),
+        ],
+    );
+    Ok(())
+}
+
+/// `contacts timer show`.
+pub fn contacts_timer_show(label: &str) -> CliResult {
+    require_unlocked("contacts_timer_show")?;
+    let rec = contacts_entry_read(label)
+        .map_err(|_| CliError::code("contacts_store_unavailable"))?
+        .ok_or_else(|| CliError::code("peer_unknown"))?;
+    let seconds_s = rec.disappearing_timer_s.unwrap_or(0).to_string();
+    let state = if rec.disappearing_timer_s.is_some() {
+        "on"
+    } else {
+        "off"
+    };
+    emit_marker(
+        "disappearing_timer",
+        None,
+        &[
+            ("label", label),
+            ("state", state),
+            ("seconds", seconds_s.as_str()),
+        ],
+    );
+    Ok(())
+}
+
+/// The receive side of an announcement: the peer's value becomes this contact's timer.
+///
+/// Anything that stops it applying -- an out-of-range value, an unknown contact, a store
+/// failure -- is returned as the reason the caller captures the payload under.
+pub(crate) fn apply_peer_timer(peer: &str, expire_s: Option<u64>) -> Result<u64, &'static str> {
+    let expire_s = expire_s.ok_or("disappearing_timer_invalid")?;
+    contacts_set_disappearing_timer(peer, expire_s)?;
+    Ok(expire_s)
+}
+
+/// Purge everything whose disappearing timer has run out. Called once per unlock.
+pub(crate) fn purge_expired_messages() {
+    purge_expired_at(crate::clock::now_unix_s());
+}
+
+fn purge_expired_at(now: u64) {
+    if let Ok((dir, _source)) = config_dir() {
+        match msgqueue::purge_expired_at(&dir, now) {
+            Ok(out) => {
+                for rec in out.expired.iter() {
+                    emit_marker(
+                        "message_expired",
+                        None,
+                        &[
+                            ("store", "msgqueue"),
+                            ("state", rec.state.as_str()),
+                            ("msg_id", "<redacted>"),
+                        ],
+                    );
+                }
+                if out.deferred > 0 {
+                    let n = out.deferred.to_string();
+                    emit_marker(
+                        "message_expiry_deferred",
+                        None,
+                        &[
+                            ("store", "msgqueue"),
+                            ("count", n.as_str()),
+                            ("reason", "packed_inflight"),
+                        ],
+                    );
+                }
+            }
+            // Nothing to purge without the store key; the next unlocked command retries.
+            Err(msgqueue::MSGQUEUE_VAULT_LOCKED) => {}
+            Err(code) => emit_marker("error", Some(code), &[("op", "disappearing_purge")]),
+        }
+    }
+    match timeline_purge_expired_at(now) {
+        Ok(expired) => {
+            for entry in expired.iter() {
+                emit_marker(
+                    "message_expired",
+                    None,
+                    &[
+                        ("store", "timeline"),
+                        ("peer", entry.peer.as_str()),
+                        ("dir", entry.direction.as_str()),
+                        ("kind", entry.kind.as_str()),
+                    ],
+                );
+            }
+        }
+        Err("timeline_unavailable") => {}
+        Err(code) => emit_marker("error", Some(code), &[("op", "disappearing_purge")]),
+    }
+}
@@ -2,6 +2,8 @@
 //
 // Deterministic and bounded scheduling + bundling rules.
 
+use quantumshield_refimpl::qse::PaddingPolicy;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EnvelopeError {
     TickLimitExceeded,
@@ -59,16 +61,15 @@ pub fn tick_schedule(
     Ok(out)
 }
 
+/// `BUCKET_SIZES` as a padding policy, for `bucket_for_len` and overhead reporting.
+pub fn bucket_policy() -> PaddingPolicy {
+    PaddingPolicy::Buckets(BUCKET_SIZES.to_vec())
+}
+
 pub fn bucket_for_len(len: usize, max_bucket: usize) -> Result<usize, EnvelopeError> {
-    for b in BUCKET_SIZES {
-        if b > max_bucket {
-            break;
-        }
-        if len <= b {
-            return Ok(b);
-        }
-    }
-    Err(EnvelopeError::BucketUnavailable)
+    bucket_policy()
+        .padded_len_within(len, max_bucket)
+        .ok_or(EnvelopeError::BucketUnavailable)
 }
 
 pub fn pack_bundle(
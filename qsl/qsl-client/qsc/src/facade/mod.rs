@@ -27,7 +27,7 @@ use crate::identity::{identity_read_pin, identity_voice_form};
 use crate::invite;
 use crate::model::ErrorCode;
 use crate::output::CliError;
-use crate::protocol_state::qsp_status_tuple;
+use crate::protocol_state::{qsp_healing_status, qsp_status_tuple, QspHealingStatus};
 use crate::store::ContactRecord;
 
 // ─────────────────────────────────────────────────────────────────────────────────────────
@@ -169,7 +169,7 @@ impl FacadeError {
     /// a variant makes this match non-exhaustive and the build goes RED here.
     ///
     /// ⚠ [`FacadeError::Store`] FANS OUT at the DTO boundary: its discriminant is the inner
-    /// `ErrorCode::as_str()`, so the pinned set is 25 + 13 = 38, not 26. Collapsing `Store`
+    /// `ErrorCode::as_str()`, so the pinned set is 25 + 14 = 39, not 26. Collapsing `Store`
     /// to one code would put `lock_upgrade_refused` beyond a GUI's reach and undo the reason
     /// the variant exists.
     pub fn as_wire(&self) -> &'static str {
@@ -280,7 +280,7 @@ fn map_code(code: &str) -> FacadeError {
     }
 }
 
-/// The EXACT INVERSE of `ErrorCode::as_str` (`model/mod.rs:43-59`), so a store failure that
+/// The EXACT INVERSE of `ErrorCode::as_str` (`model/mod.rs:46-62`), so a store failure that
 /// was flattened to a string by one of the crate's four bridges arrives as the SAME variant it
 /// would have on a typed verb.
 ///
@@ -302,6 +302,7 @@ fn store_code_from_wire(wire: &str) -> Option<ErrorCode> {
         "io_read_failed" => ErrorCode::IoReadFailed,
         "parse_failed" => ErrorCode::ParseFailed,
         "identity_self_ambiguous" => ErrorCode::IdentitySelfAmbiguous,
+        "session_rollback" => ErrorCode::SessionRollback,
         // `store_err_marker`'s own rename, documented at `vault/mod.rs:1034-1039`.
         "vault_write_failed" => ErrorCode::IoWriteFailed,
         _ => return None,
@@ -451,6 +452,79 @@ pub fn connect_status(peer: &str) -> ConnectStatus {
     ConnectStatus { state, reason }
 }
 
+// ─────────────────────────────────────────────────────────────────────────────────────────
+// SESSION HEALING
+// ─────────────────────────────────────────────────────────────────────────────────────────
+
+/// Post-compromise-security healing for one channel, for field monitoring: how far the session
+/// is from its last DH step and its last PQ reseed in each direction, and which advertised PQ
+/// targets are still open. Read through `qsp_healing_status` (`protocol_state/mod.rs`); the
+/// same facts ride the `heal_*` fields of the `handshake_status` marker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionHealing {
+    /// Messages on the current send chain — since our last DH step.
+    pub sent_since_dh_step: u32,
+    /// Messages on the current receive chain — since the peer's last DH step.
+    pub received_since_dh_step: u32,
+    /// Receive DH ratchet steps over the session's life.
+    pub recv_dh_steps: u32,
+    /// The next send is a DH boundary (ratchet-on-reply armed).
+    pub ratchet_pending: bool,
+    /// Sends counted toward the `QSP_DH_FALLBACK_N` fallback.
+    pub msgs_since_ratchet: u32,
+    /// `None` until this client has ratcheted once.
+    pub secs_since_ratchet: Option<u64>,
+    /// Sent DH boundaries counted toward `QSP_PQ_RESEED_N`.
+    pub boundaries_since_reseed: u32,
+    /// `None` until this client has originated a reseed.
+    pub secs_since_reseed: Option<u64>,
+    /// `None` until the peer's first DH boundary has been received.
+    pub secs_since_recv_ratchet: Option<u64>,
+    /// `None` until the peer's first reseed has been received.
+    pub secs_since_recv_reseed: Option<u64>,
+    /// Our advertised PQ targets the peer has not yet consumed, ascending.
+    pub outstanding_targets: Vec<u32>,
+    /// Advertised ids whose receive secret is still held.
+    pub live_adv_ids: Vec<u32>,
+    /// The peer advertisement our next reseed will target.
+    pub pending_peer_adv_id: Option<u32>,
+    /// Skipped message keys retained.
+    pub skipped_keys: usize,
+}
+
+impl SessionHealing {
+    fn from_status(h: QspHealingStatus) -> Self {
+        SessionHealing {
+            sent_since_dh_step: h.state.sent_since_dh_step,
+            received_since_dh_step: h.state.received_since_dh_step,
+            recv_dh_steps: h.state.recv_dh_steps,
+            ratchet_pending: h.ratchet_pending,
+            msgs_since_ratchet: h.msgs_since_ratchet,
+            secs_since_ratchet: h.secs_since_ratchet,
+            boundaries_since_reseed: h.boundaries_since_reseed,
+            secs_since_reseed: h.secs_since_reseed,
+            secs_since_recv_ratchet: h.secs_since_recv_ratchet,
+            secs_since_recv_reseed: h.secs_since_recv_reseed,
+            outstanding_targets: h.state.outstanding_targets,
+            live_adv_ids: h.live_adv_ids,
+            pending_peer_adv_id: h.pending_peer_adv_id,
+            skipped_keys: h.state.skipped_keys,
+        }
+    }
+}
+
+/// Wraps `qsp_healing_status` at the ONE clock, `crate::clock`. `Ok(None)` is no session.
+pub fn session_healing(peer: &str) -> Result<Option<SessionHealing>, FacadeError> {
+    session_healing_at(peer, crate::clock::now_unix_s())
+}
+
+/// The `_at` seam: elapsed seconds are measured against `now`. Read-only — the session blob
+/// is decrypted, never re-written. Store errors are CARRIED as [`FacadeError::Store`].
+pub fn session_healing_at(peer: &str, now: u64) -> Result<Option<SessionHealing>, FacadeError> {
+    require_unlocked_here()?;
+    Ok(qsp_healing_status(peer, now)?.map(SessionHealing::from_status))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────────────────
 // CONTACTS
 // ─────────────────────────────────────────────────────────────────────────────────────────
@@ -612,6 +686,21 @@ pub fn contact_request_block(alias: &str) -> Result<(), FacadeError> {
     Ok(crate::contacts::contacts_request_block(alias)?)
 }
 
+// ─────────────────────────────────────────────────────────────────────────────────────────
+// MESSAGES
+// ─────────────────────────────────────────────────────────────────────────────────────────
+
+/// Tell the core an inbound message was DISPLAYED — the facade's half of `timeline show`.
+///
+/// Wraps `timeline_mark_displayed`, which decides the message's read receipt against the
+/// account opt-in and the contact's opt-out at this instant. Nothing is sent here; an owed
+/// receipt rides the next pull's batched flush. Calling it again for the same id is a no-op.
+pub fn message_displayed(peer: &str, id: &str) -> Result<(), FacadeError> {
+    require_unlocked_here()?;
+    crate::timeline::timeline_mark_displayed(peer, id).map_err(map_code)?;
+    Ok(())
+}
+
 // ─────────────────────────────────────────────────────────────────────────────────────────
 // INVITES
 // ─────────────────────────────────────────────────────────────────────────────────────────
@@ -1026,7 +1115,7 @@ mod na0751_facade_mapping_tests {
 
     #[test]
     fn na0751_as_wire_discriminants_are_distinct_and_store_fans_out() {
-        // W4's pinned set is 25 + 13 = 38, not 26: `Store` fans out over `ErrorCode::as_str`.
+        // W4's pinned set is 25 + 14 = 39, not 26: `Store` fans out over `ErrorCode::as_str`.
         let singles = [
             FacadeError::Locked, FacadeError::VaultUnavailable, FacadeError::Expired,
             FacadeError::AlreadyRedeemed, FacadeError::RevokedLocally, FacadeError::SoftCapReached,
@@ -1046,17 +1135,17 @@ mod na0751_facade_mapping_tests {
             ErrorCode::UnsafeParentPerms, ErrorCode::LockOpenFailed, ErrorCode::LockContended,
             ErrorCode::LockFailed, ErrorCode::LockUpgradeRefused, ErrorCode::IoWriteFailed,
             ErrorCode::IoReadFailed, ErrorCode::ParseFailed, ErrorCode::IdentitySecretUnavailable,
-            ErrorCode::IdentitySelfAmbiguous,
+            ErrorCode::IdentitySelfAmbiguous, ErrorCode::SessionRollback,
         ];
-        assert_eq!(store_codes.len(), 13);
+        assert_eq!(store_codes.len(), 14);
         for c in store_codes {
             wires.push(FacadeError::Store(c).as_wire());
         }
-        assert_eq!(wires.len(), 38, "the pinned discriminant set is 38");
+        assert_eq!(wires.len(), 39, "the pinned discriminant set is 39");
         let mut sorted = wires.clone();
         sorted.sort_unstable();
         sorted.dedup();
-        assert_eq!(sorted.len(), 38, "all 38 discriminants are DISTINCT");
+        assert_eq!(sorted.len(), 39, "all 39 discriminants are DISTINCT");
         // The reason `Store` exists: `lock_upgrade_refused` survives to the boundary.
         assert!(wires.contains(&"lock_upgrade_refused"));
     }
@@ -1,5 +1,8 @@
 use crate::model::{ConfigSource, ErrorCode, LockGuard, LockMode};
-use crate::{ACK_MODE_KEY, LOCK_FILE_NAME, POLICY_KEY, STORE_META_NAME, STORE_META_TEMPLATE};
+use crate::{
+    ACK_MODE_KEY, DELIVERY_RULE_KEY, LOCK_FILE_NAME, POLICY_KEY, READ_RECEIPTS_KEY,
+    STORE_META_NAME, STORE_META_TEMPLATE,
+};
 use std::env;
 use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write};
@@ -45,6 +48,23 @@ pub(crate) fn normalize_ack_mode(value: &str) -> Result<String, ErrorCode> {
     }
 }
 
+pub(crate) fn normalize_read_receipts(value: &str) -> Result<String, ErrorCode> {
+    match value {
+        "on" => Ok("on".to_string()),
+        "off" => Ok("off".to_string()),
+        _ => Err(ErrorCode::ParseFailed),
+    }
+}
+
+pub(crate) fn normalize_delivery_rule(value: &str) -> Result<String, ErrorCode> {
+    match value {
+        "any" => Ok("any".to_string()),
+        "all" => Ok("all".to_string()),
+        "primary" => Ok("primary".to_string()),
+        _ => Err(ErrorCode::ParseFailed),
+    }
+}
+
 /// NA-0688 C4 (D622 R7): parse `config.txt` as an ordered `key=value` list.
 ///
 /// ⚠ **THIS FILE BECAME MULTI-KEY IN C4, AND THAT IS WHY THIS FUNCTION EXISTS.** It previously held
@@ -111,6 +131,36 @@ pub(crate) fn read_ack_mode(path: &Path) -> Result<Option<String>, ErrorCode> {
     Ok(None)
 }
 
+pub(crate) fn read_read_receipts(path: &Path) -> Result<Option<String>, ErrorCode> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    for (k, v) in read_config_kv(path)? {
+        if k == READ_RECEIPTS_KEY {
+            return match normalize_read_receipts(v.as_str()) {
+                Ok(v) => Ok(Some(v)),
+                Err(_) => Err(ErrorCode::ParseFailed),
+            };
+        }
+    }
+    Ok(None)
+}
+
+pub(crate) fn read_delivery_rule(path: &Path) -> Result<Option<String>, ErrorCode> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    for (k, v) in read_config_kv(path)? {
+        if k == DELIVERY_RULE_KEY {
+            return match normalize_delivery_rule(v.as_str()) {
+                Ok(v) => Ok(Some(v)),
+                Err(_) => Err(ErrorCode::ParseFailed),
+            };
+        }
+    }
+    Ok(None)
+}
+
 pub(crate) fn ensure_dir_secure(dir: &Path, source: ConfigSource) -> Result<(), ErrorCode> {
     enforce_safe_parents(dir, source)?;
     if !dir.exists() {
@@ -0,0 +1,1029 @@
+//! DESIGN F5: small groups on top of the pairwise Suite-2 sessions.
+//!
+//! There is no group key. A group message is enqueued once per member -- one `QueuedMessage`
+//! each, all sharing the group message's `msg_id` -- and every copy drains through that
+//! member's own pairwise session. What makes a copy a group message is the `group` field on
+//! its data envelope; what makes the group a group is a membership statement signed by its
+//! admin with the same ML-DSA identity key the handshake already pins.
+//!
+//! Members are named by signing-key fingerprint, so the statement means the same thing to
+//! every member whatever they called each other. A change is accepted only from the admin it
+//! names, only under the key pinned for the contact it arrived from, and only at a higher
+//! epoch than the one held; a member may also sign its own `leave`.
+//!
+//! A group created with `--sender-keys` seals each message once instead. Every member owns a
+//! `suite2::sender_key` chain for the group, hands it to each other member over their pairwise
+//! session as a `kind=sender_key` control, and seals its messages under it; the copies that
+//! then fan out are the same signed ciphertext. A chain is retired as soon as anyone it was
+//! handed to stops being a member -- by removal or by leaving -- so the next send rotates to a
+//! fresh one that only the remaining members receive.
+//!
+//! ⚠ HONEST LIMITS. Pairwise fan-out costs one Suite-2 send per member, which is why groups
+//! stop at `GROUP_MEMBERS_MAX`. Sender-key copies still travel through each member's session,
+//! because that is the only mailbox a member reads; what the mode saves is sealing and signing
+//! the content per member, and it stops at `GROUP_SENDER_KEY_MEMBERS_MAX`. Rotation happens at
+//! the next send, not at the removal, and a removed member can still read whatever was sealed
+//! before it. A member is reachable only from someone holding a contact and a session with
+//! them -- a group does not introduce its members to each other. An older build shows a
+//! pairwise group message as a one-to-one message from its sender, and refuses to join a
+//! sender-key group at all. There are no group read receipts and no group disappearing timers
+//! yet.
+
+use super::*;
+use crate::adversarial::payload::{
+    group_id_valid, group_members_cap, GroupMembershipBody, GroupMembershipSigned,
+    GROUP_CONTROL_TYPE, GROUP_LEAVE_KIND, GROUP_MEMBERSHIP_KIND, GROUP_MEMBERSHIP_VERSION,
+    GROUP_MODE_SENDER_KEY, GROUP_SENDER_KEY_KIND,
+};
+use quantumshield_refimpl::suite2::sender_key::{
+    SenderKeyDistribution, SenderKeyError, SenderKeyMessage, SenderKeyReceiver, SenderKeyState,
+    SZ_SENDER_KEY_GROUP_ID,
+};
+
+/// Domain for the membership signature. Ends in NUL so it can never be a prefix of a body.
+const GROUP_SIG_DOMAIN: &[u8] = b"qsc.group.membership.v1\0";
+const GROUP_NAME_MAX: usize = 64;
+
+fn group_store_load() -> Result<GroupStore, &'static str> {
+    match vault::secret_get(GROUPS_SECRET_KEY) {
+        Ok(None) => Ok(GroupStore::default()),
+        Ok(Some(v)) => serde_json::from_str::<GroupStore>(&v).map_err(|_| "groups_tampered"),
+        Err(_) => Err("groups_unavailable"),
+    }
+}
+
+fn group_store_save(store: &GroupStore) -> Result<(), &'static str> {
+    let json = serde_json::to_string(store).map_err(|_| "groups_unavailable")?;
+    vault::secret_set(GROUPS_SECRET_KEY, &json).map_err(|_| "groups_unavailable")
+}
+
+fn sender_key_store_load() -> Result<SenderKeyStore, &'static str> {
+    match vault::secret_get(GROUP_SENDER_KEYS_SECRET_KEY) {
+        Ok(None) => Ok(SenderKeyStore::default()),
+        Ok(Some(v)) => serde_json::from_str::<SenderKeyStore>(&v).map_err(|_| "groups_tampered"),
+        Err(_) => Err("groups_unavailable"),
+    }
+}
+
+fn sender_key_store_save(store: &SenderKeyStore) -> Result<(), &'static str> {
+    let json = serde_json::to_string(store).map_err(|_| "groups_unavailable")?;
+    vault::secret_set(GROUP_SENDER_KEYS_SECRET_KEY, &json).map_err(|_| "groups_unavailable")
+}
+
+fn mint_group_id() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    hex_encode(&bytes)
+}
+
+fn self_signing_keypair() -> Result<IdentityKeypair, &'static str> {
+    let label = crate::identity::identity_resolved_self_label(None).map_err(|e| e.as_str())?;
+    identity_self_kem_keypair(&label).map_err(|_| "identity_secret_unavailable")
+}
+
+fn member_fp(sig_pk: &[u8]) -> String {
+    identity_fingerprint_single(FpRole::Sig, sig_pk)
+}
+
+/// The member id of a contact: its pinned signing-key fingerprint. A contact added without a
+/// signing key cannot be a member -- nothing it sent could be checked against the statement.
+fn member_fp_for_label(label: &str) -> Result<String, &'static str> {
+    identity_read_sig_pin(label)
+        .map_err(|_| "contacts_store_unavailable")?
+        .map(|fp| fp.to_ascii_lowercase())
+        .ok_or("group_member_unpinned")
+}
+
+/// The contact a member id belongs to here, if this side holds one.
+fn label_for_member_fp(fp: &str, contacts: &[(String, ContactRecord)]) -> Option<String> {
+    let is_fp = |v: &Option<String>| v.as_deref().is_some_and(|s| s.eq_ignore_ascii_case(fp));
+    contacts
+        .iter()
+        .find(|(_, rec)| is_fp(&rec.sig_fp) || rec.devices.iter().any(|d| is_fp(&d.sig_fp)))
+        .map(|(label, _)| label.clone())
+}
+
+fn statement_msg(body: &[u8]) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(GROUP_SIG_DOMAIN.len() + body.len());
+    msg.extend_from_slice(GROUP_SIG_DOMAIN);
+    msg.extend_from_slice(body);
+    msg
+}
+
+fn sign_statement(
+    kp: &IdentityKeypair,
+    body: &GroupMembershipBody,
+) -> Result<GroupMembershipSigned, &'static str> {
+    let text = serde_json::to_string(body).map_err(|_| "group_encode_failed")?;
+    let sig = StdCrypto
+        .sign(&kp.sig_sk, &statement_msg(text.as_bytes()))
+        .map_err(|_| "group_sign_failed")?;
+    Ok(GroupMembershipSigned {
+        body: text,
+        sig_pk: hex_encode(&kp.sig_pk),
+        sig: hex_encode(&sig),
+    })
+}
+
+fn build_group_control(kind: &str, signed: GroupMembershipSigned) -> Result<Vec<u8>, &'static str> {
+    let ctrl = ReceiptControlPayload {
+        v: CTRL_VERSION,
+        t: GROUP_CONTROL_TYPE.to_string(),
+        kind: kind.to_string(),
+        msg_id: msgqueue::mint_msg_id(),
+        body: None,
+        ns: Some(adversarial::payload::CTRL_NS.to_string()),
+        expire_s: None,
+        group: None,
+        membership: Some(signed),
+        sender_key: None,
+    };
+    serde_json::to_vec(&ctrl).map_err(|_| "group_encode_failed")
+}
+
+fn statement_well_formed(body: &GroupMembershipBody, kind: &str) -> Result<(), &'static str> {
+    if body.v != GROUP_MEMBERSHIP_VERSION || body.op != kind || !group_id_valid(&body.group_id) {
+        return Err("group_statement_invalid");
+    }
+    let cap = group_members_cap(body.mode.as_deref()).ok_or("group_statement_invalid")?;
+    if body.members.len() > cap {
+        return Err("group_too_large");
+    }
+    if kind == GROUP_MEMBERSHIP_KIND && !body.members.contains(&body.admin) {
+        return Err("group_statement_invalid");
+    }
+    Ok(())
+}
+
+/// Verify a statement that arrived from `from` and return it with its signer's member id.
+///
+/// ORDER IS THE CONTRACT, as in `invite::verify_redeemed_bundle`: the carried key must be the
+/// one pinned for this contact (a valid signature under some other key proves nothing), then
+/// the signature must verify over the exact body text, and only then is the text parsed.
+fn verify_statement(
+    from: &str,
+    ctrl: &ReceiptControlPayload,
+) -> Result<(GroupMembershipBody, String), &'static str> {
+    let signed = ctrl.membership.as_ref().ok_or("group_statement_invalid")?;
+    let sig_pk = hex_decode(&signed.sig_pk).map_err(|_| "group_statement_invalid")?;
+    let sig = hex_decode(&signed.sig).map_err(|_| "group_statement_invalid")?;
+    let signer = member_fp(&sig_pk);
+    let pinned = identity_read_sig_pin(from)
+        .map_err(|_| "contacts_store_unavailable")?
+        .ok_or("group_signer_mismatch")?;
+    if !pinned.eq_ignore_ascii_case(&signer) {
+        return Err("group_signer_mismatch");
+    }
+    match StdCrypto.verify(&sig_pk, &statement_msg(signed.body.as_bytes()), &sig) {
+        Ok(true) => {}
+        _ => return Err("group_signature_invalid"),
+    }
+    let body: GroupMembershipBody =
+        serde_json::from_str(&signed.body).map_err(|_| "group_statement_invalid")?;
+    statement_well_formed(&body, ctrl.kind.as_str())?;
+    Ok((body, signer))
+}
+
+/// What an accepted change did, for the receive path's marker.
+pub(crate) struct GroupChange {
+    pub(crate) op: &'static str,
+    pub(crate) group_id: String,
+    pub(crate) epoch: u64,
+}
+
+/// The receive side of a membership statement.
+///
+/// Anything that stops it applying is returned as the reason the caller captures the payload
+/// under, exactly as for a disappearing timer.
+pub(crate) fn apply_peer_membership(
+    from: &str,
+    ctrl: &ReceiptControlPayload,
+) -> Result<GroupChange, &'static str> {
+    let (body, signer) = verify_statement(from, ctrl)?;
+    let mut store = group_store_load()?;
+    let op = if body.op == GROUP_MEMBERSHIP_KIND {
+        if !signer.eq_ignore_ascii_case(&body.admin) {
+            return Err("group_not_admin");
+        }
+        if let Some(held) = store.groups.get(&body.group_id) {
+            if held.admin != body.admin {
+                return Err("group_not_admin");
+            }
+            if held.mode != body.mode {
+                return Err("group_mode_mismatch");
+            }
+            if body.epoch <= held.epoch {
+                return Err("group_epoch_stale");
+            }
+        }
+        let self_fp = member_fp(&self_signing_keypair()?.sig_pk);
+        if body.members.contains(&self_fp) {
+            store.groups.insert(
+                body.group_id.clone(),
+                GroupRecord {
+                    name: body.name.clone(),
+                    admin: body.admin.clone(),
+                    epoch: body.epoch,
+                    members: body.members.clone(),
+                    updated_at: crate::clock::now_unix_s(),
+                    mode: body.mode.clone(),
+                },
+            );
+            "update"
+        } else {
+            // Removed, or never added: either way this side no longer holds the group.
+            store.groups.remove(&body.group_id);
+            "removed"
+        }
+    } else {
+        let held = store
+            .groups
+            .get_mut(&body.group_id)
+            .ok_or("group_unknown")?;
+        if body.epoch < held.epoch {
+            return Err("group_epoch_stale");
+        }
+        if held.admin == signer || !held.members.contains(&signer) {
+            return Err("group_not_member");
+        }
+        held.members.retain(|m| m != &signer);
+        held.updated_at = crate::clock::now_unix_s();
+        "leave"
+    };
+    group_store_save(&store)?;
+    let members = store
+        .groups
+        .get(&body.group_id)
+        .map(|g| g.members.as_slice());
+    sender_keys_forget(&body.group_id, members.unwrap_or(&[]));
+    Ok(GroupChange {
+        op,
+        group_id: body.group_id,
+        epoch: body.epoch,
+    })
+}
+
+/// The group as held here and the member id of `from`, who must be a current member of it.
+fn group_sender(group_id: &str, from: &str) -> Result<(GroupRecord, String), &'static str> {
+    let mut store = group_store_load()?;
+    let held = store.groups.remove(group_id).ok_or("group_unknown")?;
+    let sender = identity_read_sig_pin(from)
+        .map_err(|_| "contacts_store_unavailable")?
+        .ok_or("group_sender_not_member")?;
+    let member = held
+        .members
+        .iter()
+        .find(|m| m.eq_ignore_ascii_case(&sender))
+        .cloned()
+        .ok_or("group_sender_not_member")?;
+    Ok((held, member))
+}
+
+fn is_sender_key_group(rec: &GroupRecord) -> bool {
+    rec.mode.as_deref() == Some(GROUP_MODE_SENDER_KEY)
+}
+
+/// A copy that opened once already under the sender's chain. Not a forgery: the receive path
+/// checks it against the msg_id dedup before capturing anything.
+pub(crate) const GROUP_SENDER_KEY_REPLAY: &str = "group_sender_key_replay";
+
+fn sender_key_reject_reason(err: SenderKeyError) -> &'static str {
+    match err {
+        SenderKeyError::UnknownKey => "group_sender_key_unknown",
+        SenderKeyError::BadSignature => "group_sender_key_signature_invalid",
+        SenderKeyError::Duplicate => GROUP_SENDER_KEY_REPLAY,
+        SenderKeyError::SkipLimit => "group_sender_key_skip_limit",
+        _ => "group_sender_key_invalid",
+    }
+}
+
+/// A group copy is taken in only from a current member of a group this side holds.
+///
+/// In a sender-key group the copy is the member's sealed message, opened here under the chain
+/// that member sent; the plaintext comes back. A pairwise copy is already plaintext (`None`).
+pub(crate) fn group_message_accept(
+    group_id: &str,
+    from: &str,
+    payload: &[u8],
+) -> Result<Option<Vec<u8>>, &'static str> {
+    let (held, sender) = group_sender(group_id, from)?;
+    if !is_sender_key_group(&held) {
+        return Ok(None);
+    }
+    let msg = SenderKeyMessage::decode(payload).map_err(|_| "group_sender_key_invalid")?;
+    let mut keys = sender_key_store_load()?;
+    let slot = keys
+        .peers
+        .get_mut(group_id)
+        .and_then(|peers| peers.get_mut(&sender))
+        .ok_or("group_sender_key_missing")?;
+    let mut rx = hex_decode(slot)
+        .ok()
+        .and_then(|raw| SenderKeyReceiver::decode(&raw).ok())
+        .ok_or("groups_tampered")?;
+    let c = StdCrypto;
+    let plaintext = rx
+        .open(&c, &c, &c, &c, &msg)
+        .map_err(sender_key_reject_reason)?;
+    *slot = hex_encode(&rx.encode().map_err(|_| "group_encode_failed")?);
+    sender_key_store_save(&keys)?;
+    Ok(Some(plaintext))
+}
+
+/// The receive side of a sender-key distribution; returns what it did, for the marker.
+///
+/// It arrived inside `from`'s pairwise session, which is what makes it that member's chain. A
+/// redelivery of the chain already held is a no-op: replacing it would rewind the chain.
+pub(crate) fn apply_peer_sender_key(
+    from: &str,
+    ctrl: &ReceiptControlPayload,
+) -> Result<&'static str, &'static str> {
+    let group_id = ctrl.group.as_deref().ok_or("group_sender_key_invalid")?;
+    let (held, sender) = group_sender(group_id, from)?;
+    if !is_sender_key_group(&held) {
+        return Err("group_mode_mismatch");
+    }
+    let dist = ctrl
+        .sender_key
+        .as_deref()
+        .and_then(|v| hex_decode(v).ok())
+        .and_then(|raw| SenderKeyDistribution::decode(&raw).ok())
+        .ok_or("group_sender_key_invalid")?;
+    if dist.group_id != group_id_bytes(group_id)? {
+        return Err("group_sender_key_invalid");
+    }
+    let mut keys = sender_key_store_load()?;
+    let peers = keys.peers.entry(group_id.to_string()).or_default();
+    let current = peers
+        .get(&sender)
+        .and_then(|v| hex_decode(v).ok())
+        .and_then(|raw| SenderKeyReceiver::decode(&raw).ok());
+    if current.is_some_and(|rx| rx.key_id() == dist.key_id) {
+        return Ok("kept");
+    }
+    let rx = SenderKeyReceiver::new(&dist)
+        .encode()
+        .map_err(|_| "group_encode_failed")?;
+    peers.insert(sender, hex_encode(&rx));
+    sender_key_store_save(&keys)?;
+    Ok("stored")
+}
+
+/// Drop the chains of everyone no longer in `members`; an empty list drops the group's keys.
+///
+/// This side's own chain is not dropped here -- `sender_key_prepare` retires it at the next
+/// send. Best effort: a chain left behind opens nothing, because its sender is no longer a
+/// member, so a failure is reported rather than failing the change that caused it.
+fn sender_keys_forget(group_id: &str, members: &[String]) {
+    let res = sender_key_store_load().and_then(|mut keys| {
+        if members.is_empty() {
+            keys.own.remove(group_id);
+            keys.peers.remove(group_id);
+        } else if let Some(peers) = keys.peers.get_mut(group_id) {
+            peers.retain(|fp, _| members.contains(fp));
+        }
+        sender_key_store_save(&keys)
+    });
+    if let Err(code) = res {
+        emit_marker("error", Some(code), &[("op", "group_sender_key_forget")]);
+    }
+}
+
+fn group_id_bytes(group_id: &str) -> Result<[u8; SZ_SENDER_KEY_GROUP_ID], &'static str> {
+    hex_decode(group_id)
+        .ok()
+        .and_then(|v| v.try_into().ok())
+        .ok_or("group_id_invalid")
+}
+
+/// A fresh chain under a random key id, with a signing key pair of its own: the identity key
+/// never signs group messages, and a retired chain's key signs nothing again.
+fn mint_sender_key(group_id: &str) -> Result<SenderKeyState, &'static str> {
+    let mut chain_key = [0u8; 32];
+    OsRng.fill_bytes(&mut chain_key);
+    let (sig_pk, sig_sk) = runtime_pq_sig_keypair();
+    let state = SenderKeyState::new(
+        group_id_bytes(group_id)?,
+        OsRng.next_u32(),
+        chain_key,
+        sig_pk,
+        sig_sk,
+    )
+    .map_err(|_| "group_sender_key_failed");
+    chain_key.zeroize();
+    state
+}
+
+fn build_sender_key_control(
+    group_id: &str,
+    dist: &SenderKeyDistribution,
+) -> Result<Vec<u8>, &'static str> {
+    let ctrl = ReceiptControlPayload {
+        v: CTRL_VERSION,
+        t: GROUP_CONTROL_TYPE.to_string(),
+        kind: GROUP_SENDER_KEY_KIND.to_string(),
+        msg_id: msgqueue::mint_msg_id(),
+        body: None,
+        ns: Some(adversarial::payload::CTRL_NS.to_string()),
+        expire_s: None,
+        group: Some(group_id.to_string()),
+        membership: None,
+        sender_key: Some(hex_encode(
+            &dist.encode().map_err(|_| "group_encode_failed")?,
+        )),
+    };
+    serde_json::to_vec(&ctrl).map_err(|_| "group_encode_failed")
+}
+
+fn decode_own(own: &OwnSenderKey) -> Result<SenderKeyState, &'static str> {
+    hex_decode(&own.state)
+        .ok()
+        .and_then(|raw| SenderKeyState::decode(&raw).ok())
+        .ok_or("groups_tampered")
+}
+
+/// Make sure this side seals with a chain no departed member was handed, and that every ready
+/// member holds it. Returns the ready members that do; the rest are named and skipped.
+///
+/// The chain is stored before it is handed out, so what a member receives is always the chain
+/// this side goes on sealing with.
+fn sender_key_prepare(
+    relay: &str,
+    group_id: &str,
+    rec: &GroupRecord,
+    ready: Vec<(String, String)>,
+) -> Result<Vec<String>, &'static str> {
+    let mut keys = sender_key_store_load()?;
+    let retired = keys.own.get(group_id).map(|own| {
+        own.distributed_to
+            .iter()
+            .any(|fp| !rec.members.contains(fp))
+    });
+    if retired != Some(false) {
+        let state = mint_sender_key(group_id)?;
+        keys.own.insert(
+            group_id.to_string(),
+            OwnSenderKey {
+                state: hex_encode(&state.encode().map_err(|_| "group_encode_failed")?),
+                distributed_to: Vec::new(),
+            },
+        );
+        sender_key_store_save(&keys)?;
+        let (op, reason) = match retired {
+            Some(_) => ("rotate", "member_removed"),
+            None => ("create", "first_send"),
+        };
+        emit_marker(
+            "group_sender_key",
+            None,
+            &[
+                ("op", op),
+                ("group", group_id),
+                ("reason", reason),
+                ("origin", "local"),
+            ],
+        );
+    }
+    let own = keys.own.get(group_id).ok_or("groups_unavailable")?;
+    let payload = build_sender_key_control(group_id, &decode_own(own)?.distribution())?;
+    let mut holders = Vec::with_capacity(ready.len());
+    let mut handed = Vec::new();
+    for (fp, label) in ready {
+        if !own.distributed_to.contains(&fp) {
+            if let Err(reason) = send_control_payload(relay, &label, &payload) {
+                emit_member_skipped(group_id, Some(&label), reason);
+                continue;
+            }
+            handed.push(fp);
+        }
+        holders.push(label);
+    }
+    if !handed.is_empty() {
+        let handed_s = handed.len().to_string();
+        if let Some(own) = keys.own.get_mut(group_id) {
+            own.distributed_to.extend(handed);
+        }
+        sender_key_store_save(&keys)?;
+        emit_marker(
+            "group_sender_key",
+            None,
+            &[
+                ("op", "distribute"),
+                ("group", group_id),
+                ("count", handed_s.as_str()),
+                ("origin", "local"),
+            ],
+        );
+    }
+    Ok(holders)
+}
+
+/// Seal `body` once under this side's chain. The advanced chain is stored before the sealed
+/// message can leave, so no iteration is ever sealed twice.
+fn sender_key_seal(group_id: &str, body: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let mut keys = sender_key_store_load()?;
+    let own = keys
+        .own
+        .get_mut(group_id)
+        .ok_or("group_sender_key_missing")?;
+    let mut state = decode_own(own)?;
+    let c = StdCrypto;
+    let msg = state
+        .seal(&c, &c, &c, &c, body)
+        .map_err(|_| "group_sender_key_failed")?;
+    own.state = hex_encode(&state.encode().map_err(|_| "group_encode_failed")?);
+    sender_key_store_save(&keys)?;
+    msg.encode().map_err(|_| "group_encode_failed")
+}
+
+fn group_held(store: &GroupStore, group_id: &str) -> Result<GroupRecord, CliError> {
+    if !group_id_valid(group_id) {
+        return Err(CliError::code("group_id_invalid"));
+    }
+    store
+        .groups
+        .get(group_id)
+        .cloned()
+        .ok_or_else(|| CliError::code("group_unknown"))
+}
+
+/// Every member but this side, as the contact each resolves to here (`None` when unknown).
+fn other_members(
+    rec: &GroupRecord,
+    self_fp: &str,
+) -> Result<Vec<(String, Option<String>)>, CliError> {
+    let contacts =
+        contacts_list_entries().map_err(|_| CliError::code("contacts_store_unavailable"))?;
+    Ok(rec
+        .members
+        .iter()
+        .filter(|m| m.as_str() != self_fp)
+        .map(|m| (m.clone(), label_for_member_fp(m, &contacts)))
+        .collect())
+}
+
+/// The per-member send gates, each a skip rather than a failure of the whole fan-out.
+fn member_send_ready(label: &str) -> Result<(), &'static str> {
+    enforce_cli_send_contact_trust(label)?;
+    enforce_peer_not_blocked(label)?;
+    protocol_active_or_reason_for_send_peer(label).map_err(|_| "protocol_inactive")
+}
+
+fn emit_member_skipped(group_id: &str, label: Option<&str>, reason: &str) {
+    emit_marker(
+        "group_member_skipped",
+        None,
+        &[
+            ("group", group_id),
+            ("label", label.unwrap_or("unknown")),
+            ("reason", reason),
+        ],
+    );
+}
+
+/// Push a membership statement to every member this side can reach.
+///
+/// A member that cannot be reached is named, not fatal: the change already holds here, and
+/// the admin's next change carries the full list, so a missed one heals.
+fn announce(
+    relay: &str,
+    group_id: &str,
+    recipients: &[(String, Option<String>)],
+    payload: &[u8],
+) -> usize {
+    let mut sent = 0usize;
+    for (_, label) in recipients {
+        let Some(label) = label.as_deref() else {
+            emit_member_skipped(group_id, None, "no_contact");
+            continue;
+        };
+        let res =
+            member_send_ready(label).and_then(|_| send_control_payload(relay, label, payload));
+        match res {
+            Ok(()) => sent += 1,
+            Err(reason) => emit_member_skipped(group_id, Some(label), reason),
+        }
+    }
+    sent
+}
+
+fn mode_name(rec: &GroupRecord) -> &'static str {
+    if is_sender_key_group(rec) {
+        "sender_key"
+    } else {
+        "pairwise"
+    }
+}
+
+fn emit_group_membership(op: &str, group_id: &str, rec: &GroupRecord, announced: usize) {
+    let epoch_s = rec.epoch.to_string();
+    let members_s = rec.members.len().to_string();
+    let announced_s = announced.to_string();
+    emit_marker(
+        "group_membership",
+        None,
+        &[
+            ("op", op),
+            ("group", group_id),
+            ("epoch", epoch_s.as_str()),
+            ("members", members_s.as_str()),
+            ("announced", announced_s.as_str()),
+            ("origin", "local"),
+        ],
+    );
+}
+
+/// Resolve contact labels to member ids, refusing a label that is unknown or unpinned.
+fn member_fps_for_labels(labels: &[String]) -> Result<Vec<String>, CliError> {
+    let mut out = Vec::with_capacity(labels.len());
+    for label in labels {
+        match contacts_entry_read(label) {
+            Ok(Some(_)) => {}
+            Ok(None) => return Err(CliError::code("peer_unknown")),
+            Err(_) => return Err(CliError::code("contacts_store_unavailable")),
+        }
+        let fp = member_fp_for_label(label).map_err(CliError::code)?;
+        if !out.contains(&fp) {
+            out.push(fp);
+        }
+    }
+    Ok(out)
+}
+
+/// Sign the admin's full list at the record's epoch, store it, then announce it.
+///
+/// Stored first: a statement this side has signed is this side's truth, whatever the network
+/// does next. `extra` are recipients outside the new list -- the members just removed, who
+/// must be told or they would go on sending to a group that has dropped them.
+fn commit_and_announce(
+    relay: &str,
+    group_id: &str,
+    rec: GroupRecord,
+    kp: &IdentityKeypair,
+    extra: &[String],
+    op: &str,
+) -> CliResult {
+    let body = GroupMembershipBody {
+        v: GROUP_MEMBERSHIP_VERSION,
+        op: GROUP_MEMBERSHIP_KIND.to_string(),
+        group_id: group_id.to_string(),
+        name: rec.name.clone(),
+        epoch: rec.epoch,
+        admin: rec.admin.clone(),
+        members: rec.members.clone(),
+        mode: rec.mode.clone(),
+    };
+    let signed = sign_statement(kp, &body).map_err(CliError::code)?;
+    let payload = build_group_control(GROUP_MEMBERSHIP_KIND, signed).map_err(CliError::code)?;
+    let mut store = group_store_load().map_err(CliError::code)?;
+    store.groups.insert(group_id.to_string(), rec.clone());
+    group_store_save(&store).map_err(CliError::code)?;
+    if !extra.is_empty() {
+        sender_keys_forget(group_id, &rec.members);
+    }
+    let self_fp = member_fp(&kp.sig_pk);
+    let mut recipients = other_members(&rec, &self_fp)?;
+    if !extra.is_empty() {
+        let contacts =
+            contacts_list_entries().map_err(|_| CliError::code("contacts_store_unavailable"))?;
+        for fp in extra {
+            recipients.push((fp.clone(), label_for_member_fp(fp, &contacts)));
+        }
+    }
+    let announced = announce(relay, group_id, &recipients, &payload);
+    emit_group_membership(op, group_id, &rec, announced);
+    Ok(())
+}
+
+/// `group create`: a new group with this side as admin, pairwise unless `sender_keys`.
+pub fn group_create(name: &str, members: &[String], sender_keys: bool, relay: &str) -> CliResult {
+    require_unlocked("group_create")?;
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > GROUP_NAME_MAX {
+        return Err(CliError::code("group_name_invalid"));
+    }
+    let kp = self_signing_keypair().map_err(CliError::code)?;
+    let self_fp = member_fp(&kp.sig_pk);
+    let mut fps = vec![self_fp.clone()];
+    for fp in member_fps_for_labels(members)? {
+        if !fps.contains(&fp) {
+            fps.push(fp);
+        }
+    }
+    if fps.len() < 2 {
+        return Err(CliError::code("group_members_required"));
+    }
+    let mode = sender_keys.then(|| GROUP_MODE_SENDER_KEY.to_string());
+    if group_members_cap(mode.as_deref()).is_none_or(|cap| fps.len() > cap) {
+        return Err(CliError::code("group_too_large"));
+    }
+    let group_id = mint_group_id();
+    let rec = GroupRecord {
+        name: name.to_string(),
+        admin: self_fp,
+        epoch: 1,
+        members: fps,
+        updated_at: crate::clock::now_unix_s(),
+        mode,
+    };
+    commit_and_announce(relay, &group_id, rec, &kp, &[], "create")
+}
+
+fn admin_group(group_id: &str) -> Result<(GroupRecord, IdentityKeypair), CliError> {
+    let store = group_store_load().map_err(CliError::code)?;
+    let rec = group_held(&store, group_id)?;
+    let kp = self_signing_keypair().map_err(CliError::code)?;
+    if rec.admin != member_fp(&kp.sig_pk) {
+        return Err(CliError::code("group_not_admin"));
+    }
+    Ok((rec, kp))
+}
+
+/// `group add`: admin only; the new list goes to everyone, new members included.
+pub fn group_add(group_id: &str, members: &[String], relay: &str) -> CliResult {
+    require_unlocked("group_add")?;
+    let (mut rec, kp) = admin_group(group_id)?;
+    for fp in member_fps_for_labels(members)? {
+        if !rec.members.contains(&fp) {
+            rec.members.push(fp);
+        }
+    }
+    if group_members_cap(rec.mode.as_deref()).is_none_or(|cap| rec.members.len() > cap) {
+        return Err(CliError::code("group_too_large"));
+    }
+    rec.epoch = rec.epoch.saturating_add(1);
+    rec.updated_at = crate::clock::now_unix_s();
+    commit_and_announce(relay, group_id, rec, &kp, &[], "add")
+}
+
+/// `group remove`: admin only; the removed members are told too.
+pub fn group_remove(group_id: &str, members: &[String], relay: &str) -> CliResult {
+    require_unlocked("group_remove")?;
+    let (mut rec, kp) = admin_group(group_id)?;
+    let fps = member_fps_for_labels(members)?;
+    if fps.contains(&rec.admin) {
+        return Err(CliError::code("group_admin_not_removable"));
+    }
+    let removed: Vec<String> = rec
+        .members
+        .iter()
+        .filter(|m| fps.contains(m))
+        .cloned()
+        .collect();
+    if removed.is_empty() {
+        return Err(CliError::code("group_member_unknown"));
+    }
+    rec.members.retain(|m| !fps.contains(m));
+    rec.epoch = rec.epoch.saturating_add(1);
+    rec.updated_at = crate::clock::now_unix_s();
+    commit_and_announce(relay, group_id, rec, &kp, &removed, "remove")
+}
+
+/// `group leave`: a member signs its own departure and drops the group here.
+///
+/// The admin cannot leave: nobody else could sign the next change, so the group would be
+/// frozen for everyone still in it.
+pub fn group_leave(group_id: &str, relay: &str) -> CliResult {
+    require_unlocked("group_leave")?;
+    let mut store = group_store_load().map_err(CliError::code)?;
+    let rec = group_held(&store, group_id)?;
+    let kp = self_signing_keypair().map_err(CliError::code)?;
+    let self_fp = member_fp(&kp.sig_pk);
+    if rec.admin == self_fp {
+        return Err(CliError::code("group_admin_cannot_leave"));
+    }
+    let body = GroupMembershipBody {
+        v: GROUP_MEMBERSHIP_VERSION,
+        op: GROUP_LEAVE_KIND.to_string(),
+        group_id: group_id.to_string(),
+        name: rec.name.clone(),
+        epoch: rec.epoch,
+        admin: rec.admin.clone(),
+        members: Vec::new(),
+        mode: rec.mode.clone(),
+    };
+    let signed = sign_statement(&kp, &body).map_err(CliError::code)?;
+    let payload = build_group_control(GROUP_LEAVE_KIND, signed).map_err(CliError::code)?;
+    let recipients = other_members(&rec, &self_fp)?;
+    let announced = announce(relay, group_id, &recipients, &payload);
+    store.groups.remove(group_id);
+    group_store_save(&store).map_err(CliError::code)?;
+    sender_keys_forget(group_id, &[]);
+    emit_group_membership("leave", group_id, &rec, announced);
+    Ok(())
+}
+
+/// `group list`.
+pub fn group_list() -> CliResult {
+    require_unlocked("group_list")?;
+    let store = group_store_load().map_err(CliError::code)?;
+    let self_fp = member_fp(&self_signing_keypair().map_err(CliError::code)?.sig_pk);
+    let count_s = store.groups.len().to_string();
+    emit_marker("group_list", None, &[("count", count_s.as_str())]);
+    for (group_id, rec) in store.groups.iter() {
+        let members_s = rec.members.len().to_string();
+        let epoch_s = rec.epoch.to_string();
+        let role = if rec.admin == self_fp {
+            "admin"
+        } else {
+            "member"
+        };
+        emit_marker(
+            "group_item",
+            None,
+            &[
+                ("id", group_id.as_str()),
+                ("name", rec.name.as_str()),
+                ("members", members_s.as_str()),
+                ("epoch", epoch_s.as_str()),
+                ("role", role),
+                ("mode", mode_name(rec)),
+            ],
+        );
+    }
+    Ok(())
+}
+
+/// `group show`: the group and each member as this side knows them.
+pub fn group_show(group_id: &str) -> CliResult {
+    require_unlocked("group_show")?;
+    let store = group_store_load().map_err(CliError::code)?;
+    let rec = group_held(&store, group_id)?;
+    let self_fp = member_fp(&self_signing_keypair().map_err(CliError::code)?.sig_pk);
+    let contacts =
+        contacts_list_entries().map_err(|_| CliError::code("contacts_store_unavailable"))?;
+    let members_s = rec.members.len().to_string();
+    let epoch_s = rec.epoch.to_string();
+    emit_marker(
+        "group",
+        None,
+        &[
+            ("id", group_id),
+            ("name", rec.name.as_str()),
+            ("members", members_s.as_str()),
+            ("epoch", epoch_s.as_str()),
+            ("mode", mode_name(&rec)),
+        ],
+    );
+    for fp in rec.members.iter() {
+        let label = if *fp == self_fp {
+            Some("self".to_string())
+        } else {
+            label_for_member_fp(fp, &contacts)
+        };
+        let role = if *fp == rec.admin { "admin" } else { "member" };
+        emit_marker(
+            "group_member",
+            None,
+            &[
+                ("fp", fp.as_str()),
+                ("label", label.as_deref().unwrap_or("unknown")),
+                ("role", role),
+            ],
+        );
+    }
+    Ok(())
+}
+
+/// `group send`: fan the file's bytes out to every member this side can reach.
+///
+/// In a sender-key group a member that cannot be handed this side's current chain is skipped
+/// like an unreachable one; it gets the chain, and later messages, on the next send.
+///
+/// Same O1 rule as `qsc send`: every copy is durably queued before anything is packed, so a
+/// crash mid-fan-out leaves queued copies, never a half-sent group message with no trace.
+pub fn group_send(group_id: &str, file: &Path, relay: &str) -> CliResult {
+    require_unlocked("group_send")?;
+    let store = group_store_load().map_err(CliError::code)?;
+    let rec = group_held(&store, group_id)?;
+    let self_fp = member_fp(&self_signing_keypair().map_err(CliError::code)?.sig_pk);
+    let body = fs::read(file).map_err(|_| CliError::code("relay_payload_read_failed"))?;
+    let mut ready = Vec::new();
+    for (fp, label) in other_members(&rec, &self_fp)? {
+        let Some(label) = label else {
+            emit_member_skipped(group_id, None, "no_contact");
+            continue;
+        };
+        match member_send_ready(&label) {
+            Ok(()) => ready.push((fp, label)),
+            Err(reason) => emit_member_skipped(group_id, Some(&label), reason),
+        }
+    }
+    if ready.is_empty() {
+        return Err(CliError::code("group_no_reachable_members"));
+    }
+    // Sender keys: sealed once here, and every queued copy is the same sealed message.
+    let (labels, wire) = if is_sender_key_group(&rec) {
+        let holders = sender_key_prepare(relay, group_id, &rec, ready).map_err(CliError::code)?;
+        if holders.is_empty() {
+            return Err(CliError::code("group_no_reachable_members"));
+        }
+        let sealed = sender_key_seal(group_id, &body).map_err(CliError::code)?;
+        (holders, sealed)
+    } else {
+        let labels: Vec<String> = ready.into_iter().map(|(_, label)| label).collect();
+        (labels, body.clone())
+    };
+    let (dir, source) = config_dir().map_err(cli_err)?;
+    let now = msgqueue::now_unix_s();
+    let rows = msgqueue::enqueue_group_at(&dir, source, &labels, group_id, &wire, now)
+        .map_err(CliError::code)?;
+    let msg_id = rows.first().map(|r| r.msg_id.clone()).unwrap_or_default();
+    timeline_append_group_entry(group_id, group_id, "out", body.len(), &msg_id, &labels)
+        .map_err(CliError::code)?;
+    let mut sender = transport::RelayMessageSender::new(relay);
+    let outcome = msgqueue::drain_at(
+        &dir,
+        source,
+        msgqueue::DrainTrigger::Scheduled,
+        now,
+        &mut sender,
+    )
+    .map_err(CliError::code)?;
+    let queued_s = labels.len().to_string();
+    let sent = rows
+        .iter()
+        .filter(|r| {
+            msgqueue::load_contact(&dir, &r.peer).is_ok_and(|v| {
+                v.iter()
+                    .any(|q| q.msg_id == msg_id && q.state == msgqueue::MsgState::Sent)
+            })
+        })
+        .count();
+    let sent_s = sent.to_string();
+    emit_marker(
+        "group_send",
+        None,
+        &[
+            ("group", group_id),
+            ("queued", queued_s.as_str()),
+            ("sent", sent_s.as_str()),
+            ("msg_id", "<redacted>"),
+        ],
+    );
+    // ⚠ Safe is not sent: copies still queued exit non-zero, as a one-to-one send does.
+    if outcome.sent == 0 || sent < labels.len() {
+        return Err(CliError::code(
+            sender.last_code().unwrap_or("msgqueue_queued"),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adversarial::payload::{GROUP_MEMBERS_MAX, GROUP_SENDER_KEY_MEMBERS_MAX};
+
+    fn body(op: &str, members: &[&str]) -> GroupMembershipBody {
+        GroupMembershipBody {
+            v: GROUP_MEMBERSHIP_VERSION,
+            op: op.to_string(),
+            group_id: "00112233445566778899aabbccddeeff".to_string(),
+            name: "ops".to_string(),
+            epoch: 2,
+            admin: "aa".to_string(),
+            members: members.iter().map(|m| m.to_string()).collect(),
+            mode: None,
+        }
+    }
+
+    #[test]
+    fn a_statement_must_match_its_envelope_and_name_its_admin() {
+        let ok = body(GROUP_MEMBERSHIP_KIND, &["aa", "bb"]);
+        assert_eq!(statement_well_formed(&ok, GROUP_MEMBERSHIP_KIND), Ok(()));
+        // A leave signed as a leave cannot be replayed inside a membership envelope.
+        assert_eq!(
+            statement_well_formed(&ok, GROUP_LEAVE_KIND),
+            Err("group_statement_invalid")
+        );
+        let headless = body(GROUP_MEMBERSHIP_KIND, &["bb"]);
+        assert_eq!(
+            statement_well_formed(&headless, GROUP_MEMBERSHIP_KIND),
+            Err("group_statement_invalid")
+        );
+        let leave = body(GROUP_LEAVE_KIND, &[]);
+        assert_eq!(statement_well_formed(&leave, GROUP_LEAVE_KIND), Ok(()));
+    }
+
+    #[test]
+    fn a_statement_over_the_cap_is_refused() {
+        let fps: Vec<String> = (0..=GROUP_MEMBERS_MAX)
+            .map(|i| format!("{i:02x}"))
+            .collect();
+        let mut big = body(GROUP_MEMBERSHIP_KIND, &[]);
+        big.admin = fps[0].clone();
+        big.members = fps;
+        assert_eq!(
+            statement_well_formed(&big, GROUP_MEMBERSHIP_KIND),
+            Err("group_too_large")
+        );
+    }
+
+    #[test]
+    fn the_signed_message_is_domain_separated() {
+        let msg = statement_msg(b"{}");
+        assert!(msg.starts_with(GROUP_SIG_DOMAIN));
+        assert_eq!(&msg[GROUP_SIG_DOMAIN.len()..], b"{}");
+    }
+}
@@ -1,18 +1,20 @@
 #![allow(unexpected_cfgs)]
 
 use crate::output::{CliError, CliResult};
+use crate::protocol_state::{qsp_healing_status, QspHealingStatus};
 use super::{
     cmd::HandshakeSuiteMode, config_dir, emit_marker, enforce_peer_not_blocked,
     enforce_safe_parents, fs, identity_fingerprint_from_identity,
     identity_fingerprint_single, identity_peer_status, identity_pin_matches_seen,
     identity_pin_matches_seen_identity, identity_read_peer_kem_pk, identity_read_pin,
-    identity_read_sig_pin, identity_self_kem_keypair, init_from_base_handshake, kmac_out,
-        qsp_send_ready_tuple, qsp_session_load, qsp_session_store, relay_peer_route_token,
+    identity_read_sig_pin, identity_self_kem_keypair, init_from_negotiated_handshake, kmac_out,
+        qsp_send_ready_tuple, qsp_session_load, qsp_session_store_established, relay_peer_route_token,
     relay_self_inbox_route_token, require_unlocked, resolve_peer_device_target,
     runtime_pq_kem_ciphertext_bytes, runtime_pq_kem_keypair, runtime_pq_kem_public_key_bytes,
     runtime_pq_sig_keypair, runtime_pq_sig_public_key_bytes, runtime_pq_sig_signature_bytes,
     transport, vault, vault_unlocked, Deserialize, ErrorCode, IdentityKeypair, OsRng, Path,
-    PathBuf, PqKem768, PqSigMldsa65, RngCore, Serialize, StdCrypto, Suite2SessionState, X25519Dh,
+    PathBuf, PqKem768, PqSigMldsa65, RngCore, Serialize, StdCrypto, Suite2NegotiationInput,
+    Suite2SessionState, X25519Dh,
     FpRole, X25519Priv, X25519Pub, SUITE2_PROTOCOL_VERSION, SUITE2_SUITE_ID,
 };
 
@@ -1130,6 +1132,7 @@ where
 fn hs_build_session(
     authenticated: bool,
     role_is_a: bool,
+    suite_context: &HsSuiteContext,
     session_id: [u8; 16],
     dh_init: [u8; 32],
     pq_init_ss: [u8; 32],
@@ -1138,11 +1141,32 @@ fn hs_build_session(
     dh_self_priv: [u8; 32],
 ) -> Result<Suite2SessionState, &'static str> {
     let c = StdCrypto;
-    let mut st = init_from_base_handshake(
+    // The suite tuple both transcripts bound; a legacy-compat handshake binds none and is
+    // Suite-2 by definition. qsc offers only 0x0002, so the peer's committed list is that one
+    // tuple, and `check_negotiated` refuses anything else before a key is derived.
+    let (ad_protocol_version, ad_suite_id) = match suite_context {
+        HsSuiteContext::ExplicitV2 {
+            protocol_version,
+            suite_id,
+            ..
+        } => (*protocol_version, *suite_id),
+        HsSuiteContext::LegacyV1 => (SUITE2_PROTOCOL_VERSION, SUITE2_SUITE_ID),
+    };
+    let local = [SUITE2_SUITE_ID];
+    let committed = [ad_suite_id];
+    let negotiation = Suite2NegotiationInput {
+        local: &local,
+        peer_offered: &committed,
+        peer_committed: &committed,
+        negotiated_protocol_version: SUITE2_PROTOCOL_VERSION,
+        negotiated_suite_id: SUITE2_SUITE_ID,
+        ad_protocol_version,
+        ad_suite_id,
+    };
+    let mut st = init_from_negotiated_handshake(
         &c,
         role_is_a,
-        SUITE2_PROTOCOL_VERSION,
-        SUITE2_SUITE_ID,
+        &negotiation,
         &session_id,
         &dh_init,
         &pq_init_ss,
@@ -1323,34 +1347,24 @@ pub fn handshake_status(peer: Option<&str>) -> CliResult {
     match qsp_session_load(peer_label) {
         Ok(Some(st)) => {
             let (status, peer_confirmed, local_reason) = hs_status_truth(&st);
+            let healing = qsp_healing_status(peer_label, crate::clock::now_unix_s())
+                .ok()
+                .flatten()
+                .map(|h| healing_marker_fields(&h))
+                .unwrap_or_default();
+            let mut kv: Vec<(&str, &str)> = vec![
+                ("status", status),
+                ("peer", peer_label),
+                ("peer_fp", peer_fp.as_str()),
+                ("pinned", pinned_s),
+                ("peer_confirmed", peer_confirmed),
+                ("send_ready", send_ready_s),
+            ];
             if let Some(reason) = local_reason {
-                emit_marker(
-                    "handshake_status",
-                    None,
-                    &[
-                        ("status", status),
-                        ("peer", peer_label),
-                        ("peer_fp", peer_fp.as_str()),
-                        ("pinned", pinned_s),
-                        ("peer_confirmed", peer_confirmed),
-                        ("send_ready", send_ready_s),
-                        ("send_ready_reason", reason),
-                    ],
-                );
-            } else {
-                emit_marker(
-                    "handshake_status",
-                    None,
-                    &[
-                        ("status", status),
-                        ("peer", peer_label),
-                        ("peer_fp", peer_fp.as_str()),
-                        ("pinned", pinned_s),
-                        ("peer_confirmed", peer_confirmed),
-                        ("send_ready", send_ready_s),
-                    ],
-                );
+                kv.push(("send_ready_reason", reason));
             }
+            kv.extend(healing.iter().map(|(k, v)| (*k, v.as_str())));
+            emit_marker("handshake_status", None, &kv);
         }
         Ok(None) => {
             emit_marker(
@@ -1385,6 +1399,57 @@ pub fn handshake_status(peer: Option<&str>) -> CliResult {
     Ok(())
 }
 
+/// PCS healing fields appended to an established `handshake_status` marker: messages and
+/// seconds since the last DH step and the last PQ reseed, sent and received, and which
+/// advertised PQ targets are outstanding. Id lists are comma-joined, `none` when empty.
+fn healing_marker_fields(h: &QspHealingStatus) -> Vec<(&'static str, String)> {
+    let ids = |ids: &[u32]| {
+        if ids.is_empty() {
+            "none".to_string()
+        } else {
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+    };
+    let secs = |v: Option<u64>| v.map_or_else(|| "never".to_string(), |s| s.to_string());
+    vec![
+        ("heal_sent_since_dh", h.state.sent_since_dh_step.to_string()),
+        (
+            "heal_recv_since_dh",
+            h.state.received_since_dh_step.to_string(),
+        ),
+        ("heal_recv_dh_steps", h.state.recv_dh_steps.to_string()),
+        (
+            "heal_ratchet_pending",
+            String::from(if h.ratchet_pending { "yes" } else { "no" }),
+        ),
+        ("heal_secs_since_dh", secs(h.secs_since_ratchet)),
+        (
+            "heal_boundaries_since_reseed",
+            h.boundaries_since_reseed.to_string(),
+        ),
+        ("heal_secs_since_reseed", secs(h.secs_since_reseed)),
+        ("heal_secs_since_recv_dh", secs(h.secs_since_recv_ratchet)),
+        (
+            "heal_secs_since_recv_reseed",
+            secs(h.secs_since_recv_reseed),
+        ),
+        (
+            "heal_outstanding_targets",
+            ids(&h.state.outstanding_targets),
+        ),
+        ("heal_live_adv", ids(&h.live_adv_ids)),
+        (
+            "heal_peer_adv_pending",
+            h.pending_peer_adv_id
+                .map_or_else(|| "none".to_string(), |id| id.to_string()),
+        ),
+        ("heal_skipped_keys", h.state.skipped_keys.to_string()),
+    ]
+}
+
 /// NA-0681 (D616 F1): how the A1 frame is delivered.
 ///
 /// `Direct` is the shipped behaviour, byte-identical. `InviteSlot` wraps the SAME A1 bytes
@@ -1908,6 +1973,7 @@ pub(crate) fn perform_handshake_poll_with_tokens(
                         let st = match hs_build_session(
                             true,
                             true,
+                            &active_suite_context,
                             pending.session_id,
                             dh_init_arr,
                             pq_init_ss,
@@ -1925,7 +1991,7 @@ pub(crate) fn perform_handshake_poll_with_tokens(
                                 return Ok(());
                             }
                         };
-                        qsp_session_store(peer, &st)
+                        qsp_session_store_established(peer, &st)
                             .map_err(|_| "handshake_session_store_failed")?;
                         let _ = hs_pending_clear(self_label, peer);
                         if active_suite_context.is_explicit() {
@@ -2152,7 +2218,7 @@ pub(crate) fn perform_handshake_poll_with_tokens(
                         {
                             continue;
                         }
-                        qsp_session_store(peer, &st)
+                        qsp_session_store_established(peer, &st)
                             .map_err(|_| "handshake_session_store_failed")?;
                         let _ = hs_pending_clear(self_label, peer);
                         if active_suite_context.is_explicit() {
@@ -2161,7 +2227,7 @@ pub(crate) fn perform_handshake_poll_with_tokens(
                             hs_emit_suite_accept(&active_suite_context, true);
                         }
                         // NA-0742: the responder branch's last effect IS the durable commit —
-                        // `qsp_session_store` then `hs_pending_clear` above, with NO push
+                        // `qsp_session_store_established` then `hs_pending_clear` above, with NO push
                         // following. Verified from the bytes rather than assumed symmetric with
                         // the initiator, because in that branch a push DOES follow.
                         if acks_own_frames {
@@ -2324,6 +2390,7 @@ pub(crate) fn perform_handshake_poll_with_tokens(
                 let st = match hs_build_session(
                     true,
                     false,
+                    &init.suite_context,
                     init.session_id,
                     dh_init_arr,
                     pq_init_ss,
@@ -0,0 +1,244 @@
+//! KT self-monitoring: watch a pinned Key Transparency log for bundles published under this
+//! vault's identity that this vault did not mint.
+//!
+//! A logged bundle is ours when its `ik_sig_pq_pub` is the identity signing key from
+//! `identity` — the key whose secret half only this vault holds. Any other bundle logged
+//! under our `user_id` is a key-substitution candidate and raises the distinct
+//! `kt_monitor_alert` marker with code `kt_foreign_bundle`; it is never folded into an
+//! ordinary error code.
+//!
+//! The checked position is kept per (self label, log) under `<config>/kt_monitor/`, so each
+//! run walks only the entries appended since the last one. The walk is complete, not
+//! best-effort: `KtMonitorState::advance` only accepts a pass whose entries reproduce the
+//! log's signed root, so a log cannot skip an entry for us without forking its tree.
+//!
+//! Alerts are emitted BEFORE the advanced position is persisted. A failed persist therefore
+//! re-raises the same alerts on the next run; the reverse order could lose one.
+
+use super::*;
+use crate::transport::KtHttpLog;
+use quantumshield_refimpl::crypto::stdcrypto::StdEd25519;
+use quantumshield_refimpl::kt::{
+    CanonicalKtVerifier, KtMonitorState, KtOwnIdentity, KtPinnedLog, KtTimeSource,
+};
+use quantumshield_refimpl::qsp::PrekeyBundle;
+
+const KT_MONITOR_DIR: &str = "kt_monitor";
+
+/// Oldest STH the monitor accepts from the log, in seconds.
+pub const KT_MONITOR_DEFAULT_MAX_STH_AGE_SECS: u64 = 300;
+
+/// Code on `kt_monitor_alert`: a bundle under our user id that this vault did not mint.
+pub const KT_FOREIGN_BUNDLE: &str = "kt_foreign_bundle";
+
+pub struct KtMonitorArgs {
+    pub as_label: Option<String>,
+    pub user_id: String,
+    pub log_url: String,
+    pub log_id: String,
+    pub log_key: String,
+    pub max_sth_age_secs: u64,
+}
+
+struct VaultIdentity {
+    user_id: Vec<u8>,
+    sig_pk: Vec<u8>,
+}
+
+impl KtOwnIdentity for VaultIdentity {
+    fn user_id(&self) -> &[u8] {
+        &self.user_id
+    }
+
+    fn minted(&self, bundle: &PrekeyBundle) -> bool {
+        bundle.ik_sig_pq_pub == self.sig_pk
+    }
+}
+
+fn kt_monitor_hex32(s: &str, code: &'static str) -> Result<[u8; 32], CliError> {
+    hex_decode(s.trim())
+        .ok()
+        .and_then(|v| <[u8; 32]>::try_from(v).ok())
+        .ok_or_else(|| CliError::code(code))
+}
+
+fn kt_monitor_state_path(dir: &Path, self_label: &str, log_id: &[u8; 32]) -> PathBuf {
+    dir.join(KT_MONITOR_DIR)
+        .join(format!("{}_{}.bin", self_label, hex_encode(log_id)))
+}
+
+fn kt_monitor_state_load(
+    self_label: &str,
+    log_id: &[u8; 32],
+) -> Result<KtMonitorState, &'static str> {
+    let (dir, source) = config_dir().map_err(ErrorCode::as_str)?;
+    ensure_dir_secure(&dir.join(KT_MONITOR_DIR), source).map_err(ErrorCode::as_str)?;
+    let path = kt_monitor_state_path(&dir, self_label, log_id);
+    if !path.exists() {
+        return Ok(KtMonitorState::new(*log_id));
+    }
+    enforce_safe_parents(&path, source).map_err(ErrorCode::as_str)?;
+    let bytes = fs::read(&path).map_err(|_| ErrorCode::IoReadFailed.as_str())?;
+    // Fail closed on a damaged file rather than silently re-walking from leaf 0: a reset
+    // position is harmless, but a file that no longer parses is something to look at.
+    match KtMonitorState::decode(&bytes) {
+        Ok(state) if state.log_id() == *log_id => Ok(state),
+        _ => Err("kt_monitor_state_corrupt"),
+    }
+}
+
+fn kt_monitor_state_store(self_label: &str, state: &KtMonitorState) -> Result<(), &'static str> {
+    let (dir, source) = config_dir().map_err(ErrorCode::as_str)?;
+    ensure_dir_secure(&dir.join(KT_MONITOR_DIR), source).map_err(ErrorCode::as_str)?;
+    let path = kt_monitor_state_path(&dir, self_label, &state.log_id());
+    write_atomic(&path, &state.encode(), source).map_err(ErrorCode::as_str)
+}
+
+/// `qsc identity kt-monitor`: one monitoring pass. Returns `CliError::Emitted` after any
+/// `kt_monitor_alert`, so a scheduled run exits non-zero on a foreign bundle.
+pub fn identity_kt_monitor(args: KtMonitorArgs) -> CliResult {
+    let self_label = crate::identity::identity_resolved_self_label(args.as_label.as_deref())
+        .map_err(|e| CliError::code(e.as_str()))?;
+    let Some(rec) =
+        identity_read_self_public(&self_label).map_err(|e| CliError::code(e.as_str()))?
+    else {
+        emit_marker(
+            "kt_monitor",
+            None,
+            &[("ok", "false"), ("reason", "missing_identity")],
+        );
+        return Err(CliError::code("identity_missing"));
+    };
+    if rec.sig_pk.is_empty() {
+        return Err(CliError::code("identity_sig_pk_missing"));
+    }
+    let user_id = args.user_id.trim();
+    if user_id.is_empty() {
+        return Err(CliError::code("kt_user_id_missing"));
+    }
+    let log_id = kt_monitor_hex32(&args.log_id, "kt_log_id_invalid")?;
+    let verifying_key = kt_monitor_hex32(&args.log_key, "kt_log_key_invalid")?;
+    let log = KtHttpLog::new(&args.log_url).map_err(CliError::code)?;
+
+    let identity = VaultIdentity {
+        user_id: user_id.as_bytes().to_vec(),
+        sig_pk: rec.sig_pk,
+    };
+    let verifier = CanonicalKtVerifier::new(
+        [KtPinnedLog {
+            log_id,
+            verifying_key,
+            proof_cache_ttl_seconds: args.max_sth_age_secs,
+        }],
+        KtTimeSource::System,
+        false,
+    );
+    let state = kt_monitor_state_load(&self_label, &log_id).map_err(CliError::code)?;
+    let (next, report) = match state.advance(&verifier, &log, &StdEd25519, &identity) {
+        Ok(v) => v,
+        Err(e) => {
+            emit_marker("error", Some(e.detail()), &[("op", "kt_monitor")]);
+            return Err(CliError::Emitted);
+        }
+    };
+
+    for alert in &report.alerts {
+        let leaf_index = alert.leaf_index.to_string();
+        let device_id = alert.device_id.to_string();
+        let seen_fp = identity_fingerprint_single(FpRole::Sig, &alert.ik_sig_pq_pub);
+        emit_marker(
+            "kt_monitor_alert",
+            Some(KT_FOREIGN_BUNDLE),
+            &[
+                ("leaf_index", leaf_index.as_str()),
+                ("device_id", device_id.as_str()),
+                ("seen_fp", seen_fp.as_str()),
+            ],
+        );
+    }
+    let from = report.from_tree_size.to_string();
+    let to = report.to_tree_size.to_string();
+    let own = report.own.len().to_string();
+    let alerts = report.alerts.len().to_string();
+    let undecodable = report.undecodable.len().to_string();
+    emit_marker(
+        "kt_monitor",
+        None,
+        &[
+            (
+                "ok",
+                if report.alerts.is_empty() {
+                    "true"
+                } else {
+                    "false"
+                },
+            ),
+            ("from", from.as_str()),
+            ("to", to.as_str()),
+            ("own", own.as_str()),
+            ("alerts", alerts.as_str()),
+            ("undecodable", undecodable.as_str()),
+        ],
+    );
+
+    kt_monitor_state_store(&self_label, &next).map_err(CliError::code)?;
+    if report.alerts.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::Emitted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_vault_signing_key_counts_as_minted() {
+        let identity = VaultIdentity {
+            user_id: b"alice".to_vec(),
+            sig_pk: vec![0xA1; 1952],
+        };
+        let mut bundle = PrekeyBundle {
+            user_id: b"alice".to_vec(),
+            device_id: 1,
+            valid_from: 0,
+            valid_to: 0,
+            ik_sig_ec_pub: [0; 32],
+            ik_sig_pq_pub: vec![0xA1; 1952],
+            spk_dh_pub: [0; 32],
+            spk_pq_pub: Vec::new(),
+            pq_rcv_id: 0,
+            pq_rcv_pub: Vec::new(),
+            opk_dh: None,
+            opk_pq: None,
+            sig_ec: Vec::new(),
+            sig_pq: Vec::new(),
+            kt_log_id: [0; 32],
+            kt_sth: Vec::new(),
+            kt_inclusion_proof: Vec::new(),
+            kt_consistency_proof: Vec::new(),
+        };
+        assert!(identity.minted(&bundle));
+        bundle.ik_sig_pq_pub[0] ^= 1;
+        assert!(!identity.minted(&bundle));
+        bundle.ik_sig_pq_pub.truncate(32);
+        assert!(!identity.minted(&bundle));
+    }
+
+    #[test]
+    fn log_pins_must_be_32_byte_hex() {
+        assert!(kt_monitor_hex32(&"ab".repeat(32), "x").is_ok());
+        assert!(kt_monitor_hex32(&"ab".repeat(31), "x").is_err());
+        assert!(kt_monitor_hex32(&"zz".repeat(32), "x").is_err());
+    }
+
+    #[test]
+    fn state_file_is_keyed_by_label_and_log() {
+        let path = kt_monitor_state_path(Path::new("/cfg"), "self", &[0xAB; 32]);
+        assert_eq!(
+            path,
+            Path::new("/cfg/kt_monitor").join(format!("self_{}.bin", "ab".repeat(32)))
+        );
+    }
+}
@@ -12,8 +12,9 @@ use quantumshield_refimpl::crypto::stdcrypto::{
 use quantumshield_refimpl::crypto::traits::{
     Hash, Kmac, PqKem768, PqSigMldsa65, X25519Dh, X25519Priv, X25519Pub,
 };
-use quantumshield_refimpl::qse::{Envelope, EnvelopeProfile};
-use quantumshield_refimpl::suite2::establish::init_from_base_handshake;
+use quantumshield_refimpl::qse::{EnvNegotiation, Envelope, EnvelopeProfile};
+use quantumshield_refimpl::suite2::establish::init_from_negotiated_handshake;
+use quantumshield_refimpl::suite2::negotiate::Suite2NegotiationInput;
 use quantumshield_refimpl::suite2::ratchet::Suite2RecvWireState;
 use quantumshield_refimpl::suite2::ratchet::{
     recv_dh_boundary, recv_pq_adv_session, recv_pq_reseed, send_boundary, send_pq_advertise,
@@ -51,6 +52,16 @@ const OUTBOX_FILE_NAME: &str = "outbox.json";
 const CTRL_VERSION: u8 = 2;
 const SEND_STATE_NAME: &str = "send.state";
 const QSE_ENV_VERSION_V1: u16 = 0x0100;
+// Envelope versions qsc advertises. v1 only: v2 needs a length key derived from session state,
+// which qsc does not carry yet. qsc also exchanges no envelope capabilities, so the peer's
+// advertisement is taken to be ours and version negotiation is a fixed v1 here: nothing in qsc
+// can reach `env_version_not_negotiated`. That path is exercised by the refimpl vectors only
+// (README "Envelope versions"); wiring a peer list from the contact record is out of scope.
+const QSC_ENV_VERSIONS: [u16; 1] = [QSE_ENV_VERSION_V1];
+const QSC_ENV_NEGOTIATION: EnvNegotiation<'static> = EnvNegotiation {
+    local: &QSC_ENV_VERSIONS,
+    peer: &QSC_ENV_VERSIONS,
+};
 const POLICY_KEY: &str = "policy_profile";
 // NA-0688 C4 (D622 R7): the per-install acknowledged-pull preference, in the CONFIG FILE rather
 // than the vault. It is not a secret, and a config-file preference cannot silently fail to apply
@@ -58,6 +69,12 @@ const POLICY_KEY: &str = "policy_profile";
 // ⚠ Deliberately NOT named `tui.*`: that namespace belongs to a subsystem that was retired and
 // stripped (NA-0645), and four of its keys are dead reads with no writer at all.
 pub(crate) const ACK_MODE_KEY: &str = "ack_mode";
+// The account-wide read-receipt opt-in, beside `ack_mode` for the same reason: a preference, not a
+// secret, and one that must apply identically whether or not the vault is open.
+pub(crate) const READ_RECEIPTS_KEY: &str = "read_receipts";
+// Which devices must confirm before a fanned-out message reads DELIVERED (DESIGN F1): `any`,
+// `all` or `primary`. Stamped on each message at enqueue, so a change applies to new sends only.
+pub(crate) const DELIVERY_RULE_KEY: &str = "delivery_rule";
 const STORE_META_TEMPLATE: &str = "store_version=1\nvmk_status=unset\nkeyslots=0\n";
 pub const MAX_QUEUE_LEN: usize = 64;
 pub const MAX_HISTORY_LEN: usize = 128;
@@ -116,10 +133,14 @@ pub mod clock;
 pub mod cmd;
 pub mod contacts;
 pub mod dedup;
+// DESIGN F4: per-contact disappearing-message timers and the vault-open purge.
+pub mod disappearing;
 pub mod envelope;
 // NA-0751 (D-1393): the GUI-facing typed facade. Calls; never edits.
 pub mod facade;
 pub mod fs_store;
+// DESIGN F5: small groups fanned out over the pairwise sessions, with signed membership.
+pub mod groups;
 // NA-0741 (D-1376) lane 1: the receive-side frame classifier. Crate-private — it is an
 // internal dispatch aid, not a surface, and LANE 2 will call it from `invite/` and
 // `handshake/` as well, which is why it is a top-level module rather than a child of
@@ -131,6 +152,8 @@ pub mod identity;
 // canonical encodings, commitment, signature, state machine, handshake envelope.
 // Sockets stay in `transport` (D616 F4).
 pub mod invite;
+// KT self-monitoring over `identity`; the log's socket lives in `transport`.
+pub mod kt_monitor;
 pub mod model;
 pub mod msgqueue;
 pub mod output;
@@ -150,8 +173,9 @@ use contacts::*;
 use fs_store::{
     check_parent_safe, check_symlink_safe, config_dir, enforce_file_perms, enforce_safe_parents,
     ensure_dir_secure, ensure_store_layout, fsync_dir_best_effort, lock_store_exclusive,
-    lock_store_shared, normalize_ack_mode, normalize_profile, probe_dir_writable, read_ack_mode,
-    read_policy_profile, write_atomic, write_config_key,
+    lock_store_shared, normalize_ack_mode, normalize_delivery_rule, normalize_profile,
+    normalize_read_receipts, probe_dir_writable, read_ack_mode, read_delivery_rule,
+    read_policy_profile, read_read_receipts, write_atomic, write_config_key,
 };
 use handshake::{
     hs_kem_keypair, hs_sig_keypair,
@@ -172,7 +196,7 @@ use protocol_state::{
     kmac_out, protocol_active_or_reason_for_peer,
     protocol_inactive_error, qsp_scka_load, qsp_scka_store, qsp_send_ready_tuple,
     qsp_session_for_channel, qsp_session_load, qsp_session_store,
-    qsp_session_store_with_trigger, qsp_trigger_load, record_qsp_status,
+    qsp_session_store_established, qsp_session_store_with_trigger, qsp_trigger_load, record_qsp_status,
     zero32, QspTriggerState, SckaLocalState, SckaPeerAdv, SendOrigination, QSP_DH_FALLBACK_N,
     QSP_DH_FALLBACK_T_SECS, QSP_PQ_RESEED_N, QSP_PQ_RESEED_T_SECS,
 };
@@ -180,19 +204,29 @@ use relay::*;
 use store::*;
 use timeline::{
     apply_attachment_peer_confirmation, apply_file_peer_confirmation,
-    apply_message_peer_confirmation, emit_cli_confirm_policy, emit_cli_delivery_state_with_device,
+    apply_message_peer_confirmation, apply_message_peer_read, emit_cli_confirm_policy,
+    emit_cli_delivery_state_with_device,
     emit_cli_file_delivery_with_device, emit_cli_receipt_ignored_wrong_device,
     emit_message_state_reject, emit_tui_delivery_state_with_device,
     emit_tui_file_delivery_with_device, emit_tui_receipt_ignored_wrong_device,
     file_delivery_short_id, file_transfer_confirm_id,
     file_transfer_upsert_outbound_record, latest_outbound_file_id,
-    timeline_append_entry, timeline_append_entry_for_target, timeline_store_load, timeline_store_save, ConfirmApplyOutcome, MessageState,
+    timeline_append_entry, timeline_append_entry_for_target, timeline_append_group_entry,
+    timeline_group_member_transition, timeline_purge_expired_at, timeline_read_receipts_owed,
+    timeline_set_read_receipt, timeline_store_load, timeline_store_save,
+    timeline_transition_entry_state, ConfirmApplyOutcome, MessageState, ReadReceiptState,
 };
 
 static VAULT_UNLOCKED_THIS_RUN: AtomicBool = AtomicBool::new(false);
 
+/// Every unlock -- the CLI bootstrap, `vault::protection`'s guarded unlock, a facade host --
+/// lands here, so this is where DESIGN F4's purge runs: once per locked-to-unlocked edge,
+/// after the flag is set so the stores it rewrites are reachable.
 pub fn set_vault_unlocked(unlocked: bool) {
-    VAULT_UNLOCKED_THIS_RUN.store(unlocked, Ordering::SeqCst);
+    let was = VAULT_UNLOCKED_THIS_RUN.swap(unlocked, Ordering::SeqCst);
+    if unlocked && !was {
+        disappearing::purge_expired_messages();
+    }
 }
 
 pub fn vault_unlocked() -> bool {
@@ -414,6 +448,14 @@ pub fn config_set(key: &str, value: &str) -> CliResult {
             Ok(v) => (ACK_MODE_KEY, v),
             Err(e) => return Err(cli_err(e)),
         },
+        "read-receipts" => match normalize_read_receipts(value) {
+            Ok(v) => (READ_RECEIPTS_KEY, v),
+            Err(e) => return Err(cli_err(e)),
+        },
+        "delivery-rule" => match normalize_delivery_rule(value) {
+            Ok(v) => (DELIVERY_RULE_KEY, v),
+            Err(e) => return Err(cli_err(e)),
+        },
         _ => return Err(cli_err(ErrorCode::ParseFailed)),
     };
 
@@ -446,6 +488,8 @@ pub fn config_get(key: &str) -> CliResult {
     let store_key = match key {
         "policy-profile" => POLICY_KEY,
         "ack-mode" => ACK_MODE_KEY,
+        "read-receipts" => READ_RECEIPTS_KEY,
+        "delivery-rule" => DELIVERY_RULE_KEY,
         _ => return Err(cli_err(ErrorCode::ParseFailed)),
     };
     let (dir, source) = match config_dir() {
@@ -468,10 +512,11 @@ pub fn config_get(key: &str) -> CliResult {
         }
     }
 
-    let read = if store_key == ACK_MODE_KEY {
-        read_ack_mode(&file)
-    } else {
-        read_policy_profile(&file)
+    let read = match store_key {
+        ACK_MODE_KEY => read_ack_mode(&file),
+        READ_RECEIPTS_KEY => read_read_receipts(&file),
+        DELIVERY_RULE_KEY => read_delivery_rule(&file),
+        _ => read_policy_profile(&file),
     };
     let value = match read {
         Ok(Some(v)) => v,
@@ -599,6 +644,15 @@ struct QspPackError {
     reason: Option<&'static str>,
 }
 
+impl QspPackError {
+    fn internal() -> Self {
+        Self {
+            code: "qsp_pack_failed",
+            reason: Some("QSP_PACK_INTERNAL"),
+        }
+    }
+}
+
 struct QspUnpackOutcome {
     plaintext: Vec<u8>,
     next_state: Suite2SessionState,
@@ -807,6 +861,9 @@ pub struct ReceiptPolicy {
     pub batch_window_ms: u64,
     pub jitter_ms: u64,
     pub file_confirm_mode: FileConfirmEmitMode,
+    /// Send read receipts at all. OFF unless `config set read-receipts on`; a contact can still
+    /// be opted out on its own (`contacts read-receipts set --mode off`).
+    pub read_receipts: bool,
 }
 
 impl Default for ReceiptPolicy {
@@ -869,6 +926,7 @@ impl Default for ReceiptPolicy {
             batch_window_ms: RECEIPT_BATCH_WINDOW_MS_DEFAULT,
             jitter_ms: RECEIPT_JITTER_MS_DEFAULT,
             file_confirm_mode: FileConfirmEmitMode::CompleteOnly,
+            read_receipts: false,
         }
     }
 }
@@ -944,6 +1002,31 @@ fn resolve_ack_mode(explicit: Option<AckMode>) -> AckMode {
     stored_ack_mode().unwrap_or(AckMode::Lease)
 }
 
+/// The account read-receipt opt-in. Anything other than an explicit `on` -- no config, an
+/// unreadable one -- is off, because a receipt not sent is recoverable and one sent is not.
+fn stored_read_receipts() -> bool {
+    let Ok((dir, _source)) = config_dir() else {
+        return false;
+    };
+    matches!(
+        read_read_receipts(&dir.join(CONFIG_FILE_NAME)).ok().flatten().as_deref(),
+        Some("on")
+    )
+}
+
+/// The account delivery rule for fanned-out messages. Unset or unreadable is `primary`, which
+/// is what DELIVERED meant before a message reached more than one device.
+fn stored_delivery_rule() -> msgqueue::DeliveryRule {
+    let Ok((dir, _source)) = config_dir() else {
+        return msgqueue::DeliveryRule::Primary;
+    };
+    read_delivery_rule(&dir.join(CONFIG_FILE_NAME))
+        .ok()
+        .flatten()
+        .and_then(|raw| msgqueue::DeliveryRule::from_raw(raw.as_str()))
+        .unwrap_or(msgqueue::DeliveryRule::Primary)
+}
+
 fn stored_ack_mode() -> Option<AckMode> {
     let (dir, _source) = config_dir().ok()?;
     let raw = read_ack_mode(&dir.join(CONFIG_FILE_NAME)).ok()??;
@@ -955,10 +1038,13 @@ fn stored_ack_mode() -> Option<AckMode> {
 }
 
 pub fn load_receipt_policy_from_account() -> ReceiptPolicy {
+    let mut policy = ReceiptPolicy {
+        read_receipts: stored_read_receipts(),
+        ..ReceiptPolicy::default()
+    };
     if !vault_unlocked() {
-        return ReceiptPolicy::default();
+        return policy;
     }
-    let mut policy = ReceiptPolicy::default();
     if let Some(raw) = account_secret_trimmed(TUI_RECEIPT_MODE_SECRET_KEY) {
         if let Some(mode) = ReceiptEmitMode::from_raw(raw.as_str()) {
             policy.mode = mode;
@@ -1034,6 +1120,24 @@ pub(crate) fn resolve_sender_receipt_request(explicit: Option<ReceiptRequest>) -
     }
 }
 
+/// THE READ-RECEIPT RULE, IN ONE PLACE. `Err` carries the reason a read receipt is withheld.
+///
+/// Three switches, any one of which is enough to keep a read private: receipts off altogether,
+/// the account opt-in not given, or this contact opted out. The contact check reads an unreadable
+/// store as opted out -- the same fail-closed direction as the account switch.
+pub(crate) fn read_receipt_allowed(peer: &str, policy: &ReceiptPolicy) -> Result<(), &'static str> {
+    if policy.mode == ReceiptEmitMode::Off {
+        return Err("receipts_off");
+    }
+    if !policy.read_receipts {
+        return Err("account_off");
+    }
+    if contact_read_receipts_off(peer) {
+        return Err("contact_off");
+    }
+    Ok(())
+}
+
 fn resolve_receipt_policy(overrides: ReceiptPolicyOverrides) -> ReceiptPolicy {
     let mut policy = load_receipt_policy_from_account();
     if overrides.emit_receipts.is_some() {
@@ -1062,6 +1166,7 @@ fn resolve_receipt_policy(overrides: ReceiptPolicyOverrides) -> ReceiptPolicy {
 fn receipt_kind_str(kind: ReceiptKind) -> &'static str {
     match kind {
         ReceiptKind::Delivered => "delivered",
+        ReceiptKind::Read => adversarial::payload::READ_RECEIPT_KIND,
     }
 }
 
@@ -1072,10 +1177,14 @@ fn receipt_kind_str(kind: ReceiptKind) -> &'static str {
 /// makes the delivery-ack correlate to the queued row: the peer echoes this id back, and
 /// the sender flips exactly that record SENT -> DELIVERED. Minting a second id here would
 /// leave the ack pointing at nothing.
+///
+/// DESIGN F5: `group` names the group a fanned-out copy belongs to; every member's copy carries
+/// the same `msg_id`, and the member's ack is what moves that member's delivery state.
 pub(crate) fn encode_data_payload_with_id(
     payload: Vec<u8>,
     kind: ReceiptKind,
     msg_id: &str,
+    group: Option<&str>,
 ) -> CliResult<Vec<u8>> {
     let ctrl = ReceiptControlPayload {
         v: CTRL_VERSION,
@@ -1084,6 +1193,10 @@ pub(crate) fn encode_data_payload_with_id(
         msg_id: msg_id.to_string(),
         body: Some(payload),
         ns: Some(adversarial::payload::CTRL_NS.to_string()),
+        expire_s: None,
+        group: group.map(str::to_string),
+        membership: None,
+        sender_key: None,
     };
     serde_json::to_vec(&ctrl).map_err(|_| CliError::code("receipt_encode_failed"))
 }
@@ -1110,6 +1223,10 @@ fn encode_receipt_data_payload(
         msg_id: msg_id.clone(),
         body: Some(payload),
         ns: Some(adversarial::payload::CTRL_NS.to_string()),
+        expire_s: None,
+        group: None,
+        membership: None,
+        sender_key: None,
     };
     let encoded =
         serde_json::to_vec(&ctrl).map_err(|_| CliError::code("receipt_encode_failed"))?;
@@ -1156,14 +1273,18 @@ fn parse_receipt_payload(plaintext: &[u8]) -> Option<ReceiptControlPayload> {
     adversarial::payload::parse_receipt_payload(plaintext)
 }
 
-fn build_delivered_ack(msg_id: &str) -> CliResult<Vec<u8>> {
+fn build_receipt_ack(kind: ReceiptKind, msg_id: &str) -> CliResult<Vec<u8>> {
     let ack = ReceiptControlPayload {
         v: CTRL_VERSION,
         t: "ack".to_string(),
-        kind: "delivered".to_string(),
+        kind: receipt_kind_str(kind).to_string(),
         msg_id: msg_id.to_string(),
         body: None,
         ns: Some(adversarial::payload::CTRL_NS.to_string()),
+        expire_s: None,
+        group: None,
+        membership: None,
+        sender_key: None,
     };
     serde_json::to_vec(&ack).map_err(|_| CliError::code("receipt_encode_failed"))
 }
@@ -1181,6 +1302,10 @@ enum PendingReceipt {
         attachment_id: String,
         confirm_handle: String,
     },
+    /// A read receipt owed by `timeline show` or the facade; it rides the next pull's flush.
+    Read {
+        msg_id: String,
+    },
 }
 
 /// The DATA-ENVELOPE receipt obligation, honoured independently of what the inner body turned
@@ -1224,6 +1349,7 @@ fn queue_or_send_receipt(
         PendingReceipt::Message { .. } => "message",
         PendingReceipt::FileComplete { .. } => "file_complete",
         PendingReceipt::AttachmentComplete { .. } => "attachment_complete",
+        PendingReceipt::Read { .. } => "read",
     };
     // NA-0682 (D617 F6): with acks ON by default, we now attempt them for every received
     // message -- including from peers we have no route BACK to.
@@ -1238,7 +1364,9 @@ fn queue_or_send_receipt(
     // Skipping quietly is both quieter and more correct: an ack we structurally cannot
     // deliver is not a failure to report, it is a thing not to attempt. The sender simply
     // stays at SENT, which is the honest state -- we have no way to tell them otherwise.
-    if matches!(item, PendingReceipt::Message { .. }) && relay_peer_route_token(ctx.from).is_err() {
+    if matches!(item, PendingReceipt::Message { .. } | PendingReceipt::Read { .. })
+        && relay_peer_route_token(ctx.from).is_err()
+    {
         emit_marker(
             "receipt_skipped",
             None,
@@ -1304,7 +1432,7 @@ pub(crate) fn flush_owed_receipts(peer: &str, relay: &str) {
     };
     let mut sent = 0usize;
     for msg_id in owed {
-        match send_delivered_receipt_ack(relay, peer, &msg_id) {
+        match send_receipt_ack(relay, peer, ReceiptKind::Delivered, &msg_id) {
             Ok(()) => {
                 sent += 1;
                 emit_marker(
@@ -1362,7 +1490,7 @@ fn send_pending_receipt(ctx: &ReceivePullCtx<'_>, item: PendingReceipt) -> CliRe
                     }
                 }
             }
-            match send_delivered_receipt_ack(ctx.relay, ctx.from, &msg_id) {
+            match send_receipt_ack(ctx.relay, ctx.from, ReceiptKind::Delivered, &msg_id) {
                 Ok(()) => {
                     emit_marker(
                         "receipt_send",
@@ -1469,6 +1597,97 @@ fn send_pending_receipt(ctx: &ReceivePullCtx<'_>, item: PendingReceipt) -> CliRe
                 ],
             );
         }
+        PendingReceipt::Read { msg_id } => {
+            // A read receipt is already durable as an OWED timeline row, so a chain with nowhere
+            // to carry it simply leaves it owed for a later pull -- no second hold is needed.
+            if qsp_send_chain_unseeded(ctx.from) {
+                emit_marker(
+                    "receipt_deferred",
+                    None,
+                    &[("kind", "read"), ("reason", "chain_unseeded")],
+                );
+                return Ok(());
+            }
+            match send_receipt_ack(ctx.relay, ctx.from, ReceiptKind::Read, &msg_id) {
+                Ok(()) => {
+                    // ⚠ Sent is recorded AFTER the push. A crash in between re-sends one read
+                    // receipt, which the sender rejects as a duplicate; recording first would
+                    // lose it instead.
+                    if let Err(code) =
+                        timeline_set_read_receipt(ctx.from, &msg_id, ReadReceiptState::Sent)
+                    {
+                        emit_marker("error", Some(code), &[("op", "read_receipt_record")]);
+                    }
+                    emit_marker(
+                        "receipt_send",
+                        None,
+                        &[
+                            ("kind", "read"),
+                            ("bucket", "small"),
+                            ("msg_id", "<redacted>"),
+                        ],
+                    );
+                    emit_cli_receipt_policy_event(
+                        ctx.receipt_policy.mode,
+                        "sent",
+                        "read",
+                        ctx.from,
+                    );
+                    emit_tui_receipt_policy_event(
+                        ctx.receipt_policy.mode,
+                        "sent",
+                        "read",
+                        ctx.from,
+                    );
+                }
+                Err(ReceiptSendError::Soft(code)) => {
+                    emit_marker("receipt_send_failed", Some(code), &[("code", code)])
+                }
+                Err(ReceiptSendError::Fatal(e)) => return Err(e),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Queue the read receipts `timeline show` and the facade left owed to this peer.
+///
+/// ⚠ The decision is RE-TAKEN here, not trusted from display time: a user who turns read receipts
+/// off -- for the account, for this contact, or receipts altogether -- between reading and the
+/// next pull must not have the earlier display leak out. Anything no longer allowed is withheld
+/// for good rather than left owed, so turning the switch back on never sends old reads.
+fn queue_owed_read_receipts(
+    ctx: &ReceivePullCtx<'_>,
+    queue: &mut Vec<PendingReceipt>,
+) -> CliResult {
+    let owed = match timeline_read_receipts_owed(ctx.from) {
+        Ok(v) => v,
+        Err(code) => {
+            emit_marker("error", Some(code), &[("op", "read_receipt_load")]);
+            return Ok(());
+        }
+    };
+    if owed.is_empty() {
+        return Ok(());
+    }
+    if let Err(reason) = read_receipt_allowed(ctx.from, &ctx.receipt_policy) {
+        for msg_id in owed.iter() {
+            if let Err(code) =
+                timeline_set_read_receipt(ctx.from, msg_id, ReadReceiptState::Withheld)
+            {
+                emit_marker("error", Some(code), &[("op", "read_receipt_record")]);
+            }
+        }
+        let n = owed.len().to_string();
+        emit_marker(
+            "read_receipt_withheld",
+            None,
+            &[("count", n.as_str()), ("reason", reason)],
+        );
+        return Ok(());
+    }
+    for msg_id in owed {
+        queue_or_send_receipt(ctx, queue, PendingReceipt::Read { msg_id })?;
     }
     Ok(())
 }
@@ -1515,6 +1734,18 @@ fn flush_batched_receipts(ctx: &ReceivePullCtx<'_>, queue: &mut Vec<PendingRecei
             };
             (2u8, bias, attachment_id.clone())
         }
+        PendingReceipt::Read { msg_id } => {
+            let bias = if ctx.receipt_policy.jitter_ms == 0 {
+                0
+            } else {
+                let mut acc: u64 = 0;
+                for b in msg_id.as_bytes() {
+                    acc = acc.wrapping_add(*b as u64);
+                }
+                acc % (ctx.receipt_policy.jitter_ms + 1)
+            };
+            (3u8, bias, msg_id.clone())
+        }
     });
     let pending = std::mem::take(queue);
     for item in pending {
@@ -1543,8 +1774,13 @@ impl From<CliError> for ReceiptSendError {
     }
 }
 
-fn send_delivered_receipt_ack(relay: &str, to: &str, msg_id: &str) -> Result<(), ReceiptSendError> {
-    let payload = build_delivered_ack(msg_id)?;
+fn send_receipt_ack(
+    relay: &str,
+    to: &str,
+    kind: ReceiptKind,
+    msg_id: &str,
+) -> Result<(), ReceiptSendError> {
+    let payload = build_receipt_ack(kind, msg_id)?;
     let pad_cfg = Some(MetaPadConfig {
         target_len: None,
         profile: Some(EnvelopeProfile::Standard),
@@ -1581,6 +1817,31 @@ fn send_delivered_receipt_ack(relay: &str, to: &str, msg_id: &str) -> Result<(),
     Ok(())
 }
 
+/// Send a control payload that is not a receipt (a timer announcement, a group change).
+///
+/// ⚠ ENG-0095: the same barrier as `send_receipt_ack` -- route token first, pack, COMMIT
+/// fail-closed, only then push. A push failure burns the index rather than reusing it.
+pub(crate) fn send_control_payload(
+    relay: &str,
+    to: &str,
+    payload: &[u8],
+) -> Result<(), &'static str> {
+    let pad_cfg = Some(MetaPadConfig {
+        target_len: None,
+        profile: Some(EnvelopeProfile::Standard),
+        label: Some("small"),
+    });
+    let route_token = relay_peer_route_token(to)?;
+    let pack =
+        qsp_pack(to, payload, pad_cfg, None, SendOrigination::Control).map_err(|e| e.code)?;
+    qsp_session_store_with_trigger(to, &pack.next_state, &pack.trigger)
+        .map_err(|_| "qsp_session_store_failed")?;
+    for pre in pack.pre_envelopes.iter() {
+        transport::relay_inbox_push(relay, route_token.as_str(), pre)?;
+    }
+    transport::relay_inbox_push(relay, route_token.as_str(), &pack.envelope)
+}
+
 fn send_file_completion_ack(
     relay: &str,
     to: &str,
@@ -1593,7 +1854,7 @@ fn send_file_completion_ack(
         profile: Some(EnvelopeProfile::Standard),
         label: Some("small"),
     });
-    // ⚠ ENG-0095: the same barrier and the same reasoning as `send_delivered_receipt_ack`.
+    // ⚠ ENG-0095: the same barrier and the same reasoning as `send_receipt_ack`.
     // Route token first, pack, COMMIT fail-closed, only then push. Both receipt kinds move
     // together, because a barrier covering one of two sibling paths is not a barrier.
     let route_token = relay_peer_route_token(to)?;
@@ -1767,14 +2028,16 @@ fn qsp_wrap_standard_envelope(
     meta_seed: Option<u64>,
 ) -> Result<Vec<u8>, QspPackError> {
     let mut env = Envelope {
-        env_version: QSE_ENV_VERSION_V1,
+        env_version: QSC_ENV_NEGOTIATION
+            .send_version()
+            .ok_or_else(QspPackError::internal)?,
         flags: 0,
         route_token: Vec::new(),
         timestamp_bucket: 0,
         payload: wire,
         padding: Vec::new(),
     };
-    let encoded_len = env.encode().len();
+    let encoded_len = qsp_env_encode(&env)?.len();
     let min_len = EnvelopeProfile::Standard.min_size_bytes();
     if encoded_len < min_len {
         let need = min_len - encoded_len;
@@ -1790,7 +2053,12 @@ fn qsp_wrap_standard_envelope(
                 reason: Some("QSP_PACK_INTERNAL"),
             })?;
     }
-    Ok(env.encode())
+    qsp_env_encode(&env)
+}
+
+/// v1 envelope encoding for the pack paths; an envelope that does not encode fails the pack.
+fn qsp_env_encode(env: &Envelope) -> Result<Vec<u8>, QspPackError> {
+    env.encode().map_err(|_| QspPackError::internal())
 }
 
 fn qsp_pack(
@@ -1967,6 +2235,7 @@ fn qsp_pack(
                 pending_send_ratchet: false,
                 msgs_since_ratchet: 0,
                 last_ratchet_unix_secs: now,
+                ..trig
             };
         }
         if scka_on && !scka.is_default() {
@@ -2057,7 +2326,9 @@ fn qsp_pack(
         (out.wire, ns, out.n)
     };
     let mut env = Envelope {
-        env_version: QSE_ENV_VERSION_V1,
+        env_version: QSC_ENV_NEGOTIATION
+            .send_version()
+            .ok_or_else(QspPackError::internal)?,
         flags: 0,
         route_token: Vec::new(),
         timestamp_bucket: 0,
@@ -2065,7 +2336,7 @@ fn qsp_pack(
         padding: Vec::new(),
     };
     let mut pad_label = None;
-    let mut encoded_len = env.encode().len();
+    let mut encoded_len = qsp_env_encode(&env)?.len();
     let min_len = EnvelopeProfile::Standard.min_size_bytes();
     if encoded_len < min_len {
         let need = min_len - encoded_len;
@@ -2080,7 +2351,7 @@ fn qsp_pack(
                 code: "qsp_pack_failed",
                 reason: Some("QSP_PACK_INTERNAL"),
             })?;
-        encoded_len = env.encode().len();
+        encoded_len = qsp_env_encode(&env)?.len();
     }
     if let Some(cfg) = pad_cfg {
         if let Some(target) = cfg.target_len {
@@ -2098,7 +2369,7 @@ fn qsp_pack(
                 }
                 let pad = c.kmac256(&env.payload, "QSC.META.PAD", &seed_bytes, need);
                 env.padding.extend_from_slice(&pad);
-                encoded_len = env.encode().len();
+                encoded_len = qsp_env_encode(&env)?.len();
             }
             pad_label = cfg.label;
         } else if let Some(profile) = cfg.profile {
@@ -2116,7 +2387,7 @@ fn qsp_pack(
                         code: "qsp_pack_failed",
                         reason: Some("QSP_PACK_INTERNAL"),
                     })?;
-                encoded_len = env.encode().len();
+                encoded_len = qsp_env_encode(&env)?.len();
             }
             pad_label = cfg.label;
         }
@@ -2132,7 +2403,7 @@ fn qsp_pack(
         })?;
     }
     Ok(QspPackOutcome {
-        envelope: env.encode(),
+        envelope: qsp_env_encode(&env)?,
         pre_envelopes,
         next_state,
         trigger: trig,
@@ -2182,7 +2453,8 @@ fn qsp_unpack_for_peer(
 }
 
 fn qsp_unpack(channel: &str, envelope_bytes: &[u8]) -> Result<QspUnpackOutcome, &'static str> {
-    let env = Envelope::decode(envelope_bytes).map_err(|_| "qsp_env_decode_failed")?;
+    let env = Envelope::decode_negotiated(envelope_bytes, &QSC_ENV_NEGOTIATION, None)
+        .map_err(|_| "qsp_env_decode_failed")?;
     let st = qsp_session_for_channel(channel)?;
     let mut trig = qsp_trigger_load(channel);
     let c = StdCrypto;
@@ -2309,6 +2581,12 @@ fn qsp_unpack(channel: &str, envelope_bytes: &[u8]) -> Result<QspUnpackOutcome,
         let evicted = bound_mkskipped(&mut next_state.recv);
         scka.consume_advkey(target_id);
         qsp_scka_store(channel, &scka).map_err(|_| "qsp_session_store_failed")?;
+        // Receive-side healing: the trigger rides the same atomic session commit.
+        let now = qsp_now_unix_secs();
+        trig.last_recv_reseed_unix_secs = now;
+        if combined {
+            trig.last_recv_ratchet_unix_secs = now;
+        }
         let target_s = target_id.to_string();
         emit_marker(
             "qsp_pq_reseed",
@@ -2325,6 +2603,7 @@ fn qsp_unpack(channel: &str, envelope_bytes: &[u8]) -> Result<QspUnpackOutcome,
         if !out.ok {
             return Err(out.reason.unwrap_or("qsp_recv_failed"));
         }
+        trig.last_recv_ratchet_unix_secs = qsp_now_unix_secs();
         emit_marker("qsp_dh_ratchet", None, &[("dir", "recv")]);
         (out.plaintext, out.state, 0u32, 0usize, 0usize)
     } else {
@@ -2625,6 +2904,15 @@ pub fn util_receipt_apply(
                 emit_cli_delivery_state_with_device(peer, "peer_confirmed", device);
                 Ok(())
             }
+            Ok((ConfirmApplyOutcome::DeviceRecorded, target)) => {
+                let device = target.unwrap_or_else(|| channel_device_marker(channel));
+                emit_marker(
+                    "device_delivered",
+                    None,
+                    &[("device", device.as_str()), ("msg_id", "<redacted>")],
+                );
+                Ok(())
+            }
             Err(code) => return Err(CliError::code(code)),
         },
         (None, Some(file), Some(confirm)) => {
@@ -2646,6 +2934,8 @@ pub fn util_receipt_apply(
                     emit_cli_receipt_ignored_wrong_device(peer, dev.as_str());
                     Ok(())
                 }
+                // Only message acks are judged per fanned-out device.
+                Ok((ConfirmApplyOutcome::DeviceRecorded, _)) => Ok(()),
                 Ok((ConfirmApplyOutcome::Confirmed, target)) => {
                     let device = target.as_deref().or_else(|| channel_device_id(channel));
                     emit_cli_file_delivery_with_device(
@@ -2927,6 +3217,7 @@ pub fn outbox_status() -> CliResult {
         let q = s.queued.to_string();
         let sent = s.sent.to_string();
         let del = s.delivered.to_string();
+        let read = s.read.to_string();
         let failed = s.failed.to_string();
         let line = s.honest_line().unwrap_or_else(|| "idle".to_string());
         emit_marker(
@@ -2937,6 +3228,7 @@ pub fn outbox_status() -> CliResult {
                 ("queued", q.as_str()),
                 ("sent", sent.as_str()),
                 ("delivered", del.as_str()),
+                ("read", read.as_str()),
                 ("failed", failed.as_str()),
                 ("paused", s.paused.map(|c| c.as_str()).unwrap_or("none")),
                 ("status", line.as_str()),
@@ -3076,4 +3368,26 @@ mod message_state_tests {
                 .expect_err("RECEIVED -> DELIVERED must reject for inbound timeline");
         assert_eq!(err, "state_invalid_transition");
     }
+
+    /// Read receipts are a privacy disclosure, so unlike delivery acks they stay opt-in.
+    #[test]
+    fn read_receipts_default_off() {
+        assert!(!super::ReceiptPolicy::default().read_receipts);
+    }
+
+    #[test]
+    fn read_is_reached_from_sent_or_delivered_and_only_outbound() {
+        for from in [MessageState::Sent, MessageState::Delivered] {
+            message_state_transition_allowed(from, MessageState::Read, "out")
+                .expect("SENT/DELIVERED -> READ must be allowed");
+        }
+        let err =
+            message_state_transition_allowed(MessageState::Read, MessageState::Delivered, "out")
+                .expect_err("READ is terminal");
+        assert_eq!(err, "state_invalid_transition");
+        let err =
+            message_state_transition_allowed(MessageState::Received, MessageState::Read, "in")
+                .expect_err("RECEIVED -> READ must reject for inbound timeline");
+        assert_eq!(err, "state_invalid_transition");
+    }
 }
@@ -8,19 +8,25 @@ use qsc::cmd::{
     QuarantineCmd,
     InviteCmd,
     Cli, Cmd, ConfigCmd, ContactsCmd, ContactsDeviceCmd, ContactsDevicePrimaryCmd,
-    ContactsRequestCmd, ContactsTrustModeCmd, EnvelopeCmd, FileCmd, HandshakeCmd, IdentityCmd,
-    MetaCmd, PeersCmd, RelayCmd, SendCmd, TimelineCmd, UtilCmd,
+    ContactsReadReceiptsCmd, ContactsRequestCmd, ContactsTimerCmd, ContactsTrustModeCmd,
+    EnvelopeCmd, FileCmd, GroupCmd,
+    HandshakeCmd, IdentityCmd, MetaCmd, PeersCmd, RelayCmd, SendCmd, TimelineCmd, UtilCmd,
 };
 use qsc::contacts::{
     contacts_add, contacts_block, contacts_device_add, contacts_device_list,
     contacts_device_primary_set, contacts_device_primary_show, contacts_device_revoke,
     contacts_device_status, contacts_device_trust, contacts_device_verify, contacts_list,
-    contacts_request_accept, contacts_request_block, contacts_request_ignore,
-    contacts_request_list, contacts_route_set, contacts_show, contacts_trust_mode_set,
-    contacts_trust_mode_show, contacts_unblock, contacts_verify, normalize_route_token,
-    route_token_hash8,
+    contacts_read_receipts_set, contacts_read_receipts_show, contacts_request_accept,
+    contacts_request_block, contacts_request_ignore, contacts_request_list, contacts_route_set,
+    contacts_show, contacts_trust_mode_set, contacts_trust_mode_show, contacts_unblock,
+    contacts_verify, normalize_route_token, route_token_hash8,
 };
+use qsc::disappearing::{contacts_timer_set, contacts_timer_show};
 use qsc::fs_store::set_umask_077;
+use qsc::groups::{
+    group_add, group_create, group_leave, group_list, group_remove, group_send, group_show,
+};
+use qsc::kt_monitor::{identity_kt_monitor, KtMonitorArgs};
 use qsc::handshake::{
     handshake_init_with_suite_mode, handshake_poll_with_suite_mode, handshake_status,
 };
@@ -32,7 +38,7 @@ use qsc::output::{
 use qsc::protocol_state::{allow_unsafe_seed_fallback_for_tests, qsp_status_tuple};
 use qsc::relay::{RelayConfig, SendExecuteArgs};
 use qsc::store::{TUI_RELAY_INBOX_TOKEN_SECRET_KEY, TUI_RELAY_TOKEN_FILE_SECRET_KEY};
-use qsc::timeline::{timeline_clear, timeline_list, timeline_show};
+use qsc::timeline::{timeline_clear, timeline_group_list, timeline_list, timeline_show};
 use qsc::*;
 
 fn bootstrap_unlock(passphrase_file: Option<&Path>, passphrase_env: Option<&str>) {
@@ -121,6 +127,10 @@ fn run(cli: Cli) -> CliResult {
                     ("batch_window_ms", batch_window_s.as_str()),
                     ("jitter_ms", jitter_s.as_str()),
                     ("file_confirm_mode", policy.file_confirm_mode.as_str()),
+                    (
+                        "read_receipts",
+                        if policy.read_receipts { "on" } else { "off" },
+                    ),
                 ],
             );
         }
@@ -320,6 +330,21 @@ fn run(cli: Cli) -> CliResult {
                 confirm,
                 reset_peers,
             } => identity_rotate(&as_label, confirm, reset_peers),
+            IdentityCmd::KtMonitor {
+                as_label,
+                user_id,
+                log_url,
+                log_id,
+                log_key,
+                max_sth_age_secs,
+            } => identity_kt_monitor(KtMonitorArgs {
+                as_label,
+                user_id,
+                log_url,
+                log_id,
+                log_key,
+                max_sth_age_secs,
+            }),
         }?,
         Some(Cmd::Peers { cmd }) => match cmd {
             PeersCmd::List => peers_list(),
@@ -452,12 +477,41 @@ fn run(cli: Cli) -> CliResult {
                 ContactsRequestCmd::Ignore { label } => contacts_request_ignore(&label)?,
                 ContactsRequestCmd::Block { label } => contacts_request_block(&label)?,
             },
+            ContactsCmd::Timer { cmd } => match cmd {
+                ContactsTimerCmd::Set {
+                    label,
+                    seconds,
+                    relay,
+                } => contacts_timer_set(&label, seconds, &relay)?,
+                ContactsTimerCmd::Show { label } => contacts_timer_show(&label)?,
+            },
+            ContactsCmd::ReadReceipts { cmd } => match cmd {
+                ContactsReadReceiptsCmd::Show { label } => contacts_read_receipts_show(&label)?,
+                ContactsReadReceiptsCmd::Set { label, mode } => {
+                    contacts_read_receipts_set(&label, mode)?
+                }
+            },
         },
         Some(Cmd::Timeline { cmd }) => match cmd {
             TimelineCmd::List { peer, limit } => timeline_list(&peer, limit)?,
             TimelineCmd::Show { peer, id } => timeline_show(&peer, &id)?,
             TimelineCmd::Clear { peer, confirm } => timeline_clear(&peer, confirm)?,
         },
+        Some(Cmd::Group { cmd }) => match cmd {
+            GroupCmd::Create {
+                name,
+                members,
+                sender_keys,
+                relay,
+            } => group_create(&name, &members, sender_keys, &relay)?,
+            GroupCmd::Add { id, members, relay } => group_add(&id, &members, &relay)?,
+            GroupCmd::Remove { id, members, relay } => group_remove(&id, &members, &relay)?,
+            GroupCmd::Leave { id, relay } => group_leave(&id, &relay)?,
+            GroupCmd::List => group_list()?,
+            GroupCmd::Show { id } => group_show(&id)?,
+            GroupCmd::Send { id, file, relay } => group_send(&id, &file, &relay)?,
+            GroupCmd::Timeline { id, limit } => timeline_group_list(&id, limit)?,
+        },
         Some(Cmd::Quarantine { cmd }) => match cmd {
             QuarantineCmd::List => qsc::quarantine_list()?,
             QuarantineCmd::Drop { id } => qsc::quarantine_drop(&id)?,
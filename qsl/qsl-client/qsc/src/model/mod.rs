@@ -37,6 +37,9 @@ pub enum ErrorCode {
     // NA-0616 (ENG-0001): refused to auto-mint a second, divergent self-identity
     // (a self-identity under a different label already exists in the config dir).
     IdentitySelfAmbiguous,
+    // A stored session is older than the state it already advanced past (a rewound blob or
+    // SCKA side-record). Not recoverable in place: the session must be re-established.
+    SessionRollback,
 }
 
 impl ErrorCode {
@@ -55,6 +58,7 @@ impl ErrorCode {
             ErrorCode::ParseFailed => "parse_failed",
             ErrorCode::IdentitySecretUnavailable => "identity_secret_unavailable",
             ErrorCode::IdentitySelfAmbiguous => "identity_self_ambiguous",
+            ErrorCode::SessionRollback => "session_rollback",
         }
     }
 }
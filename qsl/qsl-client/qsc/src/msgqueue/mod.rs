@@ -74,6 +74,9 @@ pub enum MsgState {
     Queued,
     Sent,
     Delivered,
+    /// The peer displayed it. Only ever reached from SENT or DELIVERED, and only when the
+    /// peer opted in to read receipts -- so a row that stops at DELIVERED says nothing.
+    Read,
     /// Terminal for THIS MESSAGE, but not a permanent CAUSE.
     ///
     /// ⚠ This state resolves an inconsistency between two parts of the design, and the
@@ -98,6 +101,7 @@ impl MsgState {
             MsgState::Queued => "QUEUED",
             MsgState::Sent => "SENT",
             MsgState::Delivered => "DELIVERED",
+            MsgState::Read => "READ",
             MsgState::Failed => "FAILED",
             MsgState::FailedPermanent => "FAILED_PERMANENT",
         }
@@ -106,11 +110,43 @@ impl MsgState {
     pub fn is_terminal(self) -> bool {
         matches!(
             self,
-            MsgState::Delivered | MsgState::Failed | MsgState::FailedPermanent
+            MsgState::Delivered | MsgState::Read | MsgState::Failed | MsgState::FailedPermanent
         )
     }
 }
 
+/// DESIGN F1 multi-device: which delivery acks make a fanned-out message DELIVERED.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryRule {
+    /// The first device to confirm.
+    Any,
+    /// Every device the message went to.
+    All,
+    /// The primary device, whatever the others do. The default, because it is what a send
+    /// meant when only the primary ever received anything.
+    Primary,
+}
+
+impl DeliveryRule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeliveryRule::Any => "any",
+            DeliveryRule::All => "all",
+            DeliveryRule::Primary => "primary",
+        }
+    }
+
+    pub fn from_raw(raw: &str) -> Option<Self> {
+        match raw {
+            "any" => Some(DeliveryRule::Any),
+            "all" => Some(DeliveryRule::All),
+            "primary" => Some(DeliveryRule::Primary),
+            _ => None,
+        }
+    }
+}
+
 /// Why retries are not running for a QUEUED row.
 ///
 /// ⚠ None of these is permanent. O4 allows exactly ONE permanent cause (session-revoked),
@@ -166,11 +202,33 @@ pub struct QueuedMessage {
     pub paused_cause: Option<PausedCause>,
     /// The plaintext body, encrypted at rest under the store key.
     pub body: Vec<u8>,
-    /// Per-device delivery (DESIGN F1). SINGLE entry in v1; the map SHAPE is what lets
-    /// Tier-1.5 multi-device ride later without a schema change.
+    /// Per-device delivery (DESIGN F1): device id -> unix seconds its delivery ack arrived.
+    /// Kept on the contact's own row for every device in `devices`; a device copy holds none.
     pub ack_map: BTreeMap<String, u64>,
-    /// DESIGN F4 disappearing-messages hook. DORMANT in v1: nothing reads it.
+    /// DESIGN F1 multi-device: the devices this message fanned out to, primary first. Empty on
+    /// a device copy and on a row written before fan-out, which is DELIVERED on its one ack.
+    #[serde(default)]
+    pub devices: Vec<String>,
+    /// When the acks in `ack_map` make this row DELIVERED. Stamped at enqueue, so changing the
+    /// setting never reinterprets a message already on its way.
+    #[serde(default)]
+    pub delivery_rule: Option<DeliveryRule>,
+    /// Set on a device copy: the non-primary device it carries the message to. Its `peer` is
+    /// that device's channel, so the copy drains through that device's own session.
+    #[serde(default)]
+    pub device: Option<String>,
+    /// Set on the contact's own row when fan-out skipped the primary: the channel of the
+    /// first reachable device, which this row drains through in the primary's place. `None`
+    /// routes through `peer`, re-resolved at every attempt.
+    #[serde(default)]
+    pub route: Option<String>,
+    /// DESIGN F4 disappearing messages: unix seconds after which the row is purged, stamped
+    /// at enqueue from the contact's timer. See `purge_expired_at` for the one exception.
     pub expires_at: Option<u64>,
+    /// DESIGN F5: the group this row is one member's copy of. Every copy of a group message
+    /// shares the group message's `msg_id`; the per-contact directory keeps them apart.
+    #[serde(default)]
+    pub group_id: Option<String>,
     pub enqueued_at: u64,
     pub attempts: u32,
     pub next_attempt_at: u64,
@@ -221,6 +279,31 @@ impl QueuedMessage {
         self.state == MsgState::Queued && now.saturating_sub(self.enqueued_at) >= threshold_secs
     }
 
+    /// The channel this row is packed and pushed through.
+    pub fn route_channel(&self) -> &str {
+        self.route.as_deref().unwrap_or(self.peer.as_str())
+    }
+
+    /// The contact's disappearing timer has run out for this row.
+    pub fn is_expired_at(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|t| t <= now)
+    }
+
+    /// The acks recorded so far satisfy this row's delivery rule.
+    ///
+    /// A row with no `devices` predates fan-out and has one recipient, so any ack will do.
+    pub fn delivery_met(&self) -> bool {
+        let Some(primary) = self.devices.first() else {
+            return !self.ack_map.is_empty();
+        };
+        let acked = |d: &String| self.ack_map.contains_key(d);
+        match self.delivery_rule.unwrap_or(DeliveryRule::Primary) {
+            DeliveryRule::Any => self.devices.iter().any(acked),
+            DeliveryRule::All => self.devices.iter().all(acked),
+            DeliveryRule::Primary => acked(primary),
+        }
+    }
+
     /// Already packed: the next attempt MUST replay `ciphertext` verbatim.
     ///
     /// ⚠ The single most important predicate in this module. Every send path must consult
@@ -547,24 +630,152 @@ fn next_seq(cfg_dir: &Path, peer: &str) -> Result<u64, &'static str> {
 /// a crash at any later point leaves it visible and re-drainable -- never lost, never
 /// invisible. This is the seam Slice 2 asserted by construction and never crash-tested;
 /// A1 kills the process immediately after it, inside the network call.
-pub(crate) fn enqueue_at(
+///
+/// The disappearing-message deadline is stamped in the SAME write, so a crash can never leave
+/// a row that outlives its timer.
+pub(crate) fn enqueue_expiring_at(
     cfg_dir: &Path,
     source: ConfigSource,
     peer: &str,
     body: Vec<u8>,
     now: u64,
+    expires_at: Option<u64>,
+) -> Result<QueuedMessage, &'static str> {
+    enqueue_row(
+        cfg_dir,
+        source,
+        peer,
+        body,
+        now,
+        expires_at,
+        mint_msg_id(),
+        |_| {},
+    )
+}
+
+/// DESIGN F5: fan one group message out as one QUEUED row per member, all under ONE `msg_id`.
+///
+/// Each row drains through its member's own pairwise session exactly as a one-to-one message
+/// would; the shared id is what lets every member's ack land on the one group timeline row.
+/// A failure part-way leaves the rows already written in place -- each is a complete,
+/// drainable message to its member, and the caller reports how far it got.
+pub(crate) fn enqueue_group_at(
+    cfg_dir: &Path,
+    source: ConfigSource,
+    members: &[String],
+    group_id: &str,
+    body: &[u8],
+    now: u64,
+) -> Result<Vec<QueuedMessage>, &'static str> {
+    let msg_id = mint_msg_id();
+    let mut out = Vec::with_capacity(members.len());
+    for member in members {
+        out.push(enqueue_row(
+            cfg_dir,
+            source,
+            member,
+            body.to_vec(),
+            now,
+            None,
+            msg_id.clone(),
+            |rec| rec.group_id = Some(group_id.to_string()),
+        )?);
+    }
+    Ok(out)
+}
+
+/// DESIGN F1 multi-device: fan one message out to every device it is addressed to.
+///
+/// `devices` is `(device_id, channel)`, primary first. The primary's row is the contact's own
+/// row under `peer` -- it routes and drains exactly as a single-device send does -- and it
+/// carries the device list, the rule and, later, every device's ack. Each other device gets a
+/// copy under its own channel, so it drains through that device's own session and a stuck
+/// device holds up only itself. All rows share one `msg_id`.
+///
+/// `route` is set when the primary was skipped: `devices[0]` is then the first reachable
+/// device, and the contact's row drains through that channel instead, standing in for the
+/// primary under the `primary` rule.
+///
+/// Like `enqueue_group_at`, a failure part-way leaves the rows already written in place. The
+/// primary's row is written first, so whatever survives is always tracked.
+///
+/// ⚠ HONEST LIMITS. The device list is fixed here: under `all`, a device whose copy never
+/// arrives -- it fails, is discarded, or was never written because of that part-way failure --
+/// holds the message at SENT for good, which is the truth about it. Group messages still reach
+/// each member's primary only.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn enqueue_fanout_at(
+    cfg_dir: &Path,
+    source: ConfigSource,
+    peer: &str,
+    devices: &[(String, String)],
+    rule: DeliveryRule,
+    route: Option<&str>,
+    body: Vec<u8>,
+    now: u64,
+    expires_at: Option<u64>,
+) -> Result<Vec<QueuedMessage>, &'static str> {
+    let msg_id = mint_msg_id();
+    let ids: Vec<String> = devices.iter().map(|(id, _)| id.clone()).collect();
+    let mut out = Vec::with_capacity(devices.len());
+    out.push(enqueue_row(
+        cfg_dir,
+        source,
+        peer,
+        body.clone(),
+        now,
+        expires_at,
+        msg_id.clone(),
+        |rec| {
+            rec.devices = ids;
+            rec.delivery_rule = Some(rule);
+            rec.route = route.map(str::to_string);
+        },
+    )?);
+    for (id, channel) in devices.iter().skip(1) {
+        out.push(enqueue_row(
+            cfg_dir,
+            source,
+            channel,
+            body.clone(),
+            now,
+            expires_at,
+            msg_id.clone(),
+            |rec| rec.device = Some(id.clone()),
+        )?);
+    }
+    Ok(out)
+}
+
+/// Build and commit one QUEUED row. `shape` fills in what makes it a group or device copy
+/// before the write, so no row is ever on disk without the fields that say what it is.
+#[allow(clippy::too_many_arguments)]
+fn enqueue_row(
+    cfg_dir: &Path,
+    source: ConfigSource,
+    peer: &str,
+    body: Vec<u8>,
+    now: u64,
+    expires_at: Option<u64>,
+    msg_id: String,
+    shape: impl FnOnce(&mut QueuedMessage),
 ) -> Result<QueuedMessage, &'static str> {
     let seq = next_seq(cfg_dir, peer)?;
-    let rec = QueuedMessage {
+    let mut rec = QueuedMessage {
         v: RECORD_VERSION,
-        msg_id: mint_msg_id(),
+        msg_id,
         peer: peer.to_string(),
         seq,
         state: MsgState::Queued,
         paused_cause: None,
         body,
         ack_map: BTreeMap::new(),
-        expires_at: None,
+        devices: Vec::new(),
+        delivery_rule: None,
+        device: None,
+        route: None,
+        expires_at,
+        group_id: None,
         enqueued_at: now,
         attempts: 0,
         next_attempt_at: now,
@@ -576,6 +787,7 @@ pub(crate) fn enqueue_at(
         next_state: None,
         channel: None,
     };
+    shape(&mut rec);
     write_record(cfg_dir, source, &rec)?;
     Ok(rec)
 }
@@ -858,6 +1070,11 @@ pub(crate) fn drain_at(
             if rec.state.is_terminal() || rec.state == MsgState::Sent {
                 continue;
             }
+            // An expired row is never packed, so skipping it consumes no key and cannot let a
+            // later message reuse one. The purge deletes it; FIFO carries on behind it.
+            if !rec.is_packed() && rec.is_expired_at(now) {
+                continue;
+            }
             if trigger.is_immediate() {
                 arm_immediate(rec, now);
                 save(cfg_dir, source, rec)?;
@@ -927,7 +1144,13 @@ fn attempt_one(
 
     // --- accepted (O2: the relay durably took it) -----------------------------
     sender.commit(rec)?;
-    rec.state = MsgState::Sent;
+    // A fanned-out row can already hold enough acks: another device confirmed while this
+    // one was still retrying, under the `any` rule.
+    rec.state = if !rec.devices.is_empty() && rec.delivery_met() {
+        MsgState::Delivered
+    } else {
+        MsgState::Sent
+    };
     rec.paused_cause = None;
     rec.last_error = None;
     // Safe here, and ONLY here, because `sender.commit` above already advanced the session
@@ -1027,6 +1250,166 @@ pub(crate) fn discard_at(
     remove(cfg_dir, peer, rec.seq, &rec.msg_id)
 }
 
+/// Move a SENT or DELIVERED row to READ on the peer's read receipt.
+///
+/// `Ok(false)` when no row moved: the row may have been discarded or expired since, or the
+/// receipt is a duplicate. The timeline, not the queue, is where a bad receipt is judged.
+pub(crate) fn mark_read(
+    cfg_dir: &Path,
+    source: ConfigSource,
+    peer: &str,
+    msg_id: &str,
+) -> Result<bool, &'static str> {
+    let Some(mut rec) = load_contact(cfg_dir, peer)?
+        .into_iter()
+        .find(|r| r.msg_id == msg_id && matches!(r.state, MsgState::Sent | MsgState::Delivered))
+    else {
+        return Ok(false);
+    };
+    rec.state = MsgState::Read;
+    save(cfg_dir, source, &rec)?;
+    Ok(true)
+}
+
+/// What one device's delivery ack did to a fanned-out message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeliveryAck {
+    /// No fan-out row holds this `msg_id`: a group copy, a row written before fan-out, or one
+    /// already discarded or expired. The caller judges the ack the way it always has.
+    Untracked,
+    /// The message never went to this device.
+    NotAddressed,
+    /// Recorded, but the delivery rule is not met yet.
+    Pending,
+    /// Recorded, and this ack met the rule: the row is now DELIVERED.
+    Delivered,
+    /// Recorded against a row the rule had already made DELIVERED (or READ).
+    AlreadyDelivered,
+}
+
+/// The contact's own row for `msg_id`: not a group copy, not a device copy.
+fn find_contact_row(
+    cfg_dir: &Path,
+    peer: &str,
+    msg_id: &str,
+) -> Result<Option<QueuedMessage>, &'static str> {
+    Ok(load_contact(cfg_dir, peer)?
+        .into_iter()
+        .find(|r| r.msg_id == msg_id && r.group_id.is_none() && r.device.is_none()))
+}
+
+/// The devices `msg_id` fanned out to, primary first. Empty when it was not fanned out or the
+/// store cannot be read, which leaves the caller's own device check in charge.
+pub(crate) fn fanout_devices(peer: &str, msg_id: &str) -> Vec<String> {
+    let Ok((dir, _source)) = config_dir() else {
+        return Vec::new();
+    };
+    find_contact_row(&dir, peer, msg_id)
+        .ok()
+        .flatten()
+        .map(|r| r.devices)
+        .unwrap_or_default()
+}
+
+/// `record_delivery_at` against the configured store, now.
+///
+/// Without the store key there is no row to consult, so the ack is `Untracked` and judged
+/// the way it was before fan-out rather than failing the receive.
+pub(crate) fn record_delivery(
+    peer: &str,
+    msg_id: &str,
+    device: Option<&str>,
+) -> Result<DeliveryAck, &'static str> {
+    let (dir, source) = config_dir().map_err(|_| MSGQUEUE_STORE_UNAVAILABLE)?;
+    match record_delivery_at(&dir, source, peer, msg_id, device, now_unix_s()) {
+        Err(MSGQUEUE_VAULT_LOCKED) => Ok(DeliveryAck::Untracked),
+        other => other,
+    }
+}
+
+/// DESIGN F1 multi-device: record `device`'s delivery ack for `msg_id` on `peer`'s row.
+///
+/// `device` is the device the ack's session belongs to; `None` means the contact's one
+/// device, whose session is not device-qualified. The ack lands in the row's `ack_map`, the
+/// device's own copy (if it has one) moves to DELIVERED, and the row itself moves only when
+/// the rule it was stamped with is met. A row still QUEUED keeps the ack for `attempt_one`.
+pub(crate) fn record_delivery_at(
+    cfg_dir: &Path,
+    source: ConfigSource,
+    peer: &str,
+    msg_id: &str,
+    device: Option<&str>,
+    now: u64,
+) -> Result<DeliveryAck, &'static str> {
+    let Some(mut rec) = find_contact_row(cfg_dir, peer, msg_id)? else {
+        return Ok(DeliveryAck::Untracked);
+    };
+    let Some(primary) = rec.devices.first().cloned() else {
+        return Ok(DeliveryAck::Untracked);
+    };
+    let device = device.unwrap_or(primary.as_str()).to_string();
+    if !rec.devices.contains(&device) {
+        return Ok(DeliveryAck::NotAddressed);
+    }
+    if device != primary {
+        let channel = format!("{peer}#{device}");
+        if let Some(mut copy) = load_contact(cfg_dir, &channel)?
+            .into_iter()
+            .find(|r| r.msg_id == msg_id && r.state == MsgState::Sent)
+        {
+            copy.state = MsgState::Delivered;
+            save(cfg_dir, source, &copy)?;
+        }
+    }
+    let already = matches!(rec.state, MsgState::Delivered | MsgState::Read);
+    rec.ack_map.entry(device).or_insert(now);
+    let out = if already {
+        DeliveryAck::AlreadyDelivered
+    } else if rec.state == MsgState::Sent && rec.delivery_met() {
+        rec.state = MsgState::Delivered;
+        DeliveryAck::Delivered
+    } else {
+        DeliveryAck::Pending
+    };
+    save(cfg_dir, source, &rec)?;
+    Ok(out)
+}
+
+/// What one disappearing-message purge did.
+#[derive(Clone, Debug, Default)]
+pub struct PurgeOutcome {
+    /// The rows deleted, as they were just before deletion.
+    pub expired: Vec<QueuedMessage>,
+    /// Expired rows left in place because they are packed (see `purge_expired_at`).
+    pub deferred: usize,
+}
+
+/// DESIGN F4: delete every row whose disappearing timer has run out, in any state.
+///
+/// ⚠ EXCEPT A PACKED ROW. Its ratchet advance lives only in `next_state`, and deleting it
+/// without that commit is the nonce reuse `discard_at` is built around -- which needs a
+/// `MessageSender` this purge deliberately does not have, because it runs on vault open with
+/// no relay in hand. A packed expired row is DEFERRED: the drain still resolves it to SENT or
+/// FAILED, which clears the in-flight state, and the next purge after that deletes it.
+pub(crate) fn purge_expired_at(cfg_dir: &Path, now: u64) -> Result<PurgeOutcome, &'static str> {
+    let mut out = PurgeOutcome::default();
+    for ck in contact_keys(cfg_dir)? {
+        let dir = queue_root(cfg_dir).join(&ck);
+        for rec in load_dir(cfg_dir, &ck, &dir)? {
+            if !rec.is_expired_at(now) {
+                continue;
+            }
+            if rec.is_packed() {
+                out.deferred += 1;
+                continue;
+            }
+            remove(cfg_dir, &rec.peer, rec.seq, &rec.msg_id)?;
+            out.expired.push(rec);
+        }
+    }
+    Ok(out)
+}
+
 /// A contact's queue, summarised as DATA (D617 §2i).
 ///
 /// ⚠ Structured, not marker strings. Slice 4 must not have to parse stdout -- today's
@@ -1038,6 +1421,7 @@ pub struct ContactQueueSummary {
     pub queued: usize,
     pub sent: usize,
     pub delivered: usize,
+    pub read: usize,
     pub failed: usize,
     pub paused: Option<PausedCause>,
     /// Oldest QUEUED row's age in seconds, for the stuck threshold (DESIGN §9 Q2 = 60s).
@@ -1058,6 +1442,7 @@ pub fn summarize_at(cfg_dir: &Path, now: u64) -> Result<Vec<ContactQueueSummary>
             queued: 0,
             sent: 0,
             delivered: 0,
+            read: 0,
             failed: 0,
             paused: None,
             oldest_queued_age_s: None,
@@ -1074,6 +1459,7 @@ pub fn summarize_at(cfg_dir: &Path, now: u64) -> Result<Vec<ContactQueueSummary>
                 }
                 MsgState::Sent => s.sent += 1,
                 MsgState::Delivered => s.delivered += 1,
+                MsgState::Read => s.read += 1,
                 MsgState::Failed | MsgState::FailedPermanent => s.failed += 1,
             }
         }
@@ -1144,6 +1530,16 @@ mod tests {
         [7u8; STORE_KEY_LEN]
     }
 
+    fn enqueue_at(
+        cfg_dir: &Path,
+        source: ConfigSource,
+        peer: &str,
+        body: Vec<u8>,
+        now: u64,
+    ) -> Result<QueuedMessage, &'static str> {
+        enqueue_expiring_at(cfg_dir, source, peer, body, now, None)
+    }
+
     fn sample(peer: &str, seq: u64, msg_id: &str) -> QueuedMessage {
         QueuedMessage {
             v: RECORD_VERSION,
@@ -1154,7 +1550,12 @@ mod tests {
             paused_cause: None,
             body: b"hello".to_vec(),
             ack_map: BTreeMap::new(),
+            devices: Vec::new(),
+            delivery_rule: None,
+            device: None,
+            route: None,
             expires_at: None,
+            group_id: None,
             enqueued_at: 100,
             attempts: 0,
             next_attempt_at: 100,
@@ -1203,6 +1604,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_group_copy_round_trips_and_an_older_record_reads_as_one_to_one() {
+        let k = test_key();
+        let mut rec = sample("alice", 3, "0123456789abcdef0123456789abcdef");
+        rec.group_id = Some("00112233445566778899aabbccddeeff".to_string());
+        let aad = record_aad(&contact_key("alice"), &rec.msg_id, rec.seq);
+        let back = decrypt_record(&k, &aad, &encrypt_record(&k, &aad, &rec).expect("encrypt"))
+            .expect("decrypt");
+        assert_eq!(back.group_id, rec.group_id);
+
+        // A record written before groups existed has no `group_id` key at all.
+        let mut legacy = serde_json::to_value(sample("alice", 4, "aa")).expect("to value");
+        legacy.as_object_mut().expect("object").remove("group_id");
+        let legacy: QueuedMessage = serde_json::from_value(legacy).expect("legacy decodes");
+        assert_eq!(legacy.group_id, None);
+    }
+
     #[test]
     fn a_minted_msg_id_is_128_bits_of_lowercase_hex() {
         let id = mint_msg_id();
@@ -1432,7 +1850,7 @@ mod tests {
         }
         fn push(&mut self, rec: &QueuedMessage) -> Result<(), AttemptResult> {
             self.pushed.push(rec.msg_id.clone());
-            if self.fail_push_for.as_deref() == Some(rec.peer.as_str()) {
+            if self.fail_push_for.as_deref() == Some(rec.route_channel()) {
                 return Err(self.result);
             }
             Ok(())
@@ -1840,6 +2258,7 @@ mod tests {
             queued: 2,
             sent: 0,
             delivered: 0,
+            read: 0,
             failed: 0,
             paused: Some(PausedCause::VaultLocked),
             oldest_queued_age_s: Some(120),
@@ -1862,6 +2281,7 @@ mod tests {
             queued: 1,
             sent: 0,
             delivered: 0,
+            read: 0,
             failed: 0,
             paused: None,
             oldest_queued_age_s: Some(5),
@@ -1934,6 +2354,114 @@ mod tests {
         let _ = fs::remove_dir_all(&cfg);
     }
 
+    // --- read receipts -----------------------------------------------------------
+
+    #[test]
+    fn a_read_receipt_moves_a_sent_or_delivered_row_and_nothing_else() {
+        install_test_store_key();
+        let cfg = temp_cfg("mark_read");
+        let src = ConfigSource::EnvOverride;
+        let mut sent = enqueue_at(&cfg, src, "alice", b"s".to_vec(), 100).expect("e1");
+        sent.state = MsgState::Sent;
+        save(&cfg, src, &sent).expect("save sent");
+        let mut delivered = enqueue_at(&cfg, src, "alice", b"d".to_vec(), 100).expect("e2");
+        delivered.state = MsgState::Delivered;
+        save(&cfg, src, &delivered).expect("save delivered");
+        let queued = enqueue_at(&cfg, src, "alice", b"q".to_vec(), 100).expect("e3");
+
+        assert!(mark_read(&cfg, src, "alice", &sent.msg_id).expect("sent"));
+        assert!(mark_read(&cfg, src, "alice", &delivered.msg_id).expect("delivered"));
+        // Never sent, so it cannot have been read.
+        assert!(!mark_read(&cfg, src, "alice", &queued.msg_id).expect("queued"));
+        // A duplicate receipt moves nothing, and neither does one for another contact's row.
+        assert!(!mark_read(&cfg, src, "alice", &sent.msg_id).expect("dup"));
+        assert!(!mark_read(&cfg, src, "bob", &delivered.msg_id).expect("other peer"));
+
+        let states: Vec<MsgState> = load_contact(&cfg, "alice")
+            .expect("alice")
+            .iter()
+            .map(|r| r.state)
+            .collect();
+        assert_eq!(
+            states,
+            vec![MsgState::Read, MsgState::Read, MsgState::Queued]
+        );
+        assert!(MsgState::Read.is_terminal());
+        let _ = fs::remove_dir_all(&cfg);
+    }
+
+    // --- disappearing messages (DESIGN F4) ------------------------------------
+
+    #[test]
+    fn expired_rows_are_purged_in_any_state_and_live_ones_are_kept() {
+        install_test_store_key();
+        let cfg = temp_cfg("expiry_purge");
+        let src = ConfigSource::EnvOverride;
+        let queued =
+            enqueue_expiring_at(&cfg, src, "alice", b"q".to_vec(), 100, Some(160)).expect("e1");
+        let mut sent =
+            enqueue_expiring_at(&cfg, src, "alice", b"s".to_vec(), 100, Some(150)).expect("e2");
+        sent.state = MsgState::Sent;
+        save(&cfg, src, &sent).expect("save sent");
+        enqueue_expiring_at(&cfg, src, "alice", b"later".to_vec(), 100, Some(10_000)).expect("e3");
+        enqueue_at(&cfg, src, "bob", b"no timer".to_vec(), 100).expect("e4");
+
+        let out = purge_expired_at(&cfg, 159).expect("purge early");
+        assert_eq!(out.expired.len(), 1, "only the row past its deadline goes");
+        assert_eq!(out.expired[0].msg_id, sent.msg_id);
+
+        let out = purge_expired_at(&cfg, 160).expect("purge at deadline");
+        assert_eq!(out.expired.len(), 1);
+        assert_eq!(out.expired[0].msg_id, queued.msg_id);
+        assert_eq!(out.deferred, 0);
+
+        let alice = load_contact(&cfg, "alice").expect("alice");
+        assert_eq!(alice.len(), 1);
+        assert_eq!(alice[0].body, b"later".to_vec());
+        assert_eq!(load_contact(&cfg, "bob").expect("bob").len(), 1);
+        let _ = fs::remove_dir_all(&cfg);
+    }
+
+    #[test]
+    fn an_expired_packed_row_is_deferred_not_deleted() {
+        // ⚠ The nonce-reuse guard: deleting a packed row abandons its ratchet advance.
+        install_test_store_key();
+        let cfg = temp_cfg("expiry_packed");
+        let src = ConfigSource::EnvOverride;
+        let mut rec =
+            enqueue_expiring_at(&cfg, src, "alice", b"x".to_vec(), 100, Some(150)).expect("e1");
+        rec.mark_packed(b"CT".to_vec(), b"NEXT".to_vec(), "chan".to_string());
+        save(&cfg, src, &rec).expect("save packed");
+
+        let out = purge_expired_at(&cfg, 500).expect("purge");
+        assert!(out.expired.is_empty());
+        assert_eq!(out.deferred, 1);
+        assert_eq!(load_contact(&cfg, "alice").expect("load").len(), 1);
+        let _ = fs::remove_dir_all(&cfg);
+    }
+
+    #[test]
+    fn the_drain_never_packs_an_expired_row_and_fifo_continues_behind_it() {
+        install_test_store_key();
+        let cfg = temp_cfg("expiry_drain");
+        let src = ConfigSource::EnvOverride;
+        let stale =
+            enqueue_expiring_at(&cfg, src, "alice", b"stale".to_vec(), 100, Some(120)).expect("e1");
+        enqueue_at(&cfg, src, "alice", b"fresh".to_vec(), 100).expect("e2");
+
+        let mut s = FakeSender::new();
+        let out = drain_at(&cfg, src, DrainTrigger::Scheduled, 200, &mut s).expect("drain");
+        assert_eq!(out.sent, 1);
+        assert!(
+            !s.packed.contains(&stale.msg_id),
+            "an expired row consumed a key"
+        );
+        let recs = load_contact(&cfg, "alice").expect("load");
+        assert_eq!(recs[0].state, MsgState::Queued, "left for the purge");
+        assert_eq!(recs[1].state, MsgState::Sent);
+        let _ = fs::remove_dir_all(&cfg);
+    }
+
     // --- state and backoff ---------------------------------------------------
 
     #[test]
@@ -2041,4 +2569,211 @@ mod tests {
         schedule_retry_at(&mut b, 500, "x");
         assert_eq!(a.next_attempt_at, b.next_attempt_at);
     }
+
+    #[test]
+    fn each_delivery_rule_reads_the_ack_map_its_own_way() {
+        let mut rec = sample("alice", 1, "aa");
+        rec.devices = vec!["p".to_string(), "d2".to_string()];
+        for rule in [DeliveryRule::Any, DeliveryRule::All, DeliveryRule::Primary] {
+            rec.delivery_rule = Some(rule);
+            rec.ack_map.clear();
+            assert!(
+                !rec.delivery_met(),
+                "{}: no ack is never delivered",
+                rule.as_str()
+            );
+        }
+        rec.ack_map.insert("d2".to_string(), 10);
+        rec.delivery_rule = Some(DeliveryRule::Any);
+        assert!(rec.delivery_met());
+        rec.delivery_rule = Some(DeliveryRule::Primary);
+        assert!(
+            !rec.delivery_met(),
+            "a second device alone never satisfies `primary`"
+        );
+        rec.delivery_rule = Some(DeliveryRule::All);
+        assert!(!rec.delivery_met());
+        rec.ack_map.insert("p".to_string(), 11);
+        assert!(rec.delivery_met());
+
+        // A row from before fan-out has one recipient and no device list.
+        let mut legacy = sample("alice", 2, "bb");
+        assert!(!legacy.delivery_met());
+        legacy.ack_map.insert("anything".to_string(), 1);
+        assert!(legacy.delivery_met());
+        assert_eq!(DeliveryRule::from_raw("ALL"), None);
+        assert_eq!(DeliveryRule::from_raw("most"), None);
+    }
+
+    #[test]
+    fn a_fanned_out_message_drains_per_device_and_is_delivered_only_by_its_rule() {
+        install_test_store_key();
+        let cfg = temp_cfg("fanout_all");
+        let src = ConfigSource::EnvOverride;
+        let devices = vec![
+            ("p".to_string(), "alice#p".to_string()),
+            ("d2".to_string(), "alice#d2".to_string()),
+        ];
+        let rows = enqueue_fanout_at(
+            &cfg,
+            src,
+            "alice",
+            &devices,
+            DeliveryRule::All,
+            None,
+            b"hi".to_vec(),
+            100,
+            None,
+        )
+        .expect("fan out");
+        assert_eq!(rows.len(), 2);
+        assert!(
+            rows.iter().all(|r| r.msg_id == rows[0].msg_id),
+            "one msg_id for every copy"
+        );
+        assert_eq!(rows[0].devices, vec!["p".to_string(), "d2".to_string()]);
+        assert_eq!(rows[1].peer, "alice#d2");
+        assert_eq!(rows[1].device.as_deref(), Some("d2"));
+        assert!(
+            rows[1].devices.is_empty(),
+            "acks are kept on the contact's own row only"
+        );
+
+        let mut s = FakeSender::new();
+        let out = drain_at(&cfg, src, DrainTrigger::Scheduled, 100, &mut s).expect("drain");
+        assert_eq!(
+            out.sent, 2,
+            "each device's copy goes through its own session"
+        );
+        let id = rows[0].msg_id.clone();
+
+        assert_eq!(
+            record_delivery_at(&cfg, src, "alice", &id, Some("nope"), 101).expect("ack"),
+            DeliveryAck::NotAddressed
+        );
+        assert_eq!(
+            record_delivery_at(&cfg, src, "alice", &id, Some("d2"), 102).expect("ack"),
+            DeliveryAck::Pending,
+            "`all` waits for the primary too"
+        );
+        let copy = load_contact(&cfg, "alice#d2").expect("load")[0].clone();
+        assert_eq!(
+            copy.state,
+            MsgState::Delivered,
+            "the device's own copy is done"
+        );
+        assert_eq!(
+            load_contact(&cfg, "alice").expect("load")[0].state,
+            MsgState::Sent
+        );
+
+        assert_eq!(
+            record_delivery_at(&cfg, src, "alice", &id, Some("p"), 103).expect("ack"),
+            DeliveryAck::Delivered
+        );
+        assert_eq!(
+            record_delivery_at(&cfg, src, "alice", &id, Some("p"), 104).expect("ack"),
+            DeliveryAck::AlreadyDelivered
+        );
+        let lead = load_contact(&cfg, "alice").expect("load")[0].clone();
+        assert_eq!(lead.state, MsgState::Delivered);
+        assert_eq!(lead.ack_map.get("d2"), Some(&102));
+        assert_eq!(
+            lead.ack_map.get("p"),
+            Some(&103),
+            "a repeated ack keeps the first time"
+        );
+        let _ = fs::remove_dir_all(&cfg);
+    }
+
+    #[test]
+    fn an_ack_that_arrives_before_the_primary_is_sent_is_kept_for_the_send() {
+        install_test_store_key();
+        let cfg = temp_cfg("fanout_any");
+        let src = ConfigSource::EnvOverride;
+        let devices = vec![
+            ("p".to_string(), "bob#p".to_string()),
+            ("d2".to_string(), "bob#d2".to_string()),
+        ];
+        let rows = enqueue_fanout_at(
+            &cfg,
+            src,
+            "bob",
+            &devices,
+            DeliveryRule::Any,
+            None,
+            b"hi".to_vec(),
+            100,
+            None,
+        )
+        .expect("fan out");
+        // The primary's push keeps failing; the second device's copy goes out and is acked.
+        let mut s = FakeSender::new();
+        s.fail_push_for = Some("bob".to_string());
+        drain_at(&cfg, src, DrainTrigger::Scheduled, 100, &mut s).expect("drain");
+        let id = rows[0].msg_id.clone();
+        assert_eq!(
+            record_delivery_at(&cfg, src, "bob", &id, Some("d2"), 101).expect("ack"),
+            DeliveryAck::Pending,
+            "the row is still QUEUED, so it cannot be DELIVERED yet"
+        );
+        assert_eq!(
+            load_contact(&cfg, "bob").expect("load")[0].state,
+            MsgState::Queued
+        );
+
+        let mut s = FakeSender::new();
+        drain_at(&cfg, src, DrainTrigger::ManualRetry, 200, &mut s).expect("drain");
+        assert_eq!(
+            load_contact(&cfg, "bob").expect("load")[0].state,
+            MsgState::Delivered,
+            "under `any` the earlier ack already met the rule"
+        );
+        // A message with no fan-out row is left to the caller.
+        assert_eq!(
+            record_delivery_at(&cfg, src, "bob", "ff", None, 300).expect("ack"),
+            DeliveryAck::Untracked
+        );
+        let _ = fs::remove_dir_all(&cfg);
+    }
+
+    #[test]
+    fn a_skipped_primary_hands_the_contact_row_to_the_first_reachable_device() {
+        install_test_store_key();
+        let cfg = temp_cfg("fanout_stand_in");
+        let src = ConfigSource::EnvOverride;
+        let devices = vec![
+            ("d2".to_string(), "carol#d2".to_string()),
+            ("d3".to_string(), "carol#d3".to_string()),
+        ];
+        let rows = enqueue_fanout_at(
+            &cfg,
+            src,
+            "carol",
+            &devices,
+            DeliveryRule::Primary,
+            Some("carol#d2"),
+            b"hi".to_vec(),
+            100,
+            None,
+        )
+        .expect("fan out");
+        assert_eq!(rows.len(), 2, "no copy is written for the skipped primary");
+        assert_eq!(rows[0].peer, "carol");
+        assert_eq!(rows[0].route_channel(), "carol#d2");
+        assert_eq!(rows[1].route_channel(), "carol#d3");
+
+        // Anything pushed through the primary's own channel would fail.
+        let mut s = FakeSender::new();
+        s.fail_push_for = Some("carol".to_string());
+        let out = drain_at(&cfg, src, DrainTrigger::Scheduled, 100, &mut s).expect("drain");
+        assert_eq!(out.sent, 2);
+        let id = rows[0].msg_id.clone();
+        assert_eq!(
+            record_delivery_at(&cfg, src, "carol", &id, Some("d2"), 101).expect("ack"),
+            DeliveryAck::Delivered,
+            "the stand-in meets the `primary` rule"
+        );
+        let _ = fs::remove_dir_all(&cfg);
+    }
 }
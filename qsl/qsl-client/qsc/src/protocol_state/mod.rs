@@ -4,6 +4,8 @@ use chacha20poly1305::aead::{Aead, KeyInit, Payload};
 use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
 use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
 use quantumshield_refimpl::crypto::traits::{Hash, Kmac};
+use quantumshield_refimpl::suite2::healing::Suite2HealingStatus;
+use quantumshield_refimpl::suite2::limits::Suite2Limits;
 use quantumshield_refimpl::suite2::ratchet::{
     Suite2DhRatchetState, Suite2RecvWireState, Suite2SendState,
 };
@@ -23,7 +25,7 @@ use crate::model::{ConfigSource, ErrorCode};
 use crate::output::{emit_marker, CliError};
 use crate::store::{
     QspStatusRecord, QSP_SESSIONS_DIR, QSP_SESSION_BLOB_MAGIC, QSP_SESSION_BLOB_VERSION,
-    QSP_SESSION_LEGACY_TOMBSTONE, QSP_SESSION_STORE_KEY_SECRET,
+    QSP_SESSION_GEN_SECRET_PREFIX, QSP_SESSION_LEGACY_TOMBSTONE, QSP_SESSION_STORE_KEY_SECRET,
 };
 use crate::vault;
 use crate::{channel_label_ok, env_bool, hex_decode, hex_encode};
@@ -227,6 +229,37 @@ fn qsp_session_store_key_get_or_create(peer: &str) -> Result<[u8; 32], ErrorCode
     }
 }
 
+/// Rollback anchor: the snapshot epoch of the last session blob written for a peer. It lives in
+/// the vault, not beside the blob, so restoring an old `.qsv` file (backup restore, attacker
+/// with disk access) cannot restore the anchor with it.
+fn qsp_session_gen_secret_name(peer: &str) -> String {
+    format!("{}{}", QSP_SESSION_GEN_SECRET_PREFIX, peer)
+}
+
+/// `Ok(None)` = nothing to compare against: no blob stored since the anchor was introduced,
+/// or the vault is unavailable under the unsafe test fallback (the same condition under which
+/// the blob key itself falls back).
+fn qsp_session_gen_anchor_load(peer: &str) -> Result<Option<u64>, ErrorCode> {
+    match vault::secret_get(&qsp_session_gen_secret_name(peer)) {
+        Ok(Some(v)) if v.is_empty() => Ok(None),
+        Ok(Some(v)) => v
+            .parse::<u64>()
+            .map(Some)
+            .map_err(|_| ErrorCode::ParseFailed),
+        Ok(None) => Ok(None),
+        Err("vault_missing" | "vault_locked") if allow_unsafe_seed_fallback_for_tests() => Ok(None),
+        Err(_) => Err(ErrorCode::IdentitySecretUnavailable),
+    }
+}
+
+fn qsp_session_gen_anchor_store(peer: &str, generation: u64) -> Result<(), ErrorCode> {
+    match vault::secret_set(&qsp_session_gen_secret_name(peer), &generation.to_string()) {
+        Ok(()) => Ok(()),
+        Err("vault_missing" | "vault_locked") if allow_unsafe_seed_fallback_for_tests() => Ok(()),
+        Err(_) => Err(ErrorCode::IoWriteFailed),
+    }
+}
+
 // NA-0622 (ENG-0012 Stage 1b-ii): reply-driven DH-ratchet trigger state, persisted INSIDE the
 // qsc session blob's encrypted plaintext (v2 = b"QTRG" + trigger(13) + QS2S snapshot; NA-0624
 // v3 additionally carries a length-delimited SCKA section between the trigger and the snapshot).
@@ -286,6 +319,11 @@ pub(crate) struct QspTriggerState {
     pub msgs_since_ratchet: u32,
     /// Unix seconds of the last DH ratchet (bounded fallback, T).
     pub last_ratchet_unix_secs: u64,
+    /// Unix seconds of the last DH boundary received from the peer (0 = never). Healing
+    /// telemetry only; persisted in the `QRCV` section, not the 13-byte trigger.
+    pub last_recv_ratchet_unix_secs: u64,
+    /// Unix seconds of the last PQ reseed received from the peer (0 = never).
+    pub last_recv_reseed_unix_secs: u64,
 }
 
 // NA-0624 (ENG-0012 Stage 2b): qsc-side SCKA state (DOC-CAN-004 §2/§3), persisted INSIDE the
@@ -656,10 +694,18 @@ impl QspTriggerState {
             pending_send_ratchet: b[0] != 0,
             msgs_since_ratchet: u32::from_le_bytes(m),
             last_ratchet_unix_secs: u64::from_le_bytes(t),
+            ..QspTriggerState::default()
         }
     }
 }
 
+/// Optional receive-side healing section of a v3 plaintext, between the SCKA section and the
+/// snapshot: `QRCV` + last received DH boundary (u64 LE) + last received reseed (u64 LE).
+/// Written only once either has happened, so a session that never received a boundary keeps
+/// the plain v3 layout; the snapshot section starts with `QSSX` or `QS2S`, never `QRCV`.
+const QSP_RECV_MAGIC: &[u8; 4] = b"QRCV";
+const QSP_RECV_LEN: usize = 16;
+
 /// The QS2S snapshot magic (the refimpl `Suite2SessionState` snapshot prefix), used to tell a
 /// legacy v2 plaintext (trigger + raw snapshot) from a v3 plaintext (trigger + SCKA section +
 /// snapshot) without ambiguity.
@@ -676,7 +722,33 @@ enum QspPlaintextError {
     Malformed,
 }
 
-/// Split a decrypted session-blob plaintext into (trigger, SCKA state, raw QS2S snapshot).
+/// Open the snapshot section of a v3 plaintext, returning the state and its epoch. The section
+/// is a sealed snapshot (`quantumshield_refimpl::snapshot`) whose epoch is the rollback
+/// generation; a bare QS2S section, written before sealing, reads as epoch 0 and is sealed by
+/// the next store. The epoch is NOT checked here: the load path compares it with the vault
+/// anchor after the G2 guard, so each rollback keeps its own marker. `Err` is a marker code.
+fn qsp_snapshot_open(
+    peer: &str,
+    section: &[u8],
+) -> Result<(Suite2SessionState, u64), &'static str> {
+    if section.starts_with(QS2S_SNAPSHOT_MAGIC) {
+        // Valid magic + non-v3 version = the unrecoverable class; anything else is generic.
+        let st = Suite2SessionState::restore_bytes(section).map_err(|_| {
+            if section.len() >= 5 && section[4] != 3 {
+                "session_unsupported_version"
+            } else {
+                "session_decrypt_failed"
+            }
+        })?;
+        return Ok((st, 0));
+    }
+    let key = qsp_session_store_key_load(peer).map_err(|_| "session_decrypt_failed")?;
+    let c = StdCrypto;
+    Suite2SessionState::open_snapshot(&c, &c, &key, 0, section)
+        .map_err(|_| "session_decrypt_failed")
+}
+
+/// Split a decrypted session-blob plaintext into (trigger, SCKA state, snapshot section).
 /// v3 = magic + trigger + scka_len(u32 LE) + scka + snapshot is the ONLY accepted layout
 /// (NA-0626: the v2 trigger+raw-snapshot and legacy v1 raw-snapshot migration branches are
 /// REMOVED — they necessarily carry a pre-v3 QS2S section and are unrecoverable). Fail-closed
@@ -688,7 +760,7 @@ fn qsp_split_plaintext(
     if pt.len() >= hdr && &pt[..QSP_TRIGGER_MAGIC.len()] == QSP_TRIGGER_MAGIC {
         let mut t = [0u8; QSP_TRIGGER_LEN];
         t.copy_from_slice(&pt[QSP_TRIGGER_MAGIC.len()..hdr]);
-        let trig = QspTriggerState::decode(&t);
+        let mut trig = QspTriggerState::decode(&t);
         let rest = &pt[hdr..];
         if rest.starts_with(QS2S_SNAPSHOT_MAGIC) {
             // Pre-SCKA v2 layout (a QS2S snapshot follows the trigger directly): unrecoverable.
@@ -703,23 +775,47 @@ fn qsp_split_plaintext(
         }
         let scka = SckaLocalState::decode(&rest[4..4 + scka_len])
             .map_err(|()| QspPlaintextError::Malformed)?;
-        Ok((trig, scka, &rest[4 + scka_len..]))
+        let mut snapshot = &rest[4 + scka_len..];
+        if let Some(recv) = snapshot.strip_prefix(QSP_RECV_MAGIC.as_slice()) {
+            if recv.len() < QSP_RECV_LEN {
+                return Err(QspPlaintextError::Malformed);
+            }
+            let mut v = [0u8; 8];
+            v.copy_from_slice(&recv[..8]);
+            trig.last_recv_ratchet_unix_secs = u64::from_le_bytes(v);
+            v.copy_from_slice(&recv[8..QSP_RECV_LEN]);
+            trig.last_recv_reseed_unix_secs = u64::from_le_bytes(v);
+            snapshot = &recv[QSP_RECV_LEN..];
+        }
+        Ok((trig, scka, snapshot))
     } else {
         // Legacy v1 layout (a raw QS2S snapshot with no trigger/SCKA prefix): unrecoverable.
         Err(QspPlaintextError::UnrecoverableLegacy)
     }
 }
 
-/// Build a v3 session-blob plaintext = magic + trigger + scka_len + scka + snapshot.
+/// Build a v3 session-blob plaintext = magic + trigger + scka_len + scka + [QRCV section] +
+/// snapshot.
 fn qsp_join_plaintext(trig: &QspTriggerState, scka: &SckaLocalState, snapshot: &[u8]) -> Vec<u8> {
     let scka_bytes = scka.encode();
     let mut out = Vec::with_capacity(
-        QSP_TRIGGER_MAGIC.len() + QSP_TRIGGER_LEN + 4 + scka_bytes.len() + snapshot.len(),
+        QSP_TRIGGER_MAGIC.len()
+            + QSP_TRIGGER_LEN
+            + 4
+            + scka_bytes.len()
+            + QSP_RECV_MAGIC.len()
+            + QSP_RECV_LEN
+            + snapshot.len(),
     );
     out.extend_from_slice(QSP_TRIGGER_MAGIC);
     out.extend_from_slice(&trig.encode());
     out.extend_from_slice(&(scka_bytes.len() as u32).to_le_bytes());
     out.extend_from_slice(&scka_bytes);
+    if trig.last_recv_ratchet_unix_secs != 0 || trig.last_recv_reseed_unix_secs != 0 {
+        out.extend_from_slice(QSP_RECV_MAGIC);
+        out.extend_from_slice(&trig.last_recv_ratchet_unix_secs.to_le_bytes());
+        out.extend_from_slice(&trig.last_recv_reseed_unix_secs.to_le_bytes());
+    }
     out.extend_from_slice(snapshot);
     out
 }
@@ -776,6 +872,63 @@ pub(crate) fn qsp_scka_load(peer: &str) -> SckaLocalState {
     }
 }
 
+/// Read-only PCS healing view of one channel: the refimpl state metrics plus the qsc trigger
+/// and SCKA cadence counters that decide the next DH step (`QSP_DH_FALLBACK_N`/`_T_SECS`) and
+/// PQ reseed (`QSP_PQ_RESEED_N`/`_T_SECS`), for both directions: what this client originated
+/// and what it received from the peer. Elapsed seconds are `None` until the event has happened
+/// once on this session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct QspHealingStatus {
+    pub state: Suite2HealingStatus,
+    /// Ratchet-on-reply is armed: the next send is a DH boundary.
+    pub ratchet_pending: bool,
+    pub msgs_since_ratchet: u32,
+    pub secs_since_ratchet: Option<u64>,
+    /// Sent DH boundaries since the last originated reseed.
+    pub boundaries_since_reseed: u32,
+    pub secs_since_reseed: Option<u64>,
+    /// Since the last DH boundary received from the peer.
+    pub secs_since_recv_ratchet: Option<u64>,
+    /// Since the last PQ reseed received from the peer.
+    pub secs_since_recv_reseed: Option<u64>,
+    /// Local advertised ids whose secret is still held, ascending.
+    pub live_adv_ids: Vec<u32>,
+    /// Peer advertisement waiting to be targeted by our next reseed.
+    pub pending_peer_adv_id: Option<u32>,
+}
+
+/// Assemble `QspHealingStatus` for a channel at `now` (unix seconds). Never mutates the store;
+/// `Ok(None)` when there is no session, errors as `qsp_session_load`.
+pub(crate) fn qsp_healing_status(
+    peer: &str,
+    now: u64,
+) -> Result<Option<QspHealingStatus>, ErrorCode> {
+    let st = match qsp_session_load(peer)? {
+        Some(st) => st,
+        None => return Ok(None),
+    };
+    let trig = qsp_trigger_load(peer);
+    let scka = qsp_scka_load(peer);
+    let since = |at: u64| (at != 0).then(|| now.saturating_sub(at));
+    Ok(Some(QspHealingStatus {
+        state: Suite2HealingStatus::of(&st),
+        ratchet_pending: trig.pending_send_ratchet,
+        msgs_since_ratchet: trig.msgs_since_ratchet,
+        secs_since_ratchet: since(trig.last_ratchet_unix_secs),
+        boundaries_since_reseed: scka.boundaries_since_reseed,
+        secs_since_reseed: since(scka.last_reseed_unix_secs),
+        secs_since_recv_ratchet: since(trig.last_recv_ratchet_unix_secs),
+        secs_since_recv_reseed: since(trig.last_recv_reseed_unix_secs),
+        live_adv_ids: scka
+            .advkeys
+            .iter()
+            .filter(|k| !k.consumed && !k.secret.is_empty())
+            .map(|k| k.adv_id)
+            .collect(),
+        pending_peer_adv_id: scka.peer_adv.as_ref().map(|a| a.adv_id),
+    }))
+}
+
 /// Persist an updated SCKA state against the CURRENTLY STORED session snapshot and trigger
 /// (read-modify-write). Used by the message path at SCKA mutation points (an advertised secret
 /// key MUST be durable before its advertisement can leave the client; a consumed peer
@@ -790,11 +943,9 @@ pub(crate) fn qsp_scka_store(peer: &str, scka: &SckaLocalState) -> Result<(), Er
     let pt = qsp_session_decrypt_blob(peer, &blob).map_err(|_| ErrorCode::ParseFailed)?;
     let (trig, _old_scka, snapshot) =
         qsp_split_plaintext(&pt).map_err(|_| ErrorCode::ParseFailed)?;
-    let recv = Suite2SessionState::restore_bytes(snapshot)
-        .map_err(|_| ErrorCode::ParseFailed)?
-        .recv;
-    qsp_session_store_inner(peer, &qsp_join_plaintext(&trig, scka, snapshot))?;
-    qsp_scka_mono_update(&dir, source, peer, &recv, scka)
+    let (st, _epoch) = qsp_snapshot_open(peer, snapshot).map_err(|_| ErrorCode::ParseFailed)?;
+    qsp_session_store_inner(peer, &trig, scka, &st)?;
+    qsp_scka_mono_update(&dir, source, peer, &st.recv, scka)
 }
 
 /// Store the session state together with an explicit DH-ratchet trigger (message path). The
@@ -816,7 +967,7 @@ pub(crate) fn qsp_session_store_with_trigger_scka(
     trig: &QspTriggerState,
     scka: &SckaLocalState,
 ) -> Result<(), ErrorCode> {
-    qsp_session_store_inner(peer, &qsp_join_plaintext(trig, scka, &st.snapshot_bytes()))?;
+    qsp_session_store_inner(peer, trig, scka, st)?;
     let (dir, source) = config_dir()?;
     qsp_scka_mono_update(&dir, source, peer, &st.recv, scka)
 }
@@ -910,15 +1061,7 @@ fn qsp_session_load_encrypted(
         emit_marker("error", Some(code), &[]);
         ErrorCode::ParseFailed
     })?;
-    let st = Suite2SessionState::restore_bytes(snapshot).map_err(|_| {
-        // Valid magic + non-v3 version = the unrecoverable class; anything else is generic.
-        let code =
-            if snapshot.len() >= 5 && snapshot.starts_with(QS2S_SNAPSHOT_MAGIC) && snapshot[4] != 3
-            {
-                "session_unsupported_version"
-            } else {
-                "session_decrypt_failed"
-            };
+    let (st, epoch) = qsp_snapshot_open(peer, snapshot).map_err(|code| {
         emit_marker("error", Some(code), &[]);
         ErrorCode::ParseFailed
     })?;
@@ -929,16 +1072,38 @@ fn qsp_session_load_encrypted(
             Ok(Some(rec)) => {
                 if qsp_scka_rollback_check(&rec, &st.recv, &scka).is_err() {
                     emit_marker("error", Some("session_rollback_detected"), &[]);
-                    return Err(ErrorCode::ParseFailed);
+                    return Err(ErrorCode::SessionRollback);
                 }
             }
             Ok(None) => {}
             Err(()) => {
                 emit_marker("error", Some("session_rollback_detected"), &[]);
-                return Err(ErrorCode::ParseFailed);
+                return Err(ErrorCode::SessionRollback);
             }
         }
     }
+    // Epoch guard: every store seals the snapshot at the next epoch and then advances the vault
+    // anchor, so a snapshot older than the anchor is a rewound copy whose message keys were
+    // already used. Refused with a distinct marker; the recovery is a fresh handshake
+    // (`qsp_session_store_established` discards the rewound state).
+    let anchor = qsp_session_gen_anchor_load(peer)
+        .inspect_err(|e| emit_marker("error", Some(e.as_str()), &[]))?;
+    if let Some(anchor) = anchor {
+        if epoch < anchor {
+            let stored_s = epoch.to_string();
+            let anchor_s = anchor.to_string();
+            emit_marker(
+                "error",
+                Some("session_state_rollback"),
+                &[
+                    ("stored", stored_s.as_str()),
+                    ("anchor", anchor_s.as_str()),
+                    ("recovery", "rehandshake"),
+                ],
+            );
+            return Err(ErrorCode::SessionRollback);
+        }
+    }
     emit_marker("session_load", None, &[("ok", "true"), ("format", "v3")]);
     Ok(st)
 }
@@ -972,7 +1137,43 @@ pub(crate) fn qsp_session_store(peer: &str, st: &Suite2SessionState) -> Result<(
     qsp_session_store_with_trigger(peer, st, &trig)
 }
 
-fn qsp_session_store_inner(peer: &str, plaintext: &[u8]) -> Result<(), ErrorCode> {
+/// Store a session that a handshake just established. If the stored session for this peer
+/// trips a rollback guard, it is discarded first — its blob and SCKA side-record belong to
+/// the rewound state and would otherwise be merged into the new one — which makes the forced
+/// re-handshake the recovery path. The vault anchor is kept, so the new session's
+/// generations continue above it.
+pub(crate) fn qsp_session_store_established(
+    peer: &str,
+    st: &Suite2SessionState,
+) -> Result<(), ErrorCode> {
+    if let Err(ErrorCode::SessionRollback) = qsp_session_load(peer) {
+        let (dir, _) = config_dir()?;
+        for path in [
+            qsp_session_blob_path(&dir, peer),
+            qsp_scka_mono_path(&dir, peer),
+        ] {
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(_) => return Err(ErrorCode::IoWriteFailed),
+            }
+        }
+        emit_marker(
+            "session_rollback_recovered",
+            None,
+            &[("peer", peer), ("via", "rehandshake")],
+        );
+    }
+    qsp_session_store(peer, st)
+}
+
+/// Seal `st` at the next epoch, write the blob, then advance the vault anchor to that epoch.
+fn qsp_session_store_inner(
+    peer: &str,
+    trig: &QspTriggerState,
+    scka: &SckaLocalState,
+    st: &Suite2SessionState,
+) -> Result<(), ErrorCode> {
     if !channel_label_ok(peer) {
         return Err(ErrorCode::ParseFailed);
     }
@@ -980,9 +1181,22 @@ fn qsp_session_store_inner(peer: &str, plaintext: &[u8]) -> Result<(), ErrorCode
     let sessions = qsp_sessions_dir(&dir);
     enforce_safe_parents(&sessions, source)?;
     fs::create_dir_all(&sessions).map_err(|_| ErrorCode::IoWriteFailed)?;
-    let blob = qsp_session_encrypt_blob(peer, plaintext)?;
+    // Blob first, anchor second: a crash in between leaves the blob one epoch AHEAD of the
+    // anchor, which loads; the reverse order would strand a valid session as a rollback.
+    let epoch = qsp_session_gen_anchor_load(peer)?
+        .unwrap_or(0)
+        .checked_add(1)
+        .ok_or(ErrorCode::ParseFailed)?;
+    let key = qsp_session_store_key_get_or_create(peer)?;
+    let c = StdCrypto;
+    // A failed seal stops here, before the blob is written or the anchor moves.
+    let sealed = st
+        .seal_snapshot(&c, &c, &key, epoch)
+        .map_err(|_| ErrorCode::ParseFailed)?;
+    let blob = qsp_session_encrypt_blob(peer, &qsp_join_plaintext(trig, scka, &sealed))?;
     let blob_path = qsp_session_blob_path(&dir, peer);
     write_atomic(&blob_path, &blob, source)?;
+    qsp_session_gen_anchor_store(peer, epoch)?;
     let legacy_path = qsp_session_path(&dir, peer);
     if legacy_path.exists() {
         write_atomic(&legacy_path, QSP_SESSION_LEGACY_TOMBSTONE, source)?;
@@ -1093,6 +1307,9 @@ pub(crate) fn qsp_session_for_channel(channel: &str) -> Result<Suite2SessionStat
         consumed_targets: BTreeSet::new(),
         tombstoned_targets: BTreeSet::new(),
         mkskipped: Vec::new(),
+        limits: Suite2Limits::default(),
+        dh_epoch: 0,
+        now_secs: 0,
     };
     let dh = Suite2DhRatchetState {
         dhs_priv: dh_priv,
@@ -1188,6 +1405,7 @@ mod scka_tests {
             pending_send_ratchet: true,
             msgs_since_ratchet: 9,
             last_ratchet_unix_secs: 42,
+            ..QspTriggerState::default()
         };
         let scka = sample_scka();
         let snapshot = b"QS2Sfake-snapshot-bytes".to_vec();
@@ -1212,6 +1430,30 @@ mod scka_tests {
         );
     }
 
+    #[test]
+    fn recv_healing_section_roundtrips_and_is_omitted_until_used() {
+        let scka = sample_scka();
+        let snapshot = b"QSSXfake-sealed-snapshot".to_vec();
+        let quiet = QspTriggerState::default();
+        let pt = qsp_join_plaintext(&quiet, &scka, &snapshot);
+        assert!(!pt.windows(4).any(|w| w == QSP_RECV_MAGIC));
+        let trig = QspTriggerState {
+            last_recv_ratchet_unix_secs: 1_700_000_200,
+            last_recv_reseed_unix_secs: 1_700_000_300,
+            ..quiet
+        };
+        let pt = qsp_join_plaintext(&trig, &scka, &snapshot);
+        let (t, s, snap) = qsp_split_plaintext(&pt).expect("split");
+        assert_eq!((t, s), (trig, scka.clone()));
+        assert_eq!(snap, snapshot.as_slice());
+        // A truncated section is malformed, never read as a snapshot.
+        let cut = pt.len() - snapshot.len() - 1;
+        assert_eq!(
+            qsp_split_plaintext(&pt[..cut]).err(),
+            Some(QspPlaintextError::Malformed)
+        );
+    }
+
     // NA-0626 (Operator Decision 1): one test per REMOVED legacy-migration branch. A pre-v3
     // blob layout necessarily carries a pre-v3 QS2S section (which restore_bytes no longer
     // accepts) and is UNRECOVERABLE by design — distinct from a merely malformed blob, and
@@ -1222,6 +1464,7 @@ mod scka_tests {
             pending_send_ratchet: true,
             msgs_since_ratchet: 9,
             last_ratchet_unix_secs: 42,
+            ..QspTriggerState::default()
         };
         // The removed v2 branch: trigger + raw QS2S snapshot, no SCKA section.
         let mut v2 = Vec::new();
@@ -1270,6 +1513,9 @@ mod scka_tests {
             consumed_targets: BTreeSet::new(),
             tombstoned_targets: tombs.iter().copied().collect(),
             mkskipped: Vec::new(),
+            limits: Suite2Limits::default(),
+            dh_epoch: 0,
+            now_secs: 0,
         }
     }
 
@@ -35,6 +35,8 @@ pub(crate) const QSP_SESSION_LEGACY_TOMBSTONE: &[u8] = b"QSC_SESSION_MIGRATED_V1
 pub(crate) const QSP_SESSION_BLOB_MAGIC: &[u8; 6] = b"QSSV01";
 pub(crate) const QSP_SESSION_BLOB_VERSION: u8 = 1;
 pub(crate) const QSP_SESSION_STORE_KEY_SECRET: &str = "qsp_session_store_key_v1";
+/// Per-peer vault secret (`<prefix><peer>`) anchoring the epoch of the sealed session snapshot.
+pub(crate) const QSP_SESSION_GEN_SECRET_PREFIX: &str = "qsp_session_gen_v1.";
 pub(crate) const CONTACTS_SECRET_KEY: &str = "contacts.json";
 pub(crate) const TIMELINE_SECRET_KEY: &str = "timeline.json";
 pub(crate) const TUI_RECEIPT_MODE_SECRET_KEY: &str = "tui.receipt.mode";
@@ -65,6 +67,10 @@ pub const INVITES_SECRET_KEY: &str = "invite.created";
 pub const REDEMPTIONS_SECRET_KEY: &str = "invite.redeemed";
 pub(crate) const OUTBOX_NEXT_STATE_SECRET_KEY: &str = "outbox.next_state.v1";
 pub(crate) const CONTACT_REQUESTS_SECRET_KEY: &str = "contact_requests.json";
+// DESIGN F5: groups, in the vault as one JSON blob -- the same shape contacts use.
+pub(crate) const GROUPS_SECRET_KEY: &str = "groups.json";
+// DESIGN F5 sender-key mode: chain keys and signing secrets, apart from the group metadata.
+pub(crate) const GROUP_SENDER_KEYS_SECRET_KEY: &str = "group_sender_keys.json";
 pub(crate) const ATTACHMENT_JOURNAL_SECRET_KEY: &str = "attachments.json";
 // NA-0658 (D594, D-1281): the ENG-0044 vault-protection consts restored to where the
 // originals lived (deleted with the TUI at NA-0645/86c0858d). The bounds and the wipe
@@ -243,6 +249,13 @@ pub(crate) struct ContactRecord {
     /// single-use check reads.
     #[serde(default)]
     pub(crate) invite_id: Option<String>,
+    /// DESIGN F4: this conversation's disappearing-message timer in seconds, as last set here
+    /// or announced by the peer. `None` means messages do not expire.
+    #[serde(default)]
+    pub(crate) disappearing_timer_s: Option<u64>,
+    /// This contact is never sent read receipts, whatever the account setting says.
+    #[serde(default)]
+    pub(crate) read_receipts_off: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -271,5 +284,51 @@ pub(crate) struct TimelineStore {
     pub(crate) peers: BTreeMap<String, Vec<crate::TimelineEntry>>,
     #[serde(default)]
     pub(crate) file_transfers: BTreeMap<String, FileTransferRecord>,
+    /// DESIGN F5: one timeline per group, keyed by group id. Inbound rows name their sender
+    /// in `peer`; outbound rows carry per-member delivery in `members`.
+    #[serde(default)]
+    pub(crate) groups: BTreeMap<String, Vec<crate::TimelineEntry>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct GroupStore {
+    #[serde(default)]
+    pub(crate) groups: BTreeMap<String, GroupRecord>,
+}
+
+/// A group as this side last accepted it. Members, admin and self included, are signing-key
+/// fingerprints; which contact each one is gets resolved at send time, so a member added as a
+/// contact later becomes reachable without a membership change.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct GroupRecord {
+    pub(crate) name: String,
+    pub(crate) admin: String,
+    pub(crate) epoch: u64,
+    pub(crate) members: Vec<String>,
+    #[serde(default)]
+    pub(crate) updated_at: u64,
+    /// The statement's `mode`: `None` for pairwise fan-out, `sender_key` for sender keys.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) mode: Option<String>,
+}
+
+/// Sender-key groups: this side's own chain per group, and the chains other members sent it.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub(crate) struct SenderKeyStore {
+    #[serde(default)]
+    pub(crate) own: BTreeMap<String, OwnSenderKey>,
+    /// Group id, then member id, to that member's hex `SenderKeyReceiver`.
+    #[serde(default)]
+    pub(crate) peers: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct OwnSenderKey {
+    /// Hex `SenderKeyState`, signing secret included.
+    pub(crate) state: String,
+    /// Members this chain has been handed to. Once any of them is no longer a member, the
+    /// chain is retired before it seals again.
+    #[serde(default)]
+    pub(crate) distributed_to: Vec<String>,
 }
 
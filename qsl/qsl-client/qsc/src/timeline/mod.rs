@@ -1,14 +1,16 @@
 use crate::output::{CliError, CliResult};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use crate::store::{FileTransferRecord, TimelineStore, TIMELINE_SECRET_KEY};
 use crate::vault;
 
 use super::{
-    attachment_journal_load, attachment_journal_save, attachment_record_key, channel_label_ok,
-    confirm_target_matches_channel, emit_cli_named_marker, emit_marker, emit_tui_named_marker,
-    file_xfer_store_key, require_unlocked, short_device_marker,
-    short_peer_marker,
+    attachment_journal_load, attachment_journal_save, attachment_record_key, channel_device_id,
+    channel_label_ok, confirm_target_matches_channel, contact_disappearing_timer,
+    emit_cli_named_marker, emit_marker, emit_tui_named_marker, file_xfer_store_key,
+    load_receipt_policy_from_account, msgqueue, read_receipt_allowed, require_unlocked,
+    short_device_marker, short_peer_marker,
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -25,6 +27,50 @@ pub(crate) struct TimelineEntry {
     pub(super) state: String,
     #[serde(default)]
     pub(super) status: String,
+    /// DESIGN F4: unix seconds after which reads skip this row and the unlock purge deletes
+    /// it. Stamped from the contact's disappearing timer when the row is written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) expires_at: Option<u64>,
+    /// Where this inbound message's read receipt stands. `None` for anything a read receipt
+    /// cannot reference: outbound rows, files, and messages that arrived without an id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) read_receipt: Option<ReadReceiptState>,
+    /// DESIGN F5: an outbound group row's delivery, member label -> state. Empty everywhere
+    /// else; a one-to-one row's delivery is `state` itself.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(super) members: BTreeMap<String, String>,
+}
+
+/// An inbound message's read receipt, from arrival to its one and only send.
+///
+/// `Unread` becomes `Owed` or `Withheld` the first time the message is displayed, and `Owed`
+/// becomes `Sent` or `Withheld` at the next pull. Nothing goes back: a message is read once.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ReadReceiptState {
+    Unread,
+    Owed,
+    Sent,
+    Withheld,
+}
+
+impl ReadReceiptState {
+    fn as_str(self) -> &'static str {
+        match self {
+            ReadReceiptState::Unread => "unread",
+            ReadReceiptState::Owed => "owed",
+            ReadReceiptState::Sent => "sent",
+            ReadReceiptState::Withheld => "withheld",
+        }
+    }
+}
+
+impl TimelineEntry {
+    /// DESIGN F4: whether the row's disappearing timer has run out by `now`. Reads skip such
+    /// rows even before the purge has deleted them.
+    pub(super) fn expired_at(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|t| t <= now)
+    }
 }
 
 pub(crate) fn timeline_ts_default() -> u64 {
@@ -48,6 +94,7 @@ pub(super) enum MessageState {
     Sent,
     Received,
     Delivered,
+    Read,
     Failed,
 }
 
@@ -58,6 +105,7 @@ impl MessageState {
             MessageState::Sent => "SENT",
             MessageState::Received => "RECEIVED",
             MessageState::Delivered => "DELIVERED",
+            MessageState::Read => "READ",
             MessageState::Failed => "FAILED",
         }
     }
@@ -68,6 +116,7 @@ impl MessageState {
             MessageState::Sent => "sent",
             MessageState::Received => "received",
             MessageState::Delivered => "delivered",
+            MessageState::Read => "read",
             MessageState::Failed => "failed",
         }
     }
@@ -78,6 +127,7 @@ impl MessageState {
             "SENT" | "sent" => Some(MessageState::Sent),
             "RECEIVED" | "received" => Some(MessageState::Received),
             "DELIVERED" | "delivered" => Some(MessageState::Delivered),
+            "READ" | "read" => Some(MessageState::Read),
             "FAILED" | "failed" => Some(MessageState::Failed),
             _ => None,
         }
@@ -91,6 +141,7 @@ fn message_delivery_semantic(direction: &str, state: MessageState) -> Option<&'s
     match state {
         MessageState::Sent => Some("accepted_by_relay"),
         MessageState::Delivered => Some("peer_confirmed"),
+        MessageState::Read => Some("peer_read"),
         _ => None,
     }
 }
@@ -192,7 +243,11 @@ pub(super) fn message_state_transition_allowed(
             (MessageState::Created, MessageState::Sent)
             | (MessageState::Created, MessageState::Failed)
             | (MessageState::Sent, MessageState::Delivered)
-            | (MessageState::Sent, MessageState::Failed) => Ok(()),
+            | (MessageState::Sent, MessageState::Failed)
+            // READ may skip DELIVERED: the delivery ack can be lost, or turned off, while the
+            // read receipt still arrives.
+            | (MessageState::Sent, MessageState::Read)
+            | (MessageState::Delivered, MessageState::Read) => Ok(()),
             _ => Err("state_invalid_transition"),
         };
     }
@@ -499,6 +554,11 @@ pub(super) fn timeline_append_entry_for_target(
         }
     }
     message_state_transition_allowed(MessageState::Created, final_state, direction)?;
+    let expires_at = contact_disappearing_timer(peer)
+        .map(|secs| crate::clock::now_unix_s().saturating_add(secs));
+    // Only a message that arrived with the sender's id can be named in a read receipt.
+    let read_receipt = (direction == "in" && kind == "msg" && forced_id.is_some())
+        .then_some(ReadReceiptState::Unread);
     let mut store = timeline_store_load()?;
     let ts = store.next_ts;
     store.next_ts = store.next_ts.saturating_add(1);
@@ -515,6 +575,9 @@ pub(super) fn timeline_append_entry_for_target(
         target_device_id: target_device_id.map(short_device_marker),
         state: final_state.as_str().to_string(),
         status: final_state.as_status().to_string(),
+        expires_at,
+        read_receipt,
+        members: BTreeMap::new(),
     };
     store
         .peers
@@ -526,7 +589,27 @@ pub(super) fn timeline_append_entry_for_target(
     Ok(entry)
 }
 
-fn timeline_transition_entry_state(
+/// DESIGN F4: delete every row whose disappearing timer has run out, returning what went.
+///
+/// The store is rewritten only when something expired, so the common case is one read.
+pub(super) fn timeline_purge_expired_at(now: u64) -> Result<Vec<TimelineEntry>, &'static str> {
+    let mut store = timeline_store_load()?;
+    let mut expired = Vec::new();
+    for entries in store.peers.values_mut() {
+        let (gone, kept): (Vec<_>, Vec<_>) = std::mem::take(entries)
+            .into_iter()
+            .partition(|e| e.expired_at(now));
+        *entries = kept;
+        expired.extend(gone);
+    }
+    if !expired.is_empty() {
+        store.peers.retain(|_, entries| !entries.is_empty());
+        timeline_store_save(&store)?;
+    }
+    Ok(expired)
+}
+
+pub(super) fn timeline_transition_entry_state(
     peer: &str,
     id: &str,
     to: MessageState,
@@ -559,7 +642,13 @@ fn timeline_entries_for_peer(peer: &str) -> Result<Vec<TimelineEntry>, &'static
         return Err("timeline_peer_invalid");
     }
     let store = timeline_store_load()?;
-    Ok(store.peers.get(peer).cloned().unwrap_or_default())
+    let now = crate::clock::now_unix_s();
+    let entries = store.peers.get(peer).map(Vec::as_slice).unwrap_or_default();
+    Ok(entries
+        .iter()
+        .filter(|e| !e.expired_at(now))
+        .cloned()
+        .collect())
 }
 
 fn timeline_outbound_target_device(peer: &str, id: &str) -> Result<Option<String>, &'static str> {
@@ -583,6 +672,10 @@ fn timeline_outbound_target_device(peer: &str, id: &str) -> Result<Option<String
 pub(super) enum ConfirmApplyOutcome {
     Confirmed,
     IgnoredWrongDevice,
+    /// DESIGN F1: one device of a fanned-out message confirmed and its ack is recorded, but
+    /// the message's own state did not move -- the delivery rule is not met yet, or was met
+    /// by an earlier device.
+    DeviceRecorded,
 }
 
 pub(super) fn apply_message_peer_confirmation(
@@ -590,7 +683,37 @@ pub(super) fn apply_message_peer_confirmation(
     msg_id: &str,
     recv_channel: &str,
 ) -> Result<(ConfirmApplyOutcome, Option<String>), &'static str> {
-    let target = timeline_outbound_target_device(peer, msg_id)?;
+    // DESIGN F1: a fanned-out message is judged per device, by the session the ack came in on.
+    // Asked first, because the row may still be QUEUED for the primary with no timeline row.
+    let device = channel_device_id(recv_channel);
+    match msgqueue::record_delivery(peer, msg_id, device)? {
+        msgqueue::DeliveryAck::Untracked => {}
+        msgqueue::DeliveryAck::NotAddressed => {
+            return Ok((
+                ConfirmApplyOutcome::IgnoredWrongDevice,
+                device.map(short_device_marker),
+            ));
+        }
+        msgqueue::DeliveryAck::Pending | msgqueue::DeliveryAck::AlreadyDelivered => {
+            return Ok((
+                ConfirmApplyOutcome::DeviceRecorded,
+                device.map(short_device_marker),
+            ));
+        }
+        msgqueue::DeliveryAck::Delivered => {
+            let entry = timeline_transition_entry_state(peer, msg_id, MessageState::Delivered)?;
+            let target = device.map(short_device_marker).or(entry.target_device_id);
+            return Ok((ConfirmApplyOutcome::Confirmed, target));
+        }
+    }
+    let target = match timeline_outbound_target_device(peer, msg_id) {
+        // Not a one-to-one row: the id may name this member's copy of a group message.
+        Err("state_unknown") => {
+            timeline_group_member_transition(peer, msg_id, MessageState::Delivered)?;
+            return Ok((ConfirmApplyOutcome::Confirmed, None));
+        }
+        other => other?,
+    };
     if !confirm_target_matches_channel(target.as_deref(), recv_channel) {
         return Ok((ConfirmApplyOutcome::IgnoredWrongDevice, target));
     }
@@ -598,6 +721,30 @@ pub(super) fn apply_message_peer_confirmation(
     Ok((ConfirmApplyOutcome::Confirmed, target))
 }
 
+/// The read-receipt twin of `apply_message_peer_confirmation`: READ instead, and any device a
+/// fanned-out message went to may be the one that displayed it.
+pub(super) fn apply_message_peer_read(
+    peer: &str,
+    msg_id: &str,
+    recv_channel: &str,
+) -> Result<(ConfirmApplyOutcome, Option<String>), &'static str> {
+    let target = match timeline_outbound_target_device(peer, msg_id) {
+        Err("state_unknown") => {
+            timeline_group_member_transition(peer, msg_id, MessageState::Read)?;
+            return Ok((ConfirmApplyOutcome::Confirmed, None));
+        }
+        other => other?,
+    };
+    let devices = msgqueue::fanout_devices(peer, msg_id);
+    let addressed = !devices.is_empty()
+        && channel_device_id(recv_channel).is_none_or(|d| devices.iter().any(|v| v == d));
+    if !addressed && !confirm_target_matches_channel(target.as_deref(), recv_channel) {
+        return Ok((ConfirmApplyOutcome::IgnoredWrongDevice, target));
+    }
+    timeline_transition_entry_state(peer, msg_id, MessageState::Read)?;
+    Ok((ConfirmApplyOutcome::Confirmed, target))
+}
+
 pub(super) fn apply_file_peer_confirmation(
     peer: &str,
     file_id: &str,
@@ -638,18 +785,19 @@ fn timeline_emit_item(entry: &TimelineEntry) {
     let len_s = entry.byte_len.to_string();
     let ts_s = entry.ts.to_string();
     let state = timeline_entry_state(entry);
-    emit_marker(
-        "timeline_item",
-        None,
-        &[
-            ("id", entry.id.as_str()),
-            ("dir", entry.direction.as_str()),
-            ("len", len_s.as_str()),
-            ("kind", entry.kind.as_str()),
-            ("ts", ts_s.as_str()),
-            ("state", state.as_str()),
-        ],
-    );
+    let expires_s = entry.expires_at.map(|t| t.to_string());
+    let mut fields = vec![
+        ("id", entry.id.as_str()),
+        ("dir", entry.direction.as_str()),
+        ("len", len_s.as_str()),
+        ("kind", entry.kind.as_str()),
+        ("ts", ts_s.as_str()),
+        ("state", state.as_str()),
+    ];
+    if let Some(v) = expires_s.as_deref() {
+        fields.push(("expires_at", v));
+    }
+    emit_marker("timeline_item", None, &fields);
     if let Some(delivery) = message_delivery_semantic(entry.direction.as_str(), state) {
         if entry.kind == "file" {
             emit_cli_file_delivery_with_device(
@@ -705,6 +853,204 @@ pub fn timeline_show(peer: &str, id: &str) -> CliResult {
         return Err(CliError::code("timeline_item_missing"));
     };
     timeline_emit_item(&entry);
+    // The message is on screen whether or not its receipt can be recorded, so a store failure
+    // here is reported rather than failing the show.
+    if let Err(code) = timeline_mark_displayed(peer, id) {
+        emit_marker("error", Some(code), &[("op", "read_receipt_mark")]);
+    }
+    Ok(())
+}
+
+/// Record that an inbound message was displayed, deciding its read receipt there and then.
+///
+/// Returns the new state, or `None` when there was nothing to decide (already decided, or not a
+/// message a receipt can name). Nothing is sent from here: an owed receipt rides the next pull's
+/// batched flush, which is also where the decision is checked again.
+pub(super) fn timeline_mark_displayed(
+    peer: &str,
+    id: &str,
+) -> Result<Option<ReadReceiptState>, &'static str> {
+    if !channel_label_ok(peer) {
+        return Err("timeline_peer_invalid");
+    }
+    let mut store = timeline_store_load()?;
+    let now = crate::clock::now_unix_s();
+    let Some(entry) = store
+        .peers
+        .get_mut(peer)
+        .and_then(|entries| entries.iter_mut().find(|v| v.id == id))
+        .filter(|v| !v.expired_at(now))
+    else {
+        return Err("timeline_item_missing");
+    };
+    if entry.read_receipt != Some(ReadReceiptState::Unread) {
+        return Ok(None);
+    }
+    let (next, reason) = match read_receipt_allowed(peer, &load_receipt_policy_from_account()) {
+        Ok(()) => (ReadReceiptState::Owed, "displayed"),
+        Err(reason) => (ReadReceiptState::Withheld, reason),
+    };
+    entry.read_receipt = Some(next);
+    timeline_store_save(&store)?;
+    emit_marker(
+        "read_receipt",
+        None,
+        &[("state", next.as_str()), ("reason", reason)],
+    );
+    Ok(Some(next))
+}
+
+/// The ids of every read receipt owed to this peer, oldest first.
+pub(super) fn timeline_read_receipts_owed(peer: &str) -> Result<Vec<String>, &'static str> {
+    let mut owed: Vec<TimelineEntry> = timeline_entries_for_peer(peer)?
+        .into_iter()
+        .filter(|e| e.read_receipt == Some(ReadReceiptState::Owed))
+        .collect();
+    owed.sort_by(|a, b| a.ts.cmp(&b.ts).then_with(|| a.id.cmp(&b.id)));
+    Ok(owed.into_iter().map(|e| e.id).collect())
+}
+
+/// Settle one OWED read receipt as `Sent` or `Withheld`. Anything not owed is left alone.
+pub(super) fn timeline_set_read_receipt(
+    peer: &str,
+    id: &str,
+    to: ReadReceiptState,
+) -> Result<(), &'static str> {
+    let mut store = timeline_store_load()?;
+    let Some(entry) = store
+        .peers
+        .get_mut(peer)
+        .and_then(|entries| entries.iter_mut().find(|v| v.id == id))
+    else {
+        return Ok(());
+    };
+    if entry.read_receipt != Some(ReadReceiptState::Owed) {
+        return Ok(());
+    }
+    entry.read_receipt = Some(to);
+    timeline_store_save(&store)
+}
+
+/// DESIGN F5: append a row to a group's timeline.
+///
+/// An inbound row names its sender in `peer` and arrives RECEIVED. An outbound row is written
+/// once, at fan-out, as CREATED with every member CREATED beside it; each member's copy then
+/// moves on its own through `timeline_group_member_transition`.
+pub(super) fn timeline_append_group_entry(
+    group_id: &str,
+    peer: &str,
+    direction: &str,
+    byte_len: usize,
+    id: &str,
+    members: &[String],
+) -> Result<TimelineEntry, &'static str> {
+    if id.trim().is_empty() {
+        return Err("state_id_invalid");
+    }
+    let state = if direction == "out" {
+        MessageState::Created
+    } else {
+        message_state_transition_allowed(MessageState::Created, MessageState::Received, "in")?;
+        MessageState::Received
+    };
+    let mut store = timeline_store_load()?;
+    let ts = store.next_ts;
+    store.next_ts = store.next_ts.saturating_add(1);
+    let entry = TimelineEntry {
+        id: id.to_string(),
+        peer: peer.to_string(),
+        direction: direction.to_string(),
+        byte_len,
+        kind: "msg".to_string(),
+        ts,
+        target_device_id: None,
+        state: state.as_str().to_string(),
+        status: state.as_status().to_string(),
+        expires_at: None,
+        read_receipt: None,
+        members: members
+            .iter()
+            .map(|m| (m.clone(), MessageState::Created.as_str().to_string()))
+            .collect(),
+    };
+    store
+        .groups
+        .entry(group_id.to_string())
+        .or_default()
+        .push(entry.clone());
+    timeline_store_save(&store)?;
+    Ok(entry)
+}
+
+/// Move one member's copy of an outbound group message, found by the id every copy shares.
+///
+/// The row itself goes CREATED -> SENT with its first member, so "sent" means the relay took
+/// at least one copy; what happened to each copy is the member map's to say. `state_unknown`
+/// when no group row has this member under this id, which is what a one-to-one lookup would
+/// have said too.
+pub(super) fn timeline_group_member_transition(
+    peer: &str,
+    msg_id: &str,
+    to: MessageState,
+) -> Result<(), &'static str> {
+    if msg_id.trim().is_empty() {
+        return Err("state_id_invalid");
+    }
+    let mut store = timeline_store_load()?;
+    let Some(entry) = store
+        .groups
+        .values_mut()
+        .flat_map(|entries| entries.iter_mut())
+        .find(|e| e.direction == "out" && e.id == msg_id && e.members.contains_key(peer))
+    else {
+        return Err("state_unknown");
+    };
+    let member = entry.members.get_mut(peer).ok_or("state_unknown")?;
+    let from = MessageState::parse(member.as_str()).unwrap_or(MessageState::Created);
+    message_state_transition_allowed(from, to, "out")?;
+    *member = to.as_str().to_string();
+    if to == MessageState::Sent && timeline_entry_state(entry) == MessageState::Created {
+        entry.state = to.as_str().to_string();
+        entry.status = to.as_status().to_string();
+    }
+    timeline_store_save(&store)?;
+    emit_message_state_transition(from, to);
+    Ok(())
+}
+
+/// `group timeline`: a group's rows, newest first, each outbound row followed by its members.
+pub fn timeline_group_list(group_id: &str, limit: Option<usize>) -> CliResult {
+    require_unlocked("timeline_group_list")?;
+    if !crate::adversarial::payload::group_id_valid(group_id) {
+        return Err(CliError::code("group_id_invalid"));
+    }
+    let store = timeline_store_load().map_err(|code| CliError::code(code))?;
+    let mut entries = store.groups.get(group_id).cloned().unwrap_or_default();
+    entries.sort_by(|a, b| b.ts.cmp(&a.ts).then_with(|| a.id.cmp(&b.id)));
+    let take_n = limit.unwrap_or(entries.len()).min(entries.len());
+    let count_s = take_n.to_string();
+    emit_marker(
+        "group_timeline",
+        None,
+        &[("count", count_s.as_str()), ("group", group_id)],
+    );
+    for entry in entries.into_iter().take(take_n) {
+        timeline_emit_item(&entry);
+        if entry.direction == "in" {
+            emit_marker(
+                "group_timeline_sender",
+                None,
+                &[("peer", entry.peer.as_str())],
+            );
+        }
+        for (member, state) in entry.members.iter() {
+            emit_marker(
+                "group_member_delivery",
+                None,
+                &[("member", member.as_str()), ("state", state.as_str())],
+            );
+        }
+    }
     Ok(())
 }
 
@@ -1,5 +1,6 @@
 use crate::protocol_state::SendOrigination;
 use super::*;
+use quantumshield_refimpl::kt::{KtEntrySource, KtError, KtLogEndpoint};
 
 pub fn send_execute(args: SendExecuteArgs) -> CliResult {
     require_unlocked("send")?;
@@ -37,15 +38,7 @@ pub fn send_execute(args: SendExecuteArgs) -> CliResult {
                 Ok(v) => v,
                 Err(code) => return Err(CliError::code(code)),
             };
-            if let Err(code) = enforce_cli_send_contact_trust(to.as_str()) {
-                return Err(CliError::code(code));
-            }
-            if let Err(code) = enforce_peer_not_blocked(to.as_str()) {
-                return Err(CliError::code(code));
-            }
-            if let Err(reason) = protocol_active_or_reason_for_send_peer(to.as_str()) {
-                return Err(protocol_inactive_error(reason.as_str()));
-            }
+            enforce_send_gates(to.as_str(), send_fanout(to.as_str()).as_ref())?;
             if let Some(seed) = meta_seed {
                 let seed_s = seed.to_string();
                 emit_marker(
@@ -492,6 +485,9 @@ fn receive_pull_and_write(ctx: &ReceivePullCtx<'_>, max: usize) -> CliResult<Rec
         let resumed = attachment_resume_pending_for_peer(ctx, service_url)?;
         stats.count = stats.count.saturating_add(resumed);
     }
+    // Read receipts join the same end-of-pull batch, so they share its ordering and its jitter
+    // and never leave in a send of their own.
+    queue_owed_read_receipts(ctx, &mut pending_receipts)?;
     flush_batched_receipts(ctx, &mut pending_receipts)?;
     Ok(stats)
 }
@@ -624,6 +620,7 @@ fn receive_pull_rounds(
                     let mut payload = outcome.plaintext.clone();
                     let mut request_receipt = false;
                     let mut request_msg_id = String::new();
+                    let mut request_group: Option<String> = None;
                     // ⚠ NA-0688 C3 — TRANSPARENT FRAMING: UNWRAP BEFORE DISPATCH.
                     //
                     // The data control envelope is FRAMING, not a payload type. It used to be
@@ -652,6 +649,7 @@ fn receive_pull_rounds(
                                 payload = body;
                                 request_receipt = true;
                                 request_msg_id = ctrl.msg_id.clone();
+                                request_group = ctrl.group.clone();
                             }
                         }
                     }
@@ -815,6 +813,8 @@ fn receive_pull_rounds(
                                 emit_cli_receipt_ignored_wrong_device(ctx.from, dev.as_str());
                                 emit_tui_receipt_ignored_wrong_device(ctx.from, dev.as_str());
                             }
+                            // Only message acks are judged per fanned-out device.
+                            Ok((ConfirmApplyOutcome::DeviceRecorded, _)) => {}
                             Err(reason) => {
                                 emit_marker(
                                     "attachment_confirm_reject",
@@ -891,6 +891,8 @@ fn receive_pull_rounds(
                                 emit_cli_receipt_ignored_wrong_device(ctx.from, dev.as_str());
                                 emit_tui_receipt_ignored_wrong_device(ctx.from, dev.as_str());
                             }
+                            // Only message acks are judged per fanned-out device.
+                            Ok((ConfirmApplyOutcome::DeviceRecorded, _)) => {}
                             Err(reason) => {
                                 emit_marker(
                                     "file_confirm_reject",
@@ -974,17 +976,175 @@ fn receive_pull_rounds(
                             )?;
                             continue;
                         }
-                        if class == crate::adversarial::payload::ControlClass::DeliveredAck {
+                        if class == crate::adversarial::payload::ControlClass::DisappearingTimer {
                             commit_unpack_state()?;
+                            // DESIGN F4: the peer's announcement becomes this contact's timer.
+                            // If it cannot apply, the payload is captured under the reason --
+                            // acking it away would leave this side keeping messages the peer
+                            // believes are disappearing, with a marker as the only witness.
+                            let applied =
+                                crate::disappearing::apply_peer_timer(ctx.from, ctrl.expire_s);
+                            if let Ok(secs) = applied {
+                                let secs_s = secs.to_string();
+                                emit_marker(
+                                    "disappearing_timer_set",
+                                    None,
+                                    &[
+                                        ("label", ctx.from),
+                                        ("seconds", secs_s.as_str()),
+                                        ("origin", "peer"),
+                                    ],
+                                );
+                            }
+                            queue_envelope_receipt(
+                                ctx,
+                                pending_receipts,
+                                request_receipt,
+                                request_msg_id.as_str(),
+                            )?;
+                            match applied {
+                                Err(reason) => quarantine_then_ack(
+                                    ctx,
+                                    seen_ids,
+                                    pending_acks,
+                                    item.id.as_str(),
+                                    crate::quarantine::Subclass::Unrecoverable,
+                                    crate::quarantine::ContentKind::InnerPayload,
+                                    reason,
+                                    "transport::receive_pull_and_write/disappearing_timer",
+                                    &payload,
+                                )?,
+                                Ok(_) => {
+                                    record_seen_and_queue_ack(seen_ids, pending_acks, &item.id)?
+                                }
+                            }
+                            continue;
+                        }
+                        if class == crate::adversarial::payload::ControlClass::GroupMembership {
+                            commit_unpack_state()?;
+                            // DESIGN F5: a signed membership change. Same capture rule as the
+                            // timer: a change that does not verify or does not apply is kept
+                            // under its reason, never acked away behind a marker.
+                            let applied = crate::groups::apply_peer_membership(ctx.from, &ctrl);
+                            match &applied {
+                                Ok(change) => {
+                                    let epoch_s = change.epoch.to_string();
+                                    emit_marker(
+                                        "group_membership",
+                                        None,
+                                        &[
+                                            ("op", change.op),
+                                            ("group", change.group_id.as_str()),
+                                            ("epoch", epoch_s.as_str()),
+                                            ("origin", "peer"),
+                                        ],
+                                    );
+                                }
+                                Err(reason) => emit_marker(
+                                    "group_membership_reject",
+                                    Some(reason),
+                                    &[("reason", reason)],
+                                ),
+                            }
+                            queue_envelope_receipt(
+                                ctx,
+                                pending_receipts,
+                                request_receipt,
+                                request_msg_id.as_str(),
+                            )?;
+                            match applied {
+                                Err(reason) => quarantine_then_ack(
+                                    ctx,
+                                    seen_ids,
+                                    pending_acks,
+                                    item.id.as_str(),
+                                    crate::quarantine::Subclass::Unrecoverable,
+                                    crate::quarantine::ContentKind::InnerPayload,
+                                    reason,
+                                    "transport::receive_pull_and_write/group_membership",
+                                    &payload,
+                                )?,
+                                Ok(_) => {
+                                    record_seen_and_queue_ack(seen_ids, pending_acks, &item.id)?
+                                }
+                            }
+                            continue;
+                        }
+                        if class == crate::adversarial::payload::ControlClass::GroupSenderKey {
+                            commit_unpack_state()?;
+                            // DESIGN F5: another member's sender key. Without it that member's
+                            // group messages cannot be opened, so a key that does not apply is
+                            // captured like a membership change, never acked away.
+                            let applied = crate::groups::apply_peer_sender_key(ctx.from, &ctrl);
+                            match &applied {
+                                Ok(op) => emit_marker(
+                                    "group_sender_key",
+                                    None,
+                                    &[
+                                        ("op", *op),
+                                        ("group", ctrl.group.as_deref().unwrap_or("")),
+                                        ("origin", "peer"),
+                                    ],
+                                ),
+                                Err(reason) => emit_marker(
+                                    "group_sender_key_reject",
+                                    Some(reason),
+                                    &[("reason", reason)],
+                                ),
+                            }
+                            queue_envelope_receipt(
+                                ctx,
+                                pending_receipts,
+                                request_receipt,
+                                request_msg_id.as_str(),
+                            )?;
+                            match applied {
+                                Err(reason) => quarantine_then_ack(
+                                    ctx,
+                                    seen_ids,
+                                    pending_acks,
+                                    item.id.as_str(),
+                                    crate::quarantine::Subclass::Unrecoverable,
+                                    crate::quarantine::ContentKind::InnerPayload,
+                                    reason,
+                                    "transport::receive_pull_and_write/group_sender_key",
+                                    &payload,
+                                )?,
+                                Ok(_) => {
+                                    record_seen_and_queue_ack(seen_ids, pending_acks, &item.id)?
+                                }
+                            }
+                            continue;
+                        }
+                        if matches!(
+                            class,
+                            crate::adversarial::payload::ControlClass::DeliveredAck
+                                | crate::adversarial::payload::ControlClass::ReadAck
+                        ) {
+                            commit_unpack_state()?;
+                            // A read ack takes the delivered ack's path end to end -- the same
+                            // device check, the same capture rule -- and differs only in the
+                            // state it moves the row to and the words it reports.
+                            let read =
+                                class == crate::adversarial::payload::ControlClass::ReadAck;
+                            let ack_kind = if read { "read" } else { "delivered" };
                             // ⚠ See D2: the decision is `confirm_capture_reason`'s; these arms
                             // emit. Success here means `Confirmed` and nothing else -- BOTH
                             // non-success arms capture, which is the asymmetry Ruling 9 closed
                             // and Ruling 11.1 made structural.
-                            let outcome = apply_message_peer_confirmation(
-                                ctx.from,
-                                ctrl.msg_id.as_str(),
-                                channel.as_str(),
-                            );
+                            let outcome = if read {
+                                apply_message_peer_read(
+                                    ctx.from,
+                                    ctrl.msg_id.as_str(),
+                                    channel.as_str(),
+                                )
+                            } else {
+                                apply_message_peer_confirmation(
+                                    ctx.from,
+                                    ctrl.msg_id.as_str(),
+                                    channel.as_str(),
+                                )
+                            };
                             let discard_reason = confirm_capture_reason(&outcome);
                             match &outcome {
                                 Ok((ConfirmApplyOutcome::IgnoredWrongDevice, _)) => {
@@ -1002,30 +1162,63 @@ fn receive_pull_rounds(
                                     // in `confirm_capture_reason`, so it cannot go missing at one
                                     // site again.
                                 }
-                                Ok((ConfirmApplyOutcome::Confirmed, target)) => {
-                                    let device = target
-                                        .as_deref()
-                                        .or_else(|| channel_device_id(channel.as_str()));
+                                Ok((ConfirmApplyOutcome::DeviceRecorded, target)) => {
+                                    // DESIGN F1: this device has the message; the message is
+                                    // not DELIVERED until the configured rule says so.
+                                    let dev = target
+                                        .clone()
+                                        .unwrap_or_else(|| channel_device_marker(channel.as_str()));
                                     emit_marker(
                                         "receipt_recv",
                                         None,
-                                        &[("kind", "delivered"), ("msg_id", "<redacted>")],
+                                        &[("kind", ack_kind), ("msg_id", "<redacted>")],
                                     );
                                     emit_marker(
-                                        "delivered_to_peer",
+                                        "device_delivered",
                                         None,
-                                        &[("kind", "delivered"), ("msg_id", "<redacted>")],
+                                        &[("device", dev.as_str()), ("msg_id", "<redacted>")],
                                     );
-                                    emit_cli_delivery_state_with_device(
-                                        ctx.from,
-                                        "peer_confirmed",
-                                        device,
-                                    );
-                                    emit_tui_delivery_state_with_device(
-                                        ctx.from,
-                                        "peer_confirmed",
-                                        device,
+                                }
+                                Ok((ConfirmApplyOutcome::Confirmed, target)) => {
+                                    let device = target
+                                        .as_deref()
+                                        .or_else(|| channel_device_id(channel.as_str()));
+                                    emit_marker(
+                                        "receipt_recv",
+                                        None,
+                                        &[("kind", ack_kind), ("msg_id", "<redacted>")],
                                     );
+                                    let delivery = if read {
+                                        // The queue row follows the timeline row. It may
+                                        // already be gone (discarded, expired), which is fine.
+                                        if let Err(code) = msgqueue::mark_read(
+                                            ctx.cfg_dir,
+                                            ctx.cfg_source,
+                                            ctx.from,
+                                            ctrl.msg_id.as_str(),
+                                        ) {
+                                            emit_marker(
+                                                "error",
+                                                Some(code),
+                                                &[("op", "msgqueue_mark_read")],
+                                            );
+                                        }
+                                        emit_marker(
+                                            "read_by_peer",
+                                            None,
+                                            &[("kind", "read"), ("msg_id", "<redacted>")],
+                                        );
+                                        "peer_read"
+                                    } else {
+                                        emit_marker(
+                                            "delivered_to_peer",
+                                            None,
+                                            &[("kind", "delivered"), ("msg_id", "<redacted>")],
+                                        );
+                                        "peer_confirmed"
+                                    };
+                                    emit_cli_delivery_state_with_device(ctx.from, delivery, device);
+                                    emit_tui_delivery_state_with_device(ctx.from, delivery, device);
                                 }
                                 Err(reason) => {
                                     emit_message_state_reject(reason);
@@ -1047,7 +1240,11 @@ fn receive_pull_rounds(
                                     crate::quarantine::Subclass::Unrecoverable,
                                     crate::quarantine::ContentKind::InnerPayload,
                                     reason,
-                                    "transport::receive_pull_and_write/delivered_ack",
+                                    if read {
+                                        "transport::receive_pull_and_write/read_ack"
+                                    } else {
+                                        "transport::receive_pull_and_write/delivered_ack"
+                                    },
                                     &payload,
                                 )?,
                                 None => {
@@ -1067,6 +1264,64 @@ fn receive_pull_rounds(
                         // untouched on purpose; it falls through to the generic path exactly as
                         // it did before this lane.
                     }
+                    // DESIGN F5: a group copy is only accepted from a current member of a group
+                    // this side holds, and in a sender-key group only once it opens under that
+                    // member's chain. Anything else is captured, and NOT receipted -- a
+                    // delivered ack would tell the sender a stranger's message was taken in.
+                    if let Some(group_id) = request_group.as_deref() {
+                        let accepted =
+                            crate::groups::group_message_accept(group_id, ctx.from, &payload);
+                        // A retried copy of a sealed message already taken in cannot open twice;
+                        // it is the duplicate the msg_id dedup below exists for, and is re-acked
+                        // the same way rather than captured.
+                        let retried = accepted == Err(crate::groups::GROUP_SENDER_KEY_REPLAY)
+                            && !request_msg_id.is_empty()
+                            && msgqueue::inbound_already_seen(
+                                ctx.cfg_dir,
+                                ctx.from,
+                                &request_msg_id,
+                            ) == Ok(true);
+                        if retried {
+                            commit_unpack_state()?;
+                            emit_marker(
+                                "recv_dup_msg_id_skipped",
+                                None,
+                                &[("msg_id", "<redacted>")],
+                            );
+                            queue_envelope_receipt(
+                                ctx,
+                                pending_receipts,
+                                request_receipt,
+                                request_msg_id.as_str(),
+                            )?;
+                            record_seen_and_queue_ack(seen_ids, pending_acks, &item.id)?;
+                            continue;
+                        }
+                        match accepted {
+                            Ok(Some(plaintext)) => payload = plaintext,
+                            Ok(None) => {}
+                            Err(reason) => {
+                                commit_unpack_state()?;
+                                emit_marker(
+                                    "group_msg_reject",
+                                    Some(reason),
+                                    &[("reason", reason)],
+                                );
+                                quarantine_then_ack(
+                                    ctx,
+                                    seen_ids,
+                                    pending_acks,
+                                    item.id.as_str(),
+                                    crate::quarantine::Subclass::Unrecoverable,
+                                    crate::quarantine::ContentKind::InnerPayload,
+                                    reason,
+                                    "transport::receive_pull_and_write/group_message",
+                                    &payload,
+                                )?;
+                                continue;
+                            }
+                        }
+                    }
                     commit_unpack_state()?;
                     stats.count = stats.count.saturating_add(1);
                     stats.bytes = stats.bytes.saturating_add(envelope_len);
@@ -1152,18 +1407,29 @@ fn receive_pull_rounds(
                     // holds the message (it is not acked at the lease layer either), so it
                     // is redelivered and tried again -- visibly stuck rather than silently
                     // claimed as delivered.
-                    let stored = timeline_append_entry(
-                        ctx.from,
-                        "in",
-                        payload.len(),
-                        "msg",
-                        MessageState::Received,
-                        if request_msg_id.is_empty() {
-                            None
-                        } else {
-                            Some(request_msg_id.as_str())
-                        },
-                    );
+                    let stored = match request_group.as_deref() {
+                        // DESIGN F5: onto the group's timeline, under its sender.
+                        Some(group_id) => timeline_append_group_entry(
+                            group_id,
+                            ctx.from,
+                            "in",
+                            payload.len(),
+                            request_msg_id.as_str(),
+                            &[],
+                        ),
+                        None => timeline_append_entry(
+                            ctx.from,
+                            "in",
+                            payload.len(),
+                            "msg",
+                            MessageState::Received,
+                            if request_msg_id.is_empty() {
+                                None
+                            } else {
+                                Some(request_msg_id.as_str())
+                            },
+                        ),
+                    };
                     if let Err(code) = stored {
                         emit_message_state_reject(code);
                         emit_marker("error", Some(code), &[("op", "timeline_receive_ingest")]);
@@ -1362,6 +1628,8 @@ fn confirm_capture_reason(
     match outcome {
         Ok((ConfirmApplyOutcome::Confirmed, _)) => None,
         Ok((ConfirmApplyOutcome::IgnoredWrongDevice, _)) => Some("ignored_wrong_device"),
+        // Applied: the device's ack is on the row, only the message's state waits.
+        Ok((ConfirmApplyOutcome::DeviceRecorded, _)) => None,
         Err(reason) => Some(reason),
     }
 }
@@ -1390,7 +1658,13 @@ fn control_class_capture_reason(
         ControlClass::UnknownControl => Some("unknown_control_type"),
         // Known to this build, or not ours at all: each is handled on its own path and none of
         // them is a discard. Capturing here would store ordinary traffic.
-        ControlClass::DeliveredAck | ControlClass::DataEnvelope | ControlClass::NotControl => None,
+        ControlClass::DeliveredAck
+        | ControlClass::DataEnvelope
+        | ControlClass::ReadAck
+        | ControlClass::DisappearingTimer
+        | ControlClass::GroupMembership
+        | ControlClass::GroupSenderKey
+        | ControlClass::NotControl => None,
     }
 }
 
@@ -1871,6 +2145,35 @@ fn write_http_response(stream: &mut TcpStream, status: u16, content_type: &str,
     }
 }
 
+/// DESIGN F1 multi-device: a contact label reaches every device the contact trusts, each
+/// through its own session, and the fan-out gates each device itself -- an unreachable primary
+/// is skipped like any other device. `None` when `to` names one device (`alias#device`, which
+/// reaches only it) or when no device is reachable.
+fn send_fanout(to: &str) -> Option<DeviceFanout> {
+    if channel_device_id(to).is_some() {
+        return None;
+    }
+    contact_fanout_devices(to)
+        .ok()
+        .filter(|f| !f.devices.is_empty())
+}
+
+/// The gates every send passes. With a fan-out only the block applies here; without one the
+/// single-device gates run, so a send that reaches nothing is refused with the primary's
+/// reason, as it always has been.
+fn enforce_send_gates(to: &str, fanout: Option<&DeviceFanout>) -> CliResult {
+    if fanout.is_none() {
+        enforce_cli_send_contact_trust(to).map_err(CliError::code)?;
+    }
+    enforce_peer_not_blocked(to).map_err(CliError::code)?;
+    if fanout.is_none() {
+        if let Err(reason) = protocol_active_or_reason_for_send_peer(to) {
+            return Err(protocol_inactive_error(reason.as_str()));
+        }
+    }
+    Ok(())
+}
+
 pub fn relay_send(
     to: &str,
     file: &Path,
@@ -1880,15 +2183,8 @@ pub fn relay_send(
     meta_seed: Option<u64>,
     receipt: Option<ReceiptKind>,
 ) -> CliResult {
-    if let Err(code) = enforce_cli_send_contact_trust(to) {
-        return Err(CliError::code(code));
-    }
-    if let Err(code) = enforce_peer_not_blocked(to) {
-        return Err(CliError::code(code));
-    }
-    if let Err(reason) = protocol_active_or_reason_for_send_peer(to) {
-        return Err(protocol_inactive_error(reason.as_str()));
-    }
+    let fanout = send_fanout(to);
+    enforce_send_gates(to, fanout.as_ref())?;
     let payload = match fs::read(file) {
         Ok(v) => v,
         Err(_) => return Err(CliError::code("relay_payload_read_failed")),
@@ -1911,7 +2207,45 @@ pub fn relay_send(
         Err(e) => return Err(cli_err(e)),
     };
     let now = msgqueue::now_unix_s();
-    let rec = msgqueue::enqueue_at(&dir, source, to, payload, now).map_err(CliError::code)?;
+    // DESIGN F4: the contact's disappearing timer is stamped in the same write as the row.
+    let expires_at = contact_disappearing_timer(to).map(|secs| now.saturating_add(secs));
+    let rec = if let Some(fanout) = fanout {
+        for (device, reason) in fanout.skipped.iter() {
+            let dev = short_device_marker(device);
+            emit_marker(
+                "device_fanout_skipped",
+                None,
+                &[("device", dev.as_str()), ("reason", reason.as_str())],
+            );
+        }
+        let rule = stored_delivery_rule();
+        let rows = msgqueue::enqueue_fanout_at(
+            &dir,
+            source,
+            to,
+            &fanout.devices,
+            rule,
+            fanout.route.as_deref(),
+            payload,
+            now,
+            expires_at,
+        )
+        .map_err(CliError::code)?;
+        if rows.len() > 1 {
+            let n = rows.len().to_string();
+            emit_marker(
+                "device_fanout",
+                None,
+                &[("devices", n.as_str()), ("rule", rule.as_str())],
+            );
+        }
+        rows.into_iter()
+            .next()
+            .ok_or_else(|| CliError::code(msgqueue::MSGQUEUE_WRITE_FAILED))?
+    } else {
+        msgqueue::enqueue_expiring_at(&dir, source, to, payload, now, expires_at)
+            .map_err(CliError::code)?
+    };
     let queued_len = rec.body.len().to_string();
     emit_marker(
         "msgqueue_enqueued",
@@ -4352,6 +4686,10 @@ pub(crate) struct RelayMessageSender<'a> {
     pre_envelopes: Vec<Vec<u8>>,
     /// The routed device, captured at pack time for the timeline entry at commit.
     device_id: Option<String>,
+    /// `(peer, msg_id)` of the record the three fields above were captured for. One drain
+    /// walks many rows -- every contact, and since DESIGN F1 every device copy -- so a row
+    /// REPLAYED after another row's pack must not pick up that row's outcome.
+    packed_for: Option<(String, String)>,
     /// ⚠ METADATA-PRIVACY CONFIG, threaded through to `qsp_pack`.
     ///
     /// These four were silently DROPPED when `qsc send` was rewired around
@@ -4387,12 +4725,20 @@ impl<'a> RelayMessageSender<'a> {
         self.last_limit
     }
 
+    /// The captured pack outcome belongs to `rec`, which was packed by this sender.
+    fn packed_this(&self, rec: &msgqueue::QueuedMessage) -> bool {
+        self.packed_for
+            .as_ref()
+            .is_some_and(|(peer, id)| *peer == rec.peer && *id == rec.msg_id)
+    }
+
     pub(crate) fn new(relay: &'a str) -> Self {
         Self {
             relay,
             trigger: None,
             pre_envelopes: Vec::new(),
             device_id: None,
+            packed_for: None,
             pad_cfg: None,
             bucket_max: None,
             meta_seed: None,
@@ -4481,7 +4827,7 @@ impl<'a> msgqueue::MessageSender for RelayMessageSender<'a> {
         // record's device state carries it, and `resolve_send_routing_target` already
         // refuses with `device_revoked`. That makes the one permanent state deterministic
         // and testable without a hostile relay.
-        let routing = match resolve_send_routing_target(rec.peer.as_str()) {
+        let routing = match resolve_send_routing_target(rec.route_channel()) {
             Ok(v) => v,
             Err("device_revoked") => return Err(msgqueue::AttemptResult::FailPermanent),
             // Anything else about routing is a local configuration problem that can heal.
@@ -4502,11 +4848,21 @@ impl<'a> msgqueue::MessageSender for RelayMessageSender<'a> {
         // Wrap in the data control envelope ONLY when a receipt was explicitly requested.
         // The envelope is what carries the `msg_id` an ack echoes back, so no request means
         // no envelope, no ack, and a byte-for-byte pre-NA-0682 wire.
-        let wire_body = match self.receipt_kind {
+        //
+        // ⚠ DESIGN F5: a group copy is ALWAYS wrapped. The envelope is the only thing that
+        // tells the member this is a group message, and the member's delivery ack is the only
+        // per-member signal the sender's group timeline ever gets.
+        let group = rec.group_id.as_deref();
+        let receipt_kind = match group {
+            Some(_) => Some(self.receipt_kind.unwrap_or(ReceiptKind::Delivered)),
+            None => self.receipt_kind,
+        };
+        let wire_body = match receipt_kind {
             Some(kind) => match crate::encode_data_payload_with_id(
                 rec.body.clone(),
                 kind,
                 rec.msg_id.as_str(),
+                group,
             ) {
                 Ok(v) => v,
                 Err(_) => return Err(msgqueue::AttemptResult::Retry),
@@ -4578,6 +4934,7 @@ impl<'a> msgqueue::MessageSender for RelayMessageSender<'a> {
                 self.trigger = Some(v.trigger);
                 self.pre_envelopes = v.pre_envelopes.clone();
                 self.device_id = Some(routing.device_id.clone());
+                self.packed_for = Some((rec.peer.clone(), rec.msg_id.clone()));
                 Ok((v.envelope, v.next_state.snapshot_bytes(), routing.channel))
             }
             Err(err) => {
@@ -4603,7 +4960,7 @@ impl<'a> msgqueue::MessageSender for RelayMessageSender<'a> {
             // Unreachable by construction: the drain only pushes what it packed.
             return Err(msgqueue::AttemptResult::Retry);
         };
-        let routing = match resolve_send_routing_target(rec.peer.as_str()) {
+        let routing = match resolve_send_routing_target(rec.route_channel()) {
             Ok(v) => v,
             Err("device_revoked") => return Err(msgqueue::AttemptResult::FailPermanent),
             Err(_) => return Err(msgqueue::AttemptResult::Retry),
@@ -4652,7 +5009,12 @@ impl<'a> msgqueue::MessageSender for RelayMessageSender<'a> {
         // NA-0624: SCKA advertisements go first, in order. Their secret material is already
         // durable and the chain advance rides in the record's next_state, so a failure here
         // is recovered by the ordinary retry.
-        for pre in self.pre_envelopes.iter() {
+        let pre_envelopes = if self.packed_this(rec) {
+            self.pre_envelopes.clone()
+        } else {
+            Vec::new()
+        };
+        for pre in pre_envelopes.iter() {
             if let Err(f) =
                 relay_inbox_push_classified(self.relay, routing.route_token.as_str(), pre)
             {
@@ -4667,7 +5029,7 @@ impl<'a> msgqueue::MessageSender for RelayMessageSender<'a> {
             Ok(()) => {
                 emit_marker("relay_event", None, &[("action", "deliver")]);
                 emit_cli_delivery_state_with_device(
-                    rec.peer.as_str(),
+                    routing.peer_alias.as_str(),
                     "accepted_by_relay",
                     Some(routing.device_id.as_str()),
                 );
@@ -4714,7 +5076,8 @@ impl<'a> msgqueue::MessageSender for RelayMessageSender<'a> {
         // decision (the D-1336 contention contract).
         let (dir, source) = config_dir().map_err(|_| "send_commit_write_failed")?;
         let _lock = lock_store_exclusive(&dir, source).map_err(crate::vault::store_err_marker)?;
-        let stored = match self.trigger.as_ref() {
+        let packed_this = self.packed_this(rec);
+        let stored = match self.trigger.as_ref().filter(|_| packed_this) {
             Some(trig) => qsp_session_store_with_trigger(channel.as_str(), &st, trig),
             None => qsp_session_store(channel.as_str(), &st),
         };
@@ -4736,18 +5099,49 @@ impl<'a> msgqueue::MessageSender for RelayMessageSender<'a> {
         // `timeline_written_on_send_commit_only` keeps holding. The O1 row lives in the
         // message queue (a separate store, per F4); the timeline remains the record of what
         // was actually SENT. Two stores, two meanings, neither pretending to be the other.
-        if let Err(code) = timeline_append_entry_for_target(
-            rec.peer.as_str(),
-            "out",
-            rec.body.len(),
-            "file",
-            MessageState::Sent,
-            // Only carry the id when an ack could actually reference it (pre-NA-0682 shape).
-            self.receipt_kind.map(|_| rec.msg_id.as_str()),
-            self.device_id.as_deref(),
-        ) {
-            emit_message_state_reject(code);
-            emit_marker("error", Some(code), &[("op", "timeline_send_ingest")]);
+        //
+        // DESIGN F5: a group copy moves its member on the group row written at fan-out
+        // instead of adding a one-to-one row of its own.
+        if rec.device.is_some() {
+            // DESIGN F1: a device copy adds nothing. The contact's own row stands for the
+            // message and collects every device's ack.
+        } else if rec.group_id.is_some() {
+            if let Err(code) = timeline_group_member_transition(
+                rec.peer.as_str(),
+                rec.msg_id.as_str(),
+                MessageState::Sent,
+            ) {
+                emit_message_state_reject(code);
+                emit_marker("error", Some(code), &[("op", "timeline_send_ingest")]);
+            }
+        } else {
+            match timeline_append_entry_for_target(
+                rec.peer.as_str(),
+                "out",
+                rec.body.len(),
+                "file",
+                MessageState::Sent,
+                // Only carry the id when an ack could actually reference it (pre-NA-0682 shape).
+                self.receipt_kind.map(|_| rec.msg_id.as_str()),
+                self.device_id.as_deref().filter(|_| packed_this),
+            ) {
+                Err(code) => {
+                    emit_message_state_reject(code);
+                    emit_marker("error", Some(code), &[("op", "timeline_send_ingest")]);
+                }
+                // Another device met the delivery rule while this row was still retrying.
+                Ok(entry) if !rec.devices.is_empty() && rec.delivery_met() => {
+                    if let Err(code) = timeline_transition_entry_state(
+                        rec.peer.as_str(),
+                        entry.id.as_str(),
+                        MessageState::Delivered,
+                    ) {
+                        emit_message_state_reject(code);
+                        emit_marker("error", Some(code), &[("op", "timeline_send_ingest")]);
+                    }
+                }
+                Ok(_) => {}
+            }
         }
         print_marker("send_attempt", &[("ok", "true")]);
         let seq_s = next_seq.to_string();
@@ -4756,6 +5150,82 @@ impl<'a> msgqueue::MessageSender for RelayMessageSender<'a> {
     }
 }
 
+// ---------------------------------------------------------------------------
+// KT log client for `identity kt-monitor`. The log speaks the `qsl-kt-log` routes
+// (tools/kt_log): JSON bodies, every blob lowercase hex of its DOC-CAN-008 encoding.
+// Sockets stay here (D616 F4); what the bytes mean is `kt_monitor`'s business.
+// ---------------------------------------------------------------------------
+
+const KT_LOG_TIMEOUT_SECS: u64 = 10;
+
+pub struct KtHttpLog {
+    base: String,
+    client: HttpClient,
+}
+
+impl KtHttpLog {
+    /// Same URL rules as a relay endpoint: https, or http to loopback only.
+    pub fn new(log_base: &str) -> Result<Self, &'static str> {
+        let base = normalize_relay_endpoint(log_base)?;
+        let client = match relay_http_client() {
+            Ok(v) => v,
+            Err(RelayHttpClientError::CaFile(code)) => return Err(code),
+            Err(RelayHttpClientError::Build) => return Err("kt_log_client_failed"),
+        };
+        Ok(Self { base, client })
+    }
+
+    fn get_json(&self, path: &str) -> Result<serde_json::Value, KtError> {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.base, path))
+            .timeout(Duration::from_secs(KT_LOG_TIMEOUT_SECS))
+            .send()
+            .map_err(|_| KtError::log_fetch_failed())?;
+        if resp.status() != HttpStatus::OK {
+            return Err(KtError::log_fetch_failed());
+        }
+        resp.json::<serde_json::Value>()
+            .map_err(|_| KtError::log_fetch_failed())
+    }
+
+    fn hex_field(v: &serde_json::Value) -> Result<Vec<u8>, KtError> {
+        v.as_str()
+            .and_then(|s| hex_decode(s).ok())
+            .ok_or_else(KtError::log_fetch_failed)
+    }
+}
+
+impl KtLogEndpoint for KtHttpLog {
+    fn get_sth(&self) -> Result<Vec<u8>, KtError> {
+        Self::hex_field(&self.get_json("/v1/sth")?["sth"])
+    }
+
+    fn get_consistency_proof(
+        &self,
+        from_tree_size: u64,
+        to_tree_size: u64,
+    ) -> Result<Vec<u8>, KtError> {
+        let v = self.get_json(&format!(
+            "/v1/consistency?from={}&to={}",
+            from_tree_size, to_tree_size
+        ))?;
+        Self::hex_field(&v["proof"])
+    }
+}
+
+impl KtEntrySource for KtHttpLog {
+    fn get_entries(&self, start: u64, end: u64) -> Result<Vec<Vec<u8>>, KtError> {
+        let v = self.get_json(&format!("/v1/entries?start={}&end={}", start, end))?;
+        v["entries"]
+            .as_array()
+            .ok_or_else(KtError::log_fetch_failed)?
+            .iter()
+            .map(Self::hex_field)
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod receipt_sender_default_tests {
     use super::{ReceiptKind, RelayMessageSender};
@@ -5017,8 +5487,8 @@ mod relay_push_diagnostic_tests {
 // So the decision is pinned HERE, where every arm IS reachable and each is trivially red-capable:
 // delete any one line of `confirm_capture_reason` and exactly one of these goes red.
 //
-// ⚠ THE TABLE IS EXHAUSTIVE OVER `ConfirmApplyOutcome`, which has exactly two variants, plus the
-// `Err` case -- three rows, closed. A new variant makes the helper's `match` fail to compile, so
+// ⚠ THE TABLE IS EXHAUSTIVE OVER `ConfirmApplyOutcome`, which has exactly three variants, plus
+// the `Err` case -- four rows, closed. A new variant makes the helper's `match` fail to compile, so
 // this table cannot silently fall behind the enum.
 #[cfg(test)]
 mod confirm_capture_reason_tests {
@@ -5043,6 +5513,14 @@ mod confirm_capture_reason_tests {
             None,
             "the decision must not depend on whether a target device was resolved"
         );
+        // DESIGN F1: one device of a fanned-out message confirming is an applied confirm too,
+        // even though the message waits for the delivery rule.
+        let recorded = Ok((ConfirmApplyOutcome::DeviceRecorded, Some("d2".to_string())));
+        assert_eq!(
+            confirm_capture_reason(&recorded),
+            None,
+            "a recorded per-device ack must not be quarantined"
+        );
     }
 
     /// POSITIVE 1 — the wrong-device ignore. ⚠ This is the arm that was MISSING at D4 while D2 and
@@ -5101,8 +5579,8 @@ mod confirm_capture_reason_tests {
 // ⚠ WHY A UNIT TABLE AND NOT AN END-TO-END ARM -- THE SAME REASON AS D2/D3/D4, AND IT IS THE
 // SITE'S PURPOSE RATHER THAN A GAP. `UnknownControl` needs our namespace marker PLUS either an
 // unknown `t`/`kind` pair or a version above CTRL_VERSION_MAX. A sender of THIS build emits
-// neither: same binary, same version ceiling, same three known shapes. There is no env override,
-// and all three ReceiptControlPayload builders are crate-private, so no integration test can craft
+// neither: same binary, same version ceiling, same four known shapes. There is no env override,
+// and all four ReceiptControlPayload builders are crate-private, so no integration test can craft
 // one either. D5's capture is the FORWARD-COMPAT WITNESS -- only a FUTURE build can trigger it.
 //
 // ⚠ THE HONEST SHAPE OF THE WHOLE CAPTURE SURFACE, recorded above the test names because it is the
@@ -5112,7 +5590,7 @@ mod confirm_capture_reason_tests {
 // trigger is OUR OWN CRASH rather than the peer's behaviour.
 //
 // What a payload IS is `classify_control`'s call and is exhaustively pinned by NA-0682's own tests
-// (all four classes, both UnknownControl routes, and the silent-loss guard). What the SITE DOES
+// (every class, both UnknownControl routes, and the silent-loss guard). What the SITE DOES
 // about it is pinned here.
 #[cfg(test)]
 mod control_class_capture_tests {
@@ -5140,6 +5618,10 @@ mod control_class_capture_tests {
         for class in [
             ControlClass::DeliveredAck,
             ControlClass::DataEnvelope,
+            ControlClass::ReadAck,
+            ControlClass::DisappearingTimer,
+            ControlClass::GroupMembership,
+            ControlClass::GroupSenderKey,
             ControlClass::NotControl,
         ] {
             assert_eq!(
@@ -5157,6 +5639,10 @@ mod control_class_capture_tests {
         let all = [
             ControlClass::DeliveredAck,
             ControlClass::DataEnvelope,
+            ControlClass::ReadAck,
+            ControlClass::DisappearingTimer,
+            ControlClass::GroupMembership,
+            ControlClass::GroupSenderKey,
             ControlClass::UnknownControl,
             ControlClass::NotControl,
         ];
@@ -5166,7 +5652,7 @@ mod control_class_capture_tests {
             .count();
         assert_eq!(
             captured, 1,
-            "exactly one of the four classes may reach the D5 capture"
+            "exactly one of the eight classes may reach the D5 capture"
         );
     }
 }
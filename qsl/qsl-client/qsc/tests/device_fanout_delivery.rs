@@ -0,0 +1,321 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const ROUTE_TOKEN_BOB: &str = "route_token_bob_abcdefghijklmnopqr";
+const ROUTE_TOKEN_BOB_2: &str = "route_token_bob2_abcdefghijklmnopq";
+
+fn safe_test_root() -> PathBuf {
+    let root = if let Ok(v) = std::env::var("QSC_TEST_ROOT") {
+        PathBuf::from(v)
+    } else if let Ok(v) = std::env::var("CARGO_TARGET_DIR") {
+        PathBuf::from(v)
+    } else {
+        PathBuf::from("target")
+    };
+    let root = root.join("qsc-test-tmp");
+    ensure_dir_700(&root);
+    root
+}
+
+fn ensure_dir_700(path: &Path) {
+    let _ = fs::create_dir_all(path);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o700));
+    }
+}
+
+fn create_dir_700(path: &Path) {
+    let _ = fs::remove_dir_all(path);
+    ensure_dir_700(path);
+}
+
+fn output_text(out: &std::process::Output) -> String {
+    let mut s = String::from_utf8_lossy(&out.stdout).to_string();
+    s.push_str(&String::from_utf8_lossy(&out.stderr));
+    s
+}
+
+fn qsc_base(cfg: &Path) -> Command {
+    let mut cmd = common::qsc_std_command();
+    cmd.env("QSC_CONFIG_DIR", cfg)
+        .env("QSC_MARK_FORMAT", "plain")
+        .env("QSC_QSP_SEED", "1")
+        .env("QSC_ALLOW_SEED_FALLBACK", "1")
+        .env("QSC_UNSAFE_TEST_SEED_FALLBACK", "1");
+    cmd
+}
+
+fn run_ok(cfg: &Path, args: &[&str]) -> String {
+    let out = qsc_base(cfg).args(args).output().expect("run qsc");
+    let text = output_text(&out);
+    assert!(out.status.success(), "command failed {args:?}\n{text}");
+    text
+}
+
+fn device_ids(cfg: &Path) -> Vec<String> {
+    let list = run_ok(cfg, &["contacts", "device", "list", "--label", "bob"]);
+    list.lines()
+        .filter(|l| l.starts_with("device="))
+        .filter_map(|l| l.split_whitespace().find_map(|t| t.strip_prefix("device=")))
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Contact `bob` with two trusted devices, each on its own route, the first one primary.
+/// Returns `(primary, second)`; the ids derive from the fingerprints, so every side agrees.
+fn add_bob_with_two_devices(cfg: &Path) -> (String, String) {
+    run_ok(
+        cfg,
+        &[
+            "contacts",
+            "add",
+            "--label",
+            "bob",
+            "--fp",
+            "fp-test",
+            "--route-token",
+            ROUTE_TOKEN_BOB,
+        ],
+    );
+    let primary = device_ids(cfg).pop().expect("the contact's own device");
+    run_ok(
+        cfg,
+        &[
+            "contacts",
+            "device",
+            "add",
+            "--label",
+            "bob",
+            "--fp",
+            "fp-test-second",
+            "--route-token",
+            ROUTE_TOKEN_BOB_2,
+        ],
+    );
+    let second = device_ids(cfg)
+        .into_iter()
+        .find(|d| *d != primary)
+        .expect("the added device");
+    run_ok(
+        cfg,
+        &[
+            "contacts",
+            "device",
+            "trust",
+            "--label",
+            "bob",
+            "--device",
+            second.as_str(),
+            "--confirm",
+        ],
+    );
+    run_ok(
+        cfg,
+        &[
+            "contacts",
+            "device",
+            "primary",
+            "set",
+            "--label",
+            "bob",
+            "--device",
+            primary.as_str(),
+            "--confirm",
+        ],
+    );
+    (primary, second)
+}
+
+fn timeline_first_item_state(text: &str) -> Option<String> {
+    for line in text.lines() {
+        if !line.contains("event=timeline_item") {
+            continue;
+        }
+        for part in line.split_whitespace() {
+            if let Some(v) = part.strip_prefix("state=") {
+                return Some(common::scraped_marker_value("state", v));
+            }
+        }
+    }
+    None
+}
+
+/// Alice and bob's two devices, each with its own config. Bob's second device knows alice as
+/// `bob#<second>`: that label is the per-device session alice seals its copy under, which is
+/// what a real second device shares with her.
+struct Devices {
+    _server: common::InboxTestServer,
+    relay: String,
+    alice_cfg: PathBuf,
+    bob_cfg: PathBuf,
+    bob2_cfg: PathBuf,
+    base: PathBuf,
+    second: String,
+}
+
+fn setup(tag: &str, rule: &str) -> Devices {
+    let server = common::start_inbox_server(1024 * 1024, 32);
+    let base = safe_test_root().join(format!("{tag}_{}", std::process::id()));
+    create_dir_700(&base);
+    let alice_cfg = base.join("alice_cfg");
+    let bob_cfg = base.join("bob_cfg");
+    let bob2_cfg = base.join("bob2_cfg");
+    let mut second = String::new();
+    for cfg in [&alice_cfg, &bob_cfg, &bob2_cfg] {
+        create_dir_700(cfg);
+        common::init_mock_vault(cfg);
+        second = add_bob_with_two_devices(cfg).1;
+    }
+    run_ok(&alice_cfg, &["config", "set", "delivery-rule", rule]);
+    Devices {
+        relay: server.base_url().to_string(),
+        _server: server,
+        alice_cfg,
+        bob_cfg,
+        bob2_cfg,
+        base,
+        second,
+    }
+}
+
+fn receive(d: &Devices, cfg: &Path, mailbox: &str, from: &str, out: &str) -> String {
+    let out = d.base.join(out);
+    ensure_dir_700(&out);
+    run_ok(
+        cfg,
+        &[
+            "receive",
+            "--transport",
+            "relay",
+            "--relay",
+            d.relay.as_str(),
+            "--mailbox",
+            mailbox,
+            "--from",
+            from,
+            "--max",
+            "4",
+            "--out",
+            out.to_str().expect("path"),
+        ],
+    )
+}
+
+/// Alice sends once; each of bob's devices takes its own copy and queues its delivery ack
+/// on its own route. Nothing has reached alice yet when this returns.
+fn send_to_both_devices(d: &Devices, rule: &str) {
+    let payload = d.base.join("msg.bin");
+    fs::write(&payload, b"device-fanout-e2e").expect("write msg");
+    let sent = run_ok(
+        &d.alice_cfg,
+        &[
+            "send",
+            "--transport",
+            "relay",
+            "--relay",
+            d.relay.as_str(),
+            "--to",
+            "bob",
+            "--file",
+            payload.to_str().expect("path"),
+            "--receipt",
+            "delivered",
+        ],
+    );
+    let fanout = format!("event=device_fanout devices=2 rule={rule}");
+    assert!(sent.contains(fanout.as_str()), "{sent}");
+
+    let primary = receive(d, &d.bob_cfg, ROUTE_TOKEN_BOB, "bob", "bob_out");
+    assert!(
+        primary.contains("event=receipt_send kind=delivered"),
+        "{primary}"
+    );
+    let channel = format!("bob#{}", d.second);
+    let second = receive(d, &d.bob2_cfg, ROUTE_TOKEN_BOB_2, &channel, "bob2_out");
+    assert!(
+        second.contains("event=receipt_send kind=delivered"),
+        "{second}"
+    );
+    for out in ["bob_out", "bob2_out"] {
+        let got = fs::read(d.base.join(out).join("recv_1.bin")).expect("each device got a copy");
+        assert_eq!(got, b"device-fanout-e2e");
+    }
+}
+
+/// Alice pulls one device's ack. She names the contact, not the device: which device it was
+/// is decided by the session the ack opens under.
+fn alice_pulls(d: &Devices, mailbox: &str) -> String {
+    receive(d, &d.alice_cfg, mailbox, "bob", "alice_out")
+}
+
+fn alice_row_state(d: &Devices) -> String {
+    let list = run_ok(&d.alice_cfg, &["timeline", "list", "--peer", "bob"]);
+    timeline_first_item_state(&list).unwrap_or_else(|| panic!("no timeline item: {list}"))
+}
+
+fn assert_recorded_not_delivered(text: &str) {
+    assert!(text.contains("event=device_delivered"), "{text}");
+    assert!(
+        !text.contains("QSC_DELIVERY state=peer_confirmed"),
+        "{text}"
+    );
+}
+
+#[test]
+fn primary_rule_waits_for_the_primary_device() {
+    let d = setup("device_fanout_primary", "primary");
+    send_to_both_devices(&d, "primary");
+
+    let second = alice_pulls(&d, ROUTE_TOKEN_BOB_2);
+    assert_recorded_not_delivered(&second);
+    let device = format!("event=device_delivered device={}", d.second);
+    assert!(second.contains(device.as_str()), "{second}");
+    assert_eq!(alice_row_state(&d), "SENT");
+
+    let primary = alice_pulls(&d, ROUTE_TOKEN_BOB);
+    assert!(
+        primary.contains("QSC_DELIVERY state=peer_confirmed"),
+        "{primary}"
+    );
+    assert_eq!(alice_row_state(&d), "DELIVERED");
+}
+
+#[test]
+fn all_rule_waits_for_every_device() {
+    let d = setup("device_fanout_all", "all");
+    send_to_both_devices(&d, "all");
+
+    let primary = alice_pulls(&d, ROUTE_TOKEN_BOB);
+    assert_recorded_not_delivered(&primary);
+    assert_eq!(alice_row_state(&d), "SENT");
+
+    let second = alice_pulls(&d, ROUTE_TOKEN_BOB_2);
+    assert!(
+        second.contains("QSC_DELIVERY state=peer_confirmed"),
+        "{second}"
+    );
+    assert_eq!(alice_row_state(&d), "DELIVERED");
+}
+
+#[test]
+fn any_rule_takes_the_first_device_and_reports_it_once() {
+    let d = setup("device_fanout_any", "any");
+    send_to_both_devices(&d, "any");
+
+    let second = alice_pulls(&d, ROUTE_TOKEN_BOB_2);
+    assert!(
+        second.contains("QSC_DELIVERY state=peer_confirmed"),
+        "{second}"
+    );
+    assert_eq!(alice_row_state(&d), "DELIVERED");
+
+    // The primary's late ack is recorded against its device and moves nothing.
+    let primary = alice_pulls(&d, ROUTE_TOKEN_BOB);
+    assert_recorded_not_delivered(&primary);
+    assert_eq!(alice_row_state(&d), "DELIVERED");
+}
@@ -0,0 +1,321 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const ROUTE_TOKEN_BOB: &str = "route_token_bob_abcdefghijklmnopqr";
+const CLOCK_ENV: &str = "QSC_UNSAFE_TEST_CLOCK_UNIX_S";
+// Every command runs on a PINNED clock, so "past the deadline" is a value, never a sleep.
+const T0: u64 = 1_900_000_000;
+const TIMER_S: u64 = 60;
+
+fn safe_test_root() -> PathBuf {
+    let root = if let Ok(v) = std::env::var("QSC_TEST_ROOT") {
+        PathBuf::from(v)
+    } else if let Ok(v) = std::env::var("CARGO_TARGET_DIR") {
+        PathBuf::from(v)
+    } else {
+        PathBuf::from("target")
+    };
+    let root = root.join("qsc-test-tmp");
+    ensure_dir_700(&root);
+    root
+}
+
+fn ensure_dir_700(path: &Path) {
+    let _ = fs::create_dir_all(path);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o700));
+    }
+}
+
+fn create_dir_700(path: &Path) {
+    let _ = fs::remove_dir_all(path);
+    ensure_dir_700(path);
+}
+
+fn output_text(out: &std::process::Output) -> String {
+    let mut s = String::from_utf8_lossy(&out.stdout).to_string();
+    s.push_str(&String::from_utf8_lossy(&out.stderr));
+    s
+}
+
+fn qsc_at(cfg: &Path, at: u64) -> Command {
+    let mut cmd = common::qsc_std_command();
+    cmd.env("QSC_CONFIG_DIR", cfg)
+        .env("QSC_MARK_FORMAT", "plain")
+        .env("QSC_QSP_SEED", "1")
+        .env("QSC_ALLOW_SEED_FALLBACK", "1")
+        .env("QSC_UNSAFE_TEST_SEED_FALLBACK", "1")
+        .env(CLOCK_ENV, at.to_string());
+    cmd
+}
+
+fn run_ok_at(cfg: &Path, at: u64, args: &[&str]) -> String {
+    let out = qsc_at(cfg, at).args(args).output().expect("run qsc");
+    let text = output_text(&out);
+    assert!(out.status.success(), "command failed {args:?}\n{text}");
+    text
+}
+
+fn contacts_add_with_route_token(cfg: &Path, label: &str, token: &str) {
+    run_ok_at(
+        cfg,
+        T0,
+        &[
+            "contacts",
+            "add",
+            "--label",
+            label,
+            "--fp",
+            "fp-test",
+            "--route-token",
+            token,
+        ],
+    );
+}
+
+fn expired_lines(text: &str) -> Vec<&str> {
+    text.lines()
+        .filter(|l| l.contains("event=message_expired"))
+        .collect()
+}
+
+struct Pair {
+    _server: common::InboxTestServer,
+    relay: String,
+    alice_cfg: PathBuf,
+    bob_cfg: PathBuf,
+    bob_out: PathBuf,
+    base: PathBuf,
+}
+
+fn setup(tag: &str) -> Pair {
+    let server = common::start_inbox_server(1024 * 1024, 16);
+    let base = safe_test_root().join(format!("{tag}_{}", std::process::id()));
+    create_dir_700(&base);
+    let alice_cfg = base.join("alice_cfg");
+    let bob_cfg = base.join("bob_cfg");
+    let bob_out = base.join("bob_out");
+    create_dir_700(&alice_cfg);
+    create_dir_700(&bob_cfg);
+    create_dir_700(&bob_out);
+    common::init_mock_vault(&alice_cfg);
+    common::init_mock_vault(&bob_cfg);
+    contacts_add_with_route_token(&alice_cfg, "bob", ROUTE_TOKEN_BOB);
+    contacts_add_with_route_token(&bob_cfg, "bob", ROUTE_TOKEN_BOB);
+    Pair {
+        relay: server.base_url().to_string(),
+        _server: server,
+        alice_cfg,
+        bob_cfg,
+        bob_out,
+        base,
+    }
+}
+
+fn send_at(p: &Pair, at: u64, body: &[u8]) {
+    let payload = p.base.join("msg.bin");
+    fs::write(&payload, body).expect("write msg");
+    run_ok_at(
+        &p.alice_cfg,
+        at,
+        &[
+            "send",
+            "--transport",
+            "relay",
+            "--relay",
+            p.relay.as_str(),
+            "--to",
+            "bob",
+            "--file",
+            payload.to_str().expect("path"),
+        ],
+    );
+}
+
+fn receive_at(p: &Pair, at: u64) -> String {
+    run_ok_at(
+        &p.bob_cfg,
+        at,
+        &[
+            "receive",
+            "--transport",
+            "relay",
+            "--relay",
+            p.relay.as_str(),
+            "--mailbox",
+            ROUTE_TOKEN_BOB,
+            "--from",
+            "bob",
+            "--max",
+            "4",
+            "--out",
+            p.bob_out.to_str().expect("path"),
+        ],
+    )
+}
+
+fn timeline_list_at(cfg: &Path, at: u64) -> String {
+    run_ok_at(
+        cfg,
+        at,
+        &["timeline", "list", "--peer", "bob", "--limit", "10"],
+    )
+}
+
+#[test]
+fn announced_timer_expires_both_sides_with_one_marker_per_deletion() {
+    let p = setup("disappearing_e2e");
+
+    let set = run_ok_at(
+        &p.alice_cfg,
+        T0,
+        &[
+            "contacts",
+            "timer",
+            "set",
+            "--label",
+            "bob",
+            "--seconds",
+            "60",
+            "--relay",
+            p.relay.as_str(),
+        ],
+    );
+    assert!(
+        set.contains("event=disappearing_timer_set label=bob seconds=60 origin=local"),
+        "{set}"
+    );
+    send_at(&p, T0, b"disappearing-e2e");
+
+    // The announcement is pulled ahead of the message, so the receive-side row is stamped.
+    let recv = receive_at(&p, T0);
+    assert!(
+        recv.contains("event=disappearing_timer_set label=bob seconds=60 origin=peer"),
+        "{recv}"
+    );
+    assert!(p.bob_out.join("recv_1.bin").exists(), "{recv}");
+    let shown = run_ok_at(
+        &p.bob_cfg,
+        T0,
+        &["contacts", "timer", "show", "--label", "bob"],
+    );
+    assert!(
+        shown.contains("event=disappearing_timer label=bob state=on seconds=60"),
+        "{shown}"
+    );
+
+    // Inside the window nothing goes, and both sides carry the same deadline.
+    let deadline = format!("expires_at={}", T0 + TIMER_S);
+    for cfg in [&p.alice_cfg, &p.bob_cfg] {
+        let list = timeline_list_at(cfg, T0 + TIMER_S - 1);
+        assert!(expired_lines(&list).is_empty(), "{list}");
+        assert!(
+            list.contains("event=timeline_list count=1 peer=bob"),
+            "{list}"
+        );
+        assert!(list.contains(deadline.as_str()), "{list}");
+    }
+
+    // Past it, the unlock at the start of the command purges, one marker per deletion.
+    let bob_after = timeline_list_at(&p.bob_cfg, T0 + TIMER_S);
+    let bob_expired = expired_lines(&bob_after);
+    assert_eq!(bob_expired.len(), 1, "{bob_after}");
+    assert!(
+        bob_expired[0].contains("store=timeline peer=bob dir=in kind=msg"),
+        "{bob_after}"
+    );
+    assert!(
+        bob_after.contains("event=timeline_list count=0 peer=bob"),
+        "{bob_after}"
+    );
+
+    let alice_after = timeline_list_at(&p.alice_cfg, T0 + TIMER_S);
+    let alice_expired = expired_lines(&alice_after);
+    assert_eq!(alice_expired.len(), 2, "{alice_after}");
+    assert!(
+        alice_expired
+            .iter()
+            .any(|l| l.contains("store=msgqueue state=SENT msg_id=<redacted>")),
+        "{alice_after}"
+    );
+    assert!(
+        alice_expired
+            .iter()
+            .any(|l| l.contains("store=timeline peer=bob dir=out kind=file")),
+        "{alice_after}"
+    );
+    assert!(
+        alice_after.contains("event=timeline_list count=0 peer=bob"),
+        "{alice_after}"
+    );
+
+    // A deletion happens once: the next unlock finds nothing left to purge.
+    for cfg in [&p.alice_cfg, &p.bob_cfg] {
+        let again = timeline_list_at(cfg, T0 + TIMER_S + 1);
+        assert!(expired_lines(&again).is_empty(), "{again}");
+    }
+    // HONEST LIMITS: the file the user asked for in `--out` is theirs, not the store's.
+    assert!(p.bob_out.join("recv_1.bin").exists());
+}
+
+#[test]
+fn timer_off_keeps_messages_past_any_deadline() {
+    let p = setup("disappearing_off_e2e");
+
+    run_ok_at(
+        &p.alice_cfg,
+        T0,
+        &[
+            "contacts",
+            "timer",
+            "set",
+            "--label",
+            "bob",
+            "--seconds",
+            "60",
+            "--relay",
+            p.relay.as_str(),
+        ],
+    );
+    let off = run_ok_at(
+        &p.alice_cfg,
+        T0,
+        &[
+            "contacts",
+            "timer",
+            "set",
+            "--label",
+            "bob",
+            "--seconds",
+            "0",
+            "--relay",
+            p.relay.as_str(),
+        ],
+    );
+    assert!(
+        off.contains("event=disappearing_timer_set label=bob seconds=0 origin=local"),
+        "{off}"
+    );
+    send_at(&p, T0, b"kept-e2e");
+    let recv = receive_at(&p, T0);
+    assert!(
+        recv.contains("event=disappearing_timer_set label=bob seconds=0 origin=peer"),
+        "{recv}"
+    );
+
+    let later = T0 + 30 * 24 * 3600;
+    for cfg in [&p.alice_cfg, &p.bob_cfg] {
+        let list = timeline_list_at(cfg, later);
+        assert!(expired_lines(&list).is_empty(), "{list}");
+        assert!(
+            list.contains("event=timeline_list count=1 peer=bob"),
+            "{list}"
+        );
+        assert!(!list.contains("expires_at="), "{list}");
+    }
+}
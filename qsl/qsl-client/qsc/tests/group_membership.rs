@@ -0,0 +1,327 @@
+mod common;
+
+use quantumshield_refimpl::crypto::stdcrypto::{
+    runtime_pq_kem_keypair, runtime_pq_sig_keypair, StdCrypto,
+};
+use quantumshield_refimpl::crypto::traits::PqSigMldsa65;
+use sha2::{Digest, Sha512};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const ROUTE_TOKEN_BOB: &str = "route_token_bob_abcdefghijklmnopqr";
+// Chosen by the test, so it is known without scraping a (redacted) marker.
+const GROUP_ID: &str = "0123456789abcdef0123456789abcdef";
+const GROUP_SIG_DOMAIN: &[u8] = b"qsc.group.membership.v1\0";
+
+fn safe_test_root() -> PathBuf {
+    let root = if let Ok(v) = std::env::var("QSC_TEST_ROOT") {
+        PathBuf::from(v)
+    } else if let Ok(v) = std::env::var("CARGO_TARGET_DIR") {
+        PathBuf::from(v)
+    } else {
+        PathBuf::from("target")
+    };
+    let root = root.join("qsc-test-tmp");
+    ensure_dir_700(&root);
+    root
+}
+
+fn ensure_dir_700(path: &Path) {
+    let _ = fs::create_dir_all(path);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o700));
+    }
+}
+
+fn create_dir_700(path: &Path) {
+    let _ = fs::remove_dir_all(path);
+    ensure_dir_700(path);
+}
+
+fn output_text(out: &std::process::Output) -> String {
+    let mut s = String::from_utf8_lossy(&out.stdout).to_string();
+    s.push_str(&String::from_utf8_lossy(&out.stderr));
+    s
+}
+
+fn qsc_base(cfg: &Path) -> Command {
+    let mut cmd = common::qsc_std_command();
+    cmd.env("QSC_CONFIG_DIR", cfg)
+        .env("QSC_MARK_FORMAT", "plain")
+        .env("QSC_QSP_SEED", "1")
+        .env("QSC_ALLOW_SEED_FALLBACK", "1")
+        .env("QSC_UNSAFE_TEST_SEED_FALLBACK", "1");
+    cmd
+}
+
+fn run_ok(cfg: &Path, args: &[&str]) -> String {
+    let out = qsc_base(cfg).args(args).output().expect("run qsc");
+    let text = output_text(&out);
+    assert!(out.status.success(), "command failed {args:?}\n{text}");
+    text
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn hex_decode(s: &str) -> Vec<u8> {
+    let s = s.trim();
+    assert!(s.len() % 2 == 0, "odd-length hex");
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).expect("hex digit"))
+        .collect()
+}
+
+/// Mirror of the production `qsl-fp-v1` construction for `role` over length-prefixed fields.
+fn fp(role: &str, fields: &[&[u8]]) -> String {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(format!("qsl-fp-v1:{role}").as_bytes());
+    buf.push(0x00);
+    for f in fields {
+        buf.extend_from_slice(&(f.len() as u64).to_be_bytes());
+        buf.extend_from_slice(f);
+    }
+    hex_encode(&Sha512::digest(&buf)[..32])
+}
+
+/// An ML-DSA key the test holds, so it can sign statements as whoever it is pinned for.
+struct Signer {
+    sig_pk: Vec<u8>,
+    sig_sk: Vec<u8>,
+    kem_pk: Vec<u8>,
+}
+
+impl Signer {
+    fn new() -> Self {
+        let (sig_pk, sig_sk) = runtime_pq_sig_keypair();
+        let (kem_pk, _kem_sk) = runtime_pq_kem_keypair();
+        Signer {
+            sig_pk,
+            sig_sk,
+            kem_pk,
+        }
+    }
+
+    fn member_fp(&self) -> String {
+        fp("sig", &[&self.sig_pk])
+    }
+
+    fn identity_fp(&self) -> String {
+        fp("identity", &[&self.kem_pk, &self.sig_pk])
+    }
+
+    fn sign(&self, body: &str) -> String {
+        let mut msg = GROUP_SIG_DOMAIN.to_vec();
+        msg.extend_from_slice(body.as_bytes());
+        hex_encode(&StdCrypto.sign(&self.sig_sk, &msg).expect("sign"))
+    }
+}
+
+fn membership_body(epoch: u64, admin: &str, members: &[&str]) -> String {
+    serde_json::json!({
+        "v": 1,
+        "op": "membership",
+        "group_id": GROUP_ID,
+        "name": "crew",
+        "epoch": epoch,
+        "admin": admin,
+        "members": members,
+    })
+    .to_string()
+}
+
+/// The `kind=membership` control payload exactly as `groups::build_group_control` frames it.
+fn membership_ctrl(seq: u8, body: &str, sig_pk: &[u8], sig: &str) -> Vec<u8> {
+    serde_json::json!({
+        "v": 2,
+        "t": "group",
+        "kind": "membership",
+        "msg_id": format!("{seq:032x}"),
+        "ns": "qsc.ctrl",
+        "membership": { "body": body, "sig_pk": hex_encode(sig_pk), "sig": sig },
+    })
+    .to_string()
+    .into_bytes()
+}
+
+struct Pair {
+    _server: common::InboxTestServer,
+    relay: String,
+    alice_cfg: PathBuf,
+    bob_cfg: PathBuf,
+    bob_out: PathBuf,
+    base: PathBuf,
+    /// The admin, pinned on bob's side as the contact the statements arrive from.
+    admin: Signer,
+    bob_fp: String,
+}
+
+/// Alice is only the wire here: what she sends is what the test signed. Bob's contact for the
+/// sender is pinned to the test's admin key, so the statements verify (or fail to) for real.
+fn setup(tag: &str) -> Pair {
+    let server = common::start_inbox_server(1024 * 1024, 16);
+    let base = safe_test_root().join(format!("{tag}_{}", std::process::id()));
+    create_dir_700(&base);
+    let alice_cfg = base.join("alice_cfg");
+    let bob_cfg = base.join("bob_cfg");
+    let bob_out = base.join("bob_out");
+    for dir in [&alice_cfg, &bob_cfg, &bob_out] {
+        create_dir_700(dir);
+    }
+    common::init_mock_vault(&alice_cfg);
+    common::init_mock_vault(&bob_cfg);
+    run_ok(
+        &alice_cfg,
+        &[
+            "contacts",
+            "add",
+            "--label",
+            "bob",
+            "--fp",
+            "fp-test",
+            "--route-token",
+            ROUTE_TOKEN_BOB,
+        ],
+    );
+
+    run_ok(&bob_cfg, &["identity", "rotate", "--confirm"]);
+    let shown = run_ok(&bob_cfg, &["identity", "show"]);
+    let bob_sig_pk = shown
+        .lines()
+        .find_map(|l| l.strip_prefix("identity_sig_pk="))
+        .unwrap_or_else(|| panic!("missing identity_sig_pk: {shown}"));
+    let bob_fp = fp("sig", &[&hex_decode(bob_sig_pk)]);
+
+    let admin = Signer::new();
+    let identity_fp = admin.identity_fp();
+    let kem_hex = hex_encode(&admin.kem_pk);
+    let sig_hex = hex_encode(&admin.sig_pk);
+    run_ok(
+        &bob_cfg,
+        &[
+            "contacts",
+            "add",
+            "--label",
+            "bob",
+            "--fp",
+            identity_fp.as_str(),
+            "--kem-pk",
+            kem_hex.as_str(),
+            "--sig-pk",
+            sig_hex.as_str(),
+            "--route-token",
+            ROUTE_TOKEN_BOB,
+        ],
+    );
+    Pair {
+        relay: server.base_url().to_string(),
+        _server: server,
+        alice_cfg,
+        bob_cfg,
+        bob_out,
+        base,
+        admin,
+        bob_fp,
+    }
+}
+
+/// Alice sends `ctrl` and bob pulls it through the receive path. Returns bob's output.
+fn deliver(p: &Pair, ctrl: &[u8]) -> String {
+    let payload = p.base.join("ctrl.json");
+    fs::write(&payload, ctrl).expect("write ctrl");
+    run_ok(
+        &p.alice_cfg,
+        &[
+            "send",
+            "--transport",
+            "relay",
+            "--relay",
+            p.relay.as_str(),
+            "--to",
+            "bob",
+            "--file",
+            payload.to_str().expect("path"),
+        ],
+    );
+    run_ok(
+        &p.bob_cfg,
+        &[
+            "receive",
+            "--transport",
+            "relay",
+            "--relay",
+            p.relay.as_str(),
+            "--mailbox",
+            ROUTE_TOKEN_BOB,
+            "--from",
+            "bob",
+            "--max",
+            "4",
+            "--out",
+            p.bob_out.to_str().expect("path"),
+        ],
+    )
+}
+
+fn assert_rejected(text: &str, code: &str) {
+    let want = format!("event=group_membership_reject code={code}");
+    assert!(text.contains(want.as_str()), "want {want}:\n{text}");
+    assert!(!text.contains("event=group_membership "), "{text}");
+    assert!(!text.contains("event=recv_item"), "{text}");
+}
+
+fn assert_held_at_epoch_1(p: &Pair) {
+    let shown = run_ok(&p.bob_cfg, &["group", "show", "--id", GROUP_ID]);
+    assert!(shown.contains("members=2 epoch=1"), "{shown}");
+    let admin_line = format!(
+        "event=group_member fp={} label=bob role=admin",
+        p.admin.member_fp()
+    );
+    assert!(shown.contains(admin_line.as_str()), "{shown}");
+}
+
+#[test]
+fn forged_stale_and_non_admin_statements_never_change_the_held_group() {
+    let p = setup("group_membership_e2e");
+    let admin_fp = p.admin.member_fp();
+    let members = [admin_fp.as_str(), p.bob_fp.as_str()];
+
+    // The honest statement: signed by the admin it names, under the key pinned for the sender.
+    let body = membership_body(1, &admin_fp, &members);
+    let ctrl = membership_ctrl(1, &body, &p.admin.sig_pk, &p.admin.sign(&body));
+    let recv = deliver(&p, &ctrl);
+    assert!(recv.contains("event=group_membership op=update"), "{recv}");
+    assert!(recv.contains("epoch=1 origin=peer"), "{recv}");
+    assert_held_at_epoch_1(&p);
+
+    // Stale: a properly signed statement that does not move the epoch forward.
+    let body = membership_body(1, &admin_fp, &[admin_fp.as_str()]);
+    let ctrl = membership_ctrl(2, &body, &p.admin.sig_pk, &p.admin.sign(&body));
+    assert_rejected(&deliver(&p, &ctrl), "group_epoch_stale");
+
+    // Forged: the body was changed after the admin signed it.
+    let signed = membership_body(2, &admin_fp, &members);
+    let forged = membership_body(2, &admin_fp, &[admin_fp.as_str()]);
+    let ctrl = membership_ctrl(3, &forged, &p.admin.sig_pk, &p.admin.sign(&signed));
+    assert_rejected(&deliver(&p, &ctrl), "group_signature_invalid");
+
+    // A valid signature under a key nobody pinned proves nothing.
+    let stranger = Signer::new();
+    let stranger_fp = stranger.member_fp();
+    let body = membership_body(2, &stranger_fp, &[stranger_fp.as_str(), p.bob_fp.as_str()]);
+    let ctrl = membership_ctrl(4, &body, &stranger.sig_pk, &stranger.sign(&body));
+    assert_rejected(&deliver(&p, &ctrl), "group_signer_mismatch");
+
+    // Pinned and valid, but the statement names someone else as admin.
+    let body = membership_body(2, &stranger_fp, &[stranger_fp.as_str(), p.bob_fp.as_str()]);
+    let ctrl = membership_ctrl(5, &body, &p.admin.sig_pk, &p.admin.sign(&body));
+    assert_rejected(&deliver(&p, &ctrl), "group_not_admin");
+
+    // Every refusal left the accepted statement exactly as it was.
+    assert_held_at_epoch_1(&p);
+}
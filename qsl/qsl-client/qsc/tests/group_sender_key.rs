@@ -0,0 +1,278 @@
+mod common;
+
+use quantumshield_refimpl::crypto::stdcrypto::{runtime_pq_kem_keypair, runtime_pq_sig_keypair};
+use sha2::{Digest, Sha512};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const ROUTE_TOKEN_BOB: &str = "route_token_bob_abcdefghijklmnopqr";
+const ROUTE_TOKEN_CAROL: &str = "route_token_carol_abcdefghijklmnop";
+
+fn safe_test_root() -> PathBuf {
+    let root = if let Ok(v) = std::env::var("QSC_TEST_ROOT") {
+        PathBuf::from(v)
+    } else if let Ok(v) = std::env::var("CARGO_TARGET_DIR") {
+        PathBuf::from(v)
+    } else {
+        PathBuf::from("target")
+    };
+    let root = root.join("qsc-test-tmp");
+    ensure_dir_700(&root);
+    root
+}
+
+fn ensure_dir_700(path: &Path) {
+    let _ = fs::create_dir_all(path);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o700));
+    }
+}
+
+fn create_dir_700(path: &Path) {
+    let _ = fs::remove_dir_all(path);
+    ensure_dir_700(path);
+}
+
+fn output_text(out: &std::process::Output) -> String {
+    let mut s = String::from_utf8_lossy(&out.stdout).to_string();
+    s.push_str(&String::from_utf8_lossy(&out.stderr));
+    s
+}
+
+fn qsc_base(cfg: &Path) -> Command {
+    let mut cmd = common::qsc_std_command();
+    cmd.env("QSC_CONFIG_DIR", cfg)
+        .env("QSC_MARK_FORMAT", "plain")
+        .env("QSC_QSP_SEED", "1")
+        .env("QSC_ALLOW_SEED_FALLBACK", "1")
+        .env("QSC_UNSAFE_TEST_SEED_FALLBACK", "1");
+    cmd
+}
+
+fn run_ok(cfg: &Path, args: &[&str]) -> String {
+    let out = qsc_base(cfg).args(args).output().expect("run qsc");
+    let text = output_text(&out);
+    assert!(out.status.success(), "command failed {args:?}\n{text}");
+    text
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Combined identity fingerprint (`qsl-fp-v1:identity`), as `contacts add --fp` expects it.
+fn identity_fp(kem: &[u8], sig: &[u8]) -> String {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(b"qsl-fp-v1:identity");
+    buf.push(0x00);
+    for f in [kem, sig] {
+        buf.extend_from_slice(&(f.len() as u64).to_be_bytes());
+        buf.extend_from_slice(f);
+    }
+    hex_encode(&Sha512::digest(&buf)[..32])
+}
+
+/// `(fp, kem_pk, sig_pk)` for a contact add.
+struct Identity(String, String, String);
+
+fn identity_of(cfg: &Path) -> Identity {
+    let shown = run_ok(cfg, &["identity", "show"]);
+    let field = |key: &str| {
+        let prefix = format!("{key}=");
+        shown
+            .lines()
+            .find_map(|l| l.strip_prefix(prefix.as_str()).map(ToOwned::to_owned))
+            .unwrap_or_else(|| panic!("missing {key}: {shown}"))
+    };
+    Identity(
+        field("identity_fp"),
+        field("identity_kem_pk"),
+        field("identity_sig_pk"),
+    )
+}
+
+/// A member that only ever exists on the wire: nothing here runs as it.
+fn detached_identity() -> Identity {
+    let (kem, _) = runtime_pq_kem_keypair();
+    let (sig, _) = runtime_pq_sig_keypair();
+    Identity(identity_fp(&kem, &sig), hex_encode(&kem), hex_encode(&sig))
+}
+
+fn contacts_add_pinned(cfg: &Path, label: &str, id: &Identity, token: &str) {
+    run_ok(
+        cfg,
+        &[
+            "contacts",
+            "add",
+            "--label",
+            label,
+            "--fp",
+            id.0.as_str(),
+            "--kem-pk",
+            id.1.as_str(),
+            "--sig-pk",
+            id.2.as_str(),
+            "--route-token",
+            token,
+        ],
+    );
+}
+
+fn receive(cfg: &Path, relay: &str, mailbox: &str, from: &str, out: &Path) -> String {
+    run_ok(
+        cfg,
+        &[
+            "receive",
+            "--transport",
+            "relay",
+            "--relay",
+            relay,
+            "--mailbox",
+            mailbox,
+            "--from",
+            from,
+            "--max",
+            "8",
+            "--out",
+            out.to_str().expect("path"),
+        ],
+    )
+}
+
+fn group_send(cfg: &Path, relay: &str, group_id: &str, file: &Path) -> String {
+    run_ok(
+        cfg,
+        &[
+            "group",
+            "send",
+            "--id",
+            group_id,
+            "--file",
+            file.to_str().expect("path"),
+            "--relay",
+            relay,
+        ],
+    )
+}
+
+/// The id alice minted, from her own `--reveal` listing rather than a redacted marker.
+fn revealed_group_id(cfg: &Path) -> String {
+    let listed = run_ok(cfg, &["--reveal", "group", "list"]);
+    let id = listed
+        .lines()
+        .filter(|l| l.contains("event=group_item"))
+        .flat_map(|l| l.split_whitespace())
+        .find_map(|part| part.strip_prefix("id="))
+        .unwrap_or_else(|| panic!("no group_item: {listed}"));
+    common::scraped_marker_value("id", id)
+}
+
+/// Alice administers a sender-key group of herself, bob and carol, and removes bob.
+///
+/// Carol is a detached key: her copies are only ever read by the test. Bob also holds alice
+/// under a second label, `carol`, on carol's route, which stands for a removed member who got
+/// hold of a copy meant for someone else -- the relay, or a member it colludes with, can hand
+/// it over. The claim under test is that such a copy does not open.
+#[test]
+fn removed_member_cannot_open_a_message_sealed_after_the_rotation() {
+    let server = common::start_inbox_server(1024 * 1024, 32);
+    let relay = server.base_url().to_string();
+    let base = safe_test_root().join(format!("group_sender_key_e2e_{}", std::process::id()));
+    create_dir_700(&base);
+    let alice_cfg = base.join("alice_cfg");
+    let bob_cfg = base.join("bob_cfg");
+    let bob_out = base.join("bob_out");
+    let bob_late = base.join("bob_late");
+    for dir in [&alice_cfg, &bob_cfg, &bob_out, &bob_late] {
+        create_dir_700(dir);
+    }
+    common::init_mock_vault(&alice_cfg);
+    common::init_mock_vault(&bob_cfg);
+    run_ok(&alice_cfg, &["identity", "rotate", "--confirm"]);
+    run_ok(&bob_cfg, &["identity", "rotate", "--confirm"]);
+    let alice = identity_of(&alice_cfg);
+    let bob = identity_of(&bob_cfg);
+    contacts_add_pinned(&alice_cfg, "bob", &bob, ROUTE_TOKEN_BOB);
+    contacts_add_pinned(&alice_cfg, "carol", &detached_identity(), ROUTE_TOKEN_CAROL);
+    contacts_add_pinned(&bob_cfg, "bob", &alice, ROUTE_TOKEN_BOB);
+    contacts_add_pinned(&bob_cfg, "carol", &alice, ROUTE_TOKEN_CAROL);
+
+    run_ok(
+        &alice_cfg,
+        &[
+            "group",
+            "create",
+            "--name",
+            "crew",
+            "--member",
+            "bob",
+            "--member",
+            "carol",
+            "--sender-keys",
+            "--relay",
+            relay.as_str(),
+        ],
+    );
+    let group_id = revealed_group_id(&alice_cfg);
+
+    // Before the removal bob holds alice's chain and opens what she seals.
+    let first = base.join("first.bin");
+    fs::write(&first, b"sender-key-before").expect("write msg");
+    let sent = group_send(&alice_cfg, &relay, &group_id, &first);
+    assert!(sent.contains("op=create"), "{sent}");
+    assert!(sent.contains("reason=first_send origin=local"), "{sent}");
+    assert!(sent.contains("op=distribute"), "{sent}");
+    assert!(sent.contains("count=2 origin=local"), "{sent}");
+    let recv = receive(&bob_cfg, &relay, ROUTE_TOKEN_BOB, "bob", &bob_out);
+    assert!(recv.contains("event=group_membership op=update"), "{recv}");
+    assert!(recv.contains("event=group_sender_key op=stored"), "{recv}");
+    assert!(!recv.contains("event=group_msg_reject"), "{recv}");
+    assert_eq!(
+        fs::read(bob_out.join("recv_1.bin")).expect("bob opened the first message"),
+        b"sender-key-before"
+    );
+
+    // Bob never takes in his removal, so he still holds the group and alice's old chain.
+    run_ok(
+        &alice_cfg,
+        &[
+            "group",
+            "remove",
+            "--id",
+            group_id.as_str(),
+            "--member",
+            "bob",
+            "--relay",
+            relay.as_str(),
+        ],
+    );
+    let _ = server.drain_channel(ROUTE_TOKEN_BOB);
+    let _ = server.drain_channel(ROUTE_TOKEN_CAROL);
+
+    let second = base.join("second.bin");
+    fs::write(&second, b"sender-key-after").expect("write msg");
+    let sent = group_send(&alice_cfg, &relay, &group_id, &second);
+    assert!(sent.contains("op=rotate"), "{sent}");
+    assert!(
+        sent.contains("reason=member_removed origin=local"),
+        "{sent}"
+    );
+    assert!(sent.contains("count=1 origin=local"), "{sent}");
+    assert!(sent.contains("queued=1 sent=1"), "{sent}");
+    // Neither the new chain nor the message went to bob.
+    assert!(server.drain_channel(ROUTE_TOKEN_BOB).is_empty());
+
+    // Carol's mailbox holds the new chain and then the message. Bob gets only the message.
+    let mut carol = server.drain_channel(ROUTE_TOKEN_CAROL);
+    assert_eq!(carol.len(), 2, "the new chain, then the sealed message");
+    server.enqueue_raw(ROUTE_TOKEN_CAROL, carol.pop().expect("sealed message"));
+    let late = receive(&bob_cfg, &relay, ROUTE_TOKEN_CAROL, "carol", &bob_late);
+    assert!(
+        late.contains("event=group_msg_reject code=group_sender_key_unknown"),
+        "{late}"
+    );
+    assert!(!bob_late.join("recv_1.bin").exists(), "{late}");
+}
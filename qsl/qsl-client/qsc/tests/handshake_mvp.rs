@@ -1417,6 +1417,11 @@ fn hs_dance(alice_cfg: &Path, bob_cfg: &Path, relay: &str) {
     seed_authenticated_pair(alice_cfg, bob_cfg);
     relay_inbox_set(alice_cfg, ROUTE_TOKEN_ALICE);
     relay_inbox_set(bob_cfg, ROUTE_TOKEN_BOB);
+    hs_rounds(alice_cfg, bob_cfg, relay);
+}
+
+/// One alice-initiated handshake between already-paired configs; returns every poll's output.
+fn hs_rounds(alice_cfg: &Path, bob_cfg: &Path, relay: &str) -> Vec<String> {
     let init = qsc_cfg_cmd(alice_cfg)
         .args([
             "handshake",
@@ -1431,6 +1436,7 @@ fn hs_dance(alice_cfg: &Path, bob_cfg: &Path, relay: &str) {
         .output()
         .expect("hs init");
     assert!(init.status.success(), "{}", output_text(&init));
+    let mut polls = Vec::new();
     for (cfg, me, peer) in [
         (bob_cfg, "bob", "alice"),
         (alice_cfg, "alice", "bob"),
@@ -1452,6 +1458,7 @@ fn hs_dance(alice_cfg: &Path, bob_cfg: &Path, relay: &str) {
             .output()
             .expect("hs poll");
         assert!(out.status.success(), "{}", output_text(&out));
+        polls.push(output_text(&out));
     }
     assert!(
         session_path(alice_cfg, "bob").exists(),
@@ -1461,6 +1468,7 @@ fn hs_dance(alice_cfg: &Path, bob_cfg: &Path, relay: &str) {
         session_path(bob_cfg, "alice").exists(),
         "bob session missing"
     );
+    polls
 }
 
 fn send_msg(cfg: &Path, relay: &str, to: &str, path: &Path) -> String {
@@ -1640,6 +1648,34 @@ fn dh_ratchet_e2e_roundtrip_over_real_handshake() {
         fs::read(bob_out.join("recv_1.bin")).unwrap(),
         b"hello-again-alice"
     );
+
+    // The healing fields ride `handshake status`: Bob just received, so his next send is armed
+    // to ratchet, and the DH step he took on Alice's boundary is counted.
+    let status = run_qsc(&bob_cfg, &["handshake", "status", "--peer", "alice"]);
+    assert!(status.status.success(), "{}", output_text(&status));
+    let status_text = output_text(&status);
+    for field in [
+        "heal_sent_since_dh=",
+        "heal_recv_since_dh=",
+        "heal_recv_dh_steps=",
+        "heal_secs_since_dh=",
+        "heal_secs_since_reseed=",
+        "heal_secs_since_recv_dh=",
+        "heal_secs_since_recv_reseed=",
+        "heal_outstanding_targets=",
+        "heal_skipped_keys=0",
+        "heal_ratchet_pending=yes",
+    ] {
+        assert!(status_text.contains(field), "missing {field}: {status_text}");
+    }
+    assert!(
+        !status_text.contains("heal_recv_dh_steps=0 "),
+        "bob's receive DH step must be counted: {status_text}"
+    );
+    assert!(
+        !status_text.contains("heal_secs_since_recv_dh=never"),
+        "alice's boundary must be timestamped on bob's side: {status_text}"
+    );
 }
 
 #[test]
@@ -2062,6 +2098,98 @@ fn scka_e2e_rolled_back_session_blob_fails_closed() {
     );
 }
 
+/// The vault-anchored generation guard: a blob rewound TOGETHER with its SCKA side-record
+/// (both sit in `qsp_sessions/`, so one directory restore rewinds both) passes the G2 check
+/// but is still refused, because the anchor lives in the vault. A fresh handshake recovers.
+#[test]
+fn rewound_session_blob_and_side_record_fail_the_vault_anchor_then_rehandshake_recovers() {
+    let base = safe_test_root().join(format!("session_gen_rollback_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&base);
+    ensure_dir_700(&base);
+    let alice_cfg = base.join("alice");
+    let bob_cfg = base.join("bob");
+    let b_out = base.join("b_out");
+    let b_out2 = base.join("b_out2");
+    for d in [&alice_cfg, &bob_cfg, &b_out, &b_out2] {
+        ensure_dir_700(d);
+    }
+    common::init_mock_vault(&alice_cfg);
+    common::init_mock_vault(&bob_cfg);
+    let server = common::start_inbox_server(1024 * 1024, 32);
+    let relay = server.base_url().to_string();
+    hs_dance(&alice_cfg, &bob_cfg, &relay);
+
+    let mk = |name: &str, body: &[u8]| {
+        let p = base.join(name);
+        fs::write(&p, body).unwrap();
+        p
+    };
+
+    send_msg(&alice_cfg, &relay, "bob", &mk("m1", b"m1"));
+    assert!(recv_msg_drain(&bob_cfg, &relay, ROUTE_TOKEN_BOB, "alice", &b_out)
+        .status
+        .success());
+
+    // Back up Bob's whole session directory entry: blob and (if present) the SCKA record.
+    let sessions = bob_cfg.join("qsp_sessions");
+    let saved: Vec<(PathBuf, Option<Vec<u8>>)> = ["alice.qsv", "alice.scka.json"]
+        .iter()
+        .map(|name| (sessions.join(name), fs::read(sessions.join(name)).ok()))
+        .collect();
+
+    send_msg(&alice_cfg, &relay, "bob", &mk("m2", b"m2"));
+    assert!(recv_msg_drain(&bob_cfg, &relay, ROUTE_TOKEN_BOB, "alice", &b_out)
+        .status
+        .success());
+
+    for (path, bytes) in &saved {
+        match bytes {
+            Some(b) => fs::write(path, b).expect("rewind"),
+            None => {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+    let s3 = qsc_cfg_cmd(&bob_cfg)
+        .args([
+            "send",
+            "--transport",
+            "relay",
+            "--relay",
+            &relay,
+            "--to",
+            "alice",
+            "--file",
+            mk("m3", b"m3").to_str().unwrap(),
+        ])
+        .output()
+        .expect("send after rewind");
+    let s3_text = output_text(&s3);
+    assert!(!s3.status.success(), "a rewound session must not send: {s3_text}");
+    assert!(
+        s3_text.contains("session_state_rollback") && s3_text.contains("recovery=rehandshake"),
+        "the vault anchor, not G2, must catch this rewind: {s3_text}"
+    );
+    assert!(!s3_text.contains("session_rollback_detected"), "{s3_text}");
+
+    // Recovery: a fresh handshake discards the rewound state and establishes a new session.
+    let polls = hs_rounds(&alice_cfg, &bob_cfg, &relay);
+    assert!(
+        polls
+            .iter()
+            .any(|p| p.contains("event=session_rollback_recovered")),
+        "{polls:?}"
+    );
+    send_msg(&alice_cfg, &relay, "bob", &mk("m4", b"after-recovery"));
+    assert!(recv_msg_drain(&bob_cfg, &relay, ROUTE_TOKEN_BOB, "alice", &b_out2)
+        .status
+        .success());
+    assert_eq!(
+        fs::read(b_out2.join("recv_1.bin")).expect("recovered message"),
+        b"after-recovery"
+    );
+}
+
 // ============ NA-0625 (ENG-0023): authenticated-ADV + NHK rejection e2e proofs ============
 
 /// SPOOFED/PLANTED ADV REJECTION (DoD 4): a relay-inbox injector plants a syntactically valid
@@ -2126,7 +2254,8 @@ fn scka_e2e_spoofed_adv_injection_rejected_never_tracked() {
         payload: spoofed.wire,
         padding: Vec::new(),
     }
-    .encode();
+    .encode()
+    .expect("encode planted envelope");
     let mut items = server.drain_channel(ROUTE_TOKEN_BOB);
     assert!(items.is_empty(), "inbox should be drained after r1");
     items.push(planted);
@@ -2227,7 +2356,7 @@ fn scka_e2e_unauthentic_reseed_header_rejected_no_mutation_then_recovers() {
     let mut env = Envelope::decode(&items[1]).expect("decode reseed envelope");
     let hdr_ct_off = 10 + 32 + 2 + 4 + 1088;
     env.payload[hdr_ct_off] ^= 0x01;
-    items[1] = env.encode();
+    items[1] = env.encode().expect("encode reseed envelope");
     server.replace_channel(ROUTE_TOKEN_ALICE, items);
 
     // Alice: the in-pack ADV authenticates; the unauthentic boundary header is REJECTED
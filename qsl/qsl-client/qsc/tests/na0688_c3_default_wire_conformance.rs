@@ -31,6 +31,7 @@ mod common;
 use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
 use quantumshield_refimpl::crypto::traits::{Hash, Kmac};
 use quantumshield_refimpl::qse::Envelope;
+use quantumshield_refimpl::suite2::limits::Suite2Limits;
 use quantumshield_refimpl::suite2::ratchet::{
     Suite2DhRatchetState, Suite2RecvWireState, Suite2SendState,
 };
@@ -130,6 +131,9 @@ fn qsp_session_for_channel(seed: u64, channel: &str) -> Suite2SessionState {
         consumed_targets: BTreeSet::new(),
         tombstoned_targets: BTreeSet::new(),
         mkskipped: Vec::new(),
+        limits: Suite2Limits::default(),
+        dh_epoch: 0,
+        now_secs: 0,
     };
     let dh = Suite2DhRatchetState {
         dhs_priv: dh_priv,
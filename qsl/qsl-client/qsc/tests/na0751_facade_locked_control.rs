@@ -95,6 +95,10 @@ fn na0751_locked_control_fires_on_a_genuinely_default_flag() {
         qsc::facade::invite_list(),
         Err(FacadeError::Locked)
     ));
+    assert!(matches!(
+        qsc::facade::session_healing("peer-0"),
+        Err(FacadeError::Locked)
+    ));
 
     // (3) THE ARMS DIFFER — unlock and the same call succeeds, so the refusal above was a
     // measurement and not a vacuum.
@@ -105,6 +109,10 @@ fn na0751_locked_control_fires_on_a_genuinely_default_flag() {
         contact_list().is_ok(),
         "with the vault unlocked the same call must succeed"
     );
+    assert!(
+        matches!(qsc::facade::session_healing("peer-0"), Ok(None)),
+        "unlocked with no session blob, healing reports no session"
+    );
     qsc::set_vault_unlocked(false);
 }
 
@@ -233,7 +233,7 @@ fn mutate_payload(mut envelope: Envelope, label: &str) -> Vec<u8> {
         }
         other => panic!("unknown mutation label {other}"),
     }
-    envelope.encode()
+    envelope.encode().expect("encode envelope")
 }
 
 #[test]
@@ -3,6 +3,7 @@ mod common;
 use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
 use quantumshield_refimpl::crypto::traits::{Hash, Kmac};
 use quantumshield_refimpl::qse::{Envelope, EnvelopeProfile};
+use quantumshield_refimpl::suite2::limits::Suite2Limits;
 use quantumshield_refimpl::suite2::ratchet::{
     Suite2DhRatchetState, Suite2RecvWireState, Suite2SendState,
 };
@@ -106,6 +107,9 @@ fn qsp_session_for_channel(seed: u64, channel: &str) -> Suite2SessionState {
         consumed_targets: BTreeSet::new(),
         tombstoned_targets: BTreeSet::new(),
         mkskipped: Vec::new(),
+        limits: Suite2Limits::default(),
+        dh_epoch: 0,
+        now_secs: 0,
     };
     let dh = Suite2DhRatchetState {
         dhs_priv: dh_priv,
@@ -127,7 +131,7 @@ fn qsp_pack_for_channel(seed: u64, channel: &str, plaintext: &[u8]) -> Vec<u8> {
         payload: out.wire,
         padding: Vec::new(),
     };
-    let encoded_len = env.encode().len();
+    let encoded_len = env.encode().expect("encode env").len();
     let min_len = EnvelopeProfile::Standard.min_size_bytes();
     if encoded_len < min_len {
         let need = min_len - encoded_len;
@@ -136,7 +140,7 @@ fn qsp_pack_for_channel(seed: u64, channel: &str, plaintext: &[u8]) -> Vec<u8> {
             .pad_to_profile(EnvelopeProfile::Standard, &pad)
             .expect("pad");
     }
-    env.encode()
+    env.encode().expect("encode env")
 }
 
 fn contacts_route_set(cfg: &Path, label: &str, token: &str) {
@@ -264,7 +268,7 @@ fn tamper_rejects_no_write() {
     if let Some(b) = env.payload.first_mut() {
         *b ^= 0x01;
     }
-    let env_bytes = env.encode();
+    let env_bytes = env.encode().expect("encode env");
 
     let url = format!("{}/v1/push", server.base_url());
     let resp = HttpClient::new()
@@ -0,0 +1,318 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const ROUTE_TOKEN_BOB: &str = "route_token_bob_abcdefghijklmnopqr";
+
+fn safe_test_root() -> PathBuf {
+    let root = if let Ok(v) = std::env::var("QSC_TEST_ROOT") {
+        PathBuf::from(v)
+    } else if let Ok(v) = std::env::var("CARGO_TARGET_DIR") {
+        PathBuf::from(v)
+    } else {
+        PathBuf::from("target")
+    };
+    let root = root.join("qsc-test-tmp");
+    ensure_dir_700(&root);
+    root
+}
+
+fn ensure_dir_700(path: &Path) {
+    let _ = fs::create_dir_all(path);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o700));
+    }
+}
+
+fn create_dir_700(path: &Path) {
+    let _ = fs::remove_dir_all(path);
+    ensure_dir_700(path);
+}
+
+fn output_text(out: &std::process::Output) -> String {
+    let mut s = String::from_utf8_lossy(&out.stdout).to_string();
+    s.push_str(&String::from_utf8_lossy(&out.stderr));
+    s
+}
+
+fn qsc_base(cfg: &Path) -> Command {
+    let mut cmd = common::qsc_std_command();
+    cmd.env("QSC_CONFIG_DIR", cfg)
+        .env("QSC_MARK_FORMAT", "plain")
+        .env("QSC_QSP_SEED", "1")
+        .env("QSC_ALLOW_SEED_FALLBACK", "1")
+        .env("QSC_UNSAFE_TEST_SEED_FALLBACK", "1");
+    cmd
+}
+
+fn run_ok(cfg: &Path, args: &[&str]) -> String {
+    let out = qsc_base(cfg).args(args).output().expect("run qsc");
+    let text = output_text(&out);
+    assert!(out.status.success(), "command failed {args:?}\n{text}");
+    text
+}
+
+/// The id alice minted, read from her own queue store rather than scraped from a marker (the
+/// marker layer redacts it -- see `message_state_model::first_party_sent_msg_id`).
+fn first_party_sent_msg_id(cfg: &Path) -> String {
+    let root = cfg.join("msgqueue_v1");
+    let mut found: Vec<String> = Vec::new();
+    let contacts = fs::read_dir(&root).expect("msgqueue_v1 exists after a successful send");
+    for contact in contacts.flatten() {
+        let Ok(entries) = fs::read_dir(contact.path()) else {
+            continue;
+        };
+        for e in entries.flatten() {
+            let name = e.file_name().to_string_lossy().to_string();
+            let Some(stem) = name.strip_suffix(".rec") else {
+                continue;
+            };
+            let Some((_seq, id)) = stem.split_once('_') else {
+                continue;
+            };
+            found.push(id.to_string());
+        }
+    }
+    found.sort();
+    found.dedup();
+    assert_eq!(
+        found.len(),
+        1,
+        "expected one sent message id, got {found:?}"
+    );
+    common::scraped_marker_value("msg_id", found[0].as_str());
+    found.pop().expect("one record")
+}
+
+fn timeline_first_item_state(text: &str) -> Option<String> {
+    for line in text.lines() {
+        if !line.contains("event=timeline_item") {
+            continue;
+        }
+        for part in line.split_whitespace() {
+            if let Some(v) = part.strip_prefix("state=") {
+                return Some(common::scraped_marker_value("state", v));
+            }
+        }
+    }
+    None
+}
+
+struct Pair {
+    _server: common::InboxTestServer,
+    relay: String,
+    alice_cfg: PathBuf,
+    bob_cfg: PathBuf,
+    alice_out: PathBuf,
+    bob_out: PathBuf,
+    base: PathBuf,
+}
+
+fn setup(tag: &str) -> Pair {
+    let server = common::start_inbox_server(1024 * 1024, 16);
+    let base = safe_test_root().join(format!("{tag}_{}", std::process::id()));
+    create_dir_700(&base);
+    let alice_cfg = base.join("alice_cfg");
+    let bob_cfg = base.join("bob_cfg");
+    let alice_out = base.join("alice_out");
+    let bob_out = base.join("bob_out");
+    for dir in [&alice_cfg, &bob_cfg, &alice_out, &bob_out] {
+        create_dir_700(dir);
+    }
+    common::init_mock_vault(&alice_cfg);
+    common::init_mock_vault(&bob_cfg);
+    for cfg in [&alice_cfg, &bob_cfg] {
+        run_ok(
+            cfg,
+            &[
+                "contacts",
+                "add",
+                "--label",
+                "bob",
+                "--fp",
+                "fp-test",
+                "--route-token",
+                ROUTE_TOKEN_BOB,
+            ],
+        );
+    }
+    Pair {
+        relay: server.base_url().to_string(),
+        _server: server,
+        alice_cfg,
+        bob_cfg,
+        alice_out,
+        bob_out,
+        base,
+    }
+}
+
+fn receive(p: &Pair, cfg: &Path, out: &Path) -> String {
+    run_ok(
+        cfg,
+        &[
+            "receive",
+            "--transport",
+            "relay",
+            "--relay",
+            p.relay.as_str(),
+            "--mailbox",
+            ROUTE_TOKEN_BOB,
+            "--from",
+            "bob",
+            "--max",
+            "4",
+            "--out",
+            out.to_str().expect("path"),
+        ],
+    )
+}
+
+/// Alice sends one message and bob receives it; alice then pulls bob's delivery ack, so the
+/// shared mailbox is empty again and her row is DELIVERED. Returns the message id.
+fn deliver_one(p: &Pair) -> String {
+    let payload = p.base.join("msg.bin");
+    fs::write(&payload, b"read-receipt-e2e").expect("write msg");
+    run_ok(
+        &p.alice_cfg,
+        &[
+            "send",
+            "--transport",
+            "relay",
+            "--relay",
+            p.relay.as_str(),
+            "--to",
+            "bob",
+            "--file",
+            payload.to_str().expect("path"),
+            "--receipt",
+            "delivered",
+        ],
+    );
+    let bob = receive(p, &p.bob_cfg, &p.bob_out);
+    assert!(p.bob_out.join("recv_1.bin").exists(), "{bob}");
+    let alice = receive(p, &p.alice_cfg, &p.alice_out);
+    assert!(
+        alice.contains("QSC_DELIVERY state=peer_confirmed"),
+        "{alice}"
+    );
+    first_party_sent_msg_id(&p.alice_cfg)
+}
+
+fn alice_row_state(p: &Pair) -> String {
+    let list = run_ok(&p.alice_cfg, &["timeline", "list", "--peer", "bob"]);
+    timeline_first_item_state(&list).unwrap_or_else(|| panic!("no timeline item: {list}"))
+}
+
+fn show(p: &Pair, msg_id: &str) -> String {
+    run_ok(
+        &p.bob_cfg,
+        &["timeline", "show", "--peer", "bob", "--id", msg_id],
+    )
+}
+
+#[test]
+fn displayed_message_moves_the_sender_row_to_read() {
+    let p = setup("read_receipt_e2e");
+    run_ok(&p.bob_cfg, &["config", "set", "read-receipts", "on"]);
+    let msg_id = deliver_one(&p);
+    assert_eq!(alice_row_state(&p), "DELIVERED");
+
+    // Displaying owes the receipt; it leaves with bob's next pull, not on its own.
+    let shown = show(&p, &msg_id);
+    assert!(
+        shown.contains("event=read_receipt state=owed reason=displayed"),
+        "{shown}"
+    );
+    assert!(!shown.contains("event=receipt_send"), "{shown}");
+    let pull = receive(&p, &p.bob_cfg, &p.bob_out);
+    assert!(pull.contains("event=receipt_send kind=read"), "{pull}");
+
+    let alice = receive(&p, &p.alice_cfg, &p.alice_out);
+    assert!(alice.contains("event=receipt_recv kind=read"), "{alice}");
+    assert!(alice.contains("event=read_by_peer kind=read"), "{alice}");
+    assert!(alice.contains("QSC_DELIVERY state=peer_read"), "{alice}");
+    assert_eq!(alice_row_state(&p), "READ");
+
+    // One display, one receipt: showing it again owes nothing new.
+    let again = show(&p, &msg_id);
+    assert!(!again.contains("event=read_receipt "), "{again}");
+    let pull = receive(&p, &p.bob_cfg, &p.bob_out);
+    assert!(!pull.contains("event=receipt_send kind=read"), "{pull}");
+}
+
+#[test]
+fn contact_opt_out_after_display_withholds_the_receipt_for_good() {
+    let p = setup("read_receipt_opt_out_e2e");
+    run_ok(&p.bob_cfg, &["config", "set", "read-receipts", "on"]);
+    let msg_id = deliver_one(&p);
+    let shown = show(&p, &msg_id);
+    assert!(
+        shown.contains("event=read_receipt state=owed reason=displayed"),
+        "{shown}"
+    );
+
+    // The decision is re-taken at the pull, so opting out in between still keeps it private.
+    let off = run_ok(
+        &p.bob_cfg,
+        &[
+            "contacts",
+            "read-receipts",
+            "set",
+            "--label",
+            "bob",
+            "--mode",
+            "off",
+        ],
+    );
+    assert!(
+        off.contains("event=contacts_read_receipts label=bob mode=off ok=true"),
+        "{off}"
+    );
+    let pull = receive(&p, &p.bob_cfg, &p.bob_out);
+    assert!(
+        pull.contains("event=read_receipt_withheld count=1 reason=contact_off"),
+        "{pull}"
+    );
+    assert!(!pull.contains("event=receipt_send kind=read"), "{pull}");
+
+    // Turning it back on never releases the earlier read.
+    run_ok(
+        &p.bob_cfg,
+        &[
+            "contacts",
+            "read-receipts",
+            "set",
+            "--label",
+            "bob",
+            "--mode",
+            "on",
+        ],
+    );
+    let pull = receive(&p, &p.bob_cfg, &p.bob_out);
+    assert!(!pull.contains("event=receipt_send kind=read"), "{pull}");
+
+    let alice = receive(&p, &p.alice_cfg, &p.alice_out);
+    assert!(!alice.contains("event=read_by_peer"), "{alice}");
+    assert_eq!(alice_row_state(&p), "DELIVERED");
+}
+
+#[test]
+fn read_receipts_stay_off_without_the_account_opt_in() {
+    let p = setup("read_receipt_default_off_e2e");
+    let msg_id = deliver_one(&p);
+    let shown = show(&p, &msg_id);
+    assert!(
+        shown.contains("event=read_receipt state=withheld reason=account_off"),
+        "{shown}"
+    );
+    let pull = receive(&p, &p.bob_cfg, &p.bob_out);
+    assert!(!pull.contains("event=receipt_send kind=read"), "{pull}");
+    let alice = receive(&p, &p.alice_cfg, &p.alice_out);
+    assert!(!alice.contains("event=read_by_peer"), "{alice}");
+    assert_eq!(alice_row_state(&p), "DELIVERED");
+}
@@ -2,6 +2,7 @@ mod common;
 
 use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
 use quantumshield_refimpl::crypto::traits::{Hash, Kmac};
+use quantumshield_refimpl::suite2::limits::Suite2Limits;
 use quantumshield_refimpl::suite2::ratchet::{
     Suite2DhRatchetState, Suite2RecvWireState, Suite2SendState,
 };
@@ -88,6 +89,9 @@ fn seeded_session_state(seed: u64, peer: &str) -> Suite2SessionState {
         consumed_targets: BTreeSet::new(),
         tombstoned_targets: BTreeSet::new(),
         mkskipped: Vec::new(),
+        limits: Suite2Limits::default(),
+        dh_epoch: 0,
+        now_secs: 0,
     };
     let dh = Suite2DhRatchetState {
         dhs_priv: dh_priv,
@@ -537,6 +537,8 @@ fn parse_suite2_recv_state(
             dh_pub: dh_pub_arr,
             n,
             mk: mk_arr,
+            dh_epoch: 0,
+            stored_at: 0,
         });
     }
 
@@ -557,6 +559,9 @@ fn parse_suite2_recv_state(
             consumed_targets,
             tombstoned_targets,
             mkskipped,
+            limits: suite2_limits::Suite2Limits::default(),
+            dh_epoch: 0,
+            now_secs: 0,
         },
         rk_arr,
     ))
@@ -583,6 +588,7 @@ fn kmac32(std: &StdCrypto, key: &[u8], label: &str, data: &[u8]) -> Vec<u8> {
 fn kmac64(std: &StdCrypto, key: &[u8], label: &str, data: &[u8]) -> Vec<u8> {
     std.kmac256(key, label, data, 64)
 }
+use quantumshield_refimpl::crypto::provider::StdProvider;
 use quantumshield_refimpl::crypto::traits::{
     Aead, CryptoError, Hash, PqKem768, PqSigMldsa65, Rng12, SigEd25519, X25519Dh, X25519Priv,
     X25519Pub,
@@ -594,8 +600,9 @@ use quantumshield_refimpl::qsp::{
     SessionState, SZ_ED25519_SIG, SZ_MLDSA65_SIG,
 };
 use quantumshield_refimpl::suite2::{
-    binding, establish as suite2_establish, parse as suite2_parse, ratchet as suite2_ratchet,
-    scka as suite2_scka, state as suite2_state, types as suite2_types,
+    binding, establish as suite2_establish, limits as suite2_limits, parse as suite2_parse,
+    ratchet as suite2_ratchet, scka as suite2_scka, session::Suite2Session, state as suite2_state,
+    types as suite2_types,
 };
 
 // ---------------------------
@@ -1335,7 +1342,7 @@ struct Actor {
 
     pending: HashMap<[u8; 16], PendingInit>,
     sessions: HashMap<[u8; 16], SessionEntry>,
-    suite2_sessions: HashMap<[u8; 16], suite2_state::Suite2SessionState>,
+    suite2_sessions: HashMap<[u8; 16], Suite2Session>,
 }
 
 impl Actor {
@@ -1474,6 +1481,16 @@ impl Actor {
         })
     }
 
+    /// Commit a state advanced by a raw ratchet op, keeping the session's SCKA store.
+    fn store_suite2_state(&mut self, sid: [u8; 16], state: suite2_state::Suite2SessionState) {
+        match self.suite2_sessions.get_mut(&sid) {
+            Some(session) => session.replace_state(state),
+            None => {
+                self.suite2_sessions.insert(sid, Suite2Session::new(state));
+            }
+        }
+    }
+
     fn handle_handshake_init(&mut self, suite: &str) -> Result<serde_json::Value, ActorError> {
         let suite_kind = if suite == "Suite-1" {
             SuiteKind::Suite1
@@ -1533,7 +1550,9 @@ impl Actor {
             },
         );
 
-        let msg1 = hs1.encode();
+        let msg1 = hs1
+            .encode()
+            .map_err(|e| ActorError::Internal(format!("HS1 encode failed: {e}")))?;
         Ok(serde_json::json!({
             "session_id": session_id_to_string(&session_id),
             "msg1_b64": b64u_encode(&msg1)
@@ -1591,7 +1610,9 @@ impl Actor {
             },
         );
 
-        let msg2 = hs2.encode();
+        let msg2 = hs2
+            .encode()
+            .map_err(|e| ActorError::Internal(format!("HS2 encode failed: {e}")))?;
         Ok(serde_json::json!({ "msg2_b64": b64u_encode(&msg2) }))
     }
 
@@ -1665,7 +1686,10 @@ impl Actor {
         )
         .map_err(|e| ActorError::Crypto(format!("encrypt failed: {e}")))?;
 
-        Ok(serde_json::json!({ "ciphertext_b64": b64u_encode(&msg.encode()) }))
+        let wire = msg
+            .encode()
+            .map_err(|e| ActorError::Internal(format!("message encode failed: {e}")))?;
+        Ok(serde_json::json!({ "ciphertext_b64": b64u_encode(&wire) }))
     }
 
     fn handle_decrypt(
@@ -1796,8 +1820,7 @@ impl Actor {
         let version = snap_val.get("version").and_then(|v| v.as_u64());
 
         let mut new_sessions: HashMap<[u8; 16], SessionEntry> = HashMap::new();
-        let mut new_suite2_sessions: HashMap<[u8; 16], suite2_state::Suite2SessionState> =
-            HashMap::new();
+        let mut new_suite2_sessions: HashMap<[u8; 16], Suite2Session> = HashMap::new();
         let seed = if version == Some(2) {
             let snap: ActorSnapshotV2 = serde_json::from_value(snap_val)
                 .map_err(|e| ActorError::Invalid(format!("bad snapshot json: {e}")))?;
@@ -1826,9 +1849,16 @@ impl Actor {
                             ActorError::Invalid("bad session snapshot: missing s2_b64".into())
                         })?;
                         let st_raw = b64u_decode(s2_b64)?;
-                        let st = suite2_state::Suite2SessionState::restore_bytes(&st_raw).map_err(
-                            |e| ActorError::Invalid(format!("bad session snapshot: {e}")),
-                        )?;
+                        // `QS2P` carries the SCKA store; a bare `QS2S` state (older snapshots)
+                        // restores with an empty one.
+                        let st = match Suite2Session::restore_bytes(&st_raw) {
+                            Ok(session) => session,
+                            Err(_) => suite2_state::Suite2SessionState::restore_bytes(&st_raw)
+                                .map(Suite2Session::new)
+                                .map_err(|e| {
+                                    ActorError::Invalid(format!("bad session snapshot: {e}"))
+                                })?,
+                        };
                         new_suite2_sessions.insert(sid, st);
                     }
                     _ => {
@@ -1873,7 +1903,7 @@ impl Actor {
                 let sid_s = session_id_to_string(sid);
                 let path = dur_scka_path(Path::new(dir), &self.name, &sid_s);
                 if let Some(rec) = load_dur_scka(&path)? {
-                    check_dur_scka_rollback(&rec, &st.recv)?;
+                    check_dur_scka_rollback(&rec, &st.state().recv)?;
                 }
             }
         }
@@ -2397,6 +2427,7 @@ impl Actor {
                         0,
                         &msg.hdr_ct,
                         &msg.body_ct,
+                        &suite2_limits::Suite2Limits::default(),
                     );
                     state = out.state;
                     if out.ok {
@@ -2908,7 +2939,8 @@ impl Actor {
 
                 let mut sid_arr = [0u8; 16];
                 sid_arr.copy_from_slice(&session_id);
-                self.suite2_sessions.insert(sid_arr, state);
+                self.suite2_sessions
+                    .insert(sid_arr, Suite2Session::new(state));
 
                 Ok(serde_json::json!({
                     "session_id": session_id_to_string(&sid_arr),
@@ -2942,7 +2974,7 @@ impl Actor {
                 } else if let Some(sid) = session_id_arr_opt {
                     self.suite2_sessions
                         .get(&sid)
-                        .map(|e| (e.recv.clone(), e.rk))
+                        .map(|e| (e.state().recv.clone(), e.state().rk))
                         .ok_or_else(|| ActorError::Invalid("params.recv_state missing".into()))?
                 } else {
                     return Err(ActorError::Invalid("params.recv_state missing".into()));
@@ -2961,7 +2993,9 @@ impl Actor {
                         let send_v = get_json_data(&req.params, "send_state")?;
                         Some(parse_suite2_send_state(&send_v)?)
                     } else {
-                        self.suite2_sessions.get(&sid).map(|e| e.send.clone())
+                        self.suite2_sessions
+                            .get(&sid)
+                            .map(|e| e.state().send.clone())
                     }
                 } else {
                     None
@@ -3036,7 +3070,7 @@ impl Actor {
                             "params.send_state missing for new suite2 session".into(),
                         )
                     })?;
-                    self.suite2_sessions.insert(
+                    self.store_suite2_state(
                         sid,
                         suite2_state::Suite2SessionState {
                             rk: out.rk,
@@ -3114,7 +3148,7 @@ impl Actor {
                 } else if let Some(sid) = session_id_arr_opt {
                     self.suite2_sessions
                         .get(&sid)
-                        .map(|e| e.send.clone())
+                        .map(|e| e.state().send.clone())
                         .ok_or_else(|| ActorError::Invalid("params.send_state missing".into()))?
                 } else {
                     return Err(ActorError::Invalid("params.send_state missing".into()));
@@ -3135,7 +3169,7 @@ impl Actor {
                     } else {
                         self.suite2_sessions
                             .get(&sid)
-                            .map(|e| (e.recv.clone(), e.rk))
+                            .map(|e| (e.state().recv.clone(), e.state().rk))
                     }
                 } else {
                     None
@@ -3160,7 +3194,7 @@ impl Actor {
                             "params.recv_state missing for new suite2 session".into(),
                         )
                     })?;
-                    self.suite2_sessions.insert(
+                    self.store_suite2_state(
                         sid,
                         suite2_state::Suite2SessionState {
                             rk: recv_rk,
@@ -3191,6 +3225,51 @@ impl Actor {
                     } }
                 }))
             }
+            // `Suite2Session` ops: the session drives the DH ratchet and SCKA itself, so a
+            // single plaintext may produce control wires ahead of the data wire.
+            "suite2.session.encrypt" => {
+                let sid = session_id_from_string(
+                    req.params
+                        .get("session_id")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| ActorError::Invalid("missing params.session_id".into()))?,
+                )?;
+                let plaintext = get_bytes(&req.params, "plaintext_hex")?;
+                let session = self
+                    .suite2_sessions
+                    .get_mut(&sid)
+                    .ok_or_else(|| ActorError::Invalid("unknown suite2 session".into()))?;
+                let wires = session
+                    .encrypt(&StdProvider::new(), &plaintext)
+                    .map_err(|e| ActorError::Invalid(format!("reject: {e}")))?;
+                let wires: Vec<serde_json::Value> = wires
+                    .iter()
+                    .map(|w| serde_json::json!({ "type": "hex", "data": to_hex(w) }))
+                    .collect();
+                Ok(serde_json::json!({ "wires": wires }))
+            }
+            "suite2.session.decrypt" => {
+                let sid = session_id_from_string(
+                    req.params
+                        .get("session_id")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| ActorError::Invalid("missing params.session_id".into()))?,
+                )?;
+                let wire = get_bytes(&req.params, "wire_hex")?;
+                let session = self
+                    .suite2_sessions
+                    .get_mut(&sid)
+                    .ok_or_else(|| ActorError::Invalid("unknown suite2 session".into()))?;
+                let out = session
+                    .decrypt(&StdProvider::new(), &wire)
+                    .map_err(|e| ActorError::Invalid(format!("reject: {e}")))?;
+                Ok(match out {
+                    Some(pt) => serde_json::json!({
+                        "plaintext_hex": { "type": "hex", "data": to_hex(&pt) },
+                    }),
+                    None => serde_json::json!({ "control": true }),
+                })
+            }
             // NA-0623 (ENG-0012 Stage 2a): SCKA sender ops. These drive the pure session-level
             // SCKA send functions (advertisement + PQ reseed). The advertised-key store / ML-KEM
             // KeyGen+Encap are caller-side (DOC-CAN-004 §2/§3): the vector supplies the advertised
@@ -3859,6 +3938,9 @@ impl Actor {
                     consumed_targets: BTreeSet::new(),
                     tombstoned_targets: BTreeSet::new(),
                     mkskipped: Vec::new(),
+                    limits: suite2_limits::Suite2Limits::default(),
+                    dh_epoch: 0,
+                    now_secs: 0,
                 };
 
                 let out = suite2_ratchet::recv_pq_adv(
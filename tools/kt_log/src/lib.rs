@@ -0,0 +1,268 @@
+//! HTTP front end for the refimpl reference KT log (`quantumshield_refimpl::kt::KtLog`),
+//! plus the matching client endpoint.
+//!
+//! Routes (JSON bodies; every blob is lowercase hex of its DOC-CAN-008 §5 encoding):
+//! - `GET  /health`
+//! - `GET  /v1/sth` -> `{ "ok": true, "sth": <kt_sth> }`
+//! - `GET  /v1/consistency?from=M&to=N` -> `{ "ok": true, "proof": <kt_consistency_proof> }`
+//! - `GET  /v1/inclusion?index=I&size=N` -> `{ "ok": true, "proof": <kt_inclusion_proof> }`
+//! - `GET  /v1/entries?start=S&end=E` -> `{ "ok": true, "entries": [<BundleLeafData>, ...] }`
+//! - `POST /v1/bundles` `{ "bundle": <PrekeyBundle::encode()> }`
+//!   -> `{ "ok": true, "leaf_index": I, "tree_size": N }`
+//!
+//! Errors are `{ "ok": false, "error": <detail> }`. The log is in memory and local-only.
+//! `HttpLogEndpoint` is the client side, usable by `CanonicalKtVerifier::refresh_from_log`
+//! and `KtMonitorState::advance`.
+
+use quantumshield_refimpl::kt::{
+    KtEntrySource, KtError, KtLog, KtLogEndpoint, MONITOR_ENTRIES_BATCH,
+};
+use quantumshield_refimpl::qsp::PrekeyBundle;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use tiny_http::{Header, Method, Response, Server};
+
+/// Largest accepted POST body.
+pub const MAX_BODY_BYTES: usize = 64 * 1024;
+/// Largest `end - start` served by `/v1/entries`; matches what monitors ask for.
+pub const MAX_ENTRIES_PER_REQUEST: u64 = MONITOR_ENTRIES_BATCH;
+
+pub struct KtLogServer {
+    server: Server,
+    log: Mutex<KtLog>,
+}
+
+impl KtLogServer {
+    /// Bind to a loopback address (`127.0.0.1:0` picks a free port).
+    pub fn bind(addr: SocketAddr, log: KtLog) -> Result<Self, String> {
+        if !addr.ip().is_loopback() {
+            return Err("kt log is local-only; use 127.0.0.1:<port>".to_string());
+        }
+        let server = Server::http(addr).map_err(|e| format!("start kt log: {e}"))?;
+        Ok(Self {
+            server,
+            log: Mutex::new(log),
+        })
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    pub fn base_url(&self) -> Option<String> {
+        self.local_addr().map(|a| format!("http://{a}"))
+    }
+
+    /// Serve until `unblock` is called.
+    pub fn serve(&self) {
+        for mut request in self.server.incoming_requests() {
+            let method = request.method().clone();
+            let url = request.url().to_string();
+            let mut body = Vec::new();
+            let read = request
+                .as_reader()
+                .take(MAX_BODY_BYTES as u64 + 1)
+                .read_to_end(&mut body);
+            let (status, value) = if read.is_err() {
+                (400, error_json("read body failed"))
+            } else if body.len() > MAX_BODY_BYTES {
+                (413, error_json("body too large"))
+            } else {
+                handle(&self.log, &method, &url, &body)
+            };
+            let data = serde_json::to_vec(&value).unwrap_or_else(|_| b"{\"ok\":false}".to_vec());
+            let mut resp = Response::from_data(data).with_status_code(status);
+            if let Ok(h) = Header::from_bytes("Content-Type", "application/json") {
+                resp.add_header(h);
+            }
+            let _ = request.respond(resp);
+        }
+    }
+
+    pub fn unblock(&self) {
+        self.server.unblock();
+    }
+}
+
+fn error_json(detail: &str) -> Value {
+    json!({ "ok": false, "error": detail })
+}
+
+fn query(url: &str) -> (&str, HashMap<&str, &str>) {
+    let (path, q) = url.split_once('?').unwrap_or((url, ""));
+    let params = q.split('&').filter_map(|kv| kv.split_once('=')).collect();
+    (path, params)
+}
+
+fn param_u64(params: &HashMap<&str, &str>, key: &str) -> Result<u64, (u16, Value)> {
+    params
+        .get(key)
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| (400, error_json(&format!("missing or invalid {key}"))))
+}
+
+#[derive(Deserialize)]
+struct SubmitBundle {
+    bundle: String,
+}
+
+/// Route one request against `log`. Split from `serve` so it can be driven directly.
+pub fn handle(log: &Mutex<KtLog>, method: &Method, url: &str, body: &[u8]) -> (u16, Value) {
+    match route(log, method, url, body) {
+        Ok(v) => (200, v),
+        Err(e) => e,
+    }
+}
+
+fn route(
+    log: &Mutex<KtLog>,
+    method: &Method,
+    url: &str,
+    body: &[u8],
+) -> Result<Value, (u16, Value)> {
+    let (path, params) = query(url);
+    let kt = |e: KtError| (400, error_json(e.detail()));
+    let mut log = log
+        .lock()
+        .map_err(|_| (500, error_json("kt log state poisoned")))?;
+
+    match (method, path) {
+        (Method::Get, "/health") => Ok(json!({
+            "ok": true,
+            "name": "qsl-kt-log",
+            "log_id": hex::encode(log.log_id()),
+            "verifying_key": hex::encode(log.verifying_key()),
+            "tree_size": log.tree_size(),
+        })),
+        (Method::Get, "/v1/sth") => {
+            let sth = log.sth().map_err(kt)?;
+            Ok(json!({ "ok": true, "sth": hex::encode(sth) }))
+        }
+        (Method::Get, "/v1/consistency") => {
+            let from = param_u64(&params, "from")?;
+            let to = param_u64(&params, "to")?;
+            let proof = log.consistency_proof(from, to).map_err(kt)?;
+            Ok(json!({ "ok": true, "proof": hex::encode(proof) }))
+        }
+        (Method::Get, "/v1/inclusion") => {
+            let index = param_u64(&params, "index")?;
+            let size = param_u64(&params, "size")?;
+            let proof = log.inclusion_proof(index, size).map_err(kt)?;
+            Ok(json!({ "ok": true, "proof": hex::encode(proof) }))
+        }
+        (Method::Get, "/v1/entries") => {
+            let start = param_u64(&params, "start")?;
+            let end = param_u64(&params, "end")?;
+            if start > end || end > log.tree_size() {
+                return Err((400, error_json("entries range out of bounds")));
+            }
+            if end - start > MAX_ENTRIES_PER_REQUEST {
+                return Err((400, error_json("entries range too large")));
+            }
+            let entries: Vec<String> = (start..end)
+                .filter_map(|i| log.entry(i).map(hex::encode))
+                .collect();
+            Ok(json!({ "ok": true, "entries": entries }))
+        }
+        (Method::Post, "/v1/bundles") => {
+            let req: SubmitBundle =
+                serde_json::from_slice(body).map_err(|_| (400, error_json("invalid json")))?;
+            let raw = hex::decode(req.bundle).map_err(|_| (400, error_json("invalid hex")))?;
+            let bundle =
+                PrekeyBundle::decode(&raw).map_err(|_| (400, error_json("invalid bundle")))?;
+            let leaf_index = log.append_bundle(&bundle).map_err(kt)?;
+            Ok(json!({ "ok": true, "leaf_index": leaf_index, "tree_size": log.tree_size() }))
+        }
+        _ => Err((404, error_json("not found"))),
+    }
+}
+
+/// `KtLogEndpoint` over a `KtLogServer` (or anything speaking the same routes).
+#[derive(Debug, Clone)]
+pub struct HttpLogEndpoint {
+    base_url: String,
+}
+
+impl HttpLogEndpoint {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn get(&self, path: &str) -> Result<Value, KtError> {
+        let resp = ureq::get(&format!("{}{}", self.base_url, path))
+            .set("Accept", "application/json")
+            .call()
+            .map_err(|_| KtError::log_fetch_failed())?;
+        resp.into_json::<Value>()
+            .map_err(|_| KtError::log_fetch_failed())
+    }
+
+    fn blob(v: &Value, key: &str) -> Result<Vec<u8>, KtError> {
+        v.get(key)
+            .and_then(Value::as_str)
+            .and_then(|s| hex::decode(s).ok())
+            .ok_or_else(KtError::log_fetch_failed)
+    }
+
+    /// Submit a bundle for logging; returns `(leaf_index, tree_size)`.
+    pub fn submit_bundle(&self, bundle: &PrekeyBundle) -> Result<(u64, u64), KtError> {
+        let wire = bundle
+            .encode()
+            .map_err(|_| KtError::bundle_encode_failed())?;
+        let resp = ureq::post(&format!("{}/v1/bundles", self.base_url))
+            .set("Content-Type", "application/json")
+            .send_json(json!({ "bundle": hex::encode(wire) }))
+            .map_err(|_| KtError::log_fetch_failed())?;
+        let v: Value = resp.into_json().map_err(|_| KtError::log_fetch_failed())?;
+        match (v["leaf_index"].as_u64(), v["tree_size"].as_u64()) {
+            (Some(index), Some(size)) => Ok((index, size)),
+            _ => Err(KtError::log_fetch_failed()),
+        }
+    }
+
+    pub fn get_inclusion_proof(&self, leaf_index: u64, tree_size: u64) -> Result<Vec<u8>, KtError> {
+        let v = self.get(&format!(
+            "/v1/inclusion?index={leaf_index}&size={tree_size}"
+        ))?;
+        Self::blob(&v, "proof")
+    }
+}
+
+impl KtLogEndpoint for HttpLogEndpoint {
+    fn get_sth(&self) -> Result<Vec<u8>, KtError> {
+        Self::blob(&self.get("/v1/sth")?, "sth")
+    }
+
+    fn get_consistency_proof(
+        &self,
+        from_tree_size: u64,
+        to_tree_size: u64,
+    ) -> Result<Vec<u8>, KtError> {
+        let v = self.get(&format!(
+            "/v1/consistency?from={from_tree_size}&to={to_tree_size}"
+        ))?;
+        Self::blob(&v, "proof")
+    }
+}
+
+impl KtEntrySource for HttpLogEndpoint {
+    fn get_entries(&self, start: u64, end: u64) -> Result<Vec<Vec<u8>>, KtError> {
+        let v = self.get(&format!("/v1/entries?start={start}&end={end}"))?;
+        v["entries"]
+            .as_array()
+            .ok_or_else(KtError::log_fetch_failed)?
+            .iter()
+            .map(|e| {
+                e.as_str()
+                    .and_then(|s| hex::decode(s).ok())
+                    .ok_or_else(KtError::log_fetch_failed)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,53 @@
+use clap::Parser;
+use qsl_kt_log::KtLogServer;
+use quantumshield_refimpl::kt::{KtLog, KtTimeSource};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+/// Local reference KT log. In-memory, loopback-only; for tests and interop, not production.
+#[derive(Parser)]
+#[command(name = "qsl-kt-log")]
+struct Args {
+    /// Loopback address to listen on.
+    #[arg(long, default_value = "127.0.0.1:8790")]
+    listen: SocketAddr,
+    /// 32-byte log id, hex.
+    #[arg(long)]
+    log_id: String,
+    /// File holding the 32-byte Ed25519 STH signing seed, hex.
+    #[arg(long)]
+    seed_file: PathBuf,
+}
+
+fn hex32(label: &str, s: &str) -> Result<[u8; 32], String> {
+    hex::decode(s.trim())
+        .ok()
+        .and_then(|v| v.try_into().ok())
+        .ok_or_else(|| format!("{label} must be 32 bytes of hex"))
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let log_id = hex32("--log-id", &args.log_id)?;
+    let seed_hex = std::fs::read_to_string(&args.seed_file)
+        .map_err(|e| format!("read {}: {e}", args.seed_file.display()))?;
+    let seed = hex32("seed file", &seed_hex)?;
+    let log = KtLog::new(log_id, seed, KtTimeSource::System);
+    let verifying_key = log.verifying_key();
+
+    let server = KtLogServer::bind(args.listen, log)?;
+    println!(
+        "qsl-kt-log listening on {}",
+        server.base_url().unwrap_or_default()
+    );
+    println!("log_id={}", hex::encode(log_id));
+    println!("verifying_key={}", hex::encode(verifying_key));
+    server.serve();
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = run(Args::parse()) {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,215 @@
+//! Whole KT path offline: publish bundles to a local `qsl-kt-log`, attach the served
+//! evidence, and verify with `CanonicalKtVerifier`.
+
+use ed25519_dalek::SigningKey;
+use qsl_kt_log::{HttpLogEndpoint, KtLogServer};
+use quantumshield_refimpl::crypto::stdcrypto::{StdCrypto, StdEd25519};
+use quantumshield_refimpl::crypto::traits::{CryptoError, Hash, PqSigMldsa65, SigEd25519};
+use quantumshield_refimpl::kt::{
+    CanonicalKtVerifier, KtEntrySource, KtLog, KtLogEndpoint, KtMonitorState, KtOwnIdentity,
+    KtTimeSource, KtVerification, KtVerifier,
+};
+use quantumshield_refimpl::qsp::{
+    PrekeyBundle, SZ_ED25519_SIG, SZ_MLDSA65_PUB, SZ_MLDSA65_SIG, SZ_MLKEM768_PUB, SZ_X25519_PUB,
+};
+use std::sync::Arc;
+use std::thread;
+
+const LOG_ID: [u8; 32] = [0x4B; 32];
+const BUNDLE_SEED: [u8; 32] = [0x21; 32];
+
+/// Stand-in ML-DSA: the signature is the padded message digest, so only the Ed25519 and
+/// KT layers are under test here.
+struct DigestPqSig;
+
+impl PqSigMldsa65 for DigestPqSig {
+    fn sign(&self, _privk: &[u8], msg: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        let mut sig = msg.to_vec();
+        sig.resize(SZ_MLDSA65_SIG, 0);
+        Ok(sig)
+    }
+
+    fn verify(&self, _pubk: &[u8], msg: &[u8], sig: &[u8]) -> Result<bool, CryptoError> {
+        Ok(sig.len() == SZ_MLDSA65_SIG && sig.starts_with(msg))
+    }
+}
+
+fn bundle(user: &[u8]) -> PrekeyBundle {
+    PrekeyBundle {
+        user_id: user.to_vec(),
+        device_id: 1,
+        valid_from: 1,
+        valid_to: 9,
+        ik_sig_ec_pub: SigningKey::from_bytes(&BUNDLE_SEED)
+            .verifying_key()
+            .to_bytes(),
+        ik_sig_pq_pub: vec![0x42; SZ_MLDSA65_PUB],
+        spk_dh_pub: [0x11; SZ_X25519_PUB],
+        spk_pq_pub: vec![0x22; SZ_MLKEM768_PUB],
+        pq_rcv_id: 3,
+        pq_rcv_pub: vec![0x33; SZ_MLKEM768_PUB],
+        opk_dh: None,
+        opk_pq: None,
+        sig_ec: vec![0; SZ_ED25519_SIG],
+        sig_pq: vec![0; SZ_MLDSA65_SIG],
+        kt_log_id: LOG_ID,
+        kt_sth: Vec::new(),
+        kt_inclusion_proof: Vec::new(),
+        kt_consistency_proof: Vec::new(),
+    }
+}
+
+fn sign(bundle: &mut PrekeyBundle) {
+    let digest = StdCrypto.sha512(&bundle.bundle_tbs());
+    bundle.sig_ec = StdEd25519.sign(&BUNDLE_SEED, &digest);
+    bundle.sig_pq = DigestPqSig.sign(&[], &digest).unwrap();
+}
+
+/// Attach the log's current STH and inclusion proof for `leaf_index`, plus a consistency
+/// proof from `accepted_size` when the tree has grown past it, then re-sign.
+fn attach(
+    endpoint: &HttpLogEndpoint,
+    bundle: &mut PrekeyBundle,
+    leaf_index: u64,
+    accepted_size: Option<u64>,
+) {
+    bundle.kt_sth = endpoint.get_sth().expect("sth");
+    let tree_size = u64::from_be_bytes(bundle.kt_sth[33..41].try_into().unwrap());
+    bundle.kt_inclusion_proof = endpoint
+        .get_inclusion_proof(leaf_index, tree_size)
+        .expect("inclusion");
+    bundle.kt_consistency_proof = match accepted_size {
+        Some(from) if from < tree_size => endpoint
+            .get_consistency_proof(from, tree_size)
+            .expect("consistency"),
+        _ => Vec::new(),
+    };
+    sign(bundle);
+}
+
+fn start_log() -> (
+    Arc<KtLogServer>,
+    thread::JoinHandle<()>,
+    CanonicalKtVerifier,
+) {
+    let log = KtLog::new(LOG_ID, [0x5E; 32], KtTimeSource::System);
+    let verifier = CanonicalKtVerifier::new([log.pinned(300)], KtTimeSource::System, false);
+    let server = Arc::new(KtLogServer::bind("127.0.0.1:0".parse().unwrap(), log).expect("bind"));
+    let serving = Arc::clone(&server);
+    let handle = thread::spawn(move || serving.serve());
+    (server, handle, verifier)
+}
+
+#[test]
+fn bundles_published_to_local_log_verify_end_to_end() {
+    let (server, handle, verifier) = start_log();
+    let endpoint = HttpLogEndpoint::new(&server.base_url().expect("addr"));
+    let ed25519 = StdEd25519;
+
+    let mut alice = bundle(b"alice");
+    let (alice_index, size) = endpoint.submit_bundle(&alice).expect("submit");
+    assert_eq!((alice_index, size), (0, 1));
+    attach(&endpoint, &mut alice, alice_index, None);
+    assert_eq!(
+        verifier.verify_bundle(&alice, &ed25519, &DigestPqSig),
+        Ok(KtVerification::Verified)
+    );
+
+    // The log grows; a later bundle carries a consistency proof from what we accepted.
+    let mut others = Vec::new();
+    for name in [&b"bob"[..], b"carol", b"dave", b"erin"] {
+        let b = bundle(name);
+        let (index, _) = endpoint.submit_bundle(&b).expect("submit");
+        others.push((b, index));
+    }
+    let (mut carol, carol_index) = others.swap_remove(1);
+    attach(&endpoint, &mut carol, carol_index, Some(1));
+    assert_eq!(
+        verifier.verify_bundle(&carol, &ed25519, &DigestPqSig),
+        Ok(KtVerification::Verified)
+    );
+    assert_eq!(verifier.accepted_state(&LOG_ID).unwrap().tree_size, 5);
+
+    // Refreshing straight from the log agrees with what the bundles carried.
+    let refreshed = verifier
+        .refresh_from_log(&LOG_ID, &endpoint, &ed25519)
+        .expect("refresh");
+    assert_eq!(refreshed.tree_size, 5);
+    assert_eq!(
+        endpoint.get_entries(0, 1).expect("entries"),
+        vec![alice.bundle_leaf_data()]
+    );
+
+    // Evidence for the wrong leaf does not verify.
+    let mut wrong = bundle(b"mallory");
+    attach(&endpoint, &mut wrong, carol_index, None);
+    assert_eq!(
+        verifier
+            .verify_bundle(&wrong, &ed25519, &DigestPqSig)
+            .unwrap_err()
+            .detail(),
+        "inclusion_root_mismatch"
+    );
+
+    server.unblock();
+    handle.join().unwrap();
+}
+
+struct Owner;
+
+impl KtOwnIdentity for Owner {
+    fn user_id(&self) -> &[u8] {
+        b"alice"
+    }
+
+    fn minted(&self, bundle: &PrekeyBundle) -> bool {
+        bundle.ik_sig_pq_pub == [0x42; SZ_MLDSA65_PUB]
+    }
+}
+
+#[test]
+fn monitor_over_http_flags_substituted_bundle() {
+    let (server, handle, verifier) = start_log();
+    let endpoint = HttpLogEndpoint::new(&server.base_url().expect("addr"));
+    let ed25519 = StdEd25519;
+
+    endpoint.submit_bundle(&bundle(b"alice")).expect("submit");
+    endpoint.submit_bundle(&bundle(b"bob")).expect("submit");
+    let (state, report) = KtMonitorState::new(LOG_ID)
+        .advance(&verifier, &endpoint, &ed25519, &Owner)
+        .expect("first pass");
+    assert_eq!(report.own, vec![0]);
+    assert!(report.alerts.is_empty());
+
+    let mut substituted = bundle(b"alice");
+    substituted.ik_sig_pq_pub = vec![0x66; SZ_MLDSA65_PUB];
+    let (index, _) = endpoint.submit_bundle(&substituted).expect("submit");
+    let (state, report) = state
+        .advance(&verifier, &endpoint, &ed25519, &Owner)
+        .expect("second pass");
+    assert_eq!(state.tree_size(), 3);
+    assert_eq!(report.alerts.len(), 1);
+    assert_eq!(report.alerts[0].leaf_index, index);
+
+    server.unblock();
+    handle.join().unwrap();
+}
+
+#[test]
+fn bad_requests_are_refused() {
+    let (server, handle, _) = start_log();
+    let endpoint = HttpLogEndpoint::new(&server.base_url().expect("addr"));
+    assert_eq!(endpoint.get_sth().unwrap_err().detail(), "log_fetch_failed");
+    let mut other = bundle(b"alice");
+    other.kt_log_id = [0x01; 32];
+    assert_eq!(
+        endpoint.submit_bundle(&other).unwrap_err().detail(),
+        "log_fetch_failed"
+    );
+    assert_eq!(
+        endpoint.get_consistency_proof(1, 1).unwrap_err().detail(),
+        "log_fetch_failed"
+    );
+    server.unblock();
+    handle.join().unwrap();
+}
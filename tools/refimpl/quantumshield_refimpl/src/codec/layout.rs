@@ -0,0 +1,472 @@
+//! Declarative canonical layouts.
+//!
+//! A wire struct is declared once with `canonical_struct!`, each field annotated with the
+//! layout kind it is carried as (`pub ct1: Vec<u8> as Raw<SZ_MLKEM768_CT>`). The macro
+//! emits the struct and a `CanonicalCodec` impl whose encoder and decoder walk the same
+//! field list, so the two cannot drift apart; `decode_canonical` rejects trailing bytes.
+//!
+//! Per-field options, in this order, before the field:
+//! - `#[when(dep)]` / `#[when(dep & MASK)]`: an `Option<T>` field present only when an
+//!   earlier `bool` field is set, or when `MASK` bits of an earlier integer field are set.
+//!   Encoding fails closed (`Invalid("<field>_missing")`) if the field is absent although
+//!   the condition holds; a value present while the condition is clear is not written.
+//! - `#[check(expr, "detail")]`, repeatable: evaluated in order right after the field is
+//!   read, with it and all earlier fields in scope; `false` rejects with
+//!   `CodecError::Invalid("detail")`. Putting a check on a later field delays it, which is
+//!   how a layout keeps a hand-written decoder's reject order.
+//!
+//! Encoding checks what decoding would: a `Raw<N>` value that is not `N` bytes, or a
+//! length-prefixed value or list too long for its prefix, fails with `LengthOutOfRange`
+//! instead of producing a well-formed but wrong encoding.
+
+use super::{CodecError, Reader, Writer};
+
+/// How a value of type `T` is laid out on the wire.
+pub trait FieldCodec<T> {
+    fn write(w: &mut Writer, v: &T) -> Result<(), CodecError>;
+    fn read(r: &mut Reader<'_>) -> Result<T, CodecError>;
+}
+
+/// A struct with a canonical layout; implemented by `canonical_struct!`.
+pub trait CanonicalCodec: Sized {
+    fn write_canonical(&self, w: &mut Writer) -> Result<(), CodecError>;
+    fn read_canonical(r: &mut Reader<'_>) -> Result<Self, CodecError>;
+
+    fn encode_canonical(&self) -> Result<Vec<u8>, CodecError> {
+        let mut w = Writer::new();
+        self.write_canonical(&mut w)?;
+        Ok(w.into_vec())
+    }
+
+    /// Decode a whole buffer; trailing bytes are rejected.
+    fn decode_canonical(buf: &[u8]) -> Result<Self, CodecError> {
+        let mut r = Reader::new(buf);
+        let v = Self::read_canonical(&mut r)?;
+        r.finish()?;
+        Ok(v)
+    }
+}
+
+/// `u8`.
+pub struct U8;
+/// Big-endian `u16`.
+pub struct U16;
+/// Big-endian `u32`.
+pub struct U32;
+/// Big-endian `u64`.
+pub struct U64;
+/// `bool` as a `u16`; any non-zero value reads as `true`.
+pub struct Bool16;
+/// `[u8; N]`, length implied by the type.
+pub struct Fixed;
+/// `Vec<u8>` of exactly `N` bytes on read; written as-is.
+pub struct Raw<const N: usize>;
+/// `varbytes<u16>`.
+pub struct VarU16;
+/// `varbytes<u32>`.
+pub struct VarU32;
+/// `u16 count || count * K`.
+pub struct List16<K>(core::marker::PhantomData<K>);
+/// `u16 present || K?`; any non-zero presence value reads as present.
+pub struct Opt16<K>(core::marker::PhantomData<K>);
+/// `u16 present || K?`; presence must be exactly 0 or 1.
+pub struct Opt16Strict<K>(core::marker::PhantomData<K>);
+
+impl FieldCodec<u8> for U8 {
+    fn write(w: &mut Writer, v: &u8) -> Result<(), CodecError> {
+        w.write_u8(*v);
+        Ok(())
+    }
+    fn read(r: &mut Reader<'_>) -> Result<u8, CodecError> {
+        r.read_u8()
+    }
+}
+
+impl FieldCodec<u16> for U16 {
+    fn write(w: &mut Writer, v: &u16) -> Result<(), CodecError> {
+        w.write_u16(*v);
+        Ok(())
+    }
+    fn read(r: &mut Reader<'_>) -> Result<u16, CodecError> {
+        r.read_u16()
+    }
+}
+
+impl FieldCodec<u32> for U32 {
+    fn write(w: &mut Writer, v: &u32) -> Result<(), CodecError> {
+        w.write_u32(*v);
+        Ok(())
+    }
+    fn read(r: &mut Reader<'_>) -> Result<u32, CodecError> {
+        r.read_u32()
+    }
+}
+
+impl FieldCodec<u64> for U64 {
+    fn write(w: &mut Writer, v: &u64) -> Result<(), CodecError> {
+        w.write_u64(*v);
+        Ok(())
+    }
+    fn read(r: &mut Reader<'_>) -> Result<u64, CodecError> {
+        r.read_u64()
+    }
+}
+
+impl FieldCodec<bool> for Bool16 {
+    fn write(w: &mut Writer, v: &bool) -> Result<(), CodecError> {
+        w.write_u16(*v as u16);
+        Ok(())
+    }
+    fn read(r: &mut Reader<'_>) -> Result<bool, CodecError> {
+        Ok(r.read_u16()? != 0)
+    }
+}
+
+impl<const N: usize> FieldCodec<[u8; N]> for Fixed {
+    fn write(w: &mut Writer, v: &[u8; N]) -> Result<(), CodecError> {
+        w.write_bytes(v);
+        Ok(())
+    }
+    fn read(r: &mut Reader<'_>) -> Result<[u8; N], CodecError> {
+        r.read_exact::<N>()
+    }
+}
+
+impl<const N: usize> FieldCodec<Vec<u8>> for Raw<N> {
+    fn write(w: &mut Writer, v: &Vec<u8>) -> Result<(), CodecError> {
+        if v.len() != N {
+            return Err(CodecError::LengthOutOfRange);
+        }
+        w.write_bytes(v);
+        Ok(())
+    }
+    fn read(r: &mut Reader<'_>) -> Result<Vec<u8>, CodecError> {
+        r.read_bytes(N)
+    }
+}
+
+impl FieldCodec<Vec<u8>> for VarU16 {
+    fn write(w: &mut Writer, v: &Vec<u8>) -> Result<(), CodecError> {
+        u16::try_from(v.len()).map_err(|_| CodecError::LengthOutOfRange)?;
+        w.write_varbytes_u16(v);
+        Ok(())
+    }
+    fn read(r: &mut Reader<'_>) -> Result<Vec<u8>, CodecError> {
+        r.read_varbytes_u16()
+    }
+}
+
+impl FieldCodec<Vec<u8>> for VarU32 {
+    fn write(w: &mut Writer, v: &Vec<u8>) -> Result<(), CodecError> {
+        u32::try_from(v.len()).map_err(|_| CodecError::LengthOutOfRange)?;
+        w.write_varbytes_u32(v);
+        Ok(())
+    }
+    fn read(r: &mut Reader<'_>) -> Result<Vec<u8>, CodecError> {
+        r.read_varbytes_u32()
+    }
+}
+
+impl<T, K: FieldCodec<T>> FieldCodec<Vec<T>> for List16<K> {
+    fn write(w: &mut Writer, v: &Vec<T>) -> Result<(), CodecError> {
+        let count = u16::try_from(v.len()).map_err(|_| CodecError::LengthOutOfRange)?;
+        w.write_u16(count);
+        v.iter().try_for_each(|item| K::write(w, item))
+    }
+    fn read(r: &mut Reader<'_>) -> Result<Vec<T>, CodecError> {
+        let count = r.read_u16()? as usize;
+        (0..count).map(|_| K::read(r)).collect()
+    }
+}
+
+impl<T, K: FieldCodec<T>> FieldCodec<Option<T>> for Opt16<K> {
+    fn write(w: &mut Writer, v: &Option<T>) -> Result<(), CodecError> {
+        w.write_u16(v.is_some() as u16);
+        match v {
+            Some(v) => K::write(w, v),
+            None => Ok(()),
+        }
+    }
+    fn read(r: &mut Reader<'_>) -> Result<Option<T>, CodecError> {
+        if r.read_u16()? != 0 {
+            Ok(Some(K::read(r)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<T, K: FieldCodec<T>> FieldCodec<Option<T>> for Opt16Strict<K> {
+    fn write(w: &mut Writer, v: &Option<T>) -> Result<(), CodecError> {
+        Opt16::<K>::write(w, v)
+    }
+    fn read(r: &mut Reader<'_>) -> Result<Option<T>, CodecError> {
+        match r.read_u16()? {
+            0 => Ok(None),
+            1 => Ok(Some(K::read(r)?)),
+            _ => Err(CodecError::Invalid("opt_present")),
+        }
+    }
+}
+
+impl<A, B, KA: FieldCodec<A>, KB: FieldCodec<B>> FieldCodec<(A, B)> for (KA, KB) {
+    fn write(w: &mut Writer, v: &(A, B)) -> Result<(), CodecError> {
+        KA::write(w, &v.0)?;
+        KB::write(w, &v.1)
+    }
+    fn read(r: &mut Reader<'_>) -> Result<(A, B), CodecError> {
+        Ok((KA::read(r)?, KB::read(r)?))
+    }
+}
+
+macro_rules! canonical_struct {
+    (@cond $v:expr) => {
+        $v
+    };
+    (@cond $v:expr, $mask:expr) => {
+        ($v & $mask) != 0
+    };
+
+    (@write $w:ident, $kind:ty, $v:expr, if $cond:expr, $name:ident) => {
+        if $cond {
+            match $v {
+                Some(v) => <$kind as $crate::codec::FieldCodec<_>>::write($w, v)?,
+                None => {
+                    return Err($crate::codec::CodecError::Invalid(concat!(
+                        stringify!($name),
+                        "_missing"
+                    )))
+                }
+            }
+        }
+    };
+    (@write $w:ident, $kind:ty, $v:expr, $name:ident) => {
+        <$kind as $crate::codec::FieldCodec<_>>::write($w, $v)?
+    };
+
+    (@read $r:ident, $kind:ty, if $cond:expr) => {
+        if $cond {
+            Some(<$kind as $crate::codec::FieldCodec<_>>::read($r)?)
+        } else {
+            None
+        }
+    };
+    (@read $r:ident, $kind:ty) => {
+        <$kind as $crate::codec::FieldCodec<_>>::read($r)?
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[when($dep:ident $(& $mask:expr)?)])?
+                $(#[check($check:expr, $why:literal)])*
+                $fvis:vis $field:ident : $ty:ty as $kind:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $($fvis $field: $ty,)*
+        }
+
+        impl $crate::codec::CanonicalCodec for $name {
+            fn write_canonical(
+                &self,
+                w: &mut $crate::codec::Writer,
+            ) -> Result<(), $crate::codec::CodecError> {
+                $(
+                    $crate::codec::canonical_struct!(
+                        @write w, $kind, &self.$field
+                        $(, if $crate::codec::canonical_struct!(@cond self.$dep $(, $mask)?))?,
+                        $field
+                    );
+                )*
+                Ok(())
+            }
+
+            fn read_canonical(
+                r: &mut $crate::codec::Reader<'_>,
+            ) -> Result<Self, $crate::codec::CodecError> {
+                $(
+                    let $field = $crate::codec::canonical_struct!(
+                        @read r, $kind
+                        $(, if $crate::codec::canonical_struct!(@cond $dep $(, $mask)?))?
+                    );
+                    $(
+                        if !($check) {
+                            return Err($crate::codec::CodecError::Invalid($why));
+                        }
+                    )*
+                )*
+                Ok(Self { $($field),* })
+            }
+        }
+    };
+
+}
+
+pub(crate) use canonical_struct;
+
+#[cfg(test)]
+mod tests {
+    use super::super::{CodecError, Writer};
+    use super::*;
+
+    const FLAG_EXTRA: u16 = 0x0001;
+
+    canonical_struct! {
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        struct Sample {
+            #[check(version == 1, "version")]
+            version: u8 as U8,
+            flags: u16 as U16,
+            tag: [u8; 4] as Fixed,
+            name: Vec<u8> as VarU16,
+            #[when(flags & FLAG_EXTRA)]
+            extra: Option<(u32, Vec<u8>)> as (U32, Raw<3>),
+            has_tail: bool as Bool16,
+            #[when(has_tail)]
+            tail: Option<u64> as U64,
+            nodes: Vec<[u8; 2]> as List16<Fixed>,
+            opt: Option<u32> as Opt16Strict<U32>,
+        }
+    }
+
+    fn sample() -> Sample {
+        Sample {
+            version: 1,
+            flags: FLAG_EXTRA,
+            tag: *b"QSLx",
+            name: b"alice".to_vec(),
+            extra: Some((7, vec![1, 2, 3])),
+            has_tail: true,
+            tail: Some(u64::MAX),
+            nodes: vec![[1, 2], [3, 4]],
+            opt: Some(9),
+        }
+    }
+
+    #[test]
+    fn layout_round_trips_and_matches_hand_encoding() {
+        let s = sample();
+        let bytes = s.encode_canonical().expect("encode");
+        let mut w = Writer::new();
+        w.write_u8(1);
+        w.write_u16(FLAG_EXTRA);
+        w.write_bytes(b"QSLx");
+        w.write_varbytes_u16(b"alice");
+        w.write_u32(7);
+        w.write_bytes(&[1, 2, 3]);
+        w.write_u16(1);
+        w.write_u64(u64::MAX);
+        w.write_u16(2);
+        w.write_bytes(&[1, 2, 3, 4]);
+        w.write_u16(1);
+        w.write_u32(9);
+        assert_eq!(bytes, w.into_vec());
+        assert_eq!(Sample::decode_canonical(&bytes).expect("decode"), s);
+    }
+
+    #[test]
+    fn conditional_fields_follow_their_condition() {
+        let mut s = sample();
+        s.flags = 0;
+        s.has_tail = false;
+        let bytes = s.encode_canonical().expect("encode");
+        let back = Sample::decode_canonical(&bytes).expect("decode");
+        assert_eq!(back.extra, None);
+        assert_eq!(back.tail, None);
+
+        let mut missing = sample();
+        missing.tail = None;
+        assert!(matches!(
+            missing.encode_canonical(),
+            Err(CodecError::Invalid("tail_missing"))
+        ));
+    }
+
+    #[test]
+    fn encode_rejects_lengths_its_decoder_could_not_read_back() {
+        let mut list = sample();
+        list.nodes = vec![[0, 0]; usize::from(u16::MAX) + 1];
+        assert!(matches!(
+            list.encode_canonical(),
+            Err(CodecError::LengthOutOfRange)
+        ));
+        list.nodes.pop();
+        assert!(list.encode_canonical().is_ok());
+
+        let mut raw = sample();
+        raw.extra = Some((7, vec![1, 2]));
+        assert!(matches!(
+            raw.encode_canonical(),
+            Err(CodecError::LengthOutOfRange)
+        ));
+
+        let mut var = sample();
+        var.name = vec![0; usize::from(u16::MAX) + 1];
+        assert!(matches!(
+            var.encode_canonical(),
+            Err(CodecError::LengthOutOfRange)
+        ));
+    }
+
+    canonical_struct! {
+        #[derive(Debug)]
+        struct Ordered {
+            a: u8 as U8,
+            #[check(a == 1, "a")]
+            #[check(b == 2, "b")]
+            b: u8 as U8,
+        }
+    }
+
+    #[test]
+    fn repeated_checks_run_in_order_after_their_field() {
+        assert!(Ordered::decode_canonical(&[1, 2]).is_ok());
+        // `a` is only judged once `b` is read, so a short buffer is still truncated.
+        assert!(matches!(
+            Ordered::decode_canonical(&[9]),
+            Err(CodecError::Truncated)
+        ));
+        assert!(matches!(
+            Ordered::decode_canonical(&[9, 9]),
+            Err(CodecError::Invalid("a"))
+        ));
+        assert!(matches!(
+            Ordered::decode_canonical(&[1, 9]),
+            Err(CodecError::Invalid("b"))
+        ));
+    }
+
+    #[test]
+    fn decode_is_strict() {
+        let bytes = sample().encode_canonical().expect("encode");
+        for end in 0..bytes.len() {
+            assert!(
+                Sample::decode_canonical(&bytes[..end]).is_err(),
+                "len {end}"
+            );
+        }
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            Sample::decode_canonical(&trailing),
+            Err(CodecError::TrailingBytes)
+        ));
+        let mut version = bytes.clone();
+        version[0] = 2;
+        assert!(matches!(
+            Sample::decode_canonical(&version),
+            Err(CodecError::Invalid("version"))
+        ));
+        let mut presence = bytes;
+        let at = presence.len() - 6;
+        presence[at + 1] = 2;
+        assert!(matches!(
+            Sample::decode_canonical(&presence),
+            Err(CodecError::Invalid("opt_present"))
+        ));
+    }
+}
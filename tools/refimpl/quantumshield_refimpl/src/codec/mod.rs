@@ -4,6 +4,24 @@
 //! - varbytes<u16> = u16 len || len bytes
 //! - varbytes<u32> = u32 len || len bytes
 //! - trailing bytes are rejected by message-specific decoders.
+//!
+//! `Reader` hands out either owned copies (`read_bytes`, `read_varbytes_*`) or slices borrowed
+//! from the input (`read_slice`, `read_varbytes_*_ref`, `read_rest`). The borrowed forms back
+//! the zero-copy view types (`EnvelopeRef`, `Suite2ParsedRatchetMsgRef`); the owned forms are
+//! thin copies over them, so both apply identical bounds checks.
+//!
+//! Fixed-layout wire structs are declared with `canonical_struct!` (see `layout`), which
+//! generates the encoder and the strict decoder from one field list. Layouts whose lengths
+//! come from elsewhere (bucket-padded envelopes, the Suite-2 prefix) stay hand-written on
+//! `Reader`.
+
+mod layout;
+
+pub(crate) use layout::canonical_struct;
+pub use layout::{
+    Bool16, CanonicalCodec, FieldCodec, Fixed, List16, Opt16, Opt16Strict, Raw, VarU16, VarU32,
+    U16, U32, U64, U8,
+};
 
 use thiserror::Error;
 
@@ -42,6 +60,14 @@ impl<'a> Reader<'a> {
         Ok(s)
     }
 
+    /// Bytes consumed so far.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, CodecError> {
+        Ok(self.take(1)?[0])
+    }
     pub fn read_u16(&mut self) -> Result<u16, CodecError> {
         let b = self.take(2)?;
         Ok(u16::from_be_bytes([b[0], b[1]]))
@@ -50,8 +76,21 @@ impl<'a> Reader<'a> {
         let b = self.take(4)?;
         Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
     }
+    pub fn read_u64(&mut self) -> Result<u64, CodecError> {
+        Ok(u64::from_be_bytes(self.read_exact::<8>()?))
+    }
     pub fn read_bytes(&mut self, n: usize) -> Result<Vec<u8>, CodecError> {
-        Ok(self.take(n)?.to_vec())
+        Ok(self.read_slice(n)?.to_vec())
+    }
+    /// Borrowing `read_bytes`: the next `n` bytes, without copying.
+    pub fn read_slice(&mut self, n: usize) -> Result<&'a [u8], CodecError> {
+        self.take(n)
+    }
+    /// Everything not yet consumed; the reader is left finished.
+    pub fn read_rest(&mut self) -> &'a [u8] {
+        let s = &self.buf[self.pos.min(self.buf.len())..];
+        self.pos = self.buf.len();
+        s
     }
     pub fn read_exact<const N: usize>(&mut self) -> Result<[u8; N], CodecError> {
         let b = self.take(N)?;
@@ -60,18 +99,24 @@ impl<'a> Reader<'a> {
         Ok(out)
     }
     pub fn read_varbytes_u16(&mut self) -> Result<Vec<u8>, CodecError> {
-        let len = self.read_u16()? as usize;
-        if self.remaining() < len {
-            return Err(CodecError::LengthOutOfRange);
-        }
-        self.read_bytes(len)
+        Ok(self.read_varbytes_u16_ref()?.to_vec())
     }
     pub fn read_varbytes_u32(&mut self) -> Result<Vec<u8>, CodecError> {
+        Ok(self.read_varbytes_u32_ref()?.to_vec())
+    }
+    pub fn read_varbytes_u16_ref(&mut self) -> Result<&'a [u8], CodecError> {
+        let len = self.read_u16()? as usize;
+        self.read_len_prefixed(len)
+    }
+    pub fn read_varbytes_u32_ref(&mut self) -> Result<&'a [u8], CodecError> {
         let len = self.read_u32()? as usize;
+        self.read_len_prefixed(len)
+    }
+    fn read_len_prefixed(&mut self, len: usize) -> Result<&'a [u8], CodecError> {
         if self.remaining() < len {
             return Err(CodecError::LengthOutOfRange);
         }
-        self.read_bytes(len)
+        self.take(len)
     }
     pub fn finish(&self) -> Result<(), CodecError> {
         if self.remaining() != 0 {
@@ -94,12 +139,18 @@ impl Writer {
     pub fn into_vec(self) -> Vec<u8> {
         self.buf
     }
+    pub fn write_u8(&mut self, v: u8) {
+        self.buf.push(v);
+    }
     pub fn write_u16(&mut self, v: u16) {
         self.buf.extend_from_slice(&v.to_be_bytes());
     }
     pub fn write_u32(&mut self, v: u32) {
         self.buf.extend_from_slice(&v.to_be_bytes());
     }
+    pub fn write_u64(&mut self, v: u64) {
+        self.buf.extend_from_slice(&v.to_be_bytes());
+    }
     pub fn write_bytes(&mut self, b: &[u8]) {
         self.buf.extend_from_slice(b);
     }
@@ -6,7 +6,13 @@
 //! - X25519
 //! - Ed25519 signatures
 //! - ML-KEM-768, ML-DSA-65 (interfaces here; algorithm binding must be validated for your chosen PQ library)
+//!
+//! Implementations are grouped into named `provider::CryptoProvider`s and selected through
+//! `provider::ProviderRegistry`; `conformance` holds the checks every provider must pass.
 
+pub mod conformance;
+pub mod provider;
 #[cfg(feature = "stdcrypto")]
 pub mod stdcrypto;
+pub mod stream;
 pub mod traits;
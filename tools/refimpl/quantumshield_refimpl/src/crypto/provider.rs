@@ -0,0 +1,322 @@
+//! Named crypto-provider registry.
+//!
+//! A `CryptoProvider` bundles one implementation of every primitive trait in
+//! `crypto::traits` under a stable name, so callers can select a backend at runtime
+//! (e.g. to benchmark or cross-validate a second implementation) instead of hard-wiring
+//! `StdCrypto`. Every provider is expected to pass `crypto::conformance::check_provider`.
+//!
+//! PQ primitives are optional: a provider built without them reports `None`, and
+//! `handshake_deps` fails closed rather than substituting a stub.
+
+use super::traits::{
+    Aead, Hash, Kmac, PqKem1024, PqKem768, PqSigMldsa65, PqSigMldsa87, SigEd25519, X25519Dh,
+};
+use crate::kt::KtVerifier;
+use crate::qsp::HandshakeDeps;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Name under which the built-in `StdCrypto`/`StdEd25519` backend is registered.
+pub const STD_PROVIDER_NAME: &str = "std";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProviderError {
+    #[error("unknown crypto provider: {0}")]
+    Unknown(String),
+    #[error("crypto provider already registered: {0}")]
+    Duplicate(&'static str),
+    #[error("invalid crypto provider name")]
+    InvalidName,
+    #[error("crypto provider {provider} lacks required primitive {primitive}")]
+    MissingPrimitive {
+        provider: &'static str,
+        primitive: &'static str,
+    },
+}
+
+/// One backend for the full primitive set used by QSP/Suite-2.
+pub trait CryptoProvider: Send + Sync {
+    /// Stable registry name (ASCII, non-empty).
+    fn name(&self) -> &'static str;
+    fn hash(&self) -> &dyn Hash;
+    fn kmac(&self) -> &dyn Kmac;
+    fn aead(&self) -> &dyn Aead;
+    fn x25519(&self) -> &dyn X25519Dh;
+    fn ed25519(&self) -> &dyn SigEd25519;
+
+    /// ML-KEM-768, if this provider implements it.
+    fn pq_kem(&self) -> Option<&dyn PqKem768> {
+        None
+    }
+
+    /// ML-DSA-65, if this provider implements it.
+    fn pq_sig(&self) -> Option<&dyn PqSigMldsa65> {
+        None
+    }
+
+    /// Fresh ML-KEM-768 `(public, secret)` keypair in this provider's encoding.
+    ///
+    /// The traits carry no keygen; conformance needs one to exercise encap/decap.
+    fn pq_kem_keypair(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        None
+    }
+
+    /// Fresh ML-DSA-65 `(public, secret)` keypair in this provider's encoding.
+    fn pq_sig_keypair(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        None
+    }
+
+    /// ML-KEM-1024 (category 5). No Suite-2 suite uses it yet.
+    fn pq_kem1024(&self) -> Option<&dyn PqKem1024> {
+        None
+    }
+
+    /// Fresh ML-KEM-1024 `(public, secret)` keypair in this provider's encoding.
+    fn pq_kem1024_keypair(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        None
+    }
+
+    /// ML-DSA-87 (category 5). No Suite-2 suite uses it yet.
+    fn pq_sig87(&self) -> Option<&dyn PqSigMldsa87> {
+        None
+    }
+}
+
+/// Build `HandshakeDeps` from a provider. Fails closed when a PQ primitive is absent.
+pub fn handshake_deps<'a>(
+    provider: &'a dyn CryptoProvider,
+    kt: &'a dyn KtVerifier,
+) -> Result<HandshakeDeps<'a>, ProviderError> {
+    let pq_kem = provider.pq_kem().ok_or(ProviderError::MissingPrimitive {
+        provider: provider.name(),
+        primitive: "ML-KEM-768",
+    })?;
+    let pq_sig = provider.pq_sig().ok_or(ProviderError::MissingPrimitive {
+        provider: provider.name(),
+        primitive: "ML-DSA-65",
+    })?;
+    Ok(HandshakeDeps {
+        hash: provider.hash(),
+        kmac: provider.kmac(),
+        dh: provider.x25519(),
+        aead: provider.aead(),
+        ed25519: provider.ed25519(),
+        pq_kem,
+        pq_sig,
+        kt,
+    })
+}
+
+/// The built-in provider: `StdCrypto` plus `StdEd25519`.
+#[cfg(feature = "stdcrypto")]
+pub struct StdProvider {
+    crypto: super::stdcrypto::StdCrypto,
+    ed25519: super::stdcrypto::StdEd25519,
+}
+
+#[cfg(feature = "stdcrypto")]
+impl StdProvider {
+    pub fn new() -> Self {
+        Self {
+            crypto: super::stdcrypto::StdCrypto,
+            ed25519: super::stdcrypto::StdEd25519,
+        }
+    }
+}
+
+#[cfg(feature = "stdcrypto")]
+impl Default for StdProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "stdcrypto")]
+impl CryptoProvider for StdProvider {
+    fn name(&self) -> &'static str {
+        STD_PROVIDER_NAME
+    }
+    fn hash(&self) -> &dyn Hash {
+        &self.crypto
+    }
+    fn kmac(&self) -> &dyn Kmac {
+        &self.crypto
+    }
+    fn aead(&self) -> &dyn Aead {
+        &self.crypto
+    }
+    fn x25519(&self) -> &dyn X25519Dh {
+        &self.crypto
+    }
+    fn ed25519(&self) -> &dyn SigEd25519 {
+        &self.ed25519
+    }
+
+    #[cfg(feature = "pqkem")]
+    fn pq_kem(&self) -> Option<&dyn PqKem768> {
+        Some(&self.crypto)
+    }
+
+    #[cfg(feature = "pqcrypto")]
+    fn pq_sig(&self) -> Option<&dyn PqSigMldsa65> {
+        Some(&self.crypto)
+    }
+
+    #[cfg(feature = "pqkem")]
+    fn pq_kem_keypair(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        Some(super::stdcrypto::runtime_pq_kem_keypair())
+    }
+
+    #[cfg(feature = "pqcrypto")]
+    fn pq_sig_keypair(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        Some(super::stdcrypto::runtime_pq_sig_keypair())
+    }
+
+    #[cfg(feature = "pqkem")]
+    fn pq_kem1024(&self) -> Option<&dyn PqKem1024> {
+        Some(&self.crypto)
+    }
+
+    #[cfg(feature = "pqkem")]
+    fn pq_kem1024_keypair(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        Some(super::stdcrypto::runtime_pq_kem1024_keypair())
+    }
+
+    #[cfg(feature = "pqcrypto")]
+    fn pq_sig87(&self) -> Option<&dyn PqSigMldsa87> {
+        Some(&self.crypto)
+    }
+}
+
+/// Name-keyed set of providers. Iteration order is by name, so reports are stable.
+#[derive(Clone, Default)]
+pub struct ProviderRegistry {
+    providers: BTreeMap<&'static str, Arc<dyn CryptoProvider>>,
+}
+
+impl ProviderRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry pre-populated with every provider compiled into this crate.
+    pub fn with_builtin() -> Self {
+        #[allow(unused_mut)]
+        let mut reg = Self::new();
+        #[cfg(feature = "stdcrypto")]
+        reg.providers
+            .insert(STD_PROVIDER_NAME, Arc::new(StdProvider::new()));
+        reg
+    }
+
+    /// Add a provider. Names are unique; re-registering a name is rejected, not replaced.
+    pub fn register(&mut self, provider: Arc<dyn CryptoProvider>) -> Result<(), ProviderError> {
+        let name = provider.name();
+        if name.is_empty() || !name.bytes().all(|b| b.is_ascii_graphic()) {
+            return Err(ProviderError::InvalidName);
+        }
+        if self.providers.contains_key(name) {
+            return Err(ProviderError::Duplicate(name));
+        }
+        self.providers.insert(name, provider);
+        Ok(())
+    }
+
+    /// Look up a provider by name.
+    pub fn get(&self, name: &str) -> Result<Arc<dyn CryptoProvider>, ProviderError> {
+        self.providers
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ProviderError::Unknown(name.to_string()))
+    }
+
+    /// Registered names, sorted.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.providers.keys().copied().collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn CryptoProvider>> {
+        self.providers.values()
+    }
+}
+
+#[cfg(all(test, feature = "stdcrypto"))]
+mod tests {
+    use super::*;
+
+    struct Renamed(StdProvider, &'static str);
+    impl CryptoProvider for Renamed {
+        fn name(&self) -> &'static str {
+            self.1
+        }
+        fn hash(&self) -> &dyn Hash {
+            self.0.hash()
+        }
+        fn kmac(&self) -> &dyn Kmac {
+            self.0.kmac()
+        }
+        fn aead(&self) -> &dyn Aead {
+            self.0.aead()
+        }
+        fn x25519(&self) -> &dyn X25519Dh {
+            self.0.x25519()
+        }
+        fn ed25519(&self) -> &dyn SigEd25519 {
+            self.0.ed25519()
+        }
+    }
+
+    #[test]
+    fn builtin_registry_exposes_std_by_name() {
+        let reg = ProviderRegistry::with_builtin();
+        assert_eq!(reg.names(), vec![STD_PROVIDER_NAME]);
+        assert_eq!(
+            reg.get(STD_PROVIDER_NAME).unwrap().name(),
+            STD_PROVIDER_NAME
+        );
+    }
+
+    #[test]
+    fn unknown_name_is_rejected() {
+        let reg = ProviderRegistry::with_builtin();
+        assert_eq!(
+            reg.get("nope").err(),
+            Some(ProviderError::Unknown("nope".to_string()))
+        );
+    }
+
+    #[test]
+    fn duplicate_and_invalid_names_are_rejected() {
+        let mut reg = ProviderRegistry::with_builtin();
+        assert_eq!(
+            reg.register(Arc::new(StdProvider::new())),
+            Err(ProviderError::Duplicate(STD_PROVIDER_NAME))
+        );
+        assert_eq!(
+            reg.register(Arc::new(Renamed(StdProvider::new(), ""))),
+            Err(ProviderError::InvalidName)
+        );
+        assert_eq!(
+            reg.register(Arc::new(Renamed(StdProvider::new(), "has space"))),
+            Err(ProviderError::InvalidName)
+        );
+        reg.register(Arc::new(Renamed(StdProvider::new(), "alt")))
+            .unwrap();
+        assert_eq!(reg.names(), vec!["alt", STD_PROVIDER_NAME]);
+    }
+
+    #[test]
+    fn handshake_deps_fails_closed_without_pq() {
+        let p = Renamed(StdProvider::new(), "nopq");
+        let kt = crate::kt::CanonicalKtVerifier::disabled_nonproduction();
+        assert_eq!(
+            handshake_deps(&p, &kt).err(),
+            Some(ProviderError::MissingPrimitive {
+                provider: "nopq",
+                primitive: "ML-KEM-768",
+            })
+        );
+    }
+}
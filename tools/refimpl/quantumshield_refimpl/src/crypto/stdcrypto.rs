@@ -1,7 +1,8 @@
 //! Standard-crypto implementations (non-PQ).
 //!
 //! These are convenience wrappers. For the PQ primitives, use the `PqKem768` and `PqSigMldsa65` traits
-//! and provide an implementation suitable for your environment.
+//! (or `PqKem1024` / `PqSigMldsa87` for category 5) and provide an implementation suitable
+//! for your environment.
 
 use super::traits::*;
 use aes_gcm::{
@@ -36,6 +37,29 @@ fn ml_kem768_ek_from_bytes(pubk: &[u8]) -> Result<MlKem768Ek, CryptoError> {
     Ok(MlKem768Ek::from_bytes(&enc))
 }
 
+#[cfg(feature = "pqkem")]
+type MlKem1024Dk = ml_kem::kem::DecapsulationKey<ml_kem::MlKem1024Params>;
+#[cfg(feature = "pqkem")]
+type MlKem1024Ek = ml_kem::kem::EncapsulationKey<ml_kem::MlKem1024Params>;
+
+#[cfg(feature = "pqkem")]
+fn ml_kem1024_dk_from_bytes(privk: &[u8]) -> Result<MlKem1024Dk, CryptoError> {
+    use ml_kem::EncodedSizeUser as _;
+
+    let enc =
+        ml_kem::Encoded::<MlKem1024Dk>::try_from(privk).map_err(|_| CryptoError::InvalidKey)?;
+    Ok(MlKem1024Dk::from_bytes(&enc))
+}
+
+#[cfg(feature = "pqkem")]
+fn ml_kem1024_ek_from_bytes(pubk: &[u8]) -> Result<MlKem1024Ek, CryptoError> {
+    use ml_kem::EncodedSizeUser as _;
+
+    let enc =
+        ml_kem::Encoded::<MlKem1024Ek>::try_from(pubk).map_err(|_| CryptoError::InvalidKey)?;
+    Ok(MlKem1024Ek::from_bytes(&enc))
+}
+
 impl StdCrypto {
     fn seal_inner(
         &self,
@@ -124,6 +148,38 @@ pub fn runtime_pq_sig_keypair() -> (Vec<u8>, Vec<u8>) {
     )
 }
 
+/// Fresh ML-KEM-1024 `(public, secret)` keypair (category 5).
+#[cfg(feature = "pqkem")]
+pub fn runtime_pq_kem1024_keypair() -> (Vec<u8>, Vec<u8>) {
+    use ml_kem::EncodedSizeUser as _;
+    use ml_kem::KemCore as _;
+
+    let (sk, pk) = ml_kem::MlKem1024::generate(&mut OsRng);
+    let pk = pk.as_bytes();
+    let sk = sk.as_bytes();
+    let pk_bytes: &[u8] = pk.as_ref();
+    let sk_bytes: &[u8] = sk.as_ref();
+    (pk_bytes.to_vec(), sk_bytes.to_vec())
+}
+
+/// Fresh ML-DSA-87 `(public, expanded secret)` keypair (category 5).
+#[cfg(feature = "pqcrypto")]
+pub fn runtime_pq_sig87_keypair() -> (Vec<u8>, Vec<u8>) {
+    use ml_dsa::{MlDsa87, Seed, SigningKey as MlDsaSigningKey};
+
+    let mut seed = Seed::default();
+    OsRng.fill_bytes(AsMut::<[u8]>::as_mut(&mut seed));
+    let sk = MlDsaSigningKey::<MlDsa87>::from_seed(&seed);
+    let pk = sk.verifying_key();
+    #[allow(deprecated)]
+    let sk = sk.to_expanded();
+    let pk = pk.encode();
+    (
+        AsRef::<[u8]>::as_ref(&pk).to_vec(),
+        AsRef::<[u8]>::as_ref(&sk).to_vec(),
+    )
+}
+
 impl Hash for StdCrypto {
     fn sha512(&self, data: &[u8]) -> [u8; 64] {
         let mut h = Sha512::new();
@@ -236,6 +292,60 @@ impl PqSigMldsa65 for StdCrypto {
     }
 }
 
+#[cfg(feature = "pqkem")]
+impl PqKem1024 for StdCrypto {
+    fn encap(&self, pubk: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        use ml_kem::kem::Encapsulate as _;
+
+        let pk = ml_kem1024_ek_from_bytes(pubk)?;
+        let (ct, ss) = pk
+            .encapsulate(&mut OsRng)
+            .map_err(|_| CryptoError::InvalidKey)?;
+        let ct_bytes: &[u8] = ct.as_ref();
+        Ok((ct_bytes.to_vec(), ss.as_slice().to_vec()))
+    }
+
+    fn decap(&self, privk: &[u8], ct: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        use ml_kem::kem::Decapsulate as _;
+
+        let sk = ml_kem1024_dk_from_bytes(privk)?;
+        let ct = ml_kem::Ciphertext::<ml_kem::MlKem1024>::try_from(ct)
+            .map_err(|_| CryptoError::InvalidKey)?;
+        let ss = sk.decapsulate(&ct).map_err(|_| CryptoError::AuthFail)?;
+        Ok(ss.as_slice().to_vec())
+    }
+}
+
+#[cfg(feature = "pqcrypto")]
+impl PqSigMldsa87 for StdCrypto {
+    fn sign(&self, privk: &[u8], msg: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        use ml_dsa::signature::Signer as _;
+        use ml_dsa::{ExpandedSigningKey, MlDsa87, SigningKey as MlDsaSigningKey};
+
+        let enc =
+            ExpandedSigningKey::<MlDsa87>::try_from(privk).map_err(|_| CryptoError::InvalidKey)?;
+        #[allow(deprecated)]
+        let sk = MlDsaSigningKey::<MlDsa87>::from_expanded(&enc);
+        let sig = sk.sign(msg);
+        let sig = sig.encode();
+        Ok(AsRef::<[u8]>::as_ref(&sig).to_vec())
+    }
+
+    fn verify(&self, pubk: &[u8], msg: &[u8], sig: &[u8]) -> Result<bool, CryptoError> {
+        use ml_dsa::signature::Verifier as _;
+        use ml_dsa::{
+            EncodedVerifyingKey, MlDsa87, Signature as MlDsaSignature,
+            VerifyingKey as MlDsaVerifyingKey,
+        };
+
+        let enc =
+            EncodedVerifyingKey::<MlDsa87>::try_from(pubk).map_err(|_| CryptoError::InvalidKey)?;
+        let pk = MlDsaVerifyingKey::<MlDsa87>::decode(&enc);
+        let sig = MlDsaSignature::<MlDsa87>::try_from(sig).map_err(|_| CryptoError::InvalidKey)?;
+        Ok(pk.verify(msg, &sig).is_ok())
+    }
+}
+
 pub struct StdEd25519;
 
 impl SigEd25519 for StdEd25519 {
@@ -398,6 +508,31 @@ mod tests {
         assert!(!ok2);
     }
 
+    #[cfg(all(feature = "pqkem", feature = "pqcrypto"))]
+    #[test]
+    fn category5_kem_and_sig_match_fips_sizes() {
+        use super::{runtime_pq_kem1024_keypair, runtime_pq_sig87_keypair};
+        use super::{PqKem1024, PqSigMldsa87};
+        use crate::qsp::{SZ_MLDSA87_PUB, SZ_MLDSA87_SIG, SZ_MLKEM1024_CT, SZ_MLKEM1024_PUB};
+
+        let c = StdCrypto;
+        let (pk, sk) = runtime_pq_kem1024_keypair();
+        let (ct, ss1) = PqKem1024::encap(&c, &pk).unwrap();
+        assert_eq!(PqKem1024::decap(&c, &sk, &ct).unwrap(), ss1);
+        assert_eq!(
+            (pk.len(), ct.len(), ss1.len()),
+            (SZ_MLKEM1024_PUB, SZ_MLKEM1024_CT, 32)
+        );
+        assert!(PqKem1024::encap(&c, &pk[..1184]).is_err());
+
+        let (vk, sk) = runtime_pq_sig87_keypair();
+        let msg = b"qsp-category5";
+        let sig = PqSigMldsa87::sign(&c, &sk, msg).unwrap();
+        assert_eq!((vk.len(), sig.len()), (SZ_MLDSA87_PUB, SZ_MLDSA87_SIG));
+        assert!(PqSigMldsa87::verify(&c, &vk, msg, &sig).unwrap());
+        assert!(!PqSigMldsa87::verify(&c, &vk, b"other", &sig).unwrap());
+    }
+
     #[cfg(all(feature = "pqkem", feature = "pqcrypto"))]
     #[test]
     fn runtime_pq_boundary_helpers_match_provider_lengths() {
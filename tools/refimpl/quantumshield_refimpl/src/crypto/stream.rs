@@ -0,0 +1,277 @@
+//! Chunked (STREAM-style) AEAD over the one-shot `Aead` trait.
+//!
+//! A payload is split into chunks sealed under one key with per-chunk nonces derived
+//! from a fixed prefix and a big-endian u32 chunk counter. The final chunk is marked, so
+//! an opener can tell a complete stream from a truncated one and refuses anything after
+//! the final chunk. Chunks must be opened in order; reordering or dropping one fails
+//! authentication.
+//!
+//! Two nonce layouts are supported:
+//! - `StreamNonce::Stream`: prefix7 || u32be(i) || last_flag (Hoang–Reyhanitabar–Rogaway–
+//!   Vizár STREAM). Finality is authenticated by the nonce itself.
+//! - `StreamNonce::PrefixCounter`: prefix8 || u32be(i), the QATT part cipher layout
+//!   (DOC-CAN-007 §4.3). The nonce carries no flag, so the caller MUST bind finality
+//!   (chunk count and index) into each chunk's AD, as QATT does.
+
+use super::traits::{Aead, CryptoError};
+use thiserror::Error;
+
+/// AEAD tag length appended to every chunk.
+pub const STREAM_TAG_LEN: usize = 16;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum StreamError {
+    #[error("stream chunk failed authentication")]
+    AuthFail,
+    #[error("stream chunk seal failed")]
+    SealFailed,
+    #[error("stream already finished")]
+    Finished,
+    #[error("stream chunk counter exhausted")]
+    CounterOverflow,
+    #[error("stream ended before its final chunk")]
+    Truncated,
+}
+
+impl From<CryptoError> for StreamError {
+    fn from(_: CryptoError) -> Self {
+        StreamError::AuthFail
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamNonce {
+    Stream([u8; 7]),
+    PrefixCounter([u8; 8]),
+}
+
+impl StreamNonce {
+    /// The 12-byte nonce for chunk `counter`.
+    pub fn nonce(&self, counter: u32, last: bool) -> [u8; 12] {
+        let mut out = [0u8; 12];
+        match self {
+            StreamNonce::Stream(prefix) => {
+                out[..7].copy_from_slice(prefix);
+                out[7..11].copy_from_slice(&counter.to_be_bytes());
+                out[11] = u8::from(last);
+            }
+            StreamNonce::PrefixCounter(prefix) => {
+                out[..8].copy_from_slice(prefix);
+                out[8..].copy_from_slice(&counter.to_be_bytes());
+            }
+        }
+        out
+    }
+}
+
+/// Shared counter/finality bookkeeping for both directions.
+struct Cursor {
+    key: [u8; 32],
+    nonce: StreamNonce,
+    counter: u32,
+    finished: bool,
+}
+
+impl Cursor {
+    fn new(key: &[u8; 32], nonce: StreamNonce) -> Self {
+        Self {
+            key: *key,
+            nonce,
+            counter: 0,
+            finished: false,
+        }
+    }
+
+    /// Nonce for the next chunk. A non-final chunk may not take the last counter value,
+    /// otherwise no final chunk could follow it.
+    fn next_nonce(&self, last: bool) -> Result<[u8; 12], StreamError> {
+        if self.finished {
+            return Err(StreamError::Finished);
+        }
+        if !last && self.counter == u32::MAX {
+            return Err(StreamError::CounterOverflow);
+        }
+        Ok(self.nonce.nonce(self.counter, last))
+    }
+
+    fn advance(&mut self, last: bool) {
+        if last {
+            self.finished = true;
+        } else {
+            self.counter += 1;
+        }
+    }
+}
+
+impl Drop for Cursor {
+    fn drop(&mut self) {
+        #[cfg(feature = "stdcrypto")]
+        zeroize::Zeroize::zeroize(&mut self.key);
+    }
+}
+
+pub struct StreamSealer<'a> {
+    aead: &'a dyn Aead,
+    cur: Cursor,
+}
+
+impl<'a> StreamSealer<'a> {
+    pub fn new(aead: &'a dyn Aead, key: &[u8; 32], nonce: StreamNonce) -> Self {
+        Self {
+            aead,
+            cur: Cursor::new(key, nonce),
+        }
+    }
+
+    /// Seal the next chunk. `last` must be set on exactly the final chunk.
+    pub fn seal_chunk(&mut self, ad: &[u8], pt: &[u8], last: bool) -> Result<Vec<u8>, StreamError> {
+        let nonce = self.cur.next_nonce(last)?;
+        let ct = self.aead.seal(&self.cur.key, &nonce, ad, pt);
+        // `Aead::seal` signals failure with an empty (or otherwise mis-sized) output.
+        if ct.len() != pt.len() + STREAM_TAG_LEN {
+            return Err(StreamError::SealFailed);
+        }
+        self.cur.advance(last);
+        Ok(ct)
+    }
+
+    /// Index of the next chunk to be sealed.
+    pub fn counter(&self) -> u32 {
+        self.cur.counter
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.cur.finished
+    }
+}
+
+pub struct StreamOpener<'a> {
+    aead: &'a dyn Aead,
+    cur: Cursor,
+}
+
+impl<'a> StreamOpener<'a> {
+    pub fn new(aead: &'a dyn Aead, key: &[u8; 32], nonce: StreamNonce) -> Self {
+        Self {
+            aead,
+            cur: Cursor::new(key, nonce),
+        }
+    }
+
+    /// Open the next chunk. On failure the opener does not advance, so the stream must be
+    /// abandoned rather than retried with a different chunk.
+    pub fn open_chunk(&mut self, ad: &[u8], ct: &[u8], last: bool) -> Result<Vec<u8>, StreamError> {
+        let nonce = self.cur.next_nonce(last)?;
+        if ct.len() < STREAM_TAG_LEN {
+            return Err(StreamError::AuthFail);
+        }
+        let pt = self.aead.open(&self.cur.key, &nonce, ad, ct)?;
+        self.cur.advance(last);
+        Ok(pt)
+    }
+
+    /// Index of the next chunk expected.
+    pub fn counter(&self) -> u32 {
+        self.cur.counter
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.cur.finished
+    }
+
+    /// Fails with `Truncated` unless the final chunk has been opened.
+    pub fn finish(self) -> Result<(), StreamError> {
+        if self.cur.finished {
+            Ok(())
+        } else {
+            Err(StreamError::Truncated)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "stdcrypto"))]
+mod tests {
+    use super::*;
+    use crate::crypto::stdcrypto::StdCrypto;
+
+    const KEY: [u8; 32] = [0x42; 32];
+    const PREFIX: StreamNonce = StreamNonce::Stream([7; 7]);
+
+    fn seal_all(chunks: &[&[u8]]) -> Vec<Vec<u8>> {
+        let c = StdCrypto;
+        let mut s = StreamSealer::new(&c, &KEY, PREFIX);
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(i, pt)| s.seal_chunk(b"ad", pt, i + 1 == chunks.len()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn nonce_layouts() {
+        assert_eq!(
+            StreamNonce::Stream([1; 7]).nonce(0x0102_0304, true),
+            [1, 1, 1, 1, 1, 1, 1, 1, 2, 3, 4, 1]
+        );
+        assert_eq!(
+            StreamNonce::PrefixCounter([9; 8]).nonce(5, true),
+            [9, 9, 9, 9, 9, 9, 9, 9, 0, 0, 0, 5]
+        );
+    }
+
+    #[test]
+    fn roundtrip_including_empty_final_chunk() {
+        let cts = seal_all(&[b"one", b"two", b""]);
+        let c = StdCrypto;
+        let mut o = StreamOpener::new(&c, &KEY, PREFIX);
+        assert_eq!(o.open_chunk(b"ad", &cts[0], false).unwrap(), b"one");
+        assert_eq!(o.open_chunk(b"ad", &cts[1], false).unwrap(), b"two");
+        assert_eq!(o.open_chunk(b"ad", &cts[2], true).unwrap(), b"");
+        o.finish().unwrap();
+    }
+
+    #[test]
+    fn truncation_reorder_and_flag_flip_are_rejected() {
+        let cts = seal_all(&[b"one", b"two"]);
+        let c = StdCrypto;
+
+        let mut o = StreamOpener::new(&c, &KEY, PREFIX);
+        o.open_chunk(b"ad", &cts[0], false).unwrap();
+        assert_eq!(o.finish(), Err(StreamError::Truncated));
+
+        let mut o = StreamOpener::new(&c, &KEY, PREFIX);
+        assert_eq!(
+            o.open_chunk(b"ad", &cts[1], false),
+            Err(StreamError::AuthFail)
+        );
+        assert_eq!(o.counter(), 0);
+
+        // A non-final chunk presented as final must not authenticate.
+        let mut o = StreamOpener::new(&c, &KEY, PREFIX);
+        assert_eq!(
+            o.open_chunk(b"ad", &cts[0], true),
+            Err(StreamError::AuthFail)
+        );
+    }
+
+    #[test]
+    fn nothing_after_final() {
+        let c = StdCrypto;
+        let mut s = StreamSealer::new(&c, &KEY, PREFIX);
+        s.seal_chunk(b"", b"x", true).unwrap();
+        assert!(s.is_finished());
+        assert_eq!(s.seal_chunk(b"", b"y", false), Err(StreamError::Finished));
+    }
+
+    #[test]
+    fn counter_never_wraps() {
+        let c = StdCrypto;
+        let mut s = StreamSealer::new(&c, &KEY, PREFIX);
+        s.cur.counter = u32::MAX;
+        assert_eq!(
+            s.seal_chunk(b"", b"x", false),
+            Err(StreamError::CounterOverflow)
+        );
+        assert!(s.seal_chunk(b"", b"x", true).is_ok());
+    }
+}
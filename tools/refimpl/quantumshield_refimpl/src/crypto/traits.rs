@@ -39,6 +39,12 @@ pub trait X25519Dh {
 #[cfg_attr(feature = "stdcrypto", derive(Zeroize, ZeroizeOnDrop))]
 #[derive(Clone)]
 pub struct X25519Priv(pub [u8; 32]);
+
+/// Owned secret bytes, wiped on drop when `zeroize` is available (`stdcrypto`).
+#[cfg(feature = "stdcrypto")]
+pub type SecretBytes = zeroize::Zeroizing<Vec<u8>>;
+#[cfg(not(feature = "stdcrypto"))]
+pub type SecretBytes = Vec<u8>;
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct X25519Pub(pub [u8; 32]);
 
@@ -57,6 +63,18 @@ pub trait PqSigMldsa65 {
     fn verify(&self, pubk: &[u8], msg: &[u8], sig: &[u8]) -> Result<bool, CryptoError>;
 }
 
+/// ML-KEM-1024 (category 5). Same shape as `PqKem768`.
+pub trait PqKem1024 {
+    fn encap(&self, pubk: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError>; // (ct, ss)
+    fn decap(&self, privk: &[u8], ct: &[u8]) -> Result<Vec<u8>, CryptoError>; // ss
+}
+
+/// ML-DSA-87 (category 5). Same shape as `PqSigMldsa65`.
+pub trait PqSigMldsa87 {
+    fn sign(&self, privk: &[u8], msg: &[u8]) -> Result<Vec<u8>, CryptoError>;
+    fn verify(&self, pubk: &[u8], msg: &[u8], sig: &[u8]) -> Result<bool, CryptoError>;
+}
+
 pub trait Rng12 {
     fn random_nonce12(&mut self) -> [u8; 12];
 }
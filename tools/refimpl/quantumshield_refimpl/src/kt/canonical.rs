@@ -1,10 +1,18 @@
+use super::client::{
+    KtClientState, KtLogEndpoint, PeerSth, SplitViewEvidence, SthStore, MAX_PEER_STHS_PER_LOG,
+};
+use super::quorum::{KtLogEvidence, KtQuorumPolicy, KtQuorumReport};
 use super::{KtError, KtVerification, KtVerifier};
+use crate::codec::{
+    canonical_struct, CanonicalCodec, CodecError, FieldCodec, Fixed, List16, Reader, Writer, U64,
+    U8,
+};
 use crate::crypto::traits::{PqSigMldsa65, SigEd25519};
 use crate::qsp::{HandshakeInit, PrekeyBundle};
 
 use sha2::{Digest, Sha256, Sha512};
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::sync::{Mutex, MutexGuard};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,7 +29,7 @@ pub enum KtTimeSource {
 }
 
 impl KtTimeSource {
-    fn now_ms(self) -> u64 {
+    pub(super) fn now_ms(self) -> u64 {
         match self {
             Self::System => SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -39,92 +47,117 @@ pub struct KtPinnedLog {
     pub proof_cache_ttl_seconds: u64,
 }
 
+/// `PeerSth::source` used for the verifier's own accepted STH in split-view evidence.
+pub const ACCEPTED_SOURCE: &str = "accepted";
+
+/// Bundles whose extra-log evidence may wait staged at once; staging past this drops the
+/// oldest entry, which then has to be staged again.
+pub const MAX_STAGED_BUNDLES: usize = 64;
+
 #[derive(Debug)]
 pub struct CanonicalKtVerifier {
     pinned_logs: HashMap<[u8; 32], KtPinnedLog>,
     allow_disabled_nonproduction: bool,
     time_source: KtTimeSource,
-    accepted: Mutex<HashMap<[u8; 32], AcceptedSth>>,
+    /// Accepted STHs, observed peer STHs and split-view evidence, written through to
+    /// `store` (when set) before any change takes effect.
+    state: Mutex<KtClientState>,
+    store: Option<Box<dyn SthStore>>,
+    quorum: Option<KtQuorumPolicy>,
+    /// Keyed by bundle leaf hash, oldest first; see `MAX_STAGED_BUNDLES`.
+    staged_evidence: Mutex<Vec<([u8; 32], Vec<KtLogEvidence>)>>,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct SthBlob {
-    version: u8,
-    log_id: [u8; 32],
-    tree_size: u64,
-    timestamp_ms: u64,
-    root_hash: [u8; 32],
-    signature: [u8; 64],
+canonical_struct! {
+    #[derive(Debug, Clone, Copy)]
+    pub(super) struct SthBlob {
+        #[check(version == 0x01, "sth_version")]
+        pub(super) version: u8 as U8,
+        pub(super) log_id: [u8; 32] as Fixed,
+        pub(super) tree_size: u64 as U64,
+        pub(super) timestamp_ms: u64 as U64,
+        pub(super) root_hash: [u8; 32] as Fixed,
+        pub(super) signature: [u8; 64] as Fixed,
+    }
 }
 
 #[derive(Debug, Clone)]
-struct InclusionProof {
-    leaf_index: u64,
-    tree_size: u64,
-    siblings: Vec<[u8; 32]>,
+pub(super) struct InclusionProof {
+    pub(super) leaf_index: u64,
+    pub(super) tree_size: u64,
+    pub(super) siblings: Vec<[u8; 32]>,
 }
 
 #[derive(Debug, Clone)]
-struct ConsistencyProof {
-    from_tree_size: u64,
-    to_tree_size: u64,
-    nodes: Vec<[u8; 32]>,
+pub(super) struct ConsistencyProof {
+    pub(super) from_tree_size: u64,
+    pub(super) to_tree_size: u64,
+    pub(super) nodes: Vec<[u8; 32]>,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct BundleEvaluation {
-    status: KtVerification,
-    state_update: Option<([u8; 32], AcceptedSth)>,
-}
-
-struct BlobReader<'a> {
-    buf: &'a [u8],
-    pos: usize,
-}
-
-impl<'a> BlobReader<'a> {
-    fn new(buf: &'a [u8]) -> Self {
-        Self { buf, pos: 0 }
+impl SthBlob {
+    /// `SHA-512("QSL-KT/STH/v1" || version || log_id || tree_size || timestamp_ms || root_hash)`.
+    pub(super) fn signature_input(&self) -> [u8; 64] {
+        let mut msg = Vec::with_capacity(1 + 32 + 8 + 8 + 32 + 13);
+        msg.extend_from_slice(b"QSL-KT/STH/v1");
+        msg.push(self.version);
+        msg.extend_from_slice(&self.log_id);
+        msg.extend_from_slice(&self.tree_size.to_be_bytes());
+        msg.extend_from_slice(&self.timestamp_ms.to_be_bytes());
+        msg.extend_from_slice(&self.root_hash);
+        sha512(&msg)
     }
 
-    fn take(&mut self, n: usize, detail: &'static str) -> Result<&'a [u8], KtError> {
-        if self.buf.len().saturating_sub(self.pos) < n {
-            return Err(KtError::kt_fail(detail));
-        }
-        let out = &self.buf[self.pos..self.pos + n];
-        self.pos += n;
-        Ok(out)
+    /// Fails closed: an STH that did not encode must never reach the signer or a reader.
+    pub(super) fn encode(&self) -> Result<Vec<u8>, KtError> {
+        self.encode_canonical()
+            .map_err(|_| KtError::kt_fail("sth_encode_failed"))
     }
+}
 
-    fn read_u8(&mut self, detail: &'static str) -> Result<u8, KtError> {
-        Ok(self.take(1, detail)?[0])
+impl InclusionProof {
+    pub(super) fn encode(&self) -> Result<Vec<u8>, KtError> {
+        let mut w = Writer::new();
+        w.write_u8(0x01);
+        w.write_u64(self.leaf_index);
+        w.write_u64(self.tree_size);
+        List16::<Fixed>::write(&mut w, &self.siblings)
+            .map_err(|_| KtError::kt_fail("inclusion_encode_failed"))?;
+        Ok(w.into_vec())
     }
+}
 
-    fn read_u16(&mut self, detail: &'static str) -> Result<u16, KtError> {
-        let bytes = self.take(2, detail)?;
-        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+impl ConsistencyProof {
+    pub(super) fn encode(&self) -> Result<Vec<u8>, KtError> {
+        let mut w = Writer::new();
+        w.write_u8(0x01);
+        w.write_u64(self.from_tree_size);
+        w.write_u64(self.to_tree_size);
+        List16::<Fixed>::write(&mut w, &self.nodes)
+            .map_err(|_| KtError::kt_fail("consistency_encode_failed"))?;
+        Ok(w.into_vec())
     }
+}
 
-    fn read_u64(&mut self, detail: &'static str) -> Result<u64, KtError> {
-        let bytes = self.take(8, detail)?;
-        Ok(u64::from_be_bytes([
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-        ]))
-    }
+/// A log's accepted STH after a successful check, applied by `commit`.
+type StateUpdate = ([u8; 32], AcceptedSth);
 
-    fn read_exact<const N: usize>(&mut self, detail: &'static str) -> Result<[u8; N], KtError> {
-        let bytes = self.take(N, detail)?;
-        let mut out = [0u8; N];
-        out.copy_from_slice(bytes);
-        Ok(out)
-    }
+#[derive(Debug, Clone)]
+struct BundleEvaluation {
+    status: KtVerification,
+    state_updates: Vec<StateUpdate>,
+}
 
-    fn finish(&self, detail: &'static str) -> Result<(), KtError> {
-        if self.pos == self.buf.len() {
-            Ok(())
-        } else {
-            Err(KtError::kt_fail(detail))
-        }
+/// Map a codec failure on a KT blob to its `kt_fail` detail: short input and bad lengths
+/// are `truncated`, leftover input is `trailing`, and value checks keep their own detail.
+pub(super) fn blob_error(
+    truncated: &'static str,
+    trailing: &'static str,
+) -> impl Fn(CodecError) -> KtError {
+    move |e| match e {
+        CodecError::Truncated | CodecError::LengthOutOfRange => KtError::kt_fail(truncated),
+        CodecError::TrailingBytes => KtError::kt_fail(trailing),
+        CodecError::Invalid(detail) => KtError::kt_fail(detail),
     }
 }
 
@@ -145,10 +178,72 @@ impl CanonicalKtVerifier {
             pinned_logs: logs,
             allow_disabled_nonproduction,
             time_source,
-            accepted: Mutex::new(HashMap::new()),
+            state: Mutex::new(KtClientState::default()),
+            store: None,
+            quorum: None,
+            staged_evidence: Mutex::new(Vec::new()),
         }
     }
 
+    /// Require `policy.threshold` pinned logs to vouch for every bundle verified through
+    /// `KtVerifier`. Evidence beyond the bundle's own primary log comes from
+    /// `stage_log_evidence`.
+    pub fn with_quorum_policy(mut self, policy: KtQuorumPolicy) -> Result<Self, KtError> {
+        if policy.threshold == 0 || policy.threshold > self.pinned_logs.len() {
+            return Err(KtError::kt_fail("kt_quorum_policy_invalid"));
+        }
+        self.quorum = Some(policy);
+        Ok(self)
+    }
+
+    /// Make further logs' evidence for `bundle` available to the `KtVerifier` path, which
+    /// only sees the bundle itself. Replaces anything staged earlier for the same bundle; a
+    /// verification that meets the quorum consumes it. At most one entry per pinned log.
+    pub fn stage_log_evidence(
+        &self,
+        bundle: &PrekeyBundle,
+        evidence: Vec<KtLogEvidence>,
+    ) -> Result<(), KtError> {
+        if evidence.len() > self.pinned_logs.len() {
+            return Err(KtError::kt_fail("staged_evidence_too_large"));
+        }
+        let key = sha256_prefixed(0x00, &bundle.bundle_leaf_data());
+        let mut staged = self
+            .staged_evidence
+            .lock()
+            .map_err(|_| KtError::kt_fail("kt_state_poisoned"))?;
+        staged.retain(|(k, _)| *k != key);
+        staged.push((key, evidence));
+        if staged.len() > MAX_STAGED_BUNDLES {
+            let excess = staged.len() - MAX_STAGED_BUNDLES;
+            staged.drain(..excess);
+        }
+        Ok(())
+    }
+
+    fn unstage_log_evidence(&self, bundle: &PrekeyBundle) -> Result<(), KtError> {
+        let key = sha256_prefixed(0x00, &bundle.bundle_leaf_data());
+        self.staged_evidence
+            .lock()
+            .map_err(|_| KtError::kt_fail("kt_state_poisoned"))?
+            .retain(|(k, _)| *k != key);
+        Ok(())
+    }
+
+    /// Back client state with `store`: previously persisted accepted STHs, peer STHs and
+    /// split-view evidence are loaded now, and every later change is written through before
+    /// it takes effect. A store that cannot be read fails closed instead of falling back to
+    /// trust-on-first-use.
+    pub fn with_sth_store(mut self, store: Box<dyn SthStore>) -> Result<Self, KtError> {
+        let loaded = store.load()?;
+        *self
+            .state
+            .get_mut()
+            .map_err(|_| KtError::kt_fail("kt_state_poisoned"))? = loaded;
+        self.store = Some(store);
+        Ok(self)
+    }
+
     pub fn disabled_nonproduction() -> Self {
         Self::new([], KtTimeSource::System, true)
     }
@@ -165,18 +260,54 @@ impl CanonicalKtVerifier {
             if self.allow_disabled_nonproduction {
                 return Ok(BundleEvaluation {
                     status: KtVerification::DisabledNonProduction,
-                    state_update: None,
+                    state_updates: Vec::new(),
                 });
             }
             return Err(KtError::kt_fail("disabled_shape_rejected"));
         }
 
+        let Some(policy) = self.quorum else {
+            let update =
+                self.evaluate_evidence(bundle, &KtLogEvidence::from_bundle(bundle), ed25519)?;
+            return Ok(BundleEvaluation {
+                status: KtVerification::Verified,
+                state_updates: update.into_iter().collect(),
+            });
+        };
+
+        let key = sha256_prefixed(0x00, &bundle.bundle_leaf_data());
+        let staged = self
+            .staged_evidence
+            .lock()
+            .map_err(|_| KtError::kt_fail("kt_state_poisoned"))?
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, evidence)| evidence.clone())
+            .unwrap_or_default();
+        let (report, state_updates) = self.evaluate_quorum(bundle, &staged, policy, ed25519)?;
+        if !report.met() {
+            return Err(KtError::kt_fail("kt_quorum_not_met"));
+        }
+        Ok(BundleEvaluation {
+            status: KtVerification::Verified,
+            state_updates,
+        })
+    }
+
+    /// Verify one log's evidence for `bundle` under that log's pin and accepted state.
+    fn evaluate_evidence(
+        &self,
+        bundle: &PrekeyBundle,
+        evidence: &KtLogEvidence,
+        ed25519: &dyn SigEd25519,
+    ) -> Result<Option<StateUpdate>, KtError> {
         let pinned = self
             .pinned_logs
-            .get(&bundle.kt_log_id)
+            .get(&evidence.log_id)
             .ok_or_else(|| KtError::kt_fail("unpinned_log_id"))?;
-        let sth = parse_sth(&bundle.kt_sth)?;
-        if sth.log_id != bundle.kt_log_id {
+        self.ensure_no_split_view(&evidence.log_id)?;
+        let sth = parse_sth(&evidence.sth)?;
+        if sth.log_id != evidence.log_id {
             return Err(KtError::kt_fail("sth_log_id_mismatch"));
         }
 
@@ -189,27 +320,125 @@ impl CanonicalKtVerifier {
         verify_inclusion(
             bundle,
             &sth,
-            &parse_inclusion_proof(&bundle.kt_inclusion_proof)?,
+            &parse_inclusion_proof(&evidence.inclusion_proof)?,
         )?;
 
-        let state_update = self.evaluate_consistency(bundle, &sth)?;
-        Ok(BundleEvaluation {
+        self.evaluate_consistency(&evidence.log_id, &evidence.consistency_proof, &sth)
+    }
+
+    /// Judge the bundle's own evidence plus `extra` against `policy`. Nothing is
+    /// committed; the returned updates belong to the logs that vouched.
+    fn evaluate_quorum(
+        &self,
+        bundle: &PrekeyBundle,
+        extra: &[KtLogEvidence],
+        policy: KtQuorumPolicy,
+        ed25519: &dyn SigEd25519,
+    ) -> Result<(KtQuorumReport, Vec<StateUpdate>), KtError> {
+        let primary = KtLogEvidence::from_bundle(bundle);
+        let mut offered: Vec<&KtLogEvidence> = Vec::with_capacity(1 + extra.len());
+        for evidence in std::iter::once(&primary).chain(extra) {
+            if offered.iter().any(|e| e.log_id == evidence.log_id) {
+                return Err(KtError::kt_fail("duplicate_log_evidence"));
+            }
+            offered.push(evidence);
+        }
+        offered.sort_by_key(|e| e.log_id);
+
+        let mut report = KtQuorumReport {
             status: KtVerification::Verified,
-            state_update,
-        })
+            threshold: policy.threshold,
+            vouched: Vec::new(),
+            rejected: Vec::new(),
+            missing: Vec::new(),
+        };
+        let mut updates = Vec::new();
+        for evidence in offered.iter().copied() {
+            match self.evaluate_evidence(bundle, evidence, ed25519) {
+                Ok(update) => {
+                    report.vouched.push(evidence.log_id);
+                    updates.extend(update);
+                }
+                Err(e) => report.rejected.push((evidence.log_id, e.detail())),
+            }
+        }
+        report.missing = self
+            .pinned_logs
+            .keys()
+            .filter(|id| !offered.iter().any(|e| &e.log_id == *id))
+            .copied()
+            .collect();
+        report.missing.sort();
+        Ok((report, updates))
     }
 
-    fn evaluate_consistency(
+    /// Report which pinned logs vouch for `bundle` given its own evidence plus `extra`,
+    /// without changing accepted state.
+    pub fn evaluate_bundle_quorum(
+        &self,
+        bundle: &PrekeyBundle,
+        extra: &[KtLogEvidence],
+        policy: KtQuorumPolicy,
+        ed25519: &dyn SigEd25519,
+        pq_sig: &dyn PqSigMldsa65,
+    ) -> Result<KtQuorumReport, KtError> {
+        self.quorum_inner(bundle, extra, policy, ed25519, pq_sig)
+            .map(|(report, _)| report)
+    }
+
+    /// As `evaluate_bundle_quorum`, but fails with `kt_quorum_not_met` unless the policy
+    /// is met, and on success advances accepted state for every log that vouched.
+    pub fn verify_bundle_quorum(
+        &self,
+        bundle: &PrekeyBundle,
+        extra: &[KtLogEvidence],
+        policy: KtQuorumPolicy,
+        ed25519: &dyn SigEd25519,
+        pq_sig: &dyn PqSigMldsa65,
+    ) -> Result<KtQuorumReport, KtError> {
+        let (report, updates) = self.quorum_inner(bundle, extra, policy, ed25519, pq_sig)?;
+        if !report.met() {
+            return Err(KtError::kt_fail("kt_quorum_not_met"));
+        }
+        self.commit(updates)?;
+        Ok(report)
+    }
+
+    fn quorum_inner(
         &self,
         bundle: &PrekeyBundle,
+        extra: &[KtLogEvidence],
+        policy: KtQuorumPolicy,
+        ed25519: &dyn SigEd25519,
+        pq_sig: &dyn PqSigMldsa65,
+    ) -> Result<(KtQuorumReport, Vec<StateUpdate>), KtError> {
+        if policy.threshold == 0 || policy.threshold > self.pinned_logs.len() {
+            return Err(KtError::kt_fail("kt_quorum_policy_invalid"));
+        }
+        verify_bundle_signatures(bundle, ed25519, pq_sig)?;
+        if bundle.kt_disabled_shape() {
+            if self.allow_disabled_nonproduction && extra.is_empty() {
+                let report = KtQuorumReport {
+                    status: KtVerification::DisabledNonProduction,
+                    threshold: policy.threshold,
+                    vouched: Vec::new(),
+                    rejected: Vec::new(),
+                    missing: Vec::new(),
+                };
+                return Ok((report, Vec::new()));
+            }
+            return Err(KtError::kt_fail("disabled_shape_rejected"));
+        }
+        self.evaluate_quorum(bundle, extra, policy, ed25519)
+    }
+
+    fn evaluate_consistency(
+        &self,
+        log_id: &[u8; 32],
+        consistency_proof: &[u8],
         sth: &SthBlob,
-    ) -> Result<Option<([u8; 32], AcceptedSth)>, KtError> {
-        let previous = self
-            .accepted
-            .lock()
-            .map_err(|_| KtError::kt_fail("kt_state_poisoned"))?
-            .get(&bundle.kt_log_id)
-            .copied();
+    ) -> Result<Option<StateUpdate>, KtError> {
+        let previous = self.accepted_state(log_id);
 
         let next = AcceptedSth {
             tree_size: sth.tree_size,
@@ -219,13 +448,13 @@ impl CanonicalKtVerifier {
 
         match previous {
             None => {
-                if !bundle.kt_consistency_proof.is_empty() {
-                    let proof = parse_consistency_proof(&bundle.kt_consistency_proof)?;
+                if !consistency_proof.is_empty() {
+                    let proof = parse_consistency_proof(consistency_proof)?;
                     if proof.to_tree_size != sth.tree_size {
                         return Err(KtError::kt_fail("consistency_to_tree_size_mismatch"));
                     }
                 }
-                Ok(Some((bundle.kt_log_id, next)))
+                Ok(Some((*log_id, next)))
             }
             Some(prev) => {
                 if sth.tree_size < prev.tree_size {
@@ -233,7 +462,7 @@ impl CanonicalKtVerifier {
                 }
 
                 if sth.tree_size == prev.tree_size {
-                    if !bundle.kt_consistency_proof.is_empty() {
+                    if !consistency_proof.is_empty() {
                         return Err(KtError::kt_fail("same_tree_requires_empty_consistency"));
                     }
                     if sth.root_hash != prev.root_hash {
@@ -242,14 +471,14 @@ impl CanonicalKtVerifier {
                     if sth.timestamp_ms < prev.timestamp_ms {
                         return Err(KtError::kt_fail("same_tree_timestamp_regressed"));
                     }
-                    return Ok(Some((bundle.kt_log_id, next)));
+                    return Ok(Some((*log_id, next)));
                 }
 
-                if bundle.kt_consistency_proof.is_empty() {
+                if consistency_proof.is_empty() {
                     return Err(KtError::kt_fail("missing_consistency_proof"));
                 }
 
-                let proof = parse_consistency_proof(&bundle.kt_consistency_proof)?;
+                let proof = parse_consistency_proof(consistency_proof)?;
                 if proof.from_tree_size != prev.tree_size {
                     return Err(KtError::kt_fail("consistency_from_tree_size_mismatch"));
                 }
@@ -265,27 +494,240 @@ impl CanonicalKtVerifier {
                 ) {
                     return Err(KtError::kt_fail("consistency_proof_invalid"));
                 }
-                Ok(Some((bundle.kt_log_id, next)))
+                Ok(Some((*log_id, next)))
             }
         }
     }
 
-    fn commit(&self, candidate: Option<([u8; 32], AcceptedSth)>) -> Result<(), KtError> {
-        if let Some((log_id, state)) = candidate {
-            self.accepted
-                .lock()
-                .map_err(|_| KtError::kt_fail("kt_state_poisoned"))?
-                .insert(log_id, state);
+    fn commit(&self, updates: Vec<StateUpdate>) -> Result<(), KtError> {
+        if updates.is_empty() {
+            return Ok(());
         }
+        let mut state = self.lock_state()?;
+        let mut next = state.clone();
+        next.accepted.extend(updates);
+        self.write_through(&mut state, next)
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<'_, KtClientState>, KtError> {
+        self.state
+            .lock()
+            .map_err(|_| KtError::kt_fail("kt_state_poisoned"))
+    }
+
+    /// Persist `next`, then make it current. A failed save leaves `state` untouched.
+    fn write_through(&self, state: &mut KtClientState, next: KtClientState) -> Result<(), KtError> {
+        if let Some(store) = &self.store {
+            store.save(&next)?;
+        }
+        *state = next;
         Ok(())
     }
 
-    #[cfg(test)]
-    fn accepted_state(&self, log_id: &[u8; 32]) -> Option<AcceptedSth> {
-        self.accepted
+    /// The last STH accepted for `log_id`, if any.
+    pub fn accepted_state(&self, log_id: &[u8; 32]) -> Option<AcceptedSth> {
+        self.state
             .lock()
             .ok()
-            .and_then(|m| m.get(log_id).copied())
+            .and_then(|s| s.accepted.get(log_id).copied())
+    }
+
+    /// Fetch the log's current STH and advance accepted state to it.
+    ///
+    /// The STH must be signed by the pinned key and fresh. When a previous STH has been
+    /// accepted, a consistency proof from that tree size is fetched and verified first, so
+    /// the log can only move forward append-only.
+    pub fn refresh_from_log(
+        &self,
+        log_id: &[u8; 32],
+        endpoint: &dyn KtLogEndpoint,
+        ed25519: &dyn SigEd25519,
+    ) -> Result<AcceptedSth, KtError> {
+        let pinned = self
+            .pinned_logs
+            .get(log_id)
+            .ok_or_else(|| KtError::kt_fail("unpinned_log_id"))?;
+        self.ensure_no_split_view(log_id)?;
+        let sth = parse_sth(&endpoint.get_sth()?)?;
+        if &sth.log_id != log_id {
+            return Err(KtError::kt_fail("sth_log_id_mismatch"));
+        }
+        verify_sth_signature(ed25519, pinned, &sth)?;
+        verify_freshness(
+            self.time_source.now_ms(),
+            pinned.proof_cache_ttl_seconds,
+            &sth,
+        )?;
+
+        let proof = match self.accepted_state(log_id) {
+            Some(prev) if prev.tree_size < sth.tree_size => {
+                endpoint.get_consistency_proof(prev.tree_size, sth.tree_size)?
+            }
+            _ => Vec::new(),
+        };
+        let update = self.evaluate_consistency(log_id, &proof, &sth)?;
+        self.commit(update.into_iter().collect())?;
+        self.accepted_state(log_id)
+            .ok_or_else(|| KtError::kt_fail("kt_state_missing"))
+    }
+
+    /// Record a signed STH for a pinned log as seen via `source` (a peer, mirror or
+    /// gossip channel). Two correctly signed STHs of the same size with different roots,
+    /// from any pair of sources including our own accepted state, prove the log is
+    /// presenting a split view; the evidence is kept and the log fails closed from then on.
+    /// Each log keeps its last `MAX_PEER_STHS_PER_LOG` distinct observations.
+    ///
+    /// Freshness is not checked: an old STH is still evidence.
+    pub fn observe_peer_sth(
+        &self,
+        source: &str,
+        sth_blob: &[u8],
+        ed25519: &dyn SigEd25519,
+    ) -> Result<(), KtError> {
+        let sth = parse_sth(sth_blob)?;
+        let pinned = self
+            .pinned_logs
+            .get(&sth.log_id)
+            .ok_or_else(|| KtError::kt_fail("unpinned_log_id"))?;
+        verify_sth_signature(ed25519, pinned, &sth)?;
+        let seen = PeerSth {
+            source: source.to_string(),
+            sth: AcceptedSth {
+                tree_size: sth.tree_size,
+                root_hash: sth.root_hash,
+                timestamp_ms: sth.timestamp_ms,
+            },
+        };
+
+        let mut state = self.lock_state()?;
+        let known = state
+            .peer_sths
+            .get(&sth.log_id)
+            .cloned()
+            .unwrap_or_default();
+        let accepted = state.accepted.get(&sth.log_id).map(|sth| PeerSth {
+            source: ACCEPTED_SOURCE.to_string(),
+            sth: *sth,
+        });
+        let conflict = known.iter().chain(accepted.as_ref()).find(|other| {
+            other.sth.tree_size == seen.sth.tree_size && other.sth.root_hash != seen.sth.root_hash
+        });
+        if let Some(other) = conflict {
+            let evidence = SplitViewEvidence {
+                log_id: sth.log_id,
+                first: other.clone(),
+                second: seen,
+            };
+            return self.record_split_view(&mut state, evidence);
+        }
+        if known.contains(&seen) {
+            return Ok(());
+        }
+        let mut next = state.clone();
+        let known = next.peer_sths.entry(sth.log_id).or_default();
+        known.push(seen);
+        let excess = known.len().saturating_sub(MAX_PEER_STHS_PER_LOG);
+        known.drain(..excess);
+        self.write_through(&mut state, next)
+    }
+
+    /// Check every observed peer STH of `log_id` against our accepted STH using
+    /// consistency proofs fetched from the log. A peer STH that is not an append-only
+    /// extension (or prefix) of ours proves a split view.
+    pub fn cross_check_peer_sths(
+        &self,
+        log_id: &[u8; 32],
+        endpoint: &dyn KtLogEndpoint,
+    ) -> Result<(), KtError> {
+        self.ensure_no_split_view(log_id)?;
+        let ours = self
+            .accepted_state(log_id)
+            .ok_or_else(|| KtError::kt_fail("kt_state_missing"))?;
+        let peers = self
+            .lock_state()?
+            .peer_sths
+            .get(log_id)
+            .cloned()
+            .unwrap_or_default();
+
+        for peer in peers {
+            if peer.sth.tree_size == ours.tree_size {
+                continue;
+            }
+            let (old, new) = if peer.sth.tree_size < ours.tree_size {
+                (&peer.sth, &ours)
+            } else {
+                (&ours, &peer.sth)
+            };
+            let proof = parse_consistency_proof(
+                &endpoint.get_consistency_proof(old.tree_size, new.tree_size)?,
+            )?;
+            if proof.from_tree_size != old.tree_size || proof.to_tree_size != new.tree_size {
+                return Err(KtError::kt_fail("consistency_tree_size_mismatch"));
+            }
+            if !verify_consistency_proof(
+                old.tree_size,
+                new.tree_size,
+                &old.root_hash,
+                &new.root_hash,
+                &proof.nodes,
+            ) {
+                let evidence = SplitViewEvidence {
+                    log_id: *log_id,
+                    first: PeerSth {
+                        source: ACCEPTED_SOURCE.to_string(),
+                        sth: ours,
+                    },
+                    second: peer,
+                };
+                let mut state = self.lock_state()?;
+                return self.record_split_view(&mut state, evidence);
+            }
+        }
+        Ok(())
+    }
+
+    /// Split-view evidence recorded so far, oldest first.
+    pub fn split_view_evidence(&self) -> Vec<SplitViewEvidence> {
+        self.state
+            .lock()
+            .map(|s| s.split_views.clone())
+            .unwrap_or_default()
+    }
+
+    /// Keep the first evidence for a log and write it through. The log fails closed even if
+    /// the write fails; that failure is reported in place of `split_view_detected`.
+    fn record_split_view(
+        &self,
+        state: &mut KtClientState,
+        evidence: SplitViewEvidence,
+    ) -> Result<(), KtError> {
+        if state
+            .split_views
+            .iter()
+            .all(|e| e.log_id != evidence.log_id)
+        {
+            let mut next = state.clone();
+            next.split_views.push(evidence.clone());
+            if let Err(e) = self.write_through(state, next) {
+                state.split_views.push(evidence);
+                return Err(e);
+            }
+        }
+        Err(KtError::kt_fail("split_view_detected"))
+    }
+
+    fn ensure_no_split_view(&self, log_id: &[u8; 32]) -> Result<(), KtError> {
+        let split = self
+            .lock_state()?
+            .split_views
+            .iter()
+            .any(|e| &e.log_id == log_id);
+        if split {
+            Err(KtError::kt_fail("split_view_detected"))
+        } else {
+            Ok(())
+        }
     }
 }
 
@@ -297,7 +739,8 @@ impl KtVerifier for CanonicalKtVerifier {
         pq_sig: &dyn PqSigMldsa65,
     ) -> Result<KtVerification, KtError> {
         let evaluation = self.evaluate_bundle(bundle, ed25519, pq_sig)?;
-        self.commit(evaluation.state_update)?;
+        self.commit(evaluation.state_updates)?;
+        self.unstage_log_evidence(bundle)?;
         Ok(evaluation.status)
     }
 
@@ -323,7 +766,8 @@ impl KtVerifier for CanonicalKtVerifier {
         if bundle.pq_rcv_pub != hs1.pq_rcv_a_pub {
             return Err(KtError::kt_fail("hs1_pq_rcv_pub_mismatch"));
         }
-        self.commit(evaluation.state_update)?;
+        self.commit(evaluation.state_updates)?;
+        self.unstage_log_evidence(bundle)?;
         Ok(evaluation.status)
     }
 }
@@ -351,15 +795,11 @@ fn verify_sth_signature(
     pinned: &KtPinnedLog,
     sth: &SthBlob,
 ) -> Result<(), KtError> {
-    let mut msg = Vec::with_capacity(1 + 32 + 8 + 8 + 32 + 13);
-    msg.extend_from_slice(b"QSL-KT/STH/v1");
-    msg.push(sth.version);
-    msg.extend_from_slice(&sth.log_id);
-    msg.extend_from_slice(&sth.tree_size.to_be_bytes());
-    msg.extend_from_slice(&sth.timestamp_ms.to_be_bytes());
-    msg.extend_from_slice(&sth.root_hash);
-    let digest = sha512(&msg);
-    if ed25519.verify(&pinned.verifying_key, &digest, &sth.signature) {
+    if ed25519.verify(
+        &pinned.verifying_key,
+        &sth.signature_input(),
+        &sth.signature,
+    ) {
         Ok(())
     } else {
         Err(KtError::kt_fail("sth_signature_verify_failed"))
@@ -388,50 +828,33 @@ fn verify_inclusion(
         return Err(KtError::kt_fail("inclusion_tree_size_mismatch"));
     }
     let leaf_hash = sha256_prefixed(0x00, &bundle.bundle_leaf_data());
-    let root = compute_inclusion_root(leaf_hash, proof.leaf_index, &proof.siblings);
-    if root == sth.root_hash {
+    let root = compute_inclusion_root(
+        leaf_hash,
+        proof.leaf_index,
+        proof.tree_size,
+        &proof.siblings,
+    );
+    if root == Some(sth.root_hash) {
         Ok(())
     } else {
         Err(KtError::kt_fail("inclusion_root_mismatch"))
     }
 }
 
-fn parse_sth(buf: &[u8]) -> Result<SthBlob, KtError> {
-    let mut r = BlobReader::new(buf);
-    let version = r.read_u8("sth_truncated")?;
-    if version != 0x01 {
-        return Err(KtError::kt_fail("sth_version"));
-    }
-    let log_id = r.read_exact::<32>("sth_truncated")?;
-    let tree_size = r.read_u64("sth_truncated")?;
-    let timestamp_ms = r.read_u64("sth_truncated")?;
-    let root_hash = r.read_exact::<32>("sth_truncated")?;
-    let signature = r.read_exact::<64>("sth_truncated")?;
-    r.finish("sth_trailing_bytes")?;
-    Ok(SthBlob {
-        version,
-        log_id,
-        tree_size,
-        timestamp_ms,
-        root_hash,
-        signature,
-    })
+pub(super) fn parse_sth(buf: &[u8]) -> Result<SthBlob, KtError> {
+    SthBlob::decode_canonical(buf).map_err(blob_error("sth_truncated", "sth_trailing_bytes"))
 }
 
-fn parse_inclusion_proof(buf: &[u8]) -> Result<InclusionProof, KtError> {
-    let mut r = BlobReader::new(buf);
-    let version = r.read_u8("inclusion_truncated")?;
-    if version != 0x01 {
+pub(super) fn parse_inclusion_proof(buf: &[u8]) -> Result<InclusionProof, KtError> {
+    let fail = blob_error("inclusion_truncated", "inclusion_trailing_bytes");
+    let mut r = Reader::new(buf);
+    if r.read_u8().map_err(&fail)? != 0x01 {
         return Err(KtError::kt_fail("inclusion_version"));
     }
-    let leaf_index = r.read_u64("inclusion_truncated")?;
-    let tree_size = r.read_u64("inclusion_truncated")?;
-    let sibling_count = r.read_u16("inclusion_truncated")?;
-    let mut siblings = Vec::with_capacity(sibling_count as usize);
-    for _ in 0..sibling_count {
-        siblings.push(r.read_exact::<32>("inclusion_truncated")?);
-    }
-    r.finish("inclusion_trailing_bytes")?;
+    let leaf_index = r.read_u64().map_err(&fail)?;
+    let tree_size = r.read_u64().map_err(&fail)?;
+    let siblings = List16::<Fixed>::read(&mut r).map_err(&fail)?;
+    r.finish().map_err(&fail)?;
     Ok(InclusionProof {
         leaf_index,
         tree_size,
@@ -439,26 +862,22 @@ fn parse_inclusion_proof(buf: &[u8]) -> Result<InclusionProof, KtError> {
     })
 }
 
-fn parse_consistency_proof(buf: &[u8]) -> Result<ConsistencyProof, KtError> {
-    let mut r = BlobReader::new(buf);
-    let version = r.read_u8("consistency_truncated")?;
-    if version != 0x01 {
+pub(super) fn parse_consistency_proof(buf: &[u8]) -> Result<ConsistencyProof, KtError> {
+    let fail = blob_error("consistency_truncated", "consistency_trailing_bytes");
+    let mut r = Reader::new(buf);
+    if r.read_u8().map_err(&fail)? != 0x01 {
         return Err(KtError::kt_fail("consistency_version"));
     }
-    let from_tree_size = r.read_u64("consistency_truncated")?;
+    let from_tree_size = r.read_u64().map_err(&fail)?;
     if from_tree_size == 0 {
         return Err(KtError::kt_fail("consistency_from_tree_size_zero"));
     }
-    let to_tree_size = r.read_u64("consistency_truncated")?;
+    let to_tree_size = r.read_u64().map_err(&fail)?;
     if from_tree_size > to_tree_size {
         return Err(KtError::kt_fail("consistency_size_regression"));
     }
-    let node_count = r.read_u16("consistency_truncated")?;
-    let mut nodes = Vec::with_capacity(node_count as usize);
-    for _ in 0..node_count {
-        nodes.push(r.read_exact::<32>("consistency_truncated")?);
-    }
-    r.finish("consistency_trailing_bytes")?;
+    let nodes = List16::<Fixed>::read(&mut r).map_err(&fail)?;
+    r.finish().map_err(&fail)?;
     Ok(ConsistencyProof {
         from_tree_size,
         to_tree_size,
@@ -466,23 +885,40 @@ fn parse_consistency_proof(buf: &[u8]) -> Result<ConsistencyProof, KtError> {
     })
 }
 
-fn compute_inclusion_root(
+/// RFC 9162 §2.1.3.2. A node on the right edge of a tree whose size is not a power of
+/// two has no sibling at its layer and is carried up without consuming one. `None` if
+/// the sibling count does not match the path for `leaf_index` in `tree_size`.
+pub(super) fn compute_inclusion_root(
     mut current: [u8; 32],
-    mut leaf_index: u64,
+    leaf_index: u64,
+    tree_size: u64,
     siblings: &[[u8; 32]],
-) -> [u8; 32] {
+) -> Option<[u8; 32]> {
+    if leaf_index >= tree_size {
+        return None;
+    }
+    let mut fn_idx = leaf_index;
+    let mut sn_idx = tree_size - 1;
     for sibling in siblings {
-        current = if (leaf_index & 1) == 1 {
-            node_hash(sibling, &current)
+        if sn_idx == 0 {
+            return None;
+        }
+        if (fn_idx & 1) == 1 || fn_idx == sn_idx {
+            current = node_hash(sibling, &current);
+            while (fn_idx & 1) == 0 && fn_idx != 0 {
+                fn_idx >>= 1;
+                sn_idx >>= 1;
+            }
         } else {
-            node_hash(&current, sibling)
-        };
-        leaf_index >>= 1;
+            current = node_hash(&current, sibling);
+        }
+        fn_idx >>= 1;
+        sn_idx >>= 1;
     }
-    current
+    (sn_idx == 0).then_some(current)
 }
 
-fn verify_consistency_proof(
+pub(super) fn verify_consistency_proof(
     old_size: u64,
     new_size: u64,
     old_root: &[u8; 32],
@@ -530,7 +966,7 @@ fn verify_consistency_proof(
     &fr == old_root && &sr == new_root
 }
 
-fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+pub(super) fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
     let mut data = Vec::with_capacity(65);
     data.push(0x01);
     data.extend_from_slice(left);
@@ -538,14 +974,14 @@ fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
     sha256(&data)
 }
 
-fn sha256_prefixed(prefix: u8, data: &[u8]) -> [u8; 32] {
+pub(super) fn sha256_prefixed(prefix: u8, data: &[u8]) -> [u8; 32] {
     let mut buf = Vec::with_capacity(1 + data.len());
     buf.push(prefix);
     buf.extend_from_slice(data);
     sha256(&buf)
 }
 
-fn sha256(data: &[u8]) -> [u8; 32] {
+pub(super) fn sha256(data: &[u8]) -> [u8; 32] {
     let digest = Sha256::digest(data);
     let mut out = [0u8; 32];
     out.copy_from_slice(&digest);
@@ -671,7 +1107,7 @@ mod tests {
             };
 
             let leaf_hash = sha256_prefixed(0x00, &bundle.bundle_leaf_data());
-            let root_hash = compute_inclusion_root(leaf_hash, 0, &siblings);
+            let root_hash = compute_inclusion_root(leaf_hash, 0, tree_size, &siblings).unwrap();
             bundle.kt_inclusion_proof = build_inclusion_proof(0, tree_size, &siblings);
             bundle.kt_sth = build_sth(
                 self.log_seed,
@@ -1067,4 +1503,217 @@ mod tests {
             .unwrap();
         assert_eq!(status, KtVerification::Verified);
     }
+
+    const LOG_B: [u8; 32] = [0xB1; 32];
+    const LOG_C: [u8; 32] = [0xB2; 32];
+
+    fn quorum_verifier(fixture: &SigningFixture, now_ms: u64) -> CanonicalKtVerifier {
+        let pin = |log_id, seed, proof_cache_ttl_seconds| KtPinnedLog {
+            log_id,
+            verifying_key: SigningKey::from_bytes(&seed).verifying_key().to_bytes(),
+            proof_cache_ttl_seconds,
+        };
+        CanonicalKtVerifier::new(
+            [
+                pin([0xAB; 32], fixture.log_seed, 300),
+                pin(LOG_B, [0x31; 32], 300),
+                pin(LOG_C, [0x32; 32], 60),
+            ],
+            KtTimeSource::Fixed(now_ms),
+            false,
+        )
+    }
+
+    /// Log `bundle` in a fresh stand-in log and return that log's evidence for it.
+    fn logged_evidence(
+        log_id: [u8; 32],
+        seed: [u8; 32],
+        timestamp_ms: u64,
+        bundle: &PrekeyBundle,
+    ) -> KtLogEvidence {
+        let mut log = crate::kt::KtLog::new(log_id, seed, KtTimeSource::Fixed(timestamp_ms));
+        log.append_leaf_data(b"earlier entry");
+        let index = log.append_leaf_data(&bundle.bundle_leaf_data());
+        KtLogEvidence {
+            log_id,
+            sth: log.sth().unwrap(),
+            inclusion_proof: log.inclusion_proof(index, log.tree_size()).unwrap(),
+            consistency_proof: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn quorum_report_names_vouching_logs_under_per_log_freshness() {
+        let fixture = SigningFixture::new();
+        let ed25519 = StdEd25519;
+        let bundle = fixture.build_bundle(1, 90_000, vec![], vec![], None);
+        let verifier = quorum_verifier(&fixture, 100_000);
+        let policy = KtQuorumPolicy { threshold: 2 };
+        // 70 s old: fresh under a 300 s window, stale under log C's 60 s window.
+        let extra = [
+            logged_evidence(LOG_B, [0x31; 32], 30_000, &bundle),
+            logged_evidence(LOG_C, [0x32; 32], 30_000, &bundle),
+        ];
+
+        let report = verifier
+            .evaluate_bundle_quorum(&bundle, &extra, policy, &ed25519, fixture.pq_sig.as_ref())
+            .unwrap();
+        assert!(report.met());
+        assert_eq!(report.status, KtVerification::Verified);
+        assert_eq!(report.vouched, vec![[0xAB; 32], LOG_B]);
+        assert_eq!(report.rejected, vec![(LOG_C, "sth_stale")]);
+        assert!(report.missing.is_empty());
+        assert_eq!(verifier.accepted_state(&LOG_B), None);
+
+        let committed = verifier
+            .verify_bundle_quorum(&bundle, &extra, policy, &ed25519, fixture.pq_sig.as_ref())
+            .unwrap();
+        assert_eq!(committed, report);
+        assert_eq!(verifier.accepted_state(&[0xAB; 32]).unwrap().tree_size, 1);
+        assert_eq!(verifier.accepted_state(&LOG_B).unwrap().tree_size, 2);
+        assert_eq!(verifier.accepted_state(&LOG_C), None);
+    }
+
+    #[test]
+    fn unmet_quorum_fails_closed_without_advancing_any_log() {
+        let fixture = SigningFixture::new();
+        let ed25519 = StdEd25519;
+        let bundle = fixture.build_bundle(1, 4_000, vec![], vec![], None);
+        let verifier = quorum_verifier(&fixture, 5_000);
+        let policy = KtQuorumPolicy { threshold: 2 };
+
+        let report = verifier
+            .evaluate_bundle_quorum(&bundle, &[], policy, &ed25519, fixture.pq_sig.as_ref())
+            .unwrap();
+        assert!(!report.met());
+        assert_eq!(report.missing, vec![LOG_B, LOG_C]);
+
+        // A log signing with a key other than its pin does not count.
+        let compromised = [logged_evidence(LOG_B, [0x66; 32], 4_000, &bundle)];
+        let err = verifier
+            .verify_bundle_quorum(
+                &bundle,
+                &compromised,
+                policy,
+                &ed25519,
+                fixture.pq_sig.as_ref(),
+            )
+            .unwrap_err();
+        assert_eq!(err.detail(), "kt_quorum_not_met");
+        assert_eq!(verifier.accepted_state(&[0xAB; 32]), None);
+        let report = verifier
+            .evaluate_bundle_quorum(
+                &bundle,
+                &compromised,
+                policy,
+                &ed25519,
+                fixture.pq_sig.as_ref(),
+            )
+            .unwrap();
+        assert_eq!(
+            report.rejected,
+            vec![(LOG_B, "sth_signature_verify_failed")]
+        );
+
+        let unpinned = [logged_evidence([0xEE; 32], [0x31; 32], 4_000, &bundle)];
+        let report = verifier
+            .evaluate_bundle_quorum(
+                &bundle,
+                &unpinned,
+                policy,
+                &ed25519,
+                fixture.pq_sig.as_ref(),
+            )
+            .unwrap();
+        assert_eq!(report.rejected, vec![([0xEE; 32], "unpinned_log_id")]);
+        assert_eq!(report.missing, vec![LOG_B, LOG_C]);
+
+        let duplicate = [
+            logged_evidence(LOG_B, [0x31; 32], 4_000, &bundle),
+            logged_evidence(LOG_B, [0x31; 32], 4_000, &bundle),
+        ];
+        let err = verifier
+            .evaluate_bundle_quorum(
+                &bundle,
+                &duplicate,
+                policy,
+                &ed25519,
+                fixture.pq_sig.as_ref(),
+            )
+            .unwrap_err();
+        assert_eq!(err.detail(), "duplicate_log_evidence");
+    }
+
+    #[test]
+    fn configured_quorum_applies_to_kt_verifier_with_staged_evidence() {
+        let fixture = SigningFixture::new();
+        let ed25519 = StdEd25519;
+        let bundle = fixture.build_bundle(1, 4_000, vec![], vec![], None);
+        for threshold in [0, 4] {
+            let err = quorum_verifier(&fixture, 5_000)
+                .with_quorum_policy(KtQuorumPolicy { threshold })
+                .unwrap_err();
+            assert_eq!(err.detail(), "kt_quorum_policy_invalid");
+        }
+
+        let verifier = quorum_verifier(&fixture, 5_000)
+            .with_quorum_policy(KtQuorumPolicy { threshold: 2 })
+            .unwrap();
+        let err = verifier
+            .verify_bundle(&bundle, &ed25519, fixture.pq_sig.as_ref())
+            .unwrap_err();
+        assert_eq!(err.detail(), "kt_quorum_not_met");
+
+        verifier
+            .stage_log_evidence(
+                &bundle,
+                vec![logged_evidence(LOG_C, [0x32; 32], 4_000, &bundle)],
+            )
+            .unwrap();
+        let status = verifier
+            .verify_bundle(&bundle, &ed25519, fixture.pq_sig.as_ref())
+            .unwrap();
+        assert_eq!(status, KtVerification::Verified);
+        assert_eq!(verifier.accepted_state(&LOG_C).unwrap().tree_size, 2);
+
+        // A met quorum consumes the staged evidence.
+        let err = verifier
+            .verify_bundle(&bundle, &ed25519, fixture.pq_sig.as_ref())
+            .unwrap_err();
+        assert_eq!(err.detail(), "kt_quorum_not_met");
+    }
+
+    #[test]
+    fn staged_evidence_is_bounded() {
+        let fixture = SigningFixture::new();
+        let ed25519 = StdEd25519;
+        let verifier = quorum_verifier(&fixture, 5_000)
+            .with_quorum_policy(KtQuorumPolicy { threshold: 2 })
+            .unwrap();
+        let bundle = fixture.build_bundle(1, 4_000, vec![], vec![], None);
+        let evidence = logged_evidence(LOG_C, [0x32; 32], 4_000, &bundle);
+
+        let err = verifier
+            .stage_log_evidence(&bundle, vec![evidence.clone(); 4])
+            .unwrap_err();
+        assert_eq!(err.detail(), "staged_evidence_too_large");
+
+        // Staging for MAX_STAGED_BUNDLES other bundles pushes this one out.
+        verifier
+            .stage_log_evidence(&bundle, vec![evidence.clone()])
+            .unwrap();
+        for device_id in 2..2 + MAX_STAGED_BUNDLES as u32 {
+            let mut other = bundle.clone();
+            other.device_id = device_id;
+            verifier.stage_log_evidence(&other, vec![]).unwrap();
+        }
+        assert_eq!(
+            verifier.staged_evidence.lock().unwrap().len(),
+            MAX_STAGED_BUNDLES
+        );
+        let err = verifier
+            .verify_bundle(&bundle, &ed25519, fixture.pq_sig.as_ref())
+            .unwrap_err();
+        assert_eq!(err.detail(), "kt_quorum_not_met");
+    }
 }
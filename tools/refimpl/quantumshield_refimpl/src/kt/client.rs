@@ -0,0 +1,681 @@
+//! KT client plumbing around `CanonicalKtVerifier`: durable accepted-STH state, a
+//! transport-agnostic log endpoint, and split-view evidence.
+//!
+//! State file format (all integers big-endian):
+//! `version(u8)=0x02 || accepted || peers || split_views`, where
+//! `accepted = count(u16) || count * (log_id[32] || sth)` sorted by `log_id`,
+//! `peers = count(u16) || count * (log_id[32] || peer)` grouped by `log_id` in ascending
+//! order, each log's entries oldest first,
+//! `split_views = count(u16) || count * (log_id[32] || peer first || peer second)` oldest
+//! first and at most one per log,
+//! `peer = source_len(u16) || source || sth` and
+//! `sth = tree_size(u64) || timestamp_ms(u64) || root_hash[32]`.
+//! Version 0x01 is `0x01 || accepted` alone and still loads, with no peers or evidence.
+
+use super::canonical::{blob_error, AcceptedSth};
+use super::KtError;
+use crate::codec::Reader;
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const STATE_VERSION: u8 = 0x01;
+const CLIENT_STATE_VERSION: u8 = 0x02;
+
+/// Observed peer STHs kept per log; observing past this drops the log's oldest.
+pub const MAX_PEER_STHS_PER_LOG: usize = 64;
+
+/// Everything a verifier must remember across restarts: accepted STHs, the peer STHs it has
+/// observed, and any split view it has proven.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KtClientState {
+    pub accepted: HashMap<[u8; 32], AcceptedSth>,
+    pub peer_sths: HashMap<[u8; 32], Vec<PeerSth>>,
+    pub split_views: Vec<SplitViewEvidence>,
+}
+
+/// Durable home for KT client state.
+pub trait SthStore: Debug + Send + Sync {
+    /// Load persisted state. A store that has never been written loads as empty.
+    fn load(&self) -> Result<KtClientState, KtError>;
+
+    /// Replace persisted state with `state`. Must be atomic: a crash leaves either the
+    /// old or the new state, never a mix.
+    fn save(&self, state: &KtClientState) -> Result<(), KtError>;
+}
+
+/// Source of fresh log material. Blobs use the same encodings as the `kt_sth` and
+/// `kt_consistency_proof` fields of a `PrekeyBundle`.
+pub trait KtLogEndpoint {
+    /// The log's current signed tree head.
+    fn get_sth(&self) -> Result<Vec<u8>, KtError>;
+
+    /// A consistency proof between two tree sizes of the log.
+    fn get_consistency_proof(
+        &self,
+        from_tree_size: u64,
+        to_tree_size: u64,
+    ) -> Result<Vec<u8>, KtError>;
+}
+
+/// A signed STH as seen via one source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerSth {
+    pub source: String,
+    pub sth: AcceptedSth,
+}
+
+/// Two correctly signed views of one log that cannot both be honest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitViewEvidence {
+    pub log_id: [u8; 32],
+    pub first: PeerSth,
+    pub second: PeerSth,
+}
+
+/// Client state kept in a single file, replaced via write-to-temp and rename.
+#[derive(Debug, Clone)]
+pub struct FileSthStore {
+    path: PathBuf,
+}
+
+impl FileSthStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn tmp_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        self.path.with_file_name(name)
+    }
+}
+
+impl SthStore for FileSthStore {
+    fn load(&self) -> Result<KtClientState, KtError> {
+        match fs::read(&self.path) {
+            Ok(buf) => decode_client_state(&buf),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(KtClientState::default()),
+            Err(_) => Err(KtError::kt_fail("kt_state_read_failed")),
+        }
+    }
+
+    fn save(&self, state: &KtClientState) -> Result<(), KtError> {
+        let buf = encode_client_state(state)?;
+        let tmp = self.tmp_path();
+        let write = || -> std::io::Result<()> {
+            let mut f = fs::File::create(&tmp)?;
+            f.write_all(&buf)?;
+            f.sync_all()?;
+            fs::rename(&tmp, &self.path)
+        };
+        write().map_err(|_| {
+            let _ = fs::remove_file(&tmp);
+            KtError::kt_fail("kt_state_persist_failed")
+        })
+    }
+}
+
+/// Process-local store, for callers that want the `SthStore` seam without a file.
+#[derive(Debug, Default)]
+pub struct MemorySthStore {
+    state: Mutex<KtClientState>,
+}
+
+impl SthStore for MemorySthStore {
+    fn load(&self) -> Result<KtClientState, KtError> {
+        self.state
+            .lock()
+            .map(|s| s.clone())
+            .map_err(|_| KtError::kt_fail("kt_state_poisoned"))
+    }
+
+    fn save(&self, state: &KtClientState) -> Result<(), KtError> {
+        *self
+            .state
+            .lock()
+            .map_err(|_| KtError::kt_fail("kt_state_poisoned"))? = state.clone();
+        Ok(())
+    }
+}
+
+fn state_count(len: usize) -> Result<[u8; 2], KtError> {
+    u16::try_from(len)
+        .map(u16::to_be_bytes)
+        .map_err(|_| KtError::kt_fail("kt_state_too_many_entries"))
+}
+
+fn encode_sth(out: &mut Vec<u8>, sth: &AcceptedSth) {
+    out.extend_from_slice(&sth.tree_size.to_be_bytes());
+    out.extend_from_slice(&sth.timestamp_ms.to_be_bytes());
+    out.extend_from_slice(&sth.root_hash);
+}
+
+fn encode_peer(out: &mut Vec<u8>, peer: &PeerSth) -> Result<(), KtError> {
+    out.extend_from_slice(&state_count(peer.source.len())?);
+    out.extend_from_slice(peer.source.as_bytes());
+    encode_sth(out, &peer.sth);
+    Ok(())
+}
+
+fn encode_accepted(
+    out: &mut Vec<u8>,
+    accepted: &HashMap<[u8; 32], AcceptedSth>,
+) -> Result<(), KtError> {
+    out.extend_from_slice(&state_count(accepted.len())?);
+    let mut entries: Vec<_> = accepted.iter().collect();
+    entries.sort_by_key(|(log_id, _)| **log_id);
+    for (log_id, sth) in entries {
+        out.extend_from_slice(log_id);
+        encode_sth(out, sth);
+    }
+    Ok(())
+}
+
+/// Version 0x01 layout: accepted STHs only.
+pub fn encode_state(accepted: &HashMap<[u8; 32], AcceptedSth>) -> Result<Vec<u8>, KtError> {
+    let mut out = Vec::with_capacity(3 + accepted.len() * 80);
+    out.push(STATE_VERSION);
+    encode_accepted(&mut out, accepted)?;
+    Ok(out)
+}
+
+/// Version 0x02 layout: accepted STHs, observed peer STHs and split-view evidence.
+pub fn encode_client_state(state: &KtClientState) -> Result<Vec<u8>, KtError> {
+    let mut out = vec![CLIENT_STATE_VERSION];
+    encode_accepted(&mut out, &state.accepted)?;
+
+    let mut logs: Vec<_> = state.peer_sths.iter().collect();
+    logs.sort_by_key(|(log_id, _)| **log_id);
+    let peers: usize = logs.iter().map(|(_, seen)| seen.len()).sum();
+    out.extend_from_slice(&state_count(peers)?);
+    for (log_id, seen) in logs {
+        for peer in seen {
+            out.extend_from_slice(log_id);
+            encode_peer(&mut out, peer)?;
+        }
+    }
+
+    out.extend_from_slice(&state_count(state.split_views.len())?);
+    for evidence in &state.split_views {
+        out.extend_from_slice(&evidence.log_id);
+        encode_peer(&mut out, &evidence.first)?;
+        encode_peer(&mut out, &evidence.second)?;
+    }
+    Ok(out)
+}
+
+fn decode_sth(r: &mut Reader<'_>) -> Result<AcceptedSth, KtError> {
+    let fail = blob_error("kt_state_truncated", "kt_state_trailing_bytes");
+    let tree_size = r.read_u64().map_err(&fail)?;
+    let timestamp_ms = r.read_u64().map_err(&fail)?;
+    let root_hash = r.read_exact::<32>().map_err(&fail)?;
+    Ok(AcceptedSth {
+        tree_size,
+        root_hash,
+        timestamp_ms,
+    })
+}
+
+fn decode_peer(r: &mut Reader<'_>) -> Result<PeerSth, KtError> {
+    let fail = blob_error("kt_state_truncated", "kt_state_trailing_bytes");
+    let len = r.read_u16().map_err(&fail)? as usize;
+    let source = String::from_utf8(r.read_slice(len).map_err(&fail)?.to_vec())
+        .map_err(|_| KtError::kt_fail("kt_state_noncanonical"))?;
+    Ok(PeerSth {
+        source,
+        sth: decode_sth(r)?,
+    })
+}
+
+fn decode_accepted(r: &mut Reader<'_>) -> Result<HashMap<[u8; 32], AcceptedSth>, KtError> {
+    let fail = blob_error("kt_state_truncated", "kt_state_trailing_bytes");
+    let count = r.read_u16().map_err(&fail)?;
+    let mut out = HashMap::with_capacity(count as usize);
+    let mut last: Option<[u8; 32]> = None;
+    for _ in 0..count {
+        let log_id = r.read_exact::<32>().map_err(&fail)?;
+        if last.is_some_and(|prev| prev >= log_id) {
+            return Err(KtError::kt_fail("kt_state_noncanonical"));
+        }
+        last = Some(log_id);
+        out.insert(log_id, decode_sth(r)?);
+    }
+    Ok(out)
+}
+
+/// Parse the version 0x01 layout.
+pub fn decode_state(buf: &[u8]) -> Result<HashMap<[u8; 32], AcceptedSth>, KtError> {
+    let fail = blob_error("kt_state_truncated", "kt_state_trailing_bytes");
+    let mut r = Reader::new(buf);
+    if r.read_u8().map_err(&fail)? != STATE_VERSION {
+        return Err(KtError::kt_fail("kt_state_version"));
+    }
+    let out = decode_accepted(&mut r)?;
+    r.finish().map_err(&fail)?;
+    Ok(out)
+}
+
+/// Parse either layout; version 0x01 loads with no peer STHs or evidence.
+pub fn decode_client_state(buf: &[u8]) -> Result<KtClientState, KtError> {
+    let fail = blob_error("kt_state_truncated", "kt_state_trailing_bytes");
+    let mut r = Reader::new(buf);
+    match r.read_u8().map_err(&fail)? {
+        STATE_VERSION => {
+            return decode_state(buf).map(|accepted| KtClientState {
+                accepted,
+                ..KtClientState::default()
+            })
+        }
+        CLIENT_STATE_VERSION => {}
+        _ => return Err(KtError::kt_fail("kt_state_version")),
+    }
+    let accepted = decode_accepted(&mut r)?;
+
+    let mut peer_sths: HashMap<[u8; 32], Vec<PeerSth>> = HashMap::new();
+    let mut last: Option<[u8; 32]> = None;
+    for _ in 0..r.read_u16().map_err(&fail)? {
+        let log_id = r.read_exact::<32>().map_err(&fail)?;
+        if last.is_some_and(|prev| prev > log_id) {
+            return Err(KtError::kt_fail("kt_state_noncanonical"));
+        }
+        last = Some(log_id);
+        let seen = peer_sths.entry(log_id).or_default();
+        let peer = decode_peer(&mut r)?;
+        if seen.contains(&peer) || seen.len() == MAX_PEER_STHS_PER_LOG {
+            return Err(KtError::kt_fail("kt_state_noncanonical"));
+        }
+        seen.push(peer);
+    }
+
+    let mut split_views: Vec<SplitViewEvidence> = Vec::new();
+    for _ in 0..r.read_u16().map_err(&fail)? {
+        let log_id = r.read_exact::<32>().map_err(&fail)?;
+        if split_views.iter().any(|e| e.log_id == log_id) {
+            return Err(KtError::kt_fail("kt_state_noncanonical"));
+        }
+        let first = decode_peer(&mut r)?;
+        let second = decode_peer(&mut r)?;
+        split_views.push(SplitViewEvidence {
+            log_id,
+            first,
+            second,
+        });
+    }
+    r.finish().map_err(&fail)?;
+    Ok(KtClientState {
+        accepted,
+        peer_sths,
+        split_views,
+    })
+}
+
+#[cfg(all(test, feature = "stdcrypto"))]
+mod tests {
+    use super::*;
+    use crate::crypto::stdcrypto::StdEd25519;
+    use crate::kt::KtLog;
+    use crate::kt::{CanonicalKtVerifier, KtPinnedLog, KtTimeSource, ACCEPTED_SOURCE};
+    use ed25519_dalek::SigningKey;
+
+    const LOG_ID: [u8; 32] = [0xAB; 32];
+    const LOG_SEED: [u8; 32] = [0x17; 32];
+    const NOW_MS: u64 = 1_000_000;
+
+    fn stand_in_log(seed: [u8; 32], entries: &[&[u8]]) -> KtLog {
+        let mut log = KtLog::new(LOG_ID, seed, KtTimeSource::Fixed(NOW_MS));
+        entries.iter().for_each(|e| {
+            log.append_leaf_data(e);
+        });
+        log
+    }
+
+    fn verifier() -> CanonicalKtVerifier {
+        CanonicalKtVerifier::new(
+            [KtPinnedLog {
+                log_id: LOG_ID,
+                verifying_key: SigningKey::from_bytes(&LOG_SEED).verifying_key().to_bytes(),
+                proof_cache_ttl_seconds: 300,
+            }],
+            KtTimeSource::Fixed(NOW_MS),
+            false,
+        )
+    }
+
+    fn temp_state_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("qsl_kt_client_{}_{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("kt_state.bin")
+    }
+
+    #[derive(Debug)]
+    struct FailingStore;
+
+    impl SthStore for FailingStore {
+        fn load(&self) -> Result<KtClientState, KtError> {
+            Ok(KtClientState::default())
+        }
+
+        fn save(&self, _: &KtClientState) -> Result<(), KtError> {
+            Err(KtError::kt_fail("kt_state_persist_failed"))
+        }
+    }
+
+    #[test]
+    fn state_encoding_roundtrips_and_rejects_noncanonical_input() {
+        let mut state = HashMap::new();
+        for (i, size) in [(2u8, 5u64), (1, 9)] {
+            state.insert(
+                [i; 32],
+                AcceptedSth {
+                    tree_size: size,
+                    root_hash: [i ^ 0xFF; 32],
+                    timestamp_ms: 77,
+                },
+            );
+        }
+        let buf = encode_state(&state).unwrap();
+        assert_eq!(buf.len(), 3 + 2 * 80);
+        assert_eq!(&buf[3..35], &[1u8; 32]);
+        assert_eq!(decode_state(&buf).unwrap(), state);
+
+        let mut swapped = buf[..3].to_vec();
+        swapped.extend_from_slice(&buf[83..]);
+        swapped.extend_from_slice(&buf[3..83]);
+        assert_eq!(
+            decode_state(&swapped).unwrap_err().detail(),
+            "kt_state_noncanonical"
+        );
+        let mut trailing = buf.clone();
+        trailing.push(0);
+        assert_eq!(
+            decode_state(&trailing).unwrap_err().detail(),
+            "kt_state_trailing_bytes"
+        );
+        assert_eq!(
+            decode_state(&buf[..buf.len() - 1]).unwrap_err().detail(),
+            "kt_state_truncated"
+        );
+    }
+
+    #[test]
+    fn refresh_advances_over_consistency_proofs_and_persists_across_runs() {
+        let path = temp_state_path("persist");
+        let ed25519 = StdEd25519;
+        let mut log = stand_in_log(LOG_SEED, &[b"a", b"b", b"c"]);
+
+        let kt = verifier()
+            .with_sth_store(Box::new(FileSthStore::new(&path)))
+            .unwrap();
+        assert_eq!(
+            kt.refresh_from_log(&LOG_ID, &log, &ed25519)
+                .unwrap()
+                .tree_size,
+            3
+        );
+        for (entry, size) in [(b"d", 4), (b"e", 5)] {
+            log.append_leaf_data(entry);
+            let sth = kt.refresh_from_log(&LOG_ID, &log, &ed25519).unwrap();
+            assert_eq!(sth.tree_size, size);
+        }
+        for _ in 0..4 {
+            log.append_leaf_data(b"more");
+        }
+        assert_eq!(
+            kt.refresh_from_log(&LOG_ID, &log, &ed25519)
+                .unwrap()
+                .tree_size,
+            9
+        );
+        let accepted = kt.accepted_state(&LOG_ID).unwrap();
+        drop(kt);
+
+        // A later run resumes from disk instead of trusting the log's first answer.
+        let kt = verifier()
+            .with_sth_store(Box::new(FileSthStore::new(&path)))
+            .unwrap();
+        assert_eq!(kt.accepted_state(&LOG_ID), Some(accepted));
+        let forked = stand_in_log(LOG_SEED, &[b"x".as_slice(); 12]);
+        let err = kt.refresh_from_log(&LOG_ID, &forked, &ed25519).unwrap_err();
+        assert_eq!(err.detail(), "consistency_proof_invalid");
+        assert_eq!(kt.accepted_state(&LOG_ID), Some(accepted));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn corrupt_state_file_fails_closed() {
+        let path = temp_state_path("corrupt");
+        fs::write(&path, [0x01, 0x00]).unwrap();
+        let err = verifier()
+            .with_sth_store(Box::new(FileSthStore::new(&path)))
+            .unwrap_err();
+        assert_eq!(err.detail(), "kt_state_truncated");
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn client_state_encoding_roundtrips_and_loads_v1_files() {
+        let sth = |size: u64, root: u8| AcceptedSth {
+            tree_size: size,
+            root_hash: [root; 32],
+            timestamp_ms: 77,
+        };
+        let peer = |source: &str, size: u64, root: u8| PeerSth {
+            source: source.to_string(),
+            sth: sth(size, root),
+        };
+        let mut state = KtClientState::default();
+        state.accepted.insert([1; 32], sth(9, 1));
+        state
+            .peer_sths
+            .insert([2; 32], vec![peer("peer-b", 4, 2), peer("peer-a", 3, 2)]);
+        state.peer_sths.insert([1; 32], vec![peer("peer-c", 8, 1)]);
+        state.split_views.push(SplitViewEvidence {
+            log_id: [2; 32],
+            first: peer("peer-b", 4, 2),
+            second: peer("peer-d", 4, 3),
+        });
+        let buf = encode_client_state(&state).unwrap();
+        assert_eq!(buf[0], CLIENT_STATE_VERSION);
+        assert_eq!(decode_client_state(&buf).unwrap(), state);
+
+        let mut twice = state.clone();
+        twice.split_views.push(twice.split_views[0].clone());
+        assert_eq!(
+            decode_client_state(&encode_client_state(&twice).unwrap())
+                .unwrap_err()
+                .detail(),
+            "kt_state_noncanonical"
+        );
+        let mut trailing = buf.clone();
+        trailing.push(0);
+        assert_eq!(
+            decode_client_state(&trailing).unwrap_err().detail(),
+            "kt_state_trailing_bytes"
+        );
+
+        let v1 = encode_state(&state.accepted).unwrap();
+        let loaded = decode_client_state(&v1).unwrap();
+        assert_eq!(loaded.accepted, state.accepted);
+        assert!(loaded.peer_sths.is_empty() && loaded.split_views.is_empty());
+    }
+
+    #[test]
+    fn split_view_evidence_and_peer_sths_survive_restart() {
+        let path = temp_state_path("split_view");
+        let ed25519 = StdEd25519;
+        let honest = stand_in_log(LOG_SEED, &[b"a", b"b"]);
+        let fork = stand_in_log(LOG_SEED, &[b"a", b"evil"]);
+
+        let kt = verifier()
+            .with_sth_store(Box::new(FileSthStore::new(&path)))
+            .unwrap();
+        kt.observe_peer_sth("peer-1", &honest.sth_at(1, NOW_MS).unwrap(), &ed25519)
+            .unwrap();
+        kt.observe_peer_sth("peer-2", &honest.sth_at(2, NOW_MS).unwrap(), &ed25519)
+            .unwrap();
+        drop(kt);
+
+        // Observations made before a restart still catch a fork after it.
+        let kt = verifier()
+            .with_sth_store(Box::new(FileSthStore::new(&path)))
+            .unwrap();
+        assert_eq!(
+            kt.observe_peer_sth("peer-3", &fork.sth_at(2, NOW_MS).unwrap(), &ed25519)
+                .unwrap_err()
+                .detail(),
+            "split_view_detected"
+        );
+        let evidence = kt.split_view_evidence();
+        drop(kt);
+
+        let kt = verifier()
+            .with_sth_store(Box::new(FileSthStore::new(&path)))
+            .unwrap();
+        assert_eq!(kt.split_view_evidence(), evidence);
+        assert_eq!(evidence[0].first.source, "peer-2");
+        assert_eq!(evidence[0].second.source, "peer-3");
+        assert_eq!(
+            kt.refresh_from_log(&LOG_ID, &honest, &ed25519)
+                .unwrap_err()
+                .detail(),
+            "split_view_detected"
+        );
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn refresh_rejects_wrong_key_stale_sth_and_regression() {
+        let ed25519 = StdEd25519;
+        let kt = verifier();
+        let impostor = stand_in_log([0x99; 32], &[b"a"]);
+        assert_eq!(
+            kt.refresh_from_log(&LOG_ID, &impostor, &ed25519)
+                .unwrap_err()
+                .detail(),
+            "sth_signature_verify_failed"
+        );
+
+        let mut log = stand_in_log(LOG_SEED, &[b"a", b"b"]);
+        log.set_time_source(KtTimeSource::Fixed(NOW_MS - 301_000));
+        assert_eq!(
+            kt.refresh_from_log(&LOG_ID, &log, &ed25519)
+                .unwrap_err()
+                .detail(),
+            "sth_stale"
+        );
+        assert_eq!(kt.accepted_state(&LOG_ID), None);
+
+        log.set_time_source(KtTimeSource::Fixed(NOW_MS));
+        kt.refresh_from_log(&LOG_ID, &log, &ed25519).unwrap();
+        let shorter = stand_in_log(LOG_SEED, &[b"a"]);
+        assert_eq!(
+            kt.refresh_from_log(&LOG_ID, &shorter, &ed25519)
+                .unwrap_err()
+                .detail(),
+            "tree_size_regressed"
+        );
+    }
+
+    #[test]
+    fn persist_failure_rejects_without_advancing() {
+        let ed25519 = StdEd25519;
+        let kt = verifier().with_sth_store(Box::new(FailingStore)).unwrap();
+        let log = stand_in_log(LOG_SEED, &[b"a"]);
+        assert_eq!(
+            kt.refresh_from_log(&LOG_ID, &log, &ed25519)
+                .unwrap_err()
+                .detail(),
+            "kt_state_persist_failed"
+        );
+        assert_eq!(kt.accepted_state(&LOG_ID), None);
+    }
+
+    #[test]
+    fn conflicting_peer_sths_at_same_size_are_split_view() {
+        let ed25519 = StdEd25519;
+        let kt = verifier();
+        let honest = stand_in_log(LOG_SEED, &[b"a", b"b"]);
+        let fork = stand_in_log(LOG_SEED, &[b"a", b"evil"]);
+
+        kt.observe_peer_sth("peer-1", &honest.sth_at(2, NOW_MS).unwrap(), &ed25519)
+            .unwrap();
+        kt.observe_peer_sth("peer-2", &honest.sth_at(2, NOW_MS).unwrap(), &ed25519)
+            .unwrap();
+        let err = kt
+            .observe_peer_sth("peer-3", &fork.sth_at(2, NOW_MS).unwrap(), &ed25519)
+            .unwrap_err();
+        assert_eq!(err.detail(), "split_view_detected");
+
+        let evidence = kt.split_view_evidence();
+        assert_eq!(evidence.len(), 1);
+        assert_eq!(evidence[0].log_id, LOG_ID);
+        assert_eq!(evidence[0].first.source, "peer-1");
+        assert_eq!(evidence[0].second.source, "peer-3");
+        assert_ne!(
+            evidence[0].first.sth.root_hash,
+            evidence[0].second.sth.root_hash
+        );
+
+        // The log is no longer trusted for anything.
+        assert_eq!(
+            kt.refresh_from_log(&LOG_ID, &honest, &ed25519)
+                .unwrap_err()
+                .detail(),
+            "split_view_detected"
+        );
+    }
+
+    #[test]
+    fn unsigned_peer_sth_is_not_evidence() {
+        let ed25519 = StdEd25519;
+        let kt = verifier();
+        let forged = stand_in_log([0x99; 32], &[b"a"]);
+        assert_eq!(
+            kt.observe_peer_sth("peer-1", &forged.sth_at(1, NOW_MS).unwrap(), &ed25519)
+                .unwrap_err()
+                .detail(),
+            "sth_signature_verify_failed"
+        );
+        assert!(kt.split_view_evidence().is_empty());
+    }
+
+    #[test]
+    fn cross_check_flags_peer_sth_off_our_history() {
+        let ed25519 = StdEd25519;
+        let kt = verifier();
+        let mut log = stand_in_log(LOG_SEED, &[b"a", b"b", b"c"]);
+        kt.refresh_from_log(&LOG_ID, &log, &ed25519).unwrap();
+
+        // Honest peers behind and ahead of us are consistent.
+        kt.observe_peer_sth("behind", &log.sth_at(2, NOW_MS).unwrap(), &ed25519)
+            .unwrap();
+        log.append_leaf_data(b"d");
+        log.append_leaf_data(b"e");
+        kt.observe_peer_sth("ahead", &log.sth_at(5, NOW_MS).unwrap(), &ed25519)
+            .unwrap();
+        kt.cross_check_peer_sths(&LOG_ID, &log).unwrap();
+
+        let fork = stand_in_log(LOG_SEED, &[b"a", b"b", b"c", b"z"]);
+        kt.observe_peer_sth("forked", &fork.sth_at(4, NOW_MS).unwrap(), &ed25519)
+            .unwrap();
+        assert_eq!(
+            kt.cross_check_peer_sths(&LOG_ID, &log)
+                .unwrap_err()
+                .detail(),
+            "split_view_detected"
+        );
+        let evidence = kt.split_view_evidence();
+        assert_eq!(evidence[0].first.source, ACCEPTED_SOURCE);
+        assert_eq!(evidence[0].second.source, "forked");
+    }
+}
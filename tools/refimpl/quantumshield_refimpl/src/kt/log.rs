@@ -0,0 +1,371 @@
+//! Append-only reference KT log producing the blobs `CanonicalKtVerifier` consumes.
+//!
+//! Leaves are `SHA-256(0x00 || BundleLeafData)` and interior nodes
+//! `SHA-256(0x01 || left || right)` over an RFC 9162 tree. STHs are signed under the
+//! DOC-CAN-008 §5.1 input with the log's Ed25519 key; proofs use the §5.2 / §5.3
+//! encodings. State lives in memory only; this is a test and interop fixture, not a
+//! production log.
+
+use super::canonical::{
+    node_hash, sha256, sha256_prefixed, ConsistencyProof, InclusionProof, SthBlob,
+};
+use super::client::KtLogEndpoint;
+use super::monitor::KtEntrySource;
+use super::{KtError, KtPinnedLog, KtTimeSource};
+use crate::crypto::stdcrypto::StdEd25519;
+use crate::crypto::traits::SigEd25519;
+use crate::qsp::PrekeyBundle;
+
+pub struct KtLog {
+    log_id: [u8; 32],
+    signing_seed: [u8; 32],
+    time_source: KtTimeSource,
+    entries: Vec<Vec<u8>>,
+    leaves: Vec<[u8; 32]>,
+}
+
+impl KtLog {
+    pub fn new(log_id: [u8; 32], signing_seed: [u8; 32], time_source: KtTimeSource) -> Self {
+        Self {
+            log_id,
+            signing_seed,
+            time_source,
+            entries: Vec::new(),
+            leaves: Vec::new(),
+        }
+    }
+
+    pub fn log_id(&self) -> [u8; 32] {
+        self.log_id
+    }
+
+    pub fn tree_size(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    pub fn verifying_key(&self) -> [u8; 32] {
+        ed25519_dalek::SigningKey::from_bytes(&self.signing_seed)
+            .verifying_key()
+            .to_bytes()
+    }
+
+    /// The pin a verifier needs for this log.
+    pub fn pinned(&self, proof_cache_ttl_seconds: u64) -> KtPinnedLog {
+        KtPinnedLog {
+            log_id: self.log_id,
+            verifying_key: self.verifying_key(),
+            proof_cache_ttl_seconds,
+        }
+    }
+
+    pub fn set_time_source(&mut self, time_source: KtTimeSource) {
+        self.time_source = time_source;
+    }
+
+    /// Log `bundle.bundle_leaf_data()`. The bundle must name this log.
+    pub fn append_bundle(&mut self, bundle: &PrekeyBundle) -> Result<u64, KtError> {
+        if bundle.kt_log_id != self.log_id {
+            return Err(KtError::kt_fail("log_id_mismatch"));
+        }
+        Ok(self.append_leaf_data(&bundle.bundle_leaf_data()))
+    }
+
+    /// Append raw `BundleLeafData` and return its leaf index.
+    pub fn append_leaf_data(&mut self, leaf_data: &[u8]) -> u64 {
+        self.leaves.push(sha256_prefixed(0x00, leaf_data));
+        self.entries.push(leaf_data.to_vec());
+        self.leaves.len() as u64 - 1
+    }
+
+    /// Leaf data at `index`, as it was appended.
+    pub fn entry(&self, index: u64) -> Option<&[u8]> {
+        self.entries
+            .get(usize::try_from(index).ok()?)
+            .map(Vec::as_slice)
+    }
+
+    pub fn root_hash(&self, tree_size: u64) -> Result<[u8; 32], KtError> {
+        Ok(mth(self.prefix(tree_size)?))
+    }
+
+    /// STH over the whole log, stamped with the log's clock.
+    pub fn sth(&self) -> Result<Vec<u8>, KtError> {
+        self.sth_at(self.tree_size(), self.time_source.now_ms())
+    }
+
+    /// STH over the first `tree_size` leaves. Empty trees are not signed: no consistency
+    /// proof can start from size zero, so a verifier could never move past one.
+    pub fn sth_at(&self, tree_size: u64, timestamp_ms: u64) -> Result<Vec<u8>, KtError> {
+        if tree_size == 0 {
+            return Err(KtError::kt_fail("log_empty"));
+        }
+        let mut sth = SthBlob {
+            version: 0x01,
+            log_id: self.log_id,
+            tree_size,
+            timestamp_ms,
+            root_hash: self.root_hash(tree_size)?,
+            signature: [0u8; 64],
+        };
+        let sig = StdEd25519.sign(&self.signing_seed, &sth.signature_input());
+        sth.signature = sig
+            .try_into()
+            .map_err(|_| KtError::kt_fail("log_sign_failed"))?;
+        sth.encode()
+    }
+
+    pub fn inclusion_proof(&self, leaf_index: u64, tree_size: u64) -> Result<Vec<u8>, KtError> {
+        let leaves = self.prefix(tree_size)?;
+        if leaf_index >= tree_size {
+            return Err(KtError::kt_fail("log_leaf_index_out_of_range"));
+        }
+        let mut siblings = Vec::new();
+        inclusion_path(leaf_index as usize, leaves, &mut siblings);
+        InclusionProof {
+            leaf_index,
+            tree_size,
+            siblings,
+        }
+        .encode()
+    }
+
+    /// Consistency proof in the layout `verify_consistency_proof` expects: RFC 9162
+    /// `PROOF(m, D[n])`, preceded by the old root when `m` is a power of two.
+    pub fn consistency_proof(
+        &self,
+        from_tree_size: u64,
+        to_tree_size: u64,
+    ) -> Result<Vec<u8>, KtError> {
+        let leaves = self.prefix(to_tree_size)?;
+        if from_tree_size == 0 || from_tree_size > to_tree_size {
+            return Err(KtError::kt_fail("log_consistency_range"));
+        }
+        let m = from_tree_size as usize;
+        let mut nodes = Vec::new();
+        if m < leaves.len() {
+            if m.is_power_of_two() {
+                nodes.push(mth(&leaves[..m]));
+            }
+            consistency_path(m, leaves, true, &mut nodes);
+        }
+        ConsistencyProof {
+            from_tree_size,
+            to_tree_size,
+            nodes,
+        }
+        .encode()
+    }
+
+    /// Fill `bundle.kt_sth` and `bundle.kt_inclusion_proof` for the current tree. The
+    /// consistency proof depends on what the verifier last accepted, so it is left to the
+    /// caller. The bundle must be re-signed afterwards: `BundleTBS` covers these fields.
+    pub fn attach_evidence(
+        &self,
+        bundle: &mut PrekeyBundle,
+        leaf_index: u64,
+    ) -> Result<(), KtError> {
+        if self.entry(leaf_index) != Some(bundle.bundle_leaf_data().as_slice()) {
+            return Err(KtError::kt_fail("log_entry_mismatch"));
+        }
+        bundle.kt_sth = self.sth()?;
+        bundle.kt_inclusion_proof = self.inclusion_proof(leaf_index, self.tree_size())?;
+        bundle.kt_consistency_proof = Vec::new();
+        Ok(())
+    }
+
+    fn prefix(&self, tree_size: u64) -> Result<&[[u8; 32]], KtError> {
+        usize::try_from(tree_size)
+            .ok()
+            .and_then(|n| self.leaves.get(..n))
+            .ok_or_else(|| KtError::kt_fail("log_tree_size_out_of_range"))
+    }
+}
+
+impl Drop for KtLog {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.signing_seed);
+    }
+}
+
+impl KtLogEndpoint for KtLog {
+    fn get_sth(&self) -> Result<Vec<u8>, KtError> {
+        self.sth()
+    }
+
+    fn get_consistency_proof(
+        &self,
+        from_tree_size: u64,
+        to_tree_size: u64,
+    ) -> Result<Vec<u8>, KtError> {
+        self.consistency_proof(from_tree_size, to_tree_size)
+    }
+}
+
+impl KtEntrySource for KtLog {
+    fn get_entries(&self, start: u64, end: u64) -> Result<Vec<Vec<u8>>, KtError> {
+        if start > end || end > self.tree_size() {
+            return Err(KtError::kt_fail("log_entries_range"));
+        }
+        Ok((start..end)
+            .filter_map(|i| self.entry(i).map(<[u8]>::to_vec))
+            .collect())
+    }
+}
+
+/// Largest power of two strictly below `n` (`n >= 2`).
+fn split(n: usize) -> usize {
+    let mut k = 1;
+    while k << 1 < n {
+        k <<= 1;
+    }
+    k
+}
+
+/// RFC 9162 `MTH`.
+fn mth(leaves: &[[u8; 32]]) -> [u8; 32] {
+    match leaves.len() {
+        0 => sha256(&[]),
+        1 => leaves[0],
+        n => {
+            let k = split(n);
+            node_hash(&mth(&leaves[..k]), &mth(&leaves[k..]))
+        }
+    }
+}
+
+/// RFC 9162 `PATH(m, D[n])`, leaf to root.
+fn inclusion_path(m: usize, leaves: &[[u8; 32]], out: &mut Vec<[u8; 32]>) {
+    if leaves.len() <= 1 {
+        return;
+    }
+    let k = split(leaves.len());
+    if m < k {
+        inclusion_path(m, &leaves[..k], out);
+        out.push(mth(&leaves[k..]));
+    } else {
+        inclusion_path(m - k, &leaves[k..], out);
+        out.push(mth(&leaves[..k]));
+    }
+}
+
+/// RFC 9162 `SUBPROOF(m, D[n], b)`.
+fn consistency_path(m: usize, leaves: &[[u8; 32]], complete: bool, out: &mut Vec<[u8; 32]>) {
+    if m == leaves.len() {
+        if !complete {
+            out.push(mth(leaves));
+        }
+        return;
+    }
+    let k = split(leaves.len());
+    if m <= k {
+        consistency_path(m, &leaves[..k], complete, out);
+        out.push(mth(&leaves[k..]));
+    } else {
+        consistency_path(m - k, &leaves[k..], false, out);
+        out.push(mth(&leaves[..k]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::canonical::{
+        compute_inclusion_root, parse_consistency_proof, parse_inclusion_proof, parse_sth,
+        verify_consistency_proof,
+    };
+    use super::*;
+
+    fn log_with(n: usize) -> KtLog {
+        let mut log = KtLog::new([0x4C; 32], [0x07; 32], KtTimeSource::Fixed(5_000));
+        for i in 0..n {
+            log.append_leaf_data(format!("entry-{i}").as_bytes());
+        }
+        log
+    }
+
+    #[test]
+    fn every_inclusion_proof_verifies_for_every_tree_size() {
+        let log = log_with(13);
+        for size in 1..=13u64 {
+            let root = log.root_hash(size).unwrap();
+            for index in 0..size {
+                let proof =
+                    parse_inclusion_proof(&log.inclusion_proof(index, size).unwrap()).unwrap();
+                let leaf = sha256_prefixed(0x00, log.entry(index).unwrap());
+                assert_eq!(
+                    compute_inclusion_root(leaf, index, size, &proof.siblings),
+                    Some(root),
+                    "leaf {index} of {size}"
+                );
+                // Wrong position or a dropped sibling must not verify.
+                assert_ne!(
+                    compute_inclusion_root(leaf, (index + 1) % size, size, &proof.siblings),
+                    if size > 1 { Some(root) } else { None }
+                );
+                if let Some((_, short)) = proof.siblings.split_last() {
+                    assert_eq!(compute_inclusion_root(leaf, index, size, short), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_consistency_proof_verifies_for_every_size_pair() {
+        let log = log_with(13);
+        for to in 1..=13u64 {
+            for from in 1..=to {
+                let proof =
+                    parse_consistency_proof(&log.consistency_proof(from, to).unwrap()).unwrap();
+                let (old, new) = (log.root_hash(from).unwrap(), log.root_hash(to).unwrap());
+                assert!(
+                    verify_consistency_proof(from, to, &old, &new, &proof.nodes),
+                    "{from} -> {to}"
+                );
+                if from < to {
+                    assert!(!verify_consistency_proof(
+                        from,
+                        to,
+                        &new,
+                        &new,
+                        &proof.nodes
+                    ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sth_is_signed_with_the_pinned_key_format() {
+        let log = log_with(3);
+        let sth = parse_sth(&log.sth().unwrap()).unwrap();
+        assert_eq!(sth.tree_size, 3);
+        assert_eq!(sth.timestamp_ms, 5_000);
+        assert_eq!(sth.root_hash, log.root_hash(3).unwrap());
+        assert!(StdEd25519.verify(
+            &log.pinned(300).verifying_key,
+            &sth.signature_input(),
+            &sth.signature
+        ));
+    }
+
+    #[test]
+    fn out_of_range_requests_are_rejected() {
+        let log = log_with(2);
+        assert_eq!(log_with(0).sth().unwrap_err().detail(), "log_empty");
+        assert_eq!(
+            log.inclusion_proof(2, 2).unwrap_err().detail(),
+            "log_leaf_index_out_of_range"
+        );
+        assert_eq!(
+            log.inclusion_proof(0, 3).unwrap_err().detail(),
+            "log_tree_size_out_of_range"
+        );
+        assert_eq!(
+            log.consistency_proof(0, 2).unwrap_err().detail(),
+            "log_consistency_range"
+        );
+        assert_eq!(
+            log.consistency_proof(2, 1).unwrap_err().detail(),
+            "log_consistency_range"
+        );
+    }
+}
@@ -1,12 +1,29 @@
 //! Key Transparency (KT) verification interfaces and canonical verifier wiring.
 
 mod canonical;
+mod client;
+#[cfg(feature = "stdcrypto")]
+mod log;
+mod monitor;
+mod quorum;
 
 use crate::crypto::traits::{PqSigMldsa65, SigEd25519};
 use crate::qsp::{HandshakeInit, PrekeyBundle};
 use thiserror::Error;
 
-pub use canonical::{AcceptedSth, CanonicalKtVerifier, KtPinnedLog, KtTimeSource};
+pub use canonical::{AcceptedSth, CanonicalKtVerifier, KtPinnedLog, KtTimeSource, ACCEPTED_SOURCE};
+pub use client::{
+    decode_client_state, decode_state, encode_client_state, encode_state, FileSthStore,
+    KtClientState, KtLogEndpoint, MemorySthStore, PeerSth, SplitViewEvidence, SthStore,
+    MAX_PEER_STHS_PER_LOG,
+};
+#[cfg(feature = "stdcrypto")]
+pub use log::KtLog;
+pub use monitor::{
+    KtEntrySource, KtMonitorAlert, KtMonitorReport, KtMonitorState, KtOwnIdentity,
+    MONITOR_ENTRIES_BATCH,
+};
+pub use quorum::{KtLogEvidence, KtQuorumPolicy, KtQuorumReport};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KtVerification {
@@ -31,6 +48,17 @@ impl KtError {
         Self::VerifyFailed { detail }
     }
 
+    /// For `KtLogEndpoint` implementations outside this crate: the log could not be
+    /// reached or returned something other than the requested blob.
+    pub fn log_fetch_failed() -> Self {
+        Self::kt_fail("log_fetch_failed")
+    }
+
+    /// For log clients outside this crate: the bundle to submit did not encode.
+    pub fn bundle_encode_failed() -> Self {
+        Self::kt_fail("bundle_encode_failed")
+    }
+
     pub fn detail(&self) -> &'static str {
         match self {
             Self::BundleSigFail { detail } | Self::VerifyFailed { detail } => detail,
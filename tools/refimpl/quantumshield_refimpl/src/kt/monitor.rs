@@ -0,0 +1,411 @@
+//! Self-monitoring: watch a pinned KT log for bundles published under our own identity.
+//!
+//! The monitor walks every entry appended since the last run and folds it into a compact
+//! Merkle frontier (the roots of the perfect subtrees covering the tree, left to right). A
+//! refreshed, signed STH is only accepted as "checked" when the frontier reproduces its
+//! root, so a log cannot hide an entry from the monitor without also forking its tree.
+//!
+//! State file format (`version 0x01`):
+//!
+//! `version || log_id 32 || tree_size u64 || frontier_node 32 * popcount(tree_size)`
+
+use super::canonical::{blob_error, node_hash, sha256, sha256_prefixed};
+use super::client::KtLogEndpoint;
+use super::{CanonicalKtVerifier, KtError};
+use crate::codec::Reader;
+use crate::crypto::traits::SigEd25519;
+use crate::qsp::PrekeyBundle;
+
+const MONITOR_STATE_VERSION: u8 = 0x01;
+
+/// Largest entry range requested from a log in one call.
+pub const MONITOR_ENTRIES_BATCH: u64 = 256;
+
+/// A log that also serves its raw entries (`BundleLeafData`, in leaf order).
+pub trait KtEntrySource: KtLogEndpoint {
+    /// Entries `start..end`. Shorter or longer answers are treated as log misbehaviour.
+    fn get_entries(&self, start: u64, end: u64) -> Result<Vec<Vec<u8>>, KtError>;
+}
+
+/// The identity whose bundles are being watched for.
+pub trait KtOwnIdentity {
+    /// `user_id` the identity publishes under.
+    fn user_id(&self) -> &[u8];
+    /// Whether `bundle` (decoded from leaf data, so without signatures) was minted by us.
+    fn minted(&self, bundle: &PrekeyBundle) -> bool;
+}
+
+/// How far a log has been checked, and the frontier needed to continue from there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KtMonitorState {
+    log_id: [u8; 32],
+    tree_size: u64,
+    frontier: Vec<[u8; 32]>,
+}
+
+/// A bundle under our `user_id` that we did not mint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KtMonitorAlert {
+    pub leaf_index: u64,
+    pub leaf_hash: [u8; 32],
+    pub device_id: u32,
+    pub ik_sig_ec_pub: [u8; 32],
+    pub ik_sig_pq_pub: Vec<u8>,
+}
+
+/// Outcome of one monitoring pass over `from_tree_size..to_tree_size`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KtMonitorReport {
+    pub from_tree_size: u64,
+    pub to_tree_size: u64,
+    /// Leaf indexes of our own bundles.
+    pub own: Vec<u64>,
+    pub alerts: Vec<KtMonitorAlert>,
+    /// Leaf indexes whose data does not parse as `BundleLeafData`.
+    pub undecodable: Vec<u64>,
+}
+
+impl KtMonitorState {
+    /// Nothing checked yet; the first pass walks the log from leaf 0.
+    pub fn new(log_id: [u8; 32]) -> Self {
+        Self {
+            log_id,
+            tree_size: 0,
+            frontier: Vec::new(),
+        }
+    }
+
+    pub fn log_id(&self) -> [u8; 32] {
+        self.log_id
+    }
+
+    pub fn tree_size(&self) -> u64 {
+        self.tree_size
+    }
+
+    /// Root of the checked prefix of the log.
+    pub fn root_hash(&self) -> [u8; 32] {
+        let mut nodes = self.frontier.iter().rev();
+        let Some(last) = nodes.next() else {
+            return sha256(&[]);
+        };
+        nodes.fold(*last, |acc, left| node_hash(left, &acc))
+    }
+
+    fn push_leaf(&mut self, leaf_hash: [u8; 32]) {
+        let mut node = leaf_hash;
+        let mut size = self.tree_size;
+        while size & 1 == 1 {
+            // A set low bit means the last frontier node is a complete sibling subtree.
+            if let Some(left) = self.frontier.pop() {
+                node = node_hash(&left, &node);
+            }
+            size >>= 1;
+        }
+        self.frontier.push(node);
+        self.tree_size += 1;
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(41 + 32 * self.frontier.len());
+        out.push(MONITOR_STATE_VERSION);
+        out.extend_from_slice(&self.log_id);
+        out.extend_from_slice(&self.tree_size.to_be_bytes());
+        for node in &self.frontier {
+            out.extend_from_slice(node);
+        }
+        out
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, KtError> {
+        let fail = blob_error("monitor_state_truncated", "monitor_state_trailing_bytes");
+        let mut r = Reader::new(buf);
+        if r.read_u8().map_err(&fail)? != MONITOR_STATE_VERSION {
+            return Err(KtError::kt_fail("monitor_state_version"));
+        }
+        let log_id = r.read_exact::<32>().map_err(&fail)?;
+        let tree_size = r.read_u64().map_err(&fail)?;
+        let frontier = (0..tree_size.count_ones())
+            .map(|_| r.read_exact::<32>().map_err(&fail))
+            .collect::<Result<_, _>>()?;
+        r.finish().map_err(&fail)?;
+        Ok(Self {
+            log_id,
+            tree_size,
+            frontier,
+        })
+    }
+
+    /// Refresh the log's STH through `verifier` (pin, freshness, consistency, split-view
+    /// checks), then walk every entry since the last pass and classify those under
+    /// `identity.user_id()`.
+    ///
+    /// Returns the advanced state and the report. `self` is left untouched so the caller
+    /// can persist the new state only once it has acted on the alerts; on any error the
+    /// pass must be retried from the old state.
+    pub fn advance(
+        &self,
+        verifier: &CanonicalKtVerifier,
+        source: &dyn KtEntrySource,
+        ed25519: &dyn SigEd25519,
+        identity: &dyn KtOwnIdentity,
+    ) -> Result<(Self, KtMonitorReport), KtError> {
+        let sth = verifier.refresh_from_log(&self.log_id, source, ed25519)?;
+        if sth.tree_size < self.tree_size {
+            return Err(KtError::kt_fail("monitor_tree_size_regressed"));
+        }
+
+        let mut next = self.clone();
+        let mut report = KtMonitorReport {
+            from_tree_size: self.tree_size,
+            to_tree_size: sth.tree_size,
+            own: Vec::new(),
+            alerts: Vec::new(),
+            undecodable: Vec::new(),
+        };
+        while next.tree_size < sth.tree_size {
+            let start = next.tree_size;
+            let end = sth.tree_size.min(start + MONITOR_ENTRIES_BATCH);
+            let entries = source.get_entries(start, end)?;
+            if entries.len() as u64 != end - start {
+                return Err(KtError::kt_fail("monitor_entries_count_mismatch"));
+            }
+            for (leaf_index, leaf_data) in (start..end).zip(entries) {
+                let leaf_hash = sha256_prefixed(0x00, &leaf_data);
+                next.push_leaf(leaf_hash);
+                let Ok(bundle) = PrekeyBundle::decode_bundle_leaf_data(&leaf_data) else {
+                    report.undecodable.push(leaf_index);
+                    continue;
+                };
+                if bundle.user_id != identity.user_id() {
+                    continue;
+                }
+                if identity.minted(&bundle) {
+                    report.own.push(leaf_index);
+                } else {
+                    report.alerts.push(KtMonitorAlert {
+                        leaf_index,
+                        leaf_hash,
+                        device_id: bundle.device_id,
+                        ik_sig_ec_pub: bundle.ik_sig_ec_pub,
+                        ik_sig_pq_pub: bundle.ik_sig_pq_pub,
+                    });
+                }
+            }
+        }
+
+        if next.root_hash() != sth.root_hash {
+            return Err(KtError::kt_fail("monitor_root_mismatch"));
+        }
+        Ok((next, report))
+    }
+}
+
+#[cfg(all(test, feature = "stdcrypto"))]
+mod tests {
+    use super::*;
+    use crate::crypto::stdcrypto::StdEd25519;
+    use crate::kt::{KtLog, KtTimeSource};
+    use crate::qsp::{SZ_MLDSA65_PUB, SZ_MLKEM768_PUB, SZ_X25519_PUB};
+
+    const LOG_ID: [u8; 32] = [0xAB; 32];
+    const NOW_MS: u64 = 1_000_000;
+
+    struct Alice {
+        ik_sig_pq_pub: Vec<u8>,
+    }
+
+    impl KtOwnIdentity for Alice {
+        fn user_id(&self) -> &[u8] {
+            b"alice"
+        }
+
+        fn minted(&self, bundle: &PrekeyBundle) -> bool {
+            bundle.ik_sig_pq_pub == self.ik_sig_pq_pub
+        }
+    }
+
+    fn bundle(user_id: &[u8], device_id: u32, ik_sig_pq: u8) -> PrekeyBundle {
+        PrekeyBundle {
+            user_id: user_id.to_vec(),
+            device_id,
+            valid_from: 1,
+            valid_to: 9,
+            ik_sig_ec_pub: [ik_sig_pq; 32],
+            ik_sig_pq_pub: vec![ik_sig_pq; SZ_MLDSA65_PUB],
+            spk_dh_pub: [0x11; SZ_X25519_PUB],
+            spk_pq_pub: vec![0x22; SZ_MLKEM768_PUB],
+            pq_rcv_id: 17,
+            pq_rcv_pub: vec![0x33; SZ_MLKEM768_PUB],
+            opk_dh: Some((5, [0x44; SZ_X25519_PUB])),
+            opk_pq: None,
+            sig_ec: Vec::new(),
+            sig_pq: Vec::new(),
+            kt_log_id: LOG_ID,
+            kt_sth: Vec::new(),
+            kt_inclusion_proof: Vec::new(),
+            kt_consistency_proof: Vec::new(),
+        }
+    }
+
+    fn setup() -> (KtLog, CanonicalKtVerifier) {
+        let log = KtLog::new(LOG_ID, [0x17; 32], KtTimeSource::Fixed(NOW_MS));
+        let verifier =
+            CanonicalKtVerifier::new([log.pinned(300)], KtTimeSource::Fixed(NOW_MS), false);
+        (log, verifier)
+    }
+
+    fn alice() -> Alice {
+        Alice {
+            ik_sig_pq_pub: vec![0xA1; SZ_MLDSA65_PUB],
+        }
+    }
+
+    /// Serves a fixed, possibly tampered or truncated, entry list in front of an honest log.
+    struct Tampered<'a> {
+        log: &'a KtLog,
+        entries: Vec<Vec<u8>>,
+    }
+
+    impl KtLogEndpoint for Tampered<'_> {
+        fn get_sth(&self) -> Result<Vec<u8>, KtError> {
+            self.log.get_sth()
+        }
+
+        fn get_consistency_proof(&self, from: u64, to: u64) -> Result<Vec<u8>, KtError> {
+            self.log.get_consistency_proof(from, to)
+        }
+    }
+
+    impl KtEntrySource for Tampered<'_> {
+        fn get_entries(&self, start: u64, end: u64) -> Result<Vec<Vec<u8>>, KtError> {
+            let end = (end as usize).min(self.entries.len());
+            Ok(self.entries[start as usize..end].to_vec())
+        }
+    }
+
+    #[test]
+    fn leaf_data_roundtrips_through_decoder() {
+        let b = bundle(b"alice", 3, 0xA1);
+        let decoded = PrekeyBundle::decode_bundle_leaf_data(&b.bundle_leaf_data()).unwrap();
+        assert_eq!(decoded.bundle_leaf_data(), b.bundle_leaf_data());
+        let mut trailing = b.bundle_leaf_data();
+        trailing.push(0);
+        assert!(PrekeyBundle::decode_bundle_leaf_data(&trailing).is_err());
+    }
+
+    #[test]
+    fn frontier_root_matches_log_root_at_every_size() {
+        let (mut log, _) = setup();
+        let mut state = KtMonitorState::new(LOG_ID);
+        assert_eq!(state.root_hash(), sha256(&[]));
+        for i in 0..40u32 {
+            let leaf = i.to_be_bytes();
+            log.append_leaf_data(&leaf);
+            state.push_leaf(sha256_prefixed(0x00, &leaf));
+            assert_eq!(state.frontier.len(), state.tree_size.count_ones() as usize);
+            assert_eq!(state.root_hash(), log.root_hash(state.tree_size).unwrap());
+            assert_eq!(KtMonitorState::decode(&state.encode()).unwrap(), state);
+        }
+    }
+
+    #[test]
+    fn walks_new_entries_and_alerts_on_foreign_bundle() {
+        let (mut log, verifier) = setup();
+        let ed25519 = StdEd25519;
+        let alice = alice();
+        log.append_bundle(&bundle(b"alice", 1, 0xA1)).unwrap();
+        log.append_bundle(&bundle(b"bob", 1, 0xB0)).unwrap();
+        log.append_leaf_data(b"not a bundle");
+
+        let (state, report) = KtMonitorState::new(LOG_ID)
+            .advance(&verifier, &log, &ed25519, &alice)
+            .unwrap();
+        assert_eq!((report.from_tree_size, report.to_tree_size), (0, 3));
+        assert_eq!(report.own, vec![0]);
+        assert!(report.alerts.is_empty());
+        assert_eq!(report.undecodable, vec![2]);
+
+        // Someone publishes a bundle for alice under a key her vault never held.
+        log.append_bundle(&bundle(b"alice", 2, 0xA1)).unwrap();
+        let forged = bundle(b"alice", 1, 0xEE);
+        log.append_bundle(&forged).unwrap();
+        let (state, report) = state.advance(&verifier, &log, &ed25519, &alice).unwrap();
+        assert_eq!((report.from_tree_size, report.to_tree_size), (3, 5));
+        assert_eq!(report.own, vec![3]);
+        assert_eq!(report.alerts.len(), 1);
+        assert_eq!(report.alerts[0].leaf_index, 4);
+        assert_eq!(
+            report.alerts[0].leaf_hash,
+            sha256_prefixed(0x00, &forged.bundle_leaf_data())
+        );
+        assert_eq!(report.alerts[0].ik_sig_pq_pub, forged.ik_sig_pq_pub);
+
+        // Nothing new: nothing reported again.
+        let (_, report) = state.advance(&verifier, &log, &ed25519, &alice).unwrap();
+        assert_eq!((report.from_tree_size, report.to_tree_size), (5, 5));
+        assert!(report.own.is_empty() && report.alerts.is_empty());
+    }
+
+    #[test]
+    fn log_hiding_or_dropping_entries_is_detected() {
+        let (mut log, verifier) = setup();
+        let ed25519 = StdEd25519;
+        log.append_bundle(&bundle(b"alice", 1, 0xA1)).unwrap();
+        log.append_bundle(&bundle(b"alice", 1, 0xEE)).unwrap();
+        let honest: Vec<Vec<u8>> = (0..2).map(|i| log.entry(i).unwrap().to_vec()).collect();
+
+        // Swapping the foreign bundle for an innocent one breaks the signed root.
+        let mut hidden = honest.clone();
+        hidden[1] = bundle(b"bob", 1, 0xB0).bundle_leaf_data();
+        let err = KtMonitorState::new(LOG_ID)
+            .advance(
+                &verifier,
+                &Tampered {
+                    log: &log,
+                    entries: hidden,
+                },
+                &ed25519,
+                &alice(),
+            )
+            .unwrap_err();
+        assert_eq!(err.detail(), "monitor_root_mismatch");
+
+        let short = Tampered {
+            log: &log,
+            entries: honest[..1].to_vec(),
+        };
+        let err = KtMonitorState::new(LOG_ID)
+            .advance(&verifier, &short, &ed25519, &alice())
+            .unwrap_err();
+        assert_eq!(err.detail(), "monitor_entries_count_mismatch");
+    }
+
+    #[test]
+    fn state_decoding_rejects_malformed_input() {
+        let mut state = KtMonitorState::new(LOG_ID);
+        for i in 0..3u8 {
+            state.push_leaf([i; 32]);
+        }
+        let buf = state.encode();
+        assert_eq!(buf.len(), 41 + 2 * 32);
+        assert_eq!(
+            KtMonitorState::decode(&buf[..buf.len() - 1])
+                .unwrap_err()
+                .detail(),
+            "monitor_state_truncated"
+        );
+        let mut trailing = buf.clone();
+        trailing.push(0);
+        assert_eq!(
+            KtMonitorState::decode(&trailing).unwrap_err().detail(),
+            "monitor_state_trailing_bytes"
+        );
+        let mut version = buf;
+        version[0] = 0x02;
+        assert_eq!(
+            KtMonitorState::decode(&version).unwrap_err().detail(),
+            "monitor_state_version"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! Multi-log KT policy: a bundle is accepted only when enough pinned logs vouch for it.
+//!
+//! A bundle's own `kt_*` fields carry evidence for its primary log (`kt_log_id`).
+//! Evidence from further logs is over the same `BundleLeafData` and travels beside the
+//! bundle as `KtLogEvidence`; it needs no bundle signature because each log's STH
+//! signature authenticates it. Every log is judged under its own `KtPinnedLog` (key and
+//! freshness window) and its own accepted-STH state.
+
+use crate::qsp::PrekeyBundle;
+
+use super::KtVerification;
+
+/// One log's proof set for a bundle; the same blobs as the bundle's `kt_*` fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KtLogEvidence {
+    pub log_id: [u8; 32],
+    pub sth: Vec<u8>,
+    pub inclusion_proof: Vec<u8>,
+    pub consistency_proof: Vec<u8>,
+}
+
+impl KtLogEvidence {
+    /// The primary-log evidence embedded in `bundle`.
+    pub fn from_bundle(bundle: &PrekeyBundle) -> Self {
+        Self {
+            log_id: bundle.kt_log_id,
+            sth: bundle.kt_sth.clone(),
+            inclusion_proof: bundle.kt_inclusion_proof.clone(),
+            consistency_proof: bundle.kt_consistency_proof.clone(),
+        }
+    }
+}
+
+/// "`threshold` of the pinned logs must include the bundle."
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KtQuorumPolicy {
+    pub threshold: usize,
+}
+
+/// Which logs vouched for a bundle, and why the others did not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KtQuorumReport {
+    pub status: KtVerification,
+    pub threshold: usize,
+    /// Logs whose evidence fully verified, sorted by log id.
+    pub vouched: Vec<[u8; 32]>,
+    /// Evidence that was offered but failed, with the reject detail, sorted by log id.
+    pub rejected: Vec<([u8; 32], &'static str)>,
+    /// Pinned logs for which no evidence was offered, sorted by log id.
+    pub missing: Vec<[u8; 32]>,
+}
+
+impl KtQuorumReport {
+    pub fn met(&self) -> bool {
+        self.status == KtVerification::DisabledNonProduction || self.vouched.len() >= self.threshold
+    }
+}
@@ -12,8 +12,9 @@ pub mod kt;
 pub mod qse;
 pub mod qsp;
 pub mod refimpl_error;
+pub mod snapshot;
 pub mod suite2;
 
-pub use qse::{Envelope, EnvelopeProfile};
+pub use qse::{Envelope, EnvelopeProfile, EnvelopeRef};
 pub use qsp::{HandshakeInit, HandshakeResp, ProtocolMessage, SessionRole, SessionState};
 pub use refimpl_error::RefimplError;
@@ -1,7 +1,98 @@
 use crate::codec::{CodecError, Reader, Writer};
+use crate::crypto::traits::{Aead, Kmac};
+
+use super::PaddingPolicy;
 
 pub const QSE_ENV_VERSION_V1: u16 = 0x0100;
+/// QSE v2: payload and padding lengths travel sealed under a length key shared by the two
+/// endpoints, so bucket padding works for any payload, not only Suite-2 wire.
+///
+/// `env_version || flags || varbytes<u16> route_token || u32 timestamp_bucket ||
+///  len_nonce[12] || len_ct[24] || payload || padding`, where
+/// `len_ct = AEAD(len_key, len_nonce, ad = "QSE/v2/LEN" || env_version || flags ||
+///  varbytes<u16> route_token || list<u16> sender_versions || list<u16> receiver_versions,
+///  u32 payload_len || u32 pad_len)` and `list<u16>` is `u16 count || count * u16`.
+///
+/// The AD authenticates the route token to the recipient and binds the version lists both
+/// endpoints advertised (see `EnvNegotiation`); `pad_len` commits to the exact padding, so
+/// the decoder rejects any envelope whose tail is not exactly `payload_len + pad_len` bytes.
+/// `timestamp_bucket` is left out of the AD because the service edge sets it.
+pub const QSE_ENV_VERSION_V2: u16 = 0x0200;
+/// Versions this implementation speaks, most preferred first.
+pub const QSE_ENV_SUPPORTED_VERSIONS: [u16; 2] = [QSE_ENV_VERSION_V2, QSE_ENV_VERSION_V1];
+/// KMAC label for `derive_len_key`.
+pub const QSE_V2_LEN_KEY_LABEL: &str = "QSE2/LENKEY";
 const FLAG_BUCKET_PADDED: u16 = 0x0001;
+const V2_LEN_AD_LABEL: &[u8] = b"QSE/v2/LEN";
+const V2_LEN_NONCE_LEN: usize = 12;
+const V2_LEN_CT_LEN: usize = 8 + 16;
+
+/// Pick the envelope version to send: our most preferred one that the peer also speaks.
+pub fn negotiate_env_version(peer_supported: &[u16]) -> Option<u16> {
+    EnvNegotiation::new(&QSE_ENV_SUPPORTED_VERSIONS, peer_supported).send_version()
+}
+
+/// One endpoint's view of an envelope-version negotiation.
+///
+/// `local` is what this endpoint advertised, most preferred first; `peer` is what it
+/// received as the peer's advertisement. The sender sends the first of its own versions the
+/// peer speaks, and the receiver recomputes that choice from the other side and refuses any
+/// envelope that does not carry it, so a stripped advertisement cannot push two v2
+/// endpoints down to v1 unnoticed. A v2 length block also binds both lists, so a rewritten
+/// advertisement fails authentication even when both endpoints still land on v2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvNegotiation<'a> {
+    pub local: &'a [u16],
+    pub peer: &'a [u16],
+}
+
+impl<'a> EnvNegotiation<'a> {
+    pub fn new(local: &'a [u16], peer: &'a [u16]) -> Self {
+        Self { local, peer }
+    }
+
+    /// The version to send: our most preferred implemented version the peer speaks.
+    pub fn send_version(&self) -> Option<u16> {
+        first_common(self.local, self.peer)
+    }
+
+    /// The version a conforming peer sends us: its most preferred one that we speak.
+    pub fn recv_version(&self) -> Option<u16> {
+        first_common(self.peer, self.local)
+    }
+}
+
+fn first_common(preferred: &[u16], other: &[u16]) -> Option<u16> {
+    preferred
+        .iter()
+        .copied()
+        .find(|v| QSE_ENV_SUPPORTED_VERSIONS.contains(v) && other.contains(v))
+}
+
+/// `env_version` of an encoded envelope, without parsing the rest.
+pub fn peek_env_version(buf: &[u8]) -> Result<u16, CodecError> {
+    Reader::new(buf).read_u16()
+}
+
+/// v2 length key from a secret both endpoints hold (e.g. the session root).
+pub fn derive_len_key(kmac: &dyn Kmac, secret: &[u8]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&kmac.kmac256(secret, QSE_V2_LEN_KEY_LABEL, b"", 32));
+    key
+}
+
+/// The v2 length-block AD. `sender` and `receiver` are the advertised version lists, which
+/// come from the wire on one side, so an over-long list is an error rather than truncated.
+fn v2_len_ad(header: &[u8], sender: &[u16], receiver: &[u16]) -> Result<Vec<u8>, CodecError> {
+    let mut w = Writer::new();
+    w.write_bytes(V2_LEN_AD_LABEL);
+    w.write_bytes(header);
+    for list in [sender, receiver] {
+        w.write_u16(u16::try_from(list.len()).map_err(|_| CodecError::LengthOutOfRange)?);
+        list.iter().for_each(|v| w.write_u16(*v));
+    }
+    Ok(w.into_vec())
+}
 
 #[derive(Debug, Clone, Copy)]
 pub enum EnvelopeProfile {
@@ -18,6 +109,11 @@ impl EnvelopeProfile {
             EnvelopeProfile::Private => 4096,
         }
     }
+
+    /// The profile as a `PaddingPolicy`: pad up to `min_size_bytes`.
+    pub fn policy(&self) -> PaddingPolicy {
+        PaddingPolicy::Minimum(self.min_size_bytes())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -30,94 +126,292 @@ pub struct Envelope {
     pub padding: Vec<u8>,      // random bytes; contents ignored
 }
 
+/// Zero-copy view of an `Envelope`: every byte field borrows from the decoded buffer.
+/// Accepts and rejects exactly what `Envelope::decode` does (which is built on it).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvelopeRef<'a> {
+    pub env_version: u16,
+    pub flags: u16,
+    pub route_token: &'a [u8],
+    pub timestamp_bucket: u32,
+    pub payload: &'a [u8],
+    pub padding: &'a [u8],
+}
+
+impl<'a> EnvelopeRef<'a> {
+    pub fn decode(buf: &'a [u8]) -> Result<Self, CodecError> {
+        let mut r = Reader::new(buf);
+        let env_version = r.read_u16()?;
+        let flags = r.read_u16()?;
+        if env_version != QSE_ENV_VERSION_V1 {
+            return Err(CodecError::Invalid("env_version"));
+        }
+        // QSE v1.x: unknown flags must be rejected
+        if flags & !FLAG_BUCKET_PADDED != 0 {
+            return Err(CodecError::Invalid("flags"));
+        }
+        let route_token = r.read_varbytes_u16_ref()?;
+        let timestamp_bucket = r.read_u32()?;
+        let pad_len = r.read_u16()? as usize;
+        let payload_len = r.read_u32()? as usize;
+        let bucket_padded = (flags & FLAG_BUCKET_PADDED) != 0;
+        let (payload, padding) = if bucket_padded {
+            // Bucket mode keeps length fields constant to avoid leaking exact sizes.
+            if pad_len != 0 || payload_len != 0 {
+                return Err(CodecError::Invalid("bucket_len_fields"));
+            }
+            let remaining = r.read_rest();
+            let payload_len = suite2_wire_prefix_len(remaining)?;
+            remaining.split_at(payload_len)
+        } else {
+            if r.remaining() < payload_len + pad_len {
+                return Err(CodecError::LengthOutOfRange);
+            }
+            let payload = r.read_slice(payload_len)?;
+            let padding = r.read_slice(pad_len)?;
+            r.finish()?;
+            (payload, padding)
+        };
+        Ok(Self {
+            env_version,
+            flags,
+            route_token,
+            timestamp_bucket,
+            payload,
+            padding,
+        })
+    }
+
+    /// Decode a v2 envelope, opening its length block with `len_key`. `nego` is the
+    /// receiver's view; the peer is the sender.
+    pub fn decode_v2(
+        buf: &'a [u8],
+        aead: &dyn Aead,
+        len_key: &[u8; 32],
+        nego: &EnvNegotiation<'_>,
+    ) -> Result<Self, CodecError> {
+        let mut r = Reader::new(buf);
+        let env_version = r.read_u16()?;
+        let flags = r.read_u16()?;
+        if env_version != QSE_ENV_VERSION_V2 {
+            return Err(CodecError::Invalid("env_version"));
+        }
+        if flags & !FLAG_BUCKET_PADDED != 0 {
+            return Err(CodecError::Invalid("flags"));
+        }
+        let route_token = r.read_varbytes_u16_ref()?;
+        let ad = v2_len_ad(&buf[..r.position()], nego.peer, nego.local)?;
+        let timestamp_bucket = r.read_u32()?;
+        let len_nonce = r.read_exact::<V2_LEN_NONCE_LEN>()?;
+        let len_ct = r.read_slice(V2_LEN_CT_LEN)?;
+        let lens = aead
+            .open(len_key, &len_nonce, &ad, len_ct)
+            .map_err(|_| CodecError::Invalid("env_len_auth"))?;
+        let mut lr = Reader::new(&lens);
+        let payload_len = lr.read_u32()? as usize;
+        let pad_len = lr.read_u32()? as usize;
+        lr.finish()?;
+        if payload_len.checked_add(pad_len) != Some(r.remaining()) {
+            return Err(CodecError::Invalid("env_len_commitment"));
+        }
+        let payload = r.read_slice(payload_len)?;
+        let padding = r.read_rest();
+        Ok(Self {
+            env_version,
+            flags,
+            route_token,
+            timestamp_bucket,
+            payload,
+            padding,
+        })
+    }
+
+    /// Receive path for either version. The envelope must carry the version `nego` says a
+    /// conforming sender picks (`env_version_not_negotiated` otherwise, which is how a
+    /// downgrade shows up); a v2 envelope also needs `v2 = Some((aead, len_key))` and is
+    /// never parsed as v1.
+    pub fn decode_negotiated(
+        buf: &'a [u8],
+        nego: &EnvNegotiation<'_>,
+        v2: Option<(&dyn Aead, &[u8; 32])>,
+    ) -> Result<Self, CodecError> {
+        let version = peek_env_version(buf)?;
+        if nego.recv_version() != Some(version) {
+            return Err(CodecError::Invalid("env_version_not_negotiated"));
+        }
+        match (version, v2) {
+            (QSE_ENV_VERSION_V2, Some((aead, len_key))) => {
+                Self::decode_v2(buf, aead, len_key, nego)
+            }
+            (QSE_ENV_VERSION_V2, None) => Err(CodecError::Invalid("env_v2_requires_key")),
+            _ => Self::decode(buf),
+        }
+    }
+
+    pub fn to_envelope(&self) -> Envelope {
+        Envelope {
+            env_version: self.env_version,
+            flags: self.flags,
+            route_token: self.route_token.to_vec(),
+            timestamp_bucket: self.timestamp_bucket,
+            payload: self.payload.to_vec(),
+            padding: self.padding.to_vec(),
+        }
+    }
+}
+
 impl Envelope {
     fn is_bucket_padded(&self) -> bool {
         (self.flags & FLAG_BUCKET_PADDED) != 0
     }
 
-    pub fn encode(&self) -> Vec<u8> {
+    /// v1 encoding. A v2 envelope is refused (`env_version`): its lengths need `encode_v2`.
+    /// So is a field too long for its length prefix.
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        if self.env_version == QSE_ENV_VERSION_V2 {
+            return Err(CodecError::Invalid("env_version"));
+        }
+        let route_token_len =
+            u16::try_from(self.route_token.len()).map_err(|_| CodecError::LengthOutOfRange)?;
         let mut w = Writer::new();
         w.write_u16(self.env_version);
         w.write_u16(self.flags);
-        w.write_varbytes_u16(&self.route_token);
+        w.write_u16(route_token_len);
+        w.write_bytes(&self.route_token);
         w.write_u32(self.timestamp_bucket);
         if self.is_bucket_padded() {
             // Bucket mode must not expose precise payload/padding lengths.
             w.write_u16(0);
             w.write_u32(0);
         } else {
-            w.write_u16(self.padding.len() as u16);
-            w.write_u32(self.payload.len() as u32);
+            w.write_u16(
+                u16::try_from(self.padding.len()).map_err(|_| CodecError::LengthOutOfRange)?,
+            );
+            w.write_u32(
+                u32::try_from(self.payload.len()).map_err(|_| CodecError::LengthOutOfRange)?,
+            );
         }
         w.write_bytes(&self.payload);
         w.write_bytes(&self.padding);
-        w.into_vec()
+        Ok(w.into_vec())
     }
 
     pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
-        let mut r = Reader::new(buf);
-        let env_version = r.read_u16()?;
-        let flags = r.read_u16()?;
-        if env_version != QSE_ENV_VERSION_V1 {
+        EnvelopeRef::decode(buf).map(|e| e.to_envelope())
+    }
+
+    /// v2 encoding; `len_nonce` must be fresh for every envelope sealed under `len_key`.
+    /// `nego` is the sender's view, and must settle on v2.
+    pub fn encode_v2(
+        &self,
+        aead: &dyn Aead,
+        len_key: &[u8; 32],
+        len_nonce: &[u8; 12],
+        nego: &EnvNegotiation<'_>,
+    ) -> Result<Vec<u8>, CodecError> {
+        if self.env_version != QSE_ENV_VERSION_V2 {
             return Err(CodecError::Invalid("env_version"));
         }
-        // QSE v1.x: unknown flags must be rejected
-        if flags & !FLAG_BUCKET_PADDED != 0 {
+        if nego.send_version() != Some(QSE_ENV_VERSION_V2) {
+            return Err(CodecError::Invalid("env_version_not_negotiated"));
+        }
+        if self.flags & !FLAG_BUCKET_PADDED != 0 {
             return Err(CodecError::Invalid("flags"));
         }
-        let route_token = r.read_varbytes_u16()?;
-        let timestamp_bucket = r.read_u32()?;
-        let pad_len = r.read_u16()? as usize;
-        let payload_len = r.read_u32()? as usize;
-        let bucket_padded = (flags & FLAG_BUCKET_PADDED) != 0;
-        if bucket_padded {
-            // Bucket mode keeps length fields constant to avoid leaking exact sizes.
-            if pad_len != 0 || payload_len != 0 {
-                return Err(CodecError::Invalid("bucket_len_fields"));
-            }
-            let remaining = r.read_bytes(r.remaining())?;
-            let payload_len = suite2_wire_prefix_len(&remaining)?;
-            let (payload, padding) = remaining.split_at(payload_len);
-            Ok(Self {
-                env_version,
-                flags,
-                route_token,
-                timestamp_bucket,
-                payload: payload.to_vec(),
-                padding: padding.to_vec(),
-            })
+        let payload_len =
+            u32::try_from(self.payload.len()).map_err(|_| CodecError::LengthOutOfRange)?;
+        let pad_len =
+            u32::try_from(self.padding.len()).map_err(|_| CodecError::LengthOutOfRange)?;
+        let route_token_len =
+            u16::try_from(self.route_token.len()).map_err(|_| CodecError::LengthOutOfRange)?;
+
+        let mut w = Writer::new();
+        w.write_u16(self.env_version);
+        w.write_u16(self.flags);
+        w.write_u16(route_token_len);
+        w.write_bytes(&self.route_token);
+        let mut out = w.into_vec();
+        let ad = v2_len_ad(&out, nego.local, nego.peer)?;
+
+        let mut lens = Writer::new();
+        lens.write_u32(payload_len);
+        lens.write_u32(pad_len);
+        let len_ct = aead.seal(len_key, len_nonce, &ad, &lens.into_vec());
+        if len_ct.len() != V2_LEN_CT_LEN {
+            return Err(CodecError::Invalid("env_len_seal"));
+        }
+
+        out.extend_from_slice(&self.timestamp_bucket.to_be_bytes());
+        out.extend_from_slice(len_nonce);
+        out.extend_from_slice(&len_ct);
+        out.extend_from_slice(&self.payload);
+        out.extend_from_slice(&self.padding);
+        Ok(out)
+    }
+
+    pub fn decode_v2(
+        buf: &[u8],
+        aead: &dyn Aead,
+        len_key: &[u8; 32],
+        nego: &EnvNegotiation<'_>,
+    ) -> Result<Self, CodecError> {
+        EnvelopeRef::decode_v2(buf, aead, len_key, nego).map(|e| e.to_envelope())
+    }
+
+    /// See `EnvelopeRef::decode_negotiated`.
+    pub fn decode_negotiated(
+        buf: &[u8],
+        nego: &EnvNegotiation<'_>,
+        v2: Option<(&dyn Aead, &[u8; 32])>,
+    ) -> Result<Self, CodecError> {
+        EnvelopeRef::decode_negotiated(buf, nego, v2).map(|e| e.to_envelope())
+    }
+
+    /// Encoded size for either version.
+    fn encoded_len(&self) -> usize {
+        let lengths = if self.env_version == QSE_ENV_VERSION_V2 {
+            V2_LEN_NONCE_LEN + V2_LEN_CT_LEN
         } else {
-            if r.remaining() < payload_len + pad_len {
-                return Err(CodecError::LengthOutOfRange);
-            }
-            let payload = r.read_bytes(payload_len)?;
-            let padding = r.read_bytes(pad_len)?;
-            r.finish()?;
-            Ok(Self {
-                env_version,
-                flags,
-                route_token,
-                timestamp_bucket,
-                payload,
-                padding,
-            })
+            2 + 4
+        };
+        2 + 2 + 2 + self.route_token.len() + 4 + lengths + self.payload.len() + self.padding.len()
+    }
+
+    /// Borrowed view of this envelope's fields.
+    pub fn as_envelope_ref(&self) -> EnvelopeRef<'_> {
+        EnvelopeRef {
+            env_version: self.env_version,
+            flags: self.flags,
+            route_token: &self.route_token,
+            timestamp_bucket: self.timestamp_bucket,
+            payload: &self.payload,
+            padding: &self.padding,
         }
     }
 
     /// Apply padding to meet the profile's minimum envelope size (QSE §6).
     /// `rng_bytes` should be random bytes; its length must be at least the required padding length.
     pub fn pad_to_profile(
-        mut self,
+        self,
         profile: EnvelopeProfile,
         rng_bytes: &[u8],
+    ) -> Result<Self, CodecError> {
+        self.pad_to_policy(&profile.policy(), rng_bytes)
+    }
+
+    /// Pad so the encoded envelope is exactly `policy.padded_len` of its unpadded size.
+    /// Fails with `padding_policy_no_fit` when the policy has no size for it.
+    pub fn pad_to_policy(
+        mut self,
+        policy: &PaddingPolicy,
+        rng_bytes: &[u8],
     ) -> Result<Self, CodecError> {
         self.flags |= FLAG_BUCKET_PADDED;
-        let encoded_len = self.encode().len();
-        let min_len = profile.min_size_bytes();
-        if encoded_len >= min_len {
-            return Ok(self);
-        }
-        let need = min_len - encoded_len;
+        let encoded_len = self.encoded_len();
+        let target = policy
+            .padded_len(encoded_len)
+            .ok_or(CodecError::Invalid("padding_policy_no_fit"))?;
+        let need = target - encoded_len;
         if rng_bytes.len() < need {
             return Err(CodecError::Invalid("insufficient rng padding bytes"));
         }
@@ -1,4 +1,10 @@
-//! QuantumShield Envelope (QSE) v1.8.1
+//! QuantumShield Envelope (QSE) v1.8.1, plus the v2 sealed-length format (`QSE_ENV_VERSION_V2`).
 
 mod envelope;
-pub use envelope::{Envelope, EnvelopeProfile};
+mod padding;
+pub use envelope::{
+    derive_len_key, negotiate_env_version, peek_env_version, EnvNegotiation, Envelope,
+    EnvelopeProfile, EnvelopeRef, QSE_ENV_SUPPORTED_VERSIONS, QSE_ENV_VERSION_V1,
+    QSE_ENV_VERSION_V2, QSE_V2_LEN_KEY_LABEL,
+};
+pub use padding::{PaddingOverhead, PaddingPolicy};
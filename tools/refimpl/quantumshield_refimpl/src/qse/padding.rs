@@ -0,0 +1,233 @@
+use std::collections::BTreeSet;
+
+use crate::codec::CodecError;
+
+/// How a length is rounded up before it goes on the wire.
+///
+/// Every sender-side padding decision (QSE envelopes, qsc bundle buckets, qshield-cli demo
+/// buckets) goes through one of these, so the same policy can be compared by
+/// `PaddingPolicy::overhead` regardless of which layer applies it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaddingPolicy {
+    /// Pad up to a floor; longer lengths are sent as-is. The `EnvelopeProfile` sizes.
+    Minimum(usize),
+    /// Smallest listed size that fits. Sizes must be non-zero, sorted and distinct.
+    Buckets(Vec<usize>),
+    /// `min, min * factor, min * factor^2, ...` up to and including `max`.
+    Geometric {
+        min: usize,
+        factor: usize,
+        max: usize,
+    },
+    /// Padmé (Nikitin et al., PETS 2019): keep the top `floor(log2(e)) + 1` bits of a length
+    /// with exponent `e` and round the rest up. Overhead stays under 12% and a padded size
+    /// leaks `O(log log L)` bits. Unbounded.
+    Padme,
+}
+
+/// Padding cost of a policy over a set of lengths; see `PaddingPolicy::overhead`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaddingOverhead {
+    /// Lengths measured.
+    pub samples: u64,
+    /// Lengths the policy has no padded size for.
+    pub unfit: u64,
+    /// Sum of the fitting lengths.
+    pub total_len: u64,
+    /// Padding added across the fitting lengths.
+    pub total_padding: u64,
+    /// Largest padding added to one length.
+    pub max_padding: usize,
+    /// Largest `padding / len` for one length.
+    pub max_ratio: f64,
+    /// Distinct padded sizes produced: the length classes an observer can tell apart.
+    pub size_classes: usize,
+}
+
+impl PaddingOverhead {
+    /// Padding as a fraction of the fitting bytes.
+    pub fn mean_ratio(&self) -> f64 {
+        if self.total_len == 0 {
+            return 0.0;
+        }
+        self.total_padding as f64 / self.total_len as f64
+    }
+}
+
+impl PaddingPolicy {
+    /// Reject policies that cannot pad anything consistently.
+    pub fn validate(&self) -> Result<(), CodecError> {
+        match self {
+            PaddingPolicy::Minimum(_) | PaddingPolicy::Padme => Ok(()),
+            PaddingPolicy::Buckets(sizes) => {
+                if sizes.is_empty() {
+                    return Err(CodecError::Invalid("padding_buckets_empty"));
+                }
+                if sizes.contains(&0) {
+                    return Err(CodecError::Invalid("padding_bucket_zero"));
+                }
+                for pair in sizes.windows(2) {
+                    if pair[0] == pair[1] {
+                        return Err(CodecError::Invalid("padding_bucket_duplicate"));
+                    }
+                    if pair[0] > pair[1] {
+                        return Err(CodecError::Invalid("padding_buckets_unsorted"));
+                    }
+                }
+                Ok(())
+            }
+            PaddingPolicy::Geometric { min, factor, max } => {
+                if *min == 0 || *factor < 2 || min > max {
+                    return Err(CodecError::Invalid("padding_geometric_params"));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Padded size for `len`, or `None` when the policy has no size that fits (or is invalid).
+    pub fn padded_len(&self, len: usize) -> Option<usize> {
+        self.validate().ok()?;
+        match self {
+            PaddingPolicy::Minimum(min) => Some(len.max(*min)),
+            PaddingPolicy::Buckets(sizes) => sizes.iter().copied().find(|&b| b >= len),
+            PaddingPolicy::Geometric { min, factor, max } => {
+                let mut rung = *min;
+                while rung < len {
+                    rung = rung.checked_mul(*factor)?;
+                }
+                (rung <= *max).then_some(rung)
+            }
+            PaddingPolicy::Padme => padme(len),
+        }
+    }
+
+    /// `padded_len`, but only if the result is at most `max`.
+    pub fn padded_len_within(&self, len: usize, max: usize) -> Option<usize> {
+        self.padded_len(len).filter(|&p| p <= max)
+    }
+
+    /// Measure the policy over `lens` (e.g. `1..=8192` for every length up to 8 KiB).
+    pub fn overhead(&self, lens: impl IntoIterator<Item = usize>) -> PaddingOverhead {
+        let mut out = PaddingOverhead {
+            samples: 0,
+            unfit: 0,
+            total_len: 0,
+            total_padding: 0,
+            max_padding: 0,
+            max_ratio: 0.0,
+            size_classes: 0,
+        };
+        let mut classes = BTreeSet::new();
+        for len in lens {
+            out.samples += 1;
+            let Some(padded) = self.padded_len(len) else {
+                out.unfit += 1;
+                continue;
+            };
+            let pad = padded - len;
+            out.total_len += len as u64;
+            out.total_padding += pad as u64;
+            out.max_padding = out.max_padding.max(pad);
+            if len > 0 {
+                out.max_ratio = out.max_ratio.max(pad as f64 / len as f64);
+            }
+            classes.insert(padded);
+        }
+        out.size_classes = classes.len();
+        out
+    }
+}
+
+fn padme(len: usize) -> Option<usize> {
+    if len < 2 {
+        return Some(len);
+    }
+    let exp = usize::BITS - 1 - len.leading_zeros();
+    let kept = u32::BITS - exp.leading_zeros();
+    let mask = (1usize << (exp - kept)) - 1;
+    len.checked_add(mask).map(|v| v & !mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_policy_rounds_up_to_its_sizes() {
+        let min = PaddingPolicy::Minimum(1024);
+        assert_eq!(min.padded_len(10), Some(1024));
+        assert_eq!(min.padded_len(5000), Some(5000));
+
+        let buckets = PaddingPolicy::Buckets(vec![256, 768, 1024]);
+        assert_eq!(buckets.padded_len(0), Some(256));
+        assert_eq!(buckets.padded_len(257), Some(768));
+        assert_eq!(buckets.padded_len(1024), Some(1024));
+        assert_eq!(buckets.padded_len(1025), None);
+        assert_eq!(buckets.padded_len_within(700, 512), None);
+
+        let ladder = PaddingPolicy::Geometric {
+            min: 64,
+            factor: 2,
+            max: 4096,
+        };
+        assert_eq!(ladder.padded_len(1), Some(64));
+        assert_eq!(ladder.padded_len(65), Some(128));
+        assert_eq!(ladder.padded_len(4096), Some(4096));
+        assert_eq!(ladder.padded_len(4097), None);
+        assert_eq!(ladder.padded_len(usize::MAX), None);
+
+        // Reference values from the Padmé paper's algorithm.
+        for (len, want) in [(1, 1), (9, 10), (100, 104), (1000, 1024), (1025, 1088)] {
+            assert_eq!(PaddingPolicy::Padme.padded_len(len), Some(want), "{len}");
+        }
+        assert_eq!(PaddingPolicy::Padme.padded_len(usize::MAX), None);
+    }
+
+    #[test]
+    fn invalid_policies_are_rejected_and_pad_nothing() {
+        for (policy, code) in [
+            (PaddingPolicy::Buckets(vec![]), "padding_buckets_empty"),
+            (PaddingPolicy::Buckets(vec![0, 8]), "padding_bucket_zero"),
+            (
+                PaddingPolicy::Buckets(vec![8, 8]),
+                "padding_bucket_duplicate",
+            ),
+            (
+                PaddingPolicy::Buckets(vec![16, 8]),
+                "padding_buckets_unsorted",
+            ),
+            (
+                PaddingPolicy::Geometric {
+                    min: 64,
+                    factor: 1,
+                    max: 4096,
+                },
+                "padding_geometric_params",
+            ),
+        ] {
+            assert!(
+                matches!(policy.validate(), Err(CodecError::Invalid(c)) if c == code),
+                "{code}"
+            );
+            assert_eq!(policy.padded_len(1), None, "{code}");
+        }
+    }
+
+    #[test]
+    fn overhead_reports_worst_case_mean_and_classes() {
+        let stats = PaddingPolicy::Buckets(vec![4, 8]).overhead(1..=10);
+        assert_eq!(stats.samples, 10);
+        assert_eq!(stats.unfit, 2);
+        assert_eq!(stats.total_len, 36);
+        assert_eq!(stats.total_padding, 3 + 2 + 1 + 3 + 2 + 1);
+        assert_eq!(stats.max_padding, 3);
+        assert_eq!(stats.max_ratio, 3.0);
+        assert_eq!(stats.size_classes, 2);
+        assert_eq!(stats.mean_ratio(), 12.0 / 36.0);
+
+        let padme = PaddingPolicy::Padme.overhead(1..=1 << 16);
+        assert_eq!(padme.unfit, 0);
+        assert!(padme.max_ratio < 0.12, "{}", padme.max_ratio);
+    }
+}
@@ -2,6 +2,10 @@
 
 pub const QSP_PROTOCOL_VERSION: u16 = 0x0403;
 pub const QSP_SUITE_ID: u16 = 0x0001;
+/// Handshake suite for the deniable mode: the Suite-1 handshake with KEM-based implicit
+/// authentication and no transcript signatures. Only HS1/HS2 carry it; the ratchet that
+/// follows stays on `QSP_SUITE_ID`.
+pub const QSP_SUITE_ID_DENIABLE: u16 = 0x0101;
 
 // Fixed sizes (QSP §1.2)
 pub const SZ_SESSION_ID: usize = 16;
@@ -15,6 +19,13 @@ pub const SZ_MLKEM768_CT: usize = 1088;
 pub const SZ_MLDSA65_PUB: usize = 1952;
 pub const SZ_MLDSA65_SIG: usize = 3309;
 
+// Category-5 sizes (FIPS 203 / FIPS 204); no Suite-2 suite uses them yet
+pub const SZ_MLKEM1024_PUB: usize = 1568;
+pub const SZ_MLKEM1024_CT: usize = 1568;
+
+pub const SZ_MLDSA87_PUB: usize = 2592;
+pub const SZ_MLDSA87_SIG: usize = 4627;
+
 pub const SZ_NONCE: usize = 12;
 
 // Messaging flags (QSP §6.3)
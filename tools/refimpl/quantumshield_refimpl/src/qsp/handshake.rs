@@ -3,9 +3,22 @@
 //! empty, so peer authentication is MITM-able if this is wired into a real deployment. The shipped
 //! client uses `qsc`'s own QSC.HS.* handshake, not this. Kept only as the Suite-1/1B conformance
 //! reference exercised by the ci-4b / ci-4d-dur checks.
+//!
+//! Two handshake modes share one wire layout and are told apart by `suite_id`
+//! (`HandshakeMode`). The signed mode signs the HS1/HS2 transcripts with Ed25519 and ML-DSA-65,
+//! which leaves third-party-verifiable evidence that the session took place. The deniable mode
+//! signs nothing: B is authenticated by `conf_b`, keyed by RK0 and so by ss1 (only B can decap
+//! ct1 to its KT-published SPK_PQ), and A implicitly, because RK0 also mixes ss3 encapsulated to
+//! A's KT-published PQ_RCV key, so only A can derive B's keys. Either party could have produced
+//! the whole transcript alone. The mode is bound into the transcripts and into RK0, and a
+//! responder admits modes through a `HandshakeModePolicy`, so neither mode can be rewritten into
+//! the other in flight.
 
 use super::constants::*;
-use super::{HandshakeInit, HandshakeResp, PrekeyBundle, SessionRole, SessionState};
+use super::{
+    HandshakeInit, HandshakeResp, PrekeyBundle, PrekeyError, SessionRole, SessionState,
+    SignedPrekey,
+};
 use crate::codec::CodecError;
 use crate::crypto::traits::*;
 use crate::kt::KtVerifier;
@@ -20,10 +33,14 @@ pub enum HandshakeError {
     Crypto(#[from] CryptoError),
     #[error("kt: {0}")]
     Kt(#[from] crate::kt::KtError),
+    #[error("prekey: {0}")]
+    Prekey(#[from] PrekeyError),
     #[error("signature verification failed")]
     BadSignature,
     #[error("confirmation failed")]
     BadConfirmation,
+    #[error("handshake mode not admitted")]
+    ModeNotAdmitted,
     #[error("invalid parameters: {0}")]
     Invalid(&'static str),
 }
@@ -40,17 +57,88 @@ pub struct HandshakeDeps<'a> {
     pub kt: &'a dyn KtVerifier,
 }
 
-/// Derive ms = H("QSP4.3/MS" || ss1 || [ss2] || dh1 || [dh2]) and RK0 = KMAC(ms, "QSP4.3/RK0", session_id, 32)
+/// How the long-term identities are authenticated; carried as the HS1/HS2 `suite_id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeMode {
+    /// Ed25519 + ML-DSA-65 transcript signatures (`QSP_SUITE_ID`).
+    Signed,
+    /// KEM-based implicit authentication, no transcript signatures (`QSP_SUITE_ID_DENIABLE`).
+    Deniable,
+}
+
+impl HandshakeMode {
+    pub fn suite_id(self) -> u16 {
+        match self {
+            HandshakeMode::Signed => QSP_SUITE_ID,
+            HandshakeMode::Deniable => QSP_SUITE_ID_DENIABLE,
+        }
+    }
+
+    pub fn from_suite_id(suite_id: u16) -> Option<Self> {
+        match suite_id {
+            QSP_SUITE_ID => Some(HandshakeMode::Signed),
+            QSP_SUITE_ID_DENIABLE => Some(HandshakeMode::Deniable),
+            _ => None,
+        }
+    }
+
+    fn ms_label(self) -> &'static [u8] {
+        match self {
+            HandshakeMode::Signed => b"QSP4.3/MS",
+            HandshakeMode::Deniable => b"QSP4.3/MS/DENIABLE",
+        }
+    }
+}
+
+/// The handshake modes a responder accepts. The default admits only the signed mode, so
+/// deniability is opt-in; `DeniableOnly` is for users who must never leave signed transcripts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HandshakeModePolicy {
+    #[default]
+    SignedOnly,
+    DeniableOnly,
+    Either,
+}
+
+impl HandshakeModePolicy {
+    pub fn admits(self, mode: HandshakeMode) -> bool {
+        matches!(
+            (self, mode),
+            (HandshakeModePolicy::SignedOnly, HandshakeMode::Signed)
+                | (HandshakeModePolicy::DeniableOnly, HandshakeMode::Deniable)
+                | (HandshakeModePolicy::Either, _)
+        )
+    }
+}
+
+/// Responder gate, run before any HS1 processing: the mode HS1 asks for, if `policy` admits it.
+/// Dispatch on the result to `responder_process` or `responder_process_deniable`.
+pub fn responder_mode(
+    policy: HandshakeModePolicy,
+    hs1: &HandshakeInit,
+) -> Result<HandshakeMode, HandshakeError> {
+    let mode = HandshakeMode::from_suite_id(hs1.suite_id)
+        .ok_or(HandshakeError::Invalid("suite_id mismatch"))?;
+    if !policy.admits(mode) {
+        return Err(HandshakeError::ModeNotAdmitted);
+    }
+    Ok(mode)
+}
+
+/// Derive ms = H(label || ss1 || [ss2] || dh1 || [dh2]) and RK0 = KMAC(ms, "QSP4.3/RK0", session_id, 32),
+/// where label is "QSP4.3/MS" (signed) or "QSP4.3/MS/DENIABLE".
+#[allow(clippy::too_many_arguments)]
 fn derive_rk0(
     hash: &dyn Hash,
     kmac: &dyn Kmac,
+    mode: HandshakeMode,
     session_id: &[u8; 16],
     ss1: &[u8],
     ss2: Option<&[u8]>,
     dh1: &[u8; 32],
     dh2: Option<&[u8; 32]>,
 ) -> [u8; 32] {
-    let mut m = b"QSP4.3/MS".to_vec();
+    let mut m = mode.ms_label().to_vec();
     m.extend_from_slice(ss1);
     if let Some(s) = ss2 {
         m.extend_from_slice(s);
@@ -83,6 +171,10 @@ fn kmac32(kmac: &dyn Kmac, key: &[u8; 32], label: &str, data: &[u8]) -> [u8; 32]
     out
 }
 
+fn is_zero(v: &[u8]) -> bool {
+    v.iter().all(|b| *b == 0)
+}
+
 /// Initiator constructs HandshakeInit, returning the message and an InitiatorState required to finalize with HandshakeResp.
 ///
 /// This function assumes the caller has already acquired and validated `bundle_b` (including service freshness).
@@ -102,8 +194,157 @@ pub fn initiator_build(
     pq_rcv_a_id: u32,
     pq_rcv_a_pub: Vec<u8>,
 ) -> Result<(HandshakeInit, InitiatorState), HandshakeError> {
+    let (init, hs1_hash) = initiator_build_unsigned(
+        deps,
+        HandshakeMode::Signed,
+        bundle_b,
+        None,
+        user_id_b,
+        device_id_b,
+        session_id,
+        ik_sig_ec_a_pub,
+        ik_sig_pq_a_pub,
+        pq_rcv_a_id,
+        pq_rcv_a_pub,
+    )?;
+    sign_hs1(deps, init, &hs1_hash, &ik_sig_ec_a_priv, &ik_sig_pq_a_priv)
+}
+
+/// `initiator_build` using a one-time or last-resort prekey claimed from B's prekey service.
+///
+/// `bundle_b` is B's KT-logged base bundle and must carry no OPK; `prekey` is accepted only if
+/// signed by that bundle's identity keys, and then fills the OPK slots.
+#[allow(clippy::too_many_arguments)]
+pub fn initiator_build_with_prekey(
+    deps: &HandshakeDeps,
+    bundle_b: &PrekeyBundle,
+    prekey: &SignedPrekey,
+    user_id_b: Vec<u8>,
+    device_id_b: u32,
+    session_id: [u8; 16],
+    ik_sig_ec_a_pub: [u8; 32],
+    ik_sig_ec_a_priv: Vec<u8>,
+    ik_sig_pq_a_pub: Vec<u8>,
+    ik_sig_pq_a_priv: Vec<u8>,
+    pq_rcv_a_id: u32,
+    pq_rcv_a_pub: Vec<u8>,
+) -> Result<(HandshakeInit, InitiatorState), HandshakeError> {
+    let (init, hs1_hash) = initiator_build_unsigned(
+        deps,
+        HandshakeMode::Signed,
+        bundle_b,
+        Some(prekey),
+        user_id_b,
+        device_id_b,
+        session_id,
+        ik_sig_ec_a_pub,
+        ik_sig_pq_a_pub,
+        pq_rcv_a_id,
+        pq_rcv_a_pub,
+    )?;
+    sign_hs1(deps, init, &hs1_hash, &ik_sig_ec_a_priv, &ik_sig_pq_a_priv)
+}
+
+fn sign_hs1(
+    deps: &HandshakeDeps,
+    mut init: InitiatorState,
+    hs1_hash: &[u8; 64],
+    ik_sig_ec_a_priv: &[u8],
+    ik_sig_pq_a_priv: &[u8],
+) -> Result<(HandshakeInit, InitiatorState), HandshakeError> {
+    init.hs1.sig_ec_a = deps.ed25519.sign(ik_sig_ec_a_priv, hs1_hash);
+    init.hs1.sig_pq_a = deps.pq_sig.sign(ik_sig_pq_a_priv, hs1_hash)?;
+    Ok((init.hs1.clone(), init))
+}
+
+/// Deniable-mode `initiator_build`: HS1 under `QSP_SUITE_ID_DENIABLE` with all-zero signature
+/// fields. A's identity public keys are still carried so B can match them against A's KT
+/// bundle; no signing key is used.
+#[allow(clippy::too_many_arguments)]
+pub fn initiator_build_deniable(
+    deps: &HandshakeDeps,
+    bundle_b: &PrekeyBundle,
+    user_id_b: Vec<u8>,
+    device_id_b: u32,
+    session_id: [u8; 16],
+    ik_sig_ec_a_pub: [u8; 32],
+    ik_sig_pq_a_pub: Vec<u8>,
+    pq_rcv_a_id: u32,
+    pq_rcv_a_pub: Vec<u8>,
+) -> Result<(HandshakeInit, InitiatorState), HandshakeError> {
+    let (init, _) = initiator_build_unsigned(
+        deps,
+        HandshakeMode::Deniable,
+        bundle_b,
+        None,
+        user_id_b,
+        device_id_b,
+        session_id,
+        ik_sig_ec_a_pub,
+        ik_sig_pq_a_pub,
+        pq_rcv_a_id,
+        pq_rcv_a_pub,
+    )?;
+    Ok((init.hs1.clone(), init))
+}
+
+/// Deniable-mode `initiator_build_with_prekey`.
+#[allow(clippy::too_many_arguments)]
+pub fn initiator_build_deniable_with_prekey(
+    deps: &HandshakeDeps,
+    bundle_b: &PrekeyBundle,
+    prekey: &SignedPrekey,
+    user_id_b: Vec<u8>,
+    device_id_b: u32,
+    session_id: [u8; 16],
+    ik_sig_ec_a_pub: [u8; 32],
+    ik_sig_pq_a_pub: Vec<u8>,
+    pq_rcv_a_id: u32,
+    pq_rcv_a_pub: Vec<u8>,
+) -> Result<(HandshakeInit, InitiatorState), HandshakeError> {
+    let (init, _) = initiator_build_unsigned(
+        deps,
+        HandshakeMode::Deniable,
+        bundle_b,
+        Some(prekey),
+        user_id_b,
+        device_id_b,
+        session_id,
+        ik_sig_ec_a_pub,
+        ik_sig_pq_a_pub,
+        pq_rcv_a_id,
+        pq_rcv_a_pub,
+    )?;
+    Ok((init.hs1.clone(), init))
+}
+
+/// HS1 with zero signature fields for `mode`, plus its transcript hash. A claimed `prekey` is
+/// verified against the KT-verified `bundle_b` and then used as its OPK.
+#[allow(clippy::too_many_arguments)]
+fn initiator_build_unsigned(
+    deps: &HandshakeDeps,
+    mode: HandshakeMode,
+    bundle_b: &PrekeyBundle,
+    prekey: Option<&SignedPrekey>,
+    user_id_b: Vec<u8>,
+    device_id_b: u32,
+    session_id: [u8; 16],
+    ik_sig_ec_a_pub: [u8; 32],
+    ik_sig_pq_a_pub: Vec<u8>,
+    pq_rcv_a_id: u32,
+    pq_rcv_a_pub: Vec<u8>,
+) -> Result<(InitiatorState, [u8; 64]), HandshakeError> {
     // KT verification of B's identity keys (Authenticated mode)
     deps.kt.verify_bundle(bundle_b, deps.ed25519, deps.pq_sig)?;
+    let claimed;
+    let bundle_b = match prekey {
+        Some(prekey) => {
+            prekey.verify(deps.hash, deps.ed25519, deps.pq_sig, bundle_b)?;
+            claimed = prekey.attach_to(bundle_b)?;
+            &claimed
+        }
+        None => bundle_b,
+    };
 
     // Generate EK_DH_A
     let (ek_priv, ek_pub) = deps.dh.keypair();
@@ -158,6 +399,7 @@ pub fn initiator_build(
     let rk0_pre = derive_rk0(
         deps.hash,
         deps.kmac,
+        mode,
         &session_id,
         &ss1,
         ss2.as_deref(),
@@ -165,10 +407,10 @@ pub fn initiator_build(
         dh2.as_ref(),
     );
 
-    // Construct HS1 by setting sig fields to zero and hashing
-    let mut hs1 = HandshakeInit {
+    // Construct HS1 with sig fields zero; the signed mode fills them over this transcript
+    let hs1 = HandshakeInit {
         protocol_version: QSP_PROTOCOL_VERSION,
-        suite_id: QSP_SUITE_ID,
+        suite_id: mode.suite_id(),
         session_id,
         user_id_b,
         device_id_b,
@@ -185,15 +427,9 @@ pub fn initiator_build(
         sig_ec_a: vec![0u8; SZ_ED25519_SIG],
         sig_pq_a: vec![0u8; SZ_MLDSA65_SIG],
     };
-
-    let hs1_hash = hs1.hs1_transcript(deps.hash);
-    let sig_ec_a = deps.ed25519.sign(&ik_sig_ec_a_priv, &hs1_hash);
-    let sig_pq_a = deps.pq_sig.sign(&ik_sig_pq_a_priv, &hs1_hash)?;
-    hs1.sig_ec_a = sig_ec_a;
-    hs1.sig_pq_a = sig_pq_a;
+    let hs1_hash = hs1.hs1_transcript(deps.hash)?;
 
     Ok((
-        hs1.clone(),
         InitiatorState {
             session_id,
             rk0_pre,
@@ -201,6 +437,7 @@ pub fn initiator_build(
             hs1,
             pq_rcv_a_priv: Vec::new(), // caller should supply via SessionState init; left here as placeholder
         },
+        hs1_hash,
     ))
 }
 
@@ -261,7 +498,7 @@ pub fn responder_process(
     // In HS1 we only have A's IK pubs; KT proof carriage is in PrekeyBundle, not HS1.
     // Therefore this skeleton expects the caller to have performed KT pinning for A out-of-band or via service.
     // We *do* enforce that signature verification occurs.
-    let hs1_hash = hs1.hs1_transcript(deps.hash);
+    let hs1_hash = hs1.hs1_transcript(deps.hash)?;
     if !deps
         .ed25519
         .verify(&hs1.ik_sig_ec_a_pub, &hs1_hash, &hs1.sig_ec_a)
@@ -277,6 +514,105 @@ pub fn responder_process(
     deps.kt
         .verify_responder_binding(hs1, initiator_bundle, deps.ed25519, deps.pq_sig)?;
 
+    responder_respond(
+        deps,
+        HandshakeMode::Signed,
+        hs1,
+        Some((&ik_sig_ec_b_priv, &ik_sig_pq_b_priv)),
+        ik_sig_ec_b_pub,
+        ik_sig_pq_b_pub,
+        spk_dh_b_priv,
+        spk_pq_b_priv,
+        opk_dh_b_priv,
+        opk_pq_b_priv,
+        dh0_b,
+        pq_rcv_b_id,
+        pq_rcv_b_pub,
+        pq_rcv_b_priv,
+    )
+}
+
+/// Deniable-mode `responder_process`. With no signature over HS1, A's identity rests entirely
+/// on the KT binding of the HS1 keys to `initiator_bundle` (required here, not optional) and on
+/// ss3 being encapsulated to that bundle's PQ_RCV key: the resulting session is readable only
+/// by A, and B's first successful decrypt of A's traffic is the point at which A is confirmed.
+#[allow(clippy::too_many_arguments)]
+pub fn responder_process_deniable(
+    deps: &HandshakeDeps,
+    hs1: &HandshakeInit,
+    initiator_bundle: &PrekeyBundle,
+    ik_sig_ec_b_pub: [u8; 32],
+    ik_sig_pq_b_pub: Vec<u8>,
+    spk_dh_b_priv: crate::crypto::traits::X25519Priv,
+    spk_pq_b_priv: Vec<u8>,
+    opk_dh_b_priv: Option<crate::crypto::traits::X25519Priv>,
+    opk_pq_b_priv: Option<Vec<u8>>,
+    dh0_b: (
+        crate::crypto::traits::X25519Priv,
+        crate::crypto::traits::X25519Pub,
+    ),
+    pq_rcv_b_id: u32,
+    pq_rcv_b_pub: Vec<u8>,
+    pq_rcv_b_priv: Vec<u8>,
+) -> Result<(HandshakeResp, SessionState), HandshakeError> {
+    if hs1.protocol_version != QSP_PROTOCOL_VERSION {
+        return Err(HandshakeError::Invalid("protocol_version mismatch"));
+    }
+    if hs1.suite_id != QSP_SUITE_ID_DENIABLE {
+        return Err(HandshakeError::Invalid("suite_id mismatch"));
+    }
+    if hs1.sig_ec_a.len() != SZ_ED25519_SIG || !is_zero(&hs1.sig_ec_a) {
+        return Err(HandshakeError::Invalid("sig_ec_a"));
+    }
+    if hs1.sig_pq_a.len() != SZ_MLDSA65_SIG || !is_zero(&hs1.sig_pq_a) {
+        return Err(HandshakeError::Invalid("sig_pq_a"));
+    }
+    if hs1.ik_sig_pq_a_pub.len() != SZ_MLDSA65_PUB {
+        return Err(HandshakeError::Invalid("ik_sig_pq_a_pub len"));
+    }
+    deps.kt
+        .verify_responder_binding(hs1, Some(initiator_bundle), deps.ed25519, deps.pq_sig)?;
+
+    responder_respond(
+        deps,
+        HandshakeMode::Deniable,
+        hs1,
+        None,
+        ik_sig_ec_b_pub,
+        ik_sig_pq_b_pub,
+        spk_dh_b_priv,
+        spk_pq_b_priv,
+        opk_dh_b_priv,
+        opk_pq_b_priv,
+        dh0_b,
+        pq_rcv_b_id,
+        pq_rcv_b_pub,
+        pq_rcv_b_priv,
+    )
+}
+
+/// Key schedule and HS2 for an HS1 already authenticated for `mode`. `sign` carries B's signing
+/// keys in the signed mode; the deniable mode leaves the HS2 signature fields zero.
+#[allow(clippy::too_many_arguments)]
+fn responder_respond(
+    deps: &HandshakeDeps,
+    mode: HandshakeMode,
+    hs1: &HandshakeInit,
+    sign: Option<(&[u8], &[u8])>,
+    ik_sig_ec_b_pub: [u8; 32],
+    ik_sig_pq_b_pub: Vec<u8>,
+    spk_dh_b_priv: crate::crypto::traits::X25519Priv,
+    spk_pq_b_priv: Vec<u8>,
+    opk_dh_b_priv: Option<crate::crypto::traits::X25519Priv>,
+    opk_pq_b_priv: Option<Vec<u8>>,
+    dh0_b: (
+        crate::crypto::traits::X25519Priv,
+        crate::crypto::traits::X25519Pub,
+    ),
+    pq_rcv_b_id: u32,
+    pq_rcv_b_pub: Vec<u8>,
+    pq_rcv_b_priv: Vec<u8>,
+) -> Result<(HandshakeResp, SessionState), HandshakeError> {
     // Decapsulate ct1/ct2
     let ss1 = deps.pq_kem.decap(&spk_pq_b_priv, &hs1.ct1)?;
     let (ss2, dh2) = if hs1.opk_used {
@@ -308,6 +644,7 @@ pub fn responder_process(
     let rk0_pre = derive_rk0(
         deps.hash,
         deps.kmac,
+        mode,
         &hs1.session_id,
         &ss1,
         ss2.as_deref(),
@@ -323,7 +660,7 @@ pub fn responder_process(
     // Build HS2 with zero sigs to compute HS2 transcript
     let mut hs2 = HandshakeResp {
         protocol_version: QSP_PROTOCOL_VERSION,
-        suite_id: QSP_SUITE_ID,
+        suite_id: mode.suite_id(),
         session_id: hs1.session_id,
         dh0_b_pub: dh0_b.1 .0,
         pq_rcv_b_id,
@@ -336,14 +673,16 @@ pub fn responder_process(
         sig_pq_b: vec![0u8; SZ_MLDSA65_SIG],
     };
 
-    let hs2_hash = hs2.hs2_transcript(hs1, deps.hash);
+    let hs2_hash = hs2.hs2_transcript(hs1, deps.hash)?;
     let conf_b = kmac32(deps.kmac, &rk0, "QSP4.3/CONF", &hs2_hash);
     hs2.conf_b = conf_b;
 
-    let sig_ec_b = deps.ed25519.sign(&ik_sig_ec_b_priv, &hs2_hash);
-    let sig_pq_b = deps.pq_sig.sign(&ik_sig_pq_b_priv, &hs2_hash)?;
-    hs2.sig_ec_b = sig_ec_b;
-    hs2.sig_pq_b = sig_pq_b;
+    if let Some((ik_sig_ec_b_priv, ik_sig_pq_b_priv)) = sign {
+        let sig_ec_b = deps.ed25519.sign(ik_sig_ec_b_priv, &hs2_hash);
+        let sig_pq_b = deps.pq_sig.sign(ik_sig_pq_b_priv, &hs2_hash)?;
+        hs2.sig_ec_b = sig_ec_b;
+        hs2.sig_pq_b = sig_pq_b;
+    }
 
     // Initialize session (QSP §5.6)
     let mut st = SessionState::new(
@@ -377,7 +716,7 @@ pub fn initiator_finalize(
     if hs2.protocol_version != QSP_PROTOCOL_VERSION {
         return Err(HandshakeError::Invalid("protocol_version mismatch"));
     }
-    if hs2.suite_id != QSP_SUITE_ID {
+    if hs2.suite_id != QSP_SUITE_ID || init.hs1.suite_id != QSP_SUITE_ID {
         return Err(HandshakeError::Invalid("suite_id mismatch"));
     }
     if hs2.sig_ec_b.len() != SZ_ED25519_SIG {
@@ -390,7 +729,7 @@ pub fn initiator_finalize(
         return Err(HandshakeError::Invalid("ik_sig_pq_b_pub len"));
     }
     // Verify HS2 signatures
-    let hs2_hash = hs2.hs2_transcript(&init.hs1, deps.hash);
+    let hs2_hash = hs2.hs2_transcript(&init.hs1, deps.hash)?;
 
     if !deps
         .ed25519
@@ -405,12 +744,58 @@ pub fn initiator_finalize(
         return Err(HandshakeError::BadSignature);
     }
 
+    initiator_confirm(deps, init, hs2, &hs2_hash, dh0_a, pq_rcv_a_priv)
+}
+
+/// Deniable-mode `initiator_finalize`. B is authenticated by `conf_b` alone: it is keyed by RK0,
+/// which only the holder of the SPK_PQ private key from B's KT bundle can derive.
+pub fn initiator_finalize_deniable(
+    deps: &HandshakeDeps,
+    init: InitiatorState,
+    hs2: &HandshakeResp,
+    dh0_a: (
+        crate::crypto::traits::X25519Priv,
+        crate::crypto::traits::X25519Pub,
+    ),
+    pq_rcv_a_priv: Vec<u8>,
+) -> Result<SessionState, HandshakeError> {
+    if hs2.protocol_version != QSP_PROTOCOL_VERSION {
+        return Err(HandshakeError::Invalid("protocol_version mismatch"));
+    }
+    if hs2.suite_id != QSP_SUITE_ID_DENIABLE || init.hs1.suite_id != QSP_SUITE_ID_DENIABLE {
+        return Err(HandshakeError::Invalid("suite_id mismatch"));
+    }
+    if hs2.sig_ec_b.len() != SZ_ED25519_SIG || !is_zero(&hs2.sig_ec_b) {
+        return Err(HandshakeError::Invalid("sig_ec_b"));
+    }
+    if hs2.sig_pq_b.len() != SZ_MLDSA65_SIG || !is_zero(&hs2.sig_pq_b) {
+        return Err(HandshakeError::Invalid("sig_pq_b"));
+    }
+    if hs2.ik_sig_pq_b_pub.len() != SZ_MLDSA65_PUB {
+        return Err(HandshakeError::Invalid("ik_sig_pq_b_pub len"));
+    }
+    let hs2_hash = hs2.hs2_transcript(&init.hs1, deps.hash)?;
+    initiator_confirm(deps, init, hs2, &hs2_hash, dh0_a, pq_rcv_a_priv)
+}
+
+/// ct3 decapsulation, RK0 and `conf_b` check for an HS2 already authenticated for its mode.
+fn initiator_confirm(
+    deps: &HandshakeDeps,
+    init: InitiatorState,
+    hs2: &HandshakeResp,
+    hs2_hash: &[u8; 64],
+    dh0_a: (
+        crate::crypto::traits::X25519Priv,
+        crate::crypto::traits::X25519Pub,
+    ),
+    pq_rcv_a_priv: Vec<u8>,
+) -> Result<SessionState, HandshakeError> {
     // A decapsulates ct3 under PQ_RCV_A_priv (for PQ receive cache binding)
     let ss3 = deps.pq_kem.decap(&pq_rcv_a_priv, &hs2.ct3)?;
     let rk0 = mix_rk0_ss3(deps.kmac, &init.rk0_pre, &init.session_id, &ss3);
 
     // Verify confirmation
-    let conf = kmac32(deps.kmac, &rk0, "QSP4.3/CONF", &hs2_hash);
+    let conf = kmac32(deps.kmac, &rk0, "QSP4.3/CONF", hs2_hash);
     if conf != hs2.conf_b {
         return Err(HandshakeError::BadConfirmation);
     }
@@ -544,7 +929,7 @@ mod tests {
     struct DummyPqKem;
     impl PqKem768 for DummyPqKem {
         fn encap(&self, _pubk: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
-            Ok((vec![0u8; 32], vec![0x55u8; 32]))
+            Ok((vec![0u8; SZ_MLKEM768_CT], vec![0x55u8; 32]))
         }
         fn decap(&self, _privk: &[u8], _ct: &[u8]) -> Result<Vec<u8>, CryptoError> {
             Ok(vec![0x66u8; 32])
@@ -988,7 +1373,7 @@ mod tests {
         let pq_rcv_a_pub = vec![0u8; SZ_MLKEM768_PUB];
 
         let mut bundle = base_bundle(None, Some((42, vec![0u8; SZ_MLKEM768_PUB])));
-        let before = bundle.encode();
+        let before = bundle.encode().unwrap();
         let err1 = expect_err(call_initiator_build(
             &deps,
             &bundle,
@@ -1007,10 +1392,10 @@ mod tests {
             pq_rcv_a_pub.clone(),
         ));
         assert_eq!(err_debug(&err1), err_debug(&err2));
-        assert_eq!(bundle.encode(), before);
+        assert_eq!(bundle.encode().unwrap(), before);
 
         bundle = base_bundle(Some((7, [0u8; SZ_X25519_PUB])), None);
-        let before = bundle.encode();
+        let before = bundle.encode().unwrap();
         let err1 = expect_err(call_initiator_build(
             &deps,
             &bundle,
@@ -1029,7 +1414,7 @@ mod tests {
             pq_rcv_a_pub,
         ));
         assert_eq!(err_debug(&err1), err_debug(&err2));
-        assert_eq!(bundle.encode(), before);
+        assert_eq!(bundle.encode().unwrap(), before);
     }
 
     #[test]
@@ -1280,7 +1665,7 @@ mod tests {
             &pq_sig,
             &kt,
         );
-        let hs2_before = hs2.encode();
+        let hs2_before = hs2.encode().unwrap();
 
         let err1 = expect_err(initiator_finalize(
             &deps_fail,
@@ -1306,6 +1691,305 @@ mod tests {
             pq_rcv_a_priv,
         ));
         assert_eq!(err_debug(&err1), err_debug(&err2));
-        assert_eq!(hs2.encode(), hs2_before);
+        assert_eq!(hs2.encode().unwrap(), hs2_before);
+    }
+
+    struct TestHash;
+    impl Hash for TestHash {
+        fn sha512(&self, data: &[u8]) -> [u8; 64] {
+            let mut out = [0u8; 64];
+            for (i, b) in data.iter().enumerate() {
+                out[i % 64] = out[i % 64].wrapping_mul(31).wrapping_add(*b);
+            }
+            out
+        }
+    }
+
+    /// Responder-side arguments shared by the mode tests; B's signing keys are dummies.
+    fn respond_signed(
+        deps: &HandshakeDeps,
+        hs1: &HandshakeInit,
+        bundle_a: &PrekeyBundle,
+    ) -> Result<(HandshakeResp, SessionState), HandshakeError> {
+        responder_process(
+            deps,
+            hs1,
+            Some(bundle_a),
+            [0u8; SZ_ED25519_PUB],
+            vec![0u8; 32],
+            vec![0u8; SZ_MLDSA65_PUB],
+            vec![0u8; 1],
+            X25519Priv([0x10u8; 32]),
+            vec![0u8; 32],
+            None,
+            None,
+            DummyDh.keypair(),
+            7,
+            vec![0u8; SZ_MLKEM768_PUB],
+            vec![0u8; 32],
+        )
+    }
+
+    fn respond_deniable(
+        deps: &HandshakeDeps,
+        hs1: &HandshakeInit,
+        bundle_a: &PrekeyBundle,
+    ) -> Result<(HandshakeResp, SessionState), HandshakeError> {
+        responder_process_deniable(
+            deps,
+            hs1,
+            bundle_a,
+            [0u8; SZ_ED25519_PUB],
+            vec![0u8; SZ_MLDSA65_PUB],
+            X25519Priv([0x10u8; 32]),
+            vec![0u8; 32],
+            None,
+            None,
+            DummyDh.keypair(),
+            7,
+            vec![0u8; SZ_MLKEM768_PUB],
+            vec![0u8; 32],
+        )
+    }
+
+    fn build_deniable(
+        deps: &HandshakeDeps,
+        bundle_b: &PrekeyBundle,
+        bundle_a: &PrekeyBundle,
+    ) -> (HandshakeInit, InitiatorState) {
+        initiator_build_deniable(
+            deps,
+            bundle_b,
+            vec![0xB1],
+            11,
+            [0x11; SZ_SESSION_ID],
+            bundle_a.ik_sig_ec_pub,
+            bundle_a.ik_sig_pq_pub.clone(),
+            bundle_a.pq_rcv_id,
+            bundle_a.pq_rcv_pub.clone(),
+        )
+        .unwrap()
+    }
+
+    fn build_signed(
+        deps: &HandshakeDeps,
+        bundle_b: &PrekeyBundle,
+        bundle_a: &PrekeyBundle,
+    ) -> (HandshakeInit, InitiatorState) {
+        initiator_build(
+            deps,
+            bundle_b,
+            vec![0xB1],
+            11,
+            [0x11; SZ_SESSION_ID],
+            bundle_a.ik_sig_ec_pub,
+            vec![0u8; 32],
+            bundle_a.ik_sig_pq_pub.clone(),
+            vec![0u8; 1],
+            bundle_a.pq_rcv_id,
+            bundle_a.pq_rcv_pub.clone(),
+        )
+        .unwrap()
+    }
+
+    fn fixed_kem() -> PqKemFixed {
+        PqKemFixed {
+            ss3_encap: vec![0x22u8; 32],
+            ss3_decap: vec![0x22u8; 32],
+            fail_decap: false,
+        }
+    }
+
+    #[test]
+    fn deniable_round_trip_agrees_without_signing_or_verifying() {
+        let hash = TestHash;
+        let kmac = TestKmac;
+        let dh = DummyDh;
+        let aead = DummyAead;
+        // Both verifiers reject everything: the deniable path must never consult them.
+        let ed25519 = CountingEd25519::new();
+        let pq_kem = fixed_kem();
+        let pq_sig = CountingPqSig::new();
+        let kt = RequireResponderBundle;
+        let deps = mk_deps_dyn(&hash, &kmac, &dh, &aead, &ed25519, &pq_kem, &pq_sig, &kt);
+        let bundle_b = base_bundle(None, None);
+        let bundle_a = base_bundle(None, None);
+
+        let (hs1, init) = build_deniable(&deps, &bundle_b, &bundle_a);
+        assert_eq!(hs1.suite_id, QSP_SUITE_ID_DENIABLE);
+        assert!(is_zero(&hs1.sig_ec_a) && is_zero(&hs1.sig_pq_a));
+        assert_eq!(
+            HandshakeInit::decode(&hs1.encode().unwrap())
+                .unwrap()
+                .suite_id,
+            QSP_SUITE_ID_DENIABLE
+        );
+
+        let (hs2, st_b) = respond_deniable(&deps, &hs1, &bundle_a).unwrap();
+        assert_eq!(hs2.suite_id, QSP_SUITE_ID_DENIABLE);
+        assert!(is_zero(&hs2.sig_ec_b) && is_zero(&hs2.sig_pq_b));
+
+        let st_a =
+            initiator_finalize_deniable(&deps, init, &hs2, dh.keypair(), vec![0xB0; 32]).unwrap();
+        assert_eq!(st_a.rk, st_b.rk);
+        assert_eq!(ed25519.count(), 0);
+        assert_eq!(pq_sig.count(), 0);
+    }
+
+    #[test]
+    fn deniable_and_signed_modes_derive_distinct_root_keys() {
+        let hash = TestHash;
+        let kmac = TestKmac;
+        let dh = DummyDh;
+        let aead = DummyAead;
+        let ed25519 = DummyEd25519;
+        let pq_kem = fixed_kem();
+        let pq_sig = DummyPqSig;
+        let kt = AllowKt;
+        let deps = mk_deps_dyn(&hash, &kmac, &dh, &aead, &ed25519, &pq_kem, &pq_sig, &kt);
+        let bundle = base_bundle(None, None);
+
+        let (hs1_s, _) = build_signed(&deps, &bundle, &bundle);
+        let (hs1_d, _) = build_deniable(&deps, &bundle, &bundle);
+        let (_, st_s) = respond_signed(&deps, &hs1_s, &bundle).unwrap();
+        let (_, st_d) = respond_deniable(&deps, &hs1_d, &bundle).unwrap();
+        assert_ne!(st_s.rk, st_d.rk);
+    }
+
+    #[test]
+    fn each_entry_point_rejects_the_other_mode() {
+        let hash = TestHash;
+        let kmac = TestKmac;
+        let dh = DummyDh;
+        let aead = DummyAead;
+        let ed25519 = DummyEd25519;
+        let pq_kem = fixed_kem();
+        let pq_sig = DummyPqSig;
+        let kt = AllowKt;
+        let deps = mk_deps_dyn(&hash, &kmac, &dh, &aead, &ed25519, &pq_kem, &pq_sig, &kt);
+        let bundle = base_bundle(None, None);
+
+        let (hs1_s, init_s) = build_signed(&deps, &bundle, &bundle);
+        let (hs1_d, init_d) = build_deniable(&deps, &bundle, &bundle);
+        let suite_err = |e: HandshakeError| {
+            assert!(matches!(e, HandshakeError::Invalid("suite_id mismatch")));
+        };
+        suite_err(expect_err(respond_deniable(&deps, &hs1_s, &bundle)));
+        suite_err(expect_err(respond_signed(&deps, &hs1_d, &bundle)));
+
+        let (hs2_s, _) = respond_signed(&deps, &hs1_s, &bundle).unwrap();
+        let (hs2_d, _) = respond_deniable(&deps, &hs1_d, &bundle).unwrap();
+
+        // An HS2 rewritten to the initiator's suite still fails: the initiator's own HS1 pins it.
+        let mut hs2_d_as_signed = hs2_d.clone();
+        hs2_d_as_signed.suite_id = QSP_SUITE_ID;
+        suite_err(expect_err(initiator_finalize(
+            &deps,
+            init_d,
+            &hs2_d_as_signed,
+            dh.keypair(),
+            vec![0xB0; 32],
+        )));
+        let mut hs2_s_stripped = hs2_s.clone();
+        hs2_s_stripped.suite_id = QSP_SUITE_ID_DENIABLE;
+        hs2_s_stripped.sig_ec_b = vec![0u8; SZ_ED25519_SIG];
+        hs2_s_stripped.sig_pq_b = vec![0u8; SZ_MLDSA65_SIG];
+        suite_err(expect_err(initiator_finalize_deniable(
+            &deps,
+            init_s,
+            &hs2_s_stripped,
+            dh.keypair(),
+            vec![0xB0; 32],
+        )));
+    }
+
+    #[test]
+    fn signature_stripped_hs1_cannot_pass_as_signed() {
+        let hash = TestHash;
+        let kmac = TestKmac;
+        let dh = DummyDh;
+        let aead = DummyAead;
+        let ed25519 = CountingEd25519::new();
+        let pq_kem = fixed_kem();
+        let pq_sig = CountingPqSig::new();
+        let kt = AllowKt;
+        let deps = mk_deps_dyn(&hash, &kmac, &dh, &aead, &ed25519, &pq_kem, &pq_sig, &kt);
+        let bundle = base_bundle(None, None);
+
+        let (mut hs1, _) = build_deniable(&deps, &bundle, &bundle);
+        hs1.suite_id = QSP_SUITE_ID;
+        let err = expect_err(respond_signed(&deps, &hs1, &bundle));
+        assert!(matches!(err, HandshakeError::BadSignature));
+        assert_eq!(ed25519.count(), 1);
+
+        let (mut hs1, _) = build_deniable(&deps, &bundle, &bundle);
+        hs1.sig_ec_a[0] = 1;
+        let err = expect_err(respond_deniable(&deps, &hs1, &bundle));
+        assert!(matches!(err, HandshakeError::Invalid("sig_ec_a")));
+    }
+
+    #[test]
+    fn deniable_responder_requires_matching_initiator_bundle() {
+        let hash = TestHash;
+        let kmac = TestKmac;
+        let dh = DummyDh;
+        let aead = DummyAead;
+        let ed25519 = DummyEd25519;
+        let pq_kem = fixed_kem();
+        let pq_sig = DummyPqSig;
+        let kt = RequireResponderBundle;
+        let deps = mk_deps_dyn(&hash, &kmac, &dh, &aead, &ed25519, &pq_kem, &pq_sig, &kt);
+        let bundle_b = base_bundle(None, None);
+        let bundle_a = base_bundle(None, None);
+
+        let (hs1, _) = build_deniable(&deps, &bundle_b, &bundle_a);
+        let mut other_a = bundle_a.clone();
+        other_a.pq_rcv_pub[0] ^= 1;
+        let err = expect_err(respond_deniable(&deps, &hs1, &other_a));
+        assert!(matches!(
+            err,
+            HandshakeError::Kt(crate::kt::KtError::VerifyFailed { .. })
+        ));
+        assert!(respond_deniable(&deps, &hs1, &bundle_a).is_ok());
+    }
+
+    #[test]
+    fn responder_mode_applies_policy() {
+        let hash = DummyHash;
+        let kmac = DummyKmac;
+        let dh = DummyDh;
+        let aead = DummyAead;
+        let ed25519 = DummyEd25519;
+        let pq_kem = DummyPqKem;
+        let pq_sig = DummyPqSig;
+        let kt = AllowKt;
+        let deps = mk_deps(&hash, &kmac, &dh, &aead, &ed25519, &pq_kem, &pq_sig, &kt);
+        let bundle = base_bundle(None, None);
+        let (hs1_s, _) = build_signed(&deps, &bundle, &bundle);
+        let (hs1_d, _) = build_deniable(&deps, &bundle, &bundle);
+
+        use HandshakeMode::*;
+        use HandshakeModePolicy::*;
+        assert_eq!(HandshakeModePolicy::default(), SignedOnly);
+        for (policy, hs1, want) in [
+            (SignedOnly, &hs1_s, Some(Signed)),
+            (SignedOnly, &hs1_d, None),
+            (DeniableOnly, &hs1_s, None),
+            (DeniableOnly, &hs1_d, Some(Deniable)),
+            (Either, &hs1_s, Some(Signed)),
+            (Either, &hs1_d, Some(Deniable)),
+        ] {
+            match (responder_mode(policy, hs1), want) {
+                (Ok(mode), Some(w)) => assert_eq!(mode, w),
+                (Err(HandshakeError::ModeNotAdmitted), None) => {}
+                (other, _) => panic!("{policy:?}: unexpected {other:?}"),
+            }
+        }
+        let mut unknown = hs1_s.clone();
+        unknown.suite_id = 0x7fff;
+        assert!(matches!(
+            responder_mode(Either, &unknown),
+            Err(HandshakeError::Invalid("suite_id mismatch"))
+        ));
     }
 }
@@ -2,12 +2,14 @@
 
 mod constants;
 mod handshake;
+mod prekeys;
 mod ratchet;
 mod state;
 mod types;
 
 pub use constants::*;
 pub use handshake::*;
+pub use prekeys::*;
 pub use ratchet::*;
 pub use state::*;
 pub use types::*;
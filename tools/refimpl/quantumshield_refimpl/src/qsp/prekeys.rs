@@ -0,0 +1,1088 @@
+//! One-time prekey pool for the QSP handshake.
+//!
+//! The KT-logged `PrekeyBundle` is static per device, so its `opk_dh`/`opk_pq` slots cannot
+//! carry a fresh key per initiator without a fresh KT entry each time. One-time prekeys are
+//! instead published as `SignedPrekey`s: an X25519 and an ML-KEM-768 public key under one id,
+//! signed by the device's bundle identity keys over (user_id, device_id, id, keys). The
+//! initiator KT-verifies the base bundle, verifies the prekey signature against it, and only
+//! then fills the OPK slots (`initiator_build_with_prekey`).
+//!
+//! Three roles:
+//! - the owner keeps `PrekeySecrets`, generates signed batches, and deletes a one-time secret
+//!   once the handshake that used it has completed (`consume`);
+//! - the prekey service keeps one `PrekeyDirectory` per device: uploads are validated as a
+//!   whole, ids only ever increase so a one-time key is never reissued, each `claim` hands a
+//!   key out exactly once, and an empty pool falls back to the signed last-resort key;
+//! - `PrekeyPoolStatus::replenish` tells the owner to upload more before the pool runs dry.
+//!
+//! The owner persists `PrekeySecrets` through `snapshot_bytes`/`restore_bytes`, or sealed with
+//! `snapshot::SnapshotKind::Prekeys` like the session snapshots:
+//!
+//! ```text
+//! "QSPK" | version u8 (=1) | next_id u32 | next_last_resort u32
+//!   | u16 n | n * (id u32 | dh_priv[32] | varbytes_u16 pq_priv)   one-time, ascending ids
+//!   | u8 m  | m * (id u32 | dh_priv[32] | varbytes_u16 pq_priv)   last-resort, oldest first
+//! ```
+//!
+//! Last-resort keys carry `PREKEY_LAST_RESORT_FLAG` in their id and are never consumed; the
+//! owner keeps the previous one for `PREKEY_LAST_RESORT_RETAINED - 1` rotations so handshakes
+//! racing a rotation still complete.
+
+use super::constants::*;
+use super::{HandshakeInit, PrekeyBundle};
+use crate::codec::{canonical_struct, CanonicalCodec, CodecError, Fixed, Raw, Reader, Writer, U32};
+use crate::crypto::provider::CryptoProvider;
+use crate::crypto::traits::{CryptoError, Hash, PqSigMldsa65, SecretBytes, SigEd25519, X25519Priv};
+use std::collections::{BTreeMap, VecDeque};
+use thiserror::Error;
+#[cfg(feature = "stdcrypto")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Set in the id of every last-resort prekey; one-time ids stay below it.
+pub const PREKEY_LAST_RESORT_FLAG: u32 = 0x8000_0000;
+/// Most prekeys accepted in one upload.
+pub const PREKEY_UPLOAD_MAX: usize = 100;
+/// Most unclaimed one-time prekeys a directory holds per device.
+pub const PREKEY_POOL_MAX: usize = 500;
+/// Default pool size below which the owner is asked to replenish.
+pub const PREKEY_LOW_WATER: usize = 20;
+/// Last-resort secrets the owner retains: the current one plus its predecessors.
+pub const PREKEY_LAST_RESORT_RETAINED: usize = 2;
+
+const SECRETS_MAGIC: &[u8; 4] = b"QSPK";
+const SECRETS_VERSION: u8 = 1;
+
+#[derive(Debug, Error)]
+pub enum PrekeyError {
+    #[error("crypto: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error("prekey signature verification failed")]
+    BadSignature,
+    #[error("prekey pool exhausted")]
+    Exhausted,
+    #[error("prekey rejected: {0}")]
+    Rejected(&'static str),
+    #[error("unknown prekey id {0}")]
+    UnknownId(u32),
+    #[error("crypto provider lacks ML-KEM-768 keygen")]
+    MissingKeygen,
+    #[error("invalid parameters: {0}")]
+    Invalid(&'static str),
+}
+
+canonical_struct! {
+    /// A published one-time or last-resort prekey. Canonical encoding is the field order below.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct SignedPrekey {
+        pub id: u32 as U32,
+        pub dh_pub: [u8; SZ_X25519_PUB] as Fixed,
+        pub pq_pub: Vec<u8> as Raw<SZ_MLKEM768_PUB>,
+        pub sig_ec: Vec<u8> as Raw<SZ_ED25519_SIG>,
+        pub sig_pq: Vec<u8> as Raw<SZ_MLDSA65_SIG>,
+    }
+}
+
+impl SignedPrekey {
+    /// Canonical wire encoding. Fails if a key or signature is not its fixed size.
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        self.encode_canonical()
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
+        Self::decode_canonical(buf)
+    }
+
+    pub fn is_last_resort(&self) -> bool {
+        self.id & PREKEY_LAST_RESORT_FLAG != 0
+    }
+
+    /// SHA-512("QSP4.3/PREKEY" || user_id || device_id || id || dh_pub || pq_pub); both
+    /// identity signatures are over this digest, as bundle signatures are over `bundle_tbs`.
+    fn digest(
+        hash: &dyn Hash,
+        user_id: &[u8],
+        device_id: u32,
+        id: u32,
+        dh_pub: &[u8; SZ_X25519_PUB],
+        pq_pub: &[u8],
+    ) -> [u8; 64] {
+        let mut w = Writer::new();
+        w.write_bytes(b"QSP4.3/PREKEY");
+        w.write_varbytes_u32(user_id);
+        w.write_u32(device_id);
+        w.write_u32(id);
+        w.write_bytes(dh_pub);
+        w.write_varbytes_u32(pq_pub);
+        hash.sha512(&w.into_vec())
+    }
+
+    /// Verify this prekey was signed by the identity keys of `bundle`'s device.
+    ///
+    /// `bundle` must already be KT-verified; this only extends that trust to the prekey.
+    pub fn verify(
+        &self,
+        hash: &dyn Hash,
+        ed25519: &dyn SigEd25519,
+        pq_sig: &dyn PqSigMldsa65,
+        bundle: &PrekeyBundle,
+    ) -> Result<(), PrekeyError> {
+        let digest = Self::digest(
+            hash,
+            &bundle.user_id,
+            bundle.device_id,
+            self.id,
+            &self.dh_pub,
+            &self.pq_pub,
+        );
+        if !ed25519.verify(&bundle.ik_sig_ec_pub, &digest, &self.sig_ec) {
+            return Err(PrekeyError::BadSignature);
+        }
+        if !pq_sig.verify(&bundle.ik_sig_pq_pub, &digest, &self.sig_pq)? {
+            return Err(PrekeyError::BadSignature);
+        }
+        Ok(())
+    }
+
+    /// `bundle` with this prekey in its OPK slots. The bundle must not carry an OPK already.
+    pub(crate) fn attach_to(&self, bundle: &PrekeyBundle) -> Result<PrekeyBundle, PrekeyError> {
+        if bundle.opk_dh.is_some() || bundle.opk_pq.is_some() {
+            return Err(PrekeyError::Invalid(
+                "bundle already carries a one-time prekey",
+            ));
+        }
+        let mut out = bundle.clone();
+        out.opk_dh = Some((self.id, self.dh_pub));
+        out.opk_pq = Some((self.id, self.pq_pub.clone()));
+        Ok(out)
+    }
+}
+
+/// The owner's identity signing material, used to sign prekeys for one device.
+pub struct PrekeySigner<'a> {
+    pub hash: &'a dyn Hash,
+    pub ed25519: &'a dyn SigEd25519,
+    pub pq_sig: &'a dyn PqSigMldsa65,
+    pub user_id: &'a [u8],
+    pub device_id: u32,
+    pub ik_sig_ec_priv: &'a [u8],
+    pub ik_sig_pq_priv: &'a [u8],
+}
+
+impl PrekeySigner<'_> {
+    fn sign(
+        &self,
+        id: u32,
+        dh_pub: [u8; SZ_X25519_PUB],
+        pq_pub: Vec<u8>,
+    ) -> Result<SignedPrekey, PrekeyError> {
+        let digest = SignedPrekey::digest(
+            self.hash,
+            self.user_id,
+            self.device_id,
+            id,
+            &dh_pub,
+            &pq_pub,
+        );
+        let sig_ec = self.ed25519.sign(self.ik_sig_ec_priv, &digest);
+        let sig_pq = self.pq_sig.sign(self.ik_sig_pq_priv, &digest)?;
+        Ok(SignedPrekey {
+            id,
+            dh_pub,
+            pq_pub,
+            sig_ec,
+            sig_pq,
+        })
+    }
+}
+
+/// Service-side state of one device's pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrekeyPoolStatus {
+    /// Unclaimed one-time prekeys.
+    pub remaining: usize,
+    /// Id of the published last-resort prekey, if any.
+    pub last_resort_id: Option<u32>,
+    /// The pool is below its low-water mark or has no last-resort key.
+    pub replenish: bool,
+}
+
+/// Prekeys a prekey service holds for one device.
+///
+/// The directory does not verify signatures (it need not know the owner's identity keys);
+/// initiators do. What it does enforce is that a one-time id is handed out at most once.
+#[derive(Debug, Clone)]
+pub struct PrekeyDirectory {
+    pool: VecDeque<SignedPrekey>,
+    last_resort: Option<SignedPrekey>,
+    /// Highest one-time id ever accepted; later uploads must go above it.
+    high_id: Option<u32>,
+    low_water: usize,
+}
+
+impl Default for PrekeyDirectory {
+    fn default() -> Self {
+        Self::new(PREKEY_LOW_WATER)
+    }
+}
+
+impl PrekeyDirectory {
+    pub fn new(low_water: usize) -> Self {
+        Self {
+            pool: VecDeque::new(),
+            last_resort: None,
+            high_id: None,
+            low_water,
+        }
+    }
+
+    /// Add a batch of one-time prekeys. Ids must be one-time ids, strictly increasing and
+    /// above every id accepted before. The batch is accepted or rejected as a whole.
+    /// Returns the new pool size.
+    pub fn upload(&mut self, batch: Vec<SignedPrekey>) -> Result<usize, PrekeyError> {
+        if batch.is_empty() {
+            return Err(PrekeyError::Rejected("empty batch"));
+        }
+        if batch.len() > PREKEY_UPLOAD_MAX {
+            return Err(PrekeyError::Rejected("batch too large"));
+        }
+        if self.pool.len() + batch.len() > PREKEY_POOL_MAX {
+            return Err(PrekeyError::Rejected("pool full"));
+        }
+        let mut high = self.high_id;
+        for key in &batch {
+            if key.is_last_resort() {
+                return Err(PrekeyError::Rejected("last-resort id in one-time batch"));
+            }
+            if high.is_some_and(|h| key.id <= h) {
+                return Err(PrekeyError::Rejected("prekey id not fresh"));
+            }
+            high = Some(key.id);
+        }
+        self.high_id = high;
+        self.pool.extend(batch);
+        Ok(self.pool.len())
+    }
+
+    /// Publish a last-resort prekey, replacing the previous one. Ids only move forward.
+    pub fn publish_last_resort(&mut self, key: SignedPrekey) -> Result<(), PrekeyError> {
+        if !key.is_last_resort() {
+            return Err(PrekeyError::Rejected("not a last-resort id"));
+        }
+        if self.last_resort.as_ref().is_some_and(|k| key.id <= k.id) {
+            return Err(PrekeyError::Rejected("last-resort id not fresh"));
+        }
+        self.last_resort = Some(key);
+        Ok(())
+    }
+
+    /// Hand out the oldest one-time prekey, removing it from the pool, or the last-resort
+    /// prekey once the pool is empty.
+    pub fn claim(&mut self) -> Result<SignedPrekey, PrekeyError> {
+        if let Some(key) = self.pool.pop_front() {
+            return Ok(key);
+        }
+        self.last_resort.clone().ok_or(PrekeyError::Exhausted)
+    }
+
+    pub fn status(&self) -> PrekeyPoolStatus {
+        PrekeyPoolStatus {
+            remaining: self.pool.len(),
+            last_resort_id: self.last_resort.as_ref().map(|k| k.id),
+            replenish: self.pool.len() < self.low_water || self.last_resort.is_none(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "stdcrypto", derive(Zeroize, ZeroizeOnDrop))]
+struct PrekeySecret {
+    dh_priv: X25519Priv,
+    pq_priv: Vec<u8>,
+}
+
+/// The owner's private halves of its published prekeys.
+#[derive(Default)]
+pub struct PrekeySecrets {
+    one_time: BTreeMap<u32, PrekeySecret>,
+    /// Oldest first; at most `PREKEY_LAST_RESORT_RETAINED`.
+    last_resort: VecDeque<(u32, PrekeySecret)>,
+    next_id: u32,
+    next_last_resort: u32,
+}
+
+impl PrekeySecrets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn keypair(
+        provider: &dyn CryptoProvider,
+    ) -> Result<(PrekeySecret, [u8; SZ_X25519_PUB], Vec<u8>), PrekeyError> {
+        let (pq_pub, pq_priv) = provider
+            .pq_kem_keypair()
+            .ok_or(PrekeyError::MissingKeygen)?;
+        let (dh_priv, dh_pub) = provider.x25519().keypair();
+        Ok((PrekeySecret { dh_priv, pq_priv }, dh_pub.0, pq_pub))
+    }
+
+    /// Generate and sign `n` one-time prekeys for upload. Nothing is retained on error.
+    ///
+    /// At most `PREKEY_POOL_MAX` one-time secrets are kept; the oldest go first. A directory
+    /// hands keys out oldest first and holds no more than that, so a dropped secret belongs
+    /// to a key claimed by a handshake that never arrived.
+    pub fn generate_batch(
+        &mut self,
+        provider: &dyn CryptoProvider,
+        signer: &PrekeySigner,
+        n: usize,
+    ) -> Result<Vec<SignedPrekey>, PrekeyError> {
+        if n == 0 || n > PREKEY_UPLOAD_MAX {
+            return Err(PrekeyError::Invalid("batch size"));
+        }
+        let end = self
+            .next_id
+            .checked_add(n as u32)
+            .filter(|end| *end <= PREKEY_LAST_RESORT_FLAG)
+            .ok_or(PrekeyError::Invalid("one-time id space exhausted"))?;
+        let mut secrets = Vec::with_capacity(n);
+        let mut published = Vec::with_capacity(n);
+        for id in self.next_id..end {
+            let (secret, dh_pub, pq_pub) = Self::keypair(provider)?;
+            published.push(signer.sign(id, dh_pub, pq_pub)?);
+            secrets.push((id, secret));
+        }
+        self.one_time.extend(secrets);
+        while self.one_time.len() > PREKEY_POOL_MAX {
+            self.one_time.pop_first();
+        }
+        self.next_id = end;
+        Ok(published)
+    }
+
+    /// Generate a new signed last-resort prekey. The oldest retained one is dropped once more
+    /// than `PREKEY_LAST_RESORT_RETAINED` exist.
+    pub fn rotate_last_resort(
+        &mut self,
+        provider: &dyn CryptoProvider,
+        signer: &PrekeySigner,
+    ) -> Result<SignedPrekey, PrekeyError> {
+        if self.next_last_resort >= PREKEY_LAST_RESORT_FLAG {
+            return Err(PrekeyError::Invalid("last-resort id space exhausted"));
+        }
+        let id = PREKEY_LAST_RESORT_FLAG | self.next_last_resort;
+        let (secret, dh_pub, pq_pub) = Self::keypair(provider)?;
+        let published = signer.sign(id, dh_pub, pq_pub)?;
+        self.last_resort.push_back((id, secret));
+        while self.last_resort.len() > PREKEY_LAST_RESORT_RETAINED {
+            self.last_resort.pop_front();
+        }
+        self.next_last_resort += 1;
+        Ok(published)
+    }
+
+    /// Unconsumed one-time secrets held.
+    pub fn one_time_count(&self) -> usize {
+        self.one_time.len()
+    }
+
+    fn opk_id(hs1: &HandshakeInit) -> Result<Option<u32>, PrekeyError> {
+        if !hs1.opk_used {
+            return Ok(None);
+        }
+        match (hs1.opk_dh_id, hs1.opk_pq_id) {
+            (Some(dh_id), Some(pq_id)) if dh_id == pq_id => Ok(Some(dh_id)),
+            _ => Err(PrekeyError::Invalid("opk id mismatch")),
+        }
+    }
+
+    /// The OPK private keys HS1 names, in the shape `responder_process` takes them.
+    /// `(None, None)` when HS1 uses no OPK; an unknown or already consumed id is an error.
+    #[allow(clippy::type_complexity)]
+    pub fn lookup(
+        &self,
+        hs1: &HandshakeInit,
+    ) -> Result<(Option<X25519Priv>, Option<Vec<u8>>), PrekeyError> {
+        let Some(id) = Self::opk_id(hs1)? else {
+            return Ok((None, None));
+        };
+        let secret = if id & PREKEY_LAST_RESORT_FLAG == 0 {
+            self.one_time.get(&id)
+        } else {
+            self.last_resort
+                .iter()
+                .find(|(lr_id, _)| *lr_id == id)
+                .map(|(_, s)| s)
+        };
+        let secret = secret.ok_or(PrekeyError::UnknownId(id))?;
+        Ok((Some(secret.dh_priv.clone()), Some(secret.pq_priv.clone())))
+    }
+
+    /// Delete the one-time secret HS1 used, once the handshake has succeeded, so a replayed
+    /// HS1 can no longer be answered. Returns whether a secret was deleted; last-resort
+    /// secrets are kept until rotated out.
+    pub fn consume(&mut self, hs1: &HandshakeInit) -> bool {
+        match Self::opk_id(hs1) {
+            Ok(Some(id)) if id & PREKEY_LAST_RESORT_FLAG == 0 => {
+                self.one_time.remove(&id).is_some()
+            }
+            _ => false,
+        }
+    }
+
+    fn write_secret(w: &mut Writer, id: u32, secret: &PrekeySecret) {
+        w.write_u32(id);
+        w.write_bytes(&secret.dh_priv.0);
+        w.write_varbytes_u16(&secret.pq_priv);
+    }
+
+    fn read_secret(r: &mut Reader) -> Result<(u32, PrekeySecret), CodecError> {
+        let id = r.read_u32()?;
+        let dh_priv = X25519Priv(r.read_exact()?);
+        let pq_priv = r.read_varbytes_u16()?;
+        Ok((id, PrekeySecret { dh_priv, pq_priv }))
+    }
+
+    /// Bare `QSPK` layout of every retained secret and both id counters. Unauthenticated;
+    /// persist it sealed (`seal_snapshot`).
+    pub fn snapshot_bytes(&self) -> SecretBytes {
+        let mut w = Writer::new();
+        w.write_bytes(SECRETS_MAGIC);
+        w.write_u8(SECRETS_VERSION);
+        w.write_u32(self.next_id);
+        w.write_u32(self.next_last_resort);
+        // `generate_batch` keeps at most PREKEY_POOL_MAX, well inside a u16.
+        w.write_u16(self.one_time.len() as u16);
+        for (id, secret) in &self.one_time {
+            Self::write_secret(&mut w, *id, secret);
+        }
+        w.write_u8(self.last_resort.len() as u8);
+        for (id, secret) in &self.last_resort {
+            Self::write_secret(&mut w, *id, secret);
+        }
+        SecretBytes::from(w.into_vec())
+    }
+
+    /// Parse a bare `QSPK` layout. Only version 1 is accepted; ids must be in range of the
+    /// counters, one-time ids strictly ascending and last-resort ids strictly increasing, so a
+    /// restored owner can never reissue an id it has already published.
+    pub fn restore_bytes(bytes: &[u8]) -> Result<Self, PrekeyError> {
+        let bad = |_| PrekeyError::Invalid("bad prekey snapshot");
+        let mut r = Reader::new(bytes);
+        if r.read_slice(4).map_err(bad)? != SECRETS_MAGIC {
+            return Err(PrekeyError::Invalid("bad prekey snapshot"));
+        }
+        if r.read_u8().map_err(bad)? != SECRETS_VERSION {
+            return Err(PrekeyError::Invalid("unsupported prekey snapshot version"));
+        }
+        let mut out = Self {
+            next_id: r.read_u32().map_err(bad)?,
+            next_last_resort: r.read_u32().map_err(bad)?,
+            ..Self::default()
+        };
+        if out.next_id > PREKEY_LAST_RESORT_FLAG || out.next_last_resort > PREKEY_LAST_RESORT_FLAG {
+            return Err(PrekeyError::Invalid("bad prekey snapshot"));
+        }
+        let n = r.read_u16().map_err(bad)?;
+        if n as usize > PREKEY_POOL_MAX {
+            return Err(PrekeyError::Invalid("bad prekey snapshot"));
+        }
+        let mut prev = None;
+        for _ in 0..n {
+            let (id, secret) = Self::read_secret(&mut r).map_err(bad)?;
+            if id >= out.next_id || prev.is_some_and(|p| id <= p) {
+                return Err(PrekeyError::Invalid("bad prekey snapshot"));
+            }
+            prev = Some(id);
+            out.one_time.insert(id, secret);
+        }
+        let m = r.read_u8().map_err(bad)?;
+        if m as usize > PREKEY_LAST_RESORT_RETAINED {
+            return Err(PrekeyError::Invalid("bad prekey snapshot"));
+        }
+        let mut prev = None;
+        for _ in 0..m {
+            let (id, secret) = Self::read_secret(&mut r).map_err(bad)?;
+            let seq = id & !PREKEY_LAST_RESORT_FLAG;
+            if id & PREKEY_LAST_RESORT_FLAG == 0
+                || seq >= out.next_last_resort
+                || prev.is_some_and(|p| seq <= p)
+            {
+                return Err(PrekeyError::Invalid("bad prekey snapshot"));
+            }
+            prev = Some(seq);
+            out.last_resort.push_back((id, secret));
+        }
+        r.finish().map_err(bad)?;
+        Ok(out)
+    }
+}
+
+#[cfg(all(test, feature = "stdcrypto"))]
+mod tests {
+    use super::*;
+    use crate::crypto::provider::StdProvider;
+    use crate::crypto::stdcrypto::{StdCrypto, StdEd25519};
+    use crate::crypto::traits::{Aead, Kmac, PqKem768, X25519Dh};
+    use crate::kt::{KtError, KtVerification, KtVerifier};
+    use crate::qsp::{
+        initiator_build, initiator_build_with_prekey, initiator_finalize, responder_process,
+        HandshakeDeps, HandshakeError,
+    };
+    use crate::snapshot::SnapshotError;
+
+    const IK_EC_PRIV: [u8; 32] = [7u8; 32];
+
+    fn ik_ec_pub() -> [u8; 32] {
+        ed25519_dalek::SigningKey::from_bytes(&IK_EC_PRIV)
+            .verifying_key()
+            .to_bytes()
+    }
+
+    /// Stand-in ML-KEM: the public key starts with the secret, and the shared secret is a
+    /// function of it, so encap/decap agree without the pqkem feature.
+    struct SeedKem;
+    impl PqKem768 for SeedKem {
+        fn encap(&self, pubk: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+            Ok((
+                vec![0u8; SZ_MLKEM768_CT],
+                StdCrypto.sha512(&pubk[..32])[..32].to_vec(),
+            ))
+        }
+        fn decap(&self, privk: &[u8], _ct: &[u8]) -> Result<Vec<u8>, CryptoError> {
+            Ok(StdCrypto.sha512(privk)[..32].to_vec())
+        }
+    }
+
+    fn seed_kem_keypair() -> (Vec<u8>, Vec<u8>) {
+        let (seed, _) = StdCrypto.keypair();
+        let mut pubk = seed.0.to_vec();
+        pubk.resize(SZ_MLKEM768_PUB, 0);
+        (pubk, seed.0.to_vec())
+    }
+
+    /// A's PQ_RCV keypair under `SeedKem`.
+    fn pq_rcv_a() -> (Vec<u8>, Vec<u8>) {
+        let mut pubk = vec![0x5Au8; 32];
+        pubk.resize(SZ_MLKEM768_PUB, 0);
+        (pubk, vec![0x5Au8; 32])
+    }
+
+    /// ML-DSA is not built offline; Ed25519 carries the signature checks in these tests.
+    struct AcceptPqSig;
+    impl PqSigMldsa65 for AcceptPqSig {
+        fn sign(&self, _privk: &[u8], _msg: &[u8]) -> Result<Vec<u8>, CryptoError> {
+            Ok(vec![0u8; SZ_MLDSA65_SIG])
+        }
+        fn verify(&self, _pubk: &[u8], _msg: &[u8], _sig: &[u8]) -> Result<bool, CryptoError> {
+            Ok(true)
+        }
+    }
+
+    struct TestProvider(StdProvider);
+    impl CryptoProvider for TestProvider {
+        fn name(&self) -> &'static str {
+            "prekey-test"
+        }
+        fn hash(&self) -> &dyn Hash {
+            self.0.hash()
+        }
+        fn kmac(&self) -> &dyn Kmac {
+            self.0.kmac()
+        }
+        fn aead(&self) -> &dyn Aead {
+            self.0.aead()
+        }
+        fn x25519(&self) -> &dyn X25519Dh {
+            self.0.x25519()
+        }
+        fn ed25519(&self) -> &dyn SigEd25519 {
+            self.0.ed25519()
+        }
+        fn pq_kem_keypair(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+            Some(seed_kem_keypair())
+        }
+    }
+
+    struct AllowKt;
+    impl KtVerifier for AllowKt {
+        fn verify_bundle(
+            &self,
+            _bundle: &PrekeyBundle,
+            _ed25519: &dyn SigEd25519,
+            _pq_sig: &dyn PqSigMldsa65,
+        ) -> Result<KtVerification, KtError> {
+            Ok(KtVerification::Verified)
+        }
+
+        fn verify_responder_binding(
+            &self,
+            _hs1: &HandshakeInit,
+            _initiator_bundle: Option<&PrekeyBundle>,
+            _ed25519: &dyn SigEd25519,
+            _pq_sig: &dyn PqSigMldsa65,
+        ) -> Result<KtVerification, KtError> {
+            Ok(KtVerification::Verified)
+        }
+    }
+
+    fn signer(pq_sig: &AcceptPqSig) -> PrekeySigner<'_> {
+        PrekeySigner {
+            hash: &StdCrypto,
+            ed25519: &StdEd25519,
+            pq_sig,
+            user_id: b"bob",
+            device_id: 3,
+            ik_sig_ec_priv: &IK_EC_PRIV,
+            ik_sig_pq_priv: &[0u8; 1],
+        }
+    }
+
+    /// B's base bundle, with B's SPK secrets.
+    fn bundle_b() -> (PrekeyBundle, X25519Priv, Vec<u8>) {
+        let (spk_dh_priv, spk_dh_pub) = StdCrypto.keypair();
+        let (spk_pq_pub, spk_pq_priv) = seed_kem_keypair();
+        let bundle = PrekeyBundle {
+            user_id: b"bob".to_vec(),
+            device_id: 3,
+            valid_from: 1,
+            valid_to: 2,
+            ik_sig_ec_pub: ik_ec_pub(),
+            ik_sig_pq_pub: vec![0u8; SZ_MLDSA65_PUB],
+            spk_dh_pub: spk_dh_pub.0,
+            spk_pq_pub,
+            pq_rcv_id: 9,
+            pq_rcv_pub: vec![0u8; SZ_MLKEM768_PUB],
+            opk_dh: None,
+            opk_pq: None,
+            sig_ec: vec![0u8; SZ_ED25519_SIG],
+            sig_pq: vec![0u8; SZ_MLDSA65_SIG],
+            kt_log_id: [0u8; 32],
+            kt_sth: vec![],
+            kt_inclusion_proof: vec![],
+            kt_consistency_proof: vec![],
+        };
+        (bundle, spk_dh_priv, spk_pq_priv)
+    }
+
+    fn deps<'a>(
+        pq_kem: &'a SeedKem,
+        pq_sig: &'a AcceptPqSig,
+        kt: &'a AllowKt,
+    ) -> HandshakeDeps<'a> {
+        HandshakeDeps {
+            hash: &StdCrypto,
+            kmac: &StdCrypto,
+            dh: &StdCrypto,
+            aead: &StdCrypto,
+            ed25519: &StdEd25519,
+            pq_kem,
+            pq_sig,
+            kt,
+        }
+    }
+
+    fn build_with(
+        deps: &HandshakeDeps,
+        bundle: &PrekeyBundle,
+        prekey: &SignedPrekey,
+    ) -> Result<(HandshakeInit, crate::qsp::InitiatorState), HandshakeError> {
+        initiator_build_with_prekey(
+            deps,
+            bundle,
+            prekey,
+            bundle.user_id.clone(),
+            bundle.device_id,
+            [0x42; SZ_SESSION_ID],
+            ik_ec_pub(),
+            IK_EC_PRIV.to_vec(),
+            vec![0u8; SZ_MLDSA65_PUB],
+            vec![0u8; 1],
+            5,
+            pq_rcv_a().0,
+        )
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn respond(
+        deps: &HandshakeDeps,
+        hs1: &HandshakeInit,
+        spk: &(X25519Priv, Vec<u8>),
+        opk: (Option<X25519Priv>, Option<Vec<u8>>),
+    ) -> Result<(crate::qsp::HandshakeResp, crate::qsp::SessionState), HandshakeError> {
+        responder_process(
+            deps,
+            hs1,
+            None,
+            ik_ec_pub(),
+            IK_EC_PRIV.to_vec(),
+            vec![0u8; SZ_MLDSA65_PUB],
+            vec![0u8; 1],
+            spk.0.clone(),
+            spk.1.clone(),
+            opk.0,
+            opk.1,
+            StdCrypto.keypair(),
+            7,
+            vec![0u8; SZ_MLKEM768_PUB],
+            vec![0u8; 32],
+        )
+    }
+
+    fn one_time(ids: std::ops::Range<u32>) -> Vec<SignedPrekey> {
+        ids.map(|id| SignedPrekey {
+            id,
+            dh_pub: [0u8; SZ_X25519_PUB],
+            pq_pub: vec![0u8; SZ_MLKEM768_PUB],
+            sig_ec: vec![0u8; SZ_ED25519_SIG],
+            sig_pq: vec![0u8; SZ_MLDSA65_SIG],
+        })
+        .collect()
+    }
+
+    #[test]
+    fn signed_prekey_round_trips_and_verifies_against_its_bundle() {
+        let provider = TestProvider(StdProvider::new());
+        let pq_sig = AcceptPqSig;
+        let mut secrets = PrekeySecrets::new();
+        let batch = secrets
+            .generate_batch(&provider, &signer(&pq_sig), 3)
+            .unwrap();
+        assert_eq!(
+            batch.iter().map(|k| k.id).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(secrets.one_time_count(), 3);
+
+        let (bundle, _, _) = bundle_b();
+        let key = SignedPrekey::decode(&batch[1].encode().unwrap()).unwrap();
+        assert_eq!(key, batch[1]);
+        key.verify(&StdCrypto, &StdEd25519, &pq_sig, &bundle)
+            .unwrap();
+
+        let mut other_device = bundle.clone();
+        other_device.device_id = 4;
+        let mut swapped = key.clone();
+        swapped.dh_pub = batch[2].dh_pub;
+        for (k, b) in [(&key, &other_device), (&swapped, &bundle)] {
+            assert!(matches!(
+                k.verify(&StdCrypto, &StdEd25519, &pq_sig, b),
+                Err(PrekeyError::BadSignature)
+            ));
+        }
+    }
+
+    #[test]
+    fn directory_hands_out_each_one_time_key_once_then_last_resort() {
+        let mut dir = PrekeyDirectory::new(2);
+        assert_eq!(
+            dir.claim().err().map(|e| e.to_string()),
+            Some(PrekeyError::Exhausted.to_string())
+        );
+        assert_eq!(dir.upload(one_time(0..3)).unwrap(), 3);
+        let mut lr = one_time(0..1).remove(0);
+        lr.id = PREKEY_LAST_RESORT_FLAG;
+        dir.publish_last_resort(lr.clone()).unwrap();
+        assert_eq!(
+            dir.status(),
+            PrekeyPoolStatus {
+                remaining: 3,
+                last_resort_id: Some(PREKEY_LAST_RESORT_FLAG),
+                replenish: false,
+            }
+        );
+
+        let claimed: Vec<u32> = (0..3).map(|_| dir.claim().unwrap().id).collect();
+        assert_eq!(claimed, vec![0, 1, 2]);
+        assert!(dir.status().replenish);
+        assert_eq!(dir.claim().unwrap(), lr);
+        assert_eq!(dir.claim().unwrap(), lr);
+        assert_eq!(dir.status().remaining, 0);
+    }
+
+    #[test]
+    fn directory_rejects_reissued_or_malformed_uploads_atomically() {
+        let mut dir = PrekeyDirectory::default();
+        dir.upload(one_time(0..2)).unwrap();
+        dir.claim().unwrap();
+
+        let mut last_resort_in_batch = one_time(2..4);
+        last_resort_in_batch[1].id |= PREKEY_LAST_RESORT_FLAG;
+        let mut unordered = one_time(2..4);
+        unordered.swap(0, 1);
+        for (batch, why) in [
+            (one_time(1..3), "prekey id not fresh"),
+            (one_time(0..1), "prekey id not fresh"),
+            (unordered, "prekey id not fresh"),
+            (last_resort_in_batch, "last-resort id in one-time batch"),
+            (vec![], "empty batch"),
+            (
+                one_time(2..(3 + PREKEY_UPLOAD_MAX as u32)),
+                "batch too large",
+            ),
+        ] {
+            match dir.upload(batch) {
+                Err(PrekeyError::Rejected(got)) => assert_eq!(got, why),
+                other => panic!("{why}: unexpected {other:?}"),
+            }
+        }
+        assert_eq!(dir.status().remaining, 1);
+
+        let mut id = 2;
+        while dir.status().remaining + PREKEY_UPLOAD_MAX <= PREKEY_POOL_MAX {
+            dir.upload(one_time(id..id + PREKEY_UPLOAD_MAX as u32))
+                .unwrap();
+            id += PREKEY_UPLOAD_MAX as u32;
+        }
+        let room = (PREKEY_POOL_MAX - dir.status().remaining) as u32;
+        assert!(matches!(
+            dir.upload(one_time(id..id + room + 1)),
+            Err(PrekeyError::Rejected("pool full"))
+        ));
+
+        let mut lr = one_time(0..1).remove(0);
+        lr.id = PREKEY_LAST_RESORT_FLAG | 5;
+        dir.publish_last_resort(lr.clone()).unwrap();
+        lr.id = PREKEY_LAST_RESORT_FLAG | 4;
+        assert!(dir.publish_last_resort(lr.clone()).is_err());
+        lr.id = 6;
+        assert!(dir.publish_last_resort(lr).is_err());
+    }
+
+    #[test]
+    fn claimed_one_time_prekey_completes_handshake_and_is_deleted_after_use() {
+        let provider = TestProvider(StdProvider::new());
+        let (pq_kem, pq_sig, kt) = (SeedKem, AcceptPqSig, AllowKt);
+        let deps = deps(&pq_kem, &pq_sig, &kt);
+        let (bundle, spk_dh_priv, spk_pq_priv) = bundle_b();
+        let spk = (spk_dh_priv, spk_pq_priv);
+
+        let mut secrets = PrekeySecrets::new();
+        let mut dir = PrekeyDirectory::default();
+        dir.upload(
+            secrets
+                .generate_batch(&provider, &signer(&pq_sig), 2)
+                .unwrap(),
+        )
+        .unwrap();
+
+        let prekey = dir.claim().unwrap();
+        let (hs1, init) = build_with(&deps, &bundle, &prekey).unwrap();
+        assert!(hs1.opk_used);
+        assert_eq!(hs1.opk_dh_id, Some(prekey.id));
+
+        let (hs2, st_b) = respond(&deps, &hs1, &spk, secrets.lookup(&hs1).unwrap()).unwrap();
+        assert!(secrets.consume(&hs1));
+        let st_a =
+            initiator_finalize(&deps, init, &hs2, StdCrypto.keypair(), pq_rcv_a().1).unwrap();
+        assert_eq!(st_a.rk, st_b.rk);
+
+        // The secret is gone: a replayed HS1 cannot be answered, and the next claim is fresh.
+        assert!(matches!(
+            secrets.lookup(&hs1),
+            Err(PrekeyError::UnknownId(id)) if id == prekey.id
+        ));
+        assert!(!secrets.consume(&hs1));
+        assert_ne!(dir.claim().unwrap().id, prekey.id);
+        assert_eq!(secrets.one_time_count(), 1);
+    }
+
+    #[test]
+    fn last_resort_prekey_is_reusable_and_survives_one_rotation() {
+        let provider = TestProvider(StdProvider::new());
+        let (pq_kem, pq_sig, kt) = (SeedKem, AcceptPqSig, AllowKt);
+        let deps = deps(&pq_kem, &pq_sig, &kt);
+        let (bundle, spk_dh_priv, spk_pq_priv) = bundle_b();
+        let spk = (spk_dh_priv, spk_pq_priv);
+
+        let mut secrets = PrekeySecrets::new();
+        let mut dir = PrekeyDirectory::default();
+        let lr0 = secrets
+            .rotate_last_resort(&provider, &signer(&pq_sig))
+            .unwrap();
+        assert!(lr0.is_last_resort());
+        dir.publish_last_resort(lr0.clone()).unwrap();
+
+        let claimed = dir.claim().unwrap();
+        assert_eq!(claimed, lr0);
+        let (hs1, _) = build_with(&deps, &bundle, &claimed).unwrap();
+        assert!(respond(&deps, &hs1, &spk, secrets.lookup(&hs1).unwrap()).is_ok());
+        assert!(!secrets.consume(&hs1));
+
+        let lr1 = secrets
+            .rotate_last_resort(&provider, &signer(&pq_sig))
+            .unwrap();
+        dir.publish_last_resort(lr1).unwrap();
+        assert!(secrets.lookup(&hs1).is_ok());
+        secrets
+            .rotate_last_resort(&provider, &signer(&pq_sig))
+            .unwrap();
+        assert!(matches!(
+            secrets.lookup(&hs1),
+            Err(PrekeyError::UnknownId(id)) if id == lr0.id
+        ));
+    }
+
+    #[test]
+    fn restored_secrets_answer_outstanding_prekeys_but_not_consumed_ones() {
+        let provider = TestProvider(StdProvider::new());
+        let (pq_kem, pq_sig, kt) = (SeedKem, AcceptPqSig, AllowKt);
+        let deps = deps(&pq_kem, &pq_sig, &kt);
+        let (bundle, spk_dh_priv, spk_pq_priv) = bundle_b();
+        let spk = (spk_dh_priv, spk_pq_priv);
+
+        let mut secrets = PrekeySecrets::new();
+        let mut batch = secrets
+            .generate_batch(&provider, &signer(&pq_sig), 2)
+            .unwrap();
+        let lr = secrets
+            .rotate_last_resort(&provider, &signer(&pq_sig))
+            .unwrap();
+        let (used, _) = build_with(&deps, &bundle, &batch.remove(0)).unwrap();
+        assert!(secrets.consume(&used));
+
+        let mut restored = PrekeySecrets::restore_bytes(&secrets.snapshot_bytes()).unwrap();
+        assert_eq!(*restored.snapshot_bytes(), *secrets.snapshot_bytes());
+        assert_eq!(restored.one_time_count(), 1);
+        assert!(matches!(
+            restored.lookup(&used),
+            Err(PrekeyError::UnknownId(_))
+        ));
+        for prekey in [batch.remove(0), lr] {
+            let (hs1, _) = build_with(&deps, &bundle, &prekey).unwrap();
+            assert!(respond(&deps, &hs1, &spk, restored.lookup(&hs1).unwrap()).is_ok());
+        }
+
+        // The counters survive, so a restored owner never reissues a published id.
+        let next = restored
+            .generate_batch(&provider, &signer(&pq_sig), 1)
+            .unwrap();
+        assert_eq!(next[0].id, 2);
+    }
+
+    #[test]
+    fn owner_keeps_at_most_a_full_pool_of_one_time_secrets() {
+        let provider = TestProvider(StdProvider::new());
+        let pq_sig = AcceptPqSig;
+        let mut secrets = PrekeySecrets::new();
+        for _ in 0..=PREKEY_POOL_MAX / PREKEY_UPLOAD_MAX {
+            secrets
+                .generate_batch(&provider, &signer(&pq_sig), PREKEY_UPLOAD_MAX)
+                .unwrap();
+        }
+        // The first batch went: the newest PREKEY_POOL_MAX remain, and the snapshot holds them.
+        assert_eq!(secrets.one_time_count(), PREKEY_POOL_MAX);
+        assert_eq!(
+            secrets.one_time.keys().next(),
+            Some(&(PREKEY_UPLOAD_MAX as u32))
+        );
+        let bare = secrets.snapshot_bytes();
+        let restored = PrekeySecrets::restore_bytes(&bare).unwrap();
+        assert_eq!(restored.one_time_count(), PREKEY_POOL_MAX);
+
+        // A count past the cap is refused before any secret is read.
+        let mut oversized = bare.to_vec();
+        oversized[13..15].copy_from_slice(&(PREKEY_POOL_MAX as u16 + 1).to_be_bytes());
+        assert!(matches!(
+            PrekeySecrets::restore_bytes(&oversized),
+            Err(PrekeyError::Invalid("bad prekey snapshot"))
+        ));
+    }
+
+    #[test]
+    fn prekey_snapshot_rejects_bad_layouts_and_rollback() {
+        let provider = TestProvider(StdProvider::new());
+        let pq_sig = AcceptPqSig;
+        let mut secrets = PrekeySecrets::new();
+        secrets
+            .generate_batch(&provider, &signer(&pq_sig), 2)
+            .unwrap();
+        secrets
+            .rotate_last_resort(&provider, &signer(&pq_sig))
+            .unwrap();
+        let bare = secrets.snapshot_bytes();
+
+        for len in [0, 4, 5, bare.len() - 1] {
+            assert!(PrekeySecrets::restore_bytes(&bare[..len]).is_err());
+        }
+        let mut trailing = bare.to_vec();
+        trailing.push(0);
+        assert!(PrekeySecrets::restore_bytes(&trailing).is_err());
+        let mut version = bare.to_vec();
+        version[4] = 2;
+        assert!(matches!(
+            PrekeySecrets::restore_bytes(&version),
+            Err(PrekeyError::Invalid("unsupported prekey snapshot version"))
+        ));
+        // next_id rewound below an outstanding one-time id.
+        let mut rewound = bare.to_vec();
+        rewound[5..9].copy_from_slice(&1u32.to_be_bytes());
+        assert!(PrekeySecrets::restore_bytes(&rewound).is_err());
+
+        let key = [0x5c; 32];
+        let c = StdCrypto;
+        let old = secrets.seal_snapshot(&c, &c, &key, 4).expect("seal");
+        secrets
+            .generate_batch(&provider, &signer(&pq_sig), 1)
+            .unwrap();
+        let new = secrets.seal_snapshot(&c, &c, &key, 5).expect("seal");
+        let (opened, epoch) = PrekeySecrets::open_snapshot(&c, &c, &key, 5, &new).unwrap();
+        assert_eq!(epoch, 5);
+        assert_eq!(opened.one_time_count(), 3);
+        assert_eq!(
+            PrekeySecrets::open_snapshot(&c, &c, &key, 5, &old).err(),
+            Some(SnapshotError::Rollback {
+                epoch: 4,
+                committed: 5
+            })
+        );
+        assert_eq!(
+            crate::suite2::state::Suite2SessionState::open_snapshot(&c, &c, &key, 0, &new).err(),
+            Some(SnapshotError::KindMismatch)
+        );
+    }
+
+    #[test]
+    fn initiator_rejects_forged_or_doubled_prekeys() {
+        let provider = TestProvider(StdProvider::new());
+        let (pq_kem, pq_sig, kt) = (SeedKem, AcceptPqSig, AllowKt);
+        let deps = deps(&pq_kem, &pq_sig, &kt);
+        let (bundle, _, _) = bundle_b();
+
+        let mut secrets = PrekeySecrets::new();
+        let prekey = secrets
+            .generate_batch(&provider, &signer(&pq_sig), 1)
+            .unwrap()
+            .remove(0);
+
+        // A service substituting its own key cannot produce B's signature over it.
+        let mut forged = prekey.clone();
+        forged.pq_pub = seed_kem_keypair().0;
+        assert!(matches!(
+            build_with(&deps, &bundle, &forged),
+            Err(HandshakeError::Prekey(PrekeyError::BadSignature))
+        ));
+
+        let with_opk = prekey.attach_to(&bundle).unwrap();
+        assert!(matches!(
+            build_with(&deps, &with_opk, &prekey),
+            Err(HandshakeError::Prekey(PrekeyError::Invalid(_)))
+        ));
+
+        // Without a claim the handshake is unchanged and uses no OPK.
+        let (hs1, _) = initiator_build(
+            &deps,
+            &bundle,
+            bundle.user_id.clone(),
+            bundle.device_id,
+            [0x42; SZ_SESSION_ID],
+            ik_ec_pub(),
+            IK_EC_PRIV.to_vec(),
+            vec![0u8; SZ_MLDSA65_PUB],
+            vec![0u8; 1],
+            5,
+            pq_rcv_a().0,
+        )
+        .unwrap();
+        assert!(!hs1.opk_used);
+        assert!(matches!(secrets.lookup(&hs1), Ok((None, None))));
+    }
+}
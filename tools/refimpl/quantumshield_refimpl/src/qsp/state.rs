@@ -167,7 +167,8 @@ impl SessionState {
 
     /// Serialize the full session state into an opaque, versioned byte blob.
     ///
-    /// This is intended for Phase 4D durability testing only.
+    /// This is the bare layout (Phase 4D durability testing); persist through
+    /// `seal_snapshot` (`crate::snapshot`), which authenticates and versions it.
     pub fn snapshot_bytes(&self) -> Vec<u8> {
         fn push_u8(out: &mut Vec<u8>, v: u8) {
             out.push(v);
@@ -1,64 +1,41 @@
 use super::constants::*;
-use crate::codec::{CodecError, Reader, Writer};
-
-#[derive(Debug, Clone)]
-pub struct PrekeyBundle {
-    pub user_id: Vec<u8>, // varbytes<u16>
-    pub device_id: u32,
-    pub valid_from: u32,
-    pub valid_to: u32,
-    pub ik_sig_ec_pub: [u8; SZ_ED25519_PUB],
-    pub ik_sig_pq_pub: Vec<u8>, // SZ_MLDSA65_PUB
-    pub spk_dh_pub: [u8; SZ_X25519_PUB],
-    pub spk_pq_pub: Vec<u8>, // SZ_MLKEM768_PUB
-    pub pq_rcv_id: u32,
-    pub pq_rcv_pub: Vec<u8>, // SZ_MLKEM768_PUB
-    pub opk_dh: Option<(u32, [u8; SZ_X25519_PUB])>,
-    pub opk_pq: Option<(u32, Vec<u8>)>, // id + SZ_MLKEM768_PUB
-    pub sig_ec: Vec<u8>,                // 64
-    pub sig_pq: Vec<u8>,                // 3309
-    pub kt_log_id: [u8; 32],
-    pub kt_sth: Vec<u8>,
-    pub kt_inclusion_proof: Vec<u8>,
-    pub kt_consistency_proof: Vec<u8>,
-}
+use crate::codec::{
+    canonical_struct, Bool16, CanonicalCodec, CodecError, FieldCodec, Fixed, Opt16, Raw, Reader,
+    VarU16, VarU32, Writer, U16, U32,
+};
 
-impl PrekeyBundle {
-    /// Canonical wire encoding (QSP §4.3).
+canonical_struct! {
+    /// Canonical wire encoding (QSP §4.3) is the field order below.
     ///
     /// `QSP-4.3.2-KT1` bundle signatures do not use this carriage directly; see
     /// `bundle_leaf_data()` and `bundle_tbs()` for the canonical KT profile bytes.
-    pub fn encode(&self) -> Vec<u8> {
-        let mut w = Writer::new();
-        w.write_varbytes_u16(&self.user_id);
-        w.write_u32(self.device_id);
-        w.write_u32(self.valid_from);
-        w.write_u32(self.valid_to);
-        w.write_bytes(&self.ik_sig_ec_pub);
-        w.write_bytes(&self.ik_sig_pq_pub);
-        w.write_bytes(&self.spk_dh_pub);
-        w.write_bytes(&self.spk_pq_pub);
-        w.write_u32(self.pq_rcv_id);
-        w.write_bytes(&self.pq_rcv_pub);
-
-        w.write_u16(self.opk_dh.is_some() as u16);
-        if let Some((id, pubk)) = &self.opk_dh {
-            w.write_u32(*id);
-            w.write_bytes(pubk);
-        }
-        w.write_u16(self.opk_pq.is_some() as u16);
-        if let Some((id, pubk)) = &self.opk_pq {
-            w.write_u32(*id);
-            w.write_bytes(pubk);
-        }
+    #[derive(Debug, Clone)]
+    pub struct PrekeyBundle {
+        pub user_id: Vec<u8> as VarU16,
+        pub device_id: u32 as U32,
+        pub valid_from: u32 as U32,
+        pub valid_to: u32 as U32,
+        pub ik_sig_ec_pub: [u8; SZ_ED25519_PUB] as Fixed,
+        pub ik_sig_pq_pub: Vec<u8> as Raw<SZ_MLDSA65_PUB>,
+        pub spk_dh_pub: [u8; SZ_X25519_PUB] as Fixed,
+        pub spk_pq_pub: Vec<u8> as Raw<SZ_MLKEM768_PUB>,
+        pub pq_rcv_id: u32 as U32,
+        pub pq_rcv_pub: Vec<u8> as Raw<SZ_MLKEM768_PUB>,
+        pub opk_dh: Option<(u32, [u8; SZ_X25519_PUB])> as Opt16<(U32, Fixed)>,
+        pub opk_pq: Option<(u32, Vec<u8>)> as Opt16<(U32, Raw<SZ_MLKEM768_PUB>)>,
+        pub sig_ec: Vec<u8> as Raw<SZ_ED25519_SIG>,
+        pub sig_pq: Vec<u8> as Raw<SZ_MLDSA65_SIG>,
+        pub kt_log_id: [u8; 32] as Fixed,
+        pub kt_sth: Vec<u8> as VarU16,
+        pub kt_inclusion_proof: Vec<u8> as VarU16,
+        pub kt_consistency_proof: Vec<u8> as VarU16,
+    }
+}
 
-        w.write_bytes(&self.sig_ec);
-        w.write_bytes(&self.sig_pq);
-        w.write_bytes(&self.kt_log_id);
-        w.write_varbytes_u16(&self.kt_sth);
-        w.write_varbytes_u16(&self.kt_inclusion_proof);
-        w.write_varbytes_u16(&self.kt_consistency_proof);
-        w.into_vec()
+impl PrekeyBundle {
+    /// Canonical wire encoding (QSP §4.3). Fails if a key or signature is not its fixed size.
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        self.encode_canonical()
     }
 
     pub fn kt_disabled_shape(&self) -> bool {
@@ -110,43 +87,33 @@ impl PrekeyBundle {
         w.into_vec()
     }
 
-    pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
+    /// Parse canonical `BundleLeafData` (DOC-CAN-008 §3.3), as served by a KT log.
+    ///
+    /// Leaf data carries no signatures or KT evidence, so those fields come back empty.
+    /// Key lengths are taken from the `varbytes_u32` prefixes as logged.
+    pub fn decode_bundle_leaf_data(buf: &[u8]) -> Result<Self, CodecError> {
         let mut r = Reader::new(buf);
-        let user_id = r.read_varbytes_u16()?;
+        let user_id = r.read_varbytes_u32()?;
         let device_id = r.read_u32()?;
         let valid_from = r.read_u32()?;
         let valid_to = r.read_u32()?;
         let ik_sig_ec_pub = r.read_exact::<SZ_ED25519_PUB>()?;
-        let ik_sig_pq_pub = r.read_bytes(SZ_MLDSA65_PUB)?;
+        let ik_sig_pq_pub = r.read_varbytes_u32()?;
         let spk_dh_pub = r.read_exact::<SZ_X25519_PUB>()?;
-        let spk_pq_pub = r.read_bytes(SZ_MLKEM768_PUB)?;
+        let spk_pq_pub = r.read_varbytes_u32()?;
         let pq_rcv_id = r.read_u32()?;
-        let pq_rcv_pub = r.read_bytes(SZ_MLKEM768_PUB)?;
-
-        let opk_dh_present = r.read_u16()? != 0;
-        let opk_dh = if opk_dh_present {
-            let id = r.read_u32()?;
-            let pubk = r.read_exact::<SZ_X25519_PUB>()?;
-            Some((id, pubk))
-        } else {
-            None
+        let pq_rcv_pub = r.read_varbytes_u32()?;
+        let opk_dh = match r.read_u16()? {
+            0 => None,
+            1 => Some((r.read_u32()?, r.read_exact::<SZ_X25519_PUB>()?)),
+            _ => return Err(CodecError::Invalid("opk_present")),
         };
-
-        let opk_pq_present = r.read_u16()? != 0;
-        let opk_pq = if opk_pq_present {
-            let id = r.read_u32()?;
-            let pubk = r.read_bytes(SZ_MLKEM768_PUB)?;
-            Some((id, pubk))
-        } else {
-            None
+        let opk_pq = match r.read_u16()? {
+            0 => None,
+            1 => Some((r.read_u32()?, r.read_varbytes_u32()?)),
+            _ => return Err(CodecError::Invalid("opk_present")),
         };
-
-        let sig_ec = r.read_bytes(SZ_ED25519_SIG)?;
-        let sig_pq = r.read_bytes(SZ_MLDSA65_SIG)?;
         let kt_log_id = r.read_exact::<32>()?;
-        let kt_sth = r.read_varbytes_u16()?;
-        let kt_inclusion_proof = r.read_varbytes_u16()?;
-        let kt_consistency_proof = r.read_varbytes_u16()?;
         r.finish()?;
 
         Ok(Self {
@@ -162,202 +129,116 @@ impl PrekeyBundle {
             pq_rcv_pub,
             opk_dh,
             opk_pq,
-            sig_ec,
-            sig_pq,
+            sig_ec: Vec::new(),
+            sig_pq: Vec::new(),
             kt_log_id,
-            kt_sth,
-            kt_inclusion_proof,
-            kt_consistency_proof,
+            kt_sth: Vec::new(),
+            kt_inclusion_proof: Vec::new(),
+            kt_consistency_proof: Vec::new(),
         })
     }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
+        Self::decode_canonical(buf)
+    }
+}
+
+fn is_zero(v: &[u8]) -> bool {
+    v.iter().all(|b| *b == 0)
 }
 
-#[cfg_attr(test, derive(PartialEq))]
-#[derive(Debug, Clone)]
-pub struct HandshakeInit {
-    pub protocol_version: u16,
-    pub suite_id: u16,
-    pub session_id: [u8; SZ_SESSION_ID],
-    pub user_id_b: Vec<u8>,
-    pub device_id_b: u32,
-    pub ek_dh_a_pub: [u8; SZ_X25519_PUB],
-    pub ct1: Vec<u8>, // 1088
-    pub opk_used: bool,
-    pub ct2: Option<Vec<u8>>, // 1088
-    pub opk_dh_id: Option<u32>,
-    pub opk_pq_id: Option<u32>,
-    pub pq_rcv_a_id: u32,
-    pub pq_rcv_a_pub: Vec<u8>, // 1184
-    pub ik_sig_ec_a_pub: [u8; SZ_ED25519_PUB],
-    pub ik_sig_pq_a_pub: Vec<u8>, // 1952
-    pub sig_ec_a: Vec<u8>,        // 64
-    pub sig_pq_a: Vec<u8>,        // 3309
+canonical_struct! {
+    /// `suite_id` selects the handshake mode: `QSP_SUITE_ID` (signed) or `QSP_SUITE_ID_DENIABLE`
+    /// (implicitly authenticated, signature fields all-zero).
+    #[cfg_attr(test, derive(PartialEq))]
+    #[derive(Debug, Clone)]
+    pub struct HandshakeInit {
+        pub protocol_version: u16 as U16,
+        // Both header fields are read before either is judged.
+        #[check(protocol_version == QSP_PROTOCOL_VERSION, "protocol_version")]
+        #[check(suite_id == QSP_SUITE_ID || suite_id == QSP_SUITE_ID_DENIABLE, "suite_id")]
+        pub suite_id: u16 as U16,
+        pub session_id: [u8; SZ_SESSION_ID] as Fixed,
+        pub user_id_b: Vec<u8> as VarU16,
+        pub device_id_b: u32 as U32,
+        pub ek_dh_a_pub: [u8; SZ_X25519_PUB] as Fixed,
+        pub ct1: Vec<u8> as Raw<SZ_MLKEM768_CT>,
+        pub opk_used: bool as Bool16,
+        #[when(opk_used)]
+        pub ct2: Option<Vec<u8>> as Raw<SZ_MLKEM768_CT>,
+        #[when(opk_used)]
+        pub opk_dh_id: Option<u32> as U32,
+        #[when(opk_used)]
+        pub opk_pq_id: Option<u32> as U32,
+        pub pq_rcv_a_id: u32 as U32,
+        pub pq_rcv_a_pub: Vec<u8> as Raw<SZ_MLKEM768_PUB>,
+        pub ik_sig_ec_a_pub: [u8; SZ_ED25519_PUB] as Fixed,
+        pub ik_sig_pq_a_pub: Vec<u8> as Raw<SZ_MLDSA65_PUB>,
+        // A deniable HS1 carries no signatures: both fields MUST be all-zero.
+        #[check(suite_id == QSP_SUITE_ID || is_zero(&sig_ec_a), "sig_ec_a")]
+        pub sig_ec_a: Vec<u8> as Raw<SZ_ED25519_SIG>,
+        #[check(suite_id == QSP_SUITE_ID || is_zero(&sig_pq_a), "sig_pq_a")]
+        pub sig_pq_a: Vec<u8> as Raw<SZ_MLDSA65_SIG>,
+    }
 }
 
 impl HandshakeInit {
-    pub fn encode(&self) -> Vec<u8> {
-        let mut w = Writer::new();
-        w.write_u16(self.protocol_version);
-        w.write_u16(self.suite_id);
-        w.write_bytes(&self.session_id);
-        w.write_varbytes_u16(&self.user_id_b);
-        w.write_u32(self.device_id_b);
-        w.write_bytes(&self.ek_dh_a_pub);
-        w.write_bytes(&self.ct1);
-        w.write_u16(self.opk_used as u16);
-        if self.opk_used {
-            let (ct2, opk_dh_id, opk_pq_id) =
-                match (self.ct2.as_ref(), self.opk_dh_id, self.opk_pq_id) {
-                    (Some(ct2), Some(opk_dh_id), Some(opk_pq_id)) => (ct2, opk_dh_id, opk_pq_id),
-                    _ => return Vec::new(),
-                };
-            w.write_bytes(ct2);
-            w.write_u32(opk_dh_id);
-            w.write_u32(opk_pq_id);
-        }
-        w.write_u32(self.pq_rcv_a_id);
-        w.write_bytes(&self.pq_rcv_a_pub);
-        w.write_bytes(&self.ik_sig_ec_a_pub);
-        w.write_bytes(&self.ik_sig_pq_a_pub);
-        w.write_bytes(&self.sig_ec_a);
-        w.write_bytes(&self.sig_pq_a);
-        w.into_vec()
+    /// Fails closed with `Invalid("<field>_missing")` if `opk_used` is set without all three
+    /// OPK fields.
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        self.encode_canonical()
     }
 
     pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
-        let mut r = Reader::new(buf);
-        let protocol_version = r.read_u16()?;
-        let suite_id = r.read_u16()?;
-        if protocol_version != QSP_PROTOCOL_VERSION {
-            return Err(CodecError::Invalid("protocol_version"));
-        }
-        if suite_id != QSP_SUITE_ID {
-            return Err(CodecError::Invalid("suite_id"));
-        }
-        let session_id = r.read_exact::<SZ_SESSION_ID>()?;
-        let user_id_b = r.read_varbytes_u16()?;
-        let device_id_b = r.read_u32()?;
-        let ek_dh_a_pub = r.read_exact::<SZ_X25519_PUB>()?;
-        let ct1 = r.read_bytes(SZ_MLKEM768_CT)?;
-        let opk_used = r.read_u16()? != 0;
-        let (ct2, opk_dh_id, opk_pq_id) = if opk_used {
-            let ct2 = r.read_bytes(SZ_MLKEM768_CT)?;
-            let opk_dh_id = r.read_u32()?;
-            let opk_pq_id = r.read_u32()?;
-            (Some(ct2), Some(opk_dh_id), Some(opk_pq_id))
-        } else {
-            (None, None, None)
-        };
-        let pq_rcv_a_id = r.read_u32()?;
-        let pq_rcv_a_pub = r.read_bytes(SZ_MLKEM768_PUB)?;
-        let ik_sig_ec_a_pub = r.read_exact::<SZ_ED25519_PUB>()?;
-        let ik_sig_pq_a_pub = r.read_bytes(SZ_MLDSA65_PUB)?;
-        let sig_ec_a = r.read_bytes(SZ_ED25519_SIG)?;
-        let sig_pq_a = r.read_bytes(SZ_MLDSA65_SIG)?;
-        r.finish()?;
-        Ok(Self {
-            protocol_version,
-            suite_id,
-            session_id,
-            user_id_b,
-            device_id_b,
-            ek_dh_a_pub,
-            ct1,
-            opk_used,
-            ct2,
-            opk_dh_id,
-            opk_pq_id,
-            pq_rcv_a_id,
-            pq_rcv_a_pub,
-            ik_sig_ec_a_pub,
-            ik_sig_pq_a_pub,
-            sig_ec_a,
-            sig_pq_a,
-        })
+        Self::decode_canonical(buf)
     }
 
     /// HS1 transcript per QSP §5.2.1: SHA-512("QSP4.3/HS1" || HS1_input)
     /// where HS1_input is HandshakeInit with signature fields set to zero bytes.
-    pub fn hs1_transcript(&self, hash: &dyn crate::crypto::traits::Hash) -> [u8; 64] {
+    pub fn hs1_transcript(
+        &self,
+        hash: &dyn crate::crypto::traits::Hash,
+    ) -> Result<[u8; 64], CodecError> {
         let mut tmp = self.clone();
         tmp.sig_ec_a = vec![0u8; SZ_ED25519_SIG];
         tmp.sig_pq_a = vec![0u8; SZ_MLDSA65_SIG];
         let mut m = b"QSP4.3/HS1".to_vec();
-        m.extend_from_slice(&tmp.encode());
-        hash.sha512(&m)
+        m.extend_from_slice(&tmp.encode()?);
+        Ok(hash.sha512(&m))
     }
 }
 
-#[cfg_attr(test, derive(PartialEq))]
-#[derive(Debug, Clone)]
-pub struct HandshakeResp {
-    pub protocol_version: u16,
-    pub suite_id: u16,
-    pub session_id: [u8; SZ_SESSION_ID],
-    pub dh0_b_pub: [u8; SZ_X25519_PUB],
-    pub pq_rcv_b_id: u32,
-    pub pq_rcv_b_pub: Vec<u8>, // 1184
-    pub ct3: Vec<u8>,          // 1088 (encap to PQ_RCV_A_pub)
-    pub conf_b: [u8; 32],
-    pub ik_sig_ec_b_pub: [u8; SZ_ED25519_PUB],
-    pub ik_sig_pq_b_pub: Vec<u8>, // 1952
-    pub sig_ec_b: Vec<u8>,        // 64
-    pub sig_pq_b: Vec<u8>,        // 3309
+canonical_struct! {
+    #[cfg_attr(test, derive(PartialEq))]
+    #[derive(Debug, Clone)]
+    pub struct HandshakeResp {
+        pub protocol_version: u16 as U16,
+        // Both header fields are read before either is judged.
+        #[check(protocol_version == QSP_PROTOCOL_VERSION, "protocol_version")]
+        #[check(suite_id == QSP_SUITE_ID || suite_id == QSP_SUITE_ID_DENIABLE, "suite_id")]
+        pub suite_id: u16 as U16,
+        pub session_id: [u8; SZ_SESSION_ID] as Fixed,
+        pub dh0_b_pub: [u8; SZ_X25519_PUB] as Fixed,
+        pub pq_rcv_b_id: u32 as U32,
+        pub pq_rcv_b_pub: Vec<u8> as Raw<SZ_MLKEM768_PUB>,
+        pub ct3: Vec<u8> as Raw<SZ_MLKEM768_CT>, // encap to PQ_RCV_A_pub
+        pub conf_b: [u8; 32] as Fixed,
+        pub ik_sig_ec_b_pub: [u8; SZ_ED25519_PUB] as Fixed,
+        pub ik_sig_pq_b_pub: Vec<u8> as Raw<SZ_MLDSA65_PUB>,
+        #[check(suite_id == QSP_SUITE_ID || is_zero(&sig_ec_b), "sig_ec_b")]
+        pub sig_ec_b: Vec<u8> as Raw<SZ_ED25519_SIG>,
+        #[check(suite_id == QSP_SUITE_ID || is_zero(&sig_pq_b), "sig_pq_b")]
+        pub sig_pq_b: Vec<u8> as Raw<SZ_MLDSA65_SIG>,
+    }
 }
 
 impl HandshakeResp {
-    pub fn encode(&self) -> Vec<u8> {
-        let mut w = Writer::new();
-        w.write_u16(self.protocol_version);
-        w.write_u16(self.suite_id);
-        w.write_bytes(&self.session_id);
-        w.write_bytes(&self.dh0_b_pub);
-        w.write_u32(self.pq_rcv_b_id);
-        w.write_bytes(&self.pq_rcv_b_pub);
-        w.write_bytes(&self.ct3);
-        w.write_bytes(&self.conf_b);
-        w.write_bytes(&self.ik_sig_ec_b_pub);
-        w.write_bytes(&self.ik_sig_pq_b_pub);
-        w.write_bytes(&self.sig_ec_b);
-        w.write_bytes(&self.sig_pq_b);
-        w.into_vec()
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        self.encode_canonical()
     }
 
     pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
-        let mut r = Reader::new(buf);
-        let protocol_version = r.read_u16()?;
-        let suite_id = r.read_u16()?;
-        if protocol_version != QSP_PROTOCOL_VERSION {
-            return Err(CodecError::Invalid("protocol_version"));
-        }
-        if suite_id != QSP_SUITE_ID {
-            return Err(CodecError::Invalid("suite_id"));
-        }
-        let session_id = r.read_exact::<SZ_SESSION_ID>()?;
-        let dh0_b_pub = r.read_exact::<SZ_X25519_PUB>()?;
-        let pq_rcv_b_id = r.read_u32()?;
-        let pq_rcv_b_pub = r.read_bytes(SZ_MLKEM768_PUB)?;
-        let ct3 = r.read_bytes(SZ_MLKEM768_CT)?;
-        let conf_b = r.read_exact::<32>()?;
-        let ik_sig_ec_b_pub = r.read_exact::<SZ_ED25519_PUB>()?;
-        let ik_sig_pq_b_pub = r.read_bytes(SZ_MLDSA65_PUB)?;
-        let sig_ec_b = r.read_bytes(SZ_ED25519_SIG)?;
-        let sig_pq_b = r.read_bytes(SZ_MLDSA65_SIG)?;
-        r.finish()?;
-        Ok(Self {
-            protocol_version,
-            suite_id,
-            session_id,
-            dh0_b_pub,
-            pq_rcv_b_id,
-            pq_rcv_b_pub,
-            ct3,
-            conf_b,
-            ik_sig_ec_b_pub,
-            ik_sig_pq_b_pub,
-            sig_ec_b,
-            sig_pq_b,
-        })
+        Self::decode_canonical(buf)
     }
 
     /// HS2 transcript per QSP §5.3.1: SHA-512("QSP4.3/HS2" || HandshakeInit || HS2_input)
@@ -366,137 +247,96 @@ impl HandshakeResp {
         &self,
         hs1: &HandshakeInit,
         hash: &dyn crate::crypto::traits::Hash,
-    ) -> [u8; 64] {
+    ) -> Result<[u8; 64], CodecError> {
         let mut tmp = self.clone();
         tmp.conf_b.fill(0);
         tmp.sig_ec_b = vec![0u8; SZ_ED25519_SIG];
         tmp.sig_pq_b = vec![0u8; SZ_MLDSA65_SIG];
         let mut m = b"QSP4.3/HS2".to_vec();
-        m.extend_from_slice(&hs1.encode());
-        m.extend_from_slice(&tmp.encode());
-        hash.sha512(&m)
+        m.extend_from_slice(&hs1.encode()?);
+        m.extend_from_slice(&tmp.encode()?);
+        Ok(hash.sha512(&m))
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct ProtocolMessage {
-    pub protocol_version: u16,
-    pub suite_id: u16,
-    pub session_id: [u8; SZ_SESSION_ID],
-    pub dh_pub: [u8; SZ_X25519_PUB],
-    pub flags: u16,
-    pub nonce_hdr: [u8; SZ_NONCE],
-    pub pq_adv_id: Option<u32>,
-    pub pq_adv_pub: Option<Vec<u8>>, // 1184
-    pub pq_target_id: Option<u32>,
-    pub pq_ct: Option<Vec<u8>>, // 1088
-    pub hdr_ct: Vec<u8>,
-    pub body_ct: Vec<u8>,
-}
+const QSP_HDR_CT_LEN: usize = 24;
+const QSP_BODY_CT_MIN: usize = 16;
 
-impl ProtocolMessage {
-    pub fn encode(&self) -> Vec<u8> {
-        let mut w = Writer::new();
-        w.write_u16(self.protocol_version);
-        w.write_u16(self.suite_id);
-        w.write_bytes(&self.session_id);
-        w.write_bytes(&self.dh_pub);
-        w.write_u16(self.flags);
-        w.write_bytes(&self.nonce_hdr);
-
-        if (self.flags & FLAG_PQ_ADV) != 0 {
-            let (pq_adv_id, pq_adv_pub) = match (self.pq_adv_id, self.pq_adv_pub.as_ref()) {
-                (Some(pq_adv_id), Some(pq_adv_pub)) => (pq_adv_id, pq_adv_pub),
-                _ => return Vec::new(),
-            };
-            w.write_u32(pq_adv_id);
-            w.write_bytes(pq_adv_pub);
-        }
-        if (self.flags & FLAG_PQ_CTXT) != 0 {
-            let (pq_target_id, pq_ct) = match (self.pq_target_id, self.pq_ct.as_ref()) {
-                (Some(pq_target_id), Some(pq_ct)) => (pq_target_id, pq_ct),
-                _ => return Vec::new(),
-            };
-            w.write_u32(pq_target_id);
-            w.write_bytes(pq_ct);
-        }
+/// `varbytes<u16>` of exactly `QSP_HDR_CT_LEN`. The length prefix is judged before the
+/// bytes are bounds-checked, so a wrong length is `Invalid("hdr_ct_len")` even when the
+/// buffer is also short.
+struct HdrCt;
 
-        w.write_u16(self.hdr_ct.len() as u16);
-        w.write_bytes(&self.hdr_ct);
-        w.write_u32(self.body_ct.len() as u32);
-        w.write_bytes(&self.body_ct);
-        w.into_vec()
+impl FieldCodec<Vec<u8>> for HdrCt {
+    fn write(w: &mut Writer, v: &Vec<u8>) -> Result<(), CodecError> {
+        VarU16::write(w, v)
     }
-
-    pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
-        let mut r = Reader::new(buf);
-        let protocol_version = r.read_u16()?;
-        let suite_id = r.read_u16()?;
-        if protocol_version != QSP_PROTOCOL_VERSION {
-            return Err(CodecError::Invalid("protocol_version"));
-        }
-        if suite_id != QSP_SUITE_ID {
-            return Err(CodecError::Invalid("suite_id"));
-        }
-        let session_id = r.read_exact::<SZ_SESSION_ID>()?;
-        let dh_pub = r.read_exact::<SZ_X25519_PUB>()?;
-        let flags = r.read_u16()?;
-        // Unknown flags must be rejected (QSP §6.3, §2.4). Only bits 0x0001/0x0002/0x0004 are defined.
-        if flags & !(FLAG_PQ_ADV | FLAG_PQ_CTXT | FLAG_BOUNDARY) != 0 {
-            return Err(CodecError::Invalid("flags"));
+    fn read(r: &mut Reader<'_>) -> Result<Vec<u8>, CodecError> {
+        let len = r.read_u16()? as usize;
+        if len != QSP_HDR_CT_LEN {
+            return Err(CodecError::Invalid("hdr_ct_len"));
         }
-        let nonce_hdr = r.read_exact::<SZ_NONCE>()?;
-
-        let (pq_adv_id, pq_adv_pub) = if (flags & FLAG_PQ_ADV) != 0 {
-            let id = r.read_u32()?;
-            let pubk = r.read_bytes(SZ_MLKEM768_PUB)?;
-            (Some(id), Some(pubk))
-        } else {
-            (None, None)
-        };
+        read_len_prefixed(r, len)
+    }
+}
 
-        let (pq_target_id, pq_ct) = if (flags & FLAG_PQ_CTXT) != 0 {
-            let id = r.read_u32()?;
-            let ct = r.read_bytes(SZ_MLKEM768_CT)?;
-            (Some(id), Some(ct))
-        } else {
-            (None, None)
-        };
+/// `varbytes<u32>` of at least `QSP_BODY_CT_MIN`, judged the same way as `HdrCt`.
+struct BodyCt;
 
-        let hdr_ct_len = r.read_u16()? as usize;
-        const QSP_HDR_CT_LEN: usize = 24;
-        if hdr_ct_len != QSP_HDR_CT_LEN {
-            return Err(CodecError::Invalid("hdr_ct_len"));
-        }
-        if r.remaining() < hdr_ct_len {
-            return Err(CodecError::LengthOutOfRange);
-        }
-        let hdr_ct = r.read_bytes(hdr_ct_len)?;
-        let body_ct_len = r.read_u32()? as usize;
-        const QSP_BODY_CT_MIN: usize = 16;
-        if body_ct_len < QSP_BODY_CT_MIN {
+impl FieldCodec<Vec<u8>> for BodyCt {
+    fn write(w: &mut Writer, v: &Vec<u8>) -> Result<(), CodecError> {
+        VarU32::write(w, v)
+    }
+    fn read(r: &mut Reader<'_>) -> Result<Vec<u8>, CodecError> {
+        let len = r.read_u32()? as usize;
+        if len < QSP_BODY_CT_MIN {
             return Err(CodecError::Invalid("body_ct_len"));
         }
-        if r.remaining() < body_ct_len {
-            return Err(CodecError::LengthOutOfRange);
-        }
-        let body_ct = r.read_bytes(body_ct_len)?;
-        r.finish()?;
+        read_len_prefixed(r, len)
+    }
+}
 
-        Ok(Self {
-            protocol_version,
-            suite_id,
-            session_id,
-            dh_pub,
-            flags,
-            nonce_hdr,
-            pq_adv_id,
-            pq_adv_pub,
-            pq_target_id,
-            pq_ct,
-            hdr_ct,
-            body_ct,
-        })
+fn read_len_prefixed(r: &mut Reader<'_>, len: usize) -> Result<Vec<u8>, CodecError> {
+    if r.remaining() < len {
+        return Err(CodecError::LengthOutOfRange);
+    }
+    r.read_bytes(len)
+}
+
+canonical_struct! {
+    #[derive(Debug, Clone)]
+    pub struct ProtocolMessage {
+        pub protocol_version: u16 as U16,
+        #[check(protocol_version == QSP_PROTOCOL_VERSION, "protocol_version")]
+        #[check(suite_id == QSP_SUITE_ID, "suite_id")]
+        pub suite_id: u16 as U16,
+        pub session_id: [u8; SZ_SESSION_ID] as Fixed,
+        pub dh_pub: [u8; SZ_X25519_PUB] as Fixed,
+        // Unknown flags must be rejected (QSP §6.3, §2.4). Only bits 0x0001/0x0002/0x0004 are defined.
+        #[check(flags & !(FLAG_PQ_ADV | FLAG_PQ_CTXT | FLAG_BOUNDARY) == 0, "flags")]
+        pub flags: u16 as U16,
+        pub nonce_hdr: [u8; SZ_NONCE] as Fixed,
+        #[when(flags & FLAG_PQ_ADV)]
+        pub pq_adv_id: Option<u32> as U32,
+        #[when(flags & FLAG_PQ_ADV)]
+        pub pq_adv_pub: Option<Vec<u8>> as Raw<SZ_MLKEM768_PUB>,
+        #[when(flags & FLAG_PQ_CTXT)]
+        pub pq_target_id: Option<u32> as U32,
+        #[when(flags & FLAG_PQ_CTXT)]
+        pub pq_ct: Option<Vec<u8>> as Raw<SZ_MLKEM768_CT>,
+        pub hdr_ct: Vec<u8> as HdrCt,
+        pub body_ct: Vec<u8> as BodyCt,
+    }
+}
+
+impl ProtocolMessage {
+    /// Fails closed with `Invalid("<field>_missing")` if a flagged PQ field is missing.
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        self.encode_canonical()
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
+        Self::decode_canonical(buf)
     }
 }
 
@@ -526,20 +366,28 @@ mod tests {
             user_id_b: vec![],
             device_id_b: 0,
             ek_dh_a_pub: [0u8; SZ_X25519_PUB],
-            ct1: vec![0u8; 1],
+            ct1: vec![0u8; SZ_MLKEM768_CT],
             opk_used: true,
             ct2: None,
             opk_dh_id: None,
             opk_pq_id: None,
             pq_rcv_a_id: 0,
-            pq_rcv_a_pub: vec![0u8; 1],
+            pq_rcv_a_pub: vec![0u8; SZ_MLKEM768_PUB],
             ik_sig_ec_a_pub: [0u8; SZ_ED25519_PUB],
-            ik_sig_pq_a_pub: vec![0u8; 1],
+            ik_sig_pq_a_pub: vec![0u8; SZ_MLDSA65_PUB],
             sig_ec_a: vec![0u8; SZ_ED25519_SIG],
-            sig_pq_a: vec![0u8; 1],
+            sig_pq_a: vec![0u8; SZ_MLDSA65_SIG],
         };
-        let out = msg.encode();
-        assert!(out.is_empty());
+        assert!(matches!(
+            msg.encode(),
+            Err(CodecError::Invalid("ct2_missing"))
+        ));
+
+        // A fixed-size field of the wrong size fails too, rather than encoding short.
+        let mut short = msg.clone();
+        short.opk_used = false;
+        short.ct1.pop();
+        assert!(matches!(short.encode(), Err(CodecError::LengthOutOfRange)));
     }
 
     #[test]
@@ -561,10 +409,14 @@ mod tests {
 
         let r1 = std::panic::catch_unwind(|| msg.encode());
         let r2 = std::panic::catch_unwind(|| msg.encode());
-        assert!(r1.is_ok());
-        assert!(r2.is_ok());
-        assert_eq!(r1.unwrap(), Vec::<u8>::new());
-        assert_eq!(r2.unwrap(), Vec::<u8>::new());
+        assert!(matches!(
+            r1.unwrap(),
+            Err(CodecError::Invalid("pq_adv_id_missing"))
+        ));
+        assert!(matches!(
+            r2.unwrap(),
+            Err(CodecError::Invalid("pq_adv_id_missing"))
+        ));
     }
 
     #[test]
@@ -586,9 +438,108 @@ mod tests {
 
         let r1 = std::panic::catch_unwind(|| msg.encode());
         let r2 = std::panic::catch_unwind(|| msg.encode());
-        assert!(r1.is_ok());
-        assert!(r2.is_ok());
-        assert_eq!(r1.unwrap(), Vec::<u8>::new());
-        assert_eq!(r2.unwrap(), Vec::<u8>::new());
+        assert!(matches!(
+            r1.unwrap(),
+            Err(CodecError::Invalid("pq_target_id_missing"))
+        ));
+        assert!(matches!(
+            r2.unwrap(),
+            Err(CodecError::Invalid("pq_target_id_missing"))
+        ));
+    }
+
+    #[test]
+    fn deniable_handshake_init_decodes_only_with_zero_signatures() {
+        let mut msg = HandshakeInit {
+            protocol_version: QSP_PROTOCOL_VERSION,
+            suite_id: QSP_SUITE_ID_DENIABLE,
+            session_id: rng_arr::<SZ_SESSION_ID>(),
+            user_id_b: rng_vec(4),
+            device_id_b: 3,
+            ek_dh_a_pub: rng_arr::<SZ_X25519_PUB>(),
+            ct1: rng_vec(SZ_MLKEM768_CT),
+            opk_used: false,
+            ct2: None,
+            opk_dh_id: None,
+            opk_pq_id: None,
+            pq_rcv_a_id: 5,
+            pq_rcv_a_pub: rng_vec(SZ_MLKEM768_PUB),
+            ik_sig_ec_a_pub: rng_arr::<SZ_ED25519_PUB>(),
+            ik_sig_pq_a_pub: rng_vec(SZ_MLDSA65_PUB),
+            sig_ec_a: vec![0u8; SZ_ED25519_SIG],
+            sig_pq_a: vec![0u8; SZ_MLDSA65_SIG],
+        };
+        assert_eq!(HandshakeInit::decode(&msg.encode().unwrap()).unwrap(), msg);
+
+        msg.sig_pq_a[SZ_MLDSA65_SIG - 1] = 1;
+        assert!(matches!(
+            HandshakeInit::decode(&msg.encode().unwrap()),
+            Err(CodecError::Invalid("sig_pq_a"))
+        ));
+
+        // The same signature bytes are fine under the signed suite.
+        msg.suite_id = QSP_SUITE_ID;
+        assert!(HandshakeInit::decode(&msg.encode().unwrap()).is_ok());
+    }
+
+    fn protocol_message() -> ProtocolMessage {
+        ProtocolMessage {
+            protocol_version: QSP_PROTOCOL_VERSION,
+            suite_id: QSP_SUITE_ID,
+            session_id: rng_arr::<SZ_SESSION_ID>(),
+            dh_pub: rng_arr::<SZ_X25519_PUB>(),
+            flags: 0,
+            nonce_hdr: rng_arr::<SZ_NONCE>(),
+            pq_adv_id: None,
+            pq_adv_pub: None,
+            pq_target_id: None,
+            pq_ct: None,
+            hdr_ct: rng_vec(QSP_HDR_CT_LEN),
+            body_ct: rng_vec(QSP_BODY_CT_MIN),
+        }
+    }
+
+    // The layout keeps the reject order of the hand-written decoder it replaced.
+    #[test]
+    fn protocol_message_decode_keeps_the_hand_written_reject_order() {
+        let wire = protocol_message().encode().unwrap();
+        assert!(ProtocolMessage::decode(&wire).is_ok());
+
+        // The version is judged only once the suite id is read: two bytes are truncated.
+        let mut bad_version = wire.clone();
+        bad_version[1] ^= 0xff;
+        assert!(matches!(
+            ProtocolMessage::decode(&bad_version[..2]),
+            Err(CodecError::Truncated)
+        ));
+        assert!(matches!(
+            ProtocolMessage::decode(&bad_version[..4]),
+            Err(CodecError::Invalid("protocol_version"))
+        ));
+
+        // A wrong length prefix is named as such even when its bytes are also missing.
+        let hdr_at = 2 + 2 + SZ_SESSION_ID + SZ_X25519_PUB + 2 + SZ_NONCE;
+        let mut bad_hdr = wire.clone();
+        bad_hdr[hdr_at + 1] = (QSP_HDR_CT_LEN + 1) as u8;
+        assert!(matches!(
+            ProtocolMessage::decode(&bad_hdr[..hdr_at + 2]),
+            Err(CodecError::Invalid("hdr_ct_len"))
+        ));
+        assert!(matches!(
+            ProtocolMessage::decode(&wire[..hdr_at + 2]),
+            Err(CodecError::LengthOutOfRange)
+        ));
+
+        let body_at = hdr_at + 2 + QSP_HDR_CT_LEN;
+        let mut bad_body = wire.clone();
+        bad_body[body_at + 3] = (QSP_BODY_CT_MIN - 1) as u8;
+        assert!(matches!(
+            ProtocolMessage::decode(&bad_body[..body_at + 4]),
+            Err(CodecError::Invalid("body_ct_len"))
+        ));
+        assert!(matches!(
+            ProtocolMessage::decode(&wire[..body_at + 4]),
+            Err(CodecError::LengthOutOfRange)
+        ));
     }
 }
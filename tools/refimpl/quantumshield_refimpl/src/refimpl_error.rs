@@ -2,6 +2,7 @@ use core::fmt;
 
 use crate::codec::CodecError;
 use crate::qsp::RatchetError;
+use crate::suite2::reject::Suite2RejectReason;
 
 /// Canonical refimpl error surface for callers.
 ///
@@ -27,6 +28,14 @@ impl RefimplError {
             RefimplError::Internal => "REJECT_INTERNAL",
         }
     }
+
+    /// The typed Suite-2/SCKA reason, for a `Reject` carrying one of those codes.
+    pub fn reject_reason(&self) -> Option<Suite2RejectReason> {
+        match self {
+            RefimplError::Reject(c) => Suite2RejectReason::from_code(c),
+            _ => None,
+        }
+    }
 }
 
 impl From<&'static str> for RefimplError {
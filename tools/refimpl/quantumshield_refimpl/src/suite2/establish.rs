@@ -3,8 +3,11 @@
 use std::collections::BTreeSet;
 
 use crate::crypto::traits::{CryptoError, Kmac};
+use crate::suite2::limits::Suite2Limits;
+use crate::suite2::negotiate::{check_negotiated, Suite2NegotiationInput};
 use crate::suite2::ratchet::{Suite2DhRatchetState, Suite2RecvWireState, Suite2SendState};
 use crate::suite2::state::Suite2SessionState;
+use crate::suite2::types;
 
 const ZERO32: [u8; 32] = [0u8; 32];
 
@@ -18,6 +21,35 @@ fn kmac32(kmac: &dyn Kmac, key: &[u8], label: &str, data: &[u8]) -> Result<[u8;
     Ok(arr)
 }
 
+/// Establish from a negotiated handshake: the suite must pass `check_negotiated` for the
+/// lists and AD tuple the handshake authenticated before any key is derived for it.
+#[allow(clippy::too_many_arguments)]
+pub fn init_from_negotiated_handshake(
+    kmac: &dyn Kmac,
+    role_is_a: bool,
+    negotiation: &Suite2NegotiationInput<'_>,
+    session_id: &[u8],
+    dh_init: &[u8],
+    pq_init_ss: &[u8],
+    dh_self_pub: &[u8],
+    dh_peer_pub: &[u8],
+    authenticated: bool,
+) -> Result<Suite2SessionState, &'static str> {
+    let params = check_negotiated(negotiation)?;
+    init_from_base_handshake(
+        kmac,
+        role_is_a,
+        negotiation.negotiated_protocol_version,
+        params.suite_id,
+        session_id,
+        dh_init,
+        pq_init_ss,
+        dh_self_pub,
+        dh_peer_pub,
+        authenticated,
+    )
+}
+
 /// Initialize Suite-2 session state from base-handshake outputs (DOC-CAN-003 §8.2).
 #[allow(clippy::too_many_arguments)]
 pub fn init_from_base_handshake(
@@ -43,6 +75,11 @@ pub fn init_from_base_handshake(
     if !authenticated {
         return Err("REJECT_S2_ESTABLISH_UNAUTHENTICATED");
     }
+    if protocol_version != types::SUITE2_PROTOCOL_VERSION
+        || types::suite2_params(suite_id).is_none()
+    {
+        return Err("REJECT_S2_SUITE_MISMATCH");
+    }
 
     let mut sid = [0u8; 16];
     sid.copy_from_slice(session_id);
@@ -115,6 +152,9 @@ pub fn init_from_base_handshake(
             consumed_targets: BTreeSet::new(),
             tombstoned_targets: BTreeSet::new(),
             mkskipped: Vec::new(),
+            limits: Suite2Limits::default(),
+            dh_epoch: 0,
+            now_secs: 0,
         }
     } else {
         Suite2RecvWireState {
@@ -133,6 +173,9 @@ pub fn init_from_base_handshake(
             consumed_targets: BTreeSet::new(),
             tombstoned_targets: BTreeSet::new(),
             mkskipped: Vec::new(),
+            limits: Suite2Limits::default(),
+            dh_epoch: 0,
+            now_secs: 0,
         }
     };
 
@@ -0,0 +1,82 @@
+//! Read-only post-compromise-security healing metrics.
+//!
+//! Suite-2 heals through DH ratchet steps (DOC-CAN-003 §8.5.1/§8.5.2) and SCKA PQ reseeds
+//! (DOC-CAN-004 §3). `Suite2HealingStatus` reports how far a `Suite2SessionState` is from its
+//! last step in each direction and which of its advertised PQ targets the peer can still
+//! reseed against; `Suite2SessionHealing` (`Suite2Session::healing_status`) adds the
+//! caller-side SCKA store and trigger counters. Nothing here reads a clock: "seconds since"
+//! belongs to a caller that keeps wall-clock timestamps (qsc's trigger and SCKA state).
+
+use crate::suite2::state::Suite2SessionState;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suite2HealingStatus {
+    /// Messages sent on the current send chain, i.e. since our last DH step (`send.ns`).
+    pub sent_since_dh_step: u32,
+    /// Messages received on the current receive chain, i.e. since the peer's last DH step
+    /// (`recv.nr`).
+    pub received_since_dh_step: u32,
+    /// Receive DH ratchet steps taken over the session's life.
+    pub recv_dh_steps: u32,
+    /// Local advertised PQ targets not yet consumed or tombstoned, ascending: the reseeds the
+    /// peer can still send us.
+    pub outstanding_targets: Vec<u32>,
+    /// Targets the peer has consumed with a reseed.
+    pub consumed_targets: usize,
+    /// Highest of our advertised targets the peer has reseeded against (0 = none); the
+    /// peer's advertisement watermark is caller-owned (`Suite2SessionHealing`).
+    pub reseed_target_watermark: u32,
+    /// Skipped message keys retained; each still opens a past message.
+    pub skipped_keys: usize,
+}
+
+impl Suite2HealingStatus {
+    pub fn of(st: &Suite2SessionState) -> Self {
+        let recv = &st.recv;
+        Suite2HealingStatus {
+            sent_since_dh_step: st.send.ns,
+            received_since_dh_step: recv.nr,
+            recv_dh_steps: recv.dh_epoch,
+            outstanding_targets: recv
+                .known_targets
+                .iter()
+                .filter(|id| {
+                    !recv.consumed_targets.contains(id) && !recv.tombstoned_targets.contains(id)
+                })
+                .copied()
+                .collect(),
+            consumed_targets: recv.consumed_targets.len(),
+            reseed_target_watermark: recv.peer_max_adv_id_seen,
+            skipped_keys: recv.mkskipped.len(),
+        }
+    }
+}
+
+impl Suite2SessionState {
+    /// Healing metrics for this state (`Suite2HealingStatus::of`).
+    pub fn healing_status(&self) -> Suite2HealingStatus {
+        Suite2HealingStatus::of(self)
+    }
+}
+
+/// `Suite2HealingStatus` plus the `Suite2Session` trigger and SCKA counters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suite2SessionHealing {
+    pub state: Suite2HealingStatus,
+    /// Sends since the last sent DH boundary; the ratchet fires at `ratchet_after_msgs`.
+    pub msgs_since_ratchet: u32,
+    /// The next `encrypt` DH-ratchets (a reply is owed, or `request_ratchet` was called).
+    pub ratchet_pending: bool,
+    /// Application messages sent or received since the last PQ reseed in either direction.
+    pub msgs_since_reseed: u64,
+    /// Sent DH boundaries since the last sent reseed; reseeds fire at `reseed_after_boundaries`.
+    pub boundaries_since_reseed: u32,
+    pub reseeds_sent: u64,
+    pub reseeds_received: u64,
+    /// Local advertisements whose secret is still held, ascending.
+    pub live_adv_ids: Vec<u32>,
+    /// Peer advertisement waiting to be targeted by our next reseed.
+    pub pending_peer_adv_id: Option<u32>,
+    /// Highest peer advertisement id this session accepted (0 = none).
+    pub peer_adv_max_seen: u32,
+}
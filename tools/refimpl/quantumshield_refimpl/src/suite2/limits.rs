@@ -0,0 +1,159 @@
+//! Receive-path bounds for Suite-2 (DOC-CAN-003 §8.3 out-of-order handling).
+//!
+//! How far ahead a message may skip, how many skipped message keys are retained, how many
+//! header decryptions one receive may attempt, and (optionally) how long a skipped key may be
+//! kept. The defaults are the historical fixed values, with no expiry; deployments tune them per
+//! session through a validated `Suite2Limits`, which travels with `Suite2RecvWireState` and is
+//! persisted in its snapshot.
+
+/// Historical fixed bounds, kept as the defaults.
+pub const DEFAULT_MAX_SKIP: u32 = 1000;
+pub const DEFAULT_MAX_MKSKIPPED: usize = 1000;
+pub const DEFAULT_MAX_HEADER_ATTEMPTS: usize = 100;
+
+/// Upper bounds accepted by `Suite2Limits::new` and by snapshot restore. They cap the work one
+/// hostile message can cause (chain steps, AEAD opens) and the size of a restored state.
+/// Values above the defaults exceed the DOC-SCL-001 §3.2 canonical profile caps
+/// (`qsp.max_skip`, `qsp.max_header_attempts`); they are a deployment choice, not the profile.
+pub const MAX_SKIP_CEILING: u32 = 65_536;
+pub const MAX_MKSKIPPED_CEILING: usize = 65_536;
+pub const MAX_HEADER_ATTEMPTS_CEILING: usize = 4_096;
+
+/// Longest skipped-key age accepted: DOC-SCL-001 §3.2 `qsp.mkskipped_ttl_seconds` (7 days).
+pub const MKSKIPPED_MAX_AGE_CEILING_SECS: u64 = 604_800;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suite2LimitsError {
+    MaxSkip,
+    MaxMkSkipped,
+    MaxHeaderAttempts,
+    MkSkippedMaxAge,
+    MkSkippedMaxDhSteps,
+}
+
+impl std::fmt::Display for Suite2LimitsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Suite2LimitsError::MaxSkip => {
+                write!(f, "max_skip must be in 1..={MAX_SKIP_CEILING}")
+            }
+            Suite2LimitsError::MaxMkSkipped => {
+                write!(f, "max_mkskipped must be in 1..={MAX_MKSKIPPED_CEILING}")
+            }
+            Suite2LimitsError::MaxHeaderAttempts => {
+                write!(
+                    f,
+                    "max_header_attempts must be in 1..={MAX_HEADER_ATTEMPTS_CEILING}"
+                )
+            }
+            Suite2LimitsError::MkSkippedMaxAge => {
+                write!(
+                    f,
+                    "mkskipped max age must be in 1..={MKSKIPPED_MAX_AGE_CEILING_SECS} seconds"
+                )
+            }
+            Suite2LimitsError::MkSkippedMaxDhSteps => {
+                write!(f, "mkskipped max DH steps must be at least 1")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Suite2LimitsError {}
+
+/// Validated receive bounds. Construct with `new` (or `Default`); the fields are private so a
+/// held value is always within the ceilings above.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suite2Limits {
+    max_skip: u32,
+    max_mkskipped: usize,
+    max_header_attempts: usize,
+    mkskipped_max_age_secs: Option<u64>,
+    mkskipped_max_dh_steps: Option<u32>,
+}
+
+impl Default for Suite2Limits {
+    fn default() -> Self {
+        Suite2Limits {
+            max_skip: DEFAULT_MAX_SKIP,
+            max_mkskipped: DEFAULT_MAX_MKSKIPPED,
+            max_header_attempts: DEFAULT_MAX_HEADER_ATTEMPTS,
+            mkskipped_max_age_secs: None,
+            mkskipped_max_dh_steps: None,
+        }
+    }
+}
+
+impl Suite2Limits {
+    pub fn new(
+        max_skip: u32,
+        max_mkskipped: usize,
+        max_header_attempts: usize,
+    ) -> Result<Self, Suite2LimitsError> {
+        if max_skip == 0 || max_skip > MAX_SKIP_CEILING {
+            return Err(Suite2LimitsError::MaxSkip);
+        }
+        if max_mkskipped == 0 || max_mkskipped > MAX_MKSKIPPED_CEILING {
+            return Err(Suite2LimitsError::MaxMkSkipped);
+        }
+        if max_header_attempts == 0 || max_header_attempts > MAX_HEADER_ATTEMPTS_CEILING {
+            return Err(Suite2LimitsError::MaxHeaderAttempts);
+        }
+        Ok(Suite2Limits {
+            max_skip,
+            max_mkskipped,
+            max_header_attempts,
+            mkskipped_max_age_secs: None,
+            mkskipped_max_dh_steps: None,
+        })
+    }
+
+    /// Expire skipped keys `max_age_secs` after they were stored, measured on the caller's
+    /// clock (`Suite2SessionState::expire_mkskipped`).
+    pub fn with_mkskipped_max_age(self, max_age_secs: u64) -> Result<Self, Suite2LimitsError> {
+        if max_age_secs == 0 || max_age_secs > MKSKIPPED_MAX_AGE_CEILING_SECS {
+            return Err(Suite2LimitsError::MkSkippedMaxAge);
+        }
+        Ok(Suite2Limits {
+            mkskipped_max_age_secs: Some(max_age_secs),
+            ..self
+        })
+    }
+
+    /// Expire skipped keys once the receive side has taken `max_dh_steps` DH ratchet steps
+    /// since they were stored. `1` drops a chain's keys at the next receive ratchet.
+    pub fn with_mkskipped_max_dh_steps(self, max_dh_steps: u32) -> Result<Self, Suite2LimitsError> {
+        if max_dh_steps == 0 {
+            return Err(Suite2LimitsError::MkSkippedMaxDhSteps);
+        }
+        Ok(Suite2Limits {
+            mkskipped_max_dh_steps: Some(max_dh_steps),
+            ..self
+        })
+    }
+
+    /// Largest accepted gap `N - Nr` for a non-boundary message.
+    pub fn max_skip(&self) -> u32 {
+        self.max_skip
+    }
+
+    /// Skipped message keys retained; the oldest are evicted beyond this.
+    pub fn max_mkskipped(&self) -> usize {
+        self.max_mkskipped
+    }
+
+    /// Header-key trial decryptions allowed per received message.
+    pub fn max_header_attempts(&self) -> usize {
+        self.max_header_attempts
+    }
+
+    /// Skipped-key age bound in seconds; `None` keeps keys until evicted by count.
+    pub fn mkskipped_max_age_secs(&self) -> Option<u64> {
+        self.mkskipped_max_age_secs
+    }
+
+    /// Skipped-key bound in receive DH ratchet steps; `None` keeps keys across ratchets.
+    pub fn mkskipped_max_dh_steps(&self) -> Option<u32> {
+        self.mkskipped_max_dh_steps
+    }
+}
@@ -2,9 +2,15 @@
 
 pub mod binding;
 pub mod establish;
+pub mod healing;
+pub mod limits;
+pub mod negotiate;
 pub mod parse;
 pub mod ratchet;
+pub mod reject;
 pub mod scka;
+pub mod sender_key;
+pub mod session;
 pub mod state;
 pub mod types;
 
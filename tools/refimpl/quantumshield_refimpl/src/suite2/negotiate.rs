@@ -0,0 +1,159 @@
+//! Suite-2 suite negotiation with downgrade protection.
+//!
+//! Both sides select the first entry of `SUITE2_SUITE_IDS` that both advertise, so the choice
+//! is a pure function of the two advertised lists. `check_negotiated` re-runs that selection
+//! on the list the peer committed to in the authenticated transcript and rejects any other
+//! outcome: a suite stripped from an unauthenticated offer yields a reject, never a session on
+//! a suite both peers rank lower. The list holds only 0x0002 today, so in practice this
+//! catches the legacy (0x0403/0x0001) tuple, an offer that differs from the commitment and an
+//! AD tuple that differs from the negotiated one.
+//!
+//! `establish::init_from_negotiated_handshake` runs this check before deriving any key.
+
+use crate::suite2::types::{self, Suite2Params, SUITE2_SUITE_IDS};
+
+const LEGACY_PROTOCOL_VERSION: u16 = 0x0403;
+const LEGACY_SUITE_ID: u16 = 0x0001;
+
+/// Everything a receiver checks about a negotiated suite.
+#[derive(Debug, Clone, Copy)]
+pub struct Suite2NegotiationInput<'a> {
+    /// Suites this endpoint accepts; unknown ids are ignored.
+    pub local: &'a [u16],
+    /// The peer's suite list as received in its (unauthenticated) offer.
+    pub peer_offered: &'a [u16],
+    /// The peer's suite list as covered by its handshake signature.
+    pub peer_committed: &'a [u16],
+    pub negotiated_protocol_version: u16,
+    pub negotiated_suite_id: u16,
+    /// `protocol_version` / `suite_id` as bound into AD.
+    pub ad_protocol_version: u16,
+    pub ad_suite_id: u16,
+}
+
+/// The suite two endpoints with these lists must agree on, if any.
+pub fn select_suite(local: &[u16], peer: &[u16]) -> Option<u16> {
+    SUITE2_SUITE_IDS
+        .into_iter()
+        .find(|s| local.contains(s) && peer.contains(s))
+}
+
+fn known_suites(list: &[u16]) -> Vec<u16> {
+    SUITE2_SUITE_IDS
+        .into_iter()
+        .filter(|s| list.contains(s))
+        .collect()
+}
+
+/// Accept a negotiated suite only if it is exactly what `select_suite` gives for the committed
+/// lists. Fail-closed with the shared `REJECT_S2_*` codes.
+pub fn check_negotiated(
+    input: &Suite2NegotiationInput<'_>,
+) -> Result<&'static Suite2Params, &'static str> {
+    let local = known_suites(input.local);
+    if local.is_empty() {
+        return Err("REJECT_S2_LOCAL_UNSUPPORTED");
+    }
+    let committed = known_suites(input.peer_committed);
+    if committed.is_empty() {
+        return Err("REJECT_S2_PEER_UNSUPPORTED");
+    }
+    if known_suites(input.peer_offered) != committed {
+        return Err("REJECT_S2_CAPABILITY_COMMITMENT_MISMATCH");
+    }
+    let Some(expected) = select_suite(&local, &committed) else {
+        return Err("REJECT_S2_PEER_UNSUPPORTED");
+    };
+    if input.ad_protocol_version != input.negotiated_protocol_version
+        || input.ad_suite_id != input.negotiated_suite_id
+    {
+        return Err("REJECT_S2_AD_MISMATCH");
+    }
+    let (pv, sid) = (input.negotiated_protocol_version, input.negotiated_suite_id);
+    if pv == LEGACY_PROTOCOL_VERSION && sid == LEGACY_SUITE_ID {
+        return Err("REJECT_S2_DOWNGRADE");
+    }
+    if pv != types::SUITE2_PROTOCOL_VERSION {
+        return Err("REJECT_S2_VERSION_UNSUPPORTED");
+    }
+    if sid == expected {
+        return types::suite2_params(sid).ok_or("REJECT_S2_SUITE_MISMATCH");
+    }
+    // A real suite that both sides speak but that ranks below the selection is a downgrade;
+    // anything else was never on the table.
+    let rank = |s: u16| SUITE2_SUITE_IDS.iter().position(|x| *x == s);
+    match (rank(sid), rank(expected)) {
+        (Some(got), Some(want))
+            if got > want && local.contains(&sid) && committed.contains(&sid) =>
+        {
+            Err("REJECT_S2_DOWNGRADE")
+        }
+        _ => Err("REJECT_S2_SUITE_MISMATCH"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suite2::types::SUITE2_SUITE_ID;
+
+    const OURS: &[u16] = &[SUITE2_SUITE_ID];
+
+    fn input<'a>(local: &'a [u16], peer: &'a [u16], sid: u16) -> Suite2NegotiationInput<'a> {
+        Suite2NegotiationInput {
+            local,
+            peer_offered: peer,
+            peer_committed: peer,
+            negotiated_protocol_version: types::SUITE2_PROTOCOL_VERSION,
+            negotiated_suite_id: sid,
+            ad_protocol_version: types::SUITE2_PROTOCOL_VERSION,
+            ad_suite_id: sid,
+        }
+    }
+
+    #[test]
+    fn selection_ignores_unknown_suites() {
+        assert_eq!(select_suite(OURS, OURS), Some(SUITE2_SUITE_ID));
+        assert_eq!(
+            select_suite(OURS, &[0x0003, SUITE2_SUITE_ID]),
+            Some(SUITE2_SUITE_ID)
+        );
+        assert_eq!(select_suite(OURS, &[0x0003]), None);
+        assert_eq!(select_suite(&[0x9999], &[0x9999]), None);
+    }
+
+    #[test]
+    fn stripped_offer_legacy_tuple_and_unknown_suite_are_rejected() {
+        let params = check_negotiated(&input(OURS, OURS, SUITE2_SUITE_ID)).expect("suite2");
+        assert_eq!(params.kem_alg, "ML-KEM-768");
+
+        let mut stripped = input(OURS, &[], SUITE2_SUITE_ID);
+        stripped.peer_committed = OURS;
+        assert_eq!(
+            check_negotiated(&stripped),
+            Err("REJECT_S2_CAPABILITY_COMMITMENT_MISMATCH")
+        );
+
+        let mut legacy = input(OURS, OURS, LEGACY_SUITE_ID);
+        legacy.negotiated_protocol_version = LEGACY_PROTOCOL_VERSION;
+        legacy.ad_protocol_version = LEGACY_PROTOCOL_VERSION;
+        assert_eq!(check_negotiated(&legacy), Err("REJECT_S2_DOWNGRADE"));
+
+        let mut ad = input(OURS, OURS, SUITE2_SUITE_ID);
+        ad.ad_suite_id = 0x0003;
+        assert_eq!(check_negotiated(&ad), Err("REJECT_S2_AD_MISMATCH"));
+
+        assert_eq!(
+            check_negotiated(&input(OURS, &[0x0003], 0x0003)),
+            Err("REJECT_S2_PEER_UNSUPPORTED")
+        );
+        assert_eq!(
+            check_negotiated(&input(&[0x0003], OURS, 0x0003)),
+            Err("REJECT_S2_LOCAL_UNSUPPORTED")
+        );
+        assert_eq!(
+            check_negotiated(&input(OURS, OURS, 0x0003)),
+            Err("REJECT_S2_SUITE_MISMATCH")
+        );
+    }
+}
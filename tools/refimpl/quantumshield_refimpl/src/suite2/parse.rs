@@ -1,6 +1,7 @@
 //! Suite-2 ratchet message parsing (strict, fail-closed).
 
-use crate::suite2::types;
+use crate::codec::Reader;
+use crate::suite2::types::{self, Suite2Params};
 
 pub struct Suite2ParsedRatchetMsg {
     pub dh_pub: [u8; 32],
@@ -14,22 +15,49 @@ pub struct Suite2ParsedRatchetMsg {
     pub body_ct: Vec<u8>,
 }
 
-fn parse_ratchet_header(header: &[u8]) -> Result<(Suite2ParsedRatchetMsg, usize), &'static str> {
-    const HDR_CT_LEN: usize = 24;
-    const PQ_ADV_PUB_LEN: usize = 1184;
-    const PQ_CT_LEN: usize = 1088;
+/// Zero-copy view of a Suite-2 ratchet message; byte fields borrow from the parsed buffer.
+/// `pq_prefix` is the contiguous `(id || pub)? || (id || ct)?` run of the header, which is
+/// what `Suite2ParsedRatchetMsg::pq_prefix` copies out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suite2ParsedRatchetMsgRef<'a> {
+    pub dh_pub: [u8; 32],
+    pub flags: u16,
+    pub pq_prefix: &'a [u8],
+    pub pq_adv_id: Option<u32>,
+    pub pq_adv_pub: Option<&'a [u8]>,
+    pub pq_target_id: Option<u32>,
+    pub pq_ct: Option<&'a [u8]>,
+    pub hdr_ct: &'a [u8],
+    pub body_ct: &'a [u8],
+}
 
-    if header.len() < 32 + 2 {
-        return Err("REJECT_S2_PARSE_PREFIX");
+impl Suite2ParsedRatchetMsgRef<'_> {
+    pub fn to_parsed(&self) -> Suite2ParsedRatchetMsg {
+        Suite2ParsedRatchetMsg {
+            dh_pub: self.dh_pub,
+            flags: self.flags,
+            pq_prefix: self.pq_prefix.to_vec(),
+            pq_adv_id: self.pq_adv_id,
+            pq_adv_pub: self.pq_adv_pub.map(<[u8]>::to_vec),
+            pq_target_id: self.pq_target_id,
+            pq_ct: self.pq_ct.map(<[u8]>::to_vec),
+            hdr_ct: self.hdr_ct.to_vec(),
+            body_ct: self.body_ct.to_vec(),
+        }
     }
+}
 
-    let mut off = 0usize;
-    let mut dh_pub = [0u8; 32];
-    dh_pub.copy_from_slice(&header[0..32]);
-    off += 32;
+fn parse_ratchet_header<'a>(
+    header: &'a [u8],
+    params: &Suite2Params,
+) -> Result<(Suite2ParsedRatchetMsgRef<'a>, usize), &'static str> {
+    const HDR_CT_LEN: usize = 24;
+    let pq_adv_pub_len = params.kem_pub_len;
+    let pq_ct_len = params.kem_ct_len;
 
-    let flags = u16::from_be_bytes([header[off], header[off + 1]]);
-    off += 2;
+    let mut r = Reader::new(header);
+    let dh_pub = r.read_exact::<32>().map_err(|_| "REJECT_S2_PARSE_PREFIX")?;
+    let flags = r.read_u16().map_err(|_| "REJECT_S2_PARSE_PREFIX")?;
 
     let known_flags = types::FLAG_PQ_ADV | types::FLAG_PQ_CTXT | types::FLAG_BOUNDARY;
     if (flags & !known_flags) != 0 {
@@ -42,62 +70,45 @@ fn parse_ratchet_header(header: &[u8]) -> Result<(Suite2ParsedRatchetMsg, usize)
         return Err("REJECT_S2_PARSE_FLAGS");
     }
 
-    let mut pq_prefix = Vec::new();
+    let pq_prefix_start = r.position();
     let mut pq_adv_id = None;
     let mut pq_adv_pub = None;
     let mut pq_target_id = None;
     let mut pq_ct = None;
 
     if (flags & types::FLAG_PQ_ADV) != 0 {
-        if header.len() < off + 4 + PQ_ADV_PUB_LEN {
+        if r.remaining() < 4 + pq_adv_pub_len {
             return Err("REJECT_S2_PQPREFIX_PARSE");
         }
-        let id = u32::from_be_bytes([
-            header[off],
-            header[off + 1],
-            header[off + 2],
-            header[off + 3],
-        ]);
-        off += 4;
-        let pub_bytes = header[off..off + PQ_ADV_PUB_LEN].to_vec();
-        off += PQ_ADV_PUB_LEN;
-        pq_prefix.extend_from_slice(&id.to_be_bytes());
-        pq_prefix.extend_from_slice(&pub_bytes);
-        pq_adv_id = Some(id);
-        pq_adv_pub = Some(pub_bytes);
+        pq_adv_id = Some(r.read_u32().map_err(|_| "REJECT_S2_PQPREFIX_PARSE")?);
+        pq_adv_pub = Some(
+            r.read_slice(pq_adv_pub_len)
+                .map_err(|_| "REJECT_S2_PQPREFIX_PARSE")?,
+        );
     }
 
     if (flags & types::FLAG_PQ_CTXT) != 0 {
-        if header.len() < off + 4 + PQ_CT_LEN {
+        if r.remaining() < 4 + pq_ct_len {
             return Err("REJECT_S2_PQPREFIX_PARSE");
         }
-        let id = u32::from_be_bytes([
-            header[off],
-            header[off + 1],
-            header[off + 2],
-            header[off + 3],
-        ]);
-        off += 4;
-        let ct = header[off..off + PQ_CT_LEN].to_vec();
-        off += PQ_CT_LEN;
-        pq_prefix.extend_from_slice(&id.to_be_bytes());
-        pq_prefix.extend_from_slice(&ct);
-        pq_target_id = Some(id);
-        pq_ct = Some(ct);
+        pq_target_id = Some(r.read_u32().map_err(|_| "REJECT_S2_PQPREFIX_PARSE")?);
+        pq_ct = Some(
+            r.read_slice(pq_ct_len)
+                .map_err(|_| "REJECT_S2_PQPREFIX_PARSE")?,
+        );
     }
 
     if (flags & types::FLAG_PQ_CTXT) != 0 && pq_target_id.is_none() {
         return Err("REJECT_S2_PQPREFIX_PARSE");
     }
+    let pq_prefix = &header[pq_prefix_start..r.position()];
 
-    if header.len() < off + HDR_CT_LEN {
-        return Err("REJECT_S2_PARSE_HDR_LEN");
-    }
-    let hdr_ct = header[off..off + HDR_CT_LEN].to_vec();
-    off += HDR_CT_LEN;
+    let hdr_ct = r
+        .read_slice(HDR_CT_LEN)
+        .map_err(|_| "REJECT_S2_PARSE_HDR_LEN")?;
 
     Ok((
-        Suite2ParsedRatchetMsg {
+        Suite2ParsedRatchetMsgRef {
             dh_pub,
             flags,
             pq_prefix,
@@ -106,19 +117,34 @@ fn parse_ratchet_header(header: &[u8]) -> Result<(Suite2ParsedRatchetMsg, usize)
             pq_target_id,
             pq_ct,
             hdr_ct,
-            body_ct: Vec::new(),
+            body_ct: &[],
         },
-        off,
+        r.position(),
     ))
 }
 
+/// Parse a bare ratchet message (no wire prefix) with the `SUITE2_SUITE_ID` PQ sizes.
 pub fn decode_suite2_ratchet_message(buf: &[u8]) -> Result<Suite2ParsedRatchetMsg, &'static str> {
+    decode_suite2_ratchet_message_ref(buf).map(|m| m.to_parsed())
+}
+
+pub fn decode_suite2_ratchet_message_ref(
+    buf: &[u8],
+) -> Result<Suite2ParsedRatchetMsgRef<'_>, &'static str> {
+    decode_suite2_ratchet_message_ref_for(buf, &types::SUITE2_PARAMS)
+}
+
+/// `decode_suite2_ratchet_message_ref` with the PQ sizes of `params`.
+pub fn decode_suite2_ratchet_message_ref_for<'a>(
+    buf: &'a [u8],
+    params: &Suite2Params,
+) -> Result<Suite2ParsedRatchetMsgRef<'a>, &'static str> {
     const BODY_CT_MIN: usize = 16;
-    let (mut parsed, off) = parse_ratchet_header(buf)?;
+    let (mut parsed, off) = parse_ratchet_header(buf, params)?;
     if buf.len() < off {
         return Err("REJECT_S2_PARSE_HDR_LEN");
     }
-    let body_ct = buf[off..].to_vec();
+    let body_ct = &buf[off..];
     if body_ct.len() < BODY_CT_MIN {
         return Err("REJECT_S2_PARSE_BODY_LEN");
     }
@@ -126,52 +152,198 @@ pub fn decode_suite2_ratchet_message(buf: &[u8]) -> Result<Suite2ParsedRatchetMs
     Ok(parsed)
 }
 
+/// Parse a Suite-2 wire message of any supported suite; the header is read with the PQ sizes
+/// of the suite id in the prefix.
 pub fn decode_suite2_wire(
     buf: &[u8],
 ) -> Result<(u16, u16, u8, Suite2ParsedRatchetMsg), &'static str> {
-    const ENVELOPE_HDR_LEN: usize = 10;
-    if buf.len() < ENVELOPE_HDR_LEN {
-        return Err("REJECT_S2_PARSE_PREFIX");
-    }
-    let mut off = 0usize;
-    let protocol_version = u16::from_be_bytes([buf[0], buf[1]]);
-    off += 2;
-    let suite_id = u16::from_be_bytes([buf[2], buf[3]]);
-    off += 2;
-    let msg_type = buf[4];
-    off += 1;
-    let _env_flags = buf[5];
-    off += 1;
-    let header_len = u16::from_be_bytes([buf[6], buf[7]]) as usize;
-    off += 2;
-    let body_len = u16::from_be_bytes([buf[8], buf[9]]) as usize;
-    off += 2;
-
-    if protocol_version != types::SUITE2_PROTOCOL_VERSION
-        || suite_id != types::SUITE2_SUITE_ID
-        || msg_type != 0x02
-    {
+    decode_suite2_wire_ref(buf).map(|(pv, sid, mt, m)| (pv, sid, mt, m.to_parsed()))
+}
+
+/// `decode_suite2_wire` for a session pinned to `suite_id`: a message of any other suite is a
+/// prefix reject, exactly like an unknown suite.
+pub fn decode_suite2_wire_for(
+    buf: &[u8],
+    suite_id: u16,
+) -> Result<(u16, u16, u8, Suite2ParsedRatchetMsg), &'static str> {
+    let out = decode_suite2_wire(buf)?;
+    if out.1 != suite_id {
         return Err("REJECT_S2_PARSE_PREFIX");
     }
+    Ok(out)
+}
 
-    if buf.len() < off + header_len + body_len {
-        return Err("REJECT_S2_PARSE_PREFIX");
+pub fn decode_suite2_wire_ref(
+    buf: &[u8],
+) -> Result<(u16, u16, u8, Suite2ParsedRatchetMsgRef<'_>), &'static str> {
+    const PREFIX: &str = "REJECT_S2_PARSE_PREFIX";
+    let mut r = Reader::new(buf);
+    let protocol_version = r.read_u16().map_err(|_| PREFIX)?;
+    let suite_id = r.read_u16().map_err(|_| PREFIX)?;
+    let msg_type = r.read_u8().map_err(|_| PREFIX)?;
+    let _env_flags = r.read_u8().map_err(|_| PREFIX)?;
+    let header_len = r.read_u16().map_err(|_| PREFIX)? as usize;
+    let body_len = r.read_u16().map_err(|_| PREFIX)? as usize;
+
+    let params = types::suite2_params(suite_id).ok_or(PREFIX)?;
+    if protocol_version != types::SUITE2_PROTOCOL_VERSION || msg_type != 0x02 {
+        return Err(PREFIX);
     }
-    let header = &buf[off..off + header_len];
-    off += header_len;
-    let body = &buf[off..off + body_len];
-    if off + body_len != buf.len() {
-        return Err("REJECT_S2_PARSE_PREFIX");
+
+    if r.remaining() < header_len + body_len {
+        return Err(PREFIX);
     }
+    let header = r.read_slice(header_len).map_err(|_| PREFIX)?;
+    let body = r.read_slice(body_len).map_err(|_| PREFIX)?;
+    r.finish().map_err(|_| PREFIX)?;
 
-    let (mut parsed, used) = parse_ratchet_header(header)?;
+    let (mut parsed, used) = parse_ratchet_header(header, params)?;
     if used != header.len() {
         return Err("REJECT_S2_PARSE_HDR_LEN");
     }
     if body.len() < 16 {
         return Err("REJECT_S2_PARSE_BODY_LEN");
     }
-    parsed.body_ct = body.to_vec();
+    parsed.body_ct = body;
 
     Ok((protocol_version, suite_id, msg_type, parsed))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(flags: u16) -> Vec<u8> {
+        header_for(flags, &types::SUITE2_PARAMS)
+    }
+
+    fn header_for(flags: u16, params: &Suite2Params) -> Vec<u8> {
+        let mut h = vec![0x11; 32];
+        h.extend_from_slice(&flags.to_be_bytes());
+        if flags & types::FLAG_PQ_ADV != 0 {
+            h.extend_from_slice(&7u32.to_be_bytes());
+            h.extend_from_slice(&vec![0x22; params.kem_pub_len]);
+        }
+        if flags & types::FLAG_PQ_CTXT != 0 {
+            h.extend_from_slice(&9u32.to_be_bytes());
+            h.extend_from_slice(&vec![0x33; params.kem_ct_len]);
+        }
+        h.extend_from_slice(&[0x44; 24]);
+        h
+    }
+
+    fn wire(flags: u16) -> Vec<u8> {
+        wire_for(flags, &types::SUITE2_PARAMS, types::SUITE2_SUITE_ID)
+    }
+
+    fn wire_for(flags: u16, params: &Suite2Params, suite_id: u16) -> Vec<u8> {
+        let hdr = header_for(flags, params);
+        let body = [0x55; 16];
+        let mut w = Vec::new();
+        w.extend_from_slice(&types::SUITE2_PROTOCOL_VERSION.to_be_bytes());
+        w.extend_from_slice(&suite_id.to_be_bytes());
+        w.push(0x02);
+        w.push(0x00);
+        w.extend_from_slice(&(hdr.len() as u16).to_be_bytes());
+        w.extend_from_slice(&(body.len() as u16).to_be_bytes());
+        w.extend_from_slice(&hdr);
+        w.extend_from_slice(&body);
+        w
+    }
+
+    fn assert_same(owned: &Suite2ParsedRatchetMsg, borrowed: &Suite2ParsedRatchetMsgRef<'_>) {
+        assert_eq!(owned.dh_pub, borrowed.dh_pub);
+        assert_eq!(owned.flags, borrowed.flags);
+        assert_eq!(owned.pq_prefix, borrowed.pq_prefix);
+        assert_eq!(owned.pq_adv_id, borrowed.pq_adv_id);
+        assert_eq!(owned.pq_adv_pub.as_deref(), borrowed.pq_adv_pub);
+        assert_eq!(owned.pq_target_id, borrowed.pq_target_id);
+        assert_eq!(owned.pq_ct.as_deref(), borrowed.pq_ct);
+        assert_eq!(owned.hdr_ct, borrowed.hdr_ct);
+        assert_eq!(owned.body_ct, borrowed.body_ct);
+    }
+
+    #[test]
+    fn borrowed_wire_parse_matches_owned_for_every_prefix() {
+        let boundary = types::FLAG_BOUNDARY;
+        for flags in [
+            0,
+            boundary,
+            boundary | types::FLAG_PQ_ADV,
+            boundary | types::FLAG_PQ_CTXT,
+            boundary | types::FLAG_PQ_ADV | types::FLAG_PQ_CTXT,
+        ] {
+            let full = wire(flags);
+            for end in 0..=full.len() {
+                let buf = &full[..end];
+                match (decode_suite2_wire(buf), decode_suite2_wire_ref(buf)) {
+                    (Ok((pv, sid, mt, owned)), Ok((pv_r, sid_r, mt_r, borrowed))) => {
+                        assert_eq!((pv, sid, mt), (pv_r, sid_r, mt_r));
+                        assert_same(&owned, &borrowed);
+                    }
+                    (Err(a), Err(b)) => assert_eq!(a, b, "flags {flags:#x} len {end}"),
+                    _ => panic!("owned/borrowed disagree: flags {flags:#x} len {end}"),
+                }
+            }
+            assert!(decode_suite2_wire_ref(&full).is_ok(), "flags {flags:#x}");
+        }
+    }
+
+    #[test]
+    fn borrowed_pq_prefix_is_the_contiguous_header_run() {
+        let mut msg = header(types::FLAG_BOUNDARY | types::FLAG_PQ_ADV | types::FLAG_PQ_CTXT);
+        msg.extend_from_slice(&[0x55; 16]);
+        let parsed = decode_suite2_ratchet_message_ref(&msg).expect("parse");
+        assert_eq!(parsed.pq_prefix, &msg[34..34 + 4 + 1184 + 4 + 1088]);
+        assert_eq!(parsed.pq_adv_id, Some(7));
+        assert_eq!(parsed.pq_target_id, Some(9));
+        assert_eq!(parsed.body_ct, &[0x55; 16]);
+        assert!(std::ptr::eq(
+            parsed.hdr_ct.as_ptr(),
+            msg[34 + 2280..].as_ptr()
+        ));
+        assert_same(
+            &decode_suite2_ratchet_message(&msg).expect("parse"),
+            &parsed,
+        );
+    }
+
+    #[test]
+    fn pq_prefix_sizes_follow_the_wire_suite_id() {
+        let all = types::FLAG_BOUNDARY | types::FLAG_PQ_ADV | types::FLAG_PQ_CTXT;
+        let w = wire(all);
+        let (_, sid, _, parsed) = decode_suite2_wire_ref(&w).expect("suite2 wire");
+        assert_eq!(sid, types::SUITE2_SUITE_ID);
+        assert_eq!(parsed.pq_adv_pub.map(<[u8]>::len), Some(1184));
+        assert_eq!(parsed.pq_ct.map(<[u8]>::len), Some(1088));
+        assert!(decode_suite2_wire_for(&w, types::SUITE2_SUITE_ID).is_ok());
+        assert_eq!(
+            decode_suite2_wire_for(&w, 0x0003).err(),
+            Some("REJECT_S2_PARSE_PREFIX")
+        );
+
+        // PQ fields sized for a larger KEM than the wire's suite uses.
+        let larger = Suite2Params {
+            kem_pub_len: 1568,
+            kem_ct_len: 1568,
+            ..types::SUITE2_PARAMS
+        };
+        for flags in [types::FLAG_BOUNDARY | types::FLAG_PQ_ADV, all] {
+            let long = wire_for(flags, &larger, types::SUITE2_SUITE_ID);
+            assert_eq!(
+                decode_suite2_wire(&long).err(),
+                Some("REJECT_S2_PARSE_HDR_LEN"),
+                "flags {flags:#x}"
+            );
+        }
+
+        for unknown_id in [0x0001u16, 0x0003, 0x0004] {
+            let mut unknown = w.clone();
+            unknown[2..4].copy_from_slice(&unknown_id.to_be_bytes());
+            assert_eq!(
+                decode_suite2_wire(&unknown).err(),
+                Some("REJECT_S2_PARSE_PREFIX")
+            );
+        }
+    }
+}
@@ -5,11 +5,10 @@ use crate::crypto::traits::{Aead, CryptoError, Hash, Kmac, X25519Dh, X25519Priv,
 use std::cell::Cell;
 use std::collections::{BTreeSet, HashSet};
 
+use crate::suite2::limits::Suite2Limits;
+use crate::suite2::reject::Suite2RejectReason;
 use crate::suite2::{binding, parse, scka, types};
 
-const MAX_HEADER_ATTEMPTS: usize = 100;
-const MAX_SKIP: u32 = 1000;
-const MAX_MKSKIPPED: usize = 1000;
 const HDR_CT_LEN: usize = 24;
 const BODY_CT_MIN: usize = 16;
 const REJECT_S2_CHAINKEY_UNSET: &str =
@@ -161,8 +160,9 @@ fn pq0_recv_label(role_is_a: bool) -> &'static str {
     }
 }
 
-fn evict_mkskipped(mut entries: Vec<MkSkippedEntry>) -> Vec<MkSkippedEntry> {
-    if entries.len() <= MAX_MKSKIPPED {
+/// Keep at most `cap` skipped keys, evicting the oldest (lowest `n`) first.
+pub(crate) fn evict_mkskipped(mut entries: Vec<MkSkippedEntry>, cap: usize) -> Vec<MkSkippedEntry> {
+    if entries.len() <= cap {
         return entries;
     }
     entries.sort_by(|a, b| {
@@ -172,18 +172,42 @@ fn evict_mkskipped(mut entries: Vec<MkSkippedEntry>) -> Vec<MkSkippedEntry> {
         }
         a.dh_pub.cmp(&b.dh_pub)
     });
-    let excess = entries.len().saturating_sub(MAX_MKSKIPPED);
+    let excess = entries.len().saturating_sub(cap);
     if excess > 0 {
         entries.drain(0..excess);
     }
     entries
 }
 
+/// Drop skipped keys that have outlived `limits`' DH-step or age bound as of `dh_epoch` /
+/// `now_secs`. Without either bound this is the identity.
+pub(crate) fn expire_mkskipped(
+    mut entries: Vec<MkSkippedEntry>,
+    limits: &Suite2Limits,
+    dh_epoch: u32,
+    now_secs: u64,
+) -> Vec<MkSkippedEntry> {
+    entries.retain(|e| {
+        let steps_ok = limits
+            .mkskipped_max_dh_steps()
+            .is_none_or(|max| dh_epoch.saturating_sub(e.dh_epoch) < max);
+        let age_ok = limits
+            .mkskipped_max_age_secs()
+            .is_none_or(|max| now_secs.saturating_sub(e.stored_at) < max);
+        steps_ok && age_ok
+    });
+    entries
+}
+
 #[derive(Clone)]
 pub struct MkSkippedEntry {
     pub dh_pub: [u8; 32],
     pub n: u32,
     pub mk: [u8; 32],
+    /// `Suite2RecvWireState.dh_epoch` when the key was stored.
+    pub dh_epoch: u32,
+    /// `Suite2RecvWireState.now_secs` (caller clock) when the key was stored.
+    pub stored_at: u64,
 }
 
 #[derive(Clone)]
@@ -314,6 +338,9 @@ pub fn derive_mk_step(
     Ok((ck_ec_p, ck_pq_p, mk))
 }
 
+/// Non-boundary receive with out-of-order recovery, bounded by `limits` (skip gap, retained
+/// skipped keys, header trial decryptions).
+#[allow(clippy::too_many_arguments)]
 pub fn recv_nonboundary_ooo(
     hash: &dyn Hash,
     kmac: &dyn Kmac,
@@ -322,7 +349,10 @@ pub fn recv_nonboundary_ooo(
     flags: u16,
     hdr_ct: &[u8],
     body_ct: &[u8],
+    limits: &Suite2Limits,
 ) -> RecvOutcome {
+    let max_skip = limits.max_skip();
+    let max_attempts = limits.max_header_attempts();
     if flags != 0 {
         return RecvOutcome {
             state: st,
@@ -381,7 +411,7 @@ pub fn recv_nonboundary_ooo(
     let mut attempts: usize = 0;
     macro_rules! try_candidate {
         ($cand:expr) => {{
-            if attempts >= MAX_HEADER_ATTEMPTS || header_pt.is_some() {
+            if attempts >= max_attempts || header_pt.is_some() {
                 false
             } else {
                 let cand = $cand;
@@ -412,17 +442,17 @@ pub fn recv_nonboundary_ooo(
         }};
     }
 
-    let mut seen: HashSet<u32> = HashSet::with_capacity(MAX_HEADER_ATTEMPTS);
+    let mut seen: HashSet<u32> = HashSet::with_capacity(max_attempts);
     macro_rules! try_unique {
         ($cand:expr) => {{
-            if attempts >= MAX_HEADER_ATTEMPTS || header_pt.is_some() {
+            if attempts >= max_attempts || header_pt.is_some() {
                 true
             } else {
                 let cand = $cand;
                 if seen.insert(cand) {
                     let _ = try_candidate!(cand);
                 }
-                attempts >= MAX_HEADER_ATTEMPTS || header_pt.is_some()
+                attempts >= max_attempts || header_pt.is_some()
             }
         }};
     }
@@ -431,12 +461,10 @@ pub fn recv_nonboundary_ooo(
     let mut seed_candidates = vec![
         st.nr,
         st.nr.saturating_add(1),
-        st.nr.saturating_add(MAX_SKIP),
-        st.nr.saturating_add(MAX_SKIP + 1),
+        st.nr.saturating_add(max_skip),
+        st.nr.saturating_add(max_skip.saturating_add(1)),
+        st.nr.saturating_add(max_skip - 1),
     ];
-    if MAX_SKIP > 0 {
-        seed_candidates.push(st.nr.saturating_add(MAX_SKIP - 1));
-    }
     if st.nr > 0 {
         seed_candidates.push(st.nr.saturating_sub(1));
     }
@@ -447,7 +475,7 @@ pub fn recv_nonboundary_ooo(
     }
 
     // Prefer most-recent skipped keys first for OOO recovery under capped work.
-    if header_pt.is_none() && attempts < MAX_HEADER_ATTEMPTS {
+    if header_pt.is_none() && attempts < max_attempts {
         for entry in st.mkskipped.iter().rev() {
             if try_unique!(entry.n) {
                 break;
@@ -456,9 +484,9 @@ pub fn recv_nonboundary_ooo(
     }
 
     // Backward window next so replay cases inside window normalize correctly.
-    if header_pt.is_none() && attempts < MAX_HEADER_ATTEMPTS {
+    if header_pt.is_none() && attempts < max_attempts {
         let mut back = st.nr;
-        let back_start = st.nr.saturating_sub(MAX_SKIP);
+        let back_start = st.nr.saturating_sub(max_skip);
         while back > back_start {
             back = back.saturating_sub(1);
             if try_unique!(back) {
@@ -468,8 +496,8 @@ pub fn recv_nonboundary_ooo(
     }
 
     // Finally probe forward window.
-    if header_pt.is_none() && attempts < MAX_HEADER_ATTEMPTS {
-        let max_forward = st.nr.saturating_add(MAX_SKIP);
+    if header_pt.is_none() && attempts < max_attempts {
+        let max_forward = st.nr.saturating_add(max_skip);
         let mut fwd = st.nr.saturating_add(2);
         while fwd < max_forward {
             if try_unique!(fwd) {
@@ -537,7 +565,7 @@ pub fn recv_nonboundary_ooo(
             n: Some(header_n),
         };
     }
-    if header_n - st.nr > MAX_SKIP {
+    if header_n - st.nr > max_skip {
         return RecvOutcome {
             state: st,
             ok: false,
@@ -580,10 +608,13 @@ pub fn recv_nonboundary_ooo(
             }
         };
         if i < header_n {
+            // Stamped by the wire-level caller (`recv_wire`), which owns the epoch and clock.
             staged.push(MkSkippedEntry {
                 dh_pub: st.dh_pub,
                 n: i,
                 mk,
+                dh_epoch: 0,
+                stored_at: 0,
             });
         } else {
             mk_n = Some(mk);
@@ -629,7 +660,7 @@ pub fn recv_nonboundary_ooo(
             new_state.ck_pq = ck_pq;
             new_state.nr = nr_next;
             new_state.mkskipped.extend(staged);
-            new_state.mkskipped = evict_mkskipped(new_state.mkskipped);
+            new_state.mkskipped = evict_mkskipped(new_state.mkskipped, limits.max_mkskipped());
             RecvOutcome {
                 state: new_state,
                 ok: true,
@@ -827,9 +858,10 @@ pub fn recv_boundary_in_order(
         }
     };
 
-    let apply = match scka::apply_pq_reseed(
+    let apply = match scka::apply_pq_reseed_for_suite(
         hash,
         kmac,
+        st.suite_id,
         st.role_is_a,
         &st.rk,
         &parsed.pq_ct,
@@ -947,6 +979,20 @@ pub struct Suite2RecvWireState {
     pub consumed_targets: BTreeSet<u32>,
     pub tombstoned_targets: BTreeSet<u32>,
     pub mkskipped: Vec<MkSkippedEntry>,
+    /// Receive-path bounds for this session; persisted with the snapshot.
+    pub limits: Suite2Limits,
+    /// Receive DH ratchet steps taken; stamps skipped keys for `mkskipped_max_dh_steps`.
+    pub dh_epoch: u32,
+    /// Latest caller-reported time (seconds; `Suite2SessionState::expire_mkskipped`). Skipped
+    /// keys are stamped with it for `mkskipped_max_age_secs`; the API itself reads no clock.
+    pub now_secs: u64,
+}
+
+/// One receive DH ratchet step: advance the epoch and drop skipped keys past the step bound.
+fn step_recv_dh_epoch(recv: &mut Suite2RecvWireState) {
+    recv.dh_epoch = recv.dh_epoch.saturating_add(1);
+    let mk = std::mem::take(&mut recv.mkskipped);
+    recv.mkskipped = expire_mkskipped(mk, &recv.limits, recv.dh_epoch, recv.now_secs);
 }
 
 pub struct RecvWireOutcome {
@@ -1091,7 +1137,7 @@ pub fn recv_wire(
     pq_epoch_ss: Option<&[u8]>,
     peer_adv_id: Option<u32>,
 ) -> Result<RecvWireOutcome, &'static str> {
-    let (_pv, _sid, _msg_type, parsed) = parse::decode_suite2_wire(wire)?;
+    let (_pv, _sid, _msg_type, parsed) = parse::decode_suite2_wire_for(wire, st.suite_id)?;
     let flags = parsed.flags;
 
     if flags == 0 {
@@ -1114,6 +1160,7 @@ pub fn recv_wire(
             flags,
             &parsed.hdr_ct,
             &parsed.body_ct,
+            &st.limits,
         );
         if !out.ok {
             return Err(out.reason.unwrap_or("REJECT_S2_HDR_AUTH_FAIL"));
@@ -1123,6 +1170,13 @@ pub fn recv_wire(
         new_state.ck_pq_recv = out.state.ck_pq;
         new_state.nr = out.state.nr;
         new_state.mkskipped = out.state.mkskipped;
+        // Keys staged by this receive are the ones at or past the old Nr on this chain.
+        for e in new_state.mkskipped.iter_mut() {
+            if e.dh_pub == parsed.dh_pub && e.n >= st.nr {
+                e.dh_epoch = st.dh_epoch;
+                e.stored_at = st.now_secs;
+            }
+        }
         return Ok(RecvWireOutcome {
             state: new_state,
             rk: *rk,
@@ -1405,7 +1459,7 @@ pub fn recv_dh_boundary(
         };
     }
 
-    let (_pv, _sid, _mt, parsed) = match parse::decode_suite2_wire(wire) {
+    let (_pv, _sid, _mt, parsed) = match parse::decode_suite2_wire_for(wire, st.recv.suite_id) {
         Ok(v) => v,
         Err(code) => reject!(st, code),
     };
@@ -1520,6 +1574,7 @@ pub fn recv_dh_boundary(
     new.recv.ck_ec = ck_ec_p;
     new.recv.ck_pq_recv = ck_pq_p;
     new.recv.nr = 1;
+    step_recv_dh_epoch(&mut new.recv);
     RecvDhBoundaryOutcome {
         state: new,
         plaintext: pt,
@@ -1546,9 +1601,11 @@ pub fn recv_dh_boundary(
 // root), closing the NA-0623 deviation. The ADV header stays `HK` (§8.5.4 states no CURRENT_NHK
 // step and an ADV advances no root; the §8.5.1/§8.5.4 textual tension is recorded in DOC-G5-008).
 
-const PQ_ADV_PUB_LEN: usize = 1184; // ML-KEM-768 public key (DOC-CAN-004 §1.3)
-const MLKEM768_CT_LEN: usize = 1088; // ML-KEM-768 ciphertext (DOC-CAN-004 §1.3)
-const MLKEM768_SS_LEN: usize = 32; // ML-KEM-768 shared secret (DOC-CAN-004 §1.3)
+// The ML-KEM public key / ciphertext / shared-secret lengths (DOC-CAN-004 §1.3) come from the
+// session's suite (`Suite2Params`).
+fn session_params(suite_id: u16) -> Result<&'static types::Suite2Params, &'static str> {
+    types::suite2_params(suite_id).ok_or("REJECT_S2_LOCAL_UNSUPPORTED")
+}
 
 pub struct SendPqAdvertiseOutcome {
     pub state: crate::suite2::state::Suite2SessionState,
@@ -1606,7 +1663,7 @@ pub fn send_pq_advertise(
     pq_adv_pub: &[u8],
     plaintext: &[u8],
 ) -> Result<SendPqAdvertiseOutcome, &'static str> {
-    if pq_adv_pub.len() != PQ_ADV_PUB_LEN {
+    if pq_adv_pub.len() != session_params(st.send.suite_id)?.kem_pub_len {
         return Err("REJECT_SCKA_ADV_BAD_PUB_LEN");
     }
     // Strictly-increasing allocation (DOC-CAN-004 §3.1 step 1): the id must exceed every id we
@@ -1630,7 +1687,7 @@ pub fn send_pq_advertise(
         .map_err(|_| "REJECT_S2_LOCAL_UNSUPPORTED")?;
 
     let flags = types::FLAG_PQ_ADV | types::FLAG_BOUNDARY;
-    let mut pq_prefix = Vec::with_capacity(4 + PQ_ADV_PUB_LEN);
+    let mut pq_prefix = Vec::with_capacity(4 + pq_adv_pub.len());
     pq_prefix.extend_from_slice(&pq_adv_id.to_be_bytes());
     pq_prefix.extend_from_slice(pq_adv_pub);
 
@@ -1700,7 +1757,7 @@ pub fn send_pq_advertise(
 /// DOC-CAN-003 §8.5.3 (sender/encapsulator side) / DOC-CAN-004 §3.3: SCKA PQ-reseed SEND
 /// (boundary with `FLAG_PQ_CTXT`).
 ///
-/// The caller has already run `MLKEM768.Encap(peer_adv_pub) -> (pq_ct, pq_epoch_ss)` on the peer's
+/// The caller has already run `ML-KEM.Encap(peer_adv_pub) -> (pq_ct, pq_epoch_ss)` on the peer's
 /// advertised public key and passes the ciphertext + shared secret in; `pq_target_id` is the peer
 /// advertisement id being targeted. The exact structural mirror of `recv_boundary_in_order`'s PQ
 /// path: derive the directional PQ seeds from `RK_old` (reused `kdf_pq_reseed_seeds`, so both
@@ -1719,10 +1776,11 @@ pub fn send_pq_reseed(
     pq_epoch_ss: &[u8],
     plaintext: &[u8],
 ) -> Result<SendPqReseedOutcome, &'static str> {
-    if pq_ct.len() != MLKEM768_CT_LEN {
+    let params = session_params(st.send.suite_id)?;
+    if pq_ct.len() != params.kem_ct_len {
         return Err("REJECT_SCKA_CTXT_BAD_CT_LEN");
     }
-    if pq_epoch_ss.len() != MLKEM768_SS_LEN {
+    if pq_epoch_ss.len() != params.kem_ss_len {
         return Err("REJECT_SCKA_CTXT_BAD_SS_LEN");
     }
     if is_zero32(&st.send.ck_ec) || is_zero32(&st.send.ck_pq) {
@@ -1770,7 +1828,7 @@ pub fn send_pq_reseed(
         .map_err(|_| "REJECT_S2_LOCAL_UNSUPPORTED")?;
 
     let flags = types::FLAG_PQ_CTXT | types::FLAG_BOUNDARY;
-    let mut pq_prefix = Vec::with_capacity(4 + MLKEM768_CT_LEN);
+    let mut pq_prefix = Vec::with_capacity(4 + pq_ct.len());
     pq_prefix.extend_from_slice(&pq_target_id.to_be_bytes());
     pq_prefix.extend_from_slice(pq_ct);
 
@@ -1854,7 +1912,7 @@ pub struct SendCombinedBoundaryOutcome {
 ///
 /// The fresh X25519 keypair is CALLER-supplied (the SCKA pure-function precedent: no key
 /// generation inside refimpl fns), which makes the sender deterministic and vector-pinnable.
-/// The caller has already run `MLKEM768.Encap(peer_adv_pub) -> (pq_ct, pq_epoch_ss)`.
+/// The caller has already run `ML-KEM.Encap(peer_adv_pub) -> (pq_ct, pq_epoch_ss)`.
 /// Fail-closed with no state mutation on any reject; no new reason code.
 #[allow(clippy::too_many_arguments)]
 pub fn send_combined_boundary(
@@ -1870,10 +1928,11 @@ pub fn send_combined_boundary(
     pq_epoch_ss: &[u8],
     plaintext: &[u8],
 ) -> Result<SendCombinedBoundaryOutcome, &'static str> {
-    if pq_ct.len() != MLKEM768_CT_LEN {
+    let params = session_params(st.send.suite_id)?;
+    if pq_ct.len() != params.kem_ct_len {
         return Err("REJECT_SCKA_CTXT_BAD_CT_LEN");
     }
-    if pq_epoch_ss.len() != MLKEM768_SS_LEN {
+    if pq_epoch_ss.len() != params.kem_ss_len {
         return Err("REJECT_SCKA_CTXT_BAD_SS_LEN");
     }
     // A combined boundary CREATES a fresh send chain from KDF_RK_DH (mirrors send_boundary: the
@@ -1928,7 +1987,7 @@ pub fn send_combined_boundary(
         derive_mk_step(kmac, &ck_ec0, &ck_pq0).map_err(|_| "REJECT_S2_LOCAL_UNSUPPORTED")?;
 
     let flags = types::FLAG_PQ_CTXT | types::FLAG_BOUNDARY;
-    let mut pq_prefix = Vec::with_capacity(4 + MLKEM768_CT_LEN);
+    let mut pq_prefix = Vec::with_capacity(4 + pq_ct.len());
     pq_prefix.extend_from_slice(&pq_target_id.to_be_bytes());
     pq_prefix.extend_from_slice(pq_ct);
 
@@ -2148,7 +2207,11 @@ pub fn recv_pq_adv(
 
     // DOC-CAN-004 §3.2 track rules (length + monotonicity against the caller-owned peer-ADV
     // watermark), existing reason codes. The advanced watermark is caller-persisted.
-    if let Err(code) = track_peer_adv(peer_adv_watermark, pq_adv_id, pq_adv_pub) {
+    let params = match session_params(st.suite_id) {
+        Ok(v) => v,
+        Err(code) => reject!(st, code, Some(header_pn), Some(n)),
+    };
+    if let Err(code) = track_peer_adv_for(params, peer_adv_watermark, pq_adv_id, pq_adv_pub) {
         reject!(st, code, Some(header_pn), Some(n));
     }
 
@@ -2182,7 +2245,22 @@ pub fn track_peer_adv(
     peer_adv_id: u32,
     peer_adv_pub: &[u8],
 ) -> Result<u32, &'static str> {
-    if peer_adv_pub.len() != PQ_ADV_PUB_LEN {
+    track_peer_adv_for(
+        &types::SUITE2_PARAMS,
+        peer_max_adv_id_seen,
+        peer_adv_id,
+        peer_adv_pub,
+    )
+}
+
+/// `track_peer_adv` with the public-key length of `params`' KEM.
+pub fn track_peer_adv_for(
+    params: &types::Suite2Params,
+    peer_max_adv_id_seen: u32,
+    peer_adv_id: u32,
+    peer_adv_pub: &[u8],
+) -> Result<u32, &'static str> {
+    if peer_adv_pub.len() != params.kem_pub_len {
         return Err("REJECT_SCKA_ADV_BAD_PUB_LEN");
     }
     if peer_adv_id <= peer_max_adv_id_seen {
@@ -2210,6 +2288,25 @@ pub struct RecvSessionOutcome {
     pub n: Option<u32>,
 }
 
+macro_rules! impl_reject_reason {
+    ($($outcome:ty),+) => {
+        $(impl $outcome {
+            /// `reason` as a typed `Suite2RejectReason` (`None` on success).
+            pub fn reject_reason(&self) -> Option<Suite2RejectReason> {
+                self.reason.and_then(Suite2RejectReason::from_code)
+            }
+        })+
+    };
+}
+
+impl_reject_reason!(
+    RecvOutcome,
+    BoundaryOutcome,
+    RecvDhBoundaryOutcome,
+    RecvPqAdvOutcome,
+    RecvSessionOutcome
+);
+
 /// NA-0626 (ENG-0030 structural + ENG-0026): session-level SCKA reseed RECEIVE — mirrors
 /// `send_pq_reseed` field-for-field, INCLUDING the send half no caller may ever hold stale
 /// again (`send.hk_s` from the advanced root, `send.ck_pq` from the send-direction seed). Also
@@ -2247,7 +2344,7 @@ pub fn recv_pq_reseed(
         };
     }
 
-    let (_pv, _sid, _mt, parsed) = match parse::decode_suite2_wire(wire) {
+    let (_pv, _sid, _mt, parsed) = match parse::decode_suite2_wire_for(wire, st.recv.suite_id) {
         Ok(v) => v,
         Err(code) => reject!(st, code, None, None),
     };
@@ -2452,9 +2549,10 @@ fn recv_combined_boundary(
 
     // ...then the PQ reseed from RK_dh (the frozen DOC-CAN-004 §3.4 validation + §3.3.6 seeds,
     // with RK_old := RK_dh — the §8.5.2-then-§8.5.3 composition in which both step lists hold).
-    let apply = match scka::apply_pq_reseed(
+    let apply = match scka::apply_pq_reseed_for_suite(
         hash,
         kmac,
+        st.recv.suite_id,
         role_is_a,
         &rk_dh,
         pq_ct,
@@ -2511,6 +2609,7 @@ fn recv_combined_boundary(
     new.recv.ck_pq_send = apply.ck_pq_send_after;
     new.recv.ck_pq_recv = apply.ck_pq_recv_after;
     new.recv.nr = 1;
+    step_recv_dh_epoch(&mut new.recv);
     new.recv.peer_max_adv_id_seen = apply.peer_max_adv_id_seen_after;
     new.recv.consumed_targets = apply.consumed_targets_after;
     new.recv.tombstoned_targets = apply.tombstoned_targets_after;
@@ -2552,7 +2651,7 @@ pub fn recv_pq_adv_session(
         };
     }
 
-    let (_pv, _sid, _mt, parsed) = match parse::decode_suite2_wire(wire) {
+    let (_pv, _sid, _mt, parsed) = match parse::decode_suite2_wire_for(wire, st.recv.suite_id) {
         Ok(v) => v,
         Err(code) => reject!(st, code, None, None),
     };
@@ -2610,9 +2709,15 @@ mod tests {
     use super::*;
     use crate::crypto::stdcrypto::StdCrypto;
     use crate::crypto::traits::CryptoError;
+    use crate::suite2::limits::{
+        DEFAULT_MAX_HEADER_ATTEMPTS as MAX_HEADER_ATTEMPTS, DEFAULT_MAX_SKIP as MAX_SKIP,
+    };
     use crate::suite2::types;
     use rand_core::{OsRng, RngCore};
 
+    const MLKEM768_CT_LEN: usize = crate::qsp::SZ_MLKEM768_CT;
+    const MLKEM768_SS_LEN: usize = 32;
+
     fn snapshot_boundary_state(st: &Suite2BoundaryState) -> Vec<u8> {
         let mut out = Vec::new();
         out.extend_from_slice(&st.session_id);
@@ -3208,8 +3313,26 @@ mod tests {
         let hdr_ct = vec![0u8; HDR_CT_LEN];
         let body_ct = vec![0u8; BODY_CT_MIN];
         let pre = snapshot_recv_state(&st);
-        let out1 = recv_nonboundary_ooo(&c, &c, &RejectAead, st.clone(), flags, &hdr_ct, &body_ct);
-        let out2 = recv_nonboundary_ooo(&c, &c, &RejectAead, st.clone(), flags, &hdr_ct, &body_ct);
+        let out1 = recv_nonboundary_ooo(
+            &c,
+            &c,
+            &RejectAead,
+            st.clone(),
+            flags,
+            &hdr_ct,
+            &body_ct,
+            &Suite2Limits::default(),
+        );
+        let out2 = recv_nonboundary_ooo(
+            &c,
+            &c,
+            &RejectAead,
+            st.clone(),
+            flags,
+            &hdr_ct,
+            &body_ct,
+            &Suite2Limits::default(),
+        );
         assert!(!out1.ok);
         let err1 = out1.reason.unwrap_or("");
         let err2 = out2.reason.unwrap_or("");
@@ -3236,8 +3359,26 @@ mod tests {
         let hdr_ct = vec![0u8; HDR_CT_LEN];
         let body_ct = vec![0u8; BODY_CT_MIN];
         let pre = snapshot_recv_state(&st);
-        let out1 = recv_nonboundary_ooo(&c, &c, &RejectAead, st.clone(), flags, &hdr_ct, &body_ct);
-        let out2 = recv_nonboundary_ooo(&c, &c, &RejectAead, st.clone(), flags, &hdr_ct, &body_ct);
+        let out1 = recv_nonboundary_ooo(
+            &c,
+            &c,
+            &RejectAead,
+            st.clone(),
+            flags,
+            &hdr_ct,
+            &body_ct,
+            &Suite2Limits::default(),
+        );
+        let out2 = recv_nonboundary_ooo(
+            &c,
+            &c,
+            &RejectAead,
+            st.clone(),
+            flags,
+            &hdr_ct,
+            &body_ct,
+            &Suite2Limits::default(),
+        );
         assert!(!out1.ok);
         assert_eq!(out1.reason, out2.reason);
         assert_eq!(pre, snapshot_recv_state(&out1.state));
@@ -3279,6 +3420,8 @@ mod tests {
                 dh_pub: [0x22; 32],
                 n: 5,
                 mk,
+                dh_epoch: 0,
+                stored_at: 0,
             }],
         };
         let flags = 0;
@@ -3287,8 +3430,26 @@ mod tests {
         let aead = MkSkippedHeaderAead { pn: 0, n: 5 };
 
         let pre = snapshot_recv_state(&st);
-        let out1 = recv_nonboundary_ooo(&c, &c, &aead, st.clone(), flags, &hdr_ct, &body_ct);
-        let out2 = recv_nonboundary_ooo(&c, &c, &aead, st.clone(), flags, &hdr_ct, &body_ct);
+        let out1 = recv_nonboundary_ooo(
+            &c,
+            &c,
+            &aead,
+            st.clone(),
+            flags,
+            &hdr_ct,
+            &body_ct,
+            &Suite2Limits::default(),
+        );
+        let out2 = recv_nonboundary_ooo(
+            &c,
+            &c,
+            &aead,
+            st.clone(),
+            flags,
+            &hdr_ct,
+            &body_ct,
+            &Suite2Limits::default(),
+        );
         assert!(!out1.ok);
         assert_eq!(out1.reason, Some("REJECT_S2_BODY_AUTH_FAIL"));
         assert_eq!(out1.reason, out2.reason);
@@ -3314,7 +3475,16 @@ mod tests {
         let flags = 0;
         let hdr_ct = vec![0u8; HDR_CT_LEN];
         let body_ct = vec![0u8; BODY_CT_MIN];
-        let out = recv_nonboundary_ooo(&c, &c, &AcceptAead, st.clone(), flags, &hdr_ct, &body_ct);
+        let out = recv_nonboundary_ooo(
+            &c,
+            &c,
+            &AcceptAead,
+            st.clone(),
+            flags,
+            &hdr_ct,
+            &body_ct,
+            &Suite2Limits::default(),
+        );
         assert!(out.ok);
         let count = S2_HDR_TRY_COUNT_NONBOUNDARY.with(|c| c.get());
         assert_eq!(count, 1);
@@ -3339,7 +3509,16 @@ mod tests {
         let body_ct = vec![0u8; BODY_CT_MIN];
         let pre = snapshot_recv_state(&st);
         S2_HDR_TRY_COUNT_NONBOUNDARY.with(|c| c.set(0));
-        let out = recv_nonboundary_ooo(&c, &c, &RejectAead, st.clone(), flags, &hdr_ct, &body_ct);
+        let out = recv_nonboundary_ooo(
+            &c,
+            &c,
+            &RejectAead,
+            st.clone(),
+            flags,
+            &hdr_ct,
+            &body_ct,
+            &Suite2Limits::default(),
+        );
         let count = S2_HDR_TRY_COUNT_NONBOUNDARY.with(|c| c.get());
         assert!(!out.ok);
         assert_eq!(out.reason, Some("REJECT_S2_HDR_AUTH_FAIL"));
@@ -3389,7 +3568,16 @@ mod tests {
         let body_ct = &wire[body_ct_start..];
         let pre = snapshot_recv_state(&recv);
         S2_HDR_TRY_COUNT_NONBOUNDARY.with(|c| c.set(0));
-        let out = recv_nonboundary_ooo(&c, &c, &c, recv.clone(), 0, hdr_ct, body_ct);
+        let out = recv_nonboundary_ooo(
+            &c,
+            &c,
+            &c,
+            recv.clone(),
+            0,
+            hdr_ct,
+            body_ct,
+            &Suite2Limits::default(),
+        );
         let count = S2_HDR_TRY_COUNT_NONBOUNDARY.with(|c| c.get());
         assert!(!out.ok);
         assert_eq!(out.reason, Some("REJECT_S2_OOO_BOUNDS"));
@@ -3635,12 +3823,12 @@ mod tests {
     const ALLOWED_UNGUARDED_DH: &[AllowedUnguardedDh] = &[
         AllowedUnguardedDh {
             file: "tools/refimpl/quantumshield_refimpl/src/qsp/handshake.rs",
-            function: "initiator_build",
+            function: "initiator_build_unsigned",
             reason: QSP_LEGACY_REASON,
         },
         AllowedUnguardedDh {
             file: "tools/refimpl/quantumshield_refimpl/src/qsp/handshake.rs",
-            function: "responder_process",
+            function: "responder_respond",
             reason: QSP_LEGACY_REASON,
         },
         AllowedUnguardedDh {
@@ -3688,6 +3876,21 @@ mod tests {
             function: "establish_pair",
             reason: "test scaffolding: establishment secret for a matched pair",
         },
+        AllowedUnguardedDh {
+            file: "tools/refimpl/quantumshield_refimpl/tests/suite2_session_api.rs",
+            function: "establish_pair",
+            reason: "test scaffolding: establishment secret for a matched pair",
+        },
+        AllowedUnguardedDh {
+            file: "tools/refimpl/quantumshield_refimpl/src/crypto/conformance.rs",
+            function: "check_x25519",
+            reason: "provider conformance: output is compared against RFC 7748 answers, never used as key material",
+        },
+        AllowedUnguardedDh {
+            file: "tools/refimpl/quantumshield_refimpl/src/crypto/conformance.rs",
+            function: "check_interop",
+            reason: "provider conformance: two providers' outputs are compared for agreement, never used as key material",
+        },
         AllowedUnguardedDh {
             file: "tools/actors/refimpl_actor_rs/src/main.rs",
             function: "dispatch",
@@ -3700,6 +3903,10 @@ mod tests {
     const PINNED_DH_SITE_COUNTS: &[(&str, usize)] = &[
         ("qsl/qsl-client/qsc/src/handshake/mod.rs", 1),
         ("tools/actors/refimpl_actor_rs/src/main.rs", 1),
+        (
+            "tools/refimpl/quantumshield_refimpl/src/crypto/conformance.rs",
+            4,
+        ),
         (
             "tools/refimpl/quantumshield_refimpl/src/qsp/handshake.rs",
             4,
@@ -3717,6 +3924,10 @@ mod tests {
             "tools/refimpl/quantumshield_refimpl/tests/suite2_scka_sender.rs",
             1,
         ),
+        (
+            "tools/refimpl/quantumshield_refimpl/tests/suite2_session_api.rs",
+            1,
+        ),
     ];
 
     fn repo_root() -> std::path::PathBuf {
@@ -4001,6 +4212,9 @@ mod tests {
                 consumed_targets: BTreeSet::new(),
                 tombstoned_targets: BTreeSet::new(),
                 mkskipped: Vec::new(),
+                limits: Suite2Limits::default(),
+                dh_epoch: 0,
+                now_secs: 0,
             },
             rng32(),
         )
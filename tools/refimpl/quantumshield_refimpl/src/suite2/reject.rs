@@ -0,0 +1,245 @@
+//! Typed Suite-2 / SCKA reject reasons.
+//!
+//! The protocol surfaces rejects as stable `REJECT_S2_*` / `REJECT_SCKA_*` codes (DOC-SCL-002
+//! §5B, the Suite-2 vector sets). `Suite2RejectReason` names every one of them once, with its
+//! wire-stable code string and a coarse category, so callers can match on a failure instead of
+//! comparing strings. The string codes stay the source of truth on every existing surface;
+//! `from_code` is the bridge back.
+
+/// Coarse class of a reject, for callers that handle families of failures alike.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Suite2RejectCategory {
+    /// The input does not parse or has a wrong length/shape.
+    Malformed,
+    /// The input was already accepted or is older than state allows (replay, rollback).
+    Replay,
+    /// A MAC, AEAD, binding or contributory-key check failed.
+    AuthFail,
+    /// Well-formed input that the local session state or policy cannot accept.
+    StateViolation,
+}
+
+macro_rules! reject_reasons {
+    ($($variant:ident => $code:literal, $category:ident;)+) => {
+        /// Every Suite-2 and SCKA reject code. `#[non_exhaustive]`: new codes are additive.
+        #[non_exhaustive]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum Suite2RejectReason {
+            $($variant,)+
+        }
+
+        impl Suite2RejectReason {
+            /// All reasons, in declaration order.
+            pub const ALL: &'static [Suite2RejectReason] = &[$(Suite2RejectReason::$variant,)+];
+
+            /// The stable `REJECT_*` code.
+            pub const fn code(self) -> &'static str {
+                match self {
+                    $(Suite2RejectReason::$variant => $code,)+
+                }
+            }
+
+            pub const fn category(self) -> Suite2RejectCategory {
+                match self {
+                    $(Suite2RejectReason::$variant => Suite2RejectCategory::$category,)+
+                }
+            }
+        }
+    };
+}
+
+reject_reasons! {
+    // Wire parse (DOC-CAN-003 §4.3).
+    ParsePrefix => "REJECT_S2_PARSE_PREFIX", Malformed;
+    ParseHdrLen => "REJECT_S2_PARSE_HDR_LEN", Malformed;
+    ParseBodyLen => "REJECT_S2_PARSE_BODY_LEN", Malformed;
+    ParseFlags => "REJECT_S2_PARSE_FLAGS", Malformed;
+    PqPrefixParse => "REJECT_S2_PQPREFIX_PARSE", Malformed;
+    // Negotiation and establishment.
+    MalformedNegotiation => "REJECT_S2_MALFORMED_NEGOTIATION", Malformed;
+    VersionUnsupported => "REJECT_S2_VERSION_UNSUPPORTED", StateViolation;
+    SuiteMismatch => "REJECT_S2_SUITE_MISMATCH", StateViolation;
+    AlgorithmUnsupported => "REJECT_S2_ALGORITHM_UNSUPPORTED", StateViolation;
+    PeerUnsupported => "REJECT_S2_PEER_UNSUPPORTED", StateViolation;
+    Downgrade => "REJECT_S2_DOWNGRADE", StateViolation;
+    CapabilityCommitmentMismatch => "REJECT_S2_CAPABILITY_COMMITMENT_MISMATCH", AuthFail;
+    EstablishBadMsgType => "REJECT_S2_ESTABLISH_BAD_MSG_TYPE", Malformed;
+    EstablishBadInputLen => "REJECT_S2_ESTABLISH_BAD_INPUT_LEN", Malformed;
+    EstablishUnauthenticated => "REJECT_S2_ESTABLISH_UNAUTHENTICATED", AuthFail;
+    EstablishPqBindMissing => "REJECT_S2_ESTABLISH_PQ_BIND_MISSING", AuthFail;
+    EstablishPqBindMismatch => "REJECT_S2_ESTABLISH_PQ_BIND_MISMATCH", AuthFail;
+    // Ratchet receive.
+    HdrAuthFail => "REJECT_S2_HDR_AUTH_FAIL", AuthFail;
+    BodyAuthFail => "REJECT_S2_BODY_AUTH_FAIL", AuthFail;
+    AdMismatch => "REJECT_S2_AD_MISMATCH", AuthFail;
+    DhNoncontributory => "REJECT_S2_DH_NONCONTRIBUTORY", AuthFail;
+    MkMismatch => "REJECT_S2_MK_MISMATCH", AuthFail;
+    Replay => "REJECT_S2_REPLAY", Replay;
+    OooBounds => "REJECT_S2_OOO_BOUNDS", StateViolation;
+    BoundaryNotInOrder => "REJECT_S2_BOUNDARY_NOT_IN_ORDER", StateViolation;
+    MkBadCkEc => "REJECT_S2_MK_BAD_CK_EC", Malformed;
+    MkBadCkPq => "REJECT_S2_MK_BAD_CK_PQ", Malformed;
+    MkDeriveFail => "REJECT_S2_MK_DERIVE_FAIL", StateViolation;
+    OooDeriveFail => "REJECT_S2_OOO_DERIVE_FAIL", StateViolation;
+    BoundaryDeriveFail => "REJECT_S2_BOUNDARY_DERIVE_FAIL", StateViolation;
+    // Local state.
+    ChainkeyUnset => "REJECT_S2_CHAINKEY_UNSET", StateViolation;
+    CounterOverflow => "REJECT_S2_COUNTER_OVERFLOW", StateViolation;
+    LocalUnsupported => "REJECT_S2_LOCAL_UNSUPPORTED", StateViolation;
+    LocalAeadFail => "REJECT_S2_LOCAL_AEAD_FAIL", StateViolation;
+    NotImplemented => "REJECT_S2_NOT_IMPLEMENTED", StateViolation;
+    // SCKA (DOC-CAN-004).
+    SckaAdvBadPubLen => "REJECT_SCKA_ADV_BAD_PUB_LEN", Malformed;
+    SckaAdvNonmonotonic => "REJECT_SCKA_ADV_NONMONOTONIC", Replay;
+    SckaCtxtBadCtLen => "REJECT_SCKA_CTXT_BAD_CT_LEN", Malformed;
+    SckaCtxtBadSsLen => "REJECT_SCKA_CTXT_BAD_SS_LEN", Malformed;
+    SckaTargetUnknown => "REJECT_SCKA_TARGET_UNKNOWN", StateViolation;
+    SckaTargetConsumed => "REJECT_SCKA_TARGET_CONSUMED", Replay;
+    SckaTargetTombstoned => "REJECT_SCKA_TARGET_TOMBSTONED", Replay;
+    SckaRollbackDetected => "REJECT_SCKA_ROLLBACK_DETECTED", Replay;
+    SckaKemBadD => "REJECT_SCKA_KEM_BAD_D", Malformed;
+    SckaKemBadZ => "REJECT_SCKA_KEM_BAD_Z", Malformed;
+    SckaKemBadM => "REJECT_SCKA_KEM_BAD_M", Malformed;
+    SckaKemBadCt => "REJECT_SCKA_KEM_BAD_CT", Malformed;
+    SckaKemBadDDecap => "REJECT_SCKA_KEM_BAD_D_DECAP", Malformed;
+    SckaKemBadZDecap => "REJECT_SCKA_KEM_BAD_Z_DECAP", Malformed;
+    SckaKemEncapFail => "REJECT_SCKA_KEM_ENCAP_FAIL", Malformed;
+    SckaKemDecapFail => "REJECT_SCKA_KEM_DECAP_FAIL", AuthFail;
+}
+
+impl Suite2RejectReason {
+    /// Look up a code. Accepts the bare code and the `CODE; reason_code=CODE` form some
+    /// entry points return.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let bare = code.split(';').next().unwrap_or("").trim();
+        Self::ALL.iter().copied().find(|r| r.code() == bare)
+    }
+}
+
+impl std::fmt::Display for Suite2RejectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Suite2RejectCategory, Suite2RejectReason};
+    use std::collections::BTreeSet;
+    use std::path::{Path, PathBuf};
+
+    /// Where reject codes are produced or pinned: the refimpl, the actor, the Suite-2 vector
+    /// sets and the registry.
+    const CODE_SOURCES: &[&str] = &[
+        "tools/refimpl/quantumshield_refimpl/src/suite2",
+        "tools/actors/refimpl_actor_rs/src",
+        "inputs/suite2/vectors",
+        "docs/spec-closure/DOC-SCL-002_Shared_Schemas_Error_Reason_Code_Registry_v1.0_DRAFT.md",
+    ];
+
+    fn repo_root() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../..")
+            .canonicalize()
+            .expect("repo root")
+    }
+
+    fn collect(path: &Path, out: &mut Vec<PathBuf>) {
+        if path.is_dir() {
+            for entry in std::fs::read_dir(path).expect("readable dir") {
+                collect(&entry.expect("dir entry").path(), out);
+            }
+        } else if path
+            .extension()
+            .is_some_and(|e| e == "rs" || e == "json" || e == "md")
+        {
+            out.push(path.to_path_buf());
+        }
+    }
+
+    fn codes_in(text: &str) -> BTreeSet<String> {
+        let mut found = BTreeSet::new();
+        for (start, _) in text.match_indices("REJECT_") {
+            let code: String = text[start..]
+                .chars()
+                .take_while(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || *c == '_')
+                .collect();
+            let code = code.trim_end_matches('_');
+            if code.starts_with("REJECT_S2_") || code.starts_with("REJECT_SCKA_") {
+                found.insert(code.to_string());
+            }
+        }
+        found
+    }
+
+    fn corpus_codes() -> BTreeSet<String> {
+        let root = repo_root();
+        let mut files = Vec::new();
+        for src in CODE_SOURCES {
+            collect(&root.join(src), &mut files);
+        }
+        files
+            .iter()
+            .flat_map(|f| codes_in(&std::fs::read_to_string(f).expect("readable file")))
+            .collect()
+    }
+
+    #[test]
+    fn every_code_in_the_tree_has_a_variant() {
+        let unknown: Vec<String> = corpus_codes()
+            .into_iter()
+            .filter(|c| Suite2RejectReason::from_code(c).is_none())
+            .collect();
+        assert!(
+            unknown.is_empty(),
+            "reject codes without a Suite2RejectReason variant: {unknown:?}"
+        );
+    }
+
+    #[test]
+    fn every_variant_is_used_somewhere() {
+        let corpus = corpus_codes();
+        for r in Suite2RejectReason::ALL {
+            assert!(
+                corpus.contains(r.code()),
+                "{r:?} is not produced or pinned anywhere"
+            );
+        }
+    }
+
+    #[test]
+    fn codes_are_unique_and_round_trip() {
+        let mut seen = BTreeSet::new();
+        for r in Suite2RejectReason::ALL {
+            assert!(seen.insert(r.code()), "duplicate code {}", r.code());
+            assert_eq!(Suite2RejectReason::from_code(r.code()), Some(*r));
+            assert_eq!(r.to_string(), r.code());
+        }
+        assert_eq!(
+            Suite2RejectReason::from_code(
+                "REJECT_S2_CHAINKEY_UNSET; reason_code=REJECT_S2_CHAINKEY_UNSET"
+            ),
+            Some(Suite2RejectReason::ChainkeyUnset)
+        );
+        assert_eq!(Suite2RejectReason::from_code("REJECT_S2_"), None);
+        assert_eq!(Suite2RejectReason::from_code("qsp_recv_failed"), None);
+    }
+
+    #[test]
+    fn categories_cover_the_four_classes() {
+        use Suite2RejectCategory::*;
+        for (r, want) in [
+            (Suite2RejectReason::ParsePrefix, Malformed),
+            (Suite2RejectReason::Replay, Replay),
+            (Suite2RejectReason::SckaTargetConsumed, Replay),
+            (Suite2RejectReason::HdrAuthFail, AuthFail),
+            (Suite2RejectReason::BoundaryNotInOrder, StateViolation),
+        ] {
+            assert_eq!(r.category(), want, "{r:?}");
+        }
+        for cat in [Malformed, Replay, AuthFail, StateViolation] {
+            assert!(Suite2RejectReason::ALL.iter().any(|r| r.category() == cat));
+        }
+    }
+}
@@ -3,12 +3,22 @@
 use std::collections::BTreeSet;
 
 use crate::crypto::traits::{Hash, Kmac};
+use crate::suite2::reject::Suite2RejectReason;
+use crate::suite2::types;
 
 #[derive(Debug)]
 pub enum Suite2Reject {
     Code(&'static str),
 }
 
+impl Suite2Reject {
+    pub fn reason(&self) -> Option<Suite2RejectReason> {
+        match self {
+            Suite2Reject::Code(code) => Suite2RejectReason::from_code(code),
+        }
+    }
+}
+
 pub struct ApplyReseedOut {
     pub ck_pq_seed_a2b: [u8; 32],
     pub ck_pq_seed_b2a: [u8; 32],
@@ -74,8 +84,50 @@ pub fn apply_pq_reseed(
     ck_pq_send: &[u8; 32],
     ck_pq_recv: &[u8; 32],
 ) -> Result<ApplyReseedOut, Suite2Reject> {
-    const MLKEM768_CT_LEN: usize = 1088;
-    if pq_ct.len() != MLKEM768_CT_LEN {
+    apply_pq_reseed_for_suite(
+        hash,
+        kmac,
+        types::SUITE2_SUITE_ID,
+        role_is_a,
+        rk,
+        pq_ct,
+        pq_epoch_ss,
+        peer_adv_id,
+        peer_max_adv_id_seen,
+        known_targets,
+        consumed_targets,
+        tombstoned_targets,
+        pq_target_id,
+        commit,
+        ck_pq_send,
+        ck_pq_recv,
+    )
+}
+
+/// `apply_pq_reseed` for a session of `suite_id`: the ciphertext must have that suite's
+/// ML-KEM ciphertext length.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_pq_reseed_for_suite(
+    hash: &dyn Hash,
+    kmac: &dyn Kmac,
+    suite_id: u16,
+    role_is_a: bool,
+    rk: &[u8; 32],
+    pq_ct: &[u8],
+    pq_epoch_ss: &[u8],
+    peer_adv_id: u32,
+    peer_max_adv_id_seen: u32,
+    known_targets: &BTreeSet<u32>,
+    consumed_targets: &BTreeSet<u32>,
+    tombstoned_targets: &BTreeSet<u32>,
+    pq_target_id: u32,
+    commit: bool,
+    ck_pq_send: &[u8; 32],
+    ck_pq_recv: &[u8; 32],
+) -> Result<ApplyReseedOut, Suite2Reject> {
+    let params =
+        types::suite2_params(suite_id).ok_or(Suite2Reject::Code("REJECT_S2_LOCAL_UNSUPPORTED"))?;
+    if pq_ct.len() != params.kem_ct_len {
         return Err(Suite2Reject::Code("REJECT_SCKA_CTXT_BAD_CT_LEN"));
     }
     if peer_adv_id <= peer_max_adv_id_seen {
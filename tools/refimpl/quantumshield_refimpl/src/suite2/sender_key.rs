@@ -0,0 +1,536 @@
+//! Sender keys: one symmetric chain per group sender.
+//!
+//! Pairwise fan-out seals a group message once per member. In sender-key mode every member
+//! instead owns a hash ratchet and an ML-DSA-65 signing key for the group, hands both to each
+//! other member once as a `SenderKeyDistribution` -- carried over the pairwise Suite-2 session,
+//! which supplies its confidentiality and its origin -- and then seals each message once for
+//! all of them.
+//!
+//! - chain: `ck[n+1] = KMAC256(ck[n], "QSL/SK/CK", 0x01)`, `mk[n] = KMAC256(ck[n], "QSL/SK/MK", 0x02)`;
+//! - nonce: `SHA-512("QSL/SK/NONCE" || group_id || u32be(key_id) || u32be(n))[..12]`;
+//! - AD: `"QSL/SK/AD" || group_id || u32be(key_id) || u32be(n)`;
+//! - signature: ML-DSA-65 over `SHA-512("QSL/SK/SIG" || group_id || u32be(key_id) || u32be(n)
+//!   || varbytes_u32(ct))`.
+//!
+//! Every member holds every chain key, so the chain alone would let any member forge messages
+//! as any other; the signature is what binds a message to its sender, and it is checked before
+//! anything is decrypted. A chain only ratchets forward, so a distribution at `n` reads nothing
+//! before `n`. Nothing heals, though: whoever held a distribution reads until the sender
+//! rotates to a fresh chain under a new `key_id`, which is why a removal must be followed by a
+//! rotation distributed to the members that remain.
+//!
+//! Receive is bounded like the pairwise ratchet: at most `SENDER_KEY_MAX_SKIP` steps ahead per
+//! message, at most `SENDER_KEY_MAX_STORED` skipped keys held, each usable once, and a rejected
+//! message leaves the receiver exactly as it was.
+
+use crate::codec::{
+    canonical_struct, CanonicalCodec, CodecError, Fixed, List16, Raw, VarU32, Writer, U32,
+};
+use crate::crypto::traits::{Aead, CryptoError, Hash, Kmac, PqSigMldsa65};
+use crate::qsp::{SZ_MLDSA65_PUB, SZ_MLDSA65_SIG};
+use core::fmt;
+use thiserror::Error;
+#[cfg(feature = "stdcrypto")]
+use zeroize::Zeroize;
+
+/// Group ids are 16 opaque bytes, chosen by whoever creates the group.
+pub const SZ_SENDER_KEY_GROUP_ID: usize = 16;
+/// Most chain steps one message may move a receiver forward.
+pub const SENDER_KEY_MAX_SKIP: u32 = 1000;
+/// Most skipped message keys a receiver holds; the oldest are dropped first.
+pub const SENDER_KEY_MAX_STORED: usize = 2000;
+
+#[derive(Debug, Error)]
+pub enum SenderKeyError {
+    #[error("crypto: {0}")]
+    Crypto(#[from] CryptoError),
+    #[error("codec: {0}")]
+    Codec(#[from] CodecError),
+    #[error("sender key message for another group or key id")]
+    UnknownKey,
+    #[error("sender key signature verification failed")]
+    BadSignature,
+    #[error("sender key message authentication failed")]
+    AuthFail,
+    #[error("sender key message already received or its key dropped")]
+    Duplicate,
+    #[error("sender key message beyond the skip limit")]
+    SkipLimit,
+    #[error("sender key chain exhausted")]
+    Exhausted,
+}
+
+#[cfg(feature = "stdcrypto")]
+fn wipe(mk: &mut [u8; 32]) {
+    mk.zeroize();
+}
+
+#[cfg(not(feature = "stdcrypto"))]
+fn wipe(_mk: &mut [u8; 32]) {}
+
+fn kmac32(kmac: &dyn Kmac, key: &[u8], label: &str, data: &[u8]) -> Result<[u8; 32], CryptoError> {
+    kmac.kmac256(key, label, data, 32)
+        .as_slice()
+        .try_into()
+        .map_err(|_| CryptoError::InvalidKey)
+}
+
+/// One chain step: `(ck[n+1], mk[n])` from `ck[n]`.
+pub fn chain_step(kmac: &dyn Kmac, ck: &[u8; 32]) -> Result<([u8; 32], [u8; 32]), CryptoError> {
+    let next = kmac32(kmac, ck, "QSL/SK/CK", &[0x01])?;
+    let mk = kmac32(kmac, ck, "QSL/SK/MK", &[0x02])?;
+    Ok((next, mk))
+}
+
+fn header(label: &[u8], group_id: &[u8; SZ_SENDER_KEY_GROUP_ID], key_id: u32, n: u32) -> Writer {
+    let mut w = Writer::new();
+    w.write_bytes(label);
+    w.write_bytes(group_id);
+    w.write_u32(key_id);
+    w.write_u32(n);
+    w
+}
+
+pub fn message_nonce(
+    hash: &dyn Hash,
+    group_id: &[u8; SZ_SENDER_KEY_GROUP_ID],
+    key_id: u32,
+    n: u32,
+) -> [u8; 12] {
+    let h = hash.sha512(&header(b"QSL/SK/NONCE", group_id, key_id, n).into_vec());
+    let mut out = [0u8; 12];
+    out.copy_from_slice(&h[..12]);
+    out
+}
+
+pub fn message_ad(group_id: &[u8; SZ_SENDER_KEY_GROUP_ID], key_id: u32, n: u32) -> Vec<u8> {
+    header(b"QSL/SK/AD", group_id, key_id, n).into_vec()
+}
+
+fn sig_digest(
+    hash: &dyn Hash,
+    group_id: &[u8; SZ_SENDER_KEY_GROUP_ID],
+    key_id: u32,
+    n: u32,
+    ct: &[u8],
+) -> [u8; 64] {
+    let mut w = header(b"QSL/SK/SIG", group_id, key_id, n);
+    w.write_varbytes_u32(ct);
+    hash.sha512(&w.into_vec())
+}
+
+canonical_struct! {
+    /// What a sender hands each member over their pairwise session: enough to read its chain
+    /// from `iteration` on, and the key its messages are signed under.
+    #[derive(Clone, PartialEq, Eq)]
+    pub struct SenderKeyDistribution {
+        pub group_id: [u8; SZ_SENDER_KEY_GROUP_ID] as Fixed,
+        pub key_id: u32 as U32,
+        pub iteration: u32 as U32,
+        pub chain_key: [u8; 32] as Fixed,
+        pub sig_pub: Vec<u8> as Raw<SZ_MLDSA65_PUB>,
+    }
+}
+
+impl fmt::Debug for SenderKeyDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SenderKeyDistribution")
+            .field("key_id", &self.key_id)
+            .field("iteration", &self.iteration)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SenderKeyDistribution {
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        self.encode_canonical()
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
+        Self::decode_canonical(buf)
+    }
+}
+
+canonical_struct! {
+    /// One group message, sealed once for every member.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct SenderKeyMessage {
+        pub group_id: [u8; SZ_SENDER_KEY_GROUP_ID] as Fixed,
+        pub key_id: u32 as U32,
+        pub iteration: u32 as U32,
+        pub ct: Vec<u8> as VarU32,
+        pub sig: Vec<u8> as Raw<SZ_MLDSA65_SIG>,
+    }
+}
+
+impl SenderKeyMessage {
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        self.encode_canonical()
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
+        Self::decode_canonical(buf)
+    }
+}
+
+canonical_struct! {
+    /// The sending half: this member's own chain for one group. Encodes for storage only; it
+    /// carries the signing secret and never goes on the wire.
+    #[derive(Clone)]
+    pub struct SenderKeyState {
+        group_id: [u8; SZ_SENDER_KEY_GROUP_ID] as Fixed,
+        key_id: u32 as U32,
+        iteration: u32 as U32,
+        chain_key: [u8; 32] as Fixed,
+        sig_pub: Vec<u8> as Raw<SZ_MLDSA65_PUB>,
+        sig_priv: Vec<u8> as VarU32,
+    }
+}
+
+#[cfg(feature = "stdcrypto")]
+impl Drop for SenderKeyState {
+    fn drop(&mut self) {
+        self.chain_key.zeroize();
+        self.sig_priv.zeroize();
+    }
+}
+
+impl SenderKeyState {
+    /// A fresh chain. The caller draws `key_id` and `chain_key` at random and a new signing
+    /// key pair per chain; rotating is nothing more than calling this again.
+    pub fn new(
+        group_id: [u8; SZ_SENDER_KEY_GROUP_ID],
+        key_id: u32,
+        chain_key: [u8; 32],
+        sig_pub: Vec<u8>,
+        sig_priv: Vec<u8>,
+    ) -> Result<Self, SenderKeyError> {
+        if sig_pub.len() != SZ_MLDSA65_PUB || sig_priv.is_empty() {
+            return Err(SenderKeyError::Crypto(CryptoError::InvalidKey));
+        }
+        Ok(Self {
+            group_id,
+            key_id,
+            iteration: 0,
+            chain_key,
+            sig_pub,
+            sig_priv,
+        })
+    }
+
+    pub fn key_id(&self) -> u32 {
+        self.key_id
+    }
+
+    pub fn iteration(&self) -> u32 {
+        self.iteration
+    }
+
+    /// The chain from its current position, for a member joining now or a sender rotating.
+    pub fn distribution(&self) -> SenderKeyDistribution {
+        SenderKeyDistribution {
+            group_id: self.group_id,
+            key_id: self.key_id,
+            iteration: self.iteration,
+            chain_key: self.chain_key,
+            sig_pub: self.sig_pub.clone(),
+        }
+    }
+
+    /// Seal and sign `pt` at the next iteration. The chain advances only if both succeed.
+    pub fn seal(
+        &mut self,
+        hash: &dyn Hash,
+        kmac: &dyn Kmac,
+        aead: &dyn Aead,
+        pq_sig: &dyn PqSigMldsa65,
+        pt: &[u8],
+    ) -> Result<SenderKeyMessage, SenderKeyError> {
+        let n = self.iteration;
+        let next_n = n.checked_add(1).ok_or(SenderKeyError::Exhausted)?;
+        let (next, mk) = chain_step(kmac, &self.chain_key)?;
+        let nonce = message_nonce(hash, &self.group_id, self.key_id, n);
+        let ct = aead.seal(&mk, &nonce, &message_ad(&self.group_id, self.key_id, n), pt);
+        if ct.is_empty() {
+            return Err(SenderKeyError::Crypto(CryptoError::InvalidKey));
+        }
+        let digest = sig_digest(hash, &self.group_id, self.key_id, n, &ct);
+        let sig = pq_sig.sign(&self.sig_priv, &digest)?;
+        self.chain_key = next;
+        self.iteration = next_n;
+        Ok(SenderKeyMessage {
+            group_id: self.group_id,
+            key_id: self.key_id,
+            iteration: n,
+            ct,
+            sig,
+        })
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        self.encode_canonical()
+    }
+
+    pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
+        Self::decode_canonical(buf)
+    }
+}
+
+canonical_struct! {
+    /// The receiving half: one other member's chain for one group, as this member holds it.
+    /// `skipped` is kept sorted by iteration.
+    #[derive(Clone)]
+    pub struct SenderKeyReceiver {
+        group_id: [u8; SZ_SENDER_KEY_GROUP_ID] as Fixed,
+        key_id: u32 as U32,
+        iteration: u32 as U32,
+        chain_key: [u8; 32] as Fixed,
+        sig_pub: Vec<u8> as Raw<SZ_MLDSA65_PUB>,
+        skipped: Vec<(u32, [u8; 32])> as List16<(U32, Fixed)>,
+    }
+}
+
+#[cfg(feature = "stdcrypto")]
+impl Drop for SenderKeyReceiver {
+    fn drop(&mut self) {
+        self.chain_key.zeroize();
+        for (_, mk) in self.skipped.iter_mut() {
+            wipe(mk);
+        }
+    }
+}
+
+impl SenderKeyReceiver {
+    /// Start reading a chain from a distribution. A newer distribution for the same sender
+    /// replaces the receiver outright; the old chain's skipped keys go with it.
+    pub fn new(dist: &SenderKeyDistribution) -> Self {
+        Self {
+            group_id: dist.group_id,
+            key_id: dist.key_id,
+            iteration: dist.iteration,
+            chain_key: dist.chain_key,
+            sig_pub: dist.sig_pub.clone(),
+            skipped: Vec::new(),
+        }
+    }
+
+    pub fn key_id(&self) -> u32 {
+        self.key_id
+    }
+
+    /// Next iteration expected in order.
+    pub fn iteration(&self) -> u32 {
+        self.iteration
+    }
+
+    /// Verify, then open, one message. On any error the receiver is unchanged.
+    pub fn open(
+        &mut self,
+        hash: &dyn Hash,
+        kmac: &dyn Kmac,
+        aead: &dyn Aead,
+        pq_sig: &dyn PqSigMldsa65,
+        msg: &SenderKeyMessage,
+    ) -> Result<Vec<u8>, SenderKeyError> {
+        if msg.group_id != self.group_id || msg.key_id != self.key_id {
+            return Err(SenderKeyError::UnknownKey);
+        }
+        let n = msg.iteration;
+        let digest = sig_digest(hash, &self.group_id, self.key_id, n, &msg.ct);
+        if !pq_sig.verify(&self.sig_pub, &digest, &msg.sig)? {
+            return Err(SenderKeyError::BadSignature);
+        }
+        let nonce = message_nonce(hash, &self.group_id, self.key_id, n);
+        let ad = message_ad(&self.group_id, self.key_id, n);
+
+        if n < self.iteration {
+            let idx = self
+                .skipped
+                .binary_search_by_key(&n, |(i, _)| *i)
+                .map_err(|_| SenderKeyError::Duplicate)?;
+            let pt = aead
+                .open(&self.skipped[idx].1, &nonce, &ad, &msg.ct)
+                .map_err(|_| SenderKeyError::AuthFail)?;
+            wipe(&mut self.skipped.remove(idx).1);
+            return Ok(pt);
+        }
+
+        if n - self.iteration > SENDER_KEY_MAX_SKIP {
+            return Err(SenderKeyError::SkipLimit);
+        }
+        let next_n = n.checked_add(1).ok_or(SenderKeyError::Exhausted)?;
+        let mut ck = self.chain_key;
+        let mut fresh = Vec::with_capacity((n - self.iteration) as usize);
+        for i in self.iteration..n {
+            let (next, mk) = chain_step(kmac, &ck)?;
+            fresh.push((i, mk));
+            ck = next;
+        }
+        let (next, mk) = chain_step(kmac, &ck)?;
+        let pt = aead
+            .open(&mk, &nonce, &ad, &msg.ct)
+            .map_err(|_| SenderKeyError::AuthFail)?;
+
+        self.skipped.extend(fresh);
+        if self.skipped.len() > SENDER_KEY_MAX_STORED {
+            let excess = self.skipped.len() - SENDER_KEY_MAX_STORED;
+            for (_, mut mk) in self.skipped.drain(..excess) {
+                wipe(&mut mk);
+            }
+        }
+        self.chain_key = next;
+        self.iteration = next_n;
+        Ok(pt)
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, CodecError> {
+        self.encode_canonical()
+    }
+
+    /// Decode a stored receiver, refusing one whose skipped keys are unordered or over the cap.
+    pub fn decode(buf: &[u8]) -> Result<Self, CodecError> {
+        let rx = Self::decode_canonical(buf)?;
+        let ordered = rx.skipped.windows(2).all(|w| w[0].0 < w[1].0);
+        let below = rx.skipped.last().is_none_or(|(i, _)| *i < rx.iteration);
+        if !ordered || !below || rx.skipped.len() > SENDER_KEY_MAX_STORED {
+            return Err(CodecError::Invalid("sender_key_skipped"));
+        }
+        Ok(rx)
+    }
+}
+
+#[cfg(all(test, feature = "stdcrypto"))]
+mod tests {
+    use super::*;
+    use crate::crypto::stdcrypto::StdCrypto;
+
+    /// Signs with a keyed hash of the digest; enough to tell senders and messages apart.
+    struct TestSig;
+
+    impl PqSigMldsa65 for TestSig {
+        fn sign(&self, privk: &[u8], msg: &[u8]) -> Result<Vec<u8>, CryptoError> {
+            Ok(StdCrypto.kmac256(privk, "TEST/SIG", msg, SZ_MLDSA65_SIG))
+        }
+
+        fn verify(&self, pubk: &[u8], msg: &[u8], sig: &[u8]) -> Result<bool, CryptoError> {
+            Ok(self.sign(&pubk[..32], msg)? == sig)
+        }
+    }
+
+    const GROUP: [u8; 16] = [0x47; 16];
+
+    fn sender(seed: u8) -> SenderKeyState {
+        let mut sig_pub = vec![seed; SZ_MLDSA65_PUB];
+        sig_pub[..32].copy_from_slice(&[seed; 32]);
+        SenderKeyState::new(GROUP, 7, [0x11; 32], sig_pub, vec![seed; 32]).expect("state")
+    }
+
+    fn seal(tx: &mut SenderKeyState, pt: &[u8]) -> SenderKeyMessage {
+        let c = StdCrypto;
+        tx.seal(&c, &c, &c, &TestSig, pt).expect("seal")
+    }
+
+    fn open(rx: &mut SenderKeyReceiver, msg: &SenderKeyMessage) -> Result<Vec<u8>, SenderKeyError> {
+        let c = StdCrypto;
+        rx.open(&c, &c, &c, &TestSig, msg)
+    }
+
+    #[test]
+    fn out_of_order_messages_open_once_each() {
+        let mut tx = sender(1);
+        let mut rx = SenderKeyReceiver::new(&tx.distribution());
+        let msgs: Vec<_> = (0..4u8).map(|i| seal(&mut tx, &[i])).collect();
+
+        assert_eq!(open(&mut rx, &msgs[2]).expect("m2"), vec![2]);
+        assert_eq!(open(&mut rx, &msgs[0]).expect("m0"), vec![0]);
+        assert!(matches!(
+            open(&mut rx, &msgs[0]),
+            Err(SenderKeyError::Duplicate)
+        ));
+        assert!(matches!(
+            open(&mut rx, &msgs[2]),
+            Err(SenderKeyError::Duplicate)
+        ));
+        assert_eq!(open(&mut rx, &msgs[3]).expect("m3"), vec![3]);
+        assert_eq!(open(&mut rx, &msgs[1]).expect("m1"), vec![1]);
+
+        let restored = SenderKeyReceiver::decode(&rx.encode().unwrap()).expect("decode");
+        assert_eq!(restored.iteration(), 4);
+    }
+
+    #[test]
+    fn a_rejected_message_leaves_the_receiver_unchanged() {
+        let mut tx = sender(1);
+        let mut rx = SenderKeyReceiver::new(&tx.distribution());
+        let good = seal(&mut tx, b"hello");
+        let before = rx.encode().unwrap();
+
+        let mut forged = good.clone();
+        forged.ct[0] ^= 1;
+        assert!(matches!(
+            open(&mut rx, &forged),
+            Err(SenderKeyError::BadSignature)
+        ));
+
+        // Another member holds the same chain key but not this sender's signing key.
+        let mut impostor = sender(2);
+        let spoof = seal(&mut impostor, b"hello");
+        assert!(matches!(
+            open(&mut rx, &spoof),
+            Err(SenderKeyError::BadSignature)
+        ));
+
+        let mut far = good.clone();
+        far.iteration = SENDER_KEY_MAX_SKIP + 1;
+        far.sig = TestSig
+            .sign(
+                &[1; 32],
+                &sig_digest(&StdCrypto, &GROUP, 7, far.iteration, &far.ct),
+            )
+            .expect("sign");
+        assert!(matches!(
+            open(&mut rx, &far),
+            Err(SenderKeyError::SkipLimit)
+        ));
+
+        let mut other_key = good.clone();
+        other_key.key_id = 8;
+        assert!(matches!(
+            open(&mut rx, &other_key),
+            Err(SenderKeyError::UnknownKey)
+        ));
+
+        assert_eq!(rx.encode().unwrap(), before);
+        assert_eq!(open(&mut rx, &good).expect("good"), b"hello".to_vec());
+    }
+
+    #[test]
+    fn a_rotated_chain_is_unreadable_under_the_old_distribution() {
+        let mut tx = sender(1);
+        let mut rx = SenderKeyReceiver::new(&tx.distribution());
+        let _ = seal(&mut tx, b"before");
+        let mut sig_pub = vec![1; SZ_MLDSA65_PUB];
+        sig_pub[..32].copy_from_slice(&[1; 32]);
+        let mut rotated =
+            SenderKeyState::new(GROUP, 9, [0x22; 32], sig_pub, vec![1; 32]).expect("state");
+        let msg = seal(&mut rotated, b"after");
+        assert!(matches!(
+            open(&mut rx, &msg),
+            Err(SenderKeyError::UnknownKey)
+        ));
+
+        let state = SenderKeyState::decode(&rotated.encode().unwrap()).expect("decode");
+        assert_eq!((state.key_id(), state.iteration()), (9, 1));
+    }
+
+    #[test]
+    fn a_stored_receiver_with_unordered_skipped_keys_is_refused() {
+        let tx = sender(1);
+        let mut rx = SenderKeyReceiver::new(&tx.distribution());
+        rx.iteration = 5;
+        rx.skipped = vec![(3, [0; 32]), (1, [0; 32])];
+        assert!(SenderKeyReceiver::decode(&rx.encode().unwrap()).is_err());
+        rx.skipped = vec![(1, [0; 32]), (5, [0; 32])];
+        assert!(SenderKeyReceiver::decode(&rx.encode().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,653 @@
+//! Suite-2 typed session API.
+//!
+//! `Suite2Session` owns a `Suite2SessionState` plus the caller-side SCKA store (advertised
+//! secret keys, the tracked peer advertisement, the peer-ADV watermark) and the ratchet
+//! trigger counters, and decides per message whether to advertise, DH-ratchet, PQ-reseed or
+//! send on the current chain. The cadence mirrors qsc's `qsp_pack` / `qsp_unpack`: advertise
+//! when no local advertised key is live, DH-ratchet on reply or after `ratchet_after_msgs`
+//! sends (or to create an unset send chain), reseed against a fresh peer advertisement first
+//! and then every `reseed_after_boundaries` DH boundaries.
+//!
+//! The API is clock-free: the wall-clock fallbacks (qsc's T thresholds) stay with the caller,
+//! who can force the next send to ratchet with `request_ratchet`, and who reports time to
+//! age out skipped keys with `expire_mkskipped`. Every operation commits state only on success;
+//! on `Err` the session is unchanged.
+//!
+//! The refimpl actor drives this API; qsc still runs its own copy of the cadence, because its
+//! send path gates rotation on message origination and persists its SCKA record in the vault.
+//!
+//! Persist the whole session, SCKA store included, with `seal_snapshot` (`crate::snapshot`):
+//! restoring only `into_state()` would lose the advertised secret keys, so any reseed already
+//! in flight to this session could never be opened. The bare layout is
+//!
+//! ```text
+//! "QS2P" | version u8 (=1) | varbytes<u32> QS2S state | policy | trigger | scka
+//! policy  = u32 ratchet_after_msgs | u32 reseed_after_boundaries | u8 scka
+//! trigger = u8 pending_reply_ratchet | u32 msgs_since_ratchet
+//! scka    = u32 local_next_adv_id | u32 peer_adv_max_seen | u32 boundaries_since_reseed |
+//!           u64 reseeds_sent | u64 reseeds_received | u64 msgs_since_reseed |
+//!           u16 count | count * (u32 adv_id | varbytes<u16> secret) |
+//!           u8 has_peer_adv | [u32 adv_id | varbytes<u16> pub]
+//! ```
+//!
+//! A restored policy is held to the same ranges as `Suite2SessionPolicy::new`.
+
+use crate::codec::{Reader, Writer};
+use crate::crypto::provider::CryptoProvider;
+use crate::crypto::traits::SecretBytes;
+use crate::suite2::healing::Suite2SessionHealing;
+use crate::suite2::limits::Suite2Limits;
+use crate::suite2::parse::decode_suite2_wire_for;
+use crate::suite2::ratchet::{
+    recv_dh_boundary, recv_pq_adv_session, recv_pq_reseed, recv_wire, send_boundary,
+    send_pq_advertise, send_pq_reseed, send_wire,
+};
+use crate::suite2::reject::Suite2RejectReason;
+use crate::suite2::state::{Suite2SessionState, Suite2StateError};
+use crate::suite2::types;
+
+const SESSION_MAGIC: &[u8; 4] = b"QS2P";
+const SESSION_VERSION: u8 = 1;
+
+/// Why a `Suite2Session` operation failed. The session state is unchanged in every case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suite2SessionError {
+    /// A Suite-2 entry point rejected the message.
+    Reject(Suite2RejectReason),
+    /// The provider implements no KEM for the session's suite, so a reseed cannot be opened.
+    KemUnavailable(u16),
+    /// Decapsulating a reseed ciphertext against the targeted advertised key failed.
+    DecapFailed,
+}
+
+impl std::fmt::Display for Suite2SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Suite2SessionError::Reject(reason) => write!(f, "{}", reason),
+            Suite2SessionError::KemUnavailable(suite_id) => {
+                write!(f, "no KEM for suite_id 0x{:04x}", suite_id)
+            }
+            Suite2SessionError::DecapFailed => write!(f, "pq decapsulation failed"),
+        }
+    }
+}
+
+impl std::error::Error for Suite2SessionError {}
+
+impl Suite2SessionError {
+    /// The reason for a `Reject`.
+    pub fn reason(&self) -> Option<Suite2RejectReason> {
+        match self {
+            Suite2SessionError::Reject(reason) => Some(*reason),
+            _ => None,
+        }
+    }
+
+    /// A reject from an entry point that reports string codes. Every code those produce is
+    /// registered (`reject::tests`), so the fallback is unreachable in practice.
+    fn code(code: &str) -> Self {
+        Suite2SessionError::Reject(
+            Suite2RejectReason::from_code(code).unwrap_or(Suite2RejectReason::LocalUnsupported),
+        )
+    }
+}
+
+/// Upper bounds accepted by `Suite2SessionPolicy::new` and by snapshot restore. Past them a
+/// one-sided conversation would go effectively unhealed.
+pub const RATCHET_AFTER_MSGS_CEILING: u32 = 1_024;
+pub const RESEED_AFTER_BOUNDARIES_CEILING: u32 = 1_024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suite2SessionPolicyError {
+    RatchetAfterMsgs,
+    ReseedAfterBoundaries,
+}
+
+impl std::fmt::Display for Suite2SessionPolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Suite2SessionPolicyError::RatchetAfterMsgs => {
+                write!(
+                    f,
+                    "ratchet_after_msgs must be in 1..={RATCHET_AFTER_MSGS_CEILING}"
+                )
+            }
+            Suite2SessionPolicyError::ReseedAfterBoundaries => {
+                write!(
+                    f,
+                    "reseed_after_boundaries must be in 1..={RESEED_AFTER_BOUNDARIES_CEILING}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Suite2SessionPolicyError {}
+
+/// Message-count cadence for the ratchet and SCKA. Defaults match qsc (N=4, N_pq=8). Construct
+/// with `new` (or `Default`); the fields are private so a held value is always within the
+/// ceilings above.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Suite2SessionPolicy {
+    ratchet_after_msgs: u32,
+    reseed_after_boundaries: u32,
+    scka: bool,
+}
+
+impl Default for Suite2SessionPolicy {
+    fn default() -> Self {
+        Self {
+            ratchet_after_msgs: 4,
+            reseed_after_boundaries: 8,
+            scka: true,
+        }
+    }
+}
+
+impl Suite2SessionPolicy {
+    pub fn new(
+        ratchet_after_msgs: u32,
+        reseed_after_boundaries: u32,
+        scka: bool,
+    ) -> Result<Self, Suite2SessionPolicyError> {
+        if ratchet_after_msgs == 0 || ratchet_after_msgs > RATCHET_AFTER_MSGS_CEILING {
+            return Err(Suite2SessionPolicyError::RatchetAfterMsgs);
+        }
+        if reseed_after_boundaries == 0 || reseed_after_boundaries > RESEED_AFTER_BOUNDARIES_CEILING
+        {
+            return Err(Suite2SessionPolicyError::ReseedAfterBoundaries);
+        }
+        Ok(Self {
+            ratchet_after_msgs,
+            reseed_after_boundaries,
+            scka,
+        })
+    }
+
+    /// Sends on one chain before a DH ratchet even without a reply.
+    pub fn ratchet_after_msgs(&self) -> u32 {
+        self.ratchet_after_msgs
+    }
+
+    /// Sent DH boundaries between PQ reseeds (after the first, which is immediate).
+    pub fn reseed_after_boundaries(&self) -> u32 {
+        self.reseed_after_boundaries
+    }
+
+    /// Advertise and reseed at all. Off leaves the session classical-ratchet only.
+    pub fn scka(&self) -> bool {
+        self.scka
+    }
+}
+
+#[derive(Clone, Default)]
+struct SckaStore {
+    /// Unconsumed local advertised keys as `(adv_id, secret)`; erased when a reseed consumes one.
+    advkeys: Vec<(u32, SecretBytes)>,
+    local_next_adv_id: u32,
+    /// The latest authenticated, not yet targeted peer advertisement `(adv_id, pub)`.
+    peer_adv: Option<(u32, Vec<u8>)>,
+    peer_adv_max_seen: u32,
+    boundaries_since_reseed: u32,
+    reseeds_sent: u64,
+    reseeds_received: u64,
+    /// Application messages either way since the last reseed either way.
+    msgs_since_reseed: u64,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Trigger {
+    pending_reply_ratchet: bool,
+    msgs_since_ratchet: u32,
+}
+
+/// A Suite-2 session with `encrypt` / `decrypt` that own the ratchet and SCKA orchestration.
+#[derive(Clone)]
+pub struct Suite2Session {
+    state: Suite2SessionState,
+    policy: Suite2SessionPolicy,
+    scka: SckaStore,
+    trigger: Trigger,
+}
+
+impl Suite2Session {
+    /// Wrap an established session. Ratcheting needs `dh.dhs_priv` populated
+    /// (`Suite2SessionState::set_dh_self_priv`); without it the session sends and receives on
+    /// its established chains only.
+    pub fn new(state: Suite2SessionState) -> Self {
+        Self::with_policy(state, Suite2SessionPolicy::default())
+    }
+
+    pub fn with_policy(state: Suite2SessionState, policy: Suite2SessionPolicy) -> Self {
+        Self {
+            state,
+            policy,
+            scka: SckaStore::default(),
+            trigger: Trigger::default(),
+        }
+    }
+
+    pub fn state(&self) -> &Suite2SessionState {
+        &self.state
+    }
+
+    pub fn policy(&self) -> &Suite2SessionPolicy {
+        &self.policy
+    }
+
+    /// The ratchet state alone. This drops the SCKA store; to persist a session, use
+    /// `seal_snapshot`.
+    pub fn into_state(self) -> Suite2SessionState {
+        self.state
+    }
+
+    /// Swap in a state advanced outside the session (a raw ratchet entry point), keeping the
+    /// SCKA store and trigger counters.
+    pub fn replace_state(&mut self, state: Suite2SessionState) {
+        self.state = state;
+    }
+
+    /// Bare `QS2P` layout (see the module docs). Holds advertised secret keys and is
+    /// unauthenticated: persist through `seal_snapshot` (`crate::snapshot`).
+    pub fn snapshot_bytes(&self) -> SecretBytes {
+        let state = SecretBytes::from(self.state.snapshot_bytes());
+        let mut w = Writer::new();
+        w.write_bytes(SESSION_MAGIC);
+        w.write_u8(SESSION_VERSION);
+        w.write_varbytes_u32(&state);
+        w.write_u32(self.policy.ratchet_after_msgs);
+        w.write_u32(self.policy.reseed_after_boundaries);
+        w.write_u8(u8::from(self.policy.scka));
+        w.write_u8(u8::from(self.trigger.pending_reply_ratchet));
+        w.write_u32(self.trigger.msgs_since_ratchet);
+        let scka = &self.scka;
+        w.write_u32(scka.local_next_adv_id);
+        w.write_u32(scka.peer_adv_max_seen);
+        w.write_u32(scka.boundaries_since_reseed);
+        w.write_u64(scka.reseeds_sent);
+        w.write_u64(scka.reseeds_received);
+        w.write_u64(scka.msgs_since_reseed);
+        // KEM secret keys are a few KiB and only one or two are live at a time.
+        w.write_u16(scka.advkeys.len() as u16);
+        for (adv_id, sk) in scka.advkeys.iter() {
+            w.write_u32(*adv_id);
+            w.write_varbytes_u16(sk);
+        }
+        match &scka.peer_adv {
+            Some((adv_id, pk)) => {
+                w.write_u8(1);
+                w.write_u32(*adv_id);
+                w.write_varbytes_u16(pk);
+            }
+            None => w.write_u8(0),
+        }
+        SecretBytes::from(w.into_vec())
+    }
+
+    /// Parse a bare `QS2P` layout. Only version 1 is accepted.
+    pub fn restore_bytes(bytes: &[u8]) -> Result<Self, Suite2StateError> {
+        let bad = |_| Suite2StateError::Invalid("bad suite2 session snapshot");
+        let mut r = Reader::new(bytes);
+        if r.read_slice(4).map_err(bad)? != SESSION_MAGIC {
+            return Err(Suite2StateError::Invalid("bad suite2 session snapshot"));
+        }
+        if r.read_u8().map_err(bad)? != SESSION_VERSION {
+            return Err(Suite2StateError::Invalid(
+                "unsupported suite2 session snapshot version",
+            ));
+        }
+        let state = Suite2SessionState::restore_bytes(r.read_varbytes_u32_ref().map_err(bad)?)?;
+        let flag = |v: u8| match v {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Suite2StateError::Invalid("bad suite2 session snapshot")),
+        };
+        let ratchet_after_msgs = r.read_u32().map_err(bad)?;
+        let reseed_after_boundaries = r.read_u32().map_err(bad)?;
+        let scka_on = flag(r.read_u8().map_err(bad)?)?;
+        let policy = Suite2SessionPolicy::new(ratchet_after_msgs, reseed_after_boundaries, scka_on)
+            .map_err(|_| Suite2StateError::Invalid("bad suite2 session snapshot policy"))?;
+        let trigger = Trigger {
+            pending_reply_ratchet: flag(r.read_u8().map_err(bad)?)?,
+            msgs_since_ratchet: r.read_u32().map_err(bad)?,
+        };
+        let mut scka = SckaStore {
+            local_next_adv_id: r.read_u32().map_err(bad)?,
+            peer_adv_max_seen: r.read_u32().map_err(bad)?,
+            boundaries_since_reseed: r.read_u32().map_err(bad)?,
+            reseeds_sent: r.read_u64().map_err(bad)?,
+            reseeds_received: r.read_u64().map_err(bad)?,
+            msgs_since_reseed: r.read_u64().map_err(bad)?,
+            ..SckaStore::default()
+        };
+        for _ in 0..r.read_u16().map_err(bad)? {
+            let adv_id = r.read_u32().map_err(bad)?;
+            let sk = SecretBytes::from(r.read_varbytes_u16().map_err(bad)?);
+            if scka.advkeys.iter().any(|(id, _)| *id == adv_id) {
+                return Err(Suite2StateError::Invalid("bad suite2 session snapshot"));
+            }
+            scka.advkeys.push((adv_id, sk));
+        }
+        if flag(r.read_u8().map_err(bad)?)? {
+            let adv_id = r.read_u32().map_err(bad)?;
+            scka.peer_adv = Some((adv_id, r.read_varbytes_u16().map_err(bad)?));
+        }
+        r.finish().map_err(bad)?;
+        Ok(Self {
+            state,
+            policy,
+            scka,
+            trigger,
+        })
+    }
+
+    /// Read-only PCS healing metrics: the state view plus this session's trigger and SCKA
+    /// counters.
+    pub fn healing_status(&self) -> Suite2SessionHealing {
+        let mut live_adv_ids: Vec<u32> = self.scka.advkeys.iter().map(|(id, _)| *id).collect();
+        live_adv_ids.sort_unstable();
+        Suite2SessionHealing {
+            state: self.state.healing_status(),
+            msgs_since_ratchet: self.trigger.msgs_since_ratchet,
+            ratchet_pending: self.trigger.pending_reply_ratchet,
+            msgs_since_reseed: self.scka.msgs_since_reseed,
+            boundaries_since_reseed: self.scka.boundaries_since_reseed,
+            reseeds_sent: self.scka.reseeds_sent,
+            reseeds_received: self.scka.reseeds_received,
+            live_adv_ids,
+            pending_peer_adv_id: self.scka.peer_adv.as_ref().map(|(id, _)| *id),
+            peer_adv_max_seen: self.scka.peer_adv_max_seen,
+        }
+    }
+
+    /// Make the next `encrypt` perform a DH ratchet (e.g. a caller-side time fallback).
+    pub fn request_ratchet(&mut self) {
+        self.trigger.pending_reply_ratchet = true;
+    }
+
+    /// Replace the receive limits (`Suite2SessionState::set_recv_limits`).
+    pub fn set_recv_limits(&mut self, limits: Suite2Limits) {
+        self.state.set_recv_limits(limits);
+    }
+
+    /// Report the caller's clock and purge skipped keys past their age bound
+    /// (`Suite2SessionState::expire_mkskipped`). Returns the number purged.
+    pub fn expire_mkskipped(&mut self, now_secs: u64) -> usize {
+        self.state.expire_mkskipped(now_secs)
+    }
+
+    /// Seal `plaintext`. Returns the wires to transmit in order: any SCKA control message
+    /// first, the message carrying `plaintext` last.
+    pub fn encrypt(
+        &mut self,
+        provider: &dyn CryptoProvider,
+        plaintext: &[u8],
+    ) -> Result<Vec<Vec<u8>>, Suite2SessionError> {
+        let (hash, kmac, aead) = (provider.hash(), provider.kmac(), provider.aead());
+        let ratchets = ratchet_capable(&self.state);
+        let scka_on = ratchets && self.policy.scka;
+        let mut st = self.state.clone();
+        let mut scka = self.scka.clone();
+        let mut trig = self.trigger;
+        let mut wires = Vec::new();
+
+        // Advertise on the current chain before the message whenever no advertised key is live.
+        // Fail-safe skip (as qsc): no advertisement only means no reseed.
+        if scka_on && chains_live(&st) && scka.advkeys.is_empty() {
+            let suite_id = st.send.suite_id;
+            let max_known = st.recv.known_targets.iter().next_back().copied();
+            let adv_id = scka
+                .local_next_adv_id
+                .max(max_known.unwrap_or(0).saturating_add(1))
+                .max(1);
+            if let Ok((pk, sk)) = kem_keypair(provider, suite_id) {
+                if let Ok(out) = send_pq_advertise(hash, kmac, aead, st.clone(), adv_id, &pk, &[]) {
+                    scka.advkeys.push((adv_id, sk));
+                    scka.local_next_adv_id = adv_id.saturating_add(1);
+                    st = out.state;
+                    trig.msgs_since_ratchet = trig.msgs_since_ratchet.saturating_add(1);
+                    wires.push(out.wire);
+                }
+            }
+        }
+
+        let should_ratchet = ratchets
+            && (!chains_live(&st)
+                || trig.pending_reply_ratchet
+                || trig.msgs_since_ratchet >= self.policy.ratchet_after_msgs);
+        let reseed_due = scka_on
+            && scka.peer_adv.is_some()
+            && (scka.reseeds_sent == 0
+                || scka.boundaries_since_reseed >= self.policy.reseed_after_boundaries);
+
+        if should_ratchet {
+            let out = send_boundary(hash, kmac, aead, provider.x25519(), st, plaintext)
+                .map_err(Suite2SessionError::code)?;
+            trig = Trigger::default();
+            scka.msgs_since_reseed = scka.msgs_since_reseed.saturating_add(1);
+            if scka_on {
+                scka.boundaries_since_reseed = scka.boundaries_since_reseed.saturating_add(1);
+            }
+            st = out.state;
+            wires.push(out.wire);
+        } else if let (true, Some((target_id, peer_pub))) = (reseed_due, scka.peer_adv.clone()) {
+            // A consumed peer advertisement is never re-targeted, even when encap fails.
+            scka.peer_adv = None;
+            match kem_encap(provider, st.send.suite_id, &peer_pub) {
+                Ok((ct, ss)) => {
+                    let out = send_pq_reseed(hash, kmac, aead, st, target_id, &ct, &ss, plaintext)
+                        .map_err(Suite2SessionError::code)?;
+                    scka.boundaries_since_reseed = 0;
+                    scka.reseeds_sent = scka.reseeds_sent.saturating_add(1);
+                    scka.msgs_since_reseed = 0;
+                    st = out.state;
+                    wires.push(out.wire);
+                }
+                Err(_) => {
+                    let out = send_wire(hash, kmac, aead, st.send.clone(), 0, plaintext)
+                        .map_err(Suite2SessionError::code)?;
+                    st.send = out.state;
+                    scka.msgs_since_reseed = scka.msgs_since_reseed.saturating_add(1);
+                    wires.push(out.wire);
+                }
+            }
+            trig.msgs_since_ratchet = trig.msgs_since_ratchet.saturating_add(1);
+        } else {
+            let out = send_wire(hash, kmac, aead, st.send.clone(), 0, plaintext)
+                .map_err(Suite2SessionError::code)?;
+            st.send = out.state;
+            trig.msgs_since_ratchet = trig.msgs_since_ratchet.saturating_add(1);
+            scka.msgs_since_reseed = scka.msgs_since_reseed.saturating_add(1);
+            wires.push(out.wire);
+        }
+
+        self.state = st;
+        self.scka = scka;
+        self.trigger = trig;
+        Ok(wires)
+    }
+
+    /// Open one wire. `Ok(None)` is an authenticated SCKA advertisement (a control message
+    /// with no application payload); `Ok(Some(pt))` is an application message.
+    pub fn decrypt(
+        &mut self,
+        provider: &dyn CryptoProvider,
+        wire: &[u8],
+    ) -> Result<Option<Vec<u8>>, Suite2SessionError> {
+        let (hash, kmac, aead) = (provider.hash(), provider.kmac(), provider.aead());
+        let suite_id = self.state.recv.suite_id;
+        let (_, _, _, parsed) =
+            decode_suite2_wire_for(wire, suite_id).map_err(Suite2SessionError::code)?;
+        let flags = parsed.flags;
+        let is_adv = (flags & types::FLAG_PQ_ADV) != 0;
+        let is_boundary = !is_adv && (flags & types::FLAG_BOUNDARY) != 0;
+        let is_ctxt = is_boundary && (flags & types::FLAG_PQ_CTXT) != 0;
+
+        if (is_adv || is_ctxt) && !ratchet_capable(&self.state) {
+            return Err(Suite2SessionError::Reject(
+                Suite2RejectReason::LocalUnsupported,
+            ));
+        }
+
+        if is_adv {
+            let (adv_id, adv_pub) = match (parsed.pq_adv_id, parsed.pq_adv_pub) {
+                (Some(id), Some(pk)) => (id, pk),
+                _ => {
+                    return Err(Suite2SessionError::Reject(
+                        Suite2RejectReason::PqPrefixParse,
+                    ))
+                }
+            };
+            let out = recv_pq_adv_session(
+                hash,
+                kmac,
+                aead,
+                self.state.clone(),
+                wire,
+                self.scka.peer_adv_max_seen,
+            );
+            if !out.ok {
+                return Err(reject(out.reason));
+            }
+            self.state = out.state;
+            self.scka.peer_adv = Some((adv_id, adv_pub));
+            self.scka.peer_adv_max_seen = adv_id;
+            self.trigger.pending_reply_ratchet = true;
+            return Ok(None);
+        }
+
+        let plaintext = if is_ctxt {
+            let (target_id, ct) = match (parsed.pq_target_id, parsed.pq_ct) {
+                (Some(id), Some(ct)) => (id, ct),
+                _ => {
+                    return Err(Suite2SessionError::Reject(
+                        Suite2RejectReason::PqPrefixParse,
+                    ))
+                }
+            };
+            let slot = self
+                .scka
+                .advkeys
+                .iter()
+                .position(|(id, _)| *id == target_id)
+                .ok_or(Suite2SessionError::Reject(
+                    Suite2RejectReason::SckaTargetUnknown,
+                ))?;
+            let ss = kem_decap(provider, suite_id, &self.scka.advkeys[slot].1, &ct)?;
+            let out = recv_pq_reseed(
+                hash,
+                kmac,
+                aead,
+                provider.x25519(),
+                self.state.clone(),
+                wire,
+                &ss,
+                target_id,
+            );
+            if !out.ok {
+                return Err(reject(out.reason));
+            }
+            self.state = out.state;
+            self.scka.advkeys.remove(slot);
+            self.scka.reseeds_received = self.scka.reseeds_received.saturating_add(1);
+            self.scka.msgs_since_reseed = 0;
+            out.plaintext
+        } else if is_boundary {
+            let out = recv_dh_boundary(
+                hash,
+                kmac,
+                aead,
+                provider.x25519(),
+                self.state.clone(),
+                wire,
+            );
+            if !out.ok {
+                return Err(reject(out.reason));
+            }
+            self.state = out.state;
+            out.plaintext
+        } else {
+            let out = recv_wire(
+                hash,
+                kmac,
+                aead,
+                self.state.recv.clone(),
+                &self.state.rk,
+                wire,
+                None,
+                None,
+            )
+            .map_err(Suite2SessionError::code)?;
+            self.state.recv = out.state;
+            self.state.rk = out.rk;
+            out.plaintext
+        };
+        if !is_ctxt {
+            self.scka.msgs_since_reseed = self.scka.msgs_since_reseed.saturating_add(1);
+        }
+        self.trigger.pending_reply_ratchet = true;
+        Ok(Some(plaintext))
+    }
+}
+
+fn reject(reason: Option<&'static str>) -> Suite2SessionError {
+    reason.map_or(
+        Suite2SessionError::Reject(Suite2RejectReason::LocalUnsupported),
+        Suite2SessionError::code,
+    )
+}
+
+fn is_zero32(v: &[u8; 32]) -> bool {
+    v.iter().all(|b| *b == 0)
+}
+
+/// A session can DH-ratchet (and so run SCKA) only with its own DH private key and a distinct
+/// peer DH key; the degenerate self-DH seed session keeps its pre-ratchet behaviour, as in qsc.
+fn ratchet_capable(st: &Suite2SessionState) -> bool {
+    !is_zero32(&st.dh.dhs_priv) && st.dh.dhr != st.dh.dhs_pub
+}
+
+fn chains_live(st: &Suite2SessionState) -> bool {
+    !is_zero32(&st.send.ck_ec) && !is_zero32(&st.send.ck_pq)
+}
+
+fn kem_keypair(
+    provider: &dyn CryptoProvider,
+    suite_id: u16,
+) -> Result<(Vec<u8>, SecretBytes), Suite2SessionError> {
+    match suite_id {
+        types::SUITE2_SUITE_ID => provider.pq_kem_keypair(),
+        _ => None,
+    }
+    .map(|(pk, sk)| (pk, SecretBytes::from(sk)))
+    .ok_or(Suite2SessionError::KemUnavailable(suite_id))
+}
+
+fn kem_encap(
+    provider: &dyn CryptoProvider,
+    suite_id: u16,
+    pubk: &[u8],
+) -> Result<(Vec<u8>, SecretBytes), Suite2SessionError> {
+    let out = match suite_id {
+        types::SUITE2_SUITE_ID => provider.pq_kem().map(|k| k.encap(pubk)),
+        _ => None,
+    };
+    out.ok_or(Suite2SessionError::KemUnavailable(suite_id))?
+        .map(|(ct, ss)| (ct, SecretBytes::from(ss)))
+        .map_err(|_| Suite2SessionError::Reject(Suite2RejectReason::LocalUnsupported))
+}
+
+fn kem_decap(
+    provider: &dyn CryptoProvider,
+    suite_id: u16,
+    privk: &[u8],
+    ct: &[u8],
+) -> Result<SecretBytes, Suite2SessionError> {
+    let out = match suite_id {
+        types::SUITE2_SUITE_ID => provider.pq_kem().map(|k| k.decap(privk, ct)),
+        _ => None,
+    };
+    out.ok_or(Suite2SessionError::KemUnavailable(suite_id))?
+        .map(SecretBytes::from)
+        .map_err(|_| Suite2SessionError::DecapFailed)
+}
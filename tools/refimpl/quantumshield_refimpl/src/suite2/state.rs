@@ -1,12 +1,24 @@
 //! Suite-2 session snapshot/restore (durability test support).
 
+use crate::suite2::limits::{Suite2Limits, MAX_MKSKIPPED_CEILING};
 use crate::suite2::ratchet::{
-    MkSkippedEntry, Suite2DhRatchetState, Suite2RecvWireState, Suite2SendState,
+    evict_mkskipped, expire_mkskipped, MkSkippedEntry, Suite2DhRatchetState, Suite2RecvWireState,
+    Suite2SendState,
 };
 use std::collections::BTreeSet;
 
 const MAX_TARGETS_RESTORE: usize = 10_000; // DOC-SCL-001 rsf.inbox_max_items cap (deployment profile default/upper bound).
-const MAX_MKSKIPPED_RESTORE: usize = 1000; // Align with suite2/ratchet.rs MAX_MKSKIPPED.
+
+/// Optional v3 trailer sections, after the DH material, each at most once and in this order.
+/// A session with default limits that has never ratcheted, aged or stored a stamped key writes
+/// neither, so its snapshot is byte-identical to the pre-trailer layout.
+///
+/// `QS2L`: non-default receive bounds — max_skip, max_mkskipped, max_header_attempts (u32 BE).
+const LIMITS_TRAILER_TAG: &[u8; 4] = b"QS2L";
+/// `QS2E`: skipped-key expiry — max age secs (u64, 0 = none), max DH steps (u32, 0 = none),
+/// `recv.dh_epoch` (u32), `recv.now_secs` (u64), then `dh_epoch` (u32) + `stored_at` (u64) for
+/// each skipped key, in body order.
+const EXPIRY_TRAILER_TAG: &[u8; 4] = b"QS2E";
 
 #[derive(Debug)]
 pub enum Suite2StateError {
@@ -45,6 +57,34 @@ impl Suite2SessionState {
         self.dh.dhs_priv = dh_self_priv;
     }
 
+    /// Replace the receive limits. Skipped keys beyond a lowered `max_mkskipped` are evicted
+    /// (oldest first), and keys past a new expiry bound purged, immediately, so the state always
+    /// fits its own limits.
+    pub fn set_recv_limits(&mut self, limits: Suite2Limits) {
+        self.recv.limits = limits;
+        let mk = std::mem::take(&mut self.recv.mkskipped);
+        let mk = expire_mkskipped(mk, &limits, self.recv.dh_epoch, self.recv.now_secs);
+        self.recv.mkskipped = evict_mkskipped(mk, limits.max_mkskipped());
+    }
+
+    /// Report the caller's clock (seconds, any fixed origin) and purge skipped keys older than
+    /// `mkskipped_max_age_secs`. Keys stored from now on are stamped with this time. The clock
+    /// never moves backwards: an earlier `now_secs` than already reported is ignored, so a
+    /// rewound clock cannot extend a key's life. Returns the number of keys purged.
+    pub fn expire_mkskipped(&mut self, now_secs: u64) -> usize {
+        self.recv.now_secs = self.recv.now_secs.max(now_secs);
+        let before = self.recv.mkskipped.len();
+        let mk = std::mem::take(&mut self.recv.mkskipped);
+        self.recv.mkskipped = expire_mkskipped(
+            mk,
+            &self.recv.limits,
+            self.recv.dh_epoch,
+            self.recv.now_secs,
+        );
+        before - self.recv.mkskipped.len()
+    }
+
+    /// Bare `QS2S` layout. Unauthenticated: persist through `seal_snapshot` (`crate::snapshot`).
     pub fn snapshot_bytes(&self) -> Vec<u8> {
         fn push_u8(out: &mut Vec<u8>, v: u8) {
             out.push(v);
@@ -55,6 +95,9 @@ impl Suite2SessionState {
         fn push_u32(out: &mut Vec<u8>, v: u32) {
             out.extend_from_slice(&v.to_be_bytes());
         }
+        fn push_u64(out: &mut Vec<u8>, v: u64) {
+            out.extend_from_slice(&v.to_be_bytes());
+        }
         fn push_arr16(out: &mut Vec<u8>, a: &[u8; 16]) {
             out.extend_from_slice(a);
         }
@@ -114,6 +157,45 @@ impl Suite2SessionState {
         push_arr32(&mut out, &self.dh.dhs_pub);
         push_arr32(&mut out, &self.dh.dhr);
 
+        let limits = &self.recv.limits;
+        let default = Suite2Limits::default();
+        if (
+            limits.max_skip(),
+            limits.max_mkskipped(),
+            limits.max_header_attempts(),
+        ) != (
+            default.max_skip(),
+            default.max_mkskipped(),
+            default.max_header_attempts(),
+        ) {
+            out.extend_from_slice(LIMITS_TRAILER_TAG);
+            push_u32(&mut out, limits.max_skip());
+            push_u32(&mut out, limits.max_mkskipped() as u32);
+            push_u32(&mut out, limits.max_header_attempts() as u32);
+        }
+
+        let stamped = self
+            .recv
+            .mkskipped
+            .iter()
+            .any(|e| e.dh_epoch != 0 || e.stored_at != 0);
+        if limits.mkskipped_max_age_secs().is_some()
+            || limits.mkskipped_max_dh_steps().is_some()
+            || self.recv.dh_epoch != 0
+            || self.recv.now_secs != 0
+            || stamped
+        {
+            out.extend_from_slice(EXPIRY_TRAILER_TAG);
+            push_u64(&mut out, limits.mkskipped_max_age_secs().unwrap_or(0));
+            push_u32(&mut out, limits.mkskipped_max_dh_steps().unwrap_or(0));
+            push_u32(&mut out, self.recv.dh_epoch);
+            push_u64(&mut out, self.recv.now_secs);
+            for entry in self.recv.mkskipped.iter() {
+                push_u32(&mut out, entry.dh_epoch);
+                push_u64(&mut out, entry.stored_at);
+            }
+        }
+
         out
     }
 
@@ -144,6 +226,12 @@ impl Suite2SessionState {
                 let s = self.take(4)?;
                 Ok(u32::from_be_bytes([s[0], s[1], s[2], s[3]]))
             }
+            fn u64(&mut self) -> Result<u64, Suite2StateError> {
+                let s = self.take(8)?;
+                let mut a = [0u8; 8];
+                a.copy_from_slice(s);
+                Ok(u64::from_be_bytes(a))
+            }
             fn arr16(&mut self) -> Result<[u8; 16], Suite2StateError> {
                 let s = self.take(16)?;
                 let mut a = [0u8; 16];
@@ -207,6 +295,9 @@ impl Suite2SessionState {
             consumed_targets: BTreeSet::new(),
             tombstoned_targets: BTreeSet::new(),
             mkskipped: Vec::new(),
+            limits: Suite2Limits::default(),
+            dh_epoch: 0,
+            now_secs: 0,
         };
 
         let role_flag = c.u8()?;
@@ -252,7 +343,8 @@ impl Suite2SessionState {
         }
 
         let mk_len = c.u32()? as usize;
-        if mk_len > MAX_MKSKIPPED_RESTORE {
+        // Bounded by the ceiling here; by the session's own limits once the trailer is read.
+        if mk_len > MAX_MKSKIPPED_CEILING {
             return Err(invalid());
         }
         let mk_bytes = mk_len.checked_mul(68).ok_or_else(invalid)?;
@@ -263,7 +355,13 @@ impl Suite2SessionState {
             let dh_pub = c.arr32()?;
             let n = c.u32()?;
             let mk = c.arr32()?;
-            recv.mkskipped.push(MkSkippedEntry { dh_pub, n, mk });
+            recv.mkskipped.push(MkSkippedEntry {
+                dh_pub,
+                n,
+                mk,
+                dh_epoch: 0,
+                stored_at: 0,
+            });
         }
 
         // DH-ratchet key material (v3: no embedded root copy).
@@ -273,7 +371,57 @@ impl Suite2SessionState {
             dhr: c.arr32()?,
         };
 
-        if c.i != bytes.len() {
+        let bad_limits = |_| Suite2StateError::Invalid("bad suite2 snapshot limits");
+        let mut next_section = 0u8;
+        while remaining(&c) > 0 {
+            let tag = c.take(4)?;
+            if tag == LIMITS_TRAILER_TAG && next_section == 0 {
+                let max_skip = c.u32()?;
+                let max_mkskipped = c.u32()? as usize;
+                let max_header_attempts = c.u32()? as usize;
+                recv.limits = Suite2Limits::new(max_skip, max_mkskipped, max_header_attempts)
+                    .map_err(bad_limits)?;
+                next_section = 1;
+            } else if tag == EXPIRY_TRAILER_TAG && next_section <= 1 {
+                let max_age = c.u64()?;
+                if max_age != 0 {
+                    recv.limits = recv
+                        .limits
+                        .with_mkskipped_max_age(max_age)
+                        .map_err(bad_limits)?;
+                }
+                let max_dh_steps = c.u32()?;
+                if max_dh_steps != 0 {
+                    recv.limits = recv
+                        .limits
+                        .with_mkskipped_max_dh_steps(max_dh_steps)
+                        .map_err(bad_limits)?;
+                }
+                recv.dh_epoch = c.u32()?;
+                recv.now_secs = c.u64()?;
+                for entry in recv.mkskipped.iter_mut() {
+                    entry.dh_epoch = c.u32()?;
+                    entry.stored_at = c.u64()?;
+                    if entry.dh_epoch > recv.dh_epoch || entry.stored_at > recv.now_secs {
+                        return Err(invalid());
+                    }
+                }
+                next_section = 2;
+            } else {
+                return Err(invalid());
+            }
+        }
+        if recv.mkskipped.len() > recv.limits.max_mkskipped() {
+            return Err(invalid());
+        }
+        // Expiry is applied eagerly, so a consistent writer never persists an expired key.
+        let live = expire_mkskipped(
+            recv.mkskipped.clone(),
+            &recv.limits,
+            recv.dh_epoch,
+            recv.now_secs,
+        );
+        if live.len() != recv.mkskipped.len() {
             return Err(invalid());
         }
 
@@ -316,6 +464,9 @@ mod tests {
             consumed_targets: BTreeSet::new(),
             tombstoned_targets: BTreeSet::new(),
             mkskipped: Vec::new(),
+            limits: Suite2Limits::default(),
+            dh_epoch: 0,
+            now_secs: 0,
         };
         let dh = Suite2DhRatchetState {
             dhs_priv: [0x88; 32],
@@ -418,7 +569,7 @@ mod tests {
         let (known_off, _consumed_off, _tomb_off, mk_off) = length_offsets(&bytes);
         let oversize = (MAX_TARGETS_RESTORE as u32).saturating_add(1).to_be_bytes();
         bytes[known_off..known_off + 4].copy_from_slice(&oversize);
-        let oversize_mk = (MAX_MKSKIPPED_RESTORE as u32)
+        let oversize_mk = (MAX_MKSKIPPED_CEILING as u32)
             .saturating_add(1)
             .to_be_bytes();
         bytes[mk_off..mk_off + 4].copy_from_slice(&oversize_mk);
@@ -428,7 +579,7 @@ mod tests {
         );
         assert_eq!(
             read_u32_be(&bytes, mk_off),
-            MAX_MKSKIPPED_RESTORE as u32 + 1
+            MAX_MKSKIPPED_CEILING as u32 + 1
         );
 
         let err1 = Suite2SessionState::restore_bytes(&bytes)
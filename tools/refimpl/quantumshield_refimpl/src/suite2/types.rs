@@ -1,8 +1,49 @@
 //! Suite-2 constants and shared types.
 
+use crate::qsp::{SZ_MLDSA65_PUB, SZ_MLDSA65_SIG, SZ_MLKEM768_CT, SZ_MLKEM768_PUB};
+
 pub const SUITE2_PROTOCOL_VERSION: u16 = 0x0500;
 pub const SUITE2_SUITE_ID: u16 = 0x0002;
 
+/// Suite-2 suite ids this implementation speaks, most preferred first. A suite belongs here
+/// only once a base handshake can establish it.
+pub const SUITE2_SUITE_IDS: [u16; 1] = [SUITE2_SUITE_ID];
+
 pub const FLAG_PQ_ADV: u16 = 0x0001;
 pub const FLAG_PQ_CTXT: u16 = 0x0002;
 pub const FLAG_BOUNDARY: u16 = 0x0004;
+
+/// The PQ parameter set behind a Suite-2 suite id. Every length that depends on the KEM or
+/// signature choice (handshake, SCKA advertise/reseed, the header parser) is read from here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suite2Params {
+    pub suite_id: u16,
+    pub kem_alg: &'static str,
+    pub sig_alg: &'static str,
+    pub kdf_alg: &'static str,
+    pub kem_pub_len: usize,
+    pub kem_ct_len: usize,
+    pub kem_ss_len: usize,
+    pub sig_pub_len: usize,
+    pub sig_len: usize,
+}
+
+pub const SUITE2_PARAMS: Suite2Params = Suite2Params {
+    suite_id: SUITE2_SUITE_ID,
+    kem_alg: "ML-KEM-768",
+    sig_alg: "ML-DSA-65+Ed25519",
+    kdf_alg: "KDF_HYBRID_KMAC256_SHA512",
+    kem_pub_len: SZ_MLKEM768_PUB,
+    kem_ct_len: SZ_MLKEM768_CT,
+    kem_ss_len: 32,
+    sig_pub_len: SZ_MLDSA65_PUB,
+    sig_len: SZ_MLDSA65_SIG,
+};
+
+/// Parameter set for `suite_id`, or `None` for anything that is not a Suite-2 suite.
+pub fn suite2_params(suite_id: u16) -> Option<&'static Suite2Params> {
+    match suite_id {
+        SUITE2_SUITE_ID => Some(&SUITE2_PARAMS),
+        _ => None,
+    }
+}
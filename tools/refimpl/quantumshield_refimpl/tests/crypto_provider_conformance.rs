@@ -0,0 +1,243 @@
+//! Every registered crypto provider must pass the primitive KATs and replay the Suite-2
+//! KDF, hybrid-MK and transcript vector sets with identical results.
+
+use quantumshield_refimpl::crypto::conformance::{check_interop, check_provider};
+use quantumshield_refimpl::crypto::provider::{
+    handshake_deps, CryptoProvider, ProviderRegistry, STD_PROVIDER_NAME,
+};
+use quantumshield_refimpl::kt::CanonicalKtVerifier;
+use quantumshield_refimpl::suite2::binding;
+use quantumshield_refimpl::suite2::ratchet::derive_mk_step;
+use serde_json::Value;
+use std::path::PathBuf;
+
+const KDF_VECTORS: &str = "inputs/suite2/vectors/qshield_suite2_kdf_vectors_v1.json";
+const MK_HYBRID_VECTORS: &str = "inputs/suite2/vectors/qshield_suite2_mk_hybrid_vectors_v1.json";
+const TRANSCRIPT_VECTORS: &str = "inputs/suite2/vectors/qshield_suite2_transcript_vectors_v1.json";
+
+fn repo_path(relative: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../..")
+        .join(relative)
+}
+
+fn load_vectors(relative: &str) -> Vec<Value> {
+    let raw = std::fs::read_to_string(repo_path(relative)).expect("read vector file");
+    let root: Value = serde_json::from_str(&raw).expect("parse vector file");
+    root["vectors"].as_array().expect("vectors array").clone()
+}
+
+fn data<'a>(obj: &'a Value, key: &str) -> &'a Value {
+    obj.get(key)
+        .and_then(|v| v.get("data"))
+        .unwrap_or_else(|| panic!("missing {key}.data"))
+}
+
+fn hex_field(obj: &Value, key: &str) -> Vec<u8> {
+    hex::decode(data(obj, key).as_str().expect("hex string")).expect("hex decode")
+}
+
+fn arr32(v: &[u8]) -> [u8; 32] {
+    v.try_into().expect("32 bytes")
+}
+
+fn u_field(obj: &Value, key: &str, width: &str) -> u64 {
+    data(obj, key)[width].as_u64().expect("integer field")
+}
+
+fn hex_u16(v: &Value) -> u16 {
+    u16::from_str_radix(v.as_str().expect("hex u16").trim_start_matches("0x"), 16).expect("u16")
+}
+
+fn kmac32(p: &dyn CryptoProvider, key: &[u8], label: &str, data: &[u8]) -> Vec<u8> {
+    p.kmac().kmac256(key, label, data, 32)
+}
+
+fn replay_kdf(p: &dyn CryptoProvider, v: &Value) {
+    let id = v["id"].as_str().unwrap_or("?");
+    let input = &v["input"];
+    let out = &v["expect"]["output"];
+    let cat = |a: &[u8], b: &[u8]| [a, b].concat();
+    let checks: Vec<(&str, Vec<u8>)> = match v["op"].as_str().unwrap_or("") {
+        "suite2.kdf_ec_ck" => {
+            let ck = hex_field(input, "CK_ec");
+            vec![
+                ("CK_ec_prime", kmac32(p, &ck, "QSP5.0/CK", &[0x01])),
+                ("ec_mk", kmac32(p, &ck, "QSP5.0/MK", &[0x02])),
+            ]
+        }
+        "suite2.kdf_pq_ck" => {
+            let ck = hex_field(input, "CK_pq");
+            vec![
+                ("CK_pq_prime", kmac32(p, &ck, "QSP5.0/PQCK", &[0x01])),
+                ("pq_mk", kmac32(p, &ck, "QSP5.0/PQMK", &[0x02])),
+            ]
+        }
+        "suite2.kdf_hybrid" => {
+            let ec = hex_field(input, "ec_mk");
+            let pq = hex_field(input, "pq_mk");
+            vec![("mk", kmac32(p, &ec, "QSP5.0/HYBRID", &cat(&pq, &[0x01])))]
+        }
+        "suite2.kdf_rk_dh" => {
+            let rk = hex_field(input, "RK");
+            let dh = hex_field(input, "dh_out");
+            let tmp = p.kmac().kmac256(&rk, "QSP5.0/RKDH", &dh, 64);
+            vec![
+                ("RK_prime", tmp[..32].to_vec()),
+                ("CK_ec0", tmp[32..].to_vec()),
+            ]
+        }
+        "suite2.kdf_rk_pq" => {
+            let rk = hex_field(input, "RK");
+            let ss = hex_field(input, "pq_ss");
+            vec![(
+                "RK_prime",
+                kmac32(p, &rk, "QSP5.0/RKPQ", &cat(&ss, &[0x01])),
+            )]
+        }
+        "suite2.kdf_pq_reseed" => {
+            let rk = hex_field(input, "RK");
+            let tid = u_field(input, "pq_target_id", "u32") as u32;
+            let ct = hex_field(input, "pq_ct");
+            let ss = hex_field(input, "pq_epoch_ss");
+            let h = p.hash().sha512(&ct);
+            let mut ctx = b"QSP5.0/SCKA/CTXT".to_vec();
+            ctx.extend_from_slice(&tid.to_be_bytes());
+            ctx.extend_from_slice(&h[..32]);
+            ctx.extend_from_slice(&ss);
+            vec![
+                ("CK_pq_seed_A2B", kmac32(p, &rk, "QSP5.0/PQSEED/A->B", &ctx)),
+                ("CK_pq_seed_B2A", kmac32(p, &rk, "QSP5.0/PQSEED/B->A", &ctx)),
+            ]
+        }
+        other => panic!("{id}: unexpected op {other}"),
+    };
+    for (key, got) in checks {
+        assert_eq!(got, hex_field(out, key), "{}: {id} {key}", p.name());
+    }
+}
+
+fn replay_mk_hybrid(p: &dyn CryptoProvider, v: &Value) {
+    let id = v["id"].as_str().unwrap_or("?");
+    let input = &v["input"];
+    let mut ck_ec = arr32(&hex_field(input, "CK_ec"));
+    let mut ck_pq = arr32(&hex_field(input, "CK_pq"));
+    let mut mks = Vec::new();
+    for _ in 0..u_field(input, "count", "u32") {
+        let (ec, pq, mk) = derive_mk_step(p.kmac(), &ck_ec, &ck_pq).expect("derive_mk_step");
+        mks.push(hex::encode(mk));
+        ck_ec = ec;
+        ck_pq = pq;
+    }
+    if v["expect"]["ok"].as_bool() == Some(true) {
+        let out = &v["expect"]["output"];
+        let want: Vec<String> = data(out, "mk_list")
+            .as_array()
+            .expect("mk_list")
+            .iter()
+            .map(|e| e["data"].as_str().expect("mk hex").to_string())
+            .collect();
+        assert_eq!(mks, want, "{}: {id} mk_list", p.name());
+        assert_eq!(
+            ck_ec.to_vec(),
+            hex_field(out, "CK_ec_final"),
+            "{}: {id}",
+            p.name()
+        );
+        assert_eq!(
+            ck_pq.to_vec(),
+            hex_field(out, "CK_pq_final"),
+            "{}: {id}",
+            p.name()
+        );
+    } else {
+        let expected: Vec<&str> = data(input, "expected_mk_list")
+            .as_array()
+            .expect("expected_mk_list")
+            .iter()
+            .map(|e| e["data"].as_str().expect("mk hex"))
+            .collect();
+        assert_ne!(mks, expected, "{}: {id} must mismatch", p.name());
+    }
+}
+
+fn replay_transcript(p: &dyn CryptoProvider, v: &Value) {
+    let id = v["id"].as_str().unwrap_or("?");
+    let input = &v["input"];
+    let negotiated = data(input, "negotiated");
+    let pv = hex_u16(&negotiated["protocol_version"]);
+    let sid = hex_u16(&negotiated["suite_id"]);
+    let session_id = hex_field(input, "session_id");
+    let flags = u_field(input, "flags", "u16") as u16;
+    let pq_bind = binding::pq_bind_sha512_32(p.hash(), flags, &hex_field(input, "pq_prefix"));
+    let ad_hdr = binding::ad_hdr(
+        &session_id,
+        pv,
+        sid,
+        &hex_field(input, "DH_pub"),
+        flags,
+        &pq_bind,
+    );
+    let ad_body = binding::ad_body(&session_id, pv, sid, &pq_bind);
+    let matches = ad_hdr == hex_field(input, "ad_hdr") && ad_body == hex_field(input, "ad_body");
+    assert_eq!(
+        Some(matches),
+        v["expect"]["ok"].as_bool(),
+        "{}: {id}",
+        p.name()
+    );
+    if matches {
+        let out = &v["expect"]["output"];
+        assert_eq!(
+            pq_bind.to_vec(),
+            hex_field(out, "pq_bind"),
+            "{}: {id}",
+            p.name()
+        );
+    }
+}
+
+#[test]
+fn every_registered_provider_passes_conformance() {
+    let reg = ProviderRegistry::with_builtin();
+    assert!(reg.names().contains(&STD_PROVIDER_NAME));
+    for p in reg.iter() {
+        let report = check_provider(p.as_ref()).unwrap_or_else(|e| panic!("{e}"));
+        assert!(!report.passed.is_empty());
+    }
+}
+
+#[test]
+fn every_registered_provider_replays_suite2_vector_sets() {
+    let kdf = load_vectors(KDF_VECTORS);
+    let mk = load_vectors(MK_HYBRID_VECTORS);
+    let transcript = load_vectors(TRANSCRIPT_VECTORS);
+    assert!(!kdf.is_empty() && !mk.is_empty() && !transcript.is_empty());
+    for p in ProviderRegistry::with_builtin().iter() {
+        kdf.iter().for_each(|v| replay_kdf(p.as_ref(), v));
+        mk.iter().for_each(|v| replay_mk_hybrid(p.as_ref(), v));
+        transcript
+            .iter()
+            .for_each(|v| replay_transcript(p.as_ref(), v));
+    }
+}
+
+#[test]
+fn registered_providers_interoperate_pairwise() {
+    let reg = ProviderRegistry::with_builtin();
+    for a in reg.iter() {
+        for b in reg.iter() {
+            check_interop(a.as_ref(), b.as_ref()).unwrap_or_else(|e| panic!("{e}"));
+        }
+    }
+}
+
+#[test]
+fn selected_provider_drives_handshake_deps_only_with_pq() {
+    let p = ProviderRegistry::with_builtin()
+        .get(STD_PROVIDER_NAME)
+        .expect("std provider");
+    let kt = CanonicalKtVerifier::disabled_nonproduction();
+    let deps = handshake_deps(p.as_ref(), &kt);
+    assert_eq!(deps.is_ok(), p.pq_kem().is_some() && p.pq_sig().is_some());
+}
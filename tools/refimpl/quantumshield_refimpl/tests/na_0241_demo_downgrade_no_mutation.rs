@@ -1,4 +1,5 @@
 use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
+use quantumshield_refimpl::suite2::limits::Suite2Limits;
 use quantumshield_refimpl::suite2::ratchet::{recv_nonboundary_ooo, Suite2RecvState};
 use quantumshield_refimpl::suite2::types;
 
@@ -51,6 +52,7 @@ fn capability_commitment_flags_mismatch_rejects_without_mutation() {
         types::FLAG_BOUNDARY,
         &hdr_ct,
         &body_ct,
+        &Suite2Limits::default(),
     );
     let out2 = recv_nonboundary_ooo(
         &crypto,
@@ -60,6 +62,7 @@ fn capability_commitment_flags_mismatch_rejects_without_mutation() {
         types::FLAG_BOUNDARY,
         &hdr_ct,
         &body_ct,
+        &Suite2Limits::default(),
     );
 
     assert!(!out1.ok);
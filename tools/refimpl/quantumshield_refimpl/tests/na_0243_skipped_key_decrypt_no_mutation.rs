@@ -1,5 +1,6 @@
 use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
 use quantumshield_refimpl::crypto::traits::{Aead, CryptoError};
+use quantumshield_refimpl::suite2::limits::Suite2Limits;
 use quantumshield_refimpl::suite2::ratchet::{
     MkSkippedEntry, Suite2DhRatchetState, Suite2RecvWireState, Suite2SendState,
 };
@@ -82,6 +83,9 @@ fn base_session() -> Suite2SessionState {
             consumed_targets: BTreeSet::new(),
             tombstoned_targets: BTreeSet::new(),
             mkskipped: Vec::new(),
+            limits: Suite2Limits::default(),
+            dh_epoch: 0,
+            now_secs: 0,
         },
         dh: Suite2DhRatchetState::default(),
     }
@@ -134,6 +138,8 @@ fn skipped_key_body_auth_reject_does_not_consume_skipped_key_or_mutate_snapshot(
         dh_pub: session.recv.dh_pub,
         n: 5,
         mk: arr32(0x81),
+        dh_epoch: 0,
+        stored_at: 0,
     });
 
     let wire = nonboundary_wire(
@@ -1,4 +1,5 @@
 use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
+use quantumshield_refimpl::suite2::limits::Suite2Limits;
 use quantumshield_refimpl::suite2::ratchet::{
     Suite2DhRatchetState, Suite2RecvWireState, Suite2SendState,
 };
@@ -54,6 +55,9 @@ fn base_session() -> Suite2SessionState {
             consumed_targets: BTreeSet::new(),
             tombstoned_targets: BTreeSet::new(),
             mkskipped: Vec::new(),
+            limits: Suite2Limits::default(),
+            dh_epoch: 0,
+            now_secs: 0,
         },
         dh: Suite2DhRatchetState::default(),
     }
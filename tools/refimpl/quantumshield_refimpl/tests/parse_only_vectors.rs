@@ -1,4 +1,4 @@
-use quantumshield_refimpl::{Envelope, ProtocolMessage};
+use quantumshield_refimpl::{Envelope, EnvelopeRef, ProtocolMessage};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
@@ -27,8 +27,24 @@ fn parse_only_vectors() {
     if let Some(v) = root.envelope {
         for f in v {
             let b = hex_to_bytes(&f.wire_hex);
-            let ok = Envelope::decode(&b).is_ok();
-            assert_eq!(ok, f.expect_ok, "envelope fixture {}", f.name);
+            let owned = Envelope::decode(&b);
+            assert_eq!(owned.is_ok(), f.expect_ok, "envelope fixture {}", f.name);
+            // The borrowed view must accept and reject the same inputs, field for field.
+            let borrowed = EnvelopeRef::decode(&b);
+            assert_eq!(
+                borrowed.is_ok(),
+                f.expect_ok,
+                "envelope ref fixture {}",
+                f.name
+            );
+            if let (Ok(owned), Ok(borrowed)) = (owned, borrowed) {
+                assert_eq!(
+                    owned.as_envelope_ref(),
+                    borrowed,
+                    "envelope ref fixture {}",
+                    f.name
+                );
+            }
         }
     }
 
@@ -1,3 +1,5 @@
+use quantumshield_refimpl::codec::CodecError;
+use quantumshield_refimpl::qse::PaddingPolicy;
 use quantumshield_refimpl::{Envelope, EnvelopeProfile};
 
 fn make_suite2_wire(body_len: usize) -> Vec<u8> {
@@ -37,8 +39,8 @@ fn qse_header_prefix_len(route_token_len: usize) -> usize {
 fn bucket_mode_hides_exact_length_fields_for_same_profile() {
     let env_a = make_bucketed(make_suite2_wire(32));
     let env_b = make_bucketed(make_suite2_wire(176));
-    let enc_a = env_a.encode();
-    let enc_b = env_b.encode();
+    let enc_a = env_a.encode().expect("encode");
+    let enc_b = env_b.encode().expect("encode");
 
     assert_eq!(enc_a.len(), EnvelopeProfile::Standard.min_size_bytes());
     assert_eq!(enc_b.len(), EnvelopeProfile::Standard.min_size_bytes());
@@ -55,7 +57,7 @@ fn bucket_mode_hides_exact_length_fields_for_same_profile() {
 fn bucket_mode_decode_recovers_payload_and_padding_split() {
     let payload = make_suite2_wire(80);
     let env = make_bucketed(payload.clone());
-    let encoded = env.encode();
+    let encoded = env.encode().expect("encode");
     let decoded = Envelope::decode(&encoded).expect("decode");
     assert_eq!(decoded.payload, payload);
     assert_eq!(
@@ -69,7 +71,7 @@ fn bucket_mode_decode_recovers_payload_and_padding_split() {
 #[test]
 fn bucket_mode_rejects_nonzero_cleartext_len_fields() {
     let env = make_bucketed(make_suite2_wire(64));
-    let mut encoded = env.encode();
+    let mut encoded = env.encode().expect("encode");
     let route_len = env.route_token.len();
     let pad_len_off = 2 + 2 + 2 + route_len + 4;
     // Mutate cleartext pad_len from 0 -> 1; bucket-mode decoder must reject.
@@ -78,3 +80,40 @@ fn bucket_mode_rejects_nonzero_cleartext_len_fields() {
     let err = Envelope::decode(&encoded).expect_err("expected reject");
     assert!(err.to_string().contains("bucket_len_fields"));
 }
+
+#[test]
+fn pad_to_policy_lands_on_policy_sizes_and_fails_closed() {
+    let policy = PaddingPolicy::Geometric {
+        min: 256,
+        factor: 2,
+        max: 1024,
+    };
+    let base = Envelope {
+        env_version: 0x0100,
+        flags: 0,
+        route_token: b"route-token-fixed".to_vec(),
+        timestamp_bucket: 42,
+        payload: make_suite2_wire(32),
+        padding: vec![],
+    };
+    let encoded = base
+        .clone()
+        .pad_to_policy(&policy, &[0xAA; 1024])
+        .expect("pad")
+        .encode()
+        .expect("encode");
+    assert_eq!(encoded.len(), 256);
+    assert_eq!(
+        Envelope::decode(&encoded).expect("decode").payload,
+        base.payload
+    );
+
+    let big = Envelope {
+        payload: make_suite2_wire(1024),
+        ..base
+    };
+    assert!(matches!(
+        big.pad_to_policy(&policy, &[0xAA; 2048]),
+        Err(CodecError::Invalid("padding_policy_no_fit"))
+    ));
+}
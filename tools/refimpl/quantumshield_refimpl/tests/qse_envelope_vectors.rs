@@ -0,0 +1,282 @@
+use quantumshield_refimpl::codec::CodecError;
+use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
+use quantumshield_refimpl::qse::{
+    derive_len_key, negotiate_env_version, EnvNegotiation, Envelope, EnvelopeProfile, EnvelopeRef,
+    QSE_ENV_SUPPORTED_VERSIONS, QSE_ENV_VERSION_V1, QSE_ENV_VERSION_V2,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Root {
+    meta: Meta,
+    negotiation: Vec<Negotiation>,
+    v1: Cases,
+    v2: Cases,
+    downgrade: Vec<Downgrade>,
+}
+
+#[derive(Deserialize)]
+struct Meta {
+    len_key_hex: String,
+    len_nonce_hex: String,
+    sender_versions: Vec<u16>,
+    receiver_versions: Vec<u16>,
+}
+
+#[derive(Deserialize)]
+struct Negotiation {
+    peer_supported: Vec<u16>,
+    expect: Option<u16>,
+}
+
+#[derive(Deserialize)]
+struct Downgrade {
+    id: String,
+    local: Vec<u16>,
+    peer: Vec<u16>,
+    wire_hex: String,
+    expect_version: Option<u16>,
+    expect_error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Cases {
+    positive: Vec<Case>,
+    negative: Vec<Case>,
+}
+
+#[derive(Deserialize)]
+struct Case {
+    id: String,
+    wire_hex: String,
+    flags: Option<u16>,
+    route_token_hex: Option<String>,
+    timestamp_bucket: Option<u32>,
+    payload_hex: Option<String>,
+    padding_hex: Option<String>,
+    len_key_hex: Option<String>,
+    sender_versions: Option<Vec<u16>>,
+    receiver_versions: Option<Vec<u16>>,
+    expect_error: Option<String>,
+}
+
+fn hex_to_bytes(s: &str) -> Vec<u8> {
+    hex::decode(s.trim()).expect("hex")
+}
+
+fn load() -> Root {
+    let data = std::fs::read_to_string("vectors/qse_envelope_v1_v2.json").expect("vectors");
+    serde_json::from_str(&data).expect("json")
+}
+
+fn error_name(e: &CodecError) -> &'static str {
+    match e {
+        CodecError::Truncated => "truncated",
+        CodecError::LengthOutOfRange => "length_out_of_range",
+        CodecError::TrailingBytes => "trailing",
+        CodecError::Invalid(detail) => detail,
+    }
+}
+
+fn expected(case: &Case, env_version: u16) -> Envelope {
+    let f = |v: &Option<String>| hex_to_bytes(v.as_deref().expect("field"));
+    Envelope {
+        env_version,
+        flags: case.flags.expect("flags"),
+        route_token: f(&case.route_token_hex),
+        timestamp_bucket: case.timestamp_bucket.expect("timestamp_bucket"),
+        payload: f(&case.payload_hex),
+        padding: f(&case.padding_hex),
+    }
+}
+
+fn key32(s: &str) -> [u8; 32] {
+    hex_to_bytes(s).try_into().expect("key32")
+}
+
+#[test]
+fn qse_v1_vectors() {
+    let root = load();
+    for case in &root.v1.positive {
+        let wire = hex_to_bytes(&case.wire_hex);
+        let env = expected(case, QSE_ENV_VERSION_V1);
+        assert_eq!(env.encode().expect("encode"), wire, "{}", case.id);
+        assert_eq!(
+            Envelope::decode(&wire).expect("decode").as_envelope_ref(),
+            env.as_envelope_ref(),
+            "{}",
+            case.id
+        );
+        let v1_only = [QSE_ENV_VERSION_V1];
+        assert_eq!(
+            EnvelopeRef::decode_negotiated(&wire, &EnvNegotiation::new(&v1_only, &v1_only), None)
+                .expect("decode_negotiated"),
+            env.as_envelope_ref(),
+            "{}",
+            case.id
+        );
+    }
+    for case in &root.v1.negative {
+        let wire = hex_to_bytes(&case.wire_hex);
+        let err = Envelope::decode(&wire).expect_err(&case.id);
+        assert_eq!(
+            error_name(&err),
+            case.expect_error.as_deref().expect("expect_error"),
+            "{}",
+            case.id
+        );
+    }
+}
+
+#[test]
+fn qse_v2_vectors() {
+    let c = StdCrypto;
+    let root = load();
+    let key = key32(&root.meta.len_key_hex);
+    let nonce: [u8; 12] = hex_to_bytes(&root.meta.len_nonce_hex)
+        .try_into()
+        .expect("nonce12");
+    let sender = EnvNegotiation::new(&root.meta.sender_versions, &root.meta.receiver_versions);
+    let receiver = EnvNegotiation::new(&root.meta.receiver_versions, &root.meta.sender_versions);
+    for case in &root.v2.positive {
+        let wire = hex_to_bytes(&case.wire_hex);
+        let env = expected(case, QSE_ENV_VERSION_V2);
+        assert_eq!(
+            env.encode_v2(&c, &key, &nonce, &sender).expect("encode_v2"),
+            wire,
+            "{}",
+            case.id
+        );
+        assert_eq!(
+            EnvelopeRef::decode_v2(&wire, &c, &key, &receiver).expect("decode_v2"),
+            env.as_envelope_ref(),
+            "{}",
+            case.id
+        );
+        assert_eq!(
+            Envelope::decode_negotiated(&wire, &receiver, Some((&c, &key)))
+                .expect("decode_negotiated")
+                .as_envelope_ref(),
+            env.as_envelope_ref(),
+            "{}",
+            case.id
+        );
+        // Keyless and v1-only receivers must not mistake a v2 envelope for v1.
+        assert!(matches!(
+            Envelope::decode_negotiated(&wire, &receiver, None),
+            Err(CodecError::Invalid("env_v2_requires_key"))
+        ));
+        assert!(matches!(
+            Envelope::decode(&wire),
+            Err(CodecError::Invalid("env_version"))
+        ));
+    }
+    for case in &root.v2.negative {
+        let wire = hex_to_bytes(&case.wire_hex);
+        let key = case.len_key_hex.as_deref().map(key32).unwrap_or(key);
+        // The receiver's view of the advertisements, where the case says it differs.
+        let peer = case
+            .sender_versions
+            .as_deref()
+            .unwrap_or(&root.meta.sender_versions);
+        let local = case
+            .receiver_versions
+            .as_deref()
+            .unwrap_or(&root.meta.receiver_versions);
+        let err = Envelope::decode_v2(&wire, &c, &key, &EnvNegotiation::new(local, peer))
+            .expect_err(&case.id);
+        assert_eq!(
+            error_name(&err),
+            case.expect_error.as_deref().expect("expect_error"),
+            "{}",
+            case.id
+        );
+    }
+}
+
+#[test]
+fn qse_version_negotiation_vectors() {
+    for case in load().negotiation {
+        assert_eq!(
+            negotiate_env_version(&case.peer_supported),
+            case.expect,
+            "{:?}",
+            case.peer_supported
+        );
+    }
+}
+
+#[test]
+fn qse_downgrade_vectors() {
+    let c = StdCrypto;
+    let root = load();
+    let key = key32(&root.meta.len_key_hex);
+    for case in &root.downgrade {
+        let wire = hex_to_bytes(&case.wire_hex);
+        let nego = EnvNegotiation::new(&case.local, &case.peer);
+        match Envelope::decode_negotiated(&wire, &nego, Some((&c, &key))) {
+            Ok(env) => assert_eq!(Some(env.env_version), case.expect_version, "{}", case.id),
+            Err(err) => assert_eq!(
+                Some(error_name(&err)),
+                case.expect_error.as_deref(),
+                "{}",
+                case.id
+            ),
+        }
+    }
+}
+
+#[test]
+fn qse_v2_sender_refuses_a_version_the_peer_did_not_advertise() {
+    let c = StdCrypto;
+    let key = derive_len_key(&c, b"shared session secret");
+    let env = Envelope {
+        env_version: QSE_ENV_VERSION_V2,
+        flags: 0,
+        route_token: b"rt".to_vec(),
+        timestamp_bucket: 1,
+        payload: b"payload".to_vec(),
+        padding: Vec::new(),
+    };
+    let v1_peer = [QSE_ENV_VERSION_V1];
+    assert!(matches!(
+        env.encode_v2(
+            &c,
+            &key,
+            &[7; 12],
+            &EnvNegotiation::new(&QSE_ENV_SUPPORTED_VERSIONS, &v1_peer)
+        ),
+        Err(CodecError::Invalid("env_version_not_negotiated"))
+    ));
+}
+
+#[test]
+fn qse_v2_refuses_v1_layout_and_pads_to_profile() {
+    let c = StdCrypto;
+    let key = derive_len_key(&c, b"shared session secret");
+    assert_ne!(key, derive_len_key(&c, b"another secret"));
+    let env = Envelope {
+        env_version: QSE_ENV_VERSION_V2,
+        flags: 0,
+        route_token: b"rt".to_vec(),
+        timestamp_bucket: 1,
+        payload: b"not suite-2 wire".to_vec(),
+        padding: Vec::new(),
+    };
+    assert!(
+        matches!(env.encode(), Err(CodecError::Invalid("env_version"))),
+        "v2 must not fall back to v1 layout"
+    );
+    let both = EnvNegotiation::new(&QSE_ENV_SUPPORTED_VERSIONS, &QSE_ENV_SUPPORTED_VERSIONS);
+    let padded = env
+        .clone()
+        .pad_to_profile(EnvelopeProfile::Standard, &[0xAA; 1024])
+        .expect("pad");
+    let wire = padded
+        .encode_v2(&c, &key, &[7; 12], &both)
+        .expect("encode_v2");
+    assert_eq!(wire.len(), 1024);
+    let back = Envelope::decode_v2(&wire, &c, &key, &both).expect("decode_v2");
+    assert_eq!(back.payload, env.payload);
+    assert_eq!(back.padding, padded.padding);
+}
@@ -0,0 +1,207 @@
+use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
+use quantumshield_refimpl::crypto::traits::{Aead, CryptoError, PqSigMldsa65};
+use quantumshield_refimpl::qsp::{SZ_MLDSA65_PUB, SZ_MLDSA65_SIG};
+use quantumshield_refimpl::suite2::sender_key::{
+    chain_step, message_ad, message_nonce, SenderKeyDistribution, SenderKeyError, SenderKeyMessage,
+    SenderKeyReceiver, SenderKeyState,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Root {
+    positive: Vec<Case>,
+    negative: Vec<Case>,
+}
+
+#[derive(Deserialize)]
+struct Case {
+    id: String,
+    group_id_hex: String,
+    key_id: u32,
+    iteration: u32,
+    chain_key_hex: String,
+    #[serde(default)]
+    steps: Vec<Step>,
+    receive: Vec<Recv>,
+}
+
+#[derive(Deserialize)]
+struct Step {
+    iteration: u32,
+    chain_key_hex: String,
+    message_key_hex: String,
+    nonce_hex: String,
+    ad_hex: String,
+    pt_hex: String,
+    ct_hex: String,
+}
+
+#[derive(Deserialize)]
+struct Recv {
+    iteration: u32,
+    key_id: Option<u32>,
+    ct_hex: String,
+    pt_hex: Option<String>,
+    expect_error: Option<String>,
+}
+
+/// Signatures are randomized ML-DSA-65 and not part of the vectors; this one signs with zeros
+/// and accepts everything, so the receive sequences exercise the chain alone.
+struct AcceptPqSig;
+
+impl PqSigMldsa65 for AcceptPqSig {
+    fn sign(&self, _privk: &[u8], _msg: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        Ok(vec![0u8; SZ_MLDSA65_SIG])
+    }
+
+    fn verify(&self, _pubk: &[u8], _msg: &[u8], _sig: &[u8]) -> Result<bool, CryptoError> {
+        Ok(true)
+    }
+}
+
+fn hex_to_bytes(s: &str) -> Vec<u8> {
+    hex::decode(s.trim()).expect("hex")
+}
+
+fn arr<const N: usize>(s: &str) -> [u8; N] {
+    hex_to_bytes(s).try_into().expect("length")
+}
+
+fn error_name(e: &SenderKeyError) -> &'static str {
+    match e {
+        SenderKeyError::UnknownKey => "UNKNOWN_KEY",
+        SenderKeyError::BadSignature => "BAD_SIGNATURE",
+        SenderKeyError::AuthFail => "AUTH_FAIL",
+        SenderKeyError::Duplicate => "DUPLICATE",
+        SenderKeyError::SkipLimit => "SKIP_LIMIT",
+        SenderKeyError::Exhausted => "EXHAUSTED",
+        SenderKeyError::Crypto(_) | SenderKeyError::Codec(_) => "OTHER",
+    }
+}
+
+fn load() -> Root {
+    let data = std::fs::read_to_string("vectors/sender_key_chain_v1.json").expect("vectors");
+    serde_json::from_str(&data).expect("json")
+}
+
+fn distribution(c: &Case) -> SenderKeyDistribution {
+    SenderKeyDistribution {
+        group_id: arr(&c.group_id_hex),
+        key_id: c.key_id,
+        iteration: c.iteration,
+        chain_key: arr(&c.chain_key_hex),
+        sig_pub: vec![0u8; SZ_MLDSA65_PUB],
+    }
+}
+
+fn message(c: &Case, r: &Recv) -> SenderKeyMessage {
+    SenderKeyMessage {
+        group_id: arr(&c.group_id_hex),
+        key_id: r.key_id.unwrap_or(c.key_id),
+        iteration: r.iteration,
+        ct: hex_to_bytes(&r.ct_hex),
+        sig: vec![0u8; SZ_MLDSA65_SIG],
+    }
+}
+
+/// Runs a receive sequence step by step; a rejected step must leave the receiver unchanged.
+fn run_receive(c: &Case) {
+    let s = StdCrypto;
+    let mut rx = SenderKeyReceiver::new(&distribution(c));
+    for r in &c.receive {
+        let before = rx.encode().expect("encode");
+        match rx.open(&s, &s, &s, &AcceptPqSig, &message(c, r)) {
+            Ok(pt) => {
+                assert_eq!(r.expect_error, None, "{} @{}", c.id, r.iteration);
+                let want = hex_to_bytes(r.pt_hex.as_deref().expect("pt_hex"));
+                assert_eq!(pt, want, "{} @{}", c.id, r.iteration);
+            }
+            Err(e) => {
+                let got = error_name(&e);
+                assert_eq!(Some(got), r.expect_error.as_deref(), "{}", c.id);
+                assert_eq!(
+                    rx.encode().expect("encode"),
+                    before,
+                    "{}: reject mutated state",
+                    c.id
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn sender_key_chain_matches_vectors() {
+    let s = StdCrypto;
+    for case in load().positive {
+        let group_id = arr(&case.group_id_hex);
+        let mut ck: [u8; 32] = arr(&case.chain_key_hex);
+        for step in &case.steps {
+            assert_eq!(
+                ck.to_vec(),
+                hex_to_bytes(&step.chain_key_hex),
+                "{}",
+                case.id
+            );
+            let (next, mk) = chain_step(&s, &ck).expect("step");
+            assert_eq!(
+                mk.to_vec(),
+                hex_to_bytes(&step.message_key_hex),
+                "{}",
+                case.id
+            );
+            let nonce = message_nonce(&s, &group_id, case.key_id, step.iteration);
+            assert_eq!(nonce.to_vec(), hex_to_bytes(&step.nonce_hex), "{}", case.id);
+            let ad = message_ad(&group_id, case.key_id, step.iteration);
+            assert_eq!(ad, hex_to_bytes(&step.ad_hex), "{}", case.id);
+            let ct = s.seal(&mk, &nonce, &ad, &hex_to_bytes(&step.pt_hex));
+            assert_eq!(ct, hex_to_bytes(&step.ct_hex), "{}", case.id);
+            ck = next;
+        }
+    }
+}
+
+#[test]
+fn sender_key_state_seals_vector_chains_from_zero() {
+    let s = StdCrypto;
+    for case in load().positive.into_iter().filter(|c| c.iteration == 0) {
+        let mut tx = SenderKeyState::new(
+            arr(&case.group_id_hex),
+            case.key_id,
+            arr(&case.chain_key_hex),
+            vec![0u8; SZ_MLDSA65_PUB],
+            vec![1u8; 32],
+        )
+        .expect("state");
+        for step in &case.steps {
+            let msg = tx
+                .seal(&s, &s, &s, &AcceptPqSig, &hex_to_bytes(&step.pt_hex))
+                .expect("seal");
+            assert_eq!(msg.iteration, step.iteration, "{}", case.id);
+            assert_eq!(msg.ct, hex_to_bytes(&step.ct_hex), "{}", case.id);
+            assert_eq!(
+                SenderKeyMessage::decode(&msg.encode().expect("encode")).expect("decode"),
+                msg
+            );
+        }
+    }
+}
+
+#[test]
+fn sender_key_positive_receive_sequences_open() {
+    for case in load().positive {
+        run_receive(&case);
+    }
+}
+
+#[test]
+fn sender_key_negative_receive_sequences_reject_without_mutation() {
+    for case in load().negative {
+        assert!(
+            case.receive.iter().any(|r| r.expect_error.is_some()),
+            "{}",
+            case.id
+        );
+        run_receive(&case);
+    }
+}
@@ -0,0 +1,109 @@
+use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
+use quantumshield_refimpl::crypto::stream::{StreamError, StreamNonce, StreamOpener, StreamSealer};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Root {
+    positive: Vec<Case>,
+    negative: Vec<Case>,
+}
+
+#[derive(Deserialize)]
+struct Case {
+    id: String,
+    nonce_mode: String,
+    key_hex: String,
+    prefix_hex: String,
+    chunks: Vec<Chunk>,
+    expect_error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Chunk {
+    ad_hex: String,
+    pt_hex: Option<String>,
+    last: bool,
+    ct_hex: String,
+}
+
+fn hex_to_bytes(s: &str) -> Vec<u8> {
+    hex::decode(s.trim()).expect("hex")
+}
+
+fn key(c: &Case) -> [u8; 32] {
+    hex_to_bytes(&c.key_hex).try_into().expect("key32")
+}
+
+fn nonce(c: &Case) -> StreamNonce {
+    let prefix = hex_to_bytes(&c.prefix_hex);
+    match c.nonce_mode.as_str() {
+        "stream" => StreamNonce::Stream(prefix.try_into().expect("prefix7")),
+        "prefix_counter" => StreamNonce::PrefixCounter(prefix.try_into().expect("prefix8")),
+        other => panic!("{}: unknown nonce_mode {other}", c.id),
+    }
+}
+
+fn error_name(e: &StreamError) -> &'static str {
+    match e {
+        StreamError::AuthFail => "AUTH_FAIL",
+        StreamError::SealFailed => "SEAL_FAILED",
+        StreamError::Finished => "FINISHED",
+        StreamError::CounterOverflow => "COUNTER_OVERFLOW",
+        StreamError::Truncated => "TRUNCATED",
+    }
+}
+
+fn load() -> Root {
+    let data = std::fs::read_to_string("vectors/stream_aead_v1.json").expect("vectors");
+    serde_json::from_str(&data).expect("json")
+}
+
+#[test]
+fn stream_aead_positive_vectors_seal_and_open() {
+    let c = StdCrypto;
+    for case in load().positive {
+        let mut sealer = StreamSealer::new(&c, &key(&case), nonce(&case));
+        let mut opener = StreamOpener::new(&c, &key(&case), nonce(&case));
+        for ch in &case.chunks {
+            let ad = hex_to_bytes(&ch.ad_hex);
+            let pt = hex_to_bytes(ch.pt_hex.as_deref().expect("pt_hex"));
+            let ct = hex_to_bytes(&ch.ct_hex);
+            assert_eq!(
+                sealer.seal_chunk(&ad, &pt, ch.last).expect("seal"),
+                ct,
+                "{}",
+                case.id
+            );
+            assert_eq!(
+                opener.open_chunk(&ad, &ct, ch.last).expect("open"),
+                pt,
+                "{}",
+                case.id
+            );
+        }
+        assert!(sealer.is_finished(), "{}", case.id);
+        opener.finish().expect(&case.id);
+    }
+}
+
+#[test]
+fn stream_aead_negative_vectors_reject() {
+    let c = StdCrypto;
+    for case in load().negative {
+        let want = case.expect_error.as_deref().expect("expect_error");
+        let mut opener = StreamOpener::new(&c, &key(&case), nonce(&case));
+        let mut got = None;
+        for ch in &case.chunks {
+            let ad = hex_to_bytes(&ch.ad_hex);
+            if let Err(e) = opener.open_chunk(&ad, &hex_to_bytes(&ch.ct_hex), ch.last) {
+                got = Some(e);
+                break;
+            }
+        }
+        let got = match got {
+            Some(e) => e,
+            None => opener.finish().expect_err(&case.id),
+        };
+        assert_eq!(error_name(&got), want, "{}", case.id);
+    }
+}
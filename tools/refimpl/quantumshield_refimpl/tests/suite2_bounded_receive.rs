@@ -1,5 +1,6 @@
 use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
 use quantumshield_refimpl::crypto::traits::{Aead, CryptoError};
+use quantumshield_refimpl::suite2::limits::Suite2Limits;
 use quantumshield_refimpl::suite2::ratchet::{
     recv_nonboundary_ooo, send_wire, Suite2RecvState, Suite2SendState,
 };
@@ -134,7 +135,16 @@ fn recv_invalid_message_caps_header_attempts() {
     let hdr_ct = vec![0u8; HDR_CT_LEN];
     let body_ct = vec![0u8; BODY_CT_MIN];
 
-    let out = recv_nonboundary_ooo(&c, &c, &aead, st, 0, &hdr_ct, &body_ct);
+    let out = recv_nonboundary_ooo(
+        &c,
+        &c,
+        &aead,
+        st,
+        0,
+        &hdr_ct,
+        &body_ct,
+        &Suite2Limits::default(),
+    );
 
     assert!(!out.ok);
     assert_eq!(out.reason, Some("REJECT_S2_HDR_AUTH_FAIL"));
@@ -170,7 +180,16 @@ fn recv_far_future_message_fails_fast_without_mutation() {
     let (hdr_ct, body_ct) = split_wire_for_nonboundary(&far_wire);
     let aead = CountingStdAead::new();
     let pre = snapshot_recv(&recv);
-    let out = recv_nonboundary_ooo(&c, &c, &aead, recv, 0, hdr_ct, body_ct);
+    let out = recv_nonboundary_ooo(
+        &c,
+        &c,
+        &aead,
+        recv,
+        0,
+        hdr_ct,
+        body_ct,
+        &Suite2Limits::default(),
+    );
 
     assert!(!out.ok);
     assert_eq!(out.reason, Some("REJECT_S2_HDR_AUTH_FAIL"));
@@ -200,7 +219,16 @@ fn recv_in_order_message_uses_small_attempt_count() {
     let (hdr_ct, body_ct) = split_wire_for_nonboundary(&wire);
     let aead = CountingStdAead::new();
 
-    let out = recv_nonboundary_ooo(&c, &c, &aead, recv, 0, hdr_ct, body_ct);
+    let out = recv_nonboundary_ooo(
+        &c,
+        &c,
+        &aead,
+        recv,
+        0,
+        hdr_ct,
+        body_ct,
+        &Suite2Limits::default(),
+    );
     assert!(out.ok);
     assert_eq!(out.plaintext.as_deref(), Some(b"hello".as_slice()));
     assert!(aead.opens() <= 3);
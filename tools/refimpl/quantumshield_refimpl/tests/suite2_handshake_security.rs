@@ -1,5 +1,8 @@
 use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
-use quantumshield_refimpl::suite2::establish::init_from_base_handshake;
+use quantumshield_refimpl::suite2::establish::{
+    init_from_base_handshake, init_from_negotiated_handshake,
+};
+use quantumshield_refimpl::suite2::negotiate::Suite2NegotiationInput;
 use quantumshield_refimpl::suite2::types::{SUITE2_PROTOCOL_VERSION, SUITE2_SUITE_ID};
 
 fn derive_send_hk(dh_init: [u8; 32], pq_init_ss: [u8; 32], sid: [u8; 16]) -> [u8; 32] {
@@ -35,3 +38,77 @@ fn suite2_init_depends_on_both_pq_and_dh_inputs() {
     assert_ne!(hk_a, hk_b, "dh_init must affect key schedule");
     assert_ne!(hk_a, hk_c, "pq_init_ss must affect key schedule");
 }
+
+#[test]
+fn establish_rejects_unknown_suite_and_version() {
+    let c = StdCrypto;
+    for (pv, sid) in [
+        (SUITE2_PROTOCOL_VERSION, 0x0004),
+        (SUITE2_PROTOCOL_VERSION, 0x0003),
+        (SUITE2_PROTOCOL_VERSION, 0x0001),
+        (0x0403, SUITE2_SUITE_ID),
+    ] {
+        let err = init_from_base_handshake(
+            &c,
+            true,
+            pv,
+            sid,
+            &[0x31; 16],
+            &[0x41; 32],
+            &[0x51; 32],
+            &[0x61; 32],
+            &[0x71; 32],
+            true,
+        )
+        .err();
+        assert_eq!(
+            err,
+            Some("REJECT_S2_SUITE_MISMATCH"),
+            "{pv:#06x}/{sid:#06x}"
+        );
+    }
+}
+
+#[test]
+fn negotiated_establish_runs_the_downgrade_check_first() {
+    const OURS: &[u16] = &[SUITE2_SUITE_ID];
+    let input = |offered, sid, ad_sid| Suite2NegotiationInput {
+        local: OURS,
+        peer_offered: offered,
+        peer_committed: OURS,
+        negotiated_protocol_version: SUITE2_PROTOCOL_VERSION,
+        negotiated_suite_id: sid,
+        ad_protocol_version: SUITE2_PROTOCOL_VERSION,
+        ad_suite_id: ad_sid,
+    };
+    let establish = |input: &Suite2NegotiationInput<'_>| {
+        init_from_negotiated_handshake(
+            &StdCrypto,
+            true,
+            input,
+            &[0x31; 16],
+            &[0x41; 32],
+            &[0x51; 32],
+            &[0x61; 32],
+            &[0x71; 32],
+            true,
+        )
+        .map(|st| st.send.suite_id)
+    };
+    assert_eq!(
+        establish(&input(OURS, SUITE2_SUITE_ID, SUITE2_SUITE_ID)),
+        Ok(SUITE2_SUITE_ID)
+    );
+    assert_eq!(
+        establish(&input(OURS, SUITE2_SUITE_ID, 0x0003)),
+        Err("REJECT_S2_AD_MISMATCH")
+    );
+    assert_eq!(
+        establish(&input(&[], SUITE2_SUITE_ID, SUITE2_SUITE_ID)),
+        Err("REJECT_S2_CAPABILITY_COMMITMENT_MISMATCH")
+    );
+    assert_eq!(
+        establish(&input(OURS, 0x0003, 0x0003)),
+        Err("REJECT_S2_SUITE_MISMATCH")
+    );
+}
@@ -0,0 +1,585 @@
+use quantumshield_refimpl::crypto::stdcrypto::StdCrypto;
+use quantumshield_refimpl::crypto::traits::{Aead, CryptoError};
+use quantumshield_refimpl::suite2::limits::{
+    Suite2Limits, Suite2LimitsError, DEFAULT_MAX_HEADER_ATTEMPTS, DEFAULT_MAX_MKSKIPPED,
+    DEFAULT_MAX_SKIP, MAX_HEADER_ATTEMPTS_CEILING, MAX_MKSKIPPED_CEILING, MAX_SKIP_CEILING,
+    MKSKIPPED_MAX_AGE_CEILING_SECS,
+};
+use quantumshield_refimpl::suite2::ratchet::{
+    recv_nonboundary_ooo, MkSkippedEntry, Suite2DhRatchetState, Suite2RecvState,
+    Suite2RecvWireState, Suite2SendState,
+};
+use quantumshield_refimpl::suite2::state::{Suite2SessionState, Suite2StateError};
+use quantumshield_refimpl::suite2::{recv_wire_canon, send_wire_canon, types};
+use quantumshield_refimpl::RefimplError;
+use std::cell::Cell;
+use std::collections::BTreeSet;
+
+const HDR_CT_LEN: usize = 24;
+const BODY_CT_MIN: usize = 16;
+const LIMITS_TRAILER_LEN: usize = 16;
+
+struct CountingRejectAead {
+    opens: Cell<usize>,
+}
+
+impl Aead for CountingRejectAead {
+    fn seal(&self, _key32: &[u8; 32], _nonce12: &[u8; 12], _ad: &[u8], _pt: &[u8]) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn open(
+        &self,
+        _key32: &[u8; 32],
+        _nonce12: &[u8; 12],
+        _ad: &[u8],
+        _ct: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.opens.set(self.opens.get().saturating_add(1));
+        Err(CryptoError::AuthFail)
+    }
+}
+
+fn arr16(seed: u8) -> [u8; 16] {
+    std::array::from_fn(|i| seed.wrapping_add((i as u8).wrapping_mul(3)).rotate_left(1))
+}
+
+fn arr32(seed: u8) -> [u8; 32] {
+    std::array::from_fn(|i| seed.wrapping_add((i as u8).wrapping_mul(5)).rotate_left(1))
+}
+
+fn base_session() -> Suite2SessionState {
+    let session_id = arr16(0x11);
+    let dh_pub = arr32(0x21);
+    let hk = arr32(0x31);
+    let ck_ec = arr32(0x41);
+    let ck_pq = arr32(0x51);
+
+    Suite2SessionState {
+        rk: arr32(0x61),
+        send: Suite2SendState {
+            session_id,
+            protocol_version: types::SUITE2_PROTOCOL_VERSION,
+            suite_id: types::SUITE2_SUITE_ID,
+            dh_pub,
+            hk_s: hk,
+            ck_ec,
+            ck_pq,
+            ns: 0,
+            pn: 0,
+        },
+        recv: Suite2RecvWireState {
+            session_id,
+            protocol_version: types::SUITE2_PROTOCOL_VERSION,
+            suite_id: types::SUITE2_SUITE_ID,
+            dh_pub,
+            hk_r: hk,
+            ck_ec,
+            ck_pq_send: arr32(0x71),
+            ck_pq_recv: ck_pq,
+            nr: 0,
+            role_is_a: true,
+            peer_max_adv_id_seen: 0,
+            known_targets: BTreeSet::new(),
+            consumed_targets: BTreeSet::new(),
+            tombstoned_targets: BTreeSet::new(),
+            mkskipped: Vec::new(),
+            limits: Suite2Limits::default(),
+            dh_epoch: 0,
+            now_secs: 0,
+        },
+        dh: Suite2DhRatchetState::default(),
+    }
+}
+
+/// Wires for n = 0..count sent from `session.send` (the receive side of the same session
+/// mirrors its chains, so every wire is receivable by `session.recv`).
+fn sent_wires(session: &Suite2SessionState, count: usize) -> Vec<Vec<u8>> {
+    let c = StdCrypto;
+    let mut send = session.send.clone();
+    let mut wires = Vec::with_capacity(count);
+    for _ in 0..count {
+        let out = send_wire_canon(&c, &c, &c, send, 0, b"limits").expect("send wire");
+        send = out.state;
+        wires.push(out.wire);
+    }
+    wires
+}
+
+fn split_wire_for_nonboundary(wire: &[u8]) -> (&[u8], &[u8]) {
+    let header_offset = 10usize;
+    let hdr_ct_start = header_offset + 32 + 2;
+    let hdr_ct_end = hdr_ct_start + HDR_CT_LEN;
+    (&wire[hdr_ct_start..hdr_ct_end], &wire[hdr_ct_end..])
+}
+
+fn ooo_state(session: &Suite2SessionState) -> Suite2RecvState {
+    Suite2RecvState {
+        session_id: session.recv.session_id,
+        protocol_version: session.recv.protocol_version,
+        suite_id: session.recv.suite_id,
+        dh_pub: session.recv.dh_pub,
+        hk_r: session.recv.hk_r,
+        ck_ec: session.recv.ck_ec,
+        ck_pq: session.recv.ck_pq_recv,
+        nr: session.recv.nr,
+        mkskipped: session.recv.mkskipped.clone(),
+    }
+}
+
+fn recv_into_session(
+    session: &mut Suite2SessionState,
+    wire: &[u8],
+) -> Result<Vec<u8>, RefimplError> {
+    let c = StdCrypto;
+    let out = recv_wire_canon(
+        &c,
+        &c,
+        &c,
+        session.recv.clone(),
+        &session.rk,
+        wire,
+        None,
+        None,
+    )?;
+    session.recv = out.state;
+    session.rk = out.rk;
+    Ok(out.plaintext)
+}
+
+fn limits(max_skip: u32, max_mkskipped: usize, max_header_attempts: usize) -> Suite2Limits {
+    Suite2Limits::new(max_skip, max_mkskipped, max_header_attempts).expect("valid limits")
+}
+
+#[test]
+fn default_limits_are_the_historical_bounds() {
+    let d = Suite2Limits::default();
+    assert_eq!(d.max_skip(), DEFAULT_MAX_SKIP);
+    assert_eq!(d.max_mkskipped(), DEFAULT_MAX_MKSKIPPED);
+    assert_eq!(d.max_header_attempts(), DEFAULT_MAX_HEADER_ATTEMPTS);
+    assert_eq!(
+        Suite2Limits::new(
+            DEFAULT_MAX_SKIP,
+            DEFAULT_MAX_MKSKIPPED,
+            DEFAULT_MAX_HEADER_ATTEMPTS
+        ),
+        Ok(d)
+    );
+}
+
+#[test]
+fn limits_validate_each_field_at_zero_one_ceiling_and_past_ceiling() {
+    assert_eq!(Suite2Limits::new(0, 1, 1), Err(Suite2LimitsError::MaxSkip));
+    assert_eq!(
+        Suite2Limits::new(MAX_SKIP_CEILING + 1, 1, 1),
+        Err(Suite2LimitsError::MaxSkip)
+    );
+    assert_eq!(
+        Suite2Limits::new(1, 0, 1),
+        Err(Suite2LimitsError::MaxMkSkipped)
+    );
+    assert_eq!(
+        Suite2Limits::new(1, MAX_MKSKIPPED_CEILING + 1, 1),
+        Err(Suite2LimitsError::MaxMkSkipped)
+    );
+    assert_eq!(
+        Suite2Limits::new(1, 1, 0),
+        Err(Suite2LimitsError::MaxHeaderAttempts)
+    );
+    assert_eq!(
+        Suite2Limits::new(1, 1, MAX_HEADER_ATTEMPTS_CEILING + 1),
+        Err(Suite2LimitsError::MaxHeaderAttempts)
+    );
+
+    let low = limits(1, 1, 1);
+    assert_eq!(
+        (
+            low.max_skip(),
+            low.max_mkskipped(),
+            low.max_header_attempts()
+        ),
+        (1, 1, 1)
+    );
+    let high = limits(
+        MAX_SKIP_CEILING,
+        MAX_MKSKIPPED_CEILING,
+        MAX_HEADER_ATTEMPTS_CEILING,
+    );
+    assert_eq!(
+        (
+            high.max_skip(),
+            high.max_mkskipped(),
+            high.max_header_attempts()
+        ),
+        (
+            MAX_SKIP_CEILING,
+            MAX_MKSKIPPED_CEILING,
+            MAX_HEADER_ATTEMPTS_CEILING
+        )
+    );
+}
+
+#[test]
+fn max_skip_accepts_the_gap_at_the_limit_and_rejects_one_past_it() {
+    let c = StdCrypto;
+    let session = base_session();
+    let wires = sent_wires(&session, 10);
+    let lim = limits(8, DEFAULT_MAX_MKSKIPPED, DEFAULT_MAX_HEADER_ATTEMPTS);
+
+    let (hdr_ct, body_ct) = split_wire_for_nonboundary(&wires[8]);
+    let out = recv_nonboundary_ooo(&c, &c, &c, ooo_state(&session), 0, hdr_ct, body_ct, &lim);
+    assert!(out.ok, "gap == max_skip must be accepted: {:?}", out.reason);
+    assert_eq!(out.n, Some(8));
+    assert_eq!(out.state.nr, 9);
+    assert_eq!(out.state.mkskipped.len(), 8);
+
+    let (hdr_ct, body_ct) = split_wire_for_nonboundary(&wires[9]);
+    let out = recv_nonboundary_ooo(&c, &c, &c, ooo_state(&session), 0, hdr_ct, body_ct, &lim);
+    assert!(!out.ok);
+    assert_eq!(out.reason, Some("REJECT_S2_OOO_BOUNDS"));
+    assert_eq!(out.state.nr, 0);
+    assert!(out.state.mkskipped.is_empty());
+}
+
+#[test]
+fn max_mkskipped_evicts_oldest_skipped_keys() {
+    let c = StdCrypto;
+    let session = base_session();
+    let wires = sent_wires(&session, 9);
+    let lim = limits(8, 3, DEFAULT_MAX_HEADER_ATTEMPTS);
+
+    let (hdr_ct, body_ct) = split_wire_for_nonboundary(&wires[8]);
+    let out = recv_nonboundary_ooo(&c, &c, &c, ooo_state(&session), 0, hdr_ct, body_ct, &lim);
+    assert!(out.ok, "{:?}", out.reason);
+    let kept: Vec<u32> = out.state.mkskipped.iter().map(|e| e.n).collect();
+    assert_eq!(kept, vec![5, 6, 7]);
+
+    // An evicted key is gone for good; a retained one still opens.
+    let st = out.state;
+    let (hdr_ct, body_ct) = split_wire_for_nonboundary(&wires[4]);
+    let evicted = recv_nonboundary_ooo(&c, &c, &c, st.clone(), 0, hdr_ct, body_ct, &lim);
+    assert!(!evicted.ok);
+    let (hdr_ct, body_ct) = split_wire_for_nonboundary(&wires[6]);
+    let retained = recv_nonboundary_ooo(&c, &c, &c, st, 0, hdr_ct, body_ct, &lim);
+    assert!(retained.ok, "{:?}", retained.reason);
+    assert_eq!(retained.plaintext.as_deref(), Some(b"limits".as_slice()));
+}
+
+#[test]
+fn max_header_attempts_caps_trial_decryptions_exactly() {
+    let c = StdCrypto;
+    let session = base_session();
+    let hdr_ct = vec![0u8; HDR_CT_LEN];
+    let body_ct = vec![0u8; BODY_CT_MIN];
+
+    for cap in [1usize, 7, DEFAULT_MAX_HEADER_ATTEMPTS] {
+        let aead = CountingRejectAead {
+            opens: Cell::new(0),
+        };
+        let lim = limits(DEFAULT_MAX_SKIP, DEFAULT_MAX_MKSKIPPED, cap);
+        let out = recv_nonboundary_ooo(
+            &c,
+            &c,
+            &aead,
+            ooo_state(&session),
+            0,
+            &hdr_ct,
+            &body_ct,
+            &lim,
+        );
+        assert!(!out.ok);
+        assert_eq!(out.reason, Some("REJECT_S2_HDR_AUTH_FAIL"));
+        assert_eq!(aead.opens.get(), cap);
+    }
+}
+
+#[test]
+fn recv_wire_applies_the_session_limits() {
+    let mut session = base_session();
+    let wires = sent_wires(&session, 10);
+
+    // Default limits: a gap of 9 is well within bounds.
+    let mut default_session = session.clone();
+    assert_eq!(
+        recv_into_session(&mut default_session, &wires[9]).expect("default limits"),
+        b"limits".to_vec()
+    );
+
+    session.set_recv_limits(limits(
+        8,
+        DEFAULT_MAX_MKSKIPPED,
+        DEFAULT_MAX_HEADER_ATTEMPTS,
+    ));
+    let before = session.snapshot_bytes();
+    let err = recv_into_session(&mut session, &wires[9]).expect_err("gap past max_skip");
+    assert!(
+        err.to_string().contains("reason_code=REJECT_S2_OOO_BOUNDS"),
+        "{err}"
+    );
+    assert_eq!(before, session.snapshot_bytes(), "reject must not mutate");
+
+    recv_into_session(&mut session, &wires[8]).expect("gap at max_skip");
+    assert_eq!(session.recv.nr, 9);
+}
+
+#[test]
+fn set_recv_limits_evicts_down_to_a_lowered_cap() {
+    let mut session = base_session();
+    for n in 0..5u32 {
+        session.recv.mkskipped.push(MkSkippedEntry {
+            dh_pub: session.recv.dh_pub,
+            n,
+            mk: arr32(0x80 + n as u8),
+            dh_epoch: 0,
+            stored_at: 0,
+        });
+    }
+    session.set_recv_limits(limits(DEFAULT_MAX_SKIP, 2, DEFAULT_MAX_HEADER_ATTEMPTS));
+    let kept: Vec<u32> = session.recv.mkskipped.iter().map(|e| e.n).collect();
+    assert_eq!(kept, vec![3, 4]);
+}
+
+#[test]
+fn snapshot_round_trips_custom_limits_and_keeps_default_layout_unchanged() {
+    let session = base_session();
+    let default_bytes = session.snapshot_bytes();
+    let restored = Suite2SessionState::restore_bytes(&default_bytes).expect("restore default");
+    assert_eq!(restored.recv.limits, Suite2Limits::default());
+
+    let mut custom = session.clone();
+    let lim = limits(MAX_SKIP_CEILING, 3, 1);
+    custom.set_recv_limits(lim);
+    let custom_bytes = custom.snapshot_bytes();
+    assert_eq!(custom_bytes.len(), default_bytes.len() + LIMITS_TRAILER_LEN);
+    assert_eq!(
+        &custom_bytes[..default_bytes.len()],
+        default_bytes.as_slice()
+    );
+    assert_eq!(&custom_bytes[default_bytes.len()..][..4], b"QS2L");
+
+    let restored = Suite2SessionState::restore_bytes(&custom_bytes).expect("restore custom");
+    assert_eq!(restored.recv.limits, lim);
+    assert_eq!(restored.snapshot_bytes(), custom_bytes);
+}
+
+#[test]
+fn snapshot_restore_rejects_bad_limits_trailers() {
+    let mut session = base_session();
+    session.set_recv_limits(limits(8, 3, 1));
+    let bytes = session.snapshot_bytes();
+    let trailer = bytes.len() - LIMITS_TRAILER_LEN;
+
+    let mut bad_tag = bytes.clone();
+    bad_tag[trailer] ^= 0x01;
+    assert!(matches!(
+        Suite2SessionState::restore_bytes(&bad_tag),
+        Err(Suite2StateError::Invalid("bad suite2 snapshot"))
+    ));
+
+    // Each field out of range: zero max_skip, oversize max_mkskipped, zero attempts.
+    for (off, val) in [
+        (4usize, 0u32),
+        (8, MAX_MKSKIPPED_CEILING as u32 + 1),
+        (12, 0),
+    ] {
+        let mut bad = bytes.clone();
+        bad[trailer + off..trailer + off + 4].copy_from_slice(&val.to_be_bytes());
+        assert!(matches!(
+            Suite2SessionState::restore_bytes(&bad),
+            Err(Suite2StateError::Invalid("bad suite2 snapshot limits"))
+        ));
+    }
+
+    // A truncated trailer is neither "no trailer" nor a trailer.
+    assert!(matches!(
+        Suite2SessionState::restore_bytes(&bytes[..bytes.len() - 1]),
+        Err(Suite2StateError::Invalid("bad suite2 snapshot"))
+    ));
+}
+
+#[test]
+fn snapshot_restore_rejects_more_skipped_keys_than_the_persisted_cap() {
+    let mut session = base_session();
+    session.set_recv_limits(limits(DEFAULT_MAX_SKIP, 2, DEFAULT_MAX_HEADER_ATTEMPTS));
+    for n in 0..3u32 {
+        session.recv.mkskipped.push(MkSkippedEntry {
+            dh_pub: session.recv.dh_pub,
+            n,
+            mk: arr32(0x90 + n as u8),
+            dh_epoch: 0,
+            stored_at: 0,
+        });
+    }
+    assert!(matches!(
+        Suite2SessionState::restore_bytes(&session.snapshot_bytes()),
+        Err(Suite2StateError::Invalid("bad suite2 snapshot"))
+    ));
+
+    session.recv.mkskipped.pop();
+    let restored =
+        Suite2SessionState::restore_bytes(&session.snapshot_bytes()).expect("at the cap");
+    assert_eq!(restored.recv.mkskipped.len(), 2);
+}
+
+#[test]
+fn expiry_bounds_validate_and_default_to_none() {
+    let d = Suite2Limits::default();
+    assert_eq!(d.mkskipped_max_age_secs(), None);
+    assert_eq!(d.mkskipped_max_dh_steps(), None);
+    assert_eq!(
+        d.with_mkskipped_max_age(0),
+        Err(Suite2LimitsError::MkSkippedMaxAge)
+    );
+    assert_eq!(
+        d.with_mkskipped_max_age(MKSKIPPED_MAX_AGE_CEILING_SECS + 1),
+        Err(Suite2LimitsError::MkSkippedMaxAge)
+    );
+    assert_eq!(
+        d.with_mkskipped_max_dh_steps(0),
+        Err(Suite2LimitsError::MkSkippedMaxDhSteps)
+    );
+    let both = d
+        .with_mkskipped_max_age(MKSKIPPED_MAX_AGE_CEILING_SECS)
+        .and_then(|l| l.with_mkskipped_max_dh_steps(1))
+        .expect("valid expiry");
+    assert_eq!(
+        both.mkskipped_max_age_secs(),
+        Some(MKSKIPPED_MAX_AGE_CEILING_SECS)
+    );
+    assert_eq!(both.mkskipped_max_dh_steps(), Some(1));
+    assert_eq!(both.max_skip(), DEFAULT_MAX_SKIP);
+}
+
+fn aged_session(max_age: u64) -> (Suite2SessionState, Vec<Vec<u8>>) {
+    let mut session = base_session();
+    let wires = sent_wires(&session, 7);
+    session.set_recv_limits(
+        Suite2Limits::default()
+            .with_mkskipped_max_age(max_age)
+            .expect("age"),
+    );
+    (session, wires)
+}
+
+#[test]
+fn skipped_keys_expire_by_age_on_the_caller_clock() {
+    let (mut session, wires) = aged_session(60);
+    assert_eq!(session.expire_mkskipped(1_000), 0);
+    recv_into_session(&mut session, &wires[3]).expect("n=3");
+    let stamps: Vec<(u32, u64)> = session
+        .recv
+        .mkskipped
+        .iter()
+        .map(|e| (e.n, e.stored_at))
+        .collect();
+    assert_eq!(stamps, vec![(0, 1_000), (1, 1_000), (2, 1_000)]);
+
+    assert_eq!(session.expire_mkskipped(1_059), 0);
+    // A rewound clock is ignored rather than extending the keys' life.
+    assert_eq!(session.expire_mkskipped(500), 0);
+    assert_eq!(session.recv.now_secs, 1_059);
+    assert_eq!(session.expire_mkskipped(1_060), 3);
+    assert!(session.recv.mkskipped.is_empty());
+    assert!(recv_into_session(&mut session, &wires[1]).is_err());
+
+    // Keys skipped later carry the later time.
+    recv_into_session(&mut session, &wires[6]).expect("n=6");
+    let stamps: Vec<(u32, u64)> = session
+        .recv
+        .mkskipped
+        .iter()
+        .map(|e| (e.n, e.stored_at))
+        .collect();
+    assert_eq!(stamps, vec![(4, 1_060), (5, 1_060)]);
+    recv_into_session(&mut session, &wires[5]).expect("live skipped key opens");
+}
+
+#[test]
+fn snapshot_persists_expiry_policy_clock_and_stamps() {
+    let base_len = base_session().snapshot_bytes().len();
+    let (mut session, wires) = aged_session(60);
+    session.expire_mkskipped(1_000);
+    recv_into_session(&mut session, &wires[3]).expect("n=3");
+
+    let bytes = session.snapshot_bytes();
+    // No QS2L (bounds are default); QS2E = 4 + 8 + 4 + 4 + 8 + 12 per key.
+    assert_eq!(&bytes[base_len + 3 * 68..][..4], b"QS2E");
+    assert_eq!(bytes.len(), base_len + 3 * 68 + 28 + 3 * 12);
+
+    let restored = Suite2SessionState::restore_bytes(&bytes).expect("restore");
+    assert_eq!(restored.recv.limits, session.recv.limits);
+    assert_eq!(restored.recv.now_secs, 1_000);
+    let stamps: Vec<(u32, u32, u64)> = restored
+        .recv
+        .mkskipped
+        .iter()
+        .map(|e| (e.n, e.dh_epoch, e.stored_at))
+        .collect();
+    assert_eq!(stamps, vec![(0, 0, 1_000), (1, 0, 1_000), (2, 0, 1_000)]);
+    assert_eq!(restored.snapshot_bytes(), bytes);
+
+    // The purge is persisted too: after expiry the restored state has no skipped keys.
+    session.expire_mkskipped(2_000);
+    let restored =
+        Suite2SessionState::restore_bytes(&session.snapshot_bytes()).expect("restore purged");
+    assert!(restored.recv.mkskipped.is_empty());
+    assert_eq!(restored.recv.now_secs, 2_000);
+}
+
+#[test]
+fn snapshot_restore_rejects_inconsistent_expiry_sections() {
+    let invalid = |bytes: &[u8]| {
+        matches!(
+            Suite2SessionState::restore_bytes(bytes),
+            Err(Suite2StateError::Invalid("bad suite2 snapshot"))
+        )
+    };
+    let entry = |n: u32, stored_at: u64| MkSkippedEntry {
+        dh_pub: arr32(0x21),
+        n,
+        mk: arr32(0xa0),
+        dh_epoch: 0,
+        stored_at,
+    };
+
+    // A key stamped after the persisted clock.
+    let mut future = base_session();
+    future.recv.now_secs = 100;
+    future.recv.mkskipped.push(entry(0, 101));
+    assert!(invalid(&future.snapshot_bytes()));
+
+    // A key the persisted policy would already have purged.
+    let mut stale = base_session();
+    stale.recv.limits = Suite2Limits::default().with_mkskipped_max_age(10).unwrap();
+    stale.recv.now_secs = 100;
+    stale.recv.mkskipped.push(entry(0, 90));
+    assert!(invalid(&stale.snapshot_bytes()));
+    stale.recv.mkskipped[0].stored_at = 91;
+    Suite2SessionState::restore_bytes(&stale.snapshot_bytes()).expect("still live");
+
+    // Sections out of order or repeated.
+    let base_len = base_session().snapshot_bytes().len();
+    let mut both = base_session();
+    both.set_recv_limits(
+        limits(8, 3, 1)
+            .with_mkskipped_max_dh_steps(2)
+            .expect("steps"),
+    );
+    let bytes = both.snapshot_bytes();
+    let (body, trailer) = bytes.split_at(base_len);
+    let (l, e) = trailer.split_at(LIMITS_TRAILER_LEN);
+    assert_eq!((&l[..4], &e[..4]), (&b"QS2L"[..], &b"QS2E"[..]));
+    assert!(invalid(&[body, e, l].concat()));
+    assert!(invalid(&[body, l, e, e].concat()));
+    assert!(invalid(&[body, l, l, e].concat()));
+
+    // An out-of-range age in the section is a limits error.
+    let mut bad_age = bytes.clone();
+    let age_at = base_len + LIMITS_TRAILER_LEN + 4;
+    bad_age[age_at..age_at + 8]
+        .copy_from_slice(&(MKSKIPPED_MAX_AGE_CEILING_SECS + 1).to_be_bytes());
+    assert!(matches!(
+        Suite2SessionState::restore_bytes(&bad_age),
+        Err(Suite2StateError::Invalid("bad suite2 snapshot limits"))
+    ));
+}
@@ -0,0 +1,480 @@
+//! `Suite2Session`: encrypt/decrypt drive the DH ratchet and SCKA advertise/reseed on their
+//! own, and every failure leaves the session unchanged.
+//!
+//! ML-KEM is not needed to exercise the orchestration: `XorKemProvider` wraps `StdProvider`
+//! with a size-correct toy KEM (secret = first 32 bytes of the public key, ct = ss ^ secret).
+//!
+//! Snapshots carry the SCKA store: a session restored between advertising and receiving the
+//! peer's reseed still opens it.
+
+use quantumshield_refimpl::crypto::provider::{CryptoProvider, StdProvider};
+use quantumshield_refimpl::crypto::traits::{
+    Aead, CryptoError, Hash, Kmac, PqKem768, SigEd25519, X25519Dh,
+};
+use quantumshield_refimpl::snapshot::SnapshotError;
+use quantumshield_refimpl::suite2::establish::init_from_base_handshake;
+use quantumshield_refimpl::suite2::limits::Suite2Limits;
+use quantumshield_refimpl::suite2::parse::decode_suite2_wire_for;
+use quantumshield_refimpl::suite2::reject::{Suite2RejectCategory, Suite2RejectReason};
+use quantumshield_refimpl::suite2::session::{
+    Suite2Session, Suite2SessionError, Suite2SessionPolicy, Suite2SessionPolicyError,
+    RATCHET_AFTER_MSGS_CEILING, RESEED_AFTER_BOUNDARIES_CEILING,
+};
+use quantumshield_refimpl::suite2::types::{self, Suite2Params};
+
+struct XorKemProvider {
+    std: StdProvider,
+}
+
+struct XorKem(&'static Suite2Params);
+
+impl XorKem {
+    fn keypair(&self, x: &dyn X25519Dh) -> (Vec<u8>, Vec<u8>) {
+        let sk = x.keypair().0 .0.to_vec();
+        let mut pk = sk.clone();
+        pk.resize(self.0.kem_pub_len, 0x5a);
+        (pk, sk)
+    }
+
+    fn encap_with(&self, x: &dyn X25519Dh, pubk: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        if pubk.len() != self.0.kem_pub_len {
+            return Err(CryptoError::InvalidKey);
+        }
+        let ss = x.keypair().0 .0.to_vec();
+        let mut ct: Vec<u8> = ss.iter().zip(pubk).map(|(s, k)| s ^ k).collect();
+        ct.resize(self.0.kem_ct_len, 0);
+        Ok((ct, ss))
+    }
+
+    fn decap_with(&self, privk: &[u8], ct: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if ct.len() != self.0.kem_ct_len || privk.len() != 32 {
+            return Err(CryptoError::InvalidKey);
+        }
+        Ok(ct.iter().zip(privk).map(|(c, k)| c ^ k).collect())
+    }
+}
+
+const KEM768: XorKem = XorKem(&types::SUITE2_PARAMS);
+
+impl PqKem768 for XorKemProvider {
+    fn encap(&self, pubk: &[u8]) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        KEM768.encap_with(self.std.x25519(), pubk)
+    }
+    fn decap(&self, privk: &[u8], ct: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        KEM768.decap_with(privk, ct)
+    }
+}
+
+impl CryptoProvider for XorKemProvider {
+    fn name(&self) -> &'static str {
+        "xor-kem-test"
+    }
+    fn hash(&self) -> &dyn Hash {
+        self.std.hash()
+    }
+    fn kmac(&self) -> &dyn Kmac {
+        self.std.kmac()
+    }
+    fn aead(&self) -> &dyn Aead {
+        self.std.aead()
+    }
+    fn x25519(&self) -> &dyn X25519Dh {
+        self.std.x25519()
+    }
+    fn ed25519(&self) -> &dyn SigEd25519 {
+        self.std.ed25519()
+    }
+    fn pq_kem(&self) -> Option<&dyn PqKem768> {
+        Some(self)
+    }
+    fn pq_kem_keypair(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        Some(KEM768.keypair(self.std.x25519()))
+    }
+}
+
+fn provider() -> XorKemProvider {
+    XorKemProvider {
+        std: StdProvider::new(),
+    }
+}
+
+fn establish_pair(
+    p: &dyn CryptoProvider,
+    suite_id: u16,
+    policy: Suite2SessionPolicy,
+) -> (Suite2Session, Suite2Session) {
+    let x = p.x25519();
+    let (a_priv, a_pub) = x.keypair();
+    let (b_priv, b_pub) = x.keypair();
+    let dh_init = x.dh(&a_priv, &b_pub);
+    let init = |role_is_a: bool, self_pub: &[u8; 32], peer_pub: &[u8; 32]| {
+        init_from_base_handshake(
+            p.kmac(),
+            role_is_a,
+            types::SUITE2_PROTOCOL_VERSION,
+            suite_id,
+            &[0x07; 16],
+            &dh_init,
+            &[0x42; 32],
+            self_pub,
+            peer_pub,
+            true,
+        )
+        .expect("establish")
+    };
+    let mut a = init(true, &a_pub.0, &b_pub.0);
+    let mut b = init(false, &b_pub.0, &a_pub.0);
+    a.set_dh_self_priv(a_priv.0);
+    b.set_dh_self_priv(b_priv.0);
+    (
+        Suite2Session::with_policy(a, policy),
+        Suite2Session::with_policy(b, policy),
+    )
+}
+
+fn flags_of(wire: &[u8], suite_id: u16) -> u16 {
+    decode_suite2_wire_for(wire, suite_id)
+        .expect("parse")
+        .3
+        .flags
+}
+
+/// Send `count` messages `from -> to`, deliver them in order, and return every wire's flags.
+fn burst(
+    p: &dyn CryptoProvider,
+    from: &mut Suite2Session,
+    to: &mut Suite2Session,
+    count: usize,
+    tag: &str,
+) -> Vec<u16> {
+    let suite_id = from.state().send.suite_id;
+    let mut flags = Vec::new();
+    for i in 0..count {
+        let pt = format!("{tag}-{i}").into_bytes();
+        let wires = from.encrypt(p, &pt).expect("encrypt");
+        let (last, control) = wires.split_last().expect("at least one wire");
+        for w in control {
+            flags.push(flags_of(w, suite_id));
+            assert_eq!(to.decrypt(p, w).expect("control"), None);
+        }
+        flags.push(flags_of(last, suite_id));
+        assert_eq!(to.decrypt(p, last).expect("decrypt"), Some(pt));
+    }
+    flags
+}
+
+fn conversation_exercises_every_arm(suite_id: u16) {
+    let p = provider();
+    let (mut a, mut b) = establish_pair(&p, suite_id, Suite2SessionPolicy::default());
+    let rk0 = a.state().rk;
+    let mut flags = Vec::new();
+    for round in 0..4 {
+        flags.extend(burst(&p, &mut a, &mut b, 3, &format!("a{round}")));
+        flags.extend(burst(&p, &mut b, &mut a, 3, &format!("b{round}")));
+    }
+    let has = |want: u16| flags.contains(&want);
+    assert!(has(types::FLAG_BOUNDARY), "no DH boundary");
+    assert!(
+        has(types::FLAG_PQ_ADV | types::FLAG_BOUNDARY),
+        "no advertisement"
+    );
+    assert!(has(types::FLAG_PQ_CTXT | types::FLAG_BOUNDARY), "no reseed");
+    assert!(has(0), "no plain chain message");
+    assert_eq!(a.state().rk, b.state().rk, "roots diverged");
+    assert_ne!(a.state().rk, rk0, "root never advanced");
+}
+
+#[test]
+fn cat3_conversation_ratchets_advertises_and_reseeds() {
+    conversation_exercises_every_arm(types::SUITE2_SUITE_ID);
+}
+
+#[test]
+fn rejects_leave_the_session_unchanged() {
+    let p = provider();
+    let (mut a, mut b) = establish_pair(&p, types::SUITE2_SUITE_ID, Suite2SessionPolicy::default());
+    burst(&p, &mut a, &mut b, 2, "warmup");
+    burst(&p, &mut b, &mut a, 2, "reply");
+
+    let wires = a.encrypt(&p, b"payload").expect("encrypt");
+    let wire = wires.last().expect("wire").clone();
+    let mut tampered = wire.clone();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 0x01;
+
+    let before = b.state().snapshot_bytes();
+    for (bad, category) in [
+        (&tampered[..], Suite2RejectCategory::AuthFail),
+        (&wire[..8], Suite2RejectCategory::Malformed),
+    ] {
+        let err = b.decrypt(&p, bad).expect_err("reject");
+        assert!(matches!(err, Suite2SessionError::Reject(_)), "{err}");
+        assert_eq!(err.reason().map(|r| r.category()), Some(category), "{err}");
+        assert_eq!(before, b.state().snapshot_bytes(), "{err} mutated state");
+    }
+
+    for w in &wires[..wires.len() - 1] {
+        assert_eq!(b.decrypt(&p, w).expect("control"), None);
+    }
+    assert_eq!(
+        b.decrypt(&p, &wire).expect("decrypt"),
+        Some(b"payload".to_vec())
+    );
+    let after = b.state().snapshot_bytes();
+    assert!(b.decrypt(&p, &wire).is_err(), "replay accepted");
+    assert_eq!(after, b.state().snapshot_bytes(), "replay mutated state");
+}
+
+#[test]
+fn classical_policy_never_touches_the_kem() {
+    let p = StdProvider::new();
+    let policy = Suite2SessionPolicy::new(4, 8, false).expect("policy");
+    let (mut a, mut b) = establish_pair(&p, types::SUITE2_SUITE_ID, policy);
+    let mut flags = Vec::new();
+    for round in 0..3 {
+        flags.extend(burst(&p, &mut a, &mut b, 5, &format!("a{round}")));
+        flags.extend(burst(&p, &mut b, &mut a, 1, &format!("b{round}")));
+    }
+    assert!(flags.iter().all(|f| *f == 0 || *f == types::FLAG_BOUNDARY));
+    // Five sends in a row cross the N=4 fallback: a boundary without a reply.
+    assert!(flags[1..5].contains(&types::FLAG_BOUNDARY));
+}
+
+#[test]
+fn session_without_dh_key_stays_on_established_chains() {
+    let p = provider();
+    let (a, _) = establish_pair(&p, types::SUITE2_SUITE_ID, Suite2SessionPolicy::default());
+    let mut st = a.into_state();
+    st.set_dh_self_priv([0u8; 32]);
+    let mut a = Suite2Session::new(st);
+    let wires = a.encrypt(&p, b"no ratchet").expect("encrypt");
+    assert_eq!(wires.len(), 1);
+    assert_eq!(flags_of(&wires[0], types::SUITE2_SUITE_ID), 0);
+}
+
+/// `a -> b`: send `count` messages and deliver only the last, leaving `count - 1` skipped keys.
+fn burst_deliver_last(
+    p: &dyn CryptoProvider,
+    from: &mut Suite2Session,
+    to: &mut Suite2Session,
+    count: usize,
+) -> Vec<Vec<u8>> {
+    let mut sent = Vec::new();
+    for i in 0..count {
+        let wires = from
+            .encrypt(p, format!("gap-{i}").as_bytes())
+            .expect("encrypt");
+        assert_eq!(wires.len(), 1);
+        sent.push(wires[0].clone());
+    }
+    let last = sent.last().expect("sent");
+    to.decrypt(p, last).expect("deliver last");
+    sent
+}
+
+#[test]
+fn skipped_keys_expire_after_configured_receive_dh_steps() {
+    let p = StdProvider::new();
+    let policy = Suite2SessionPolicy::new(100, 8, false).expect("policy");
+    for max_steps in [1u32, 2] {
+        let (mut a, mut b) = establish_pair(&p, types::SUITE2_SUITE_ID, policy);
+        let limits = Suite2Limits::default()
+            .with_mkskipped_max_dh_steps(max_steps)
+            .expect("steps");
+        b.set_recv_limits(limits);
+
+        // All four ride a's established chain; b sees only the last.
+        let sent = burst_deliver_last(&p, &mut a, &mut b, 4);
+        let epoch0 = b.state().recv.dh_epoch;
+        assert_eq!(b.state().recv.mkskipped.len(), 3, "steps={max_steps}");
+        assert!(b
+            .state()
+            .recv
+            .mkskipped
+            .iter()
+            .all(|e| e.dh_epoch == epoch0));
+
+        // Each round trip makes b take one receive DH step.
+        for step in 1..=max_steps {
+            burst(&p, &mut b, &mut a, 1, "reply");
+            burst(&p, &mut a, &mut b, 1, "ratchet");
+            assert_eq!(b.state().recv.dh_epoch, epoch0 + step);
+            let expected = if step < max_steps { 3 } else { 0 };
+            assert_eq!(
+                b.state().recv.mkskipped.len(),
+                expected,
+                "steps={max_steps} after {step}"
+            );
+        }
+        assert!(
+            b.decrypt(&p, &sent[1]).is_err(),
+            "expired key must not open"
+        );
+
+        let restored = quantumshield_refimpl::suite2::state::Suite2SessionState::restore_bytes(
+            &b.state().snapshot_bytes(),
+        )
+        .expect("restore");
+        assert_eq!(restored.recv.dh_epoch, b.state().recv.dh_epoch);
+        assert_eq!(restored.recv.limits, limits);
+    }
+}
+
+#[test]
+fn healing_status_tracks_ratchets_reseeds_and_outstanding_targets() {
+    let p = provider();
+    let (mut a, mut b) = establish_pair(&p, types::SUITE2_SUITE_ID, Suite2SessionPolicy::default());
+    let h = a.healing_status();
+    assert_eq!(h.reseeds_sent + h.reseeds_received, 0);
+    assert!(h.live_adv_ids.is_empty() && h.state.outstanding_targets.is_empty());
+
+    // a advertises before its first message; the advertisement is outstanding on both views.
+    burst(&p, &mut a, &mut b, 1, "a0");
+    let (ha, hb) = (a.healing_status(), b.healing_status());
+    assert_eq!(ha.live_adv_ids.len(), 1);
+    let adv_id = ha.live_adv_ids[0];
+    assert_eq!(ha.state.outstanding_targets, vec![adv_id]);
+    assert_eq!(hb.pending_peer_adv_id, Some(adv_id));
+    assert_eq!(hb.peer_adv_max_seen, adv_id);
+    assert!(hb.ratchet_pending);
+    assert_eq!(ha.msgs_since_reseed, 1);
+    assert_eq!(hb.msgs_since_reseed, 1);
+
+    // b ratchets on reply, then reseeds against a's advertisement.
+    let flags = burst(&p, &mut b, &mut a, 2, "b0");
+    assert!(flags.contains(&(types::FLAG_PQ_CTXT | types::FLAG_BOUNDARY)));
+    let (ha, hb) = (a.healing_status(), b.healing_status());
+    assert_eq!(hb.reseeds_sent, 1);
+    assert_eq!(hb.pending_peer_adv_id, None);
+    assert_eq!(hb.boundaries_since_reseed, 0);
+    assert_eq!(hb.msgs_since_reseed, 0);
+    assert_eq!(ha.reseeds_received, 1);
+    assert_eq!(ha.msgs_since_reseed, 0);
+    assert!(ha.live_adv_ids.is_empty());
+    assert!(ha.state.outstanding_targets.is_empty());
+    assert_eq!(ha.state.consumed_targets, 1);
+    assert_eq!(ha.state.reseed_target_watermark, adv_id);
+    assert_eq!(ha.state.received_since_dh_step, hb.state.sent_since_dh_step);
+
+    // a's reply is a DH step: b's receive side counts it and a's send chain restarts.
+    let steps = hb.state.recv_dh_steps;
+    burst(&p, &mut a, &mut b, 1, "a1");
+    let (ha, hb) = (a.healing_status(), b.healing_status());
+    assert!(!ha.ratchet_pending);
+    assert_eq!(ha.msgs_since_ratchet, 0);
+    assert_eq!(ha.msgs_since_reseed, 1);
+    assert_eq!(hb.state.recv_dh_steps, steps + 1);
+    assert_eq!(hb.state.received_since_dh_step, ha.state.sent_since_dh_step);
+    assert_eq!(ha.state, a.state().healing_status());
+}
+
+/// a advertises, is snapshotted and restored, and the restored session opens b's reseed.
+fn advertise_then_snapshot(p: &XorKemProvider) -> (Suite2Session, Suite2Session) {
+    let (mut a, mut b) = establish_pair(p, types::SUITE2_SUITE_ID, Suite2SessionPolicy::default());
+    burst(p, &mut a, &mut b, 1, "a0");
+    assert_eq!(a.healing_status().live_adv_ids.len(), 1);
+    (a, b)
+}
+
+#[test]
+fn session_snapshot_carries_the_scka_store() {
+    let p = provider();
+    let (a, mut b) = advertise_then_snapshot(&p);
+    let mut restored = Suite2Session::restore_bytes(&a.snapshot_bytes()).expect("restore");
+    assert_eq!(restored.snapshot_bytes(), a.snapshot_bytes());
+    assert_eq!(restored.healing_status(), a.healing_status());
+
+    let flags = burst(&p, &mut b, &mut restored, 2, "b0");
+    assert!(flags.contains(&(types::FLAG_PQ_CTXT | types::FLAG_BOUNDARY)));
+    assert_eq!(restored.healing_status().reseeds_received, 1);
+    burst(&p, &mut restored, &mut b, 2, "a1");
+    assert_eq!(restored.state().rk, b.state().rk);
+
+    // The ratchet state alone loses the advertised secret: the same reseed cannot be opened.
+    let (a, mut b) = advertise_then_snapshot(&p);
+    let mut bare = Suite2Session::new(a.into_state());
+    let wires: Vec<Vec<u8>> = (0..2)
+        .flat_map(|i| b.encrypt(&p, &[i]).expect("encrypt"))
+        .collect();
+    let (reseed, before) = wires
+        .split_last()
+        .filter(|(w, _)| flags_of(w, types::SUITE2_SUITE_ID) & types::FLAG_PQ_CTXT != 0)
+        .expect("reseed wire");
+    for w in before {
+        bare.decrypt(&p, w).expect("decrypt");
+    }
+    let err = bare.decrypt(&p, reseed).expect_err("reject");
+    assert_eq!(
+        err,
+        Suite2SessionError::Reject(Suite2RejectReason::SckaTargetUnknown)
+    );
+}
+
+#[test]
+fn session_snapshot_rejects_bad_layouts() {
+    let p = provider();
+    let (a, _) = advertise_then_snapshot(&p);
+    let bytes = a.snapshot_bytes();
+
+    let mut wrong_version = bytes.to_vec();
+    wrong_version[4] = 2;
+    assert!(Suite2Session::restore_bytes(&wrong_version).is_err());
+    assert!(Suite2Session::restore_bytes(&bytes[..bytes.len() - 1]).is_err());
+    let mut trailing = bytes.to_vec();
+    trailing.push(0);
+    assert!(Suite2Session::restore_bytes(&trailing).is_err());
+    assert!(Suite2Session::restore_bytes(&a.state().snapshot_bytes()).is_err());
+
+    // The policy follows the magic, version and length-prefixed state.
+    let state_len = u32::from_be_bytes(bytes[5..9].try_into().expect("len")) as usize;
+    let policy_at = 9 + state_len;
+    for (offset, value) in [(0, 0u32), (0, 1_025), (4, 0), (4, u32::MAX)] {
+        let mut bad = bytes.to_vec();
+        bad[policy_at + offset..policy_at + offset + 4].copy_from_slice(&value.to_be_bytes());
+        assert!(
+            Suite2Session::restore_bytes(&bad).is_err(),
+            "{offset}:{value}"
+        );
+    }
+}
+
+#[test]
+fn session_policy_rejects_out_of_range_cadences() {
+    assert_eq!(
+        Suite2SessionPolicy::new(0, 8, true),
+        Err(Suite2SessionPolicyError::RatchetAfterMsgs)
+    );
+    assert_eq!(
+        Suite2SessionPolicy::new(4, RESEED_AFTER_BOUNDARIES_CEILING + 1, true),
+        Err(Suite2SessionPolicyError::ReseedAfterBoundaries)
+    );
+    let policy = Suite2SessionPolicy::new(RATCHET_AFTER_MSGS_CEILING, 1, false).expect("policy");
+    assert_eq!(policy.ratchet_after_msgs(), RATCHET_AFTER_MSGS_CEILING);
+    assert!(!policy.scka());
+}
+
+#[test]
+fn sealed_session_snapshot_round_trips_and_rejects_rollback() {
+    let p = provider();
+    let key = [0x3a; 32];
+    let (a, _) = advertise_then_snapshot(&p);
+    let sealed = a.seal_snapshot(p.kmac(), p.aead(), &key, 4).expect("seal");
+
+    let (opened, epoch) =
+        Suite2Session::open_snapshot(p.kmac(), p.aead(), &key, 4, &sealed).expect("open");
+    assert_eq!(epoch, 4);
+    assert_eq!(opened.snapshot_bytes(), a.snapshot_bytes());
+
+    assert_eq!(
+        Suite2Session::open_snapshot(p.kmac(), p.aead(), &key, 5, &sealed).err(),
+        Some(SnapshotError::Rollback {
+            epoch: 4,
+            committed: 5
+        })
+    );
+    let state_only = a
+        .state()
+        .seal_snapshot(p.kmac(), p.aead(), &key, 4)
+        .expect("seal");
+    assert!(Suite2Session::open_snapshot(p.kmac(), p.aead(), &key, 0, &state_only).is_err());
+}